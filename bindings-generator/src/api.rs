@@ -22,8 +22,24 @@ impl Api {
     ///
     /// If the `data` is not valid JSON data the function will panic.
     pub fn new(data: &str) -> Self {
+        let classes: Vec<miniserde::json::Value> =
+            miniserde::json::from_str(data).expect("Invalid JSON data");
+
+        // Each class is parsed from its own JSON, so that it can be hashed for incremental
+        // regeneration.
+        let classes = classes
+            .iter()
+            .map(|class| {
+                let json = miniserde::json::to_string(class);
+                let mut class: GodotClass =
+                    miniserde::json::from_str(&json).expect("Invalid JSON data");
+                class.json_hash = Some(crate::cache::hash_of(&json));
+                class
+            })
+            .collect();
+
         let mut api = Self {
-            classes: miniserde::json::from_str(data).expect("Invalid JSON data"),
+            classes,
             api_underscore: Default::default(),
         };
 
@@ -54,6 +70,107 @@ impl Api {
         false
     }
 
+    /// Makes an `Api` that was constructed from a subset of `api.json` self-contained, by replacing
+    /// references to classes that are not part of it.
+    ///
+    /// Without this, the generated code refers to types that don't exist. With it:
+    ///
+    /// - Arguments of missing classes take any `Object` instead, i.e. `impl AsArg<Object>`.
+    /// - Arguments of enums of missing classes take their underlying `i64` value instead.
+    /// - Methods that return a missing class, or one of its enums, are removed, since it can't be
+    ///   known whether the returned object is reference-counted.
+    /// - Classes whose base class is missing inherit `Reference` or `Object` directly instead,
+    ///   depending on whether they are reference-counted.
+    ///
+    /// This allows generating minimal binding crates with only the classes that are needed.
+    ///
+    /// # Panics
+    ///
+    /// If `Object` is not part of the `Api`, or if a reference-counted class is part of it, but
+    /// `Reference` is not.
+    pub fn stub_missing_classes(&mut self) -> StubReport {
+        let known = self
+            .classes
+            .iter()
+            .map(|class| class.name.clone())
+            .collect::<HashSet<_>>();
+
+        assert!(
+            known.contains("Object"),
+            "`Object` should be part of the API to stub missing classes"
+        );
+
+        // Returns the class named by a type, if it's a missing one.
+        let missing_class = |ty: &str| -> Option<String> {
+            let class = match ty.strip_prefix("enum.") {
+                Some(path) => path.split("::").next().unwrap_or(path),
+                None => ty,
+            };
+
+            match Ty::from_src(class) {
+                Ty::Object(_) if !known.contains(class) => Some(class.to_owned()),
+                _ => None,
+            }
+        };
+
+        let mut missing_classes = HashSet::new();
+        let mut removed_methods = Vec::new();
+
+        for class in &mut self.classes {
+            if !class.base_class.is_empty() && !known.contains(&class.base_class) {
+                missing_classes.insert(std::mem::take(&mut class.base_class));
+
+                class.base_class = if class.is_reference && class.name != "Reference" {
+                    assert!(
+                        known.contains("Reference"),
+                        "`Reference` should be part of the API to stub the base class of `{}`",
+                        class.name
+                    );
+                    "Reference".to_owned()
+                } else {
+                    "Object".to_owned()
+                };
+                class.generate_module_name();
+            }
+
+            let class_name = &class.name;
+            class
+                .methods
+                .retain(|method| match missing_class(&method.return_type) {
+                    Some(missing) => {
+                        missing_classes.insert(missing);
+                        removed_methods.push(format!("{}::{}", class_name, method.name));
+                        false
+                    }
+                    None => true,
+                });
+
+            for arg in class
+                .methods
+                .iter_mut()
+                .flat_map(|m| m.arguments.iter_mut())
+            {
+                if let Some(missing) = missing_class(&arg.ty) {
+                    arg.ty = if arg.ty.starts_with("enum.") {
+                        "int".to_owned()
+                    } else {
+                        "Object".to_owned()
+                    };
+                    missing_classes.insert(missing);
+                }
+            }
+        }
+
+        let mut missing_classes = missing_classes.into_iter().collect::<Vec<_>>();
+        missing_classes.sort();
+        removed_methods.sort();
+
+        StubReport {
+            missing_classes,
+            removed_methods,
+        }
+    }
+
     fn strip_leading_underscores(&mut self) {
         for class in &mut self.classes {
             if class.name.starts_with('_') {
@@ -80,6 +197,15 @@ impl Api {
     }
 }
 
+/// Changes made by [`Api::stub_missing_classes`].
+#[derive(Clone, Debug, Default)]
+pub struct StubReport {
+    /// Names of the classes that are referenced, but not part of the `Api`, sorted.
+    pub missing_classes: Vec<String>,
+    /// Methods that were removed because they return a missing class, as `Class::method`, sorted.
+    pub removed_methods: Vec<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct GodotClass {
     pub name: String,
@@ -97,6 +223,7 @@ pub struct GodotClass {
 
     module_name: Option<String>,
     base_class_module_name: Option<String>,
+    json_hash: Option<u64>,
 }
 
 impl GodotClass {
@@ -120,6 +247,12 @@ impl GodotClass {
             .expect("Module Names should have been generated.")
     }
 
+    /// Returns the hash of the JSON data of the class, or `None` if it wasn't constructed from JSON
+    /// data.
+    pub(crate) fn json_hash(&self) -> Option<u64> {
+        self.json_hash
+    }
+
     /// Returns the name of the base class if `base_class` is not empty. Returns `None` otherwise.
     pub fn base_class_name(&self) -> Option<&str> {
         if self.base_class.is_empty() {
@@ -137,6 +270,11 @@ impl GodotClass {
         )
     }
 
+    /// Returns `true` if the class is one of the engine servers, e.g. `VisualServer`.
+    pub fn is_server(&self) -> bool {
+        self.singleton && self.name.ends_with("Server")
+    }
+
     /// Returns the base class from `api` if `base_class` is not empty. Returns `None` otherwise.
     pub fn base_class<'a>(&self, api: &'a Api) -> Option<&'a Self> {
         self.base_class_name()
@@ -155,9 +293,10 @@ impl GodotClass {
         self.properties.iter().any(|p| p.getter == name)
     }
 
-    /// Whether there is a snake_case module containing related symbols (nested types in C++)
+    /// Whether there is a snake_case module containing related symbols (nested types in C++, or
+    /// typed property names)
     pub fn has_related_module(&self) -> bool {
-        !self.enums.is_empty()
+        !self.enums.is_empty() || !self.properties.is_empty()
     }
 }
 
@@ -741,6 +880,50 @@ pub fn module_name_from_class_name(class_name: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn stub_missing_classes_in_subset() {
+        let mut api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        api.classes.retain(|class| {
+            ["Object", "Reference", "Node", "Node2D"].contains(&class.name.as_str())
+        });
+
+        // Node2D inherits CanvasItem, which is missing.
+        let report = api.stub_missing_classes();
+        assert!(report.missing_classes.contains(&"CanvasItem".to_owned()));
+        assert!(report.missing_classes.contains(&"SceneTree".to_owned()));
+        assert!(report
+            .removed_methods
+            .contains(&"Node::get_tree".to_owned()));
+        assert_eq!("Object", api.find_class("Node2D").unwrap().base_class);
+
+        let result = crate::generate_bindings(&api, None);
+        let code = |name: &str| {
+            result
+                .class_bindings
+                .iter()
+                .find(|(class, _)| class.name == name)
+                .map(|(_, code)| code.to_string())
+                .unwrap()
+        };
+
+        let node = code("Node");
+        assert!(!node.contains("SceneTree"));
+        assert!(!node.contains("fn get_tree ("));
+        assert!(node.contains("fn add_child ("));
+        assert!(node.contains("fn set_custom_multiplayer (& self , api : impl AsArg < crate :: generated :: Object >)"));
+
+        for (class, code) in &result.class_bindings {
+            let code = code.to_string();
+            for missing in &report.missing_classes {
+                assert!(
+                    !code.contains(&format!("crate :: generated :: {missing} ")),
+                    "{} should not refer to {missing}",
+                    class.name
+                );
+            }
+        }
+    }
+
     #[test]
     fn module_name_generator() {
         let tests = vec![
@@ -0,0 +1,299 @@
+//! Async variants of blocking engine methods, for `gdnative-async`.
+//!
+//! `api.json` doesn't tell which methods block until a long-running operation is finished, so
+//! they are listed here, along with the way their async variants are run. The variants are
+//! associated functions of a wrapper type per class, e.g. `IPAsync::resolve_hostname` for
+//! `IP::resolve_hostname`, returning a `Yield` future that resolves with the result.
+//!
+//! The generated code expects the following items to be in scope where it's included:
+//!
+//! - The core types, `Ref`, `Shared` and `Yield`, as well as the classes of `gdnative_bindings`.
+//! - `spawn_blocking`, which calls a `Send` closure on a worker thread and returns a `Yield`
+//!   that resolves with its return value.
+//! - The wrapper types of the classes with [`Strategy::Interactive`] methods.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::api::{Api, GodotClass, GodotMethod, Ty};
+use crate::methods::rename_property_getter;
+use crate::rust_safe_name;
+
+/// How the async variant of a blocking method is run.
+#[derive(Copy, Clone, Debug)]
+enum Strategy {
+    /// The method is called on a worker thread. Only available for methods of thread-safe
+    /// singletons.
+    Thread,
+    /// The operation is driven over several frames through the named interactive variant of the
+    /// method, e.g. `load_interactive` for `load`. The wrapper type is written by hand, and must
+    /// have an associated function `from_interactive` taking the return value of the interactive
+    /// method, and returning a `Yield` that resolves with the loaded object, or an error.
+    Interactive(&'static str),
+}
+
+/// Methods documented as blocking the calling thread, as `(class, method, strategy)`.
+const BLOCKING_METHODS: &[(&str, &str, Strategy)] = &[
+    ("IP", "resolve_hostname", Strategy::Thread),
+    ("IP", "resolve_hostname_addresses", Strategy::Thread),
+    (
+        "ResourceLoader",
+        "load",
+        Strategy::Interactive("load_interactive"),
+    ),
+    ("ResourceSaver", "save", Strategy::Thread),
+];
+
+/// Generates the async variants of the blocking engine methods in `api`. Methods of classes
+/// that aren't in `api` are left out, so that this also works with trimmed custom APIs.
+///
+/// # Panics
+///
+/// If a listed method doesn't exist in its class, or takes arguments that can't be sent to
+/// another thread.
+pub fn generate_async_methods(api: &Api) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    for class in &api.classes {
+        let methods = BLOCKING_METHODS
+            .iter()
+            .filter(|(class_name, _, _)| *class_name == class.name)
+            .map(|&(_, method_name, strategy)| (find_method(class, method_name), strategy))
+            .collect::<Vec<_>>();
+
+        if methods.is_empty() {
+            continue;
+        }
+
+        let wrapper = format_ident!("{}Async", class.name);
+        let hand_written = methods
+            .iter()
+            .any(|(_, strategy)| matches!(strategy, Strategy::Interactive(_)));
+
+        let functions = methods
+            .iter()
+            .map(|&(method, strategy)| match strategy {
+                Strategy::Thread => generate_thread_method(class, method),
+                Strategy::Interactive(interactive) => {
+                    generate_interactive_method(class, method, find_method(class, interactive))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !hand_written {
+            let doc = format!(
+                "Async variants of the blocking methods of [`{0}`](gdnative_bindings::{0}).",
+                class.name
+            );
+
+            result.extend(quote! {
+                #[doc = #doc]
+                #[derive(Copy, Clone, Debug)]
+                pub struct #wrapper;
+            });
+        }
+
+        result.extend(quote! {
+            impl #wrapper {
+                #(#functions)*
+            }
+        });
+    }
+
+    result
+}
+
+fn find_method<'a>(class: &'a GodotClass, name: &str) -> &'a GodotMethod {
+    class
+        .methods
+        .iter()
+        .find(|method| method.name == name)
+        .unwrap_or_else(|| panic!("blocking method {}::{name} not found", class.name))
+}
+
+fn generate_thread_method(class: &GodotClass, method: &GodotMethod) -> TokenStream {
+    assert!(
+        class.singleton && class.is_singleton_thread_safe(),
+        "{}::{} can only be called on a worker thread if its class is a thread-safe singleton",
+        class.name,
+        method.name
+    );
+
+    let class_name = format_ident!("{}", class.name);
+    let name = rust_safe_name(rename_property_getter(&method.name, class));
+    let (params_decl, conversions, params_use) = generate_params(class, method);
+    let ret = async_return_type(&method.get_return_type());
+
+    let doc = format!(
+        "Async variant of [`{0}::{1}`](gdnative_bindings::{0}::{1}), called on a worker thread.",
+        class.name, name
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[inline]
+        pub fn #name(#(#params_decl),*) -> Yield<#ret> {
+            #(#conversions)*
+            spawn_blocking(move || #class_name::godot_singleton().#name(#(#params_use),*))
+        }
+    }
+}
+
+fn generate_interactive_method(
+    class: &GodotClass,
+    method: &GodotMethod,
+    interactive: &GodotMethod,
+) -> TokenStream {
+    let class_name = format_ident!("{}", class.name);
+    let name = rust_safe_name(rename_property_getter(&method.name, class));
+    let interactive_name = rust_safe_name(rename_property_getter(&interactive.name, class));
+    let (params_decl, conversions, params_use) = generate_params(class, interactive);
+
+    let ret = match method.get_return_type() {
+        Ty::Object(path) => object_type(&path),
+        _ => panic!(
+            "{}::{} must return an object to be run interactively",
+            class.name, method.name
+        ),
+    };
+
+    let doc = format!(
+        "Async variant of [`{0}::{1}`](gdnative_bindings::{0}::{1}), driven over several \
+         frames through [`{0}::{2}`](gdnative_bindings::{0}::{2}).",
+        class.name, name, interactive_name
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[inline]
+        pub fn #name(#(#params_decl),*) -> Yield<Result<Ref<#ret, Shared>, GodotError>> {
+            #(#conversions)*
+            Self::from_interactive(#class_name::godot_singleton().#interactive_name(#(#params_use),*))
+        }
+    }
+}
+
+/// Returns the parameter declarations of the async variant of `method`, the statements that
+/// convert them to owned values that can be sent to another thread, and the arguments for the
+/// blocking method.
+fn generate_params(
+    class: &GodotClass,
+    method: &GodotMethod,
+) -> (Vec<TokenStream>, Vec<TokenStream>, Vec<Ident>) {
+    assert!(
+        !method.has_varargs,
+        "{}::{} takes varargs, which can't be sent to another thread",
+        class.name, method.name
+    );
+
+    let mut params_decl = Vec::new();
+    let mut conversions = Vec::new();
+    let mut params_use = Vec::new();
+
+    for argument in &method.arguments {
+        let name = rust_safe_name(&argument.name);
+
+        let ty = match argument.get_type() {
+            Ty::String => {
+                conversions.push(quote! { let #name: GodotString = #name.into(); });
+                quote! { impl Into<GodotString> }
+            }
+            Ty::NodePath => {
+                conversions.push(quote! { let #name: NodePath = #name.into(); });
+                quote! { impl Into<NodePath> }
+            }
+            Ty::Variant => {
+                conversions.push(quote! { let #name = #name.owned_to_variant(); });
+                quote! { impl OwnedToVariant }
+            }
+            Ty::Object(path) => {
+                let path = object_type(&path);
+                quote! { Ref<#path, Shared> }
+            }
+            Ty::Enum(path) => {
+                let path = bindings_path(&path);
+                quote! { #path }
+            }
+            ty => {
+                let ty = ty.to_rust();
+                quote! { #ty }
+            }
+        };
+
+        params_decl.push(quote! { #name: #ty });
+        params_use.push(name);
+    }
+
+    (params_decl, conversions, params_use)
+}
+
+fn async_return_type(ty: &Ty) -> TokenStream {
+    match ty {
+        Ty::Object(path) => {
+            let path = object_type(path);
+            quote! { Option<Ref<#path, Shared>> }
+        }
+        Ty::Enum(path) => {
+            let path = bindings_path(path);
+            quote! { #path }
+        }
+        ty => {
+            let ty = ty.to_rust();
+            quote! { #ty }
+        }
+    }
+}
+
+/// Returns the name of the class in an object type, which is relative to the generated bindings.
+fn object_type(path: &syn::TypePath) -> Ident {
+    path.path
+        .segments
+        .last()
+        .expect("object types have a name")
+        .ident
+        .clone()
+}
+
+/// Makes an enum path that's relative to the generated bindings usable from other crates.
+fn bindings_path(path: &syn::TypePath) -> TokenStream {
+    let segments = path
+        .path
+        .segments
+        .iter()
+        .map(|segment| &segment.ident)
+        .skip_while(|ident| *ident == "crate" || *ident == "generated");
+
+    quote! { gdnative_bindings #(::#segments)* }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking_methods() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let code = generate_async_methods(&api).to_string();
+
+        assert!(code.contains("pub struct IPAsync"));
+        assert!(code.contains(
+            "pub fn resolve_hostname (host : impl Into < GodotString > , ip_type : i64) -> Yield < GodotString >"
+        ));
+        assert!(code.contains(
+            "pub fn save (path : impl Into < GodotString > , resource : Ref < Resource , Shared > , flags : i64) -> Yield < GodotResult >"
+        ));
+        assert!(code.contains("spawn_blocking (move || ResourceSaver :: godot_singleton () . save (path , resource , flags))"));
+
+        // The wrapper of interactive methods is written by hand
+        assert!(!code.contains("pub struct ResourceLoaderAsync"));
+        assert!(code.contains(
+            "pub fn load (path : impl Into < GodotString > , type_hint : impl Into < GodotString >) -> Yield < Result < Ref < Resource , Shared > , GodotError >>"
+        ));
+    }
+
+    #[test]
+    fn trimmed_api() {
+        let api = Api::new("[]");
+        assert!(generate_async_methods(&api).is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+//! Incremental regeneration of the bindings.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::api::{Api, GodotClass};
+use crate::class_docs::GodotXmlDocs;
+use crate::documentation::{generate_class_documentation, generate_module_doc};
+use crate::hooks::Hooks;
+use crate::methods::rename_property_getter;
+use crate::GeneratorResult;
+
+/// Hashes of the inputs that each class was generated from, stored in a file between builds.
+///
+/// Passed to [`generate_bindings_incremental`] to only regenerate the classes whose inputs
+/// changed since the last build. The inputs of a class are its JSON data and that of its base
+/// classes, its class-level and method documentation, and what the hooks return for it. Changing
+/// the generator itself, adding or removing classes, or changing which classes are excluded
+/// regenerates all of them.
+///
+/// The cache doesn't know whether the output of the last build is still around. Use
+/// [`retain`](Self::retain) to forget the classes whose output is gone.
+///
+/// [`generate_bindings_incremental`]: crate::generate_bindings_incremental
+#[derive(Debug)]
+pub struct BindingCache {
+    path: PathBuf,
+    hashes: HashMap<String, u64>,
+}
+
+impl BindingCache {
+    /// Loads the cache stored at `path`. The cache is empty if the file doesn't exist or can't
+    /// be parsed, so that all classes are generated.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let hashes = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| parse(&content))
+            .unwrap_or_default();
+
+        BindingCache { path, hashes }
+    }
+
+    /// Returns the number of classes in the cache.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if the cache contains no classes.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Forgets the classes for which `f` returns `false`, so that they are generated again.
+    pub fn retain(&mut self, mut f: impl FnMut(&str) -> bool) {
+        self.hashes.retain(|class, _| f(class));
+    }
+
+    /// Writes the cache to the file it was loaded from. This should only be done once the
+    /// output of the build has been written.
+    pub fn save(&self) -> GeneratorResult {
+        let mut entries = self.hashes.iter().collect::<Vec<_>>();
+        entries.sort();
+
+        let content = entries
+            .into_iter()
+            .map(|(class, hash)| format!("{hash:016x} {class}\n"))
+            .collect::<String>();
+
+        fs::write(&self.path, content)
+    }
+
+    pub(crate) fn is_unchanged(&self, class: &GodotClass, hash: u64) -> bool {
+        self.hashes.get(&class.name) == Some(&hash)
+    }
+
+    pub(crate) fn replace(&mut self, hashes: HashMap<String, u64>) {
+        self.hashes = hashes;
+    }
+}
+
+fn parse(content: &str) -> Option<HashMap<String, u64>> {
+    content
+        .lines()
+        .map(|line| {
+            let (hash, class) = line.split_once(' ')?;
+            let hash = u64::from_str_radix(hash, 16).ok()?;
+            Some((class.to_owned(), hash))
+        })
+        .collect()
+}
+
+pub(crate) fn hash_of(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sources of the generator, so that changing the generator without bumping its version, e.g.
+/// through a `path` or `git` dependency, regenerates all classes.
+const GENERATOR_SOURCES: &[&str] = &[
+    include_str!("api.rs"),
+    include_str!("cache.rs"),
+    include_str!("class_docs.rs"),
+    include_str!("classes.rs"),
+    include_str!("dependency.rs"),
+    include_str!("documentation.rs"),
+    include_str!("godot_api_json.rs"),
+    include_str!("godot_version.rs"),
+    include_str!("hooks.rs"),
+    include_str!("lib.rs"),
+    include_str!("methods.rs"),
+    include_str!("rids.rs"),
+    include_str!("special_methods.rs"),
+];
+
+/// Hashes the inputs of classes.
+pub(crate) struct InputHasher<'a> {
+    api: &'a Api,
+    docs: Option<&'a GodotXmlDocs>,
+    hooks: &'a Hooks<'a>,
+    /// Hash of the inputs that are shared by all classes.
+    common: u64,
+}
+
+impl<'a> InputHasher<'a> {
+    pub(crate) fn new(api: &'a Api, docs: Option<&'a GodotXmlDocs>, hooks: &'a Hooks<'a>) -> Self {
+        let mut classes = api
+            .classes
+            .iter()
+            .map(|class| class.name.as_str())
+            .collect::<Vec<_>>();
+        classes.sort_unstable();
+
+        let mut underscore = api.api_underscore.iter().collect::<Vec<_>>();
+        underscore.sort_unstable();
+
+        let mut excluded = hooks.excluded().collect::<Vec<_>>();
+        excluded.sort_unstable();
+
+        let common = hash_of((
+            env!("CARGO_PKG_VERSION"),
+            GENERATOR_SOURCES,
+            cfg!(feature = "ptrcall"),
+            docs.is_some(),
+            classes,
+            underscore,
+            excluded,
+        ));
+
+        InputHasher {
+            api,
+            docs,
+            hooks,
+            common,
+        }
+    }
+
+    /// Returns the hash of the inputs of `class`, or `None` if the class or one of its base
+    /// classes wasn't constructed from JSON data.
+    pub(crate) fn class_hash(&self, class: &GodotClass) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.common.hash(&mut hasher);
+
+        // The code of a class depends on its base classes, e.g. for `Deref` and `SubClass`
+        let mut current = Some(class);
+        while let Some(c) = current {
+            c.json_hash()?.hash(&mut hasher);
+            current = c
+                .base_class_name()
+                .and_then(|name| self.api.find_class(name));
+        }
+
+        generate_module_doc(class).to_string().hash(&mut hasher);
+        generate_class_documentation(self.api, class)
+            .to_string()
+            .hash(&mut hasher);
+
+        for method in &class.methods {
+            let name = method.get_name().rust_name;
+            self.docs
+                .and_then(|docs| docs.get_class_method_desc(&class.name, name))
+                .hash(&mut hasher);
+            self.hooks
+                .rename_method(class, method, rename_property_getter(name, class))
+                .hash(&mut hasher);
+        }
+
+        self.hooks.extra_items(class).to_string().hash(&mut hasher);
+
+        Some(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miniserde::json::{self, Number, Value};
+
+    use super::*;
+    use crate::{generate_bindings_incremental, NoHooks};
+
+    /// Returns an `Api` with a few classes, with `modify` applied to the JSON data of `Node2D`.
+    fn subset(modify: impl FnOnce(&mut json::Object)) -> Api {
+        let Value::Array(mut classes) =
+            json::from_str(include_str!("../../gdnative-bindings/api.json")).unwrap()
+        else {
+            panic!("api.json should contain an array");
+        };
+
+        classes.retain(|class| match class {
+            Value::Object(class) => matches!(
+                &class["name"],
+                Value::String(name) if ["Object", "Reference", "Node", "Node2D"].contains(&name.as_str())
+            ),
+            _ => false,
+        });
+
+        let node_2d = classes.iter_mut().find_map(|class| match class {
+            Value::Object(class) if matches!(&class["name"], Value::String(name) if name == "Node2D") => {
+                Some(class)
+            }
+            _ => None,
+        });
+        modify(node_2d.unwrap());
+
+        let mut api = Api::new(&json::to_string(&classes));
+        api.stub_missing_classes();
+        api
+    }
+
+    fn names<'a>(classes: impl IntoIterator<Item = &'a GodotClass>) -> Vec<String> {
+        let mut names = classes
+            .into_iter()
+            .map(|class| class.name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn only_changed_classes_are_regenerated() {
+        let path = std::env::temp_dir().join(format!(
+            "gdnative_bindings_cache_{}.txt",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let api = subset(|_| {});
+        let mut cache = BindingCache::load(&path);
+        assert!(cache.is_empty());
+
+        let first = generate_bindings_incremental(&api, None, &NoHooks, &mut cache);
+        assert_eq!(4, first.class_bindings.len());
+        assert!(first.unchanged_classes.is_empty());
+        assert_eq!(4, cache.len());
+
+        let second = generate_bindings_incremental(&api, None, &NoHooks, &mut cache);
+        assert!(second.class_bindings.is_empty());
+        assert_eq!(4, second.unchanged_classes.len());
+        assert_eq!(first.icalls.to_string(), second.icalls.to_string());
+
+        cache.save().unwrap();
+        let mut cache = BindingCache::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(4, cache.len());
+
+        let api = subset(|node_2d| {
+            let Value::Object(constants) = node_2d.get_mut("constants").unwrap() else {
+                panic!("constants should be an object");
+            };
+            constants.insert("TEST_CONSTANT".into(), Value::Number(Number::I64(1)));
+        });
+
+        let third = generate_bindings_incremental(&api, None, &NoHooks, &mut cache);
+        assert_eq!(
+            vec!["Node2D"],
+            names(third.class_bindings.iter().map(|(class, _)| *class))
+        );
+        assert_eq!(
+            vec!["Node", "Object", "Reference"],
+            names(third.unchanged_classes.iter().copied())
+        );
+        assert!(third.class_bindings[0]
+            .1
+            .to_string()
+            .contains("TEST_CONSTANT"));
+
+        cache.retain(|class| class != "Node");
+        let fourth = generate_bindings_incremental(&api, None, &NoHooks, &mut cache);
+        assert_eq!(
+            vec!["Node"],
+            names(fourth.class_bindings.iter().map(|(class, _)| *class))
+        );
+    }
+}
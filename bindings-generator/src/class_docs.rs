@@ -67,18 +67,18 @@ impl GodotXmlDocs {
                         if let Some(property_name) = node.attribute("name") {
                             if !property_name.contains('/') {
                                 if node.has_attribute("setter") {
-                                    self.add_fn(class, &format!("set_{property_name}"), desc, &[]);
+                                    self.add_fn(class, &format!("set_{property_name}"), desc);
                                 }
                                 if node.has_attribute("getter") {
-                                    self.add_fn(class, &format!("get_{property_name}"), desc, &[]);
+                                    self.add_fn(class, &format!("get_{property_name}"), desc);
                                 }
                             }
                         }
                         if let Some(func) = node.attribute("setter") {
-                            self.add_fn(class, func, desc, &[]);
+                            self.add_fn(class, func, desc);
                         }
                         if let Some(func) = node.attribute("getter") {
-                            self.add_fn(class, func, desc, &[]);
+                            self.add_fn(class, func, desc);
                         }
                     }
                 }
@@ -97,47 +97,26 @@ impl GodotXmlDocs {
     }
 
     fn parse_method(&mut self, class: &str, method: Node) {
+        // Default arguments are documented from the API JSON, see `generate_method_signature_doc`.
         if let Some(method_name) = method.attribute("name") {
-            let default_args = method
-                .descendants()
-                .filter_map(|node| {
-                    if node.tag_name().name() == "argument" {
-                        let key = node
-                            .attribute("name")
-                            .expect("expecting argument tags to have name attribute");
-                        node.attribute("default").map(|val| (key, val))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<(&str, &str)>>();
-
             if let Some(desc_node) = method
                 .descendants()
                 .find(|node| node.tag_name().name() == "description")
             {
                 if let Some(desc) = desc_node.text() {
-                    self.add_fn(class, method_name, desc, default_args.as_slice());
+                    self.add_fn(class, method_name, desc);
                 }
             }
         }
     }
 
-    fn add_fn(&mut self, class: &str, method: &str, desc: &str, default_args: &[(&str, &str)]) {
-        let mut doc = unindent::unindent(desc.trim());
+    fn add_fn(&mut self, class: &str, method: &str, desc: &str) {
+        let doc = unindent::unindent(desc.trim());
 
-        if doc.is_empty() && default_args.is_empty() {
+        if doc.is_empty() {
             return;
         }
 
-        if !default_args.is_empty() {
-            doc.push_str("\n# Default Arguments");
-
-            for arg in default_args {
-                doc.push_str(format!("\n* `{}` - `{}`", arg.0, arg.1).as_str());
-            }
-        }
-
         self.class_fn_desc.insert(
             (class.into(), method.into()),
             Self::reformat_as_rustdoc(&self.regexes, doc),
@@ -1,13 +1,15 @@
 use crate::api::*;
 use crate::class_docs::GodotXmlDocs;
+use crate::hooks::Hooks;
 use crate::methods;
+use crate::rids::MethodRids;
 use crate::special_methods;
 
 use heck::ToPascalCase as _;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 pub(crate) fn generate_class_struct(class: &GodotClass, class_doc: TokenStream) -> TokenStream {
     let class_name = format_ident!("{}", &class.name);
@@ -30,8 +32,8 @@ pub(crate) fn generate_class_struct(class: &GodotClass, class_doc: TokenStream)
 
 pub(crate) fn generate_class_impl(
     class: &GodotClass,
-    icalls: &mut HashMap<String, methods::MethodSig>,
     docs: Option<&GodotXmlDocs>,
+    hooks: &Hooks,
 ) -> TokenStream {
     let class_singleton = if class.singleton {
         special_methods::generate_singleton_getter(class)
@@ -51,7 +53,7 @@ pub(crate) fn generate_class_impl(
         Default::default()
     };
 
-    let class_methods = methods::generate_methods(class, icalls, docs);
+    let class_methods = methods::generate_methods(class, docs, hooks);
 
     let class_name = format_ident!("{}", class.name);
     quote! {
@@ -95,6 +97,78 @@ pub(crate) fn generate_class_constants(class: &GodotClass) -> TokenStream {
     }
 }
 
+pub(crate) fn generate_properties(api: &Api, class: &GodotClass, hooks: &Hooks) -> TokenStream {
+    assert!(
+        !class.properties.is_empty(),
+        "Only call on class with properties."
+    );
+
+    let class_name = format_ident!("{}", &class.name);
+
+    let mut properties: Vec<&Property> = class.properties.iter().collect();
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let constants = properties.iter().map(|property| {
+        let mut const_name = property.name.to_uppercase().replace('/', "_");
+        if const_name.starts_with(|c: char| c.is_ascii_digit()) {
+            const_name.insert(0, '_');
+        }
+        let const_name = format_ident!("{}", const_name);
+
+        let name = &property.name;
+        let ty = if property.type_ == "RID" {
+            class
+                .methods
+                .iter()
+                .find(|method| method.name == property.getter)
+                .and_then(|getter| MethodRids::of(class, getter, hooks).return_type)
+        } else {
+            None
+        };
+        let ty = ty.unwrap_or_else(|| property_type(api, &property.type_, hooks));
+        let doc = format!("The `{}` property, of type `{}`.", name, property.type_);
+
+        quote! {
+            #[doc = #doc]
+            pub const #const_name: TypedProperty<crate::generated::#class_name, #ty> =
+                TypedProperty::new(#name);
+        }
+    });
+
+    let doc = format!(
+        "Typed names of the properties of [`{}`][super::{}], excluding inherited ones.",
+        class.name, class.name,
+    );
+
+    quote! {
+        #[doc = #doc]
+        pub mod properties {
+            use super::*;
+
+            #(#constants)*
+        }
+    }
+}
+
+/// Returns the Rust type for values of a property. Properties that accept multiple classes or
+/// classes left out by the hooks use `Variant`.
+fn property_type(api: &Api, type_: &str, hooks: &Hooks) -> syn::Type {
+    if type_.contains([',', ':', '/']) {
+        return syn::parse_quote! { Variant };
+    }
+
+    match Ty::from_src(type_) {
+        Ty::Object(_)
+            if api
+                .find_class(type_)
+                .map_or(true, |class| hooks.is_excluded(class)) =>
+        {
+            syn::parse_quote! { Variant }
+        }
+        ty => ty.to_rust(),
+    }
+}
+
 pub(crate) fn generate_enums(class: &GodotClass) -> TokenStream {
     let mut enums: Vec<&Enum> = class.enums.iter().collect();
     enums.sort();
@@ -113,12 +187,49 @@ pub(crate) fn generate_enums(class: &GodotClass) -> TokenStream {
             }
         });
 
+        // Several constants may share a value. The first name in sorted order is the canonical one.
+        let mut seen = HashSet::new();
+        let names = values
+            .iter()
+            .filter(|(_, val)| seen.insert(**val))
+            .map(|(key, val)| {
+                let key = key.to_uppercase();
+                quote! {
+                    #val => Some(#key),
+                }
+            });
+
+        let doc = format!(
+            "Values of the engine enum `{}.{}`.\n\n\
+             Newer engine versions may add values that are not listed here. Such values are \
+             preserved as-is and can be detected with [`is_known`][Self::is_known], so `match` \
+             statements on this type always need a wildcard arm.",
+            class.name, e.name,
+        );
+
         quote! {
+            #[doc = #doc]
             #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
             pub struct #typ_name(pub i64);
 
             impl #typ_name {
                 #(#consts)*
+
+                /// Returns the name of the constant with this value, or `None` if the value is
+                /// unknown to these bindings.
+                #[inline]
+                pub fn name(self) -> Option<&'static str> {
+                    match self.0 {
+                        #(#names)*
+                        _ => None,
+                    }
+                }
+
+                /// Returns `true` if this value is one of the constants known to these bindings.
+                #[inline]
+                pub fn is_known(self) -> bool {
+                    self.name().is_some()
+                }
             }
             impl From<i64> for #typ_name {
                 #[inline]
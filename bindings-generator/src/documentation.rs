@@ -171,3 +171,141 @@ fn append_related_module(string: &mut String, class: &GodotClass) {
         .expect("append to string via write!");
     }
 }
+
+/// Generates a "Godot signature" section for `method`, with the argument types and default
+/// values as declared by the engine. Enum types are shown by their original names, since the
+/// Rust signature only contains the generated types.
+pub fn generate_method_signature_doc(class: &GodotClass, method: &GodotMethod) -> String {
+    // Setters of enum properties take an `int` in the API JSON, but the getters return the enum.
+    let setter_enum = class
+        .properties
+        .iter()
+        .filter(|property| property.setter == method.name)
+        .find_map(|property| class.methods.iter().find(|m| m.name == property.getter))
+        .map(|getter| getter.return_type.as_str())
+        .filter(|ty| ty.starts_with("enum."));
+
+    let arg_types = method
+        .arguments
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| match setter_enum {
+            Some(ty) if arg.ty == "int" && i + 1 == method.arguments.len() => ty,
+            _ => arg.ty.as_str(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut args = method
+        .arguments
+        .iter()
+        .zip(&arg_types)
+        .map(|(arg, ty)| {
+            let mut decl = format!("{}: {}", arg.name, godot_type_name(ty));
+            if arg.has_default_value {
+                decl.push_str(" = ");
+                decl.push_str(&godot_default_value(&arg.ty, &arg.default_value));
+            }
+            decl
+        })
+        .collect::<Vec<_>>();
+
+    if method.has_varargs {
+        args.push("...".into());
+    }
+
+    let mut doc = format!(
+        "\n# Godot signature\n\n```gdscript\nfunc {}({}) -> {}\n```",
+        method.name,
+        args.join(", "),
+        godot_type_name(&method.return_type),
+    );
+
+    let enums = method
+        .arguments
+        .iter()
+        .zip(&arg_types)
+        .map(|(arg, ty)| (format!("`{}`", arg.name), *ty))
+        .chain(Some((
+            "Return value".to_owned(),
+            method.return_type.as_str(),
+        )))
+        .filter(|(_, ty)| ty.starts_with("enum."))
+        .collect::<Vec<_>>();
+
+    if !enums.is_empty() {
+        doc.push_str("\n\nEnumerations:");
+        for (name, ty) in enums {
+            doc.push_str(&format!(
+                "\n* {name} - [`{}`]({})",
+                godot_type_name(ty),
+                enum_doc_url(ty)
+            ));
+        }
+    }
+
+    doc
+}
+
+/// Converts a type from the API JSON to the name used in the Godot documentation.
+fn godot_type_name(ty: &str) -> String {
+    match ty.strip_prefix("enum.") {
+        Some(path) => path.replace("::", "."),
+        None => ty.to_owned(),
+    }
+}
+
+/// Formats a default value from the API JSON as a GDScript expression.
+fn godot_default_value(ty: &str, value: &str) -> String {
+    match (ty, value) {
+        ("bool", "True") => "true".into(),
+        ("bool", "False") => "false".into(),
+        ("String" | "NodePath", value) => format!("{value:?}"),
+        (_, "Null" | "[Object:null]") => "null".into(),
+        (_, "[RID]") => "RID()".into(),
+        (ty, value) if value.starts_with('(') => format!("{ty}{value}"),
+        (ty, "") => format!("{ty}()"),
+        (_, value) => value.into(),
+    }
+}
+
+/// Returns the URL of the section of the Godot documentation describing the enum `ty`.
+fn enum_doc_url(ty: &str) -> String {
+    let path = ty.strip_prefix("enum.").unwrap_or(ty);
+    let (class, name) = path.split_once("::").unwrap_or(("@GlobalScope", path));
+    let class = class.to_lowercase();
+    format!(
+        "https://godot.readthedocs.io/en/stable/classes/class_{}.html#enum-{}-{}",
+        class,
+        class.trim_start_matches('@'),
+        name.to_lowercase(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_signature_doc() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let method = |class: &str, method: &str| {
+            let class = api.find_class(class).unwrap();
+            let method = class.methods.iter().find(|m| m.name == method).unwrap();
+            generate_method_signature_doc(class, method)
+        };
+
+        let doc = method("Node", "add_child");
+        assert!(
+            doc.contains("func add_child(node: Node, legible_unique_name: bool = false) -> void")
+        );
+
+        let doc = method("Node", "set_pause_mode");
+        assert!(doc.contains("func set_pause_mode(mode: Node.PauseMode) -> void"));
+        assert!(doc.contains(
+            "* `mode` - [`Node.PauseMode`](https://godot.readthedocs.io/en/stable/classes/class_node.html#enum-node-pausemode)"
+        ));
+
+        let doc = method("Node", "rpc");
+        assert!(doc.contains("func rpc(method: String, ...) -> Variant"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Extension points for custom binding crates.
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+
+use crate::api::{Api, GodotClass, GodotMethod};
+
+/// Callbacks that customize the generated bindings, passed to [`generate_bindings_with_hooks`].
+///
+/// All methods have default implementations that keep the standard output, so implementors only
+/// need to override the ones they are interested in.
+///
+/// Classes are generated in parallel, so the hooks may be called from several threads at once.
+///
+/// [`generate_bindings_with_hooks`]: crate::generate_bindings_with_hooks
+pub trait GeneratorHooks: Sync {
+    /// Returns `false` to leave `class` out of the bindings.
+    ///
+    /// Classes that inherit from an excluded class are excluded as well. Methods of other classes
+    /// that take or return an excluded class, or one of its enums, are skipped.
+    fn include_class(&self, _class: &GodotClass) -> bool {
+        true
+    }
+
+    /// Returns the Rust name of `method`, or `None` to use the default name.
+    ///
+    /// `default_name` is the name that would be generated otherwise, e.g. without the `get_`
+    /// prefix for property getters. Names that are Rust keywords are escaped with a trailing
+    /// underscore.
+    fn rename_method(
+        &self,
+        _class: &GodotClass,
+        _method: &GodotMethod,
+        _default_name: &str,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Returns additional items to place in the module of `class`, e.g. `impl` blocks with
+    /// custom methods.
+    ///
+    /// The items can refer to the generated types in the same way as the generated code does.
+    fn extra_items(&self, _class: &GodotClass) -> TokenStream {
+        TokenStream::new()
+    }
+}
+
+/// Hooks that keep the standard output.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoHooks;
+
+impl GeneratorHooks for NoHooks {}
+
+/// Hooks that leave out the classes that are only available in the editor, i.e. those with the
+/// `tools` API type, such as `EditorPlugin`.
+///
+/// This is intended for release exports, which never run in the editor.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StripTools;
+
+impl GeneratorHooks for StripTools {
+    fn include_class(&self, class: &GodotClass) -> bool {
+        class.api_type != "tools"
+    }
+}
+
+/// `GeneratorHooks` together with the set of excluded classes in an `Api`.
+pub(crate) struct Hooks<'a> {
+    hooks: &'a dyn GeneratorHooks,
+    excluded: HashSet<&'a str>,
+    available: HashSet<&'a str>,
+}
+
+impl<'a> Hooks<'a> {
+    pub(crate) fn new(api: &'a Api, hooks: &'a dyn GeneratorHooks) -> Self {
+        let mut excluded = HashSet::new();
+        for class in &api.classes {
+            let mut current = Some(class);
+            while let Some(c) = current {
+                if !hooks.include_class(c) {
+                    excluded.insert(class.name.as_str());
+                    break;
+                }
+                current = c.base_class_name().and_then(|name| api.find_class(name));
+            }
+        }
+
+        let available = api
+            .classes
+            .iter()
+            .map(|class| class.name.as_str())
+            .filter(|name| !excluded.contains(name))
+            .collect();
+
+        Hooks {
+            hooks,
+            excluded,
+            available,
+        }
+    }
+
+    /// Returns the names of the excluded classes, in no particular order.
+    pub(crate) fn excluded(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.excluded.iter().copied()
+    }
+
+    pub(crate) fn is_excluded(&self, class: &GodotClass) -> bool {
+        self.excluded.contains(class.name.as_str())
+    }
+
+    /// Returns `true` if a class named `name` is part of the bindings.
+    pub(crate) fn is_available(&self, name: &str) -> bool {
+        self.available.contains(name)
+    }
+
+    /// Returns `true` if the return type or an argument of `method` refers to an excluded class.
+    pub(crate) fn uses_excluded(&self, method: &GodotMethod) -> bool {
+        std::iter::once(&method.return_type)
+            .chain(method.arguments.iter().map(|arg| &arg.ty))
+            .any(|ty| {
+                // Enum types are spelled `enum.Class::Name`.
+                let class = match ty.strip_prefix("enum.") {
+                    Some(path) => path.split("::").next().unwrap_or(path),
+                    None => ty,
+                };
+                self.excluded.contains(class)
+            })
+    }
+
+    pub(crate) fn rename_method(
+        &self,
+        class: &GodotClass,
+        method: &GodotMethod,
+        default_name: &str,
+    ) -> Option<String> {
+        self.hooks.rename_method(class, method, default_name)
+    }
+
+    pub(crate) fn extra_items(&self, class: &GodotClass) -> TokenStream {
+        self.hooks.extra_items(class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+    use crate::generate_bindings_with_hooks;
+
+    struct TestHooks;
+
+    impl GeneratorHooks for TestHooks {
+        fn include_class(&self, class: &GodotClass) -> bool {
+            class.name != "Camera"
+        }
+
+        fn rename_method(
+            &self,
+            class: &GodotClass,
+            method: &GodotMethod,
+            _default_name: &str,
+        ) -> Option<String> {
+            (class.name == "Node" && method.name == "get_child_count")
+                .then(|| "child_count".to_owned())
+        }
+
+        fn extra_items(&self, class: &GodotClass) -> TokenStream {
+            if class.name == "Node" {
+                quote! { impl Node { pub fn custom_method(&self) {} } }
+            } else {
+                TokenStream::new()
+            }
+        }
+    }
+
+    #[test]
+    fn hooks_customize_bindings() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let result = generate_bindings_with_hooks(&api, None, &TestHooks);
+
+        let code = |name: &str| {
+            result
+                .class_bindings
+                .iter()
+                .find(|(class, _)| class.name == name)
+                .map(|(_, code)| code.to_string())
+        };
+
+        assert!(code("Camera").is_none());
+        assert!(code("ClippedCamera").is_none());
+        assert!(code("Spatial").is_some());
+
+        let viewport = code("Viewport").unwrap();
+        assert!(!viewport.contains("fn get_camera ("));
+        assert!(viewport.contains("fn get_texture ("));
+
+        let node = code("Node").unwrap();
+        assert!(node.contains("fn child_count ("));
+        assert!(!node.contains("fn get_child_count ("));
+        assert!(node.contains("fn custom_method ("));
+
+        assert_eq!(
+            vec![
+                "ARVRCamera",
+                "Camera",
+                "ClippedCamera",
+                "InterpolatedCamera"
+            ],
+            result.excluded_classes
+        );
+        assert!(result
+            .excluded_methods
+            .contains(&"Viewport::get_camera".to_owned()));
+    }
+
+    #[test]
+    fn strip_tools_excludes_editor_classes() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let result = generate_bindings_with_hooks(&api, None, &StripTools);
+
+        let generated = |name: &str| result.class_bindings.iter().any(|(c, _)| c.name == name);
+        assert!(!generated("EditorPlugin"));
+        assert!(!generated("EditorSpatialGizmoPlugin"));
+        assert!(generated("Node"));
+
+        assert!(result
+            .excluded_classes
+            .contains(&"EditorInterface".to_owned()));
+        assert!(result.exclusion_report().starts_with(&format!(
+            "{} classes excluded:\n",
+            result.excluded_classes.len()
+        )));
+    }
+}
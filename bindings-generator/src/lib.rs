@@ -8,16 +8,28 @@
 //!
 //! `/path/to/godot --gdnative-generate-json-api /path/to/api.json`
 //!
+//! To generate bindings for only some of the classes, the JSON data can be trimmed to the
+//! classes of interest. [`Api::stub_missing_classes`] then replaces the references to classes
+//! that were left out, so that the generated code stays self-contained.
+//!
+//! The output can be further customized by implementing [`GeneratorHooks`] and passing it to
+//! [`generate_bindings_with_hooks`], e.g. to rename methods, leave out classes that are not
+//! needed, or add custom `impl` blocks to the generated classes.
+//!
 //! *Please note that The generator is an internal dependency.* As such, it is not covered
 //! by semver guarantees of the main `gdnative` crate. When using custom binding crates, care
 //! must be taken to ensure that the version of the generator matches the one specified in
 //! the `Cargo.toml` of the `gdnative` crate exactly, even for updates that are considered
 //! non-breaking in the `gdnative` crate.
 
+mod async_methods;
+mod cache;
 mod class_docs;
 mod classes;
 mod documentation;
+mod hooks;
 mod methods;
+mod rids;
 mod special_methods;
 
 #[cfg(feature = "custom-godot")]
@@ -27,18 +39,25 @@ mod godot_version;
 pub mod api;
 pub mod dependency;
 
+use crate::cache::InputHasher;
 use crate::classes::*;
 use crate::documentation::*;
+use crate::hooks::Hooks;
 use crate::methods::*;
+use crate::rids::generate_typed_rids;
 use crate::special_methods::*;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
 use std::io;
 
 pub use api::*;
+pub use async_methods::generate_async_methods;
+pub use cache::BindingCache;
 pub use class_docs::*;
 pub use dependency::*;
+pub use hooks::{GeneratorHooks, NoHooks, StripTools};
 
 #[cfg(feature = "custom-godot")]
 pub use godot_api_json::*;
@@ -53,31 +72,154 @@ pub type GeneratorResult<T = ()> = Result<T, io::Error>;
 
 pub struct BindingResult<'a> {
     pub class_bindings: Vec<(&'a GodotClass, TokenStream)>,
+    /// Classes that were not generated by [`generate_bindings_incremental`] because their inputs
+    /// are unchanged since the last build. Their code from that build can be used as is.
+    pub unchanged_classes: Vec<&'a GodotClass>,
     pub icalls: TokenStream,
+    /// Names of the classes left out by the hooks, sorted.
+    pub excluded_classes: Vec<String>,
+    /// Methods of the generated classes that were left out because they take or return an
+    /// excluded class, as `Class::method`, sorted.
+    pub excluded_methods: Vec<String>,
+}
+
+impl BindingResult<'_> {
+    /// Returns a human-readable listing of the classes and methods left out by the hooks, one
+    /// per line, so that it can be checked that nothing needed was removed.
+    pub fn exclusion_report(&self) -> String {
+        let mut report = format!("{} classes excluded:\n", self.excluded_classes.len());
+        for class in &self.excluded_classes {
+            report += &format!("  {class}\n");
+        }
+
+        report += &format!(
+            "{} methods of other classes excluded:\n",
+            self.excluded_methods.len()
+        );
+        for method in &self.excluded_methods {
+            report += &format!("  {method}\n");
+        }
+
+        report
+    }
 }
 
 pub fn generate_bindings<'a>(api: &'a Api, docs: Option<&GodotXmlDocs>) -> BindingResult<'a> {
-    let mut icalls = HashMap::new();
+    generate_bindings_with_hooks(api, docs, &NoHooks)
+}
+
+/// Generates bindings like [`generate_bindings`], customized by `hooks`. This allows custom
+/// binding crates to rename methods, leave out classes or add their own items.
+pub fn generate_bindings_with_hooks<'a>(
+    api: &'a Api,
+    docs: Option<&GodotXmlDocs>,
+    hooks: &dyn GeneratorHooks,
+) -> BindingResult<'a> {
+    generate(api, docs, hooks, None)
+}
 
-    let class_bindings = api
+/// Generates bindings like [`generate_bindings_with_hooks`], but only for the classes whose
+/// inputs changed since the bindings were last generated with `cache`. The other classes are
+/// listed in [`BindingResult::unchanged_classes`]. The icalls are always generated for all
+/// classes.
+///
+/// Changes made to `api` after it was constructed are not detected, except for those made by
+/// [`Api::stub_missing_classes`]. Classes that were not constructed from JSON data are always
+/// generated.
+pub fn generate_bindings_incremental<'a>(
+    api: &'a Api,
+    docs: Option<&GodotXmlDocs>,
+    hooks: &dyn GeneratorHooks,
+    cache: &mut BindingCache,
+) -> BindingResult<'a> {
+    generate(api, docs, hooks, Some(cache))
+}
+
+fn generate<'a>(
+    api: &'a Api,
+    docs: Option<&GodotXmlDocs>,
+    hooks: &dyn GeneratorHooks,
+    cache: Option<&mut BindingCache>,
+) -> BindingResult<'a> {
+    let hooks = Hooks::new(api, hooks);
+    let classes = api
         .classes
         .iter()
-        .map(|class| {
-            (
-                class,
-                generate_class_bindings(api, class, &mut icalls, docs),
-            )
-        })
-        .collect();
+        .filter(|class| !hooks.is_excluded(class))
+        .collect::<Vec<_>>();
+
+    let mut icalls = BTreeMap::new();
+    for class in &classes {
+        collect_icalls(class, &hooks, &mut icalls);
+    }
 
     let icalls = icalls
         .into_iter()
-        .map(|(name, sig)| generate_icall(name, sig))
+        .map(|(name, method)| generate_icall(name, MethodSig::from_method(method)))
         .collect();
 
+    let hasher = cache.is_some().then(|| InputHasher::new(api, docs, &hooks));
+    let previous = cache.as_deref();
+
+    // Classes are generated in parallel. `TokenStream` is not `Send`, so their code is passed
+    // back as text.
+    let generated = classes
+        .par_iter()
+        .map(|&class| {
+            let hash = hasher.as_ref().and_then(|hasher| hasher.class_hash(class));
+            let unchanged = previous
+                .zip(hash)
+                .is_some_and(|(previous, hash)| previous.is_unchanged(class, hash));
+            let code =
+                (!unchanged).then(|| generate_class_bindings(api, class, docs, &hooks).to_string());
+            (class, hash, code)
+        })
+        .collect::<Vec<_>>();
+
+    let mut class_bindings = Vec::new();
+    let mut unchanged_classes = Vec::new();
+    let mut hashes = HashMap::new();
+    for (class, hash, code) in generated {
+        if let Some(hash) = hash {
+            hashes.insert(class.name.clone(), hash);
+        }
+
+        match code {
+            Some(code) => {
+                let code = code
+                    .parse()
+                    .expect("generated code should consist of valid tokens");
+                class_bindings.push((class, code));
+            }
+            None => unchanged_classes.push(class),
+        }
+    }
+
+    if let Some(cache) = cache {
+        cache.replace(hashes);
+    }
+
+    let mut excluded_classes = hooks.excluded().map(String::from).collect::<Vec<_>>();
+    excluded_classes.sort();
+
+    let mut excluded_methods = classes
+        .iter()
+        .flat_map(|class| {
+            class
+                .methods
+                .iter()
+                .filter(|method| hooks.uses_excluded(method))
+                .map(move |method| format!("{}::{}", class.name, method.name))
+        })
+        .collect::<Vec<_>>();
+    excluded_methods.sort();
+
     BindingResult {
         class_bindings,
+        unchanged_classes,
         icalls,
+        excluded_classes,
+        excluded_methods,
     }
 }
 
@@ -92,8 +234,8 @@ pub fn generate_imports() -> TokenStream {
 fn generate_class_bindings(
     api: &Api,
     class: &GodotClass,
-    icalls: &mut HashMap<String, MethodSig>,
     docs: Option<&GodotXmlDocs>,
+    hooks: &Hooks,
 ) -> TokenStream {
     // types and methods
     let types_and_methods = {
@@ -102,6 +244,7 @@ fn generate_class_bindings(
         let class_struct = generate_class_struct(class, class_doc);
 
         let enums = generate_enums(class);
+        let typed_rids = generate_typed_rids(class);
 
         let constants = if !class.constants.is_empty() {
             generate_class_constants(class)
@@ -109,25 +252,38 @@ fn generate_class_bindings(
             Default::default()
         };
 
-        let class_impl = generate_class_impl(class, icalls, docs);
+        let class_impl = generate_class_impl(class, docs, hooks);
+
+        let properties = if !class.properties.is_empty() {
+            generate_properties(api, class, hooks)
+        } else {
+            Default::default()
+        };
 
         quote! {
             #module_doc
             #class_struct
             #enums
+            #typed_rids
             #constants
             #class_impl
+            #properties
         }
     };
 
     // traits
     let traits = {
-        let object_impl = generate_godot_object_impl(class);
+        let object_impl = generate_godot_object_impl(api, class);
 
         let free_impl = generate_queue_free_impl(api, class);
 
         let base_class = if !class.base_class.is_empty() {
-            generate_deref_impl(class)
+            let deref = generate_deref_impl(class);
+            let upcast = generate_upcast_impl(class);
+            quote! {
+                #deref
+                #upcast
+            }
         } else {
             Default::default()
         };
@@ -147,6 +303,12 @@ fn generate_class_bindings(
             Default::default()
         };
 
+        let server_class = if class.is_server() {
+            generate_server_class_impl(class)
+        } else {
+            Default::default()
+        };
+
         quote! {
             #object_impl
             #free_impl
@@ -154,6 +316,7 @@ fn generate_class_bindings(
             #sub_class
             #instantiable
             #send_sync
+            #server_class
         }
     };
 
@@ -164,10 +327,13 @@ fn generate_class_bindings(
         Default::default()
     };
 
+    let extra_items = hooks.extra_items(class);
+
     quote! {
         #types_and_methods
         #traits
         #method_table
+        #extra_items
     }
 }
 
@@ -224,9 +390,11 @@ pub(crate) mod test_prelude {
         // Tests whether each generated snippet individually constitutes a valid AST representation of Rust code
 
         let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let hooks = Hooks::new(&api, &NoHooks);
         let mut buffer = BufWriter::new(Vec::with_capacity(16384));
         for class in &api.classes {
-            let mut icalls = HashMap::new();
+            let mut icalls = BTreeMap::new();
+            collect_icalls(class, &hooks, &mut icalls);
 
             let code = generate_module_doc(&class);
             write!(buffer, "{}", code).unwrap();
@@ -245,18 +413,22 @@ pub(crate) mod test_prelude {
             write!(buffer, "{}", code).unwrap();
             validate_and_clear_buffer!(buffer);
 
+            let code = generate_typed_rids(&class);
+            write!(buffer, "{}", code).unwrap();
+            validate_and_clear_buffer!(buffer);
+
             if !class.constants.is_empty() {
                 let code = generate_class_constants(&class);
                 write!(buffer, "{}", code).unwrap();
                 validate_and_clear_buffer!(buffer);
             }
 
-            let code = generate_class_impl(&class, &mut icalls, None);
+            let code = generate_class_impl(&class, None, &hooks);
             write!(buffer, "{}", code).unwrap();
             validate_and_clear_buffer!(buffer);
 
             // traits
-            let code = generate_godot_object_impl(&class);
+            let code = generate_godot_object_impl(&api, &class);
             write!(buffer, "{}", code).unwrap();
             validate_and_clear_buffer!(buffer);
 
@@ -268,6 +440,10 @@ pub(crate) mod test_prelude {
                 let code = generate_deref_impl(&class);
                 write!(buffer, "{}", code).unwrap();
                 validate_and_clear_buffer!(buffer);
+
+                let code = generate_upcast_impl(&class);
+                write!(buffer, "{}", code).unwrap();
+                validate_and_clear_buffer!(buffer);
             }
 
             // Instantiable
@@ -282,8 +458,8 @@ pub(crate) mod test_prelude {
             write!(buffer, "{}", code).unwrap();
             validate_and_clear_buffer!(buffer);
 
-            for (name, sig) in icalls {
-                let code = generate_icall(name, sig);
+            for (name, method) in icalls {
+                let code = generate_icall(name, MethodSig::from_method(method));
                 write!(buffer, "{}", code).unwrap();
                 validate_and_clear_buffer!(buffer);
             }
@@ -1,11 +1,14 @@
 use crate::api::*;
 use crate::class_docs::GodotXmlDocs;
+use crate::documentation::generate_method_signature_doc;
+use crate::hooks::Hooks;
+use crate::rids::MethodRids;
 use crate::rust_safe_name;
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Types of icalls.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -261,7 +264,7 @@ pub fn generate_method_table(api: &Api, class: &GodotClass) -> TokenStream {
 }
 
 /// Removes 'get_' from the beginning of `name` if `name` is a property getter on `class`.
-fn rename_property_getter<'a>(name: &'a str, class: &GodotClass) -> &'a str {
+pub(crate) fn rename_property_getter<'a>(name: &'a str, class: &GodotClass) -> &'a str {
     if name.starts_with("get_") && class.is_getter(name) {
         &name[4..]
     } else {
@@ -275,10 +278,38 @@ const UNSAFE_OBJECT_METHODS: &[(&str, &str)] = &[
     ("Object", "call_deferred"),
 ];
 
+/// Returns the methods of `class` that are generated, in order. Virtual methods, methods that
+/// are implemented by hand and methods that refer to excluded classes are left out.
+pub(crate) fn generated_methods<'a>(class: &'a GodotClass, hooks: &Hooks) -> Vec<&'a GodotMethod> {
+    let mut seen = HashSet::new();
+    class
+        .methods
+        .iter()
+        .filter(|method| {
+            let name = method.get_name().rust_name;
+            // Ensure that methods are not injected several times.
+            !skip_method(method, name) && !hooks.uses_excluded(method) && seen.insert(name)
+        })
+        .collect()
+}
+
+/// Adds the icalls used by the methods of `class` to `icalls`, keyed by name. The map is ordered,
+/// so that the icalls are generated in the same order every time.
+pub(crate) fn collect_icalls<'a>(
+    class: &'a GodotClass,
+    hooks: &Hooks,
+    icalls: &mut BTreeMap<String, &'a GodotMethod>,
+) {
+    for method in generated_methods(class, hooks) {
+        let name = MethodSig::from_method(method).function_name();
+        icalls.entry(name).or_insert(method);
+    }
+}
+
 pub(crate) fn generate_methods(
     class: &GodotClass,
-    icalls: &mut HashMap<String, MethodSig>,
     docs: Option<&GodotXmlDocs>,
+    hooks: &Hooks,
 ) -> TokenStream {
     /// Memorized information about generated methods. Used to generate indexed property accessors.
     struct Generated {
@@ -311,34 +342,37 @@ pub(crate) fn generate_methods(
     }
 
     let mut generated = HashMap::new();
+    let mut indexed = HashMap::new();
+    let mut taken_names = HashSet::new();
     let mut result = TokenStream::new();
 
-    for method in &class.methods {
+    for method in generated_methods(class, hooks) {
         let MethodName {
             rust_name: method_name,
             ..
         } = method.get_name();
 
-        if skip_method(method, method_name) {
-            continue;
-        }
-
         let mut ret_type = method.get_return_type();
         let mut rust_ret_type = ret_type.to_rust();
 
-        // Ensure that methods are not injected several times.
-        if generated.contains_key(method_name) {
-            continue;
+        // RIDs of servers are typed by the kind of resource they refer to
+        let rids = MethodRids::of(class, method, hooks);
+        if let Some(rid_ty) = &rids.return_type {
+            rust_ret_type = rid_ty.clone();
         }
 
         let mut params_decl = TokenStream::new();
         let mut params_use = TokenStream::new();
-        for argument in &method.arguments {
+        let mut param_types = Vec::new();
+        for (argument, rid_ty) in method.arguments.iter().zip(rids.arguments) {
             let ty = argument.get_type();
-            let rust_ty = ty.to_rust_arg();
             let name = rust_safe_name(&argument.name);
 
-            let arg_erased = arg_erase(&ty, &name);
+            let (rust_ty, arg_erased) = match rid_ty {
+                Some(rid_ty) => (rid_ty, quote! { #name.rid() }),
+                None => (ty.to_rust_arg(), arg_erase(&ty, &name)),
+            };
+            param_types.push(rust_ty.clone());
 
             params_decl.extend(quote! {
                 , #name: #rust_ty
@@ -378,9 +412,10 @@ pub(crate) fn generate_methods(
             maybe_unsafe_reason = "";
         }
 
-        icalls.insert(icall_name.clone(), method_sig);
-
-        let rusty_name = rust_safe_name(rusty_method_name);
+        let rusty_name = match hooks.rename_method(class, method, rusty_method_name) {
+            Some(name) => rust_safe_name(&name),
+            None => rust_safe_name(rusty_method_name),
+        };
 
         let method_bind_fetch = {
             let method_table = format_ident!("{}MethodTable", class.name);
@@ -394,11 +429,16 @@ pub(crate) fn generate_methods(
         let doc_comment = docs
             .and_then(|docs| docs.get_class_method_desc(class.name.as_str(), method_name))
             .unwrap_or("");
+        let signature_doc = generate_method_signature_doc(class, method);
 
-        let recover = ret_recover(&ret_type, icall_ty);
+        let mut recover = ret_recover(&ret_type, icall_ty);
+        if let Some(rid_ty) = &rids.return_type {
+            recover = quote! { <#rid_ty>::from_rid(#recover) };
+        }
 
         let output = quote! {
             #[doc = #doc_comment]
+            #[doc = #signature_doc]
             #[doc = #maybe_unsafe_reason]
             #[inline]
             pub #maybe_unsafe fn #rusty_name(&self #params_decl) -> #rust_ret_type {
@@ -414,6 +454,19 @@ pub(crate) fn generate_methods(
 
         result.extend(output);
 
+        taken_names.insert(rusty_name.to_string());
+        if maybe_unsafe.is_empty() && !method.has_varargs {
+            indexed.insert(
+                method_name,
+                IndexedCandidate {
+                    method,
+                    rusty_name,
+                    param_types,
+                    rust_ret_type,
+                },
+            );
+        }
+
         generated.insert(
             method_name.to_string(),
             Generated {
@@ -441,6 +494,7 @@ pub(crate) fn generate_methods(
         }) = generated.get(&property.getter)
         {
             let rusty_name = rust_safe_name(&property.name);
+            taken_names.insert(rusty_name.to_string());
             let rust_ret_type = ty.to_rust();
 
             let method_bind_fetch = {
@@ -489,6 +543,7 @@ pub(crate) fn generate_methods(
         }) = generated.get(&property.setter)
         {
             let rusty_name = rust_safe_name(&format!("set_{}", property.name));
+            taken_names.insert(rusty_name.to_string());
 
             let rust_arg_ty = ty.to_rust_arg();
             let arg_ident = format_ident!("value");
@@ -533,9 +588,195 @@ pub(crate) fn generate_methods(
         }
     }
 
+    result.extend(generate_indexed_accessors(
+        class,
+        &indexed,
+        &mut taken_names,
+    ));
+
     result
 }
 
+/// A generated safe method, which may be part of an indexed getter or setter pattern.
+struct IndexedCandidate<'a> {
+    method: &'a GodotMethod,
+    rusty_name: proc_macro2::Ident,
+    param_types: Vec<syn::Type>,
+    rust_ret_type: syn::Type,
+}
+
+impl IndexedCandidate<'_> {
+    /// Returns `true` if the first `arity` parameters are an index, followed by `arity - 1`
+    /// others. Parameters named `id` are identifiers that aren't bounded by the count, e.g. the
+    /// points of `AStar`.
+    fn is_indexed(&self, arity: usize) -> bool {
+        let args = &self.method.arguments;
+        args.len() == arity
+            && args[0].get_type() == Ty::I64
+            && args[0].name != "id"
+            && !args[0].name.ends_with("_id")
+    }
+}
+
+/// Generates accessors for the common pattern of a count getter, e.g. `get_point_count()`, and
+/// getters taking an index below the count, e.g. `get_point_position(idx)`:
+///
+/// - `iter_point_positions()` returns an iterator over the values of all indices.
+/// - `set_points(values)` sets the count to the number of values, and the value of each index,
+///   if the count can be set with `set_point_count(count)`, and the elements themselves, rather
+///   than one of their attributes, can be set with `set_point(idx, value)`. Setting only an
+///   attribute, e.g. with `set_point_position(idx, value)`, must not change the count, since it
+///   would remove or reset the other attributes.
+///
+/// Accessors that would have the same name as another method are not generated.
+fn generate_indexed_accessors(
+    class: &GodotClass,
+    candidates: &HashMap<&str, IndexedCandidate<'_>>,
+    taken_names: &mut HashSet<String>,
+) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    for count in &class.methods {
+        let stem = match count
+            .name
+            .strip_prefix("get_")
+            .and_then(|name| name.strip_suffix("_count"))
+        {
+            Some(stem) => stem,
+            None => continue,
+        };
+
+        let count = match candidates.get(count.name.as_str()) {
+            Some(count)
+                if count.method.arguments.is_empty()
+                    && count.method.get_return_type() == Ty::I64 =>
+            {
+                count
+            }
+            _ => continue,
+        };
+
+        let count_setter = candidates
+            .get(format!("set_{stem}_count").as_str())
+            .filter(|setter| setter.is_indexed(1));
+
+        let get_prefix = format!("get_{stem}");
+        let set_prefix = format!("set_{stem}");
+        let is_element = |name: &str| {
+            name.strip_prefix(&get_prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('_'))
+                && !name.ends_with("_count")
+        };
+
+        for method in &class.methods {
+            let candidate = match candidates.get(method.name.as_str()) {
+                Some(candidate) => candidate,
+                None => continue,
+            };
+
+            let count_fn = &count.rusty_name;
+            let element_fn = &candidate.rusty_name;
+
+            if is_element(&method.name)
+                && candidate.is_indexed(1)
+                && method.get_return_type() != Ty::Void
+            {
+                let name = format!("iter_{}", pluralize(&method.name["get_".len()..]));
+                if !taken_names.insert(name.clone()) {
+                    continue;
+                }
+
+                let name = rust_safe_name(&name);
+                let item = &candidate.rust_ret_type;
+                let doc = format!(
+                    "Returns an iterator over the values of [`{element_fn}`](Self::{element_fn}) for \
+                     all indices below [`{count_fn}`](Self::{count_fn}). The count is read when the \
+                     iterator is created.",
+                );
+
+                result.extend(quote! {
+                    #[doc = #doc]
+                    #[inline]
+                    pub fn #name(&self) -> impl Iterator<Item = #item> + '_ {
+                        (0..self.#count_fn()).map(move |idx| self.#element_fn(idx))
+                    }
+                });
+            } else if let Some(count_setter) = count_setter {
+                if method.name != set_prefix || !candidate.is_indexed(2) {
+                    continue;
+                }
+
+                let name = format!("set_{}", pluralize(&method.name["set_".len()..]));
+                if !taken_names.insert(name.clone()) {
+                    continue;
+                }
+
+                let name = rust_safe_name(&name);
+                let item = &candidate.param_types[1];
+                let count_setter = &count_setter.rusty_name;
+                let doc = format!(
+                    "Sets the count with [`{count_setter}`](Self::{count_setter}) to the number of \
+                     `values`, and sets each element with [`{element_fn}`](Self::{element_fn}).",
+                );
+
+                result.extend(quote! {
+                    #[doc = #doc]
+                    #[inline]
+                    pub fn #name(&self, values: impl IntoIterator<Item = #item>) {
+                        let values = values.into_iter().collect::<Vec<_>>();
+                        self.#count_setter(values.len() as i64);
+                        for (idx, value) in values.into_iter().enumerate() {
+                            self.#element_fn(idx as i64, value);
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the plural of a snake case `name`, by changing the last word that isn't a number,
+/// e.g. `point_position` becomes `point_positions`, and `instance_transform_2d` becomes
+/// `instance_transforms_2d`. Words that already end in `s` are left unchanged.
+fn pluralize(name: &str) -> String {
+    let mut words = name.split('_').collect::<Vec<_>>();
+    let index = match words
+        .iter()
+        .rposition(|word| !word.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        Some(index) => index,
+        None => return name.to_owned(),
+    };
+
+    let word = words[index];
+    let plural = match word {
+        "child" => "children".to_owned(),
+        "index" => "indices".to_owned(),
+        "vertex" => "vertices".to_owned(),
+        _ if word.ends_with("ss")
+            || word.ends_with("sh")
+            || word.ends_with("ch")
+            || word.ends_with('x') =>
+        {
+            format!("{word}es")
+        }
+        _ if word.ends_with('s') => word.to_owned(),
+        _ if word.ends_with('y')
+            && !word.ends_with("ay")
+            && !word.ends_with("ey")
+            && !word.ends_with("oy") =>
+        {
+            format!("{}ies", &word[..word.len() - 1])
+        }
+        _ => format!("{word}s"),
+    };
+
+    words[index] = &plural;
+    words.join("_")
+}
+
 /// Returns a message as to why this method would be unsafe; or None if the method is safe
 fn unsafe_reason(
     class: &GodotClass,
@@ -550,8 +791,8 @@ fn unsafe_reason(
     } else if method_sig.arguments.contains(&Ty::Rid) {
         Some(
             "\n# Safety\
-             \nThis function has parameters of type `Rid` (resource ID). \
-             RIDs are untyped and interpreted as raw pointers by the engine, so passing an incorrect RID can cause UB.")
+             \nThis function has parameters of type `Rid` (resource ID), or an RID type of a server. \
+             RIDs are interpreted as raw pointers by the engine, so passing an incorrect or freed RID can cause UB.")
     } else {
         None
     }
@@ -913,3 +1154,58 @@ mod varcall {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoHooks;
+
+    #[test]
+    fn pluralize_last_word() {
+        assert_eq!("point_positions", pluralize("point_position"));
+        assert_eq!("children", pluralize("child"));
+        assert_eq!("vertex_bones", pluralize("vertex_bones"));
+        assert_eq!("instance_transforms_2d", pluralize("instance_transform_2d"));
+        assert_eq!("bus_bypass_effects", pluralize("bus_bypass_effects"));
+        assert_eq!("screen_dpis", pluralize("screen_dpi"));
+        assert_eq!("entries", pluralize("entry"));
+    }
+
+    #[test]
+    fn indexed_accessors() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let hooks = Hooks::new(&api, &NoHooks);
+        let methods = |name: &str| {
+            let class = api.find_class(name).unwrap();
+            generate_methods(class, None, &hooks).to_string()
+        };
+
+        let line_2d = methods("Line2D");
+        assert!(line_2d.contains(
+            "pub fn iter_point_positions (& self) -> impl Iterator < Item = Vector2 > + '_"
+        ));
+        // The count can't be set
+        assert!(!line_2d.contains("fn set_point_positions"));
+
+        let node = methods("Node");
+        assert!(node.contains("pub fn iter_children (& self)"));
+
+        // Setting the count would reset the other attributes of the instances
+        let multi_mesh = methods("MultiMesh");
+        assert!(multi_mesh.contains("pub fn iter_instance_transforms (& self)"));
+        assert!(!multi_mesh.contains("fn set_instance_transforms"));
+
+        let audio_server = methods("AudioServer");
+        assert!(audio_server.contains("pub fn iter_bus_names (& self)"));
+        assert!(!audio_server.contains("fn set_bus_mutes"));
+
+        // Points of `AStar` are identified by IDs, not indices
+        let astar = methods("AStar");
+        assert!(!astar.contains("fn iter_point_positions"));
+
+        // Counts aren't elements
+        let text_edit = methods("TextEdit");
+        assert!(text_edit.contains("pub fn iter_lines (& self)"));
+        assert!(!text_edit.contains("fn iter_line_wrap_counts"));
+    }
+}
@@ -0,0 +1,379 @@
+//! Typed RID wrappers for the servers.
+//!
+//! `api.json` only tells that a method takes or returns an `RID`, not which kind of resource it
+//! refers to. The kinds are described here for each server, and the generated methods take and
+//! return a newtype per kind, e.g. `visual_server::CanvasItemRid`, instead of the untyped `Rid`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::api::{module_name_from_class_name, GodotClass, GodotMethod};
+use crate::hooks::Hooks;
+
+/// A kind of resource managed by a server, wrapped as `<name>Rid`.
+struct RidKind {
+    name: &'static str,
+    /// Prefix of the methods operating on this kind, e.g. `canvas_item` for `canvas_item_create`
+    /// and `canvas_item_add_line`. The first argument of these methods is of this kind, as well
+    /// as the return value of `<prefix>_create` and `<other>_<prefix>_create`.
+    prefix: &'static str,
+    /// Names of arguments of this kind in methods of any prefix.
+    args: &'static [&'static str],
+    /// Other methods returning an RID of this kind.
+    returns: &'static [&'static str],
+}
+
+const fn kind(
+    name: &'static str,
+    prefix: &'static str,
+    args: &'static [&'static str],
+    returns: &'static [&'static str],
+) -> RidKind {
+    RidKind {
+        name,
+        prefix,
+        args,
+        returns,
+    }
+}
+
+const NAVIGATION_KINDS: &[RidKind] = &[
+    kind("Map", "map", &["map"], &["agent_get_map", "region_get_map"]),
+    kind("Region", "region", &["region"], &[]),
+    kind("Agent", "agent", &["agent"], &[]),
+];
+
+#[rustfmt::skip]
+const PHYSICS_KINDS: &[RidKind] = &[
+    kind("Space", "space", &["space"], &["area_get_space", "body_get_space"]),
+    kind("Area", "area", &["area"], &[]),
+    kind("Body", "body", &["body", "body_a", "body_b", "body_A", "body_B", "excepted_body"], &[]),
+    kind("Shape", "shape", &["shape"], &["area_get_shape", "body_get_shape"]),
+    kind("Joint", "joint", &["joint"], &[]),
+];
+
+#[rustfmt::skip]
+const VISUAL_KINDS: &[RidKind] = &[
+    kind("Camera", "camera", &["camera"], &[]),
+    kind("Canvas", "canvas", &["canvas"], &[]),
+    kind("CanvasItem", "canvas_item", &["item"], &[]),
+    kind("CanvasLight", "canvas_light", &[], &[]),
+    kind("CanvasLightOccluder", "canvas_light_occluder", &[], &[]),
+    kind("CanvasOccluderPolygon", "canvas_occluder_polygon", &["occluder_polygon", "polygon"], &[]),
+    kind("Environment", "environment", &["env", "environment"], &[]),
+    kind("GiProbe", "gi_probe", &[], &[]),
+    kind("Immediate", "immediate", &["immediate"], &[]),
+    kind("Instance", "instance", &["instance", "as_lod_of_instance", "lightmap_instance"], &[]),
+    kind("Light", "light", &[], &[]),
+    kind("LightmapCapture", "lightmap_capture", &[], &[]),
+    kind("Material", "material", &["material", "next_material", "shader_material"], &["immediate_get_material", "mesh_surface_get_material"]),
+    kind("Mesh", "mesh", &["mesh"], &["get_test_cube", "make_sphere_mesh", "multimesh_get_mesh"]),
+    kind("Multimesh", "multimesh", &["multimesh"], &[]),
+    kind("Particles", "particles", &["particles"], &[]),
+    kind("ReflectionProbe", "reflection_probe", &[], &[]),
+    kind("Scenario", "scenario", &["scenario"], &[]),
+    kind("Shader", "shader", &["shader"], &["material_get_shader"]),
+    kind("Skeleton", "skeleton", &["skeleton"], &[]),
+    kind("Sky", "sky", &["sky"], &[]),
+    kind("Texture", "texture", &["texture", "normal_map", "cube_map", "ramp", "lightmap", "left", "right", "top", "bottom"], &["get_test_texture", "get_white_texture", "shader_get_default_texture_param", "viewport_get_texture"]),
+    kind("Viewport", "viewport", &["viewport", "parent_viewport"], &[]),
+];
+
+/// Kinds of RIDs per server class.
+const SERVERS: &[(&str, &[RidKind])] = &[
+    ("Navigation2DServer", NAVIGATION_KINDS),
+    ("NavigationServer", NAVIGATION_KINDS),
+    ("Physics2DServer", PHYSICS_KINDS),
+    ("PhysicsServer", PHYSICS_KINDS),
+    ("VisualServer", VISUAL_KINDS),
+];
+
+/// RIDs of other classes that refer to server resources, as `(class, method, argument, server,
+/// kind)`. The argument is `None` for return values.
+#[rustfmt::skip]
+const OTHER_METHODS: &[(&str, &str, Option<&str>, &str, &str)] = &[
+    ("Camera", "get_camera_rid", None, "VisualServer", "Camera"),
+    ("CanvasItem", "get_canvas", None, "VisualServer", "Canvas"),
+    ("CanvasItem", "get_canvas_item", None, "VisualServer", "CanvasItem"),
+    ("CanvasLayer", "get_canvas", None, "VisualServer", "Canvas"),
+    ("Font", "draw", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Font", "draw_char", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Navigation", "get_rid", None, "NavigationServer", "Map"),
+    ("Navigation2D", "get_rid", None, "Navigation2DServer", "Map"),
+    ("NavigationAgent", "get_navigation_map", None, "NavigationServer", "Map"),
+    ("NavigationAgent", "get_rid", None, "NavigationServer", "Agent"),
+    ("NavigationAgent", "set_navigation_map", Some("navigation_map"), "NavigationServer", "Map"),
+    ("NavigationAgent2D", "get_navigation_map", None, "Navigation2DServer", "Map"),
+    ("NavigationAgent2D", "get_rid", None, "Navigation2DServer", "Agent"),
+    ("NavigationAgent2D", "set_navigation_map", Some("navigation_map"), "Navigation2DServer", "Map"),
+    ("NavigationMeshInstance", "get_region_rid", None, "NavigationServer", "Region"),
+    ("NavigationObstacle", "get_rid", None, "NavigationServer", "Agent"),
+    ("NavigationObstacle2D", "get_rid", None, "Navigation2DServer", "Agent"),
+    ("NavigationPolygonInstance", "get_region_rid", None, "Navigation2DServer", "Region"),
+    ("Physics2DShapeQueryParameters", "get_shape_rid", None, "Physics2DServer", "Shape"),
+    ("Physics2DShapeQueryParameters", "set_shape_rid", Some("shape"), "Physics2DServer", "Shape"),
+    ("PhysicsShapeQueryParameters", "get_shape_rid", None, "PhysicsServer", "Shape"),
+    ("PhysicsShapeQueryParameters", "set_shape_rid", Some("shape"), "PhysicsServer", "Shape"),
+    ("Shape2D", "draw", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("StyleBox", "draw", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Texture", "draw", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Texture", "draw_rect", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Texture", "draw_rect_region", Some("canvas_item"), "VisualServer", "CanvasItem"),
+    ("Viewport", "get_viewport_rid", None, "VisualServer", "Viewport"),
+    ("VisualInstance", "get_instance", None, "VisualServer", "Instance"),
+    ("World", "get_navigation_map", None, "NavigationServer", "Map"),
+    ("World", "get_scenario", None, "VisualServer", "Scenario"),
+    ("World", "get_space", None, "PhysicsServer", "Space"),
+    ("World2D", "get_canvas", None, "VisualServer", "Canvas"),
+    ("World2D", "get_navigation_map", None, "Navigation2DServer", "Map"),
+    ("World2D", "get_space", None, "Physics2DServer", "Space"),
+];
+
+fn server_kinds(class_name: &str) -> &'static [RidKind] {
+    SERVERS
+        .iter()
+        .find(|(server, _)| *server == class_name)
+        .map_or(&[], |(_, kinds)| *kinds)
+}
+
+/// Returns the kind of the method's first argument, from the longest prefix of the method name.
+fn prefix_kind<'k>(kinds: &'k [RidKind], method_name: &str) -> Option<&'k RidKind> {
+    kinds
+        .iter()
+        .filter(|kind| {
+            method_name
+                .strip_prefix(kind.prefix)
+                .is_some_and(|rest| rest.starts_with('_'))
+        })
+        .max_by_key(|kind| kind.prefix.len())
+}
+
+fn server_return_kind<'k>(kinds: &'k [RidKind], method_name: &str) -> Option<&'k RidKind> {
+    if let Some((stem, _)) = method_name.split_once("_create") {
+        return kinds
+            .iter()
+            .filter(|kind| {
+                stem.strip_suffix(kind.prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.ends_with('_'))
+            })
+            .max_by_key(|kind| kind.prefix.len());
+    }
+
+    kinds
+        .iter()
+        .find(|kind| kind.returns.contains(&method_name))
+}
+
+fn server_arg_kind<'k>(
+    kinds: &'k [RidKind],
+    method: &GodotMethod,
+    arg_index: usize,
+) -> Option<&'k RidKind> {
+    if arg_index == 0 {
+        if let Some(kind) = prefix_kind(kinds, &method.name) {
+            return Some(kind);
+        }
+    }
+
+    let arg_name = method.arguments[arg_index].name.as_str();
+    kinds.iter().find(|kind| kind.args.contains(&arg_name))
+}
+
+fn typed_rid(server: &str, kind: &str, class: &GodotClass, hooks: &Hooks) -> Option<syn::Type> {
+    let name = format_ident!("{}Rid", kind);
+    if server == class.name {
+        // Types in the same module are referred to by name, like enums.
+        Some(syn::parse_quote! { #name })
+    } else if hooks.is_available(server) {
+        let module = format_ident!("{}", module_name_from_class_name(server));
+        Some(syn::parse_quote! { crate::generated::#module::#name })
+    } else {
+        None
+    }
+}
+
+/// Typed RIDs of a method: the types of the RID arguments, by index, and of the return value.
+pub(crate) struct MethodRids {
+    pub(crate) arguments: Vec<Option<syn::Type>>,
+    pub(crate) return_type: Option<syn::Type>,
+}
+
+impl MethodRids {
+    pub(crate) fn of(class: &GodotClass, method: &GodotMethod, hooks: &Hooks) -> Self {
+        let kinds = server_kinds(&class.name);
+
+        let arguments = method
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                if arg.ty != "RID" {
+                    return None;
+                }
+
+                if let Some(kind) = server_arg_kind(kinds, method, index) {
+                    return typed_rid(&class.name, kind.name, class, hooks);
+                }
+
+                OTHER_METHODS
+                    .iter()
+                    .find(|(c, m, a, _, _)| {
+                        *c == class.name && *m == method.name && *a == Some(arg.name.as_str())
+                    })
+                    .and_then(|(_, _, _, server, kind)| typed_rid(server, kind, class, hooks))
+            })
+            .collect();
+
+        // Varargs methods return a `Variant`
+        let return_type = if method.return_type != "RID" || method.has_varargs {
+            None
+        } else if let Some(kind) = server_return_kind(kinds, &method.name) {
+            typed_rid(&class.name, kind.name, class, hooks)
+        } else {
+            OTHER_METHODS
+                .iter()
+                .find(|(c, m, a, _, _)| *c == class.name && *m == method.name && a.is_none())
+                .and_then(|(_, _, _, server, kind)| typed_rid(server, kind, class, hooks))
+        };
+
+        MethodRids {
+            arguments,
+            return_type,
+        }
+    }
+}
+
+/// Generates the RID newtypes of a server class.
+pub(crate) fn generate_typed_rids(class: &GodotClass) -> TokenStream {
+    let rids = server_kinds(&class.name).iter().map(|kind| {
+        let name = format_ident!("{}Rid", kind.name);
+        let doc = format!(
+            "RID of a `{}` resource of the `{}`.\n\n\
+             Wrapping RIDs by kind prevents passing an RID to a method expecting a different \
+             kind, or to another server. Methods taking RIDs are still `unsafe`, since the \
+             resource may have been freed.",
+            kind.prefix, class.name,
+        );
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+            #[repr(transparent)]
+            pub struct #name(Rid);
+
+            impl #name {
+                /// Wraps an untyped RID, which must refer to a resource of this kind, e.g. one
+                /// returned by `Resource::get_rid`.
+                #[inline]
+                pub fn from_rid(rid: Rid) -> Self {
+                    Self(rid)
+                }
+
+                /// Returns the untyped RID.
+                #[inline]
+                pub fn rid(self) -> Rid {
+                    self.0
+                }
+
+                /// Checks if this RID is non-empty. This does **not** mean it's valid or safe
+                /// to use.
+                #[inline]
+                pub fn is_occupied(self) -> bool {
+                    self.0.is_occupied()
+                }
+            }
+
+            impl From<#name> for Rid {
+                #[inline]
+                fn from(rid: #name) -> Self {
+                    rid.0
+                }
+            }
+
+            impl ToVariant for #name {
+                #[inline]
+                fn to_variant(&self) -> Variant {
+                    self.0.to_variant()
+                }
+            }
+
+            impl FromVariant for #name {
+                #[inline]
+                fn from_variant(v: &Variant) -> Result<Self, FromVariantError> {
+                    Rid::from_variant(v).map(Self)
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#rids)*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Api;
+    use crate::hooks::NoHooks;
+
+    fn rids(api: &Api, class: &str, method: &str) -> (Vec<Option<String>>, Option<String>) {
+        let hooks = Hooks::new(api, &NoHooks);
+        let class = api.find_class(class).unwrap();
+        let method = class.methods.iter().find(|m| m.name == method).unwrap();
+        let rids = MethodRids::of(class, method, &hooks);
+        let name = |ty: syn::Type| quote!(#ty).to_string().replace(' ', "");
+        (
+            rids.arguments.into_iter().map(|ty| ty.map(name)).collect(),
+            rids.return_type.map(name),
+        )
+    }
+
+    #[test]
+    fn server_rids() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        let some = |name: &str| Some(name.to_owned());
+
+        let (_, ret) = rids(&api, "VisualServer", "canvas_item_create");
+        assert_eq!(some("CanvasItemRid"), ret);
+
+        let (args, _) = rids(&api, "VisualServer", "canvas_item_set_parent");
+        assert_eq!(vec![some("CanvasItemRid"), None], args);
+
+        let (args, _) = rids(&api, "VisualServer", "canvas_light_attach_to_canvas");
+        assert_eq!(vec![some("CanvasLightRid"), some("CanvasRid")], args);
+
+        let (_, ret) = rids(&api, "VisualServer", "omni_light_create");
+        assert_eq!(some("LightRid"), ret);
+
+        let (args, _) = rids(&api, "VisualServer", "free_rid");
+        assert_eq!(vec![None], args);
+
+        let (_, ret) = rids(&api, "Physics2DServer", "circle_shape_create");
+        assert_eq!(some("ShapeRid"), ret);
+
+        let (args, ret) = rids(&api, "PhysicsServer", "body_get_shape");
+        assert_eq!(vec![some("BodyRid"), None], args);
+        assert_eq!(some("ShapeRid"), ret);
+
+        let (_, ret) = rids(&api, "CanvasItem", "get_canvas_item");
+        assert_eq!(some("crate::generated::visual_server::CanvasItemRid"), ret);
+
+        let (_, ret) = rids(&api, "World2D", "get_space");
+        assert_eq!(some("crate::generated::physics_2d_server::SpaceRid"), ret);
+    }
+
+    #[test]
+    fn other_methods_exist() {
+        let api = Api::new(include_str!("../../gdnative-bindings/api.json"));
+        for (class, method, arg, server, kind) in OTHER_METHODS {
+            let class = api.find_class(class).unwrap();
+            let method = class.methods.iter().find(|m| m.name == *method).unwrap();
+            match arg {
+                Some(arg) => assert!(method.arguments.iter().any(|a| a.name == *arg)),
+                None => assert_eq!("RID", method.return_type),
+            }
+            assert!(server_kinds(server).iter().any(|k| k.name == *kind));
+        }
+    }
+}
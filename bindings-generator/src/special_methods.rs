@@ -38,7 +38,7 @@ destroying the object) or destroyed manually using `Ref::free`, or preferably
     }
 }
 
-pub fn generate_godot_object_impl(class: &GodotClass) -> TokenStream {
+pub fn generate_godot_object_impl(api: &Api, class: &GodotClass) -> TokenStream {
     let name = &class.name;
     let class_name = format_ident!("{}", class.name);
 
@@ -48,20 +48,64 @@ pub fn generate_godot_object_impl(class: &GodotClass) -> TokenStream {
         quote! { memory::ManuallyManaged }
     };
 
+    let virtual_methods = generate_virtual_methods(api, class);
+
     quote! {
         impl gdnative_core::private::godot_object::Sealed for #class_name {}
 
         unsafe impl GodotObject for #class_name {
             type Memory = #memory;
 
-            #[inline]
-            fn class_name() -> &'static str {
-                #name
-            }
+            const CLASS_NAME: &'static str = #name;
+
+            #virtual_methods
         }
     }
 }
 
+/// Generates `GodotObject::VIRTUAL_METHODS`. Only the virtual methods declared by `class` itself
+/// are listed here, the ones of the base classes are taken from their own lists.
+fn generate_virtual_methods(api: &Api, class: &GodotClass) -> TokenStream {
+    fn type_name(ty: &str) -> &str {
+        match Ty::from_src(ty) {
+            Ty::I64
+            | Ty::Vector3Axis
+            | Ty::Result
+            | Ty::VariantType
+            | Ty::VariantOperator
+            | Ty::Enum(_) => "int",
+            Ty::Object(_) => "Object",
+            _ => ty,
+        }
+    }
+
+    let own = class.methods.iter().filter(|m| m.is_virtual).map(|m| {
+        let name = &m.name;
+        let args = m.arguments.iter().map(|arg| type_name(&arg.ty));
+        let return_type = type_name(&m.return_type);
+        quote! {
+            gdnative_core::object::VirtualMethod::new(#name, &[#(#args),*], #return_type)
+        }
+    });
+
+    let mut bases = Vec::new();
+    let mut current = class;
+    while let Some(base) = current.base_class(api) {
+        let base_ident = format_ident!("{}", base.name);
+        bases.push(quote! {
+            <crate::generated::#base_ident as GodotObject>::VIRTUAL_METHODS[0]
+        });
+        current = base;
+    }
+
+    quote! {
+        const VIRTUAL_METHODS: &'static [&'static [gdnative_core::object::VirtualMethod]] = &[
+            &[#(#own),*],
+            #(#bases),*
+        ];
+    }
+}
+
 pub fn generate_instantiable_impl(class: &GodotClass) -> TokenStream {
     assert!(class.instantiable, "class should be instantiable");
 
@@ -189,6 +233,34 @@ pub fn generate_deref_impl(class: &GodotClass) -> TokenStream {
     }
 }
 
+pub fn generate_upcast_impl(class: &GodotClass) -> TokenStream {
+    assert!(
+        !class.base_class.is_empty(),
+        "should not be called on a class with no base_class"
+    );
+
+    let class_name = format_ident!("{}", class.name);
+    let base_class = format_ident!("{}", class.base_class);
+    let method_name = format_ident!("as_{}", module_name_from_class_name(&class.base_class));
+
+    let doc = format!(
+        "Returns a reference to this object as its base class, `{}`.\n\n\
+        This is equivalent to dereferencing, and a no-op at runtime. Objects can be upcast to \
+        other ancestors by chaining these methods, or with `upcast`.",
+        class.base_class,
+    );
+
+    quote! {
+        impl #class_name {
+            #[doc = #doc]
+            #[inline]
+            pub fn #method_name(&self) -> &crate::generated::#base_class {
+                self
+            }
+        }
+    }
+}
+
 pub fn generate_sub_class_impls<'a>(api: &'a Api, mut class: &'a GodotClass) -> TokenStream {
     let class_name = format_ident!("{}", class.name);
 
@@ -214,6 +286,28 @@ pub fn generate_send_sync_impls(class: &GodotClass) -> TokenStream {
     quote! {
         unsafe impl Send for #class_name {}
         unsafe impl Sync for #class_name {}
+
+        unsafe impl ThreadSafeSingleton for #class_name {
+            #[inline]
+            fn singleton() -> &'static Self {
+                Self::godot_singleton()
+            }
+        }
+    }
+}
+
+pub fn generate_server_class_impl(class: &GodotClass) -> TokenStream {
+    assert!(class.is_server(), "class should be a server");
+    let class_name = format_ident!("{}", class.name);
+
+    quote! {
+        unsafe impl ServerClass for #class_name {
+            #[inline]
+            #[allow(unused_unsafe)]
+            unsafe fn server() -> &'static Self {
+                unsafe { Self::godot_singleton() }
+            }
+        }
     }
 }
 
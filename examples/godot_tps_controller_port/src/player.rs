@@ -265,6 +265,8 @@ impl Player {
 #[methods]
 impl Player {
     fn new(owner: &KinematicBody) -> Self {
+        Input::godot_singleton().set_mouse_mode(Input::MOUSE_MODE_CAPTURED);
+
         Player {
             // VARIABLES
             airborne_time: 100.0,
@@ -288,11 +290,6 @@ impl Player {
         }
     }
 
-    #[method]
-    fn _init(&self) {
-        Input::godot_singleton().set_mouse_mode(Input::MOUSE_MODE_CAPTURED);
-    }
-
     #[method]
     fn _ready(&mut self, #[base] owner: &KinematicBody) {
         // UPDATING ALL ONREADY VARIABLES
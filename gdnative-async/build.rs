@@ -0,0 +1,20 @@
+use gdnative_bindings_generator as gen;
+
+use std::path::PathBuf;
+
+fn main() {
+    // Set by the build script of `gdnative-bindings`, so that the wrappers match the bindings.
+    let api_json = std::env::var("DEP_GDNATIVE_BINDINGS_API_JSON")
+        .expect("gdnative-bindings should provide the path to api.json");
+
+    let api_data = std::fs::read_to_string(&api_json).expect("Unable to read api.json");
+    let api = gen::Api::new(&api_data);
+
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let async_methods = gen::generate_async_methods(&api);
+    std::fs::write(out_path.join("async_methods.rs"), async_methods.to_string())
+        .expect("Unable to write async_methods.rs");
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={api_json}");
+}
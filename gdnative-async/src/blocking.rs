@@ -0,0 +1,49 @@
+//! Async variants of engine methods that block until a long-running operation is finished.
+//!
+//! The wrappers are generated from the engine API for the methods that are documented as
+//! blocking, and named after their class, e.g. [`IPAsync::resolve_hostname`] for
+//! `IP::resolve_hostname`. Methods of thread-safe singletons are called on a worker thread,
+//! and the future resolves once the call returns. `ResourceLoader::load` is instead driven
+//! over several frames by [`ResourceLoaderAsync`], which also allows reporting progress:
+//!
+//! ```ignore
+//! use gdnative::api::IP;
+//! use gdnative::prelude::*;
+//! use gdnative::tasks::blocking::{IPAsync, ResourceLoaderAsync};
+//!
+//! async fn connect_to_server() -> Option<Ref<PackedScene>> {
+//!     let address = IPAsync::resolve_hostname("example.com", IP::TYPE_ANY).await;
+//!     godot_print!("server address: {address}");
+//!
+//!     let lobby = ResourceLoaderAsync::load("res://lobby.tscn", "").await.ok()?;
+//!     lobby.cast::<PackedScene>()
+//! }
+//! ```
+
+use gdnative_bindings::*;
+use gdnative_core::core_types::*;
+use gdnative_core::object::ownership::Shared;
+use gdnative_core::object::Ref;
+
+use crate::future::{self, Yield};
+
+pub use crate::loader::ResourceLoaderAsync;
+
+include!(concat!(env!("OUT_DIR"), "/async_methods.rs"));
+
+/// Calls `f` on a new thread, and returns a future that resolves with its return value. The
+/// future never resolves if `f` panics.
+fn spawn_blocking<F, T>(f: F) -> Yield<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (future, resume) = future::make();
+
+    std::thread::Builder::new()
+        .name("gdnative-async-blocking".into())
+        .spawn(move || resume.resume(f()))
+        .expect("should be able to spawn a thread");
+
+    future
+}
@@ -0,0 +1,261 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use futures_task::{LocalFutureObj, LocalSpawn};
+
+use crate::executor::LocalExecutor;
+
+/// Runs queued work within a fixed amount of time per frame.
+///
+/// Procedural generation, streaming and other long-running jobs can be split into small units
+/// of work, queued as closures with [`push`](Self::push) or as futures with
+/// [`spawn`](Self::spawn), and then processed by calling [`run`](Self::run) once per frame,
+/// usually from `_process`. Each call runs work until the per-frame budget is exhausted, and
+/// leaves the rest for the following frames:
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use gdnative::prelude::*;
+/// use gdnative::tasks::Budget;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// struct World {
+///     budget: Budget,
+/// }
+///
+/// #[methods]
+/// impl World {
+///     fn new(_base: &Node) -> Self {
+///         let budget = Budget::new(Duration::from_millis(4));
+///         for chunk in 0..256 {
+///             budget.push(move || generate_chunk(chunk));
+///         }
+///         World { budget }
+///     }
+///
+///     #[method]
+///     fn _process(&self, _delta: f64) {
+///         self.budget.run();
+///     }
+/// }
+/// ```
+///
+/// Futures can call [`checkpoint`](Self::checkpoint) between units of work, which suspends them
+/// until the next frame once the budget is exhausted. This also works in async methods running
+/// on the global executor, as long as `run` is called every frame:
+///
+/// ```ignore
+/// #[method(async)]
+/// fn generate(&self, #[async_ctx] _ctx: Arc<Context>) -> impl Future<Output = i64> + 'static {
+///     let budget = self.budget.clone();
+///     async move {
+///         let mut count = 0;
+///         for chunk in 0..256 {
+///             budget.checkpoint().await;
+///             count += generate_chunk(chunk);
+///         }
+///         count
+///     }
+/// }
+/// ```
+///
+/// Work is never interrupted, so a frame overruns the budget by up to the duration of one
+/// unit of work. Overruns are carried over by shortening the budget of the next frame by the
+/// same amount, up to a full frame. At least one unit of work is run by each call to `run`,
+/// so queued work always makes progress.
+///
+/// `Budget` is a handle: clones share the same queue and budget. It can only be used on the
+/// thread it was created on.
+#[derive(Clone)]
+pub struct Budget {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    per_frame: Cell<Duration>,
+    frame: Cell<u64>,
+    deadline: Cell<Option<Instant>>,
+    debt: Cell<Duration>,
+    closures: RefCell<VecDeque<Box<dyn FnOnce()>>>,
+    executor: LocalExecutor,
+    waiting: RefCell<Vec<Waker>>,
+}
+
+impl Budget {
+    /// Creates a budget of `per_frame` with no queued work.
+    #[inline]
+    pub fn new(per_frame: Duration) -> Self {
+        Budget {
+            inner: Rc::new(Inner {
+                per_frame: Cell::new(per_frame),
+                frame: Cell::new(0),
+                deadline: Cell::new(None),
+                debt: Cell::new(Duration::ZERO),
+                closures: RefCell::default(),
+                executor: LocalExecutor::new(),
+                waiting: RefCell::default(),
+            }),
+        }
+    }
+
+    /// Returns the time available per frame.
+    #[inline]
+    pub fn per_frame(&self) -> Duration {
+        self.inner.per_frame.get()
+    }
+
+    /// Changes the time available per frame, starting with the next call to `run`.
+    #[inline]
+    pub fn set_per_frame(&self, per_frame: Duration) {
+        self.inner.per_frame.set(per_frame);
+    }
+
+    /// Queues a closure. Closures run in the order they were queued.
+    #[inline]
+    pub fn push<F>(&self, f: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        self.inner.closures.borrow_mut().push_back(Box::new(f));
+    }
+
+    /// Queues a future. Each poll of the future counts as one unit of work.
+    #[inline]
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.inner
+            .executor
+            .spawn_local_obj(LocalFutureObj::new(Box::new(future)))
+            .expect("LocalExecutor never fails to spawn");
+    }
+
+    /// Returns the number of queued closures and futures that have not completed yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.closures.borrow().len() + self.inner.executor.len()
+    }
+
+    /// Returns `true` if all queued work has completed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Starts a new frame, and runs queued work until the budget of the frame is exhausted or
+    /// no work is ready. Returns `true` if all work that was ready has been run.
+    ///
+    /// Futures suspended by [`checkpoint`](Self::checkpoint) are woken at the start of the call.
+    #[inline]
+    pub fn run(&self) -> bool {
+        let inner = &self.inner;
+
+        let start = Instant::now();
+        let available = inner.per_frame.get().saturating_sub(inner.debt.get());
+        let deadline = start + available;
+        inner.deadline.set(Some(deadline));
+        inner.frame.set(inner.frame.get() + 1);
+
+        for waker in inner.waiting.take() {
+            waker.wake();
+        }
+
+        // Ensures progress even if the budget is exhausted by the overrun of the last frame.
+        let mut first = true;
+        let mut has_time = || std::mem::take(&mut first) || Instant::now() < deadline;
+
+        let mut done = inner.executor.run_while(&mut has_time);
+        while done {
+            let Some(f) = inner.closures.borrow_mut().pop_front() else {
+                break;
+            };
+            if !has_time() {
+                inner.closures.borrow_mut().push_front(f);
+                done = false;
+                break;
+            }
+            f();
+        }
+
+        let elapsed = start.elapsed();
+        inner
+            .debt
+            .set(elapsed.saturating_sub(available).min(inner.per_frame.get()));
+
+        done
+    }
+
+    /// Returns the time left in the current frame, or zero if it is exhausted or `run` hasn't
+    /// been called yet.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.inner
+            .deadline
+            .get()
+            .map_or(Duration::ZERO, |deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            })
+    }
+
+    /// Returns a future that completes immediately if there is time left in the current frame,
+    /// and otherwise at the start of the next call to `run`.
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            budget: self.clone(),
+            suspended_in: None,
+        }
+    }
+}
+
+impl fmt::Debug for Budget {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Budget")
+            .field("per_frame", &self.per_frame())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Future returned by [`Budget::checkpoint`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Checkpoint {
+    budget: Budget,
+    /// Frame this was suspended in.
+    suspended_in: Option<u64>,
+}
+
+impl Future for Checkpoint {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let frame = self.budget.inner.frame.get();
+
+        // Resume once a new frame has started, even if it's already exhausted.
+        let new_frame = self
+            .suspended_in
+            .is_some_and(|suspended| suspended != frame);
+        if new_frame || self.budget.remaining() > Duration::ZERO {
+            return Poll::Ready(());
+        }
+
+        self.suspended_in = Some(frame);
+        self.budget
+            .inner
+            .waiting
+            .borrow_mut()
+            .push(cx.waker().clone());
+        Poll::Pending
+    }
+}
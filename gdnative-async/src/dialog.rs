@@ -0,0 +1,566 @@
+//! Helpers for showing dialogs and notifications from tool scripts and editor plugins.
+//!
+//! Showing a dialog from Rust usually involves creating the dialog node, adding it to the
+//! tree, connecting its signals, popping it up and freeing it once it's closed. The types in
+//! this module take care of all of that, and deliver the user's choice either to a callback,
+//! or as a future that can be `await`ed in async methods.
+//!
+//! Dialog nodes are freed automatically once hidden. If the parent node is freed while the
+//! dialog is still open, the callback is dropped without being called, and the future never
+//! resolves.
+//!
+//! The supporting classes are registered by [`register_runtime`](crate::register_runtime),
+//! which must be called before any dialog is shown.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use gdnative::prelude::*;
+//! use gdnative::tasks::dialog::{DialogResult, MessageDialog};
+//!
+//! fn ask_to_delete(parent: TRef<Node>) {
+//!     MessageDialog::confirm("Delete the selected nodes?")
+//!         .with_title("Please confirm")
+//!         .popup_with(parent, |result| {
+//!             if result == DialogResult::Confirmed {
+//!                 godot_print!("deleting...");
+//!             }
+//!         })
+//!         .expect("dialog should be shown");
+//! }
+//! ```
+
+use gdnative_bindings::{
+    file_dialog, AcceptDialog, ConfirmationDialog, FileDialog, Label, Node, Object, Popup,
+    PopupPanel, Reference, Timer,
+};
+use gdnative_core::core_types::{
+    GodotError, GodotString, PoolArray, Rect2, Variant, VariantArray, Vector2,
+};
+use gdnative_core::export::user_data::{LocalCellData, MapMut};
+use gdnative_core::export::{ClassBuilder, Method, NativeClass, NativeClassMethods, Varargs};
+use gdnative_core::godot_site;
+use gdnative_core::object::{Instance, SubClass, TInstance, TRef};
+
+use crate::future::{self, Yield};
+
+/// Outcome of a [`MessageDialog`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum DialogResult {
+    /// The "OK" button was pressed.
+    Confirmed,
+    /// The dialog was closed in any other way.
+    Cancelled,
+}
+
+/// A message or confirmation dialog, based on `AcceptDialog` and `ConfirmationDialog`
+/// respectively.
+#[derive(Clone, Debug)]
+pub struct MessageDialog {
+    confirm: bool,
+    text: GodotString,
+    title: Option<GodotString>,
+    ok_text: Option<GodotString>,
+    cancel_text: Option<GodotString>,
+    size: Vector2,
+}
+
+impl MessageDialog {
+    /// Creates a dialog showing `text`, with a single "OK" button.
+    #[inline]
+    pub fn accept(text: impl Into<GodotString>) -> Self {
+        MessageDialog {
+            confirm: false,
+            text: text.into(),
+            title: None,
+            ok_text: None,
+            cancel_text: None,
+            size: Vector2::ZERO,
+        }
+    }
+
+    /// Creates a dialog showing `text`, with "OK" and "Cancel" buttons.
+    #[inline]
+    pub fn confirm(text: impl Into<GodotString>) -> Self {
+        MessageDialog {
+            confirm: true,
+            ..Self::accept(text)
+        }
+    }
+
+    /// Sets the title of the dialog window.
+    #[inline]
+    pub fn with_title(mut self, title: impl Into<GodotString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the label of the "OK" button.
+    #[inline]
+    pub fn with_ok_text(mut self, text: impl Into<GodotString>) -> Self {
+        self.ok_text = Some(text.into());
+        self
+    }
+
+    /// Sets the label of the "Cancel" button. Ignored for dialogs created with [`Self::accept`].
+    #[inline]
+    pub fn with_cancel_text(mut self, text: impl Into<GodotString>) -> Self {
+        self.cancel_text = Some(text.into());
+        self
+    }
+
+    /// Sets the size of the dialog. By default, the dialog is sized to fit its content.
+    #[inline]
+    pub fn with_size(mut self, size: Vector2) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Adds the dialog as a child of `parent` and shows it. `callback` is called with the
+    /// result once the dialog is closed.
+    ///
+    /// # Errors
+    ///
+    /// If connection to the dialog's signals failed.
+    #[inline]
+    pub fn popup_with<P, F>(self, parent: TRef<'_, P>, callback: F) -> Result<(), GodotError>
+    where
+        P: SubClass<Node>,
+        F: FnOnce(DialogResult) + 'static,
+    {
+        let dialog = if self.confirm {
+            let dialog = ConfirmationDialog::new();
+            if let Some(cancel_text) = self.cancel_text {
+                if let Some(button) = dialog.get_cancel() {
+                    // SAFETY: the button is owned by the dialog, which was just created.
+                    unsafe { button.assume_safe() }.set_text(cancel_text);
+                }
+            }
+            dialog.upcast::<AcceptDialog>()
+        } else {
+            AcceptDialog::new()
+        };
+
+        dialog.set_text(self.text);
+        if let Some(title) = self.title {
+            dialog.set_title(title);
+        }
+        if let Some(ok_text) = self.ok_text {
+            if let Some(button) = dialog.get_ok() {
+                // SAFETY: the button is owned by the dialog, which was just created.
+                unsafe { button.assume_safe() }.set_text(ok_text);
+            }
+        }
+
+        let dialog = dialog.into_shared();
+        // SAFETY: the dialog was just created, and isn't accessible from anywhere else yet.
+        let dialog = unsafe { dialog.assume_safe() };
+
+        DialogBridge::attach(
+            dialog.upcast(),
+            &[("confirmed", EVENT_CONFIRMED, 0)],
+            move |event| {
+                callback(match event {
+                    Event::Confirmed => DialogResult::Confirmed,
+                    _ => DialogResult::Cancelled,
+                })
+            },
+        )
+        .map_err(|err| {
+            // The dialog isn't in the tree yet, so nothing else would free it.
+            dialog.queue_free();
+            err
+        })?;
+
+        show(parent.upcast(), dialog.upcast(), self.size);
+        Ok(())
+    }
+
+    /// Adds the dialog as a child of `parent` and shows it. Returns a future that resolves
+    /// with the result once the dialog is closed.
+    ///
+    /// # Errors
+    ///
+    /// If connection to the dialog's signals failed.
+    #[inline]
+    pub fn popup<P>(self, parent: TRef<'_, P>) -> Result<Yield<DialogResult>, GodotError>
+    where
+        P: SubClass<Node>,
+    {
+        let (future, resume) = future::make();
+        self.popup_with(parent, move |result| resume.resume(result))?;
+        Ok(future)
+    }
+}
+
+/// A file selection dialog, based on `FileDialog`.
+#[derive(Clone, Debug)]
+pub struct FilePicker {
+    mode: file_dialog::Mode,
+    access: file_dialog::Access,
+    filters: Vec<GodotString>,
+    current_dir: Option<GodotString>,
+    current_file: Option<GodotString>,
+    title: Option<GodotString>,
+    size: Vector2,
+}
+
+impl FilePicker {
+    /// Creates a file dialog operating in `mode`. The dialog browses the project's resources
+    /// (`res://`) by default.
+    #[inline]
+    pub fn new(mode: file_dialog::Mode) -> Self {
+        FilePicker {
+            mode,
+            access: file_dialog::Access::RESOURCES,
+            filters: Vec::new(),
+            current_dir: None,
+            current_file: None,
+            title: None,
+            size: Vector2::new(800.0, 500.0),
+        }
+    }
+
+    /// Sets the part of the file system that can be browsed.
+    #[inline]
+    pub fn with_access(mut self, access: file_dialog::Access) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Adds a file name filter, in the same format as `FileDialog::add_filter`,
+    /// e.g. `"*.png ; PNG Images"`.
+    #[inline]
+    pub fn with_filter(mut self, filter: impl Into<GodotString>) -> Self {
+        self.filters.push(filter.into());
+        self
+    }
+
+    /// Sets the directory shown initially.
+    #[inline]
+    pub fn with_current_dir(mut self, dir: impl Into<GodotString>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the file name selected initially.
+    #[inline]
+    pub fn with_current_file(mut self, file: impl Into<GodotString>) -> Self {
+        self.current_file = Some(file.into());
+        self
+    }
+
+    /// Sets the title of the dialog window. By default, the title is chosen according to the mode.
+    #[inline]
+    pub fn with_title(mut self, title: impl Into<GodotString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the size of the dialog.
+    #[inline]
+    pub fn with_size(mut self, size: Vector2) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Adds the dialog as a child of `parent` and shows it. `callback` is called once the
+    /// dialog is closed, with the selected paths, or `None` if the selection was cancelled.
+    ///
+    /// # Errors
+    ///
+    /// If connection to the dialog's signals failed.
+    #[inline]
+    pub fn popup_with<P, F>(self, parent: TRef<'_, P>, callback: F) -> Result<(), GodotError>
+    where
+        P: SubClass<Node>,
+        F: FnOnce(Option<Vec<GodotString>>) + 'static,
+    {
+        let dialog = FileDialog::new();
+        dialog.set_mode(self.mode.0);
+        dialog.set_access(self.access.0);
+        for filter in self.filters {
+            dialog.add_filter(filter);
+        }
+        if let Some(dir) = self.current_dir {
+            dialog.set_current_dir(dir);
+        }
+        if let Some(file) = self.current_file {
+            dialog.set_current_file(file);
+        }
+        if let Some(title) = self.title {
+            dialog.set_mode_overrides_title(false);
+            dialog.set_title(title);
+        }
+
+        let dialog = dialog.into_shared();
+        // SAFETY: the dialog was just created, and isn't accessible from anywhere else yet.
+        let dialog = unsafe { dialog.assume_safe() };
+
+        DialogBridge::attach(
+            dialog.upcast(),
+            &[
+                ("file_selected", EVENT_SELECTED, 0),
+                ("files_selected", EVENT_SELECTED, 0),
+                ("dir_selected", EVENT_SELECTED, 0),
+            ],
+            move |event| {
+                callback(match event {
+                    Event::Selected(paths) => Some(paths),
+                    _ => None,
+                })
+            },
+        )
+        .map_err(|err| {
+            // The dialog isn't in the tree yet, so nothing else would free it.
+            dialog.queue_free();
+            err
+        })?;
+
+        show(parent.upcast(), dialog.upcast(), self.size);
+        Ok(())
+    }
+
+    /// Adds the dialog as a child of `parent` and shows it. Returns a future that resolves
+    /// once the dialog is closed, with the selected paths, or `None` if the selection was
+    /// cancelled.
+    ///
+    /// # Errors
+    ///
+    /// If connection to the dialog's signals failed.
+    #[inline]
+    pub fn popup<P>(
+        self,
+        parent: TRef<'_, P>,
+    ) -> Result<Yield<Option<Vec<GodotString>>>, GodotError>
+    where
+        P: SubClass<Node>,
+    {
+        let (future, resume) = future::make();
+        self.popup_with(parent, move |paths| resume.resume(paths))?;
+        Ok(future)
+    }
+}
+
+/// A short, non-modal notification shown in the bottom-right corner of the viewport,
+/// that disappears by itself after some time.
+#[derive(Clone, Debug)]
+pub struct Toast {
+    text: GodotString,
+    duration: f64,
+}
+
+impl Toast {
+    /// Creates a notification showing `text` for 3 seconds.
+    #[inline]
+    pub fn new(text: impl Into<GodotString>) -> Self {
+        Toast {
+            text: text.into(),
+            duration: 3.0,
+        }
+    }
+
+    /// Sets the time in seconds before the notification disappears.
+    #[inline]
+    pub fn with_duration(mut self, seconds: f64) -> Self {
+        self.duration = seconds;
+        self
+    }
+
+    /// Adds the notification as a child of `parent` and shows it.
+    ///
+    /// # Errors
+    ///
+    /// If the notification could not be scheduled for removal.
+    #[inline]
+    pub fn show<P>(self, parent: TRef<'_, P>) -> Result<(), GodotError>
+    where
+        P: SubClass<Node>,
+    {
+        const MARGIN: f32 = 16.0;
+
+        let label = Label::new();
+        label.set_text(self.text);
+
+        let timer = Timer::new();
+        timer.set_one_shot(true);
+        timer.set_autostart(true);
+        timer.set_wait_time(self.duration);
+
+        let panel = PopupPanel::new();
+        panel.add_child(label, false);
+
+        let panel = panel.into_shared();
+        // SAFETY: the panel was just created, and isn't accessible from anywhere else yet.
+        let panel = unsafe { panel.assume_safe() };
+
+        if let Err(err) = timer.connect(
+            "timeout",
+            panel,
+            "queue_free",
+            VariantArray::new_shared(),
+            Object::CONNECT_ONESHOT,
+        ) {
+            // Neither node is in the tree yet, so nothing else would free them.
+            timer.free();
+            panel.queue_free();
+            return Err(err);
+        }
+        panel.add_child(timer, false);
+
+        let parent = parent.upcast::<Node>();
+        parent.add_child(panel, false);
+        panel.popup(Rect2::new(Vector2::ZERO, Vector2::ZERO));
+
+        let size = panel.get_combined_minimum_size();
+        panel.set_size(size, false);
+        if let Some(viewport) = parent.get_viewport() {
+            // SAFETY: the viewport is an ancestor of `parent`, which is assumed safe.
+            let visible = unsafe { viewport.assume_safe() }.get_visible_rect();
+            let position = visible.position + visible.size - size - Vector2::new(MARGIN, MARGIN);
+            panel.set_global_position(position, false);
+        }
+
+        Ok(())
+    }
+}
+
+fn show(parent: TRef<'_, Node>, dialog: TRef<'_, Popup>, size: Vector2) {
+    parent.add_child(dialog, false);
+    dialog.popup_centered(size);
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Signal bridge
+
+const BRIDGE_META: &str = "__gdnative_dialog_bridge";
+
+const EVENT_CONFIRMED: i64 = 0;
+const EVENT_SELECTED: i64 = 1;
+const EVENT_HIDDEN: i64 = 2;
+
+enum Event {
+    Confirmed,
+    Selected(Vec<GodotString>),
+    Hidden,
+}
+
+/// Receives signals from a dialog and forwards the first one to a callback. Kept alive by the
+/// dialog through its metadata, and dropped along with it.
+pub(crate) struct DialogBridge {
+    callback: Option<Box<dyn FnOnce(Event)>>,
+}
+
+impl NativeClass for DialogBridge {
+    type Base = Reference;
+    type UserData = LocalCellData<DialogBridge>;
+
+    fn nativeclass_register_properties(_builder: &ClassBuilder<Self>) {}
+}
+
+impl DialogBridge {
+    /// Connects `signals` of `dialog` to a new bridge, along with `popup_hide` which is
+    /// reported as `Event::Hidden`. Also makes sure that `dialog` is freed once hidden.
+    fn attach<F>(
+        dialog: TRef<'_, Popup>,
+        signals: &[(&str, i64, i64)],
+        callback: F,
+    ) -> Result<(), GodotError>
+    where
+        F: FnOnce(Event) + 'static,
+    {
+        let bridge = Instance::emplace(DialogBridge {
+            callback: Some(Box::new(callback)),
+        })
+        .into_shared();
+
+        // `popup_hide` is emitted before `confirmed` and the selection signals, so it's deferred
+        // to let them through first.
+        let hidden = ("popup_hide", EVENT_HIDDEN, Object::CONNECT_DEFERRED);
+
+        for &(signal, event, flags) in signals.iter().chain(std::iter::once(&hidden)) {
+            let binds = VariantArray::new();
+            binds.push(event);
+
+            dialog.connect(
+                signal,
+                bridge.base(),
+                "_on_event",
+                binds.into_shared(),
+                flags | Object::CONNECT_ONESHOT,
+            )?;
+        }
+
+        dialog.connect(
+            "popup_hide",
+            dialog,
+            "queue_free",
+            VariantArray::new_shared(),
+            Object::CONNECT_DEFERRED | Object::CONNECT_ONESHOT,
+        )?;
+
+        dialog.set_meta(BRIDGE_META, bridge);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct OnEventFn;
+
+impl Method<DialogBridge> for OnEventFn {
+    fn call(&self, this: TInstance<'_, DialogBridge>, args: Varargs<'_>) -> Variant {
+        let mut args: Vec<Variant> = args.cloned().collect();
+        let event = args.pop().and_then(|v| v.to::<i64>());
+
+        let event = match event {
+            Some(EVENT_CONFIRMED) => Event::Confirmed,
+            Some(EVENT_SELECTED) => {
+                let paths = args.into_iter().next().and_then(|arg| {
+                    arg.to::<PoolArray<GodotString>>()
+                        .map(|paths| paths.read().to_vec())
+                        .or_else(|| arg.to::<GodotString>().map(|path| vec![path]))
+                });
+
+                match paths {
+                    Some(paths) => Event::Selected(paths),
+                    None => {
+                        gdnative_core::log::error(
+                            Self::site().unwrap(),
+                            "unexpected arguments for selection signal",
+                        );
+                        return Variant::nil();
+                    }
+                }
+            }
+            Some(EVENT_HIDDEN) => Event::Hidden,
+            _ => {
+                gdnative_core::log::error(Self::site().unwrap(), "unknown dialog event");
+                return Variant::nil();
+            }
+        };
+
+        // Take the callback out before calling it, so it's free to show other dialogs.
+        let callback = this
+            .script()
+            .map_mut(|s| s.callback.take())
+            .expect("no reentrancy");
+
+        if let Some(callback) = callback {
+            callback(event);
+        }
+
+        Variant::nil()
+    }
+
+    fn site() -> Option<gdnative_core::log::Site<'static>> {
+        Some(godot_site!(DialogBridge::_on_event))
+    }
+}
+
+impl NativeClassMethods for DialogBridge {
+    fn nativeclass_register(builder: &ClassBuilder<Self>) {
+        builder.method("_on_event", OnEventFn).done_stateless();
+    }
+}
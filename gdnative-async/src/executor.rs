@@ -2,6 +2,10 @@ use std::cell::Cell;
 
 use futures_task::LocalSpawn;
 
+mod local;
+
+pub use local::LocalExecutor;
+
 thread_local!(
     static LOCAL_SPAWN: Cell<Option<&'static dyn LocalSpawn>> = Cell::new(None);
 );
@@ -0,0 +1,204 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use futures_task::{LocalFutureObj, LocalSpawn, SpawnError};
+use parking_lot::Mutex;
+
+/// A minimal single-threaded executor that can be used as the global executor for async
+/// methods.
+///
+/// Unlike general-purpose executors, `LocalExecutor` never spawns threads or blocks. Instead,
+/// tasks are polled when [`LocalExecutor::run_until_stalled`] is called, usually once per frame.
+/// This makes it suitable for platforms without thread support, such as HTML5 exports, where
+/// most thread-pool based executors cannot be used.
+///
+/// Tasks are never polled during `spawn`, so the `FuncState` returned by an async method is
+/// always received by the caller before the method can complete.
+///
+/// # Example
+///
+/// ```ignore
+/// use gdnative::prelude::*;
+/// use gdnative::tasks::LocalExecutor;
+///
+/// thread_local! {
+///     static EXECUTOR: &'static LocalExecutor = Box::leak(Box::default());
+/// }
+///
+/// struct Library;
+///
+/// #[gdnative::init::callbacks]
+/// impl GDNativeCallbacks for Library {
+///     fn nativescript_init(handle: InitHandle) {
+///         gdnative::tasks::register_runtime(&handle);
+///         gdnative::tasks::set_executor(EXECUTOR.with(|e| *e));
+///     }
+///
+///     fn nativescript_frame() {
+///         EXECUTOR.with(|e| e.run_until_stalled());
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct LocalExecutor {
+    tasks: RefCell<Vec<Option<LocalFutureObj<'static, ()>>>>,
+    vacant: RefCell<Vec<usize>>,
+    len: Cell<usize>,
+    running: Cell<bool>,
+    queue: Arc<ReadyQueue>,
+}
+
+#[derive(Default)]
+struct ReadyQueue {
+    ready: Mutex<VecDeque<usize>>,
+}
+
+struct TaskWaker {
+    index: usize,
+    queue: Arc<ReadyQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.queue.ready.lock().push_back(self.index);
+    }
+}
+
+struct RunningGuard<'a>(&'a Cell<bool>);
+
+impl<'a> Drop for RunningGuard<'a> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// Tasks taken from the ready queue by a single `run_while` call. Those that weren't polled are
+/// put back at the front of the queue on drop, even if a task panics.
+struct Batch<'a> {
+    tasks: VecDeque<usize>,
+    queue: &'a ReadyQueue,
+}
+
+impl<'a> Drop for Batch<'a> {
+    fn drop(&mut self) {
+        if !self.tasks.is_empty() {
+            let mut ready = self.queue.ready.lock();
+            self.tasks.append(&mut ready);
+            std::mem::swap(&mut *ready, &mut self.tasks);
+        }
+    }
+}
+
+impl LocalExecutor {
+    /// Creates a new executor with no tasks.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls all tasks that were woken since the last call, as well as newly spawned ones.
+    ///
+    /// Tasks woken or spawned while this runs, including tasks that wake themselves when polled,
+    /// are polled on the next call instead, so that a task yielding to the next frame can't keep
+    /// the calling thread busy forever.
+    ///
+    /// Wake-ups may come from any thread, but tasks are always polled on the calling thread.
+    /// Calls made from within a task being polled return immediately.
+    #[inline]
+    pub fn run_until_stalled(&self) {
+        self.run_while(|| true);
+    }
+
+    /// Like `run_until_stalled`, but stops early once `cond` returns `false`. `cond` is checked
+    /// before each poll. Returns `false` if it stopped early, in which case the remaining tasks
+    /// are polled first on the next call.
+    pub(crate) fn run_while(&self, mut cond: impl FnMut() -> bool) -> bool {
+        if self.running.replace(true) {
+            return true;
+        }
+
+        // Reset the flag even if a task panics, so the executor remains usable afterwards.
+        let _guard = RunningGuard(&self.running);
+
+        // Only the tasks that are ready now are polled, wake-ups from here on go to the queue.
+        let mut batch = Batch {
+            tasks: std::mem::take(&mut *self.queue.ready.lock()),
+            queue: &self.queue,
+        };
+
+        while let Some(index) = batch.tasks.pop_front() {
+            if !cond() {
+                batch.tasks.push_front(index);
+                return false;
+            }
+
+            // The slot may be empty if the task was woken multiple times and has completed since.
+            let mut future = match self
+                .tasks
+                .borrow_mut()
+                .get_mut(index)
+                .and_then(Option::take)
+            {
+                Some(future) => future,
+                None => continue,
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                index,
+                queue: Arc::clone(&self.queue),
+            }));
+
+            // The borrow on `tasks` must not be held here, since the future may spawn new tasks.
+            match Pin::new(&mut future).poll(&mut Context::from_waker(&waker)) {
+                Poll::Pending => self.tasks.borrow_mut()[index] = Some(future),
+                Poll::Ready(()) => {
+                    self.vacant.borrow_mut().push(index);
+                    self.len.set(self.len.get() - 1);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the number of tasks that have not completed yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if all spawned tasks have completed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl LocalSpawn for LocalExecutor {
+    #[inline]
+    fn spawn_local_obj(&self, future: LocalFutureObj<'static, ()>) -> Result<(), SpawnError> {
+        let mut tasks = self.tasks.borrow_mut();
+        let index = match self.vacant.borrow_mut().pop() {
+            Some(index) => {
+                tasks[index] = Some(future);
+                index
+            }
+            None => {
+                tasks.push(Some(future));
+                tasks.len() - 1
+            }
+        };
+
+        self.len.set(self.len.get() + 1);
+        self.queue.ready.lock().push_back(index);
+        Ok(())
+    }
+}
@@ -11,12 +11,20 @@
 // Workaround for macros that expect the `gdnative` crate.
 extern crate gdnative_core as gdnative;
 
+pub mod blocking;
+pub mod dialog;
+pub mod loader;
+
+mod budget;
 mod executor;
 mod future;
 mod method;
+mod profiling;
 mod rt;
 
-pub use executor::{set_boxed_executor, set_executor};
+pub use budget::{Budget, Checkpoint};
+pub use executor::{set_boxed_executor, set_executor, LocalExecutor};
 pub use future::Yield;
 pub use method::{Async, AsyncMethod, Spawner, StaticArgs, StaticArgsAsyncMethod};
+pub use profiling::{live_tasks, profiled, Profiled};
 pub use rt::{register_runtime, terminate_runtime, Context};
@@ -0,0 +1,197 @@
+//! Loading resources over several frames, without blocking the main thread.
+//!
+//! `ResourceLoader::load` blocks until the whole resource and all its dependencies are loaded,
+//! which can take a noticeable amount of time for large scenes. [`ResourceLoaderAsync`] instead
+//! drives a `ResourceInteractiveLoader` for a limited amount of time every frame, and
+//! delivers the resource as a future that can be `await`ed in async methods. This makes it
+//! easy to keep a loading screen animated and show progress:
+//!
+//! ```ignore
+//! use gdnative::prelude::*;
+//! use gdnative::tasks::loader::ResourceLoaderAsync;
+//!
+//! async fn load_level(progress_bar: Ref<ProgressBar>) -> Option<Ref<PackedScene>> {
+//!     let scene = ResourceLoaderAsync::new("res://levels/big.tscn")
+//!         .with_progress(move |progress| {
+//!             // SAFETY: progress is reported on the main thread.
+//!             unsafe { progress_bar.assume_safe() }.set_value(progress as f64 * 100.0);
+//!         })
+//!         .start()
+//!         .await
+//!         .ok()?;
+//!
+//!     scene.cast::<PackedScene>()
+//! }
+//! ```
+//!
+//! Loading is driven by a [frame hook](gdnative_core::init::register_frame_hook), so the
+//! futures only make progress while the game is running: they never resolve in the editor.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use gdnative_bindings::{Resource, ResourceInteractiveLoader, ResourceLoader};
+use gdnative_core::core_types::{GodotError, GodotString};
+use gdnative_core::init::{register_frame_hook, FrameHook, Phase};
+use gdnative_core::object::ownership::Shared;
+use gdnative_core::object::Ref;
+use parking_lot::Mutex;
+
+use crate::future::{self, Resume, Yield};
+
+/// Result of a [`ResourceLoaderAsync`].
+pub type LoadResult = Result<Ref<Resource, Shared>, GodotError>;
+
+type ProgressFn = Box<dyn FnMut(f32) + Send>;
+
+const DEFAULT_TIME_BUDGET: Duration = Duration::from_millis(10);
+
+/// Builder for loading a resource over several frames. See the [module-level docs](self).
+pub struct ResourceLoaderAsync {
+    path: GodotString,
+    type_hint: GodotString,
+    time_budget: Duration,
+    progress: Option<ProgressFn>,
+}
+
+impl ResourceLoaderAsync {
+    /// Creates a loader for the resource at `path`. By default, the loader spends up to 10
+    /// milliseconds on loading per frame.
+    #[inline]
+    pub fn new(path: impl Into<GodotString>) -> Self {
+        ResourceLoaderAsync {
+            path: path.into(),
+            type_hint: GodotString::new(),
+            time_budget: DEFAULT_TIME_BUDGET,
+            progress: None,
+        }
+    }
+
+    /// Sets the type hint, as in `ResourceLoader::load`.
+    #[inline]
+    pub fn with_type_hint(mut self, type_hint: impl Into<GodotString>) -> Self {
+        self.type_hint = type_hint.into();
+        self
+    }
+
+    /// Sets the time spent on loading per frame. At least one loading stage is processed
+    /// every frame, regardless of the budget.
+    #[inline]
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Sets a callback to be called on the main thread with the loading progress, between
+    /// `0.0` and `1.0`, after every frame where loading happened.
+    #[inline]
+    pub fn with_progress<F>(mut self, progress: F) -> Self
+    where
+        F: FnMut(f32) + Send + 'static,
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Starts loading the resource. Returns a future that resolves with the resource, or the
+    /// error that caused loading to fail.
+    #[inline]
+    pub fn start(self) -> Yield<LoadResult> {
+        let loader = ResourceLoader::godot_singleton().load_interactive(self.path, self.type_hint);
+        Self::drive(loader, self.time_budget, self.progress)
+    }
+
+    /// Drives `loader` with the default settings. Used by the generated
+    /// [`load`](Self::load), see [`blocking`](crate::blocking).
+    pub(crate) fn from_interactive(
+        loader: Option<Ref<ResourceInteractiveLoader, Shared>>,
+    ) -> Yield<LoadResult> {
+        Self::drive(loader, DEFAULT_TIME_BUDGET, None)
+    }
+
+    fn drive(
+        loader: Option<Ref<ResourceInteractiveLoader, Shared>>,
+        time_budget: Duration,
+        progress: Option<ProgressFn>,
+    ) -> Yield<LoadResult> {
+        let (future, resume) = future::make();
+
+        let loader = match loader {
+            Some(loader) => loader,
+            None => {
+                resume.resume(Err(GodotError::CantOpen));
+                return future;
+            }
+        };
+
+        let mut task = LoadTask {
+            loader,
+            time_budget,
+            progress,
+            resume: Some(resume),
+        };
+
+        // Hooks only start running on later frames, so the handle is always in place by the
+        // time the task finishes.
+        let handle: Arc<Mutex<Option<FrameHook>>> = Arc::default();
+        let hook_handle = Arc::clone(&handle);
+        let hook = register_frame_hook(Phase::PreProcess, move |_delta| {
+            if task.step() {
+                if let Some(hook) = hook_handle.lock().take() {
+                    hook.remove();
+                }
+            }
+        });
+        *handle.lock() = Some(hook);
+
+        future
+    }
+}
+
+struct LoadTask {
+    loader: Ref<ResourceInteractiveLoader, Shared>,
+    time_budget: Duration,
+    progress: Option<ProgressFn>,
+    resume: Option<Resume<LoadResult>>,
+}
+
+impl LoadTask {
+    /// Loads for up to one time budget. Returns `true` once the task is finished.
+    fn step(&mut self) -> bool {
+        let resume = match self.resume.take() {
+            Some(resume) => resume,
+            None => return true,
+        };
+
+        // SAFETY: the loader is only ever used from the main thread, where frame hooks run.
+        let loader = unsafe { self.loader.assume_safe() };
+
+        let start = Instant::now();
+        let result = loop {
+            match loader.poll() {
+                Ok(()) if start.elapsed() < self.time_budget => continue,
+                Ok(()) => break None,
+                Err(GodotError::FileEof) => {
+                    break Some(loader.get_resource().ok_or(GodotError::Failed))
+                }
+                Err(err) => break Some(Err(err)),
+            }
+        };
+
+        if let Some(progress) = &mut self.progress {
+            let stage_count = loader.get_stage_count().max(1);
+            progress(loader.get_stage() as f32 / stage_count as f32);
+        }
+
+        match result {
+            Some(result) => {
+                resume.resume(result);
+                true
+            }
+            None => {
+                self.resume = Some(resume);
+                false
+            }
+        }
+    }
+}
@@ -8,7 +8,9 @@ use gdnative_core::core_types::{ToVariant, Variant};
 use gdnative_core::export::{FromVarargs, Method, NativeClass, Varargs};
 use gdnative_core::log::{self, Site};
 use gdnative_core::object::TInstance;
+use gdnative_core::profiler::Signature;
 
+use crate::profiling::{self, TaskGuard};
 use crate::rt::Context;
 
 /// Trait for async methods. When exported, such methods return `FunctionState`-like
@@ -116,6 +118,7 @@ pub struct Spawner<'a, C: NativeClass, A = Varargs<'a>> {
     ctx: Context,
     this: TInstance<'a, C>,
     args: A,
+    profiler_tag: Option<Signature<'static>>,
     result: &'a mut Option<Result<(), SpawnError>>,
     /// Remove Send and Sync
     _marker: PhantomData<*const ()>,
@@ -131,6 +134,7 @@ impl<'a, C: NativeClass, A> Spawner<'a, C, A> {
             ctx,
             this,
             args,
+            profiler_tag,
             result,
             ..
         } = self;
@@ -140,6 +144,7 @@ impl<'a, C: NativeClass, A> Spawner<'a, C, A> {
                 ctx,
                 this,
                 args,
+                profiler_tag,
                 result,
                 _marker: PhantomData,
             }),
@@ -148,12 +153,27 @@ impl<'a, C: NativeClass, A> Spawner<'a, C, A> {
                 ctx,
                 this,
                 args: (),
+                profiler_tag,
                 result,
                 _marker: PhantomData,
             }),
         }
     }
 
+    /// Adds the time spent polling the spawned future to Godot's built-in profiler under
+    /// `signature`, in the "Script Functions" category. See [`profiled`](crate::profiled).
+    ///
+    /// ```ignore
+    /// spawner
+    ///     .with_profiler_tag(profile_sig!("load_level"))
+    ///     .spawn(|ctx, this, args| async move { /* ... */ });
+    /// ```
+    #[inline]
+    pub fn with_profiler_tag(mut self, signature: Signature<'static>) -> Self {
+        self.profiler_tag = Some(signature);
+        self
+    }
+
     /// Consumes this `Spawner` and spawns a future returned by the closure. This indirection
     /// is necessary so that implementors of the `AsyncMethod` trait do not have to name their
     /// future types.
@@ -165,13 +185,18 @@ impl<'a, C: NativeClass, A> Spawner<'a, C, A> {
     {
         let ctx = Arc::new(self.ctx);
         let future = f(Arc::clone(&ctx), self.this, self.args);
-        *self.result = Some(
-            self.sp
-                .spawn_local_obj(LocalFutureObj::new(Box::new(async move {
-                    let value = future.await;
-                    ctx.resolve(value);
-                }))),
-        );
+        let guard = TaskGuard::new();
+        let task = async move {
+            let _guard = guard;
+            let value = future.await;
+            ctx.resolve(value);
+        };
+
+        let task = match self.profiler_tag {
+            Some(signature) => LocalFutureObj::new(Box::new(profiling::profiled(signature, task))),
+            None => LocalFutureObj::new(Box::new(task)),
+        };
+        *self.result = Some(self.sp.spawn_local_obj(task));
     }
 }
 
@@ -201,6 +226,7 @@ impl<C: NativeClass, F: AsyncMethod<C>> Method<C> for Async<F> {
                 ctx,
                 this,
                 args,
+                profiler_tag: None,
                 result: &mut result,
                 _marker: PhantomData,
             });
@@ -0,0 +1,86 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use gdnative_core::profiler::Signature;
+
+static LIVE_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of futures spawned for async methods that have neither completed nor
+/// been dropped yet, across all threads.
+///
+/// This can be shown in a debug overlay to find tasks that never complete, e.g. because they
+/// wait for a signal that is never emitted.
+#[inline]
+pub fn live_tasks() -> usize {
+    LIVE_TASKS.load(Ordering::Relaxed)
+}
+
+/// Guard counting a task as live while it exists.
+pub(crate) struct TaskGuard(());
+
+impl TaskGuard {
+    pub(crate) fn new() -> Self {
+        LIVE_TASKS.fetch_add(1, Ordering::Relaxed);
+        TaskGuard(())
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        LIVE_TASKS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Wraps `future` so that the time spent in each of its polls is added to Godot's built-in
+/// profiler under `signature`, in the "Script Functions" category.
+///
+/// Time spent waiting between polls, e.g. for signals or timers, is not counted. This can be
+/// used inside async methods to profile parts of their work separately:
+///
+/// ```ignore
+/// use gdnative::prelude::*;
+/// use gdnative::profiler::profile_sig;
+/// use gdnative::tasks::profiled;
+///
+/// #[method]
+/// async fn generate(&self) -> i64 {
+///     profiled(profile_sig!("generate_terrain"), async {
+///         // ...
+///         42
+///     })
+///     .await
+/// }
+/// ```
+///
+/// Methods that implement [`AsyncMethod`](crate::AsyncMethod) manually can use
+/// [`Spawner::with_profiler_tag`](crate::Spawner::with_profiler_tag) to profile the entire
+/// task instead.
+#[inline]
+pub fn profiled<F: Future>(signature: Signature<'static>, future: F) -> Profiled<F> {
+    Profiled { signature, future }
+}
+
+/// Future returned by [`profiled`].
+pub struct Profiled<F> {
+    signature: Signature<'static>,
+    future: F,
+}
+
+impl<F: Future> Future for Profiled<F> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is structurally pinned, and never moved out of `self`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        let start = Instant::now();
+        let poll = future.poll(cx);
+        this.signature.add_data(start.elapsed());
+        poll
+    }
+}
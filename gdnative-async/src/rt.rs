@@ -109,6 +109,7 @@ where
 {
     handle.add_class_as::<bridge::SignalBridge>(format!("{prefix}SignalBridge"));
     handle.add_class_as::<func_state::FuncState>(format!("{prefix}FuncState"));
+    handle.add_class_as::<crate::dialog::DialogBridge>(format!("{prefix}DialogBridge"));
 }
 
 /// Releases all observers still in use. This should be called in the
@@ -16,7 +16,8 @@ fn main() {
 
     let api = gen::Api::new(&api_data);
     let docs = gen::GodotXmlDocs::new("docs");
-    let binding_res = gen::generate_bindings(&api, Some(&docs));
+    let mut cache = load_cache(&out_path);
+    let binding_res = generate_bindings(&api, &docs, cache.as_mut());
 
     {
         let mut output = BufWriter::new(File::create(&generated_rs).unwrap());
@@ -24,6 +25,10 @@ fn main() {
         generate(&out_path, &mut output, &binding_res);
     }
 
+    if let Some(cache) = cache {
+        cache.save().expect("Unable to write the bindings cache");
+    }
+
     {
         let mut output = BufWriter::new(File::create(&icalls_rs).unwrap());
 
@@ -33,6 +38,11 @@ fn main() {
     format_file_if_needed(&generated_rs);
     format_file_if_needed(&icalls_rs);
 
+    // Lets the build scripts of dependents generate code from the same API, as
+    // `DEP_GDNATIVE_BINDINGS_API_JSON`.
+    let api_json = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("api.json");
+    println!("cargo:api_json={}", api_json.display());
+
     // build.rs will automatically be recompiled and run if its dependencies are updated.
     // Ignoring everything but build.rs will avoid needless rebuilds.
     // Manually rebuilding the crate does not affect this.
@@ -45,9 +55,62 @@ fn main() {
     }
 }
 
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Feature 'strip-tools'
+
+/// Generates the bindings without the editor-only classes, and writes a report of everything that
+/// was left out to `stripped.txt` in `OUT_DIR`, as well as to the path in `GDNATIVE_STRIP_REPORT`
+/// if it's set.
+#[cfg(feature = "strip-tools")]
+fn generate_bindings<'a>(
+    api: &'a gen::Api,
+    docs: &gen::GodotXmlDocs,
+    cache: Option<&mut gen::BindingCache>,
+) -> gen::BindingResult<'a> {
+    let binding_res = generate_with_hooks(api, docs, &gen::StripTools, cache);
+
+    let report = binding_res.exclusion_report();
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_path.join("stripped.txt"), &report).unwrap();
+
+    println!("cargo:rerun-if-env-changed=GDNATIVE_STRIP_REPORT");
+    if let Some(path) = std::env::var_os("GDNATIVE_STRIP_REPORT") {
+        std::fs::write(path, &report).expect("Unable to write GDNATIVE_STRIP_REPORT");
+    }
+
+    binding_res
+}
+
+#[cfg(not(feature = "strip-tools"))]
+fn generate_bindings<'a>(
+    api: &'a gen::Api,
+    docs: &gen::GodotXmlDocs,
+    cache: Option<&mut gen::BindingCache>,
+) -> gen::BindingResult<'a> {
+    generate_with_hooks(api, docs, &gen::NoHooks, cache)
+}
+
+fn generate_with_hooks<'a>(
+    api: &'a gen::Api,
+    docs: &gen::GodotXmlDocs,
+    hooks: &dyn gen::GeneratorHooks,
+    cache: Option<&mut gen::BindingCache>,
+) -> gen::BindingResult<'a> {
+    match cache {
+        Some(cache) => gen::generate_bindings_incremental(api, Some(docs), hooks, cache),
+        None => gen::generate_bindings_with_hooks(api, Some(docs), hooks),
+    }
+}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 // Feature 'one-class-one-file'
 
+/// All the class bindings are output into a single file, so there's nothing to reuse.
+#[cfg(not(feature = "one-class-one-file"))]
+fn load_cache(_out_path: &Path) -> Option<gen::BindingCache> {
+    None
+}
+
 /// Output all the class bindings into the `generated.rs` file.
 #[cfg(not(feature = "one-class-one-file"))]
 fn generate(
@@ -81,8 +144,25 @@ fn generate(
     }
 }
 
+/// Loads the hashes of the classes generated by the last build, so that only the classes that
+/// changed since are generated again. Classes whose file is gone are generated again as well.
+#[cfg(feature = "one-class-one-file")]
+fn load_cache(out_path: &Path) -> Option<gen::BindingCache> {
+    let mut cache = gen::BindingCache::load(out_path.join("bindings.cache"));
+    cache.retain(|class| module_path(out_path, class).exists());
+    Some(cache)
+}
+
+#[cfg(feature = "one-class-one-file")]
+fn module_path(out_path: &Path, class_name: &str) -> PathBuf {
+    out_path.join(format!(
+        "{}.rs",
+        gen::module_name_from_class_name(class_name)
+    ))
+}
+
 /// Output one file for each class and add `mod` and `use` declarations in
-/// the `generated.rs` file.
+/// the `generated.rs` file. The files of unchanged classes are kept from the last build.
 #[cfg(feature = "one-class-one-file")]
 fn generate(
     out_path: &std::path::Path,
@@ -90,9 +170,7 @@ fn generate(
     binding_res: &gen::BindingResult,
 ) {
     for (class, code) in &binding_res.class_bindings {
-        let mod_name = gen::module_name_from_class_name(&class.name);
-
-        let mod_path = out_path.join(format!("{mod_name}.rs"));
+        let mod_path = module_path(out_path, &class.name);
         let mut mod_output = BufWriter::new(File::create(&mod_path).unwrap());
 
         write!(
@@ -107,6 +185,17 @@ fn generate(
         drop(mod_output);
 
         format_file_if_needed(&mod_path);
+    }
+
+    let classes = binding_res
+        .class_bindings
+        .iter()
+        .map(|(class, _)| *class)
+        .chain(binding_res.unchanged_classes.iter().copied());
+
+    for class in classes {
+        let mod_name = gen::module_name_from_class_name(&class.name);
+        let mod_path = module_path(out_path, &class.name);
 
         let modifier = if class.has_related_module() {
             "pub"
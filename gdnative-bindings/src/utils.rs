@@ -1,8 +1,12 @@
 //! Utility functions and extension traits that depend on generated bindings
 
+use std::collections::HashMap;
+use std::fmt;
+
 use gdnative_core::core_types::NodePath;
 use gdnative_core::export::NativeClass;
-use gdnative_core::object::{SubClass, TInstance, TRef};
+use gdnative_core::object::ownership::Shared;
+use gdnative_core::object::{GodotObject, InstanceId, Ref, SubClass, TInstance, TRef};
 
 use super::generated::{Engine, Node, SceneTree};
 
@@ -77,3 +81,302 @@ impl<N: SubClass<Node>, P: Into<NodePath>> NodeResolveExt<P> for N {
         self.upcast().get_node(path)?.assume_safe().cast()
     }
 }
+
+/// Lookups of [scene unique nodes][unique-nodes] and other nodes relative to the owner of a node.
+///
+/// Scene unique nodes are nodes with `unique_name_in_owner` set. They can be found by name from
+/// any node in the same scene, regardless of where they are in the tree, which keeps lookups
+/// working when the scene is reorganized. This requires Godot 3.5 or later.
+///
+/// [unique-nodes]: https://docs.godotengine.org/en/3.5/tutorials/scripting/scene_unique_nodes.html
+pub trait SceneLookupExt {
+    /// Convenience method to obtain a reference to the scene unique node `name` in the scene of
+    /// `self`, and cast it to the desired type. `name` may be given with or without the leading
+    /// `%`, e.g. `"%Player"` or `"Player"`.
+    ///
+    /// Returns `None` if `name` is not a valid node name, or if the node does not exist or is
+    /// not of the correct type.
+    ///
+    /// # Safety
+    ///
+    /// This method accesses the scene tree. As a result, any calls to this function must
+    /// follow the official [thread-safety guidelines][thread-safety]. `assume_safe`
+    /// invariants must be observed for the resulting node during `'a`, if any.
+    ///
+    /// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+    unsafe fn get_unique_as<'a, T>(&self, name: &str) -> Option<TRef<'a, T>>
+    where
+        T: SubClass<Node>;
+
+    /// Convenience method to obtain a reference to the scene unique node `name` in the scene of
+    /// `self`, and cast it to an instance of the desired `NativeClass` type. See
+    /// [`get_unique_as`][Self::get_unique_as].
+    ///
+    /// # Safety
+    ///
+    /// This method accesses the scene tree. As a result, any calls to this function must
+    /// follow the official [thread-safety guidelines][thread-safety]. `assume_safe`
+    /// invariants must be observed for the resulting node during `'a`, if any.
+    ///
+    /// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+    unsafe fn get_unique_as_instance<'a, T>(&self, name: &str) -> Option<TInstance<'a, T>>
+    where
+        T: NativeClass,
+        T::Base: SubClass<Node>,
+    {
+        self.get_unique_as::<T::Base>(name)?.cast_instance()
+    }
+
+    /// Convenience method to obtain a reference to a node at `path` relative to the owner of
+    /// `self`, i.e. the root of the scene it was instanced from, and cast it to the desired
+    /// type. If `self` has no owner, `path` is relative to `self`.
+    ///
+    /// Returns `None` if the node does not exist or is not of the correct type.
+    ///
+    /// # Safety
+    ///
+    /// This method accesses the scene tree. As a result, any calls to this function must
+    /// follow the official [thread-safety guidelines][thread-safety]. `assume_safe`
+    /// invariants must be observed for the resulting node during `'a`, if any.
+    ///
+    /// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+    unsafe fn get_owned_as<'a, T>(&self, path: impl Into<NodePath>) -> Option<TRef<'a, T>>
+    where
+        T: SubClass<Node>;
+}
+
+impl<N: SubClass<Node>> SceneLookupExt for N {
+    unsafe fn get_unique_as<'a, T>(&self, name: &str) -> Option<TRef<'a, T>>
+    where
+        T: SubClass<Node>,
+    {
+        self.upcast()
+            .get_node(unique_path(name)?)?
+            .assume_safe()
+            .cast()
+    }
+
+    unsafe fn get_owned_as<'a, T>(&self, path: impl Into<NodePath>) -> Option<TRef<'a, T>>
+    where
+        T: SubClass<Node>,
+    {
+        let node = self.upcast::<Node>();
+        match node.owner() {
+            Some(owner) => owner.assume_safe().get_node(path),
+            None => node.get_node(path),
+        }?
+        .assume_safe()
+        .cast()
+    }
+}
+
+/// Returns the node path of the scene unique node `name`, which may start with `%`.
+fn unique_path(name: &str) -> Option<String> {
+    let name = name.strip_prefix('%').unwrap_or(name);
+    if name.is_empty() || name.contains(['/', ':', '%', '.', '@', '"']) {
+        return None;
+    }
+    Some(format!("%{name}"))
+}
+
+/// Cache for [scene unique node][SceneLookupExt] lookups.
+///
+/// Resolving a node path walks the scene tree on every call. Scripts that look up the same
+/// nodes every frame can keep a `NodeCache` instead, which remembers the instance IDs of the
+/// nodes it found. Cached nodes are checked before they are returned: if a node has been freed,
+/// renamed, moved to another parent or scene, or is no longer unique, it is looked up again.
+///
+/// ```no_run
+/// use gdnative_bindings::utils::NodeCache;
+/// use gdnative_bindings::{Label, Node};
+///
+/// struct Hud {
+///     nodes: NodeCache,
+/// }
+///
+/// impl Hud {
+///     fn update_score(&mut self, base: &Node, score: i64) {
+///         // SAFETY: called from `_process`, on the main thread.
+///         if let Some(label) = unsafe { self.nodes.get_unique_as::<Label>(base, "%Score") } {
+///             label.set_text(score.to_string());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct NodeCache {
+    nodes: HashMap<String, CachedNode>,
+}
+
+/// A node found by [`NodeCache`], with the nodes it was related to at the time.
+#[derive(Debug)]
+struct CachedNode {
+    node: InstanceId,
+    owner: InstanceId,
+    parent: Option<InstanceId>,
+}
+
+impl NodeCache {
+    /// Creates an empty cache.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the scene unique node `name` in the scene of `base`, like
+    /// [`SceneLookupExt::get_unique_as`], using the cached node if it is still valid.
+    ///
+    /// # Safety
+    ///
+    /// This method accesses the scene tree. As a result, any calls to this function must
+    /// follow the official [thread-safety guidelines][thread-safety]. `assume_safe`
+    /// invariants must be observed for the resulting node during `'a`, if any.
+    ///
+    /// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+    #[inline]
+    pub unsafe fn get_unique_as<'a, T>(&mut self, base: &Node, name: &str) -> Option<TRef<'a, T>>
+    where
+        T: SubClass<Node>,
+    {
+        // Unique nodes are registered with the owner of `base`, or `base` itself if it is the
+        // root of its scene.
+        let owner = match base.owner() {
+            Some(owner) => owner.assume_safe().instance_id(),
+            None => base.instance_id(),
+        };
+
+        if let Some(cached) = self.nodes.get(name) {
+            if cached.owner == owner {
+                let node = Node::try_from_instance_id(cached.node).filter(|node| {
+                    node.is_unique_name_in_owner()
+                        && node.name().to_string() == name.strip_prefix('%').unwrap_or(name)
+                        && node.owner().map(|o| o.assume_safe().instance_id()) == Some(owner)
+                        && parent_id(node) == cached.parent
+                });
+                if let Some(node) = node {
+                    return node.cast();
+                }
+            }
+        }
+
+        let node = base.get_unique_as::<Node>(name)?;
+        self.nodes.insert(
+            name.to_owned(),
+            CachedNode {
+                node: node.instance_id(),
+                owner,
+                parent: parent_id(&node),
+            },
+        );
+        node.cast()
+    }
+
+    /// Forgets all cached nodes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+}
+
+/// Returns the instance ID of the parent of `node`, if any.
+unsafe fn parent_id(node: &Node) -> Option<InstanceId> {
+    node.get_parent()
+        .map(|parent| parent.assume_safe().instance_id())
+}
+
+/// Fields of a `NativeClass` that refer to nodes in the scene tree, declared with the
+/// `#[node("path")]` field attribute of the derive macro.
+///
+/// The path is relative to the base object, and may use [scene unique node][SceneLookupExt]
+/// syntax, e.g. `#[node("%Player")]`. Fields must be of type `Option<Ref<T>>`, where `T` is
+/// the expected class of the node. They are set by [`resolve_nodes`][Self::resolve_nodes],
+/// which is usually called in `_ready`, once the children of the node have entered the tree.
+/// See the documentation of the `NativeClass` derive macro for an example.
+pub trait ResolveNodes {
+    /// Sets all `#[node]` fields to the nodes at their paths relative to `base`.
+    ///
+    /// Returns an error for the first node that doesn't exist or isn't of the type of its
+    /// field. Fields before it are set, while the others are left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// This method accesses the scene tree. As a result, any calls to this function must
+    /// follow the official [thread-safety guidelines][thread-safety].
+    ///
+    /// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+    unsafe fn resolve_nodes(&mut self, base: &Node) -> Result<(), NodeFieldError>;
+}
+
+/// Error returned by [`ResolveNodes::resolve_nodes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeFieldError {
+    /// There is no node at the path of a field.
+    NotFound {
+        /// Name of the field.
+        field: &'static str,
+        /// Path of the node.
+        path: &'static str,
+    },
+    /// The node at the path of a field isn't of the type of the field.
+    WrongType {
+        /// Name of the field.
+        field: &'static str,
+        /// Path of the node.
+        path: &'static str,
+        /// Class the field expects.
+        expected: &'static str,
+        /// Class of the node.
+        found: String,
+    },
+}
+
+impl fmt::Display for NodeFieldError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeFieldError::NotFound { field, path } => {
+                write!(f, "no node found at `{path}` for field `{field}`")
+            }
+            NodeFieldError::WrongType {
+                field,
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "node at `{path}` for field `{field}` is a `{found}`, expected `{expected}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NodeFieldError {}
+
+/// Resolves a `#[node]` field. Used by the code generated for [`ResolveNodes`].
+#[doc(hidden)]
+#[inline]
+pub unsafe fn resolve_node_field<T>(
+    base: &Node,
+    path: &'static str,
+    field: &'static str,
+) -> Result<Ref<T, Shared>, NodeFieldError>
+where
+    T: SubClass<Node>,
+{
+    let node = if path.starts_with('%') && !path.contains('/') {
+        base.get_unique_as::<Node>(path)
+    } else {
+        base.get_node_as::<Node>(path)
+    };
+
+    let node = node.ok_or(NodeFieldError::NotFound { field, path })?;
+    match node.cast::<T>() {
+        Some(node) => Ok(node.claim()),
+        None => Err(NodeFieldError::WrongType {
+            field,
+            path,
+            expected: T::class_name(),
+            found: node.get_class().to_string(),
+        }),
+    }
+}
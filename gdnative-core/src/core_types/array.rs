@@ -303,9 +303,11 @@ impl<Own: NonUniqueOwnership> VariantArray<Own> {
 impl VariantArray<Unique> {
     /// Creates an empty `VariantArray`.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new() -> Self {
         unsafe {
             let mut sys = sys::godot_array::default();
+            crate::profiler::track_alloc!(VariantArray, crate::profiler::alloc::ARRAY_PRIVATE_SIZE);
             (get_api().godot_array_new)(&mut sys);
             Self::from_sys(sys)
         }
@@ -328,6 +330,7 @@ impl VariantArray<Unique> {
 impl VariantArray<Shared> {
     /// Create a new shared array.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new_shared() -> Self {
         VariantArray::<Unique>::new().into_shared()
     }
@@ -337,6 +340,7 @@ impl VariantArray<Shared> {
 impl VariantArray<ThreadLocal> {
     /// Create a new thread-local array.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new_thread_local() -> Self {
         VariantArray::<Unique>::new().into_thread_local()
     }
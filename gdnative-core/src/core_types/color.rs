@@ -1,3 +1,15 @@
+//! RGBA colors, and a palette of named colors.
+//!
+//! The constants in this module can be used in `const` and `static` items, e.g. to define the
+//! colors of a `_draw` method once instead of on every call:
+//!
+//! ```
+//! use gdnative::core_types::color::{self, Color};
+//!
+//! const HIGHLIGHT: Color = Color::from_rgb(1.0, 0.8, 0.2);
+//! static OUTLINE: Color = color::BLACK;
+//! ```
+
 use crate::private::get_api;
 use crate::sys;
 use std::mem::transmute;
@@ -15,14 +27,34 @@ pub struct Color {
     pub a: f32,
 }
 
+// Named colors, with the same values as the named colors of Godot (`ColorN` in GDScript), which
+// follow the X11 color names.
+
+/// White with zero alpha.
+pub const TRANSPARENT: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.0);
+pub const BLACK: Color = Color::from_rgb(0.0, 0.0, 0.0);
+pub const WHITE: Color = Color::from_rgb(1.0, 1.0, 1.0);
+pub const GRAY: Color = Color::from_rgb(0.75, 0.75, 0.75);
+pub const DARK_GRAY: Color = Color::from_rgb(0.66, 0.66, 0.66);
+pub const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+pub const GREEN: Color = Color::from_rgb(0.0, 1.0, 0.0);
+pub const BLUE: Color = Color::from_rgb(0.0, 0.0, 1.0);
+pub const YELLOW: Color = Color::from_rgb(1.0, 1.0, 0.0);
+pub const CYAN: Color = Color::from_rgb(0.0, 1.0, 1.0);
+pub const MAGENTA: Color = Color::from_rgb(1.0, 0.0, 1.0);
+pub const ORANGE: Color = Color::from_rgb(1.0, 0.65, 0.0);
+pub const PURPLE: Color = Color::from_rgb(0.63, 0.13, 0.94);
+pub const PINK: Color = Color::from_rgb(1.0, 0.75, 0.8);
+pub const BROWN: Color = Color::from_rgb(0.65, 0.16, 0.16);
+
 impl Color {
     #[inline]
-    pub fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Color {
+    pub const fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Color {
         Color { r, g, b, a }
     }
 
     #[inline]
-    pub fn from_rgb(r: f32, g: f32, b: f32) -> Color {
+    pub const fn from_rgb(r: f32, g: f32, b: f32) -> Color {
         Color { r, g, b, a: 1.0 }
     }
 
@@ -31,15 +63,45 @@ impl Color {
         Color::from_hsva(h, s, v, 1.0)
     }
 
+    /// Constructs a color from hue, saturation and value, each in range 0-1. Hues outside of
+    /// the range wrap around.
     #[inline]
     pub fn from_hsva(h: f32, s: f32, v: f32, a: f32) -> Color {
-        let color = Color {
-            r: 0.0,
-            g: 0.0,
-            b: 0.0,
-            a: 0.0,
+        if s == 0.0 {
+            return Color::from_rgba(v, v, v, a);
+        }
+
+        let h = (h * 6.0).rem_euclid(6.0);
+        let sector = h.floor();
+        let f = h - sector;
+        let p = v * (1.0 - s);
+        let q = v * (1.0 - s * f);
+        let t = v * (1.0 - s * (1.0 - f));
+
+        let (r, g, b) = match sector as u8 {
+            0 => (v, t, p),
+            1 => (q, v, p),
+            2 => (p, v, t),
+            3 => (p, q, v),
+            4 => (t, p, v),
+            _ => (v, p, q),
         };
-        Color::from_sys(unsafe { (get_api().godot_color_from_hsv)(color.sys(), h, s, v, a) })
+        Color::from_rgba(r, g, b, a)
+    }
+
+    #[inline]
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        Color::from_hsla(h, s, l, 1.0)
+    }
+
+    /// Constructs a color from hue, saturation and lightness, each in range 0-1. Hues outside
+    /// of the range wrap around.
+    #[inline]
+    pub fn from_hsla(h: f32, s: f32, l: f32, a: f32) -> Color {
+        // HSL and HSV share the hue, so only the saturation and the brightness are converted.
+        let v = l + s * l.min(1.0 - l);
+        let s_v = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+        Color::from_hsva(h, s_v, v, a)
     }
 
     /// Parses from a HTML color code, or `None` on parse error.
@@ -127,19 +189,90 @@ impl Color {
         )
     }
 
+    /// Returns the hue of the color, in range 0-1.
     #[inline]
     pub fn h(&self) -> f32 {
-        unsafe { (get_api().godot_color_get_h)(self.sys()) }
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        if delta == 0.0 {
+            return 0.0;
+        }
+
+        let h = if self.r == max {
+            (self.g - self.b) / delta
+        } else if self.g == max {
+            2.0 + (self.b - self.r) / delta
+        } else {
+            4.0 + (self.r - self.g) / delta
+        };
+        (h / 6.0).rem_euclid(1.0)
     }
 
+    /// Returns the saturation of the color in the HSV model, in range 0-1.
     #[inline]
     pub fn s(&self) -> f32 {
-        unsafe { (get_api().godot_color_get_s)(self.sys()) }
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        if max == 0.0 {
+            0.0
+        } else {
+            (max - min) / max
+        }
     }
 
+    /// Returns the value (brightness) of the color in the HSV model, in range 0-1.
     #[inline]
     pub fn v(&self) -> f32 {
-        unsafe { (get_api().godot_color_get_v)(self.sys()) }
+        self.r.max(self.g).max(self.b)
+    }
+
+    /// Returns the hue, saturation and lightness of the color in the HSL model, each in range
+    /// 0-1.
+    #[inline]
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let l = (max + min) / 2.0;
+        let s = if l == 0.0 || l == 1.0 {
+            0.0
+        } else {
+            (max - l) / l.min(1.0 - l)
+        };
+        (self.h(), s, l)
+    }
+
+    /// Converts the color from the sRGB color space to linear RGB. The alpha is unchanged.
+    #[inline]
+    pub fn to_linear(&self) -> Color {
+        fn to_linear(c: f32) -> f32 {
+            if c < 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        Color::from_rgba(
+            to_linear(self.r),
+            to_linear(self.g),
+            to_linear(self.b),
+            self.a,
+        )
+    }
+
+    /// Converts the color from linear RGB to the sRGB color space. The alpha is unchanged.
+    #[inline]
+    pub fn to_srgb(&self) -> Color {
+        fn to_srgb(c: f32) -> f32 {
+            if c < 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        }
+
+        Color::from_rgba(to_srgb(self.r), to_srgb(self.g), to_srgb(self.b), self.a)
     }
 
     #[inline]
@@ -314,6 +447,73 @@ fn color_to_pixel_color_formats() {
     assert_eq!(0x0000FFFF7FFFFFFF, color.to_argb64());
 }
 
+#[test]
+fn color_hsv() {
+    use crate::core_types::IsEqualApprox;
+
+    let approx = |a: Color, b: Color| {
+        a.r.is_equal_approx(b.r) && a.g.is_equal_approx(b.g) && a.b.is_equal_approx(b.b)
+    };
+
+    assert_eq!(RED, Color::from_hsv(0.0, 1.0, 1.0));
+    assert_eq!(RED, Color::from_hsv(1.0, 1.0, 1.0));
+    assert!(approx(CYAN, Color::from_hsv(0.5, 1.0, 1.0)));
+    assert!(approx(BLUE, Color::from_hsv(-1.0 / 3.0, 1.0, 1.0)));
+    assert_eq!(GRAY, Color::from_hsv(0.3, 0.0, 0.75));
+
+    let color = Color::from_rgb(0.75, 0.5, 0.25);
+    assert!(color.h().is_equal_approx(1.0 / 12.0));
+    assert!(color.s().is_equal_approx(2.0 / 3.0));
+    assert!(color.v().is_equal_approx(0.75));
+    assert!(approx(
+        color,
+        Color::from_hsv(color.h(), color.s(), color.v())
+    ));
+
+    assert_eq!(0.0, BLACK.s());
+    assert!(MAGENTA.h().is_equal_approx(5.0 / 6.0));
+}
+
+#[test]
+fn color_hsl() {
+    use crate::core_types::IsEqualApprox;
+
+    let color = Color::from_rgb(0.75, 0.5, 0.25);
+    let (h, s, l) = color.to_hsl();
+    assert!(h.is_equal_approx(1.0 / 12.0));
+    assert!(s.is_equal_approx(0.5));
+    assert!(l.is_equal_approx(0.5));
+
+    let round_trip = Color::from_hsla(h, s, l, 0.5);
+    assert!(round_trip.r.is_equal_approx(0.75));
+    assert!(round_trip.g.is_equal_approx(0.5));
+    assert!(round_trip.b.is_equal_approx(0.25));
+    assert_eq!(0.5, round_trip.a);
+
+    assert_eq!(WHITE, Color::from_hsl(0.6, 1.0, 1.0));
+    assert_eq!(BLACK, Color::from_hsl(0.6, 1.0, 0.0));
+    assert_eq!((0.0, 0.0, 1.0), WHITE.to_hsl());
+}
+
+#[test]
+fn color_linear_srgb() {
+    use crate::core_types::IsEqualApprox;
+
+    assert_eq!(WHITE, WHITE.to_linear());
+    assert_eq!(BLACK, BLACK.to_srgb());
+
+    let color = Color::from_rgba(0.5, 0.02, 0.8, 0.5);
+    let linear = color.to_linear();
+    assert!(linear.r.is_equal_approx(0.21404114));
+    assert!(linear.g.is_equal_approx(0.02 / 12.92));
+    assert_eq!(0.5, linear.a);
+
+    let srgb = linear.to_srgb();
+    assert!(srgb.r.is_equal_approx(color.r));
+    assert!(srgb.g.is_equal_approx(color.g));
+    assert!(srgb.b.is_equal_approx(color.b));
+}
+
 godot_test!(test_color {
     // Test to_html
     assert_eq!("ffffffff", Color::from_rgba(1.0, 1.0, 1.0, 1.0).to_html(true).to_string());
@@ -249,6 +249,7 @@ impl<Own: Ownership> Dictionary<Own> {
 impl Dictionary<Shared> {
     /// Create a new shared dictionary.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new_shared() -> Self {
         Dictionary::<Unique>::new().into_shared()
     }
@@ -258,6 +259,7 @@ impl Dictionary<Shared> {
 impl Dictionary<ThreadLocal> {
     /// Create a new thread-local dictionary.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new_thread_local() -> Self {
         Dictionary::<Unique>::new().into_thread_local()
     }
@@ -319,9 +321,14 @@ impl<Own: LocalThreadOwnership> Dictionary<Own> {
 impl Dictionary<Unique> {
     /// Creates an empty `Dictionary`.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new() -> Self {
         unsafe {
             let mut sys = sys::godot_dictionary::default();
+            crate::profiler::track_alloc!(
+                Dictionary,
+                crate::profiler::alloc::DICTIONARY_PRIVATE_SIZE
+            );
             (get_api().godot_dictionary_new)(&mut sys);
             Self::from_sys(sys)
         }
@@ -596,3 +603,35 @@ godot_test!(test_dictionary_clone_clear {
     assert!(dict.is_empty());
     assert!(!dict_clone.is_empty());
 });
+
+godot_test!(test_dictionary_macros {
+    use crate::core_types::VariantArray;
+
+    let empty = dict! {};
+    assert!(empty.is_empty());
+    assert!(varray![].is_empty());
+
+    let hp = 100;
+    let key = "key";
+    let dict = dict! {
+        "name": "Ferris",
+        "hp": hp,
+        "tags": varray!["red", "fast",],
+        "stats": dict! { "speed": 2.5 },
+        key: 1,
+        (format!("slot_{}", 1)): "sword",
+    };
+
+    assert_eq!(6, dict.len());
+    assert_eq!(Some("Ferris".to_owned()), dict.get("name").and_then(|v| v.to()));
+    assert_eq!(Some(100), dict.get("hp").and_then(|v| v.to::<i64>()));
+    assert_eq!(Some(1), dict.get("key").and_then(|v| v.to::<i64>()));
+    assert_eq!(Some("sword".to_owned()), dict.get("slot_1").and_then(|v| v.to()));
+
+    let tags = dict.get("tags").and_then(|v| v.to::<VariantArray>()).unwrap();
+    assert_eq!(2, tags.len());
+    assert_eq!(Some("fast".to_owned()), tags.get(1).to());
+
+    let stats = dict.get("stats").and_then(|v| v.to::<Dictionary>()).unwrap();
+    assert_eq!(Some(2.5), stats.get("speed").and_then(|v| v.to::<f64>()));
+});
@@ -5,16 +5,17 @@
 //!
 //! godot-rust provides optional serialization support for many core types.  Enable the feature `serde` to make use of it.
 
-mod color;
 mod error;
 mod node_path;
 mod pool_array;
 mod rid;
+mod typed_array;
 mod vector2;
 mod vector3;
 
 pub mod access;
 pub mod array;
+pub mod color;
 pub mod dictionary;
 pub mod geom;
 pub mod string;
@@ -28,7 +29,8 @@ pub use geom::{Aabb, Basis, Margin, MarginError, Plane, Quat, Rect2, Transform,
 pub use node_path::NodePath;
 pub use pool_array::{PoolArray, PoolElement};
 pub use rid::Rid;
-pub use string::{GodotString, StringName};
+pub use string::{GodotStr, GodotString, StringName};
+pub use typed_array::TypedVariantArray;
 pub use variant::{
     CoerceFromVariant, FromVariant, FromVariantError, OwnedToVariant, ToVariant, ToVariantEq,
     Variant, VariantType,
@@ -72,8 +74,10 @@ pub fn test_core_types() -> bool {
     status &= array::test_array();
     status &= array::test_array_debug();
     status &= array::test_array_clone_clear();
+    status &= typed_array::test_typed_variant_array();
     status &= dictionary::test_dictionary();
     status &= dictionary::test_dictionary_clone_clear();
+    status &= dictionary::test_dictionary_macros();
 
     status &= color::test_color();
     status &= vector2::test_vector2_variants();
@@ -90,6 +94,11 @@ pub fn test_core_types() -> bool {
     status &= variant::test_to_variant_iter();
     status &= variant::test_variant_tuple();
     status &= variant::test_variant_dispatch();
+    status &= variant::test_variant_script_cmp();
+    status &= variant::test_variant_godot_hash();
+    status &= variant::bytes::test_variant_bytes_encoding();
+    status &= variant::bytes::test_variant_bytes_roundtrip();
+    status &= variant::bytes::test_variant_bytes_errors();
 
     status &= pool_array::test_byte_array_access();
     status &= pool_array::test_int32_array_access();
@@ -98,6 +107,8 @@ pub fn test_core_types() -> bool {
     status &= pool_array::test_string_array_access();
     status &= pool_array::test_vector2_array_access();
     status &= pool_array::test_vector3_array_access();
+    status &= pool_array::test_byte_array_conversions();
+    status &= pool_array::test_pool_array_bulk_insert();
 
     status &= geom::test_transform2d_behavior();
 
@@ -29,10 +29,15 @@ impl NodePath {
     /// indicate the current node and its parent.
     #[inline]
     #[allow(clippy::should_implement_trait)]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn from_str(path: &str) -> Self {
         unsafe {
             let mut dest = sys::godot_node_path::default();
             let api = get_api();
+            crate::profiler::track_alloc!(
+                NodePath,
+                (path.chars().count() + 1) * std::mem::size_of::<sys::wchar_t>()
+            );
             let mut from = (api.godot_string_chars_to_utf8_with_len)(
                 path.as_ptr() as *const _,
                 path.len() as _,
@@ -45,9 +50,14 @@ impl NodePath {
 
     /// Create a `NodePath` from a GodotString.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new(path: &GodotString) -> Self {
         unsafe {
             let mut dest = sys::godot_node_path::default();
+            crate::profiler::track_alloc!(
+                NodePath,
+                (path.len() + 1) * std::mem::size_of::<sys::wchar_t>()
+            );
             (get_api().godot_node_path_new)(&mut dest, &path.0);
             NodePath(dest)
         }
@@ -71,6 +81,12 @@ impl NodePath {
         unsafe { (get_api().godot_node_path_get_name_count)(&mut self.0) }
     }
 
+    /// Returns the node name of the specified `idx`, 0 to name_count()
+    #[inline]
+    pub fn get_name(&self, idx: i32) -> GodotString {
+        unsafe { GodotString((get_api().godot_node_path_get_name)(&self.0, idx)) }
+    }
+
     /// Returns the resource name of the specified `idx`, 0 to subname_count()
     #[inline]
     pub fn get_subname(&self, idx: i32) -> GodotString {
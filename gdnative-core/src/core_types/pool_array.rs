@@ -83,6 +83,7 @@ impl<T: PoolElement> PoolArray<T> {
     /// let arr = vec.iter().map(|&e| e as i32).collect::<PoolArray<_>>();
     /// ```
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn from_vec(mut src: Vec<T>) -> Self {
         let mut arr = Self::new();
         arr.append_vec(&mut src);
@@ -138,6 +139,7 @@ impl<T: PoolElement> PoolArray<T> {
     /// If the resulting length would not fit in `i32`.
     #[inline]
     #[allow(clippy::iter_with_drain)] // "`drain(..)` used on a `Vec`"; suggests `into_iter()` but we don't have the vec by value
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn append_vec(&mut self, src: &mut Vec<T>) {
         let start = self.len() as usize;
         let new_len = start + src.len();
@@ -168,6 +170,25 @@ impl<T: PoolElement> PoolArray<T> {
         }
     }
 
+    /// Inserts all elements of `src` at the given offset and returns `true` if successful.
+    ///
+    /// Unlike calling [`insert()`][Self::insert] for each element, this resizes the array and
+    /// locks it for writing only once. Nothing is inserted if `offset` is out of bounds.
+    #[inline]
+    pub fn insert_bulk<I: IntoIterator<Item = T>>(&mut self, offset: i32, src: I) -> bool {
+        let len = self.len();
+        if offset < 0 || offset > len {
+            return false;
+        }
+
+        self.extend(src);
+
+        let offset = offset as usize;
+        let mut write = self.write();
+        write[offset..].rotate_left(len as usize - offset);
+        true
+    }
+
     /// Inverts the order of the elements in the array.
     #[inline]
     pub fn invert(&mut self) {
@@ -184,7 +205,19 @@ impl<T: PoolElement> PoolArray<T> {
 
     /// Changes the size of the array, possibly removing elements or pushing default values.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn resize(&mut self, size: i32) {
+        #[cfg(feature = "alloc-tracking")]
+        {
+            let len = self.len();
+            if size > len {
+                crate::profiler::alloc::record(
+                    crate::profiler::alloc::AllocKind::PoolArray,
+                    (size - len) as usize * std::mem::size_of::<T>(),
+                );
+            }
+        }
+
         unsafe {
             (T::resize_fn(get_api()))(self.sys_mut(), size);
         }
@@ -277,6 +310,7 @@ impl<T: PoolElement + Copy> PoolArray<T> {
     ///
     /// If the length of `src` does not fit in `i32`.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn from_slice(src: &[T]) -> Self {
         let mut arr = Self::new();
         arr.append_slice(src);
@@ -289,6 +323,7 @@ impl<T: PoolElement + Copy> PoolArray<T> {
     ///
     /// If the resulting length would not fit in `i32`.
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn append_slice(&mut self, src: &[T]) {
         let start = self.len() as usize;
         let new_len = start + src.len();
@@ -297,6 +332,31 @@ impl<T: PoolElement + Copy> PoolArray<T> {
         let mut write = self.write();
         write[start..].copy_from_slice(src)
     }
+
+    /// Copies all elements to the end of `dst`, reusing its capacity where possible.
+    ///
+    /// Unlike collecting from [`read()`][Self::read] or extending from [`to_vec()`][Self::to_vec],
+    /// this copies the elements only once and doesn't allocate if `dst` has enough capacity,
+    /// so a single buffer can be reused for many arrays.
+    #[inline]
+    pub fn extend_vec(&self, dst: &mut Vec<T>) {
+        dst.extend_from_slice(&self.read());
+    }
+
+    /// Copies elements starting at `offset` into `dst`, and returns the number of elements
+    /// copied. This is less than `dst.len()` if the end of the array is reached.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is greater than the length of the array.
+    #[inline]
+    pub fn copy_to_slice(&self, offset: usize, dst: &mut [T]) -> usize {
+        let read = self.read();
+        let src = &read[offset..];
+        let len = src.len().min(dst.len());
+        dst[..len].copy_from_slice(&src[..len]);
+        len
+    }
 }
 
 impl<T: PoolElement> Drop for PoolArray<T> {
@@ -341,22 +401,96 @@ impl<T: PoolElement> NewRef for PoolArray<T> {
     }
 }
 
-// `FromIterator` and `Extend` implementations collect into `Vec` first, because Rust `Vec`s
-// are better at handling unknown lengths than the Godot arrays (`push` CoWs every time!)
+// `FromIterator` and `Extend` resize the array and lock it for writing once for the elements
+// announced by `size_hint`, and collect the rest into a `Vec` first, because Rust `Vec`s are
+// better at handling unknown lengths than the Godot arrays (`push` CoWs every time!)
 
 impl<T: PoolElement> FromIterator<T> for PoolArray<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let vec = iter.into_iter().collect::<Vec<_>>();
-        Self::from_vec(vec)
+        let mut arr = Self::new();
+        arr.extend(iter);
+        arr
     }
 }
 
 impl<T: PoolElement> Extend<T> for PoolArray<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        let mut vec = iter.into_iter().collect::<Vec<_>>();
-        self.append_vec(&mut vec);
+        let mut iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+
+        if lower > 0 {
+            let start = self.len() as usize;
+            self.resize(i32::try_from(start + lower).expect("new length should fit in i32"));
+
+            // Removes the default elements that weren't overwritten, if `size_hint` was wrong or
+            // the iterator panics.
+            let mut guard = TruncateOnDrop {
+                array: self,
+                len: start,
+            };
+
+            let mut write = guard.array.write();
+            for (dst, val) in write[start..].iter_mut().zip(&mut iter) {
+                *dst = val;
+                guard.len += 1;
+            }
+        }
+
+        let mut rest = iter.collect::<Vec<_>>();
+        if !rest.is_empty() {
+            self.append_vec(&mut rest);
+        }
+    }
+}
+
+/// Shrinks `array` to `len` when dropped.
+struct TruncateOnDrop<'a, T: PoolElement> {
+    array: &'a mut PoolArray<T>,
+    len: usize,
+}
+
+impl<'a, T: PoolElement> Drop for TruncateOnDrop<'a, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.array.len() as usize > self.len {
+            self.array.resize(self.len as i32);
+        }
+    }
+}
+
+impl<T: PoolElement> From<Vec<T>> for PoolArray<T> {
+    #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
+impl<T: PoolElement + Copy> From<&[T]> for PoolArray<T> {
+    #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
+    fn from(slice: &[T]) -> Self {
+        Self::from_slice(slice)
+    }
+}
+
+impl<T: PoolElement + Copy> From<&PoolArray<T>> for Vec<T> {
+    #[inline]
+    fn from(arr: &PoolArray<T>) -> Self {
+        let mut vec = Vec::with_capacity(arr.len() as usize);
+        arr.extend_vec(&mut vec);
+        vec
+    }
+}
+
+impl<T: PoolElement + Copy> From<PoolArray<T>> for Vec<T> {
+    #[inline]
+    fn from(arr: PoolArray<T>) -> Self {
+        Self::from(&arr)
     }
 }
 
@@ -104,3 +104,65 @@ godot_test!(
         );
     }
 );
+
+godot_test!(test_byte_array_conversions {
+    let bytes = (0u8..=255).collect::<Vec<_>>();
+
+    let arr = PoolArray::from(bytes.as_slice());
+    assert_eq!(bytes, Vec::from(&arr));
+    assert_eq!(arr, PoolArray::from(bytes.clone()));
+
+    let mut vec = Vec::with_capacity(512);
+    let capacity = vec.capacity();
+    vec.push(42);
+    arr.extend_vec(&mut vec);
+    assert_eq!(257, vec.len());
+    assert_eq!(capacity, vec.capacity());
+    assert_eq!(&bytes[..], &vec[1..]);
+
+    let mut chunk = [0; 100];
+    assert_eq!(100, arr.copy_to_slice(0, &mut chunk));
+    assert_eq!(&bytes[..100], &chunk[..]);
+    assert_eq!(56, arr.copy_to_slice(200, &mut chunk));
+    assert_eq!(&bytes[200..], &chunk[..56]);
+    assert_eq!(0, arr.copy_to_slice(256, &mut chunk));
+
+    assert_eq!(bytes, Vec::from(arr));
+});
+
+godot_test!(test_pool_array_bulk_insert {
+    let mut arr = (0..4).collect::<PoolArray<i32>>();
+
+    arr.extend(4..6);
+    assert_eq!(vec![0, 1, 2, 3, 4, 5], arr.to_vec());
+
+    // Iterators with an unknown or wrong length
+    arr.extend((6..10).filter(|i| i % 2 == 0));
+    assert_eq!(vec![0, 1, 2, 3, 4, 5, 6, 8], arr.to_vec());
+
+    // Elements that the iterator didn't yield before panicking aren't left behind
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        arr.extend((0..4).map(|i| if i < 2 { 20 + i } else { panic!("expected panic") }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(vec![0, 1, 2, 3, 4, 5, 6, 8, 20, 21], arr.to_vec());
+    arr.resize(8);
+
+    assert!(arr.insert_bulk(2, [10, 11]));
+    assert_eq!(vec![0, 1, 10, 11, 2, 3, 4, 5, 6, 8], arr.to_vec());
+    assert!(arr.insert_bulk(0, vec![-1]));
+    assert!(arr.insert_bulk(arr.len(), [20, 21]));
+    assert_eq!(vec![-1, 0, 1, 10, 11, 2, 3, 4, 5, 6, 8, 20, 21], arr.to_vec());
+
+    assert!(!arr.insert_bulk(-1, [30]));
+    assert!(!arr.insert_bulk(arr.len() + 1, [30]));
+    assert_eq!(13, arr.len());
+
+    let mut strings = PoolArray::<GodotString>::new();
+    strings.push("c".into());
+    assert!(strings.insert_bulk(0, ["a", "b"].into_iter().map(GodotString::from)));
+    assert_eq!(
+        vec![GodotString::from("a"), GodotString::from("b"), GodotString::from("c")],
+        strings.to_vec()
+    );
+});
@@ -18,6 +18,11 @@ use std::mem::transmute;
 ///
 /// For this reason, GDNative methods accepting `Rid` parameters are marked `unsafe`.
 ///
+/// The generated methods of the servers take and return wrappers per kind of resource instead,
+/// e.g. `visual_server::CanvasItemRid` or `physics_server::BodyRid`. They prevent passing an RID
+/// of one kind where another is expected, but are otherwise just as unsafe to use. Untyped RIDs,
+/// e.g. from `Resource::get_rid`, can be wrapped with `from_rid`.
+///
 /// [servers]: https://docs.godotengine.org/en/stable/tutorials/optimization/using_servers.html
 /// [docs]: https://docs.godotengine.org/en/stable/classes/class_rid.html
 #[derive(Copy, Clone, Debug)]
@@ -2,6 +2,7 @@ use crate::core_types::Variant;
 use crate::object::NewRef;
 use crate::private::get_api;
 use crate::sys;
+use std::borrow::Cow;
 use std::cmp::Ordering;
 
 use std::ffi::CStr;
@@ -83,6 +84,7 @@ impl GodotString {
 
     #[inline]
     #[allow(clippy::should_implement_trait)]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn from_str<S>(s: S) -> Self
     where
         S: AsRef<str>,
@@ -90,6 +92,10 @@ impl GodotString {
         unsafe {
             let api = get_api();
             let val = s.as_ref();
+            crate::profiler::track_alloc!(
+                GodotString,
+                (val.chars().count() + 1) * std::mem::size_of::<sys::wchar_t>()
+            );
             let godot_s =
                 (api.godot_string_chars_to_utf8_with_len)(val.as_ptr() as *const _, val.len() as _);
 
@@ -180,6 +186,35 @@ impl GodotString {
         }
     }
 
+    /// Returns the contents of the string as a `str`, replacing characters that can't be
+    /// represented in Unicode with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// Godot stores strings as wide characters, so non-empty strings have to be converted to
+    /// UTF-8. Use [`GodotStr`] to compare or inspect strings without converting them.
+    #[inline]
+    pub fn as_str_lossy(&self) -> Cow<'_, str> {
+        GodotStr::new(self).as_str_lossy()
+    }
+
+    /// Returns a borrowed view of the string.
+    #[inline]
+    pub fn as_godot_str(&self) -> GodotStr<'_> {
+        GodotStr::new(self)
+    }
+
+    /// Returns the characters of the string in Godot's native encoding.
+    fn wide_chars(&self) -> &[GodotChar] {
+        let len = self.len();
+        if len == 0 {
+            return &[];
+        }
+
+        unsafe {
+            let ptr = (get_api().godot_string_wide_str)(&self.0) as *const GodotChar;
+            slice::from_raw_parts(ptr, len)
+        }
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn to_utf8(&self) -> Utf8String {
@@ -454,6 +489,173 @@ impl Index<usize> for GodotString {
     }
 }
 
+/// A borrowed Godot string.
+///
+/// `GodotStr` can be used as a parameter type of exported methods, to receive text without
+/// converting it to a `String` on every call. The string is only borrowed for the duration of
+/// the call:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+/// use gdnative::core_types::GodotStr;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Console;
+///
+/// #[methods]
+/// impl Console {
+///     #[method]
+///     fn run(&self, command: GodotStr<'_>) -> bool {
+///         if command == "quit" {
+///             return true;
+///         }
+///
+///         godot_print!("unknown command: {command}");
+///         false
+///     }
+/// }
+/// ```
+///
+/// Comparisons with `str` and iteration over [`chars`](Self::chars) work on Godot's native
+/// encoding directly. Use [`as_str_lossy`](Self::as_str_lossy) or `to_string` where a `str` is
+/// needed.
+#[derive(Copy, Clone)]
+pub struct GodotStr<'a> {
+    string: &'a GodotString,
+}
+
+impl<'a> GodotStr<'a> {
+    /// Borrows `string`.
+    #[inline]
+    pub fn new(string: &'a GodotString) -> Self {
+        GodotStr { string }
+    }
+
+    /// Returns the borrowed `GodotString`.
+    #[inline]
+    pub fn as_godot_string(self) -> &'a GodotString {
+        self.string
+    }
+
+    /// Returns the length of the string in Godot's native characters.
+    #[inline]
+    pub fn len(self) -> usize {
+        self.string.len()
+    }
+
+    /// Returns `true` if the string is empty.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the Unicode characters of the string. Characters that can't be
+    /// represented in Unicode are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    #[inline]
+    pub fn chars(self) -> impl Iterator<Item = char> + 'a {
+        LossyChars {
+            wide: self.string.wide_chars().iter(),
+        }
+    }
+
+    /// Returns the contents of the string as a `str`. See [`GodotString::as_str_lossy`].
+    #[inline]
+    pub fn as_str_lossy(self) -> Cow<'a, str> {
+        if self.is_empty() {
+            Cow::Borrowed("")
+        } else {
+            Cow::Owned(self.chars().collect())
+        }
+    }
+}
+
+impl<'a> From<&'a GodotString> for GodotStr<'a> {
+    #[inline]
+    fn from(string: &'a GodotString) -> Self {
+        GodotStr::new(string)
+    }
+}
+
+impl PartialEq<str> for GodotStr<'_> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
+impl PartialEq<&str> for GodotStr<'_> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq for GodotStr<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.string == other.string
+    }
+}
+
+impl Eq for GodotStr<'_> {}
+
+impl fmt::Display for GodotStr<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars().try_for_each(|c| fmt::Write::write_char(f, c))
+    }
+}
+
+impl fmt::Debug for GodotStr<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str_lossy().fmt(f)
+    }
+}
+
+/// Decodes wide characters into Unicode, replacing invalid ones.
+struct LossyChars<'a> {
+    wide: slice::Iter<'a, GodotChar>,
+}
+
+impl Iterator for LossyChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        #[allow(clippy::unnecessary_cast)] // type wchar_t may be platform-dependent
+        let c = self.wide.next()?.0 as u32;
+
+        if std::mem::size_of::<libc::wchar_t>() != 2 {
+            return Some(char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+
+        // UTF-16, where code points outside of the BMP are encoded as surrogate pairs.
+        let c = match c {
+            0xD800..=0xDBFF =>
+            {
+                #[allow(clippy::unnecessary_cast)]
+                match self.wide.as_slice().first().map(|low| low.0 as u32) {
+                    Some(low @ 0xDC00..=0xDFFF) => {
+                        self.wide.next();
+                        0x10000 + ((c - 0xD800) << 10) + (low - 0xDC00)
+                    }
+                    _ => return Some(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            c => c,
+        };
+
+        Some(char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.wide.len();
+        ((len + 1) / 2, Some(len))
+    }
+}
+
 // TODO(#993): Is it useful to expose this type?
 // Could just make it an internal detail of how to convert to a rust string.
 #[doc(hidden)]
@@ -637,6 +839,7 @@ where
     S: AsRef<str>,
 {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn from(s: S) -> GodotString {
         GodotString::from_str(s)
     }
@@ -740,6 +943,16 @@ godot_test!(test_string {
 
     assert_eq!(foo.to_utf8().as_str(), "foo");
 
+    let text = GodotString::from("grüße 🦀");
+    assert_eq!(text.as_str_lossy(), "grüße 🦀");
+    assert!(matches!(GodotString::new().as_str_lossy(), std::borrow::Cow::Borrowed("")));
+
+    let text = text.as_godot_str();
+    assert!(text == "grüße 🦀");
+    assert!(text != "grüße");
+    assert_eq!(text.chars().count(), 7);
+    assert_eq!(text.to_string(), "grüße 🦀");
+
     let fmt_string = GodotString::from("foo {bar}");
     let fmt_data = Dictionary::new();
     fmt_data.insert("bar", "baz");
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+use crate::core_types::{
+    FromVariant, FromVariantError, OwnedToVariant, ToVariant, Variant, VariantArray,
+};
+use crate::object::ownership::*;
+use crate::object::NewRef;
+
+/// A `VariantArray` whose elements are all of type `T`.
+///
+/// Godot 3 arrays can hold values of any type. `TypedVariantArray` checks the type of each
+/// element when it is created from a `VariantArray` or a `Variant`, and reports the index of
+/// the first element that can't be converted to `T`. When exported as a property, the element
+/// type is passed to the editor as an `ArrayHint`, so that only values of that type can be
+/// added in the inspector:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+/// use gdnative::core_types::TypedVariantArray;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Spawner {
+///     #[property]
+///     waypoints: TypedVariantArray<Vector2>,
+/// }
+/// ```
+///
+/// Since the underlying array is reference-counted, it can still be modified through other
+/// references, e.g. from GDScript. Element access is therefore checked as well, and returns
+/// the same errors as the initial conversion.
+///
+/// Like `VariantArray`, this type uses the *typestate* pattern to track the ownership of the
+/// array. See [`VariantArray`] for details.
+pub struct TypedVariantArray<T, Own: Ownership = Shared> {
+    array: VariantArray<Own>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Operations allowed on all typed arrays at any point in time.
+impl<T, Own: Ownership> TypedVariantArray<T, Own> {
+    /// Returns the number of elements in the array.
+    #[inline]
+    pub fn len(&self) -> i32 {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns a reference to the untyped array.
+    #[inline]
+    pub fn as_array(&self) -> &VariantArray<Own> {
+        &self.array
+    }
+
+    /// Returns the untyped array.
+    #[inline]
+    pub fn into_array(self) -> VariantArray<Own> {
+        self.array
+    }
+
+    /// Wraps `array` without checking the types of its elements. Elements that are not of
+    /// type `T` are still reported when they are accessed.
+    #[inline]
+    pub fn from_array_unchecked(array: VariantArray<Own>) -> Self {
+        TypedVariantArray {
+            array,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FromVariant, Own: Ownership> TypedVariantArray<T, Own> {
+    /// Wraps `array`, checking that all of its elements can be converted to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FromVariantError::InvalidItem`] with the index of the first element that
+    /// can't be converted.
+    #[inline]
+    pub fn from_array(array: VariantArray<Own>) -> Result<Self, FromVariantError> {
+        let array = Self::from_array_unchecked(array);
+        array.validate()?;
+        Ok(array)
+    }
+
+    /// Returns the element at the given offset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FromVariantError::InvalidItem`] if the element has been replaced with a value
+    /// that can't be converted to `T`.
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: i32) -> Result<T, FromVariantError> {
+        convert_item(idx, &self.array.get(idx))
+    }
+
+    /// Returns an iterator through the elements of the array, converted to `T`.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Result<T, FromVariantError>> + '_ {
+        (0..self.len()).map(move |idx| self.get(idx))
+    }
+
+    /// Converts the elements of the array to a `Vec<T>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FromVariantError::InvalidItem`] with the index of the first element that
+    /// can't be converted.
+    #[inline]
+    pub fn to_vec(&self) -> Result<Vec<T>, FromVariantError> {
+        self.iter().collect()
+    }
+
+    fn validate(&self) -> Result<(), FromVariantError> {
+        self.iter().try_for_each(|item| item.map(drop))
+    }
+}
+
+impl<T: OwnedToVariant, Own: Ownership> TypedVariantArray<T, Own> {
+    /// Sets the value of the element at the given offset.
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is out of bounds.
+    #[inline]
+    pub fn set(&self, idx: i32, val: T) {
+        self.array.set(idx, val)
+    }
+}
+
+/// Operations allowed on typed arrays that can only be referenced to from the current thread.
+impl<T: OwnedToVariant, Own: LocalThreadOwnership> TypedVariantArray<T, Own> {
+    /// Appends an element at the end of the array.
+    #[inline]
+    pub fn push(&self, val: T) {
+        self.array.push(val)
+    }
+
+    /// Inserts an element at the given position in the array.
+    #[inline]
+    pub fn insert(&self, at: i32, val: T) {
+        self.array.insert(at, val)
+    }
+}
+
+/// Operations allowed on typed arrays that can only be referenced to from the current thread.
+impl<T, Own: LocalThreadOwnership> TypedVariantArray<T, Own> {
+    /// Removes the element at `idx`.
+    #[inline]
+    pub fn remove(&self, idx: i32) {
+        self.array.remove(idx)
+    }
+
+    /// Clears the array, resizing to 0.
+    #[inline]
+    pub fn clear(&self) {
+        self.array.clear()
+    }
+}
+
+/// Operations allowed on non-unique typed arrays.
+impl<T, Own: NonUniqueOwnership> TypedVariantArray<T, Own> {
+    /// Assume that this is the only reference to this array. See
+    /// [`VariantArray::assume_unique`].
+    ///
+    /// # Safety
+    ///
+    /// See [`VariantArray::assume_unique`].
+    #[inline]
+    pub unsafe fn assume_unique(self) -> TypedVariantArray<T, Unique> {
+        TypedVariantArray::from_array_unchecked(self.array.assume_unique())
+    }
+}
+
+/// Operations allowed on unique typed arrays.
+impl<T> TypedVariantArray<T, Unique> {
+    /// Creates an empty array.
+    #[inline]
+    pub fn new() -> Self {
+        Self::from_array_unchecked(VariantArray::new())
+    }
+
+    /// Put this array under the "shared" access type.
+    #[inline]
+    pub fn into_shared(self) -> TypedVariantArray<T, Shared> {
+        TypedVariantArray::from_array_unchecked(self.array.into_shared())
+    }
+
+    /// Put this array under the "thread-local" access type.
+    #[inline]
+    pub fn into_thread_local(self) -> TypedVariantArray<T, ThreadLocal> {
+        TypedVariantArray::from_array_unchecked(self.array.into_thread_local())
+    }
+}
+
+impl<T> Default for TypedVariantArray<T, Unique> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for TypedVariantArray<T, Shared> {
+    #[inline]
+    fn default() -> Self {
+        TypedVariantArray::new().into_shared()
+    }
+}
+
+impl<T, Own: NonUniqueOwnership> NewRef for TypedVariantArray<T, Own> {
+    #[inline]
+    fn new_ref(&self) -> Self {
+        Self::from_array_unchecked(self.array.new_ref())
+    }
+}
+
+impl<T, Own: Ownership> fmt::Debug for TypedVariantArray<T, Own> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.array, f)
+    }
+}
+
+impl<T: ToVariant> FromIterator<T> for TypedVariantArray<T, Unique> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_array_unchecked(iter.into_iter().collect())
+    }
+}
+
+impl<T: FromVariant> FromVariant for TypedVariantArray<T, Shared> {
+    #[inline]
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        VariantArray::from_variant(variant).and_then(Self::from_array)
+    }
+}
+
+impl<T> ToVariant for TypedVariantArray<T, Shared> {
+    #[inline]
+    fn to_variant(&self) -> Variant {
+        self.array.to_variant()
+    }
+}
+
+impl<T> OwnedToVariant for TypedVariantArray<T, Unique> {
+    #[inline]
+    fn owned_to_variant(self) -> Variant {
+        self.array.owned_to_variant()
+    }
+}
+
+fn convert_item<T: FromVariant>(idx: i32, variant: &Variant) -> Result<T, FromVariantError> {
+    T::from_variant(variant).map_err(|err| FromVariantError::InvalidItem {
+        index: idx as usize,
+        error: Box::new(err),
+    })
+}
+
+godot_test!(test_typed_variant_array {
+    let array = TypedVariantArray::<i64, _>::new();
+    array.push(1);
+    array.push(2);
+    array.insert(0, 3);
+    assert_eq!(Ok(vec![3, 1, 2]), array.to_vec());
+
+    let array = array.into_shared();
+    let variant = array.to_variant();
+    let typed = TypedVariantArray::<i64>::from_variant(&variant).unwrap();
+    assert_eq!(3, typed.len());
+    assert_eq!(Ok(2), typed.get(2));
+
+    let mixed = VariantArray::new();
+    mixed.push(1);
+    mixed.push("two");
+    let mixed = mixed.into_shared();
+    match TypedVariantArray::<i64>::from_variant(&mixed.to_variant()) {
+        Err(FromVariantError::InvalidItem { index: 1, .. }) => {}
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    // Elements are checked on access, since the array can be modified through other references.
+    let typed = TypedVariantArray::<i64>::from_array_unchecked(mixed);
+    assert_eq!(Ok(1), typed.get(0));
+    assert!(typed.get(1).is_err());
+    assert_eq!(vec![true, false], typed.iter().map(|item| item.is_ok()).collect::<Vec<_>>());
+});
@@ -1,9 +1,10 @@
 use crate::*;
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::fmt;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::mem::{forget, transmute};
 use std::ptr;
 
@@ -12,9 +13,16 @@ use crate::object::ownership::*;
 use crate::object::*;
 use crate::private::{get_api, ManuallyManagedClassPlaceholder};
 
+pub(crate) mod bytes;
+#[cfg(feature = "serde")]
+mod json;
 #[cfg(feature = "serde")]
 mod serialize;
 
+pub use bytes::BytesError;
+#[cfg(feature = "serde")]
+pub use json::{JsonOptions, NonJsonValue, ToJsonError};
+
 /// A `Variant` can represent all Godot values (core types or `Object` class instances).
 ///
 /// The underlying data is either stored inline or reference-counted on the heap,
@@ -23,7 +31,16 @@ mod serialize;
 /// If you compile godot-rust with the `serde` feature enabled, you will have
 /// access to serialization/deserialization support: the traits `Serialize`
 /// and `Deserialize` will be automatically implemented on [`VariantDispatch`]
-/// as well as most of the types in [`core_types`].
+/// as well as most of the types in [`core_types`]. Variant trees can also be
+/// converted to and from `serde_json::Value` with [`Variant::to_json_value`]
+/// and [`Variant::from_json_value`].
+///
+/// ## Comparison and hashing
+///
+/// `==` and `<` are the operators of the engine's `Variant` class: values of different types are
+/// never equal, and are ordered by their type first. [`Variant::script_cmp`] and [`ScriptOrd`]
+/// compare values like GDScript instead, and [`Variant::godot_hash`] is the hash of GDScript's
+/// `hash()`, which is also used by the `Hash` implementation.
 pub struct Variant(pub(crate) sys::godot_variant);
 
 macro_rules! impl_coerce_from_variant_inner {
@@ -311,6 +328,7 @@ type Bool = bool;
 impl Variant {
     /// Creates a `Variant` from a value that implements [`ToVariant`].
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     pub fn new<T: OwnedToVariant>(from: T) -> Self {
         from.owned_to_variant()
     }
@@ -526,6 +544,65 @@ impl Variant {
         }
     }
 
+    /// Returns the hash of the value, like `hash()` in GDScript, and the engine's hash for
+    /// dictionary keys.
+    ///
+    /// Like the `Hash` implementation, this is consistent with `==`, except for signed zeroes in
+    /// `PoolArray<f32>`, which are hashed by their bytes. Signed zeroes hash the same elsewhere,
+    /// as do all NaNs. Arrays, dictionaries and pool arrays are hashed by their contents, and
+    /// objects by their identity.
+    #[inline]
+    pub fn godot_hash(&self) -> u32 {
+        // The engine doesn't expose its variant hash function, but the hash of an array
+        // combines the hashes of its elements with djb2, so the hash of a variant can be
+        // recovered from that of an array containing only the variant.
+        const fn hash_djb2_one_32(value: u32, prev: u32) -> u32 {
+            (prev << 5).wrapping_add(prev).wrapping_add(value)
+        }
+        const ARRAY_SEED: u32 = hash_djb2_one_32(0, 5381);
+
+        let array = VariantArray::new();
+        array.push(self);
+        (array.hash() as u32).wrapping_sub(hash_djb2_one_32(0, ARRAY_SEED))
+    }
+
+    /// Compares `self` and `other` like the keys of a `Dictionary`.
+    ///
+    /// This differs from `==` in that NaNs are equal to each other, including the components of
+    /// math types, and arrays are compared with `hash_compare` recursively.
+    #[inline]
+    pub fn hash_compare(&self, other: &Variant) -> bool {
+        unsafe { (get_api().godot_variant_hash_compare)(&self.0, &other.0) }
+    }
+
+    /// Compares `self` and `other` with the `==` and `<` operators of GDScript, which
+    /// `Array.sort` and `Array.max` also use.
+    ///
+    /// Unlike the `PartialOrd` implementation of `Variant`, numbers of different types are
+    /// compared by value, so `1` is equal to `1.0`, and `None` is returned for operands that
+    /// GDScript can't compare, such as a number and a string, or two different dictionaries.
+    /// Use [`ScriptOrd`] for the `PartialOrd` implementation based on this method.
+    ///
+    /// Floats are compared exactly, like in GDScript. `is_equal_approx` has to be called
+    /// explicitly for approximate comparisons.
+    #[inline]
+    pub fn script_cmp(&self, other: &Variant) -> Option<Ordering> {
+        let check = |op, lhs: &Variant, rhs: &Variant| {
+            lhs.evaluate(op, rhs)
+                .is_ok_and(|result| result.to::<bool>() == Some(true))
+        };
+
+        if check(VariantOperator::Equal, self, other) {
+            Some(Ordering::Equal)
+        } else if check(VariantOperator::Less, self, other) {
+            Some(Ordering::Less)
+        } else if check(VariantOperator::Less, other, self) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+
     /// Get a reference to a `godot-rust` Variant from a raw sys::pointer.
     ///
     /// # Safety
@@ -623,6 +700,61 @@ impl_basic_traits_as_sys!(
 
 impl Eq for Variant {}
 
+impl Hash for Variant {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(self.godot_hash());
+    }
+}
+
+/// Wrapper comparing variants with the operators of GDScript, see [`Variant::script_cmp`].
+///
+/// The comparisons of `Variant` itself are those of the engine's `Variant` class, which order
+/// values by their type first, so that any two values can be compared. `ScriptOrd` follows the
+/// semantics of GDScript code instead, so Rust code can sort data the same way as scripts do:
+///
+/// ```no_run
+/// use gdnative::core_types::variant::ScriptOrd;
+/// use gdnative::prelude::*;
+///
+/// let mut scores = vec![Variant::new(2.5), Variant::new(1), Variant::new(2)];
+/// scores.sort_by(|a, b| ScriptOrd::cmp_or_equal(a, b));
+/// assert_eq!(Variant::new(1), scores[0]);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[repr(transparent)]
+pub struct ScriptOrd(pub Variant);
+
+impl ScriptOrd {
+    /// Compares `a` and `b` with the GDScript operators, treating values that can't be compared
+    /// as equal. Suitable for `sort_by`, which keeps such values in their original order.
+    #[inline]
+    pub fn cmp_or_equal(a: &Variant, b: &Variant) -> Ordering {
+        a.script_cmp(b).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl From<Variant> for ScriptOrd {
+    #[inline]
+    fn from(variant: Variant) -> Self {
+        ScriptOrd(variant)
+    }
+}
+
+impl PartialEq for ScriptOrd {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.script_cmp(&other.0) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for ScriptOrd {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.script_cmp(&other.0)
+    }
+}
+
 impl fmt::Display for Variant {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -712,6 +844,8 @@ godot_test!(
 /// - `PhantomData<T>` is represented as `Nil`.
 /// - `&[T]` and `Vec<T>` are represented as `VariantArray`s. `FromVariant` is only implemented
 /// for `Vec<T>`.
+/// - Tuples of up to 12 elements are represented as `VariantArray`s, with an element at each
+/// position. This is convenient for returning multiple values to GDScript.
 ///
 /// ## Deriving `ToVariant`
 ///
@@ -739,6 +873,13 @@ godot_test!(
 /// Only applicable to field-less enums with a explicit primitive `#[repr]` type. Variants of
 /// types annotated with this attribute are represented as their primitive integral values.
 ///
+/// - `#[variant(transparent)]`
+///
+/// Only applicable to structs with exactly one field that isn't skipped with
+/// `#[variant(skip)]`. The struct is represented as that field, so that newtypes like
+/// `struct Health { value: u32 }` appear as plain values in GDScript. Skipped fields are
+/// set to `Default::default()` when converting from `Variant`.
+///
 /// ### Field attributes
 ///
 /// - `#[variant(to_variant_with = "path::to::func")]`
@@ -838,6 +979,12 @@ pub trait ToVariantEq: Eq {}
 /// manually handle potentially heterogeneous values e.g. for error reporting, use `VariantArray`
 /// directly or compose with an appropriate wrapper: `Vec<Option<T>>` or `Vec<MaybeNot<T>>`.
 ///
+/// ## Tuples
+///
+/// Tuples of up to 12 elements can be converted from `VariantArray`s of the same length, with
+/// each element converted from the item at its position. Items that cannot be converted are
+/// reported with their index, e.g. "invalid value for item at index 1: ...".
+///
 /// ## Deriving `FromVariant`
 ///
 /// The derive macro provides implementation consistent with derived `ToVariant`. See `ToVariant`
@@ -948,6 +1095,27 @@ impl FromVariantError {
     pub fn custom<T: fmt::Display>(message: T) -> Self {
         FromVariantError::Custom(format!("{message}"))
     }
+
+    /// Writes the path to the innermost error through nested fields and items, such as
+    /// `.foo[1]`, followed by that error.
+    fn fmt_nested(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use FromVariantError as E;
+
+        let mut next_error = self;
+        loop {
+            match next_error {
+                E::InvalidField { field_name, error } => {
+                    write!(f, ".{field_name}")?;
+                    next_error = error.as_ref();
+                }
+                E::InvalidItem { index, error } => {
+                    write!(f, "[{index}]")?;
+                    next_error = error.as_ref();
+                }
+                _ => return write!(f, ": {next_error}"),
+            }
+        }
+    }
 }
 
 impl fmt::Display for FromVariantError {
@@ -1001,27 +1169,11 @@ impl fmt::Display for FromVariantError {
             }
             E::InvalidField { field_name, error } => {
                 write!(f, "invalid value for field {field_name}")?;
-
-                let mut next_error = error.as_ref();
-                loop {
-                    match next_error {
-                        E::InvalidField { field_name, error } => {
-                            write!(f, ".{field_name}")?;
-                            next_error = error.as_ref();
-                        }
-                        E::InvalidItem { index, error } => {
-                            write!(f, "[{index}]")?;
-                            next_error = error.as_ref();
-                        }
-                        _ => {
-                            write!(f, ": {next_error}")?;
-                            return Ok(());
-                        }
-                    }
-                }
+                error.fmt_nested(f)
             }
             E::InvalidItem { index, error } => {
-                write!(f, "invalid value for item at index {index}: {error}")
+                write!(f, "invalid value for item at index {index}")?;
+                error.fmt_nested(f)
             }
         }
     }
@@ -1031,6 +1183,7 @@ impl std::error::Error for FromVariantError {}
 
 impl<T: ToVariant> OwnedToVariant for T {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn owned_to_variant(self) -> Variant {
         self.to_variant()
     }
@@ -1038,6 +1191,7 @@ impl<T: ToVariant> OwnedToVariant for T {
 
 impl ToVariant for () {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         Variant::nil()
     }
@@ -1063,6 +1217,7 @@ where
     T: ToVariant + ?Sized,
 {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         T::to_variant(*self)
     }
@@ -1076,6 +1231,7 @@ where
     T: ToVariant + ?Sized,
 {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         T::to_variant(*self)
     }
@@ -1084,6 +1240,7 @@ impl<'a, T> ToVariantEq for &'a mut T where T: ToVariantEq + ?Sized {}
 
 impl<T: GodotObject> ToVariant for Ref<T, Shared> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         unsafe { Variant::from_object_ptr(self.as_ptr()) }
     }
@@ -1091,6 +1248,7 @@ impl<T: GodotObject> ToVariant for Ref<T, Shared> {
 
 impl<T: GodotObject> OwnedToVariant for Ref<T, Unique> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn owned_to_variant(self) -> Variant {
         unsafe { Variant::from_object_ptr(self.as_ptr()) }
     }
@@ -1098,6 +1256,7 @@ impl<T: GodotObject> OwnedToVariant for Ref<T, Unique> {
 
 impl<'a, T: GodotObject> ToVariant for TRef<'a, T, Shared> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         unsafe { Variant::from_object_ptr(self.as_ptr()) }
     }
@@ -1244,13 +1403,16 @@ macro_rules! to_variant_transmute {
         $(
             impl ToVariant for $ty {
                 #[inline]
+                #[cfg_attr(feature = "alloc-tracking", track_caller)]
                 fn to_variant(&self) -> Variant {
                     unsafe {
                         let api = get_api();
                         let mut dest = sys::godot_variant::default();
                         #[allow(clippy::useless_transmute)]
                         (api.$ctor)(&mut dest, transmute(self));
-                        Variant::from_sys(dest)
+                        let variant = Variant::from_sys(dest);
+                        crate::profiler::track_variant_alloc!(&variant);
+                        variant
                     }
                 }
             }
@@ -1273,12 +1435,15 @@ macro_rules! to_variant_as_sys {
         $(
             impl ToVariant for $ty {
                 #[inline]
+                #[cfg_attr(feature = "alloc-tracking", track_caller)]
                 fn to_variant(&self) -> Variant {
                     unsafe {
                         let api = get_api();
                         let mut dest = sys::godot_variant::default();
                         (api.$ctor)(&mut dest, self.sys());
-                        Variant::from_sys(dest)
+                        let variant = Variant::from_sys(dest);
+                        crate::profiler::track_variant_alloc!(&variant);
+                        variant
                     }
                 }
             }
@@ -1305,6 +1470,7 @@ impl ToVariantEq for GodotString {}
 
 impl OwnedToVariant for Dictionary<Unique> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn owned_to_variant(self) -> Variant {
         self.into_shared().to_variant()
     }
@@ -1312,6 +1478,7 @@ impl OwnedToVariant for Dictionary<Unique> {
 
 impl OwnedToVariant for VariantArray<Unique> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn owned_to_variant(self) -> Variant {
         self.into_shared().to_variant()
     }
@@ -1407,6 +1574,7 @@ impl<T: crate::core_types::PoolElement> FromVariant for PoolArray<T> {
 
 impl ToVariant for str {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         GodotString::from_str(self).owned_to_variant()
     }
@@ -1415,6 +1583,7 @@ impl ToVariantEq for str {}
 
 impl ToVariant for String {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         self.as_str().to_variant()
     }
@@ -1430,6 +1599,7 @@ impl FromVariant for String {
 
 impl ToVariant for Variant {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         self.clone()
     }
@@ -1444,6 +1614,7 @@ impl FromVariant for Variant {
 
 impl<T> ToVariant for std::marker::PhantomData<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         Variant::nil()
     }
@@ -1466,6 +1637,7 @@ impl<T> FromVariant for std::marker::PhantomData<T> {
 
 impl<T: ToVariant> ToVariant for Option<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         match &self {
             Some(thing) => thing.to_variant(),
@@ -1535,6 +1707,7 @@ impl<T> MaybeNot<T> {
 
 impl<T: ToVariant, E: ToVariant> ToVariant for Result<T, E> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         let dict = Dictionary::new();
         match &self {
@@ -1601,6 +1774,7 @@ impl<T: FromVariant, E: FromVariant> FromVariant for Result<T, E> {
 
 impl<T: ToVariant> ToVariant for &[T] {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         let array = VariantArray::new();
         for val in self.iter() {
@@ -1613,6 +1787,7 @@ impl<T: ToVariant> ToVariant for &[T] {
 
 impl<T: ToVariant> ToVariant for Vec<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         self.as_slice().to_variant()
     }
@@ -1646,6 +1821,7 @@ impl<T: FromVariant> FromVariant for Vec<T> {
 /// deterministic output in Godot (e.g. UI elements for properties), the elements are sorted by key.
 impl<K: ToVariant + Hash + ToVariantEq, V: ToVariant> ToVariant for HashMap<K, V> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         // Note: dictionary currently provides neither a sort() function nor random access (or at least bidirectional)
         // iterators, making it difficult to sort in-place. Workaround: copy to vector
@@ -1694,6 +1870,7 @@ impl<K: FromVariant + Hash + Eq, V: FromVariant> FromVariant for HashMap<K, V> {
 /// deterministic output in Godot (e.g. UI elements for properties), the elements are sorted by key.
 impl<T: ToVariant> ToVariant for HashSet<T> {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         let array = VariantArray::new();
         for value in self {
@@ -1750,6 +1927,7 @@ macro_rules! impl_variant_for_tuples {
         impl<$($name: ToVariant,)+> ToVariant for ($($name,)+) {
             #[allow(non_snake_case)]
             #[inline]
+            #[cfg_attr(feature = "alloc-tracking", track_caller)]
             fn to_variant(&self) -> Variant {
                 let array = VariantArray::new();
                 let ($($name,)+) = self;
@@ -2000,6 +2178,31 @@ godot_test!(
 
         let tuple = <(i64, i64)>::from_variant(&variant);
         assert_eq!(Ok((42, 54)), tuple);
+
+        assert_eq!(
+            Err(FromVariantError::InvalidLength { expected: 3, len: 2 }),
+            <(i64, i64, i64)>::from_variant(&variant),
+        );
+
+        let nested = (1i64, ("foo", 2i64)).to_variant();
+        let err = <(i64, (String, bool))>::from_variant(&nested).unwrap_err();
+        assert_eq!(
+            FromVariantError::InvalidItem {
+                index: 1,
+                error: Box::new(FromVariantError::InvalidItem {
+                    index: 1,
+                    error: Box::new(FromVariantError::InvalidVariantType {
+                        variant_type: VariantType::I64,
+                        expected: VariantType::Bool,
+                    }),
+                }),
+            },
+            err,
+        );
+        assert_eq!(
+            "invalid value for item at index 1[1]: invalid variant type: expected Bool, got I64",
+            err.to_string(),
+        );
     }
 
     test_variant_dispatch {
@@ -2025,4 +2228,58 @@ godot_test!(
         };
         approx::assert_relative_eq!(42.0, number_as_float);
     }
+
+    test_variant_script_cmp {
+        let one = Variant::new(1);
+        let one_f = Variant::new(1.0);
+        let two = Variant::new(2);
+
+        // The engine's operators compare types first.
+        assert_ne!(one, one_f);
+        assert!(one < one_f);
+
+        assert_eq!(Some(Ordering::Equal), one.script_cmp(&one_f));
+        assert_eq!(Some(Ordering::Less), one_f.script_cmp(&two));
+        assert_eq!(Some(Ordering::Greater), two.script_cmp(&one));
+        assert_eq!(None, one.script_cmp(&Variant::new("1")));
+        assert_eq!(None, Variant::new(f64::NAN).script_cmp(&one_f));
+
+        assert!(ScriptOrd(one.clone()) == ScriptOrd(one_f.clone()));
+        assert!(ScriptOrd(one_f) < ScriptOrd(two.clone()));
+
+        let mut values = vec![Variant::new(2.5), two, Variant::new(0.5), one];
+        values.sort_by(ScriptOrd::cmp_or_equal);
+        let sorted = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["0.5", "1", "2", "2.5"], sorted);
+    }
+
+    test_variant_godot_hash {
+        assert_eq!(42, Variant::new(42).godot_hash());
+        assert_eq!(1, Variant::new(true).godot_hash());
+        assert_eq!(0, Variant::nil().godot_hash());
+        assert_eq!(
+            GodotString::from("key").u32_hash(),
+            Variant::new("key").godot_hash(),
+        );
+        assert_eq!(Variant::new(0.0).godot_hash(), Variant::new(-0.0).godot_hash());
+
+        let a = VariantArray::new();
+        a.push(1);
+        a.push("two");
+        let b = a.duplicate();
+        assert_eq!(
+            a.into_shared().to_variant().godot_hash(),
+            b.into_shared().to_variant().godot_hash(),
+        );
+
+        let nan = Variant::new(f64::NAN);
+        assert_ne!(nan, nan.clone());
+        assert!(nan.hash_compare(&nan.clone()));
+        assert!(!Variant::new(1).hash_compare(&Variant::new(1.0)));
+
+        let set = [Variant::new("a"), Variant::new(3), Variant::new("a")]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(2, set.len());
+    }
 );
@@ -0,0 +1,814 @@
+//! Godot's binary serialization format for variants, as used by `var2bytes` and the high-level
+//! multiplayer API.
+
+use super::*;
+use crate::export::PropertyUsage;
+
+const TYPE_MASK: u32 = 0xFF;
+const FLAG_64: u32 = 1 << 16;
+const FLAG_OBJECT_AS_ID: u32 = 1 << 16;
+
+const NODE_PATH_NEW_FORMAT: u32 = 0x8000_0000;
+const NODE_PATH_ABSOLUTE: u32 = 1;
+const NODE_PATH_PROPERTY: u32 = 2;
+
+/// Nesting depth at which encoding and decoding fail, like in the engine.
+const MAX_DEPTH: u32 = 1024;
+
+/// Error type returned by [`Variant::to_bytes`] and [`Variant::from_bytes`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum BytesError {
+    /// The input ended before the value was complete.
+    UnexpectedEnd,
+    /// The input contains an unknown type tag.
+    InvalidType(u32),
+    /// A string in the input is not valid UTF-8.
+    InvalidUtf8,
+    /// A node path in the input uses the obsolete string-based format.
+    InvalidNodePath,
+    /// The input contains a full object, but objects are not allowed.
+    ObjectsNotAllowed,
+    /// An object class in the input cannot be instantiated.
+    InvalidClass(String),
+    /// The value is nested too deeply, possibly because it contains itself.
+    RecursionLimit,
+}
+
+impl fmt::Display for BytesError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BytesError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            BytesError::InvalidType(ty) => write!(f, "invalid variant type tag {ty}"),
+            BytesError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            BytesError::InvalidNodePath => write!(f, "node path uses an unsupported format"),
+            BytesError::ObjectsNotAllowed => write!(f, "objects are not allowed"),
+            BytesError::InvalidClass(class) => write!(f, "class {class} cannot be instantiated"),
+            BytesError::RecursionLimit => write!(f, "maximum nesting depth exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for BytesError {}
+
+impl Variant {
+    /// Encodes this variant in Godot's binary format, like `var2bytes(value, false)`.
+    ///
+    /// Objects are encoded as their instance IDs. `Rid`s cannot be serialized and are encoded
+    /// without any data, so they decode to empty `Rid`s.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if collections are nested too deeply, e.g. because an array contains
+    /// itself.
+    #[inline]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BytesError> {
+        Encoder::encode(self, false)
+    }
+
+    /// Encodes this variant in Godot's binary format, like `var2bytes(value, true)`.
+    ///
+    /// Objects are encoded with their class names and all stored properties. Scripts attached
+    /// to objects are encoded as well, which allows the receiver to execute code on decoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if values are nested too deeply.
+    #[inline]
+    pub fn to_bytes_with_objects(&self) -> Result<Vec<u8>, BytesError> {
+        Encoder::encode(self, true)
+    }
+
+    /// Decodes a variant from Godot's binary format, like `bytes2var(bytes, false)`.
+    ///
+    /// Objects encoded as instance IDs are decoded as `EncodedObjectAsID` instances. Full
+    /// objects are rejected. Trailing data after the first value is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid encoding, or contains a full object.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Variant, BytesError> {
+        Decoder::decode(bytes, false)
+    }
+
+    /// Decodes a variant from Godot's binary format, like `bytes2var(bytes, true)`.
+    ///
+    /// **Warning:** Deserialized objects can contain code which gets executed. Do not use this
+    /// with data from untrusted sources.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid encoding, or an encoded class cannot be
+    /// instantiated.
+    #[inline]
+    pub fn from_bytes_with_objects(bytes: &[u8]) -> Result<Variant, BytesError> {
+        Decoder::decode(bytes, true)
+    }
+}
+
+struct Encoder {
+    buf: Vec<u8>,
+    full_objects: bool,
+}
+
+impl Encoder {
+    fn encode(variant: &Variant, full_objects: bool) -> Result<Vec<u8>, BytesError> {
+        let mut encoder = Encoder {
+            buf: Vec::new(),
+            full_objects,
+        };
+
+        encoder.variant(variant, 0)?;
+        Ok(encoder.buf)
+    }
+
+    fn variant(&mut self, variant: &Variant, depth: u32) -> Result<(), BytesError> {
+        if depth > MAX_DEPTH {
+            return Err(BytesError::RecursionLimit);
+        }
+
+        let ty = variant.get_type() as u32;
+
+        match variant.dispatch() {
+            VariantDispatch::Nil => self.u32(ty),
+            VariantDispatch::Bool(v) => {
+                self.u32(ty);
+                self.u32(v as u32);
+            }
+            VariantDispatch::I64(v) => match i32::try_from(v) {
+                Ok(v) => {
+                    self.u32(ty);
+                    self.u32(v as u32);
+                }
+                Err(_) => {
+                    self.u32(ty | FLAG_64);
+                    self.buf.extend_from_slice(&v.to_le_bytes());
+                }
+            },
+            VariantDispatch::F64(v) => {
+                if (v as f32) as f64 == v {
+                    self.u32(ty);
+                    self.f32(v as f32);
+                } else {
+                    self.u32(ty | FLAG_64);
+                    self.buf.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+            VariantDispatch::GodotString(v) => {
+                self.u32(ty);
+                self.string(&v.to_string());
+            }
+            VariantDispatch::Vector2(v) => {
+                self.u32(ty);
+                self.vector2(v);
+            }
+            VariantDispatch::Rect2(v) => {
+                self.u32(ty);
+                self.vector2(v.position);
+                self.vector2(v.size);
+            }
+            VariantDispatch::Vector3(v) => {
+                self.u32(ty);
+                self.vector3(v);
+            }
+            VariantDispatch::Transform2D(v) => {
+                self.u32(ty);
+                self.vector2(v.a);
+                self.vector2(v.b);
+                self.vector2(v.origin);
+            }
+            VariantDispatch::Plane(v) => {
+                self.u32(ty);
+                self.vector3(v.normal);
+                self.f32(v.d);
+            }
+            VariantDispatch::Quat(v) => {
+                self.u32(ty);
+                for c in [v.x, v.y, v.z, v.w] {
+                    self.f32(c);
+                }
+            }
+            VariantDispatch::Aabb(v) => {
+                self.u32(ty);
+                self.vector3(v.position);
+                self.vector3(v.size);
+            }
+            VariantDispatch::Basis(v) => {
+                self.u32(ty);
+                self.basis(&v);
+            }
+            VariantDispatch::Transform(v) => {
+                self.u32(ty);
+                self.basis(&v.basis);
+                self.vector3(v.origin);
+            }
+            VariantDispatch::Color(v) => {
+                self.u32(ty);
+                self.color(v);
+            }
+            VariantDispatch::NodePath(v) => {
+                self.u32(ty);
+                self.node_path(v);
+            }
+            VariantDispatch::Rid(_) => self.u32(ty),
+            VariantDispatch::Object(v) => self.object(v, depth)?,
+            VariantDispatch::Dictionary(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for (key, value) in v.iter() {
+                    self.variant(&key, depth + 1)?;
+                    self.variant(&value, depth + 1)?;
+                }
+            }
+            VariantDispatch::VariantArray(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for element in v.iter() {
+                    self.variant(&element, depth + 1)?;
+                }
+            }
+            VariantDispatch::ByteArray(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                self.buf.extend_from_slice(&v.read());
+                self.pad();
+            }
+            VariantDispatch::Int32Array(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for &element in v.read().iter() {
+                    self.u32(element as u32);
+                }
+            }
+            VariantDispatch::Float32Array(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for &element in v.read().iter() {
+                    self.f32(element);
+                }
+            }
+            VariantDispatch::StringArray(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for element in v.read().iter() {
+                    self.c_string(&element.to_string());
+                }
+            }
+            VariantDispatch::Vector2Array(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for &element in v.read().iter() {
+                    self.vector2(element);
+                }
+            }
+            VariantDispatch::Vector3Array(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for &element in v.read().iter() {
+                    self.vector3(element);
+                }
+            }
+            VariantDispatch::ColorArray(v) => {
+                self.u32(ty);
+                self.u32(v.len() as u32);
+                for &element in v.read().iter() {
+                    self.color(element);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn object(&mut self, object: Variant, depth: u32) -> Result<(), BytesError> {
+        let ty = VariantType::Object as u32;
+
+        if !self.full_objects {
+            let id = if object.is_nil() {
+                0
+            } else {
+                call(&object, "get_instance_id", &[])
+                    .to::<i64>()
+                    .unwrap_or(0)
+            };
+
+            self.u32(ty | FLAG_OBJECT_AS_ID);
+            self.buf.extend_from_slice(&id.to_le_bytes());
+            return Ok(());
+        }
+
+        self.u32(ty);
+
+        if object.is_nil() {
+            self.u32(0);
+            return Ok(());
+        }
+
+        let class = call(&object, "get_class", &[]).coerce_to::<GodotString>();
+        self.string(&class.to_string());
+
+        let properties = call(&object, "get_property_list", &[])
+            .to::<VariantArray>()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|property| {
+                let property = property.to::<Dictionary>()?;
+                let usage = property.get("usage")?.to::<u32>()?;
+                let name = property.get("name")?.to::<GodotString>()?;
+                PropertyUsage::from_bits_truncate(usage)
+                    .contains(PropertyUsage::STORAGE)
+                    .then_some(name)
+            })
+            .collect::<Vec<_>>();
+
+        self.u32(properties.len() as u32);
+        for name in properties {
+            let value = call(&object, "get", &[name.to_variant()]);
+            self.string(&name.to_string());
+            self.variant(&value, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn vector2(&mut self, v: Vector2) {
+        self.f32(v.x);
+        self.f32(v.y);
+    }
+
+    fn vector3(&mut self, v: Vector3) {
+        self.f32(v.x);
+        self.f32(v.y);
+        self.f32(v.z);
+    }
+
+    fn basis(&mut self, v: &Basis) {
+        for row in v.elements {
+            self.vector3(row);
+        }
+    }
+
+    fn color(&mut self, v: Color) {
+        for c in [v.r, v.g, v.b, v.a] {
+            self.f32(c);
+        }
+    }
+
+    fn string(&mut self, v: &str) {
+        self.u32(v.len() as u32);
+        self.buf.extend_from_slice(v.as_bytes());
+        self.pad();
+    }
+
+    /// Elements of `PoolStringArray` are written with a NUL terminator that is included in
+    /// their length, unlike other strings.
+    fn c_string(&mut self, v: &str) {
+        self.u32(v.len() as u32 + 1);
+        self.buf.extend_from_slice(v.as_bytes());
+        self.buf.push(0);
+        self.pad();
+    }
+
+    fn node_path(&mut self, v: NodePath) {
+        let mut v = v;
+        let names = v.name_count();
+        let subnames = v.get_subname_count();
+        let flags = if v.is_absolute() {
+            NODE_PATH_ABSOLUTE
+        } else {
+            0
+        };
+
+        self.u32(names as u32 | NODE_PATH_NEW_FORMAT);
+        self.u32(subnames as u32);
+        self.u32(flags);
+
+        for i in 0..names {
+            self.string(&v.get_name(i).to_string());
+        }
+        for i in 0..subnames {
+            self.string(&v.get_subname(i).to_string());
+        }
+    }
+
+    fn pad(&mut self) {
+        while self.buf.len() % 4 != 0 {
+            self.buf.push(0);
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    allow_objects: bool,
+}
+
+impl<'a> Decoder<'a> {
+    fn decode(bytes: &'a [u8], allow_objects: bool) -> Result<Variant, BytesError> {
+        Decoder {
+            bytes,
+            pos: 0,
+            allow_objects,
+        }
+        .variant(0)
+    }
+
+    fn variant(&mut self, depth: u32) -> Result<Variant, BytesError> {
+        if depth > MAX_DEPTH {
+            return Err(BytesError::RecursionLimit);
+        }
+
+        let header = self.u32()?;
+        let ty = header & TYPE_MASK;
+        let is_64 = header & FLAG_64 != 0;
+
+        let variant = match ty {
+            0 => Variant::nil(),
+            1 => (self.u32()? != 0).to_variant(),
+            2 if is_64 => i64::from_le_bytes(self.array()?).to_variant(),
+            2 => (self.u32()? as i32).to_variant(),
+            3 if is_64 => f64::from_le_bytes(self.array()?).to_variant(),
+            3 => (self.f32()? as f64).to_variant(),
+            4 => GodotString::from(self.string()?).to_variant(),
+            5 => self.vector2()?.to_variant(),
+            6 => Rect2 {
+                position: self.vector2()?,
+                size: self.vector2()?,
+            }
+            .to_variant(),
+            7 => self.vector3()?.to_variant(),
+            8 => Transform2D {
+                a: self.vector2()?,
+                b: self.vector2()?,
+                origin: self.vector2()?,
+            }
+            .to_variant(),
+            9 => Plane {
+                normal: self.vector3()?,
+                d: self.f32()?,
+            }
+            .to_variant(),
+            10 => Quat {
+                x: self.f32()?,
+                y: self.f32()?,
+                z: self.f32()?,
+                w: self.f32()?,
+            }
+            .to_variant(),
+            11 => Aabb {
+                position: self.vector3()?,
+                size: self.vector3()?,
+            }
+            .to_variant(),
+            12 => self.basis()?.to_variant(),
+            13 => Transform {
+                basis: self.basis()?,
+                origin: self.vector3()?,
+            }
+            .to_variant(),
+            14 => self.color()?.to_variant(),
+            15 => self.node_path()?.to_variant(),
+            16 => Rid::new().to_variant(),
+            17 if header & FLAG_OBJECT_AS_ID != 0 => {
+                let id = i64::from_le_bytes(self.array()?);
+                if id == 0 {
+                    null_object()
+                } else {
+                    let object = instantiate("EncodedObjectAsID")?;
+                    call(&object, "set_object_id", &[id.to_variant()]);
+                    object
+                }
+            }
+            17 => self.object(depth)?,
+            18 => {
+                let len = self.u32()?;
+                let dict = Dictionary::new();
+                for _ in 0..len {
+                    let key = self.variant(depth + 1)?;
+                    let value = self.variant(depth + 1)?;
+                    dict.insert(key, value);
+                }
+                dict.owned_to_variant()
+            }
+            19 => {
+                let len = self.u32()?;
+                let array = VariantArray::new();
+                for _ in 0..len {
+                    array.push(self.variant(depth + 1)?);
+                }
+                array.owned_to_variant()
+            }
+            20 => {
+                let len = self.len(1)?;
+                let bytes = self.take(len)?;
+                let array = PoolArray::from_slice(bytes);
+                self.pad();
+                array.to_variant()
+            }
+            21 => {
+                let len = self.len(4)?;
+                (0..len)
+                    .map(|_| self.u32().map(|v| v as i32))
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            22 => {
+                let len = self.len(4)?;
+                (0..len)
+                    .map(|_| self.f32())
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            23 => {
+                let len = self.len(4)?;
+                (0..len)
+                    .map(|_| self.c_string().map(GodotString::from))
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            24 => {
+                let len = self.len(8)?;
+                (0..len)
+                    .map(|_| self.vector2())
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            25 => {
+                let len = self.len(12)?;
+                (0..len)
+                    .map(|_| self.vector3())
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            26 => {
+                let len = self.len(16)?;
+                (0..len)
+                    .map(|_| self.color())
+                    .collect::<Result<PoolArray<_>, _>>()?
+                    .to_variant()
+            }
+            _ => return Err(BytesError::InvalidType(ty)),
+        };
+
+        Ok(variant)
+    }
+
+    fn object(&mut self, depth: u32) -> Result<Variant, BytesError> {
+        if !self.allow_objects {
+            return Err(BytesError::ObjectsNotAllowed);
+        }
+
+        let class = self.string()?;
+        if class.is_empty() {
+            return Ok(null_object());
+        }
+
+        let object = instantiate(&class)?;
+
+        let len = self.u32()?;
+        for _ in 0..len {
+            let name = GodotString::from(self.string()?);
+            let value = self.variant(depth + 1)?;
+            call(&object, "set", &[name.to_variant(), value]);
+        }
+
+        Ok(object)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BytesError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(BytesError::UnexpectedEnd)?;
+
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BytesError> {
+        let bytes = self.take(N)?;
+        Ok(bytes.try_into().expect("length checked"))
+    }
+
+    /// Reads the length of a pool array, checking that the remaining input can hold at least
+    /// `element_size` bytes for each element.
+    fn len(&mut self, element_size: usize) -> Result<usize, BytesError> {
+        let len = self.u32()? as usize;
+        if len.saturating_mul(element_size) > self.bytes.len() - self.pos {
+            return Err(BytesError::UnexpectedEnd);
+        }
+        Ok(len)
+    }
+
+    fn u32(&mut self) -> Result<u32, BytesError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32, BytesError> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    fn vector2(&mut self) -> Result<Vector2, BytesError> {
+        Ok(Vector2::new(self.f32()?, self.f32()?))
+    }
+
+    fn vector3(&mut self) -> Result<Vector3, BytesError> {
+        Ok(Vector3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn basis(&mut self) -> Result<Basis, BytesError> {
+        Ok(Basis {
+            elements: [self.vector3()?, self.vector3()?, self.vector3()?],
+        })
+    }
+
+    fn color(&mut self) -> Result<Color, BytesError> {
+        Ok(Color::from_rgba(
+            self.f32()?,
+            self.f32()?,
+            self.f32()?,
+            self.f32()?,
+        ))
+    }
+
+    fn string(&mut self) -> Result<String, BytesError> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        let string = std::str::from_utf8(bytes).map_err(|_| BytesError::InvalidUtf8)?;
+        self.pad();
+        Ok(string.to_owned())
+    }
+
+    /// Reads a `PoolStringArray` element. Like the engine, the string ends at the first NUL.
+    fn c_string(&mut self) -> Result<String, BytesError> {
+        let mut string = self.string()?;
+        if let Some(end) = string.find('\0') {
+            string.truncate(end);
+        }
+        Ok(string)
+    }
+
+    fn node_path(&mut self) -> Result<NodePath, BytesError> {
+        let names = self.u32()?;
+        if names & NODE_PATH_NEW_FORMAT == 0 {
+            return Err(BytesError::InvalidNodePath);
+        }
+
+        let names = names & !NODE_PATH_NEW_FORMAT;
+        let mut subnames = self.u32()?;
+        let flags = self.u32()?;
+        if flags & NODE_PATH_PROPERTY != 0 {
+            subnames += 1;
+        }
+
+        let mut path = String::new();
+        if flags & NODE_PATH_ABSOLUTE != 0 {
+            path.push('/');
+        }
+
+        for i in 0..names {
+            if i > 0 {
+                path.push('/');
+            }
+            path.push_str(&self.string()?);
+        }
+        for _ in 0..subnames {
+            path.push(':');
+            path.push_str(&self.string()?);
+        }
+
+        Ok(NodePath::from_str(&path))
+    }
+
+    /// Skips the padding after variable-length data. Missing padding at the end of the input
+    /// is accepted, like in the engine.
+    fn pad(&mut self) {
+        self.pos = ((self.pos + 3) & !3).min(self.bytes.len());
+    }
+}
+
+/// Calls `method` on an object variant, returning `nil` on failure.
+fn call(object: &Variant, method: &str, args: &[Variant]) -> Variant {
+    // SAFETY: only engine methods without user code are called, except for property accessors
+    // of scripts on objects that were explicitly requested to be (de)serialized.
+    unsafe { object.clone().call(method, args) }.unwrap_or_default()
+}
+
+fn null_object() -> Variant {
+    // SAFETY: a null pointer creates an empty object variant.
+    unsafe { Variant::from_object_ptr(ptr::null_mut()) }
+}
+
+/// Instantiates the engine class `class` through `ClassDB`.
+fn instantiate(class: &str) -> Result<Variant, BytesError> {
+    // SAFETY: `ClassDB` is a singleton that lives for the duration of the program.
+    let class_db = unsafe {
+        let class_db = (get_api().godot_global_get_singleton)(b"ClassDB\0".as_ptr() as *mut _);
+        Variant::from_object_ptr(class_db)
+    };
+
+    let object = call(&class_db, "instance", &[class.to_variant()]);
+    if object.is_nil() {
+        return Err(BytesError::InvalidClass(class.to_owned()));
+    }
+
+    Ok(object)
+}
+
+godot_test!(
+    test_variant_bytes_encoding {
+        assert_eq!(Ok(vec![0, 0, 0, 0]), Variant::nil().to_bytes());
+        assert_eq!(Ok(vec![2, 0, 0, 0, 42, 0, 0, 0]), 42.to_variant().to_bytes());
+        assert_eq!(
+            Ok(vec![2, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0]),
+            (1_i64 << 32).to_variant().to_bytes(),
+        );
+        assert_eq!(Ok(vec![3, 0, 0, 0, 0, 0, 0xc0, 0x3f]), 1.5.to_variant().to_bytes());
+        assert_eq!(
+            Ok(vec![4, 0, 0, 0, 2, 0, 0, 0, b'h', b'i', 0, 0]),
+            "hi".to_variant().to_bytes(),
+        );
+        assert_eq!(
+            Ok(vec![20, 0, 0, 0, 3, 0, 0, 0, 1, 2, 3, 0]),
+            PoolArray::from_slice(&[1_u8, 2, 3]).to_variant().to_bytes(),
+        );
+    }
+
+    test_variant_bytes_string_array {
+        // var2bytes(PoolStringArray(["a", "bcd"])) in Godot 3.5
+        let engine = vec![
+            23, 0, 0, 0, 2, 0, 0, 0,
+            2, 0, 0, 0, b'a', 0, 0, 0,
+            4, 0, 0, 0, b'b', b'c', b'd', 0,
+        ];
+
+        let array = PoolArray::from_vec(vec![GodotString::from("a"), GodotString::from("bcd")]);
+        assert_eq!(Ok(engine.clone()), array.to_variant().to_bytes());
+
+        let decoded = Variant::from_bytes(&engine).unwrap();
+        let decoded = decoded.to::<PoolArray<GodotString>>().unwrap();
+        assert_eq!(2, decoded.len());
+        assert_eq!("a", decoded.get(0).to_string());
+        assert_eq!("bcd", decoded.get(1).to_string());
+    }
+
+    test_variant_bytes_roundtrip {
+        let dict = Dictionary::new();
+        dict.insert("path", NodePath::from_str("/root/Player:position:x"));
+        dict.insert(7, Transform2D::IDENTITY);
+        dict.insert("big", i64::MAX);
+        dict.insert("precise", 0.1_f64);
+
+        let array = VariantArray::new();
+        array.push(Vector3::new(1.0, 2.0, 3.0));
+        array.push(Color::from_rgba(0.1, 0.2, 0.3, 0.4));
+        array.push(PoolArray::from_vec(vec![GodotString::from("a"), GodotString::from("bcde")]));
+        array.push(dict.into_shared());
+        let variant = array.into_shared().to_variant();
+
+        let bytes = variant.to_bytes().unwrap();
+        assert_eq!(0, bytes.len() % 4);
+
+        let decoded = Variant::from_bytes(&bytes).unwrap();
+        assert_eq!(bytes, decoded.to_bytes().unwrap());
+
+        let decoded = decoded.to::<VariantArray>().unwrap();
+        let dict = decoded.get(3).to::<Dictionary>().unwrap();
+        assert_eq!(Some(i64::MAX), dict.get("big").and_then(|v| v.to::<i64>()));
+        assert_eq!(Some(0.1), dict.get("precise").and_then(|v| v.to::<f64>()));
+        assert_eq!(
+            "/root/Player:position:x",
+            dict.get("path").unwrap().to::<NodePath>().unwrap().to_string(),
+        );
+    }
+
+    test_variant_bytes_errors {
+        assert_eq!(Err(BytesError::UnexpectedEnd), Variant::from_bytes(&[2, 0, 0]));
+        assert_eq!(Err(BytesError::UnexpectedEnd), Variant::from_bytes(&[2, 0, 0, 0, 1]));
+        assert_eq!(Err(BytesError::InvalidType(99)), Variant::from_bytes(&[99, 0, 0, 0]));
+        assert_eq!(
+            Err(BytesError::UnexpectedEnd),
+            Variant::from_bytes(&[19, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]),
+        );
+        assert_eq!(
+            Err(BytesError::ObjectsNotAllowed),
+            Variant::from_bytes(&[17, 0, 0, 0, 0, 0, 0, 0]),
+        );
+        assert!(Variant::from_bytes_with_objects(&[17, 0, 0, 0, 0, 0, 0, 0])
+            .unwrap()
+            .is_nil());
+    }
+);
@@ -0,0 +1,252 @@
+//! Conversions between `Variant` trees and `serde_json::Value`.
+
+use super::*;
+use serde_json::{Map, Number, Value};
+
+/// How values without a JSON equivalent are converted by [`Variant::to_json_value_with`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
+pub enum NonJsonValue {
+    /// Fail the conversion with a [`ToJsonError`].
+    #[default]
+    Error,
+    /// Replace the value with `null`.
+    Null,
+    /// Replace the value with its string representation, as returned by `str()` in GDScript.
+    String,
+}
+
+/// Options for converting a `Variant` into a `serde_json::Value`.
+///
+/// By default, conversion fails when encountering objects, RIDs or non-finite floats.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct JsonOptions {
+    objects: NonJsonValue,
+    rids: NonJsonValue,
+    non_finite_floats: NonJsonValue,
+}
+
+impl JsonOptions {
+    /// Creates the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how `Object` values are converted.
+    #[inline]
+    pub fn with_objects(mut self, handling: NonJsonValue) -> Self {
+        self.objects = handling;
+        self
+    }
+
+    /// Sets how `Rid` values are converted.
+    #[inline]
+    pub fn with_rids(mut self, handling: NonJsonValue) -> Self {
+        self.rids = handling;
+        self
+    }
+
+    /// Sets how `NaN` and infinite `F64` values are converted.
+    ///
+    /// Non-finite floats nested in math types (vectors, colors, etc.) are always converted to `null`.
+    #[inline]
+    pub fn with_non_finite_floats(mut self, handling: NonJsonValue) -> Self {
+        self.non_finite_floats = handling;
+        self
+    }
+}
+
+/// Error type returned by [`Variant::to_json_value`].
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum ToJsonError {
+    /// Variant type has no JSON equivalent.
+    UnsupportedType(VariantType),
+    /// `F64` value is `NaN` or infinite.
+    NonFiniteFloat(f64),
+    /// A custom error message.
+    Custom(String),
+}
+
+impl fmt::Display for ToJsonError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ToJsonError::UnsupportedType(ty) => {
+                write!(f, "variant type {ty:?} cannot be converted to JSON")
+            }
+            ToJsonError::NonFiniteFloat(v) => {
+                write!(f, "non-finite float {v} cannot be converted to JSON")
+            }
+            ToJsonError::Custom(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl std::error::Error for ToJsonError {}
+
+impl Variant {
+    /// Converts this variant into a `serde_json::Value` with the default [`JsonOptions`].
+    ///
+    /// `Nil`, `Bool`, numbers, strings, arrays and dictionaries are mapped to their JSON
+    /// counterparts. `NodePath`s become strings, pool arrays become arrays, and math types
+    /// use the same representation as their `Serialize` implementations, e.g. `{"x":1.0,"y":2.0}`
+    /// for `Vector2`. Dictionary keys that are not strings are converted with `str()`, like
+    /// `JSON.print` in GDScript.
+    ///
+    /// # Errors
+    ///
+    /// If the tree contains objects, RIDs or non-finite floats.
+    #[inline]
+    pub fn to_json_value(&self) -> Result<Value, ToJsonError> {
+        self.to_json_value_with(&JsonOptions::default())
+    }
+
+    /// Converts this variant into a `serde_json::Value`, handling values without a JSON
+    /// equivalent according to `options`. See [`Variant::to_json_value`] for the mapping.
+    ///
+    /// # Errors
+    ///
+    /// If the tree contains a value without a JSON equivalent that `options` doesn't allow.
+    #[inline]
+    pub fn to_json_value_with(&self, options: &JsonOptions) -> Result<Value, ToJsonError> {
+        use VariantDispatch as D;
+
+        let non_json = |handling: NonJsonValue, err: ToJsonError| match handling {
+            NonJsonValue::Error => Err(err),
+            NonJsonValue::Null => Ok(Value::Null),
+            NonJsonValue::String => Ok(Value::String(self.to_string())),
+        };
+
+        match self.dispatch() {
+            D::Nil => Ok(Value::Null),
+            D::Bool(v) => Ok(Value::Bool(v)),
+            D::I64(v) => Ok(Value::Number(v.into())),
+            D::F64(v) => match Number::from_f64(v) {
+                Some(n) => Ok(Value::Number(n)),
+                None => non_json(options.non_finite_floats, ToJsonError::NonFiniteFloat(v)),
+            },
+            D::GodotString(v) => Ok(Value::String(v.to_string())),
+            D::NodePath(v) => Ok(Value::String(v.to_string())),
+            D::Rid(_) => non_json(options.rids, ToJsonError::UnsupportedType(VariantType::Rid)),
+            D::Object(_) => non_json(
+                options.objects,
+                ToJsonError::UnsupportedType(VariantType::Object),
+            ),
+            D::Dictionary(dict) => dict.to_json_value_with(options),
+            D::VariantArray(arr) => arr.to_json_value_with(options),
+            D::Vector2(v) => to_value(&v),
+            D::Rect2(v) => to_value(&v),
+            D::Vector3(v) => to_value(&v),
+            D::Transform2D(v) => to_value(&v),
+            D::Plane(v) => to_value(&v),
+            D::Quat(v) => to_value(&v),
+            D::Aabb(v) => to_value(&v),
+            D::Basis(v) => to_value(&v),
+            D::Transform(v) => to_value(&v),
+            D::Color(v) => to_value(&v),
+            D::ByteArray(v) => to_value(&v),
+            D::Int32Array(v) => to_value(&v),
+            D::Float32Array(v) => to_value(&v),
+            D::StringArray(v) => to_value(&v),
+            D::Vector2Array(v) => to_value(&v),
+            D::Vector3Array(v) => to_value(&v),
+            D::ColorArray(v) => to_value(&v),
+        }
+    }
+
+    /// Converts a `serde_json::Value` into a variant.
+    ///
+    /// Integers that fit in an `i64` become `I64`, and all other numbers become `F64`. Arrays and
+    /// objects become `VariantArray` and `Dictionary` respectively, with `GodotString` keys.
+    #[inline]
+    pub fn from_json_value(value: &Value) -> Self {
+        match value {
+            Value::Null => Variant::nil(),
+            Value::Bool(v) => v.to_variant(),
+            Value::Number(n) => match n.as_i64() {
+                Some(v) => v.to_variant(),
+                None => n.as_f64().unwrap_or(f64::NAN).to_variant(),
+            },
+            Value::String(s) => s.to_variant(),
+            Value::Array(values) => VariantArray::from_json_values(values).owned_to_variant(),
+            Value::Object(map) => Dictionary::from_json_map(map).owned_to_variant(),
+        }
+    }
+}
+
+impl<Own: Ownership> Dictionary<Own> {
+    /// Converts this dictionary into a JSON object with the default [`JsonOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Variant::to_json_value`].
+    #[inline]
+    pub fn to_json_value(&self) -> Result<Value, ToJsonError> {
+        self.to_json_value_with(&JsonOptions::default())
+    }
+
+    /// Converts this dictionary into a JSON object, handling values without a JSON equivalent
+    /// according to `options`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Variant::to_json_value_with`].
+    #[inline]
+    pub fn to_json_value_with(&self, options: &JsonOptions) -> Result<Value, ToJsonError> {
+        let mut map = Map::with_capacity(self.len() as usize);
+        for (key, value) in self.iter() {
+            map.insert(key.to_string(), value.to_json_value_with(options)?);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+impl Dictionary<Unique> {
+    /// Creates a dictionary from a JSON object. See [`Variant::from_json_value`].
+    #[inline]
+    pub fn from_json_map(map: &Map<String, Value>) -> Self {
+        map.iter()
+            .map(|(key, value)| (GodotString::from_str(key), Variant::from_json_value(value)))
+            .collect()
+    }
+}
+
+impl<Own: Ownership> VariantArray<Own> {
+    /// Converts this array into a JSON array with the default [`JsonOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Variant::to_json_value`].
+    #[inline]
+    pub fn to_json_value(&self) -> Result<Value, ToJsonError> {
+        self.to_json_value_with(&JsonOptions::default())
+    }
+
+    /// Converts this array into a JSON array, handling values without a JSON equivalent
+    /// according to `options`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Variant::to_json_value_with`].
+    #[inline]
+    pub fn to_json_value_with(&self, options: &JsonOptions) -> Result<Value, ToJsonError> {
+        self.iter()
+            .map(|v| v.to_json_value_with(options))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+}
+
+impl VariantArray<Unique> {
+    /// Creates an array from JSON values. See [`Variant::from_json_value`].
+    #[inline]
+    pub fn from_json_values(values: &[Value]) -> Self {
+        values.iter().map(Variant::from_json_value).collect()
+    }
+}
+
+fn to_value<T: serde::Serialize>(v: &T) -> Result<Value, ToJsonError> {
+    serde_json::to_value(v).map_err(|e| ToJsonError::Custom(e.to_string()))
+}
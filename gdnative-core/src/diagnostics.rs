@@ -0,0 +1,340 @@
+//! Information about the state of script calls, for debugging.
+//!
+//! Once enabled with [`record_calls`], every call of an exported method is recorded on a
+//! per-thread call stack while it runs. This makes it possible to tell how a piece of Rust code
+//! was reached, e.g. when a method is re-entered through a signal emitted by the same instance:
+//!
+//! ```no_run
+//! use gdnative::diagnostics;
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Node)]
+//! struct Player;
+//!
+//! #[methods]
+//! impl Player {
+//!     fn new(_base: &Node) -> Self {
+//!         Player
+//!     }
+//!
+//!     #[method]
+//!     fn take_damage(&mut self) {
+//!         for frame in diagnostics::call_stack() {
+//!             godot_print!("{}", frame);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! While recording, the call stack is also logged when an exported method panics, or fails to
+//! borrow its instance. Recording is off by default, since it costs a thread-local access on
+//! every call.
+//!
+//! To find calls that are responsible for frame hitches, a [`Watchdog`] can be installed to
+//! report calls that take longer than a threshold.
+//!
+//! The live instances of each class can be recorded with [`track_instances`], e.g. to find
+//! instances that are leaked.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use crate::log::Site;
+
+mod instances;
+mod watchdog;
+
+pub(crate) use instances::shutdown as shutdown_instances;
+pub(crate) use instances::{created as instance_created, destroyed as instance_destroyed};
+pub use instances::{is_tracking_instances, live_instances, track_instances};
+pub(crate) use watchdog::shutdown as shutdown_watchdog;
+pub use watchdog::{SlowCall, Watchdog};
+
+/// Whether calls are recorded on the call stack. Checked before touching the thread-local call
+/// stack, so that calls are not slowed down when recording is off.
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CALL_STACK: RefCell<Vec<RawFrame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts or stops recording the calls of exported methods, for [`call_stack`] and
+/// [`call_depth`].
+///
+/// Only calls that start while recording is enabled are recorded, so it should be enabled
+/// early, e.g. in the init callback. The [`Watchdog`] measures calls regardless of this setting.
+#[inline]
+pub fn record_calls(enabled: bool) {
+    RECORDING.store(enabled, Ordering::Release);
+}
+
+/// Returns whether calls are recorded, see [`record_calls`].
+#[inline]
+pub fn is_recording_calls() -> bool {
+    RECORDING.load(Ordering::Acquire)
+}
+
+/// A frame of the call stack, as recorded during the call. Names are only looked up when the
+/// call stack is inspected.
+#[derive(Clone, Copy)]
+struct RawFrame {
+    class: fn() -> Cow<'static, str>,
+    /// Borrowed from the method data, which outlives the call. `None` for stateless methods,
+    /// which have no method data to store their name in.
+    method: Option<*const str>,
+    site: Option<Site<'static>>,
+}
+
+impl RawFrame {
+    fn resolve(&self) -> CallFrame {
+        let method = match self.method {
+            // SAFETY: frames are removed before the call they belong to returns.
+            Some(method) => unsafe { &*method }.to_owned(),
+            None => self.function_name(),
+        };
+
+        CallFrame {
+            class: (self.class)(),
+            method,
+            site: self.site,
+        }
+    }
+
+    /// Returns the name of the Rust function from the site, e.g. `take_damage` for a site
+    /// created with `godot_site!(Player::take_damage)`.
+    fn function_name(&self) -> String {
+        let func = self.site.map(|site| site.func().to_string_lossy());
+        match func.as_deref().and_then(|func| func.rsplit("::").next()) {
+            Some(name) if !name.trim().is_empty() => name.trim().to_owned(),
+            _ => "<unknown>".to_owned(),
+        }
+    }
+}
+
+/// A call of an exported method, as returned by [`call_stack`].
+#[derive(Clone, Debug)]
+pub struct CallFrame {
+    class: Cow<'static, str>,
+    method: String,
+    site: Option<Site<'static>>,
+}
+
+impl CallFrame {
+    /// Returns the name of the class the method was called on.
+    #[inline]
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    /// Returns the name the method is registered under.
+    ///
+    /// Stateless methods, such as the ones exported with `#[method]`, don't store the name they
+    /// are registered under. The name of the Rust function from their [site](Self::site) is
+    /// returned instead, which differs if the method is renamed.
+    #[inline]
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the site where the method is defined, if known. This is always the case for
+    /// methods exported with `#[method]`.
+    #[inline]
+    pub fn site(&self) -> Option<Site<'static>> {
+        self.site
+    }
+}
+
+impl Display for CallFrame {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.class, self.method)?;
+        if let Some(site) = &self.site {
+            write!(f, " ({site})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the exported methods currently being executed on this thread, starting with the
+/// outermost call.
+///
+/// Only calls that go through the engine, or through C exports, are recorded. Rust code calling
+/// other Rust methods directly doesn't create new frames. The call stack is always empty unless
+/// [recording](record_calls) is enabled.
+#[inline]
+pub fn call_stack() -> Vec<CallFrame> {
+    CALL_STACK.with(|stack| stack.borrow().iter().map(RawFrame::resolve).collect())
+}
+
+/// Returns the number of exported methods currently being executed on this thread.
+///
+/// A depth greater than 1 means that an exported method has been re-entered, possibly through a
+/// signal or a call from GDScript.
+#[inline]
+pub fn call_depth() -> usize {
+    CALL_STACK.with(|stack| stack.borrow().len())
+}
+
+/// Guard that removes a frame from the call stack when dropped.
+pub(crate) struct FrameGuard {
+    /// Whether the frame was pushed onto the call stack.
+    recorded: bool,
+    /// The frame and the start of the call, if it's measured by the watchdog.
+    watched: Option<(Instant, RawFrame)>,
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        if self.recorded {
+            CALL_STACK.with(|stack| stack.borrow_mut().pop());
+        }
+        if let Some((start, frame)) = self.watched {
+            watchdog::finish(start, || frame.resolve());
+        }
+    }
+}
+
+/// Pushes a frame onto the call stack of this thread if calls are recorded, which is removed
+/// when the returned guard is dropped. `method` must outlive the guard.
+pub(crate) fn enter(
+    class: fn() -> Cow<'static, str>,
+    method: Option<&str>,
+    site: Option<Site<'static>>,
+) -> FrameGuard {
+    let recorded = RECORDING.load(Ordering::Acquire);
+    let start = watchdog::start();
+    if !recorded && start.is_none() {
+        return FrameGuard {
+            recorded,
+            watched: None,
+        };
+    }
+
+    let frame = RawFrame {
+        class,
+        method: method.map(|method| method as *const str),
+        site,
+    };
+    if recorded {
+        CALL_STACK.with(|stack| stack.borrow_mut().push(frame));
+    }
+
+    FrameGuard {
+        recorded,
+        watched: start.map(|start| (start, frame)),
+    }
+}
+
+/// Logs the call stack of this thread to the Godot console, if it is not empty.
+pub(crate) fn log_call_stack() {
+    let stack = call_stack();
+    if !stack.is_empty() {
+        godot_error!(
+            "gdnative-core: script call stack:\n{}",
+            format_stack(&stack)
+        );
+    }
+}
+
+/// Formats a call stack with the most recent call first, like a backtrace.
+pub(crate) fn format_stack(stack: &[CallFrame]) -> String {
+    stack
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, frame)| format!("  {i}: {frame}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_a() -> Cow<'static, str> {
+        Cow::Borrowed("A")
+    }
+
+    fn class_b() -> Cow<'static, str> {
+        Cow::Borrowed("B")
+    }
+
+    #[test]
+    fn frames_are_nested() {
+        record_calls(true);
+        assert_eq!(0, call_depth());
+
+        let outer_name = String::from("outer");
+        let outer = enter(class_a, Some(&outer_name), None);
+        {
+            let _inner = enter(class_b, Some("inner"), None);
+            assert_eq!(2, call_depth());
+
+            let stack = call_stack();
+            assert_eq!("A", stack[0].class());
+            assert_eq!("outer", stack[0].method());
+            assert_eq!("B::inner", stack[1].to_string());
+            assert_eq!("  0: B::inner\n  1: A::outer", format_stack(&stack));
+        }
+
+        assert_eq!(1, call_depth());
+        drop(outer);
+        assert!(call_stack().is_empty());
+    }
+
+    #[test]
+    fn frames_are_removed_on_panic() {
+        record_calls(true);
+        let result = std::panic::catch_unwind(|| {
+            let _frame = enter(class_a, Some("panicking"), None);
+            panic!("expected panic");
+        });
+
+        assert!(result.is_err());
+        assert_eq!(0, call_depth());
+    }
+
+    #[test]
+    fn stateless_frames_are_named_after_their_site() {
+        record_calls(true);
+
+        let _frame = enter(class_a, None, Some(crate::godot_site!(A::stateless)));
+        assert_eq!("stateless", call_stack()[0].method());
+
+        let _frame = enter(class_b, None, None);
+        assert_eq!("<unknown>", call_stack()[1].method());
+    }
+
+    #[test]
+    fn watchdog_reports_slow_calls() {
+        use parking_lot::Mutex;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&calls);
+        Watchdog::new(Duration::from_millis(10))
+            .on_slow_call(move |call| sink.lock().push(call.to_string()))
+            .install();
+        assert!(Watchdog::is_installed());
+
+        {
+            let _frame = enter(class_a, Some("slow"), None);
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        {
+            let _frame = enter(class_b, Some("fast"), None);
+        }
+
+        assert!(Watchdog::uninstall());
+        assert!(!Watchdog::uninstall());
+
+        let calls = calls.lock();
+        assert!(calls.iter().any(|call| call.starts_with("A::slow took ")));
+        assert!(!calls.iter().any(|call| call.starts_with("B::fast")));
+    }
+}
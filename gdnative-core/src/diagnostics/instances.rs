@@ -0,0 +1,81 @@
+use std::any::TypeId;
+use std::collections::{BTreeSet, HashMap};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::export::NativeClass;
+use crate::object::{InstanceId, RawObject};
+use crate::private::ManuallyManagedClassPlaceholder;
+
+/// Whether instances are tracked. Checked before calling into the engine for the instance ID, so
+/// that constructors and destructors are not slowed down when tracking is off.
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+/// Live instances, keyed by the type of their class.
+static LIVE: Lazy<Mutex<HashMap<TypeId, BTreeSet<InstanceId>>>> = Lazy::new(Mutex::default);
+
+/// Starts or stops recording the instances of all classes that are created and destroyed,
+/// for [`live_instances`].
+///
+/// Only instances created while tracking is enabled are recorded, so it should be enabled
+/// early, e.g. in the init callback. Disabling it forgets all recorded instances.
+///
+/// While tracking is enabled, the engine is queried for the instance ID on every construction
+/// and destruction of an instance, and a global lock is taken.
+#[inline]
+pub fn track_instances(enabled: bool) {
+    TRACKING.store(enabled, Ordering::Release);
+    if !enabled {
+        LIVE.lock().clear();
+    }
+}
+
+/// Returns whether instances are tracked, see [`track_instances`].
+#[inline]
+pub fn is_tracking_instances() -> bool {
+    TRACKING.load(Ordering::Acquire)
+}
+
+/// Returns the instance IDs of the base objects of the live instances of `C`, ordered by ID.
+/// Only instances created while [tracking](track_instances) is enabled are returned.
+///
+/// Instances are removed once their script instance is destroyed. The base objects of returned
+/// IDs may have been freed on other threads in the meantime.
+#[inline]
+pub fn live_instances<C: NativeClass>() -> Vec<InstanceId> {
+    LIVE.lock()
+        .get(&TypeId::of::<C>())
+        .map(|ids| ids.iter().copied().collect())
+        .unwrap_or_default()
+}
+
+/// Records a new instance of `C`. Called by the constructor of the script instance.
+pub(crate) fn created<C: NativeClass>(owner: &RawObject<C::Base>) {
+    if !TRACKING.load(Ordering::Acquire) {
+        return;
+    }
+
+    let id = owner.instance_id();
+    LIVE.lock().entry(TypeId::of::<C>()).or_default().insert(id);
+}
+
+/// Forgets an instance of `C`. Called by the destructor of the script instance.
+pub(crate) unsafe fn destroyed<C: NativeClass>(this: NonNull<crate::sys::godot_object>) {
+    if !TRACKING.load(Ordering::Acquire) {
+        return;
+    }
+
+    let id =
+        RawObject::<ManuallyManagedClassPlaceholder>::from_sys_ref_unchecked(this).instance_id();
+    if let Some(ids) = LIVE.lock().get_mut(&TypeId::of::<C>()) {
+        ids.remove(&id);
+    }
+}
+
+/// Stops tracking instances. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    track_instances(false);
+}
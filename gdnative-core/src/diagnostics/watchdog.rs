@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+
+use super::CallFrame;
+use crate::init::{register_frame_hook, FrameHook, Phase};
+
+/// Whether a watchdog is installed. Checked before reading the clock, so that calls are not
+/// slowed down when the watchdog is off.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static STATE: Lazy<RwLock<Option<State>>> = Lazy::new(RwLock::default);
+static PENDING: Lazy<Mutex<Vec<SlowCall>>> = Lazy::new(Mutex::default);
+
+type Handler = Arc<dyn Fn(&SlowCall) + Send + Sync>;
+
+struct State {
+    threshold: Duration,
+    handler: Option<Handler>,
+    report: Option<FrameHook>,
+}
+
+/// Opt-in watchdog that reports exported method calls taking longer than a threshold.
+///
+/// The watchdog is cooperative: it doesn't interrupt slow calls, but measures every call that
+/// goes through the engine once it returns. This makes it possible to find the Rust code
+/// responsible for frame hitches without attaching a profiler:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use gdnative::diagnostics::Watchdog;
+///
+/// // Somewhere in the init callback
+/// Watchdog::new(Duration::from_millis(4))
+///     .frame_report(true)
+///     .install();
+/// ```
+///
+/// By default, each slow call is logged as a warning as soon as it returns, with the name of
+/// the method and the site where it's defined. Nested calls are measured separately, so a
+/// slow call is also counted in all the calls it's nested in.
+///
+/// When the watchdog is not installed and calls aren't [recorded](super::record_calls), the
+/// overhead of each call is two atomic loads.
+#[derive(Clone)]
+pub struct Watchdog {
+    threshold: Duration,
+    handler: Option<Handler>,
+    frame_report: bool,
+}
+
+impl Watchdog {
+    /// Creates a watchdog that reports calls taking longer than `threshold`.
+    #[inline]
+    pub fn new(threshold: Duration) -> Self {
+        Watchdog {
+            threshold,
+            handler: None,
+            frame_report: false,
+        }
+    }
+
+    /// Instead of logging each slow call, logs a summary of the slow calls at the end of each
+    /// frame, grouped by method. The report is produced by a
+    /// [frame hook](crate::init::register_frame_hook) that runs after `_process`, so it's only
+    /// available if frame hooks are.
+    #[inline]
+    pub fn frame_report(mut self, enabled: bool) -> Self {
+        self.frame_report = enabled;
+        self
+    }
+
+    /// Calls `handler` with each slow call instead of logging it. If a frame report is
+    /// enabled, the call is included in it as well.
+    ///
+    /// The handler is called on the thread that made the call, right after it returns. Panics
+    /// in the handler are caught and logged.
+    #[inline]
+    pub fn on_slow_call<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&SlowCall) + Send + Sync + 'static,
+    {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Starts measuring calls, replacing any watchdog installed before.
+    #[inline]
+    pub fn install(self) {
+        let report = self
+            .frame_report
+            .then(|| register_frame_hook(Phase::PostProcess, |_| log_frame_report()));
+
+        let previous = STATE.write().replace(State {
+            threshold: self.threshold,
+            handler: self.handler,
+            report,
+        });
+        INSTALLED.store(true, Ordering::Release);
+
+        if let Some(hook) = previous.and_then(|state| state.report) {
+            hook.remove();
+        }
+    }
+
+    /// Stops measuring calls. Slow calls that haven't been included in a frame report yet are
+    /// discarded. Returns `false` if no watchdog was installed.
+    #[inline]
+    pub fn uninstall() -> bool {
+        INSTALLED.store(false, Ordering::Release);
+        let previous = STATE.write().take();
+        PENDING.lock().clear();
+
+        match previous {
+            Some(state) => {
+                if let Some(hook) = state.report {
+                    hook.remove();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether a watchdog is installed.
+    #[inline]
+    pub fn is_installed() -> bool {
+        INSTALLED.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("threshold", &self.threshold)
+            .field("handler", &self.handler.as_ref().map(|_| ".."))
+            .field("frame_report", &self.frame_report)
+            .finish()
+    }
+}
+
+/// An exported method call that took longer than the threshold of the [`Watchdog`].
+#[derive(Clone, Debug)]
+pub struct SlowCall {
+    frame: CallFrame,
+    duration: Duration,
+}
+
+impl SlowCall {
+    /// Returns the method that was called.
+    #[inline]
+    pub fn frame(&self) -> &CallFrame {
+        &self.frame
+    }
+
+    /// Returns how long the call took.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl Display for SlowCall {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} took {}", self.frame, Millis(self.duration))
+    }
+}
+
+struct Millis(Duration);
+
+impl Display for Millis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3} ms", self.0.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Returns the start time of a call, if a watchdog is installed.
+pub(super) fn start() -> Option<Instant> {
+    INSTALLED.load(Ordering::Acquire).then(Instant::now)
+}
+
+/// Reports the call that started at `start`, if it took too long. `frame` is only called for
+/// slow calls.
+pub(super) fn finish(start: Instant, frame: impl FnOnce() -> CallFrame) {
+    let duration = start.elapsed();
+
+    let (handler, report) = {
+        let state = STATE.read();
+        match &*state {
+            Some(state) if duration > state.threshold => {
+                (state.handler.clone(), state.report.is_some())
+            }
+            _ => return,
+        }
+    };
+
+    let call = SlowCall {
+        frame: frame(),
+        duration,
+    };
+
+    match &handler {
+        Some(handler) => {
+            if let Err(err) = catch_unwind(AssertUnwindSafe(|| handler(&call))) {
+                godot_error!("gdnative-core: slow call handler of the watchdog panicked");
+                crate::private::print_panic_error(err);
+            }
+        }
+        None if !report => crate::log::warn(
+            call.frame.site().unwrap_or_default(),
+            format_args!("gdnative-core: slow call: {call}"),
+        ),
+        None => {}
+    }
+
+    if report {
+        PENDING.lock().push(call);
+    }
+}
+
+/// Slow calls of the same method in a frame report.
+struct Group<'a> {
+    frame: &'a CallFrame,
+    count: usize,
+    total: Duration,
+    max: Duration,
+}
+
+/// Logs the slow calls since the last report, grouped by method and sorted by total time.
+fn log_frame_report() {
+    let calls = std::mem::take(&mut *PENDING.lock());
+    if calls.is_empty() {
+        return;
+    }
+
+    let mut groups: HashMap<(&str, &str), Group<'_>> = HashMap::new();
+    for call in &calls {
+        let group = groups
+            .entry((call.frame.class(), call.frame.method()))
+            .or_insert_with(|| Group {
+                frame: &call.frame,
+                count: 0,
+                total: Duration::ZERO,
+                max: Duration::ZERO,
+            });
+        group.count += 1;
+        group.total += call.duration;
+        group.max = group.max.max(call.duration);
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.total));
+
+    let lines = groups
+        .iter()
+        .map(|group| {
+            format!(
+                "  {}: {} call(s), {} in total, {} max",
+                group.frame,
+                group.count,
+                Millis(group.total),
+                Millis(group.max),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    godot_warn!(
+        "gdnative-core: {} slow call(s) since the last frame:\n{lines}",
+        calls.len(),
+    );
+}
+
+/// Uninstalls the watchdog. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    Watchdog::uninstall();
+}
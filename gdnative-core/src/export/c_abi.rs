@@ -0,0 +1,260 @@
+//! C ABI for driving registered classes from outside the engine's scripting layer.
+//!
+//! This module is only available with the `c-abi` feature. It exports a small set of
+//! `extern "C"` functions from the library, which other language runtimes embedded in the same
+//! process, or native test harnesses, can use to list the registered classes, create instances
+//! and call their methods with `Variant`s:
+//!
+//! ```c
+//! uint32_t gdnative_plugin_abi_version(void);
+//! size_t gdnative_plugin_class_count(void);
+//! ptrdiff_t gdnative_plugin_class_name(size_t index, char *buf, size_t len);
+//! ptrdiff_t gdnative_plugin_method_count(const char *class_name);
+//! ptrdiff_t gdnative_plugin_method_name(const char *class_name, size_t index,
+//!                                       char *buf, size_t len);
+//! int32_t gdnative_plugin_instantiate(const char *class_name, godot_variant *out);
+//! int32_t gdnative_plugin_call(godot_variant *object, const char *method,
+//!                              const godot_variant *args, size_t num_args, godot_variant *out);
+//! void gdnative_plugin_variant_destroy(godot_variant *variant);
+//! ```
+//!
+//! Names are copied into `buf` as nul-terminated UTF-8, truncated to fit `len` bytes. The
+//! functions return the full length of the name without the terminator, so a caller can retry
+//! with a larger buffer, or `-1` if the class or index is unknown. Status codes are the
+//! discriminants of [`Status`].
+//!
+//! The functions may only be called from the main thread, after the library has been
+//! initialized by the engine. Variants written to `out` are owned by the caller, and must be
+//! destroyed with `gdnative_plugin_variant_destroy`. Breaking changes to this interface
+//! increment [`ABI_VERSION`].
+
+use std::ffi::CStr;
+use std::panic::{catch_unwind, UnwindSafe};
+use std::ptr;
+
+use crate::core_types::Variant;
+use crate::export::class_db::{class_db, InstantiateError};
+use crate::private::is_api_bound;
+use crate::sys;
+
+/// Version of the interface, as returned by `gdnative_plugin_abi_version`.
+pub const ABI_VERSION: u32 = 1;
+
+/// Status codes returned by the functions that create or call objects.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The operation succeeded, and `out` was written to.
+    Ok = 0,
+    /// The library hasn't been initialized by the engine, or has already been terminated.
+    NotInitialized = 1,
+    /// A pointer argument was null, or a string was not valid UTF-8.
+    InvalidArgument = 2,
+    /// No class is registered under the name.
+    UnknownClass = 3,
+    /// The class has no constructor, or the constructor failed.
+    ConstructorFailed = 4,
+    /// The method doesn't exist, or was called with the wrong arguments.
+    CallFailed = 5,
+    /// The operation panicked. The panic is reported to the Godot log.
+    Panicked = 6,
+}
+
+/// Returns [`ABI_VERSION`].
+#[no_mangle]
+pub extern "C" fn gdnative_plugin_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Returns the number of registered classes.
+#[no_mangle]
+pub extern "C" fn gdnative_plugin_class_count() -> usize {
+    guard(0, || class_db().class_names().len())
+}
+
+/// Copies the name of the registered class at `index` into `buf`. Classes are sorted by name.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_class_name(
+    index: usize,
+    buf: *mut libc::c_char,
+    len: usize,
+) -> isize {
+    guard(-1, || match class_db().class_names().get(index) {
+        Some(name) => copy_name(name, buf, len),
+        None => -1,
+    })
+}
+
+/// Returns the number of methods of the class registered under `class_name`, or `-1` if there
+/// is none.
+///
+/// # Safety
+///
+/// `class_name` must be a valid pointer to a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_method_count(class_name: *const libc::c_char) -> isize {
+    guard(-1, || {
+        to_str(class_name)
+            .and_then(|name| class_db().class(name))
+            .map_or(-1, |class| class.methods.len() as isize)
+    })
+}
+
+/// Copies the name of the method at `index` of the class registered under `class_name` into
+/// `buf`. Methods are in registration order.
+///
+/// # Safety
+///
+/// `class_name` must be a valid pointer to a nul-terminated string. `buf` must be valid for
+/// writes of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_method_name(
+    class_name: *const libc::c_char,
+    index: usize,
+    buf: *mut libc::c_char,
+    len: usize,
+) -> isize {
+    guard(-1, || {
+        let class = to_str(class_name).and_then(|name| class_db().class(name));
+        match class.as_ref().and_then(|class| class.methods.get(index)) {
+            Some(name) => copy_name(name, buf, len),
+            None => -1,
+        }
+    })
+}
+
+/// Creates an instance of the class registered under `class_name`, and writes its base object
+/// to `out`. See [`ClassDb::instantiate`](crate::export::ClassDb::instantiate).
+///
+/// # Safety
+///
+/// `class_name` must be a valid pointer to a nul-terminated string. `out` must be valid for
+/// writes of a `godot_variant`.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_instantiate(
+    class_name: *const libc::c_char,
+    out: *mut sys::godot_variant,
+) -> Status {
+    if !is_api_bound() {
+        return Status::NotInitialized;
+    }
+
+    guard(Status::Panicked, || {
+        let Some(name) = to_str(class_name) else {
+            return Status::InvalidArgument;
+        };
+        if out.is_null() {
+            return Status::InvalidArgument;
+        }
+
+        match class_db().instantiate(name) {
+            Ok(variant) => {
+                ptr::write(out, variant.leak());
+                Status::Ok
+            }
+            Err(InstantiateError::UnknownClass(_)) => Status::UnknownClass,
+            Err(_) => Status::ConstructorFailed,
+        }
+    })
+}
+
+/// Calls `method` on the object held by `object` with `num_args` arguments from `args`, and
+/// writes the return value to `out`.
+///
+/// # Safety
+///
+/// `object` must be a valid pointer to an initialized `godot_variant`. `method` must be a valid
+/// pointer to a nul-terminated string. `args` must be valid for reads of `num_args` initialized
+/// `godot_variant`s, or null if `num_args` is zero. `out` must be valid for writes of a
+/// `godot_variant`.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_call(
+    object: *mut sys::godot_variant,
+    method: *const libc::c_char,
+    args: *const sys::godot_variant,
+    num_args: usize,
+    out: *mut sys::godot_variant,
+) -> Status {
+    if !is_api_bound() {
+        return Status::NotInitialized;
+    }
+
+    guard(Status::Panicked, || {
+        let Some(method) = to_str(method) else {
+            return Status::InvalidArgument;
+        };
+        if object.is_null() || out.is_null() || (args.is_null() && num_args > 0) {
+            return Status::InvalidArgument;
+        }
+
+        let object = Variant::cast_mut_ref(object);
+        let args: &[Variant] = if num_args == 0 {
+            &[]
+        } else {
+            // Same layout assumption as `Variant::cast_ref`.
+            std::slice::from_raw_parts(args as *const Variant, num_args)
+        };
+
+        match object.call(method, args) {
+            Ok(ret) => {
+                ptr::write(out, ret.leak());
+                Status::Ok
+            }
+            Err(_) => Status::CallFailed,
+        }
+    })
+}
+
+/// Destroys a variant written by the other functions.
+///
+/// # Safety
+///
+/// `variant` must be a valid pointer to an initialized `godot_variant`, or null. It must not be
+/// used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn gdnative_plugin_variant_destroy(variant: *mut sys::godot_variant) {
+    if variant.is_null() || !is_api_bound() {
+        return;
+    }
+
+    guard((), || drop(Variant::from_sys(ptr::read(variant))));
+}
+
+/// Runs `f`, reporting a panic to the Godot log and returning `default` instead of unwinding
+/// into foreign code.
+fn guard<R>(default: R, f: impl FnOnce() -> R + UnwindSafe) -> R {
+    match catch_unwind(f) {
+        Ok(ret) => ret,
+        Err(err) => {
+            if is_api_bound() {
+                godot_error!("gdnative-core: C ABI call panicked");
+                crate::private::print_panic_error(err);
+            }
+            default
+        }
+    }
+}
+
+unsafe fn to_str<'a>(s: *const libc::c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Copies `name` into `buf` with a nul terminator, truncating it to fit, and returns its full
+/// length.
+unsafe fn copy_name(name: &str, buf: *mut libc::c_char, len: usize) -> isize {
+    if !buf.is_null() && len > 0 {
+        let count = name.len().min(len - 1);
+        ptr::copy_nonoverlapping(name.as_ptr(), buf as *mut u8, count);
+        *buf.add(count) = 0;
+    }
+
+    name.len() as isize
+}
@@ -1,7 +1,7 @@
 use crate::export::user_data::UserData;
 use crate::export::{class_registry, ClassBuilder};
 use crate::object::ownership::{Ownership, Shared, Unique};
-use crate::object::{GodotObject, Instance, Instanciable, TRef};
+use crate::object::{GodotObject, Instance, Instanciable, Ref, TRef};
 
 /// Trait used for describing and initializing a Godot script class.
 ///
@@ -64,6 +64,17 @@ pub trait NativeClass: Sized + 'static {
     #[inline]
     fn nativeclass_register_properties(_builder: &ClassBuilder<Self>) {}
 
+    /// Returns whether the class should be registered with the engine at all. This is called
+    /// once during initialization, so runtime information like `OS.has_feature` can be used
+    /// to exclude classes that aren't supported on the current platform. The default
+    /// implementation always returns `true`.
+    ///
+    /// This is implemented by the `#[cfg_godot]` attribute of `#[derive(NativeClass)]`.
+    #[inline]
+    fn nativeclass_should_register() -> bool {
+        true
+    }
+
     /// Convenience method to create an `Instance<Self, Unique>`. This is a new `Self::Base`
     /// with the script attached.
     ///
@@ -95,6 +106,15 @@ pub trait NativeClass: Sized + 'static {
     {
         Instance::emplace(self)
     }
+
+    /// Convenience method to emplace `self` into an `Instance<Self, Unique>`, attaching it to
+    /// an existing `base` object. See [`Instance::emplace_with_base`].
+    ///
+    /// Must be called after the library is initialized.
+    #[inline]
+    fn emplace_with_base(self, base: Ref<Self::Base, Unique>) -> Instance<Self, Unique> {
+        Instance::emplace_with_base(self, base)
+    }
 }
 
 /// A NativeScript "class" that is statically named. [`NativeClass`] types that implement this
@@ -5,9 +5,12 @@ use std::ffi::CString;
 use std::marker::PhantomData;
 use std::ptr;
 
-use crate::core_types::{GodotString, VariantType};
+use crate::core_types::{GodotString, Variant, VariantType};
+use crate::export::property::list::PropertyList;
 use crate::export::*;
-use crate::object::NewRef;
+use crate::init::{ClassReport, Duplicate, RegistrationKind};
+use crate::log::Site;
+use crate::object::{NewRef, TRef};
 use crate::private::get_api;
 
 // TODO(#996): unify string parameters across all buiders
@@ -23,20 +26,53 @@ use crate::private::get_api;
 pub struct ClassBuilder<C> {
     pub(super) init_handle: *mut libc::c_void,
     pub(super) class_name: CString,
+    pub(super) property_list: RefCell<PropertyList<C>>,
     mixins: RefCell<HashSet<TypeId, ahash::RandomState>>,
+    /// Whether registration is only recorded, without calling into the engine.
+    pub(super) dry_run: bool,
+    report: RefCell<ClassReport>,
+    duplicates: RefCell<Vec<Duplicate>>,
     _marker: PhantomData<C>,
 }
 
 impl<C: NativeClass> ClassBuilder<C> {
-    pub(crate) fn new(init_handle: *mut libc::c_void, class_name: CString) -> Self {
+    pub(crate) fn new(
+        init_handle: *mut libc::c_void,
+        class_name: CString,
+        dry_run: bool,
+        report: ClassReport,
+    ) -> Self {
         Self {
             init_handle,
             class_name,
+            property_list: RefCell::default(),
             mixins: RefCell::default(),
+            dry_run,
+            report: RefCell::new(report),
+            duplicates: RefCell::default(),
             _marker: PhantomData,
         }
     }
 
+    /// Records a registered name for the registration report, noting it as a duplicate if
+    /// it was already registered.
+    pub(crate) fn record(&self, kind: RegistrationKind, name: &str, site: Option<Site<'static>>) {
+        let mut report = self.report.borrow_mut();
+        if !report.record(kind, name) {
+            self.duplicates.borrow_mut().push(Duplicate {
+                kind,
+                class: report.name.clone(),
+                name: name.to_owned(),
+                site,
+            });
+        }
+    }
+
+    /// Consumes the builder, returning what was registered with it.
+    pub(crate) fn into_report(self) -> (ClassReport, Vec<Duplicate>) {
+        (self.report.into_inner(), self.duplicates.into_inner())
+    }
+
     /// Returns a `MethodBuilder` which can be used to add a method to the class being
     /// registered.
     ///
@@ -123,6 +159,123 @@ impl<C: NativeClass> ClassBuilder<C> {
         PropertyBuilder::new(self, name)
     }
 
+    /// Exposes the properties in a [`PropertyBag`] through the dynamic property hooks `_get`,
+    /// `_set` and `_get_property_list`. Methods with these names are registered, so the class
+    /// must not define them itself. `_get_property_list` is shared with properties registered
+    /// using [`PropertyBuilder::with_visible_if`].
+    ///
+    /// `get` and `get_mut` return the bag of an instance. This is called automatically by
+    /// `#[derive(NativeClass)]` for fields of type `PropertyBag`.
+    #[inline]
+    pub fn property_bag(&self, get: fn(&C) -> &PropertyBag, get_mut: fn(&mut C) -> &mut PropertyBag)
+    where
+        C::UserData: user_data::Map + user_data::MapMut,
+    {
+        super::property::bag::register(self, get, get_mut);
+    }
+
+    /// Registers properties described by a schema built at runtime, such as one loaded from a
+    /// configuration file. Reads and writes of all these properties are dispatched to `get` and
+    /// `set` respectively, along with the name of the property.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use gdnative::prelude::*;
+    /// use gdnative::export::PropertyDefinition;
+    /// use gdnative::export::hint::{FloatHint, RangeHint};
+    ///
+    /// #[derive(NativeClass)]
+    /// #[inherit(Node)]
+    /// #[register_with(Self::my_register)]
+    /// #[no_constructor]
+    /// struct Tunables {
+    ///     values: HashMap<String, Variant>,
+    /// }
+    ///
+    /// impl Tunables {
+    ///     fn my_register(builder: &ClassBuilder<Tunables>) {
+    ///         // e.g. loaded from JSON
+    ///         let schema = vec![
+    ///             PropertyDefinition::of::<f64>(
+    ///                 "gravity",
+    ///                 Some(FloatHint::Range(RangeHint::new(0.0, 20.0))),
+    ///             )
+    ///             .with_default(Variant::new(9.8)),
+    ///             PropertyDefinition::new("title", ExportInfo::new(VariantType::GodotString)),
+    ///         ];
+    ///
+    ///         builder.properties_from_schema(
+    ///             schema,
+    ///             |this, _base, name| this.values.get(name).cloned().unwrap_or_default(),
+    ///             |this, _base, name, value| {
+    ///                 this.values.insert(name.to_owned(), value);
+    ///             },
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn properties_from_schema<G, S>(
+        &self,
+        schema: impl IntoIterator<Item = PropertyDefinition>,
+        get: G,
+        set: S,
+    ) where
+        C::UserData: user_data::Map + user_data::MapMut,
+        G: Fn(&C, TRef<'_, C::Base>, &str) -> Variant + 'static,
+        S: Fn(&mut C, TRef<'_, C::Base>, &str, Variant) + 'static,
+    {
+        super::property::schema::register(self, schema, get, set);
+    }
+
+    /// Returns a [`StorageBuilder`] for the versioned state of the class, which is saved to
+    /// scenes and resources as a single hidden property, and passed through a migration function
+    /// when loaded from an older version. This is called automatically by
+    /// `#[derive(NativeClass)]` for fields with `#[property(storage)]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gdnative::prelude::*;
+    ///
+    /// #[derive(NativeClass)]
+    /// #[inherit(Node)]
+    /// #[register_with(Self::my_register)]
+    /// #[no_constructor]
+    /// struct Enemy {
+    ///     health: i64,
+    /// }
+    ///
+    /// impl Enemy {
+    ///     fn my_register(builder: &ClassBuilder<Enemy>) {
+    ///         builder
+    ///             .storage(2)
+    ///             .with_migration(Self::migrate)
+    ///             .with_field("health", |this| &this.health, |this| &mut this.health)
+    ///             .done();
+    ///     }
+    ///
+    ///     // Version 1 saved the health as `hp`.
+    ///     fn migrate(from_version: u32, fields: Dictionary<Unique>) -> Dictionary<Unique> {
+    ///         if from_version < 2 {
+    ///             fields.insert("health", fields.get_or_nil("hp"));
+    ///             fields.erase("hp");
+    ///         }
+    ///         fields
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn storage(&self, version: u32) -> StorageBuilder<'_, C>
+    where
+        C::UserData: user_data::Map + user_data::MapMut,
+    {
+        StorageBuilder::new(self, version)
+    }
+
     /// Returns a `SignalBuilder` which can be used to add a signal to the class being
     /// registered.
     ///
@@ -132,6 +285,7 @@ impl<C: NativeClass> ClassBuilder<C> {
     ///
     /// ```
     /// use gdnative::prelude::*;
+    /// use gdnative::export::hint::{FloatHint, RangeHint};
     ///
     /// #[derive(NativeClass)]
     /// #[inherit(Node)]
@@ -159,6 +313,17 @@ impl<C: NativeClass> ClassBuilder<C> {
     ///             .with_param("fuel_spent", VariantType::I64)
     ///             .with_param_default("fuel_type", Variant::new("Kerosene"))
     ///             .done();
+    ///
+    ///         // Add a documented signal with a hinted parameter, meant to be connected once
+    ///         builder
+    ///             .signal("landed")
+    ///             .with_param_export::<f64>(
+    ///                 "speed",
+    ///                 Some(FloatHint::Range(RangeHint::new(0.0, 100.0))),
+    ///             )
+    ///             .with_documentation("Emitted when touching the ground.")
+    ///             .with_default_flags(ConnectFlags::ONESHOT)
+    ///             .done();
     ///     }
     /// }
     /// ```
@@ -167,8 +332,48 @@ impl<C: NativeClass> ClassBuilder<C> {
         SignalBuilder::new(self, GodotString::from(name))
     }
 
+    /// Enables a processing callback on each instance when it is constructed, so that the
+    /// exported `_process` or `_physics_process` method is called without enabling it in
+    /// `_ready`. This is what `#[method(process)]` and `#[method(physics_process)]` register.
+    ///
+    /// Instances whose base class doesn't inherit `Node` are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gdnative::export::Processing;
+    /// use gdnative::prelude::*;
+    ///
+    /// #[derive(NativeClass)]
+    /// #[inherit(Node)]
+    /// #[register_with(Self::register)]
+    /// #[no_constructor]
+    /// struct Spinner;
+    ///
+    /// #[methods]
+    /// impl Spinner {
+    ///     fn register(builder: &ClassBuilder<Self>) {
+    ///         builder.enable_processing(Processing::Physics);
+    ///     }
+    ///
+    ///     #[method]
+    ///     fn _physics_process(&self, _delta: f64) {}
+    /// }
+    /// ```
+    #[inline]
+    pub fn enable_processing(&self, processing: Processing) {
+        if !self.dry_run {
+            crate::export::processing::enable::<C>(processing);
+        }
+    }
+
     #[inline]
     pub(crate) fn add_signal(&self, signal: Signal) {
+        self.record(RegistrationKind::Signal, &signal.name.to_string(), None);
+        if self.dry_run {
+            return;
+        }
+
         unsafe {
             let args_and_hints = signal
                 .args
@@ -202,6 +407,16 @@ impl<C: NativeClass> ClassBuilder<C> {
                     default_args: ptr::null_mut(),
                 },
             );
+
+            if let Some(documentation) = &signal.documentation {
+                let signal_name = CString::new(signal.name.to_string()).unwrap();
+                (get_api().godot_nativescript_set_signal_documentation)(
+                    self.init_handle,
+                    self.class_name.as_ptr(),
+                    signal_name.as_ptr(),
+                    documentation.to_sys(),
+                );
+            }
         }
     }
 
@@ -215,7 +430,27 @@ impl<C: NativeClass> ClassBuilder<C> {
         }
     }
 
+    /// Returns `true` if entries for `_get_property_list` were added.
+    pub(crate) fn uses_property_list(&self) -> bool {
+        !self.property_list.borrow().is_empty()
+    }
+
+    /// Registers `_get_property_list` for the entries added during registration, such as
+    /// conditionally visible properties. Called after all other registration is done.
+    pub(crate) fn register_property_list(&self) {
+        self.property_list.take().register(self);
+    }
+
     pub(crate) fn add_method(&self, method: ScriptMethod) {
+        self.record(RegistrationKind::Method, method.name, method.site);
+        if self.dry_run {
+            if let Some(free_func) = method.free_func {
+                // SAFETY: the method data is never handed to the engine in dry runs.
+                unsafe { free_func(method.method_data) };
+            }
+            return;
+        }
+
         let method_name = CString::new(method.name).unwrap();
 
         let attr = sys::godot_method_attributes {
@@ -267,6 +502,10 @@ impl<C: NativeClass> ClassBuilder<C> {
     #[inline]
     pub fn mixin<M: Mixin<C>>(&self) {
         if self.mixins.borrow_mut().insert(TypeId::of::<M>()) {
+            self.report
+                .borrow_mut()
+                .mixins
+                .push(std::any::type_name::<M>());
             M::register(self);
         }
     }
@@ -280,6 +519,10 @@ pub trait Mixin<C>: crate::private::mixin::Sealed + 'static
 where
     C: NativeClass,
 {
+    /// Per-instance state of the mixin, declared with `#[methods(mixin = "Name", state = "S")]`,
+    /// and accessed through [`MixinState`]. `()` for mixins without state.
+    type State: Default + Send + 'static;
+
     #[doc(hidden)]
     fn register(builder: &ClassBuilder<C>);
 }
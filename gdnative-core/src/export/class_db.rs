@@ -0,0 +1,200 @@
+//! Runtime queries and instantiation of registered classes by name.
+
+use std::fmt;
+use std::ptr::NonNull;
+
+use crate::core_types::{GodotString, Variant, VariantType};
+use crate::export::class_registry;
+use crate::init::{report, ClassReport};
+use crate::object::bounds::MemorySpec;
+use crate::object::ownership::Shared;
+use crate::object::{GodotObject, RawObject, Ref};
+use crate::private::{get_api, ManuallyManagedClassPlaceholder};
+use crate::sys;
+
+/// Returns a handle to the registered Rust classes.
+///
+/// This allows plugin systems and editors to look up and instantiate classes by their names,
+/// as registered with [`InitHandle::add_class`](crate::init::InitHandle::add_class), without
+/// having to know the Rust types:
+///
+/// ```no_run
+/// use gdnative::export::class_db;
+/// use gdnative::prelude::*;
+///
+/// fn spawn(parent: &Node, class_name: &str) -> Result<(), String> {
+///     let node = class_db()
+///         .instantiate_as::<Node>(class_name)
+///         .map_err(|err| err.to_string())?;
+///     parent.add_child(node, false);
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn class_db() -> ClassDb {
+    ClassDb { _private: () }
+}
+
+/// Handle to the registered Rust classes, obtained with [`class_db`].
+#[derive(Copy, Clone, Debug)]
+pub struct ClassDb {
+    _private: (),
+}
+
+impl ClassDb {
+    /// Returns the names of all registered classes, sorted.
+    #[inline]
+    pub fn class_names(&self) -> Vec<String> {
+        class_registry::class_names()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns `true` if a class is registered under `name`.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        class_registry::is_registered(name)
+    }
+
+    /// Returns the base class, methods, properties and signals of the class registered under
+    /// `name`, if any.
+    #[inline]
+    pub fn class(&self, name: &str) -> Option<ClassReport> {
+        if !self.contains(name) {
+            return None;
+        }
+
+        report::registered_class(name)
+    }
+
+    /// Creates an instance of the class registered under `name`, returning the base object as
+    /// a `Variant`.
+    ///
+    /// If the base class is reference-counted, the object is freed once the last reference to it
+    /// is dropped. Otherwise, the caller is responsible for freeing it.
+    #[inline]
+    pub fn instantiate(&self, name: &str) -> Result<Variant, InstantiateError> {
+        let variant = unsafe {
+            crate::object::new_script_object(
+                || class_registry::is_registered(name).then(|| GodotString::from(name)),
+                || {},
+            )
+        }
+        .ok_or_else(|| InstantiateError::UnknownClass(name.to_owned()))?;
+
+        let object = RawObjectRef::from_variant(&variant)
+            .ok_or_else(|| InstantiateError::ConstructorFailed(name.to_owned()))?;
+
+        let script = unsafe { (get_api().godot_nativescript_get_userdata)(object.0.as_ptr()) };
+        if script.is_null() {
+            // Classes without a constructor, or with a panicking one, leave the object without a
+            // script instance.
+            unsafe { object.free_if_manually_managed() };
+            return Err(InstantiateError::ConstructorFailed(name.to_owned()));
+        }
+
+        Ok(variant)
+    }
+
+    /// Creates an instance of the class registered under `name`, returning the base object as a
+    /// `Ref<T>`.
+    ///
+    /// The base object must be an instance of `T`. Since `T` determines how the object is
+    /// managed, reference-counted objects must be requested as a reference-counted class like
+    /// `Reference`, and other objects as a manually-managed class like `Object` or `Node`.
+    /// Otherwise, [`InstantiateError::IncompatibleType`] is returned.
+    #[inline]
+    pub fn instantiate_as<T: GodotObject>(
+        &self,
+        name: &str,
+    ) -> Result<Ref<T, Shared>, InstantiateError> {
+        let variant = self.instantiate(name)?;
+        let object = RawObjectRef::from_variant(&variant)
+            .ok_or_else(|| InstantiateError::ConstructorFailed(name.to_owned()))?;
+
+        let compatible = unsafe {
+            let raw = object.raw();
+            raw.is_class::<T>() && raw.is_class_by_name("Reference") == T::Memory::IS_REF_COUNTED
+        };
+
+        if !compatible {
+            unsafe { object.free_if_manually_managed() };
+            return Err(InstantiateError::IncompatibleType {
+                class: name.to_owned(),
+                expected: T::class_name(),
+            });
+        }
+
+        variant
+            .to_object::<T>()
+            .ok_or_else(|| InstantiateError::IncompatibleType {
+                class: name.to_owned(),
+                expected: T::class_name(),
+            })
+    }
+}
+
+/// Error returned when instantiating a class by name fails.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum InstantiateError {
+    /// No class is registered under the name.
+    UnknownClass(String),
+    /// The class has no constructor, or the constructor failed.
+    ConstructorFailed(String),
+    /// The base object of the class can't be returned as the requested type.
+    IncompatibleType {
+        /// Name of the class.
+        class: String,
+        /// Name of the requested type.
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for InstantiateError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstantiateError::UnknownClass(class) => {
+                write!(f, "no class is registered as `{class}`")
+            }
+            InstantiateError::ConstructorFailed(class) => {
+                write!(f, "`{class}` could not be constructed")
+            }
+            InstantiateError::IncompatibleType { class, expected } => {
+                write!(f, "`{class}` can't be instantiated as `{expected}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstantiateError {}
+
+/// Pointer to an object held by a `Variant`.
+struct RawObjectRef(NonNull<sys::godot_object>);
+
+impl RawObjectRef {
+    fn from_variant(variant: &Variant) -> Option<Self> {
+        if variant.get_type() != VariantType::Object {
+            return None;
+        }
+
+        unsafe {
+            NonNull::new((get_api().godot_variant_as_object)(variant.sys())).map(RawObjectRef)
+        }
+    }
+
+    unsafe fn raw(&self) -> &RawObject<ManuallyManagedClassPlaceholder> {
+        RawObject::from_sys_ref_unchecked(self.0)
+    }
+
+    /// Frees the object, unless it is reference-counted, in which case it is freed when the
+    /// `Variant` holding it is dropped.
+    unsafe fn free_if_manually_managed(&self) {
+        let raw = self.raw();
+        if !raw.is_class_by_name("Reference") {
+            raw.free();
+        }
+    }
+}
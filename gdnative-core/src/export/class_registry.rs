@@ -9,30 +9,61 @@ use parking_lot::RwLock;
 
 use crate::export::NativeClass;
 use crate::init::InitLevel;
+use crate::object::{GodotObject, VirtualMethod};
 
-static CLASS_REGISTRY: Lazy<RwLock<HashMap<TypeId, ClassInfo>>> =
+/// Registered classes. A class can be registered with multiple libraries if the same binary is
+/// loaded by more than one `GDNativeLibrary`, but it always has the same name.
+static CLASS_REGISTRY: Lazy<RwLock<HashMap<TypeId, ClassEntry>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
+/// Identifies a library by the address of its `GDNativeLibrary` object. NativeScript handles
+/// are translated with `private::library_of_handle`.
+type Library = usize;
+
+struct ClassEntry {
+    name: Cow<'static, str>,
+    base: BaseInfo,
+    init_levels: HashMap<Library, InitLevel>,
+}
+
+/// Engine base class of a registered class, as known to the generated bindings.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct BaseInfo {
+    pub name: &'static str,
+    pub virtual_methods: &'static [&'static [VirtualMethod]],
+}
+
+impl ClassEntry {
+    fn info(&self) -> ClassInfo {
+        ClassInfo {
+            name: self.name.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct ClassInfo {
     pub name: Cow<'static, str>,
-    pub init_level: InitLevel,
-}
-
-/// Access the [`ClassInfo`] of the class `C`.
-#[inline]
-pub(crate) fn with_class_info<C: NativeClass, F, R>(f: F) -> Option<R>
-where
-    F: FnOnce(&ClassInfo) -> R,
-{
-    CLASS_REGISTRY.read().get(&TypeId::of::<C>()).map(f)
 }
 
 /// Returns the NativeScript name of the class `C` if it is registered.
 /// Can also be used to validate whether or not `C` has been added using `InitHandle::add_class<C>()`
 #[inline]
 pub(crate) fn class_name<C: NativeClass>() -> Option<Cow<'static, str>> {
-    with_class_info::<C, _, _>(|i| i.name.clone())
+    CLASS_REGISTRY
+        .read()
+        .get(&TypeId::of::<C>())
+        .map(|entry| entry.name.clone())
+}
+
+/// Returns the NativeScript name of the class `C` and the libraries it is registered with, if it
+/// is registered.
+#[inline]
+pub(crate) fn libraries<C: NativeClass>() -> Option<(Cow<'static, str>, Vec<Library>)> {
+    CLASS_REGISTRY.read().get(&TypeId::of::<C>()).map(|entry| {
+        let libraries = entry.init_levels.keys().copied().collect();
+        (entry.name.clone(), libraries)
+    })
 }
 
 /// Returns the NativeScript name of the class `C` if it is registered, or a best-effort description
@@ -45,55 +76,107 @@ pub(crate) fn class_name_or_default<C: NativeClass>() -> Cow<'static, str> {
     class_name::<C>().unwrap_or_else(|| Cow::Borrowed(std::any::type_name::<C>()))
 }
 
-/// Registers the class `C` in the class registry, using a custom name at the given level.
+/// Returns the NativeScript names of all registered classes, sorted.
+#[inline]
+pub(crate) fn class_names() -> Vec<Cow<'static, str>> {
+    let mut list = CLASS_REGISTRY
+        .read()
+        .values()
+        .map(|entry| entry.name.clone())
+        .collect::<Vec<_>>();
+
+    list.sort_unstable();
+    list
+}
+
+/// Returns the distinct base classes of all registered classes, sorted by name.
+#[inline]
+pub(crate) fn base_classes() -> Vec<BaseInfo> {
+    let mut list = CLASS_REGISTRY
+        .read()
+        .values()
+        .map(|entry| entry.base)
+        .collect::<Vec<_>>();
+
+    list.sort_unstable_by_key(|base| base.name);
+    list.dedup_by_key(|base| base.name);
+    list
+}
+
+/// Returns `true` if a class is registered under the NativeScript name `name`.
+#[inline]
+pub(crate) fn is_registered(name: &str) -> bool {
+    CLASS_REGISTRY
+        .read()
+        .values()
+        .any(|entry| entry.name == name)
+}
+
+/// Registers the class `C` with `library` in the class registry, using a custom name at the given
+/// level.
 /// Returns `Ok(true)` if FFI registration needs to be performed. `Ok(false)` if the class has
-/// already been registered on another level.
+/// already been registered with the library on another level.
 /// Returns an error with the old `ClassInfo` if a conflicting entry for `C` was already added.
 #[inline]
 pub(crate) fn register_class_as<C: NativeClass>(
+    library: Library,
     name: Cow<'static, str>,
     init_level: InitLevel,
 ) -> Result<bool, RegisterError> {
     let type_id = TypeId::of::<C>();
     let mut registry = CLASS_REGISTRY.write();
-    match registry.entry(type_id) {
-        Entry::Vacant(entry) => {
-            entry.insert(ClassInfo { name, init_level });
+
+    let entry = match registry.entry(type_id) {
+        Entry::Vacant(entry) => entry.insert(ClassEntry {
+            name,
+            base: BaseInfo {
+                name: C::Base::CLASS_NAME,
+                virtual_methods: C::Base::VIRTUAL_METHODS,
+            },
+            init_levels: HashMap::new(),
+        }),
+        Entry::Occupied(entry) if entry.get().name != name => {
+            return Err(RegisterError {
+                class_info: entry.get().info(),
+                type_name: std::any::type_name::<C>(),
+                kind: RegisterErrorKind::ConflictingName,
+            });
+        }
+        Entry::Occupied(entry) => entry.into_mut(),
+    };
+
+    match entry.init_levels.entry(library) {
+        Entry::Vacant(levels) => {
+            levels.insert(init_level);
             Ok(true)
         }
-        Entry::Occupied(entry) => {
-            let class_info = entry.get();
-            let kind = if class_info.name != name {
-                Some(RegisterErrorKind::ConflictingName)
-            } else if class_info.init_level.intersects(init_level) {
-                Some(RegisterErrorKind::AlreadyOnSameLevel)
-            } else {
-                None
-            };
-
-            if let Some(kind) = kind {
-                Err(RegisterError {
-                    class_info: class_info.clone(),
-                    type_name: std::any::type_name::<C>(),
-                    kind,
-                })
-            } else {
-                Ok(false)
-            }
+        Entry::Occupied(levels) if levels.get().intersects(init_level) => Err(RegisterError {
+            class_info: entry.info(),
+            type_name: std::any::type_name::<C>(),
+            kind: RegisterErrorKind::AlreadyOnSameLevel,
+        }),
+        Entry::Occupied(mut levels) => {
+            levels.insert(*levels.get() | init_level);
+            Ok(false)
         }
     }
 }
 
+/// Returns the names of the classes that are registered with any library on a level in `allow`,
+/// but not on a level in `deny`.
 #[inline]
 #[allow(dead_code)] // Currently unused on platforms with inventory support
 pub(crate) fn types_with_init_level(allow: InitLevel, deny: InitLevel) -> Vec<Cow<'static, str>> {
     let registry = CLASS_REGISTRY.read();
     let mut list = registry
         .values()
-        .filter(|class_info| {
-            class_info.init_level.intersects(allow) && !class_info.init_level.intersects(deny)
+        .filter(|entry| {
+            entry
+                .init_levels
+                .values()
+                .any(|level| level.intersects(allow) && !level.intersects(deny))
         })
-        .map(|class_info| class_info.name.clone())
+        .map(|entry| entry.name.clone())
         .collect::<Vec<_>>();
 
     list.sort_unstable();
@@ -130,8 +213,66 @@ impl fmt::Display for RegisterError {
     }
 }
 
+/// Removes the registrations of `library`.
+#[inline]
+pub(crate) fn cleanup_library(library: Library) {
+    CLASS_REGISTRY.write().retain(|_, entry| {
+        entry.init_levels.remove(&library);
+        !entry.init_levels.is_empty()
+    });
+}
+
 /// Clears the registry
 #[inline]
 pub(crate) fn cleanup() {
     CLASS_REGISTRY.write().clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::user_data::Aether;
+    use crate::private::ManuallyManagedClassPlaceholder;
+
+    #[derive(Copy, Clone, Default)]
+    struct Shared;
+
+    impl NativeClass for Shared {
+        type Base = ManuallyManagedClassPlaceholder;
+        type UserData = Aether<Self>;
+    }
+
+    #[test]
+    fn registrations_are_per_library() {
+        let (first, second) = (1, 2);
+
+        let register = |library, name: &'static str, level| {
+            register_class_as::<Shared>(library, Cow::Borrowed(name), level)
+        };
+
+        assert!(register(first, "Shared", InitLevel::AUTO).unwrap());
+        assert!(!register(first, "Shared", InitLevel::USER).unwrap());
+        assert!(matches!(
+            register(first, "Shared", InitLevel::USER),
+            Err(RegisterError {
+                kind: RegisterErrorKind::AlreadyOnSameLevel,
+                ..
+            })
+        ));
+
+        // Another library loading the same binary needs its own FFI registration.
+        assert!(register(second, "Shared", InitLevel::USER).unwrap());
+        assert!(matches!(
+            register(second, "Renamed", InitLevel::AUTO),
+            Err(RegisterError {
+                kind: RegisterErrorKind::ConflictingName,
+                ..
+            })
+        ));
+
+        cleanup_library(first);
+        assert_eq!(Some(Cow::Borrowed("Shared")), class_name::<Shared>());
+        cleanup_library(second);
+        assert_eq!(None, class_name::<Shared>());
+    }
+}
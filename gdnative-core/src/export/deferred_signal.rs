@@ -0,0 +1,217 @@
+//! Emitting signals from threads other than the main thread.
+
+use std::ptr;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::core_types::{GodotString, OwnedToVariant, ToVariant, Variant, VariantArray};
+use crate::export::user_data::Aether;
+use crate::export::{ClassBuilder, Method, NativeClass, NativeClassMethods, Varargs};
+use crate::init::InitHandle;
+use crate::object::ownership::{Shared, Unique};
+use crate::object::{GodotObject, Instance, InstanceId, Ref, TInstance, TRef};
+use crate::private::{get_api, ObjectMethodTable, ReferenceCountedClassPlaceholder};
+
+/// Object on which the deferred calls are queued. Created by the first emitter, and freed when
+/// the library is terminated.
+static MARSHALLER: Lazy<RwLock<Option<Ref<ReferenceCountedClassPlaceholder, Shared>>>> =
+    Lazy::new(RwLock::default);
+
+const EMIT_METHOD: &str = "_emit";
+
+/// Emits signals of an object from any thread.
+///
+/// Most of the engine API, including `emit_signal`, may only be used from the main thread. An
+/// emitter can be cloned into other threads, and queues the emissions to be performed on the
+/// main thread instead, when the engine processes deferred calls at the end of the frame:
+///
+/// ```no_run
+/// use gdnative::export::DeferredSignalEmitter;
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[register_with(Self::register)]
+/// struct Downloader;
+///
+/// #[methods]
+/// impl Downloader {
+///     fn new(_base: &Node) -> Self {
+///         Downloader
+///     }
+///
+///     fn register(builder: &ClassBuilder<Self>) {
+///         builder.signal("progress").with_param_untyped("percent").done();
+///     }
+///
+///     #[method]
+///     fn start(&self, #[base] base: &Node) {
+///         let emitter = DeferredSignalEmitter::new(base);
+///         std::thread::spawn(move || {
+///             for percent in 0..=100 {
+///                 emitter.emit("progress", &[percent.to_variant()]);
+///             }
+///         });
+///     }
+/// }
+/// ```
+///
+/// Signals are emitted in the order they were queued from each thread. If the object is freed
+/// before a queued signal is emitted, the signal is discarded.
+#[derive(Clone, Debug)]
+pub struct DeferredSignalEmitter {
+    target: InstanceId,
+}
+
+impl DeferredSignalEmitter {
+    /// Creates an emitter for the signals of `target`.
+    ///
+    /// This should be called on the main thread.
+    #[inline]
+    pub fn new<T: GodotObject>(target: &T) -> Self {
+        let mut marshaller = MARSHALLER.write();
+        if marshaller.is_none() {
+            let instance = Instance::<Marshaller, Unique>::new();
+            *marshaller = Some(instance.into_base().into_shared());
+        }
+
+        DeferredSignalEmitter {
+            target: target.instance_id(),
+        }
+    }
+
+    /// Returns the instance ID of the object whose signals are emitted.
+    #[inline]
+    pub fn target(&self) -> InstanceId {
+        self.target
+    }
+
+    /// Queues the emission of `signal` with `args` on the main thread. Returns `false` if the
+    /// library has been terminated, in which case nothing is queued.
+    ///
+    /// At most 5 arguments can be passed to a signal in Godot 3, so any further arguments are
+    /// discarded by the engine.
+    #[inline]
+    pub fn emit(&self, signal: &str, args: &[Variant]) -> bool {
+        let marshaller = MARSHALLER.read();
+        let marshaller = match &*marshaller {
+            Some(marshaller) => marshaller,
+            None => return false,
+        };
+
+        let signal_args = VariantArray::new();
+        for arg in args {
+            signal_args.push(arg);
+        }
+
+        let call_args = [
+            EMIT_METHOD.to_variant(),
+            self.target.to_variant(),
+            signal.to_variant(),
+            signal_args.owned_to_variant(),
+        ];
+        let mut call_args = call_args.iter().map(Variant::sys).collect::<Vec<_>>();
+
+        // SAFETY: The marshaller is alive while the lock is held, and deferred calls can be
+        // queued from any thread.
+        unsafe {
+            let api = get_api();
+            let ret = (api.godot_method_bind_call)(
+                ObjectMethodTable::get(api).call_deferred,
+                marshaller.as_ptr(),
+                call_args.as_mut_ptr(),
+                call_args.len() as _,
+                ptr::null_mut(),
+            );
+            drop(Variant::from_sys(ret));
+        }
+
+        true
+    }
+}
+
+/// Registers the class of the marshaller. Called during `nativescript_init`.
+pub(crate) fn register_marshaller(handle: InitHandle) {
+    handle.add_class_as::<Marshaller>("__GDNATIVE_INTERNAL__DeferredSignalEmitter".into());
+}
+
+/// Frees the marshaller, discarding the signals that have not been emitted yet. Called during
+/// `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    let marshaller = MARSHALLER.write().take();
+    drop(marshaller);
+}
+
+/// Emits `signal` on the object with the ID `target`, if it is still alive.
+///
+/// # Safety
+///
+/// Must be called on the main thread.
+unsafe fn emit(target: InstanceId, signal: GodotString, args: VariantArray) {
+    let api = get_api();
+    let object = (api.godot_instance_from_id)(target.to_i64() as sys::godot_int);
+    if object.is_null() {
+        return;
+    }
+
+    let signal = signal.owned_to_variant();
+    let args = args.iter().collect::<Vec<_>>();
+    let mut emit_args = std::iter::once(&signal)
+        .chain(&args)
+        .map(Variant::sys)
+        .collect::<Vec<_>>();
+
+    let ret = (api.godot_method_bind_call)(
+        ObjectMethodTable::get(api).emit_signal,
+        object,
+        emit_args.as_mut_ptr(),
+        emit_args.len() as _,
+        ptr::null_mut(),
+    );
+    drop(Variant::from_sys(ret));
+}
+
+/// Script of the object on which the emissions are queued.
+#[derive(Copy, Clone, Default)]
+struct Marshaller;
+
+impl NativeClass for Marshaller {
+    type Base = ReferenceCountedClassPlaceholder;
+    type UserData = Aether<Self>;
+
+    fn nativeclass_init(_owner: TRef<'_, ReferenceCountedClassPlaceholder, Shared>) -> Self {
+        Marshaller
+    }
+}
+
+impl NativeClassMethods for Marshaller {
+    fn nativeclass_register(builder: &ClassBuilder<Self>) {
+        builder.method(EMIT_METHOD, Emit).done();
+    }
+}
+
+struct Emit;
+
+impl<C: NativeClass> Method<C> for Emit {
+    fn call(&self, _this: TInstance<'_, C>, mut args: Varargs<'_>) -> Variant {
+        let target = args.read::<InstanceId>().get();
+        let signal = args.read::<GodotString>().get();
+        let signal_args = args.read::<VariantArray>().get();
+
+        match (target, signal, signal_args) {
+            // SAFETY: Deferred calls are performed on the main thread.
+            (Ok(target), Ok(signal), Ok(signal_args)) => unsafe {
+                emit(target, signal, signal_args)
+            },
+            (target, signal, signal_args) => {
+                let errors = [target.err(), signal.err(), signal_args.err()];
+                for err in errors.iter().flatten() {
+                    err.log_error();
+                }
+            }
+        }
+
+        Variant::nil()
+    }
+}
@@ -6,6 +6,7 @@ use std::{fmt, ops};
 
 use crate::core_types::{FromVariant, FromVariantError, Variant};
 use crate::export::class::NativeClass;
+use crate::export::mixin_state::InstanceStorage;
 use crate::export::{class_registry, ClassBuilder};
 use crate::log::Site;
 use crate::object::ownership::Shared;
@@ -45,7 +46,10 @@ where
     /// Register the method.
     #[inline]
     pub fn done(self) {
-        let method_data = Box::into_raw(Box::new(self.method));
+        let method_data = Box::into_raw(Box::new(MethodData {
+            name: self.name.into(),
+            method: self.method,
+        }));
 
         let script_method = ScriptMethod {
             name: self.name,
@@ -54,7 +58,8 @@ where
                 rpc_mode: self.rpc_mode,
             },
             method_data: method_data as *mut libc::c_void,
-            free_func: Some(free_func::<F>),
+            free_func: Some(free_func::<MethodData<F>>),
+            site: F::site(),
         };
 
         self.class_builder.add_method(script_method);
@@ -69,19 +74,23 @@ where
     /// Register the method as a stateless method. Stateless methods do not have data
     /// pointers and destructors and are thus slightly lighter. This is intended for ZSTs,
     /// but can be used with any `Method` type with `Copy + Default`.
+    ///
+    /// Since the name of a stateless method isn't stored, it's taken from the site of the
+    /// method in the [call stack](crate::diagnostics::call_stack).
     #[inline]
     pub fn done_stateless(self) {
         let script_method = ScriptMethod {
             name: self.name,
-            method_ptr: Some(method_wrapper::<C, Stateless<F>>),
+            method_ptr: Some(stateless_method_wrapper::<C, F>),
             attributes: ScriptMethodAttributes {
                 rpc_mode: self.rpc_mode,
             },
 
-            // Stateless<F> is a ZST for any type F, so we can use any non-zero value as
-            // a valid pointer for it.
+            // Stateless methods don't read their method data, so we can use any non-zero value
+            // as the pointer.
             method_data: 1 as *mut libc::c_void,
             free_func: None,
+            site: F::site(),
         };
 
         self.class_builder.add_method(script_method);
@@ -134,6 +143,8 @@ pub(crate) struct ScriptMethod<'l> {
 
     pub method_data: *mut libc::c_void,
     pub free_func: Option<unsafe extern "C" fn(*mut libc::c_void) -> ()>,
+
+    pub site: Option<Site<'static>>,
 }
 
 /// Safe low-level trait for stateful, variadic methods that can be called on a native script type.
@@ -160,6 +171,10 @@ impl<C: NativeClass, F: Method<C> + Copy + Default> Method<C> for Stateless<F> {
         let f = F::default();
         f.call(this, args)
     }
+
+    fn site() -> Option<Site<'static>> {
+        F::site()
+    }
 }
 
 /// Adapter for methods whose arguments are statically determined. If the arguments would fail to
@@ -839,12 +854,51 @@ impl<'a> fmt::Display for ArgumentErrorKind<'a> {
     }
 }
 
+/// Method data passed to `method_wrapper`.
+struct MethodData<F> {
+    /// Name the method is registered under, for the call stack.
+    name: Box<str>,
+    method: F,
+}
+
 unsafe extern "C" fn method_wrapper<C: NativeClass, F: Method<C>>(
     this: *mut sys::godot_object,
     method_data: *mut libc::c_void,
     user_data: *mut libc::c_void,
     num_args: libc::c_int,
     args: *mut *mut sys::godot_variant,
+) -> sys::godot_variant {
+    let data = &*(method_data as *const MethodData<F>);
+    call_method::<C, F>(
+        &data.method,
+        Some(&*data.name),
+        this,
+        user_data,
+        num_args,
+        args,
+    )
+}
+
+unsafe extern "C" fn stateless_method_wrapper<C: NativeClass, F: Method<C> + Copy + Default>(
+    this: *mut sys::godot_object,
+    _method_data: *mut libc::c_void,
+    user_data: *mut libc::c_void,
+    num_args: libc::c_int,
+    args: *mut *mut sys::godot_variant,
+) -> sys::godot_variant {
+    let method = Stateless::<F> {
+        _marker: PhantomData,
+    };
+    call_method::<C, Stateless<F>>(&method, None, this, user_data, num_args, args)
+}
+
+unsafe fn call_method<C: NativeClass, F: Method<C>>(
+    method: &F,
+    name: Option<&str>,
+    this: *mut sys::godot_object,
+    user_data: *mut libc::c_void,
+    num_args: libc::c_int,
+    args: *mut *mut sys::godot_variant,
 ) -> sys::godot_variant {
     if user_data.is_null() {
         crate::log::error(
@@ -871,11 +925,16 @@ unsafe extern "C" fn method_wrapper<C: NativeClass, F: Method<C>>(
         }
     };
 
+    let _frame =
+        crate::diagnostics::enter(class_registry::class_name_or_default::<C>, name, F::site());
+
+    let method_data = method as *const F as *const libc::c_void;
     let result = std::panic::catch_unwind(move || {
         let method = &*(method_data as *const F);
 
         let this: Ref<C::Base, Shared> = Ref::from_sys(this);
         let this: TRef<'_, C::Base, _> = this.assume_safe_unchecked();
+        let user_data = InstanceStorage::user_data(user_data) as *mut libc::c_void;
         let this: TInstance<'_, C, _> = TInstance::from_raw_unchecked(this, user_data);
 
         let args = Varargs::from_sys(num_args, args);
@@ -890,11 +949,53 @@ unsafe extern "C" fn method_wrapper<C: NativeClass, F: Method<C>>(
                 "gdnative-core: method panicked (check stderr for output)",
             );
             crate::private::print_panic_error(e);
+            crate::diagnostics::log_call_stack();
             Variant::nil()
         })
         .leak()
 }
 
+/// Calls `method` on the instance of `C` attached to `this`. Used as the body of C ABI exports
+/// generated by `#[method(c_export)]`.
+///
+/// Unlike `method_wrapper`, the user data pointer isn't supplied by the engine here, so it's
+/// obtained from the script instance after checking its type tag.
+pub(crate) unsafe fn call_c_export<C: NativeClass, F: Method<C>>(
+    method: F,
+    name: &str,
+    this: *mut sys::godot_object,
+    num_args: libc::c_int,
+    args: *mut *mut sys::godot_variant,
+) -> sys::godot_variant {
+    if this.is_null() {
+        crate::log::error(
+            F::site().unwrap_or_default(),
+            format_args!(
+                "gdnative-core: base object pointer passed to the C export of {} is null",
+                class_registry::class_name_or_default::<C>(),
+            ),
+        );
+        return Variant::nil().leak();
+    }
+
+    let api = crate::private::get_api();
+    let type_tag = (api.godot_nativescript_get_type_tag)(this);
+    if type_tag.is_null() || !crate::export::type_tag::check::<C>(type_tag) {
+        crate::log::error(
+            F::site().unwrap_or_default(),
+            format_args!(
+                "gdnative-core: object passed to the C export is not an instance of {}",
+                class_registry::class_name_or_default::<C>(),
+            ),
+        );
+        return Variant::nil().leak();
+    }
+
+    let user_data = (api.godot_nativescript_get_userdata)(this);
+
+    call_method::<C, F>(&method, Some(name), this, user_data, num_args, args)
+}
+
 unsafe extern "C" fn free_func<F>(method_data: *mut libc::c_void) {
     drop(Box::from_raw(method_data as *mut F))
 }
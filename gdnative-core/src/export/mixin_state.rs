@@ -0,0 +1,182 @@
+//! Per-instance state for mixins.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use parking_lot::{Mutex, ReentrantMutex};
+
+use crate::export::{Mixin, NativeClass};
+use crate::object::GodotObject;
+
+type StateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>, ahash::RandomState>;
+
+/// What the engine stores as the user data of a script instance: the user data of the
+/// `NativeClass`, and the states of the mixins registered to it, keyed by the mixin type.
+///
+/// Both are dropped in the instance destructor.
+pub(crate) struct InstanceStorage {
+    user_data: *const libc::c_void,
+    states: Mutex<StateMap>,
+}
+
+impl InstanceStorage {
+    /// Wraps `user_data` for the engine.
+    pub(crate) fn into_raw(user_data: *const libc::c_void) -> *mut libc::c_void {
+        let storage = Box::new(InstanceStorage {
+            user_data,
+            states: Mutex::default(),
+        });
+        Box::into_raw(storage) as *mut libc::c_void
+    }
+
+    /// Returns the user data of the `NativeClass` stored in `storage`.
+    ///
+    /// # Safety
+    ///
+    /// `storage` must be a non-null pointer returned by `into_raw` that wasn't consumed yet.
+    pub(crate) unsafe fn user_data(storage: *const libc::c_void) -> *const libc::c_void {
+        (*(storage as *const InstanceStorage)).user_data
+    }
+
+    /// Takes back ownership of `storage`, returning the user data of the `NativeClass` and the
+    /// mixin states, so that the caller can drop them in order.
+    ///
+    /// # Safety
+    ///
+    /// `storage` must be a non-null pointer returned by `into_raw` that wasn't consumed yet.
+    pub(crate) unsafe fn consume(storage: *mut libc::c_void) -> (*const libc::c_void, StateMap) {
+        let storage = Box::from_raw(storage as *mut InstanceStorage);
+        (storage.user_data, storage.states.into_inner())
+    }
+}
+
+/// Handle to the state of the mixin `M` of a single instance of `C`, as declared with
+/// `#[methods(mixin = "Name", state = "S")]`.
+///
+/// States are stored alongside the user data of the instance, created with `S::default()`
+/// on first access, and dropped together with the user data. This allows reusable behavior
+/// to carry data without requiring a field on every class it is applied to:
+///
+/// ```
+/// use gdnative::export::MixinState;
+/// use gdnative::prelude::*;
+///
+/// #[derive(Default)]
+/// struct Health {
+///     damage_taken: i64,
+/// }
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[register_with(register_enemy)]
+/// #[no_constructor]
+/// struct Enemy;
+///
+/// fn register_enemy(builder: &ClassBuilder<Enemy>) {
+///     builder.mixin::<HealthMixin>();
+/// }
+///
+/// #[methods(mixin = "HealthMixin", state = "Health")]
+/// impl Enemy {
+///     #[method]
+///     fn take_damage(&self, #[base] base: &Node, amount: i64) -> i64 {
+///         let health = MixinState::<Self, HealthMixin>::of(base).unwrap();
+///         health.map_mut(|health| {
+///             health.damage_taken += amount;
+///             health.damage_taken
+///         })
+///     }
+/// }
+/// ```
+pub struct MixinState<C: NativeClass, M: Mixin<C>> {
+    cell: Arc<ReentrantMutex<RefCell<M::State>>>,
+    _marker: PhantomData<fn() -> (C, M)>,
+}
+
+impl<C: NativeClass, M: Mixin<C>> MixinState<C, M> {
+    /// Returns the state of `M` for the instance of `C` that `owner` is the base object of,
+    /// creating it if necessary.
+    ///
+    /// Returns `None` if `owner` isn't an instance of `C`, or if its constructor failed.
+    #[inline]
+    pub fn of(owner: &C::Base) -> Option<Self> {
+        let storage = crate::object::try_get_storage_ptr::<C>(owner.as_raw())?;
+        if storage.is_null() {
+            return None;
+        }
+
+        // SAFETY: the storage of a live instance is valid until its destructor is called.
+        let storage = unsafe { &*(storage as *const InstanceStorage) };
+
+        let cell = storage
+            .states
+            .lock()
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| Arc::new(ReentrantMutex::new(RefCell::new(M::State::default()))))
+            .clone();
+
+        let cell = cell
+            .downcast::<ReentrantMutex<RefCell<M::State>>>()
+            .unwrap_or_else(|_| unreachable!("states are keyed by their mixin type"));
+
+        Some(MixinState {
+            cell,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Calls `op` with a reference to the state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is already borrowed mutably on the current thread, e.g. when a
+    /// mixin method is called re-entrantly from within `op`. Other threads block instead.
+    #[inline]
+    pub fn map<F, R>(&self, op: F) -> R
+    where
+        F: FnOnce(&M::State) -> R,
+    {
+        let guard = self.cell.lock();
+        let state = guard.borrow();
+        op(&state)
+    }
+
+    /// Calls `op` with a mutable reference to the state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is already borrowed on the current thread, e.g. when a mixin method
+    /// is called re-entrantly from within `op`. Other threads block instead.
+    #[inline]
+    pub fn map_mut<F, R>(&self, op: F) -> R
+    where
+        F: FnOnce(&mut M::State) -> R,
+    {
+        let guard = self.cell.lock();
+        let mut state = guard.borrow_mut();
+        op(&mut state)
+    }
+}
+
+impl<C: NativeClass, M: Mixin<C>> Clone for MixinState<C, M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        MixinState {
+            cell: Arc::clone(&self.cell),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: NativeClass, M: Mixin<C>> fmt::Debug for MixinState<C, M> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MixinState")
+            .field(&std::any::type_name::<M>())
+            .finish()
+    }
+}
@@ -13,22 +13,32 @@
 //! For full examples, see [`examples`](https://github.com/godot-rust/godot-rust/tree/master/examples)
 //! in the godot-rust repository.
 
+#[cfg(feature = "c-abi")]
+pub mod c_abi;
 mod class;
 mod class_builder;
+mod class_db;
 mod method;
-mod property;
+pub(crate) mod property;
 mod signal;
 
 pub(crate) mod class_registry;
+pub(crate) mod deferred_signal;
 pub(crate) mod emplace;
+pub(crate) mod mixin_state;
+pub(crate) mod processing;
 pub(crate) mod type_tag;
 
 pub mod user_data;
 
 pub use class::*;
 pub use class_builder::*;
+pub use class_db::*;
+pub use deferred_signal::DeferredSignalEmitter;
 #[doc(inline)]
 pub use gdnative_derive::godot_wrap_method;
 pub use method::*;
+pub use mixin_state::MixinState;
+pub use processing::Processing;
 pub use property::*;
 pub use signal::*;
@@ -0,0 +1,89 @@
+//! Processing callbacks that are enabled on construction.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::export::NativeClass;
+use crate::object::RawObject;
+use crate::private::{get_api, NodeMethodTable, NodePlaceholder};
+
+/// Processing callbacks to enable, keyed by the type of the class.
+static ENABLED: Lazy<RwLock<HashMap<TypeId, Enabled>>> = Lazy::new(RwLock::default);
+
+/// Whether `ENABLED` is non-empty. Checked before locking, so that constructors of libraries
+/// that don't use this feature are not slowed down.
+static HAS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Copy, Clone, Default)]
+struct Enabled {
+    idle: bool,
+    physics: bool,
+}
+
+/// Processing callbacks of `Node`, see [`ClassBuilder::enable_processing`][super::ClassBuilder::enable_processing].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Processing {
+    /// `_process`, called every frame. Enabled with `Node::set_process`.
+    Idle,
+    /// `_physics_process`, called every physics tick. Enabled with
+    /// `Node::set_physics_process`.
+    Physics,
+}
+
+/// Enables `processing` for the instances of `C` that are constructed from now on.
+pub(crate) fn enable<C: NativeClass>(processing: Processing) {
+    let mut enabled = ENABLED.write();
+    let enabled = enabled.entry(TypeId::of::<C>()).or_default();
+    match processing {
+        Processing::Idle => enabled.idle = true,
+        Processing::Physics => enabled.physics = true,
+    }
+    HAS_ENABLED.store(true, Ordering::Release);
+}
+
+/// Enables the processing callbacks of `C` on `owner`. Called by the constructor of the script
+/// instance.
+pub(crate) fn apply<C: NativeClass>(owner: &RawObject<C::Base>) {
+    if !HAS_ENABLED.load(Ordering::Acquire) {
+        return;
+    }
+
+    let Some(enabled) = ENABLED.read().get(&TypeId::of::<C>()).copied() else {
+        return;
+    };
+
+    // Classes aren't required to inherit `Node` to enable processing, e.g. when the methods are
+    // added by a mixin shared with other classes, so other bases are skipped here.
+    if !owner.is_class::<NodePlaceholder>() {
+        return;
+    }
+
+    let api = get_api();
+    let node_methods = NodeMethodTable::get(api);
+    let calls = [
+        (enabled.idle, node_methods.set_process),
+        (enabled.physics, node_methods.set_physics_process),
+    ];
+    for (_, method) in calls.into_iter().filter(|(enable, _)| *enable) {
+        let enable = true;
+        // SAFETY: `owner` is a `Node`, and the methods take a single `bool`.
+        unsafe {
+            (api.godot_method_bind_ptrcall)(
+                method,
+                owner.sys().as_ptr(),
+                [&enable as *const bool as *const libc::c_void].as_mut_ptr(),
+                std::ptr::null_mut(),
+            );
+        }
+    }
+}
+
+/// Forgets the enabled processing callbacks. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    HAS_ENABLED.store(false, Ordering::Release);
+    ENABLED.write().clear();
+}
@@ -8,9 +8,12 @@ use std::marker::PhantomData;
 
 use accessor::{Getter, RawGetter, RawSetter, Setter};
 use invalid_accessor::{InvalidGetter, InvalidSetter};
+use list::Condition;
 
 use crate::core_types::*;
+use crate::export::user_data::Map;
 use crate::export::{ClassBuilder, NativeClass};
+use crate::init::RegistrationKind;
 use crate::object::ownership::Shared;
 use crate::object::{GodotObject, Instance, Ref};
 use crate::private::get_api;
@@ -18,10 +21,22 @@ use crate::private::get_api;
 use super::RpcMode;
 
 mod accessor;
+pub(crate) mod bag;
 mod invalid_accessor;
+pub(crate) mod list;
+pub(crate) mod schema;
+pub(crate) mod storage;
+pub(crate) mod validation;
+mod watched;
 
 pub mod hint;
 
+pub use bag::PropertyBag;
+pub use schema::PropertyDefinition;
+pub use storage::{Migration, StorageBuilder, STORAGE_PROPERTY};
+pub use validation::{set_validation_handler, SetterResult, ValidationError};
+pub use watched::Watched;
+
 /// Trait for exportable types.
 ///
 /// ## Rust collections
@@ -55,6 +70,18 @@ pub mod hint;
 ///   embracing their respective semantics.
 /// - Exporting not a property, but methods that have to be explicitly called, to set clear
 ///   expectations that the return value might be expensive to produce.
+///
+/// ## `Option<T>`
+///
+/// `Option<T>` can be exported for any exportable `T`, with `null` representing `None` in both
+/// directions: `None` is converted to `null` when the property is read, and setting the property
+/// to `null` stores `None`. Hints for `T` can be used for `Option<T>` as well. `null` values of
+/// such properties are always saved in scenes and resources, so that `None` is restored
+/// correctly even when the default value is `Some`.
+///
+/// The inspector can only clear properties whose type allows `null` in Godot, i.e. objects and
+/// resources such as `Option<Ref<Texture>>`. For other types like `Option<f64>`, the inspector
+/// shows the editor for `T`, and `None` can only be set from code.
 pub trait Export: crate::core_types::ToVariant {
     /// A type-specific hint type that is valid for the type being exported.
     ///
@@ -75,6 +102,7 @@ pub struct ExportInfo {
     pub(super) variant_type: VariantType,
     pub(super) hint_kind: sys::godot_property_hint,
     pub(super) hint_string: GodotString,
+    pub(super) usage: PropertyUsage,
 }
 
 impl ExportInfo {
@@ -85,6 +113,7 @@ impl ExportInfo {
             variant_type,
             hint_kind: sys::godot_property_hint_GODOT_PROPERTY_HINT_NONE,
             hint_string: GodotString::new(),
+            usage: PropertyUsage::empty(),
         }
     }
 
@@ -98,8 +127,29 @@ impl ExportInfo {
             variant_type: VariantType::Object,
             hint_kind: sys::godot_property_hint_GODOT_PROPERTY_HINT_RESOURCE_TYPE,
             hint_string: T::class_name().into(),
+            usage: PropertyUsage::empty(),
         }
     }
+
+    /// Marks the property as nullable, so that `null` values are stored in scenes and resources
+    /// even where the engine would otherwise skip them. This is used for `Option<T>`.
+    #[inline]
+    pub fn nullable(mut self) -> Self {
+        self.usage |= PropertyUsage::STORE_IF_NULL;
+        self
+    }
+
+    /// Returns a property info dictionary for a property called `name`, in the format used by
+    /// `Object.get_property_list` and `ProjectSettings.add_property_info`.
+    #[inline]
+    pub fn to_property_info(&self, name: &str) -> Dictionary {
+        let info = Dictionary::new();
+        info.insert("name", name);
+        info.insert("type", self.variant_type as u32);
+        info.insert("hint", self.hint_kind as u32);
+        info.insert("hint_string", self.hint_string.clone());
+        info.into_shared()
+    }
 }
 
 /// Builder type used to register a property on a `NativeClass`.
@@ -113,6 +163,7 @@ pub struct PropertyBuilder<'a, C, T: Export, S = InvalidSetter<'a>, G = InvalidG
     hint: Option<T::Hint>,
     usage: PropertyUsage,
     rpc_mode: RpcMode,
+    visible_if: Option<Condition<C>>,
     class_builder: &'a ClassBuilder<C>,
 }
 
@@ -132,6 +183,7 @@ where
             hint: None,
             usage: PropertyUsage::DEFAULT,
             rpc_mode: RpcMode::Disabled,
+            visible_if: None,
             class_builder,
         }
     }
@@ -151,15 +203,38 @@ where
             variant_type,
             hint_kind,
             hint_string,
+            usage,
         } = T::export_info(self.hint);
         let default = self.default.to_variant();
+        let usage = self.usage | usage;
+
+        let registered_usage = if let Some(condition) = self.visible_if {
+            list::push_conditional(
+                self.class_builder,
+                self.name,
+                variant_type,
+                hint_kind,
+                hint_string.to_string(),
+                usage,
+                condition,
+            );
+            usage - PropertyUsage::EDITOR
+        } else {
+            usage
+        };
+
+        self.class_builder
+            .record(RegistrationKind::Property, self.name, None);
+        if self.class_builder.dry_run {
+            return;
+        }
 
         let mut attr = sys::godot_property_attributes {
             rset_type: self.rpc_mode.sys(),
             type_: variant_type as sys::godot_int,
             hint: hint_kind,
             hint_string: hint_string.to_sys(),
-            usage: self.usage.to_sys(),
+            usage: registered_usage.to_sys(),
             default_value: default.to_sys(),
         };
 
@@ -182,6 +257,9 @@ where
 
     /// Provides a setter function with the signature `fn(&mut C, owner: C::Base, value: T)`
     /// where `C` is the `NativeClass` type being registered and `T` is the type of the property.
+    ///
+    /// The setter may also return `Result<(), E>` where `E: Display`, to reject invalid values.
+    /// See [`SetterResult`] for details.
     #[inline]
     pub fn with_setter<NS>(
         self,
@@ -192,12 +270,13 @@ where
     {
         PropertyBuilder {
             name: self.name,
-            setter: Setter::new(setter),
+            setter: Setter::new(self.name, setter),
             getter: self.getter,
             default: self.default,
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -206,6 +285,8 @@ where
     /// where `C` is the `NativeClass` type being registered and `T` is the type of the property.
     ///
     /// "shr" stands for "shared reference", as opposed to the more common `&mut self`.
+    ///
+    /// As with [`Self::with_setter`], the setter may return a `Result` to reject invalid values.
     #[inline]
     pub fn with_shr_setter<NS>(
         self,
@@ -216,12 +297,13 @@ where
     {
         PropertyBuilder {
             name: self.name,
-            setter: Setter::new(setter),
+            setter: Setter::new(self.name, setter),
             getter: self.getter,
             default: self.default,
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -244,6 +326,7 @@ where
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -266,6 +349,7 @@ where
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -288,6 +372,7 @@ where
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -310,6 +395,7 @@ where
             hint: self.hint,
             usage: self.usage,
             rpc_mode: self.rpc_mode,
+            visible_if: self.visible_if,
             class_builder: self.class_builder,
         }
     }
@@ -329,7 +415,10 @@ where
         self
     }
 
-    /// Sets a property usage.
+    /// Sets a property usage. Defaults to [`PropertyUsage::DEFAULT`].
+    ///
+    /// Flags can be combined freely, e.g. `PropertyUsage::storage_only()` for a property that
+    /// is saved to scenes, but hidden from the inspector.
     #[inline]
     pub fn with_usage(mut self, usage: PropertyUsage) -> Self {
         self.usage = usage;
@@ -342,9 +431,39 @@ where
         self.rpc_mode = rpc_mode;
         self
     }
+
+    /// Only shows the property in the inspector while `condition` returns `true` for the
+    /// instance. If called multiple times, all conditions must hold.
+    ///
+    /// The property is always saved and can always be accessed from scripts. Conditionally
+    /// visible properties are listed by a `_get_property_list` method registered for the class,
+    /// so the class must not define it itself.
+    ///
+    /// The inspector is only refreshed when a property with the
+    /// [`PropertyUsage::UPDATE_ALL_IF_MODIFIED`] flag changes, which should be set on the
+    /// properties the condition depends on.
+    #[inline]
+    pub fn with_visible_if<F>(mut self, condition: F) -> Self
+    where
+        C::UserData: Map,
+        F: Fn(&C) -> bool + Send + Sync + 'static,
+    {
+        let condition = Condition::new(condition);
+        self.visible_if = Some(match self.visible_if {
+            Some(previous) => previous.and(condition),
+            None => condition,
+        });
+        self
+    }
 }
 
 bitflags::bitflags! {
+    /// Flags describing how a property is used by the engine and the editor.
+    ///
+    /// Flags can be combined with the bitwise operators, e.g. `PropertyUsage::STORAGE |
+    /// PropertyUsage::NETWORK`. Common combinations are available as constants, like
+    /// [`DEFAULT`](Self::DEFAULT) and [`NOEDITOR`](Self::NOEDITOR), and as presets, like
+    /// [`storage_only`](Self::storage_only) and [`editor_only`](Self::editor_only).
     pub struct PropertyUsage: u32 {
         const STORAGE = sys::godot_property_usage_flags_GODOT_PROPERTY_USAGE_STORAGE as u32;
         const EDITOR = sys::godot_property_usage_flags_GODOT_PROPERTY_USAGE_EDITOR as u32;
@@ -371,6 +490,29 @@ bitflags::bitflags! {
 }
 
 impl PropertyUsage {
+    /// Usage of properties that are saved to scenes and resources, but not shown in the
+    /// inspector or synchronized over the network.
+    #[inline]
+    pub const fn storage_only() -> Self {
+        Self::STORAGE
+    }
+
+    /// Usage of properties that are shown in the inspector, but not saved to scenes and
+    /// resources or synchronized over the network.
+    ///
+    /// Useful for properties that are computed from other state, like the size of a collection.
+    #[inline]
+    pub const fn editor_only() -> Self {
+        Self::EDITOR
+    }
+
+    /// Returns `self` with the [`INTERNATIONALIZED`](Self::INTERNATIONALIZED) flag, which marks
+    /// string properties as translatable.
+    #[inline]
+    pub const fn internationalized(self) -> Self {
+        Self::from_bits_truncate(self.bits | Self::INTERNATIONALIZED.bits)
+    }
+
     #[inline]
     pub fn to_sys(self) -> sys::godot_property_usage_flags {
         self.bits() as sys::godot_property_usage_flags
@@ -607,10 +749,10 @@ mod impl_export {
     where
         T: GodotObject,
     {
-        type Hint = NoHint;
+        type Hint = hint::ResourceHint;
         #[inline]
-        fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-            ExportInfo::resource_type::<T>()
+        fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
+            hint.map_or_else(ExportInfo::resource_type::<T>, Self::Hint::export_info)
         }
     }
 
@@ -619,10 +761,13 @@ mod impl_export {
         T: NativeClass,
         Instance<T, Shared>: ToVariant,
     {
-        type Hint = NoHint;
+        type Hint = hint::ResourceHint;
         #[inline]
-        fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
-            ExportInfo::resource_type::<T::Base>()
+        fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
+            hint.map_or_else(
+                ExportInfo::resource_type::<T::Base>,
+                Self::Hint::export_info,
+            )
         }
     }
 
@@ -633,7 +778,7 @@ mod impl_export {
         type Hint = T::Hint;
         #[inline]
         fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
-            T::export_info(hint)
+            T::export_info(hint).nullable()
         }
     }
 
@@ -645,4 +790,17 @@ mod impl_export {
             hint.unwrap_or_default().export_info()
         }
     }
+
+    /// The hint is passed on to the element type.
+    impl<T> Export for TypedVariantArray<T, Shared>
+    where
+        T: Export + FromVariant,
+    {
+        type Hint = T::Hint;
+
+        #[inline]
+        fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
+            hint::ArrayHint::with_maybe_element_hint::<T>(hint).export_info()
+        }
+    }
 }
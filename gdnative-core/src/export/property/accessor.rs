@@ -4,10 +4,13 @@ use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 use crate::core_types::{FromVariant, ToVariant, Variant};
+use crate::export::mixin_state::InstanceStorage;
 use crate::export::user_data::{Map, MapMut, UserData};
 use crate::export::{class_registry, NativeClass};
 use crate::object::{GodotObject, RawObject, TRef};
 
+use super::validation::{self, SetterResult, ValidationError};
+
 /// Trait for raw property setters.
 ///
 /// This is an internal interface. User code should not use this directly.
@@ -27,14 +30,16 @@ pub unsafe trait RawGetter<C, T> {
 #[derive(Debug)]
 pub struct Setter<SelfArg, F> {
     func: F,
+    property_name: String,
     _self_arg: PhantomData<SelfArg>,
 }
 
 impl<SelfArg, F> Setter<SelfArg, F> {
     #[inline]
-    pub fn new(func: F) -> Self {
+    pub fn new(property_name: &str, func: F) -> Self {
         Setter {
             func,
+            property_name: property_name.to_owned(),
             _self_arg: PhantomData,
         }
     }
@@ -63,7 +68,8 @@ pub struct Shr;
 /// Marker type for accessors that take `&mut self` as their first arguments.
 pub struct Mut;
 
-/// Helper trait for setters, generic over `self` argument mutability.
+/// Helper trait for setters, generic over `self` argument mutability. The inner result is the
+/// outcome of validation by the setter.
 pub trait MapSet<C: NativeClass, F, T> {
     type Err: Debug;
     fn map_set(
@@ -71,14 +77,15 @@ pub trait MapSet<C: NativeClass, F, T> {
         op: &F,
         owner: TRef<C::Base>,
         value: T,
-    ) -> Result<(), Self::Err>;
+    ) -> Result<Result<(), String>, Self::Err>;
 }
 
-impl<C, F, T> MapSet<C, F, T> for Shr
+impl<C, F, T, R> MapSet<C, F, T> for Shr
 where
     C: NativeClass,
     C::UserData: Map,
-    F: 'static + Fn(&C, TRef<C::Base>, T),
+    F: 'static + Fn(&C, TRef<C::Base>, T) -> R,
+    R: SetterResult,
 {
     type Err = <C::UserData as Map>::Err;
     #[inline]
@@ -87,16 +94,17 @@ where
         op: &F,
         owner: TRef<C::Base>,
         value: T,
-    ) -> Result<(), Self::Err> {
-        user_data.map(|rust_ty| op(rust_ty, owner, value))
+    ) -> Result<Result<(), String>, Self::Err> {
+        user_data.map(|rust_ty| op(rust_ty, owner, value).into_validation())
     }
 }
 
-impl<C, F, T> MapSet<C, F, T> for Mut
+impl<C, F, T, R> MapSet<C, F, T> for Mut
 where
     C: NativeClass,
     C::UserData: MapMut,
-    F: 'static + Fn(&mut C, TRef<C::Base>, T),
+    F: 'static + Fn(&mut C, TRef<C::Base>, T) -> R,
+    R: SetterResult,
 {
     type Err = <C::UserData as MapMut>::Err;
     #[inline]
@@ -105,8 +113,8 @@ where
         op: &F,
         owner: TRef<C::Base>,
         value: T,
-    ) -> Result<(), Self::Err> {
-        user_data.map_mut(|rust_ty| op(rust_ty, owner, value))
+    ) -> Result<Result<(), String>, Self::Err> {
+        user_data.map_mut(|rust_ty| op(rust_ty, owner, value).into_validation())
     }
 }
 
@@ -203,7 +211,10 @@ where
     #[inline]
     unsafe fn into_godot_function(self) -> sys::godot_property_set_func {
         let mut set = sys::godot_property_set_func::default();
-        let data = Box::new(self.func);
+        let data = Box::new(SetterData {
+            func: self.func,
+            property_name: self.property_name,
+        });
         set.method_data = Box::into_raw(data) as *mut _;
 
         extern "C" fn invoke<SelfArg, C, F, T>(
@@ -236,16 +247,24 @@ where
             };
 
             let result = std::panic::catch_unwind(|| unsafe {
-                let user_data = C::UserData::clone_from_user_data_unchecked(class as *const _);
+                let user_data = InstanceStorage::user_data(class);
+                let user_data = C::UserData::clone_from_user_data_unchecked(user_data);
                 let owner = TRef::new(C::Base::cast_ref(RawObject::from_sys_ref_unchecked(this)));
-                let func = &*(method as *const F);
+                let data = &*(method as *const SetterData<F>);
 
                 match T::from_variant(Variant::cast_ref(val)) {
-                    Ok(val) => {
-                        if let Err(err) = SelfArg::map_set(&user_data, func, owner, val) {
+                    Ok(val) => match SelfArg::map_set(&user_data, &data.func, owner, val) {
+                        Ok(Ok(())) => {}
+                        Ok(Err(message)) => validation::report(ValidationError {
+                            class_name: class_registry::class_name_or_default::<C>().into_owned(),
+                            property_name: data.property_name.clone(),
+                            instance_id: owner.instance_id(),
+                            message,
+                        }),
+                        Err(err) => {
                             godot_error!("gdnative-core: cannot call property setter: {:?}", err);
                         }
-                    }
+                    },
                     Err(err) => {
                         godot_error!("Incorrect type passed to property: {}", err);
                     }
@@ -261,7 +280,7 @@ where
 
         extern "C" fn free_func<F>(data: *mut libc::c_void) {
             unsafe {
-                drop(Box::from_raw(data as *mut F));
+                drop(Box::from_raw(data as *mut SetterData<F>));
             }
         }
         set.free_func = Some(free_func::<F>);
@@ -270,6 +289,11 @@ where
     }
 }
 
+struct SetterData<F> {
+    func: F,
+    property_name: String,
+}
+
 unsafe impl<SelfArg, RetKind, F, C, T> RawGetter<C, T> for Getter<SelfArg, RetKind, F>
 where
     C: NativeClass,
@@ -312,7 +336,8 @@ where
             };
 
             let result = std::panic::catch_unwind(|| unsafe {
-                let user_data = C::UserData::clone_from_user_data_unchecked(class as *const _);
+                let user_data = InstanceStorage::user_data(class);
+                let user_data = C::UserData::clone_from_user_data_unchecked(user_data);
                 let owner = TRef::new(C::Base::cast_ref(RawObject::from_sys_ref_unchecked(this)));
                 let func = &*(method as *const F);
 
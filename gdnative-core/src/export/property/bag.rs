@@ -0,0 +1,274 @@
+//! Dynamic properties stored in a `PropertyBag`.
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::core_types::{
+    Dictionary, GodotString, OwnedToVariant, ToVariant, Variant, VariantArray,
+};
+use crate::export::user_data::{Map, MapMut};
+use crate::export::{ClassBuilder, Method, NativeClass, PropertyUsage, Varargs};
+use crate::log::Site;
+use crate::object::ownership::Unique;
+use crate::object::{GodotObject, TInstance};
+
+/// Collection of properties that are not known at compile time.
+///
+/// When registered with [`ClassBuilder::property_bag`], properties in the bag are exposed through
+/// the dynamic property hooks `_get`, `_set` and `_get_property_list`. They can then be accessed
+/// like any other property, e.g. using `get` and `set` from GDScript, are shown in the inspector,
+/// and are saved in scenes and resources. Setting a property that doesn't exist adds it to the
+/// bag, and setting a property to `null` removes it.
+///
+/// `#[derive(NativeClass)]` registers fields of this type automatically. Only one bag can be
+/// registered per class:
+///
+/// ```
+/// use gdnative::prelude::*;
+/// use gdnative::export::PropertyBag;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// struct Moddable {
+///     #[property]
+///     health: i64,
+///
+///     extra: PropertyBag,
+/// }
+///
+/// #[methods]
+/// impl Moddable {
+///     fn new(_base: &Node) -> Self {
+///         Moddable {
+///             health: 100,
+///             extra: PropertyBag::new(),
+///         }
+///     }
+///
+///     #[method]
+///     fn speed(&self) -> f64 {
+///         self.extra
+///             .get("speed")
+///             .and_then(|v| v.to::<f64>())
+///             .unwrap_or(1.0)
+///     }
+/// }
+/// ```
+///
+/// Properties registered on the class itself, as well as properties of the base class, take
+/// precedence and are never stored in the bag. Properties are listed in insertion order.
+///
+/// Since `Variant` is not `Send`, types containing a `PropertyBag` can only be used with
+/// thread-local user data wrappers, such as the default `LocalCellData`.
+#[derive(Clone, Debug, Default)]
+pub struct PropertyBag {
+    properties: IndexMap<String, Variant>,
+}
+
+impl PropertyBag {
+    /// Creates an empty bag.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the property `name`, if it exists.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Variant> {
+        self.properties.get(name)
+    }
+
+    /// Sets the property `name` to `value`, returning the previous value if it existed.
+    ///
+    /// Unlike setting the property from Godot, this does not remove the property if `value`
+    /// is `null`.
+    #[inline]
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        value: impl OwnedToVariant,
+    ) -> Option<Variant> {
+        self.properties
+            .insert(name.into(), value.owned_to_variant())
+    }
+
+    /// Removes the property `name`, returning its value if it existed.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<Variant> {
+        self.properties.shift_remove(name)
+    }
+
+    /// Returns `true` if the property `name` exists.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.properties.contains_key(name)
+    }
+
+    /// Returns the number of properties.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Returns `true` if there are no properties.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Removes all properties.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.properties.clear();
+    }
+
+    /// Returns an iterator over the names and values of all properties, in insertion order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Variant)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Appends the entries for `_get_property_list` to `list`.
+    fn append_property_list(&self, list: &VariantArray<Unique>) {
+        let usage = PropertyUsage::DEFAULT.bits();
+
+        for (name, value) in &self.properties {
+            let entry = Dictionary::new();
+            entry.insert("name", name);
+            entry.insert("type", value.get_type() as u32);
+            entry.insert("hint", 0);
+            entry.insert("hint_string", "");
+            entry.insert("usage", usage);
+            list.push(entry.owned_to_variant());
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a PropertyBag {
+    type Item = (&'a String, &'a Variant);
+    type IntoIter = indexmap::map::Iter<'a, String, Variant>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.properties.iter()
+    }
+}
+
+/// Registers the dynamic property hooks for a bag. See [`ClassBuilder::property_bag`].
+pub(crate) fn register<C: NativeClass>(
+    builder: &ClassBuilder<C>,
+    get: fn(&C) -> &PropertyBag,
+    get_mut: fn(&mut C) -> &mut PropertyBag,
+) where
+    C::UserData: Map + MapMut,
+{
+    let reserved = crate::private::class_property_names(C::Base::class_name())
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    builder.method("_get", BagGet { get }).done();
+    builder.method("_set", BagSet { get_mut, reserved }).done();
+    builder.property_list.borrow_mut().push(move |this, list| {
+        if let Err(err) = this.map(|c, _| get(c).append_property_list(list)) {
+            godot_error!("gdnative-core: cannot read property bag: {err}");
+        }
+    });
+}
+
+struct BagGet<C> {
+    get: fn(&C) -> &PropertyBag,
+}
+
+impl<C: NativeClass> Method<C> for BagGet<C>
+where
+    C::UserData: Map,
+{
+    fn call(&self, this: TInstance<'_, C>, mut args: Varargs<'_>) -> Variant {
+        let name = match read_name(&mut args) {
+            Some(name) => name,
+            None => return Variant::nil(),
+        };
+
+        this.map(|c, _| (self.get)(c).get(&name).cloned())
+            .unwrap_or_else(|err| {
+                godot_error!("gdnative-core: cannot read property bag: {err}");
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    fn site() -> Option<Site<'static>> {
+        Some(godot_site!(PropertyBag::_get))
+    }
+}
+
+struct BagSet<C> {
+    get_mut: fn(&mut C) -> &mut PropertyBag,
+    reserved: HashSet<String>,
+}
+
+impl<C: NativeClass> Method<C> for BagSet<C>
+where
+    C::UserData: MapMut,
+{
+    fn call(&self, this: TInstance<'_, C>, mut args: Varargs<'_>) -> Variant {
+        let name = match read_name(&mut args) {
+            Some(name) => name,
+            None => return false.to_variant(),
+        };
+
+        if self.reserved.contains(&name) {
+            return false.to_variant();
+        }
+
+        let value = match args.read::<Variant>().get() {
+            Ok(value) => value,
+            Err(err) => {
+                err.with_site(Self::site().unwrap_or_default()).log_error();
+                return false.to_variant();
+            }
+        };
+
+        let result = this.map_mut(|c, owner| {
+            let bag = (self.get_mut)(c);
+
+            let list_changed = if value.is_nil() {
+                bag.remove(&name).is_some()
+            } else {
+                bag.insert(name, value).is_none()
+            };
+
+            if list_changed {
+                // SAFETY: `owner` is a valid object for the duration of the call.
+                unsafe {
+                    crate::private::property_list_changed_notify(owner.as_raw().sys().as_ptr());
+                }
+            }
+        });
+
+        match result {
+            Ok(()) => true.to_variant(),
+            Err(err) => {
+                godot_error!("gdnative-core: cannot write property bag: {err}");
+                false.to_variant()
+            }
+        }
+    }
+
+    fn site() -> Option<Site<'static>> {
+        Some(godot_site!(PropertyBag::_set))
+    }
+}
+
+fn read_name(args: &mut Varargs<'_>) -> Option<String> {
+    match args.read::<GodotString>().get() {
+        Ok(name) => Some(name.to_string()),
+        Err(err) => {
+            err.log_error();
+            None
+        }
+    }
+}
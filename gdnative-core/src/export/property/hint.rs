@@ -5,9 +5,10 @@ use std::ops::RangeInclusive;
 
 use crate::core_types::GodotString;
 use crate::core_types::VariantType;
+use crate::object::GodotObject;
 use crate::sys;
 
-use super::{Export, ExportInfo};
+use super::{Export, ExportInfo, PropertyUsage};
 
 /// Hints that an integer or float property should be within an inclusive range.
 ///
@@ -114,22 +115,75 @@ where
 ///
 /// let hint = EnumHint::new(vec!["Foo".into(), "Bar".into(), "Baz".into()]);
 /// ```
+///
+/// With explicit values for integer properties:
+///
+/// ```rust
+/// use gdnative_core::export::hint::{EnumHint, EnumHintEntry};
+///
+/// let hint = EnumHint::with_entries(vec![
+///     EnumHintEntry::with_value("Low".into(), 1),
+///     EnumHintEntry::with_value("High".into(), 10),
+/// ]);
+/// ```
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
 pub struct EnumHint {
-    values: Vec<String>,
+    entries: Vec<EnumHintEntry>,
 }
 
 impl EnumHint {
+    /// Creates a new `EnumHint` with the given keys. Values are assigned implicitly by position.
+    #[inline]
+    pub fn new(keys: Vec<String>) -> Self {
+        let entries = keys.into_iter().map(EnumHintEntry::new).collect();
+        EnumHint { entries }
+    }
+
+    /// Creates a new `EnumHint` from entries that may specify explicit values.
+    #[inline]
+    pub fn with_entries(entries: Vec<EnumHintEntry>) -> Self {
+        EnumHint { entries }
+    }
+
+    /// Creates a new `EnumHint` for [`IntHint::Flags`] from named bit masks, as used by
+    /// [`export_flags!`](crate::export_flags).
+    ///
+    /// The editor assigns flags to bits by their position in the list, so names are placed at
+    /// the index of their bit, with empty entries for unnamed bits. Masks that don't have exactly
+    /// one bit set, such as combinations of other flags, are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gdnative_core::export::hint::EnumHint;
+    ///
+    /// let hint = EnumHint::from_flags(&[("A", 0b001), ("C", 0b100), ("AC", 0b101)]);
+    /// assert_eq!(hint, EnumHint::new(vec!["A".into(), "".into(), "C".into()]));
+    /// ```
     #[inline]
-    pub fn new(values: Vec<String>) -> Self {
-        EnumHint { values }
+    pub fn from_flags(flags: &[(&str, u64)]) -> Self {
+        let mut keys = Vec::new();
+
+        for &(name, mask) in flags {
+            if mask.count_ones() != 1 {
+                continue;
+            }
+
+            let bit = mask.trailing_zeros() as usize;
+            if keys.len() <= bit {
+                keys.resize(bit + 1, String::new());
+            }
+            keys[bit] = name.to_owned();
+        }
+
+        EnumHint::new(keys)
     }
 
     /// Formats the hint as a Godot hint string.
     fn to_godot_hint_string(&self) -> GodotString {
         let mut s = String::new();
 
-        let mut iter = self.values.iter();
+        let mut iter = self.entries.iter();
 
         if let Some(first) = iter.next() {
             write!(s, "{first}").unwrap();
@@ -143,6 +197,41 @@ impl EnumHint {
     }
 }
 
+/// A single entry of an [`EnumHint`], with an optional explicit value.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EnumHintEntry {
+    key: String,
+    value: Option<i64>,
+}
+
+impl EnumHintEntry {
+    /// Creates an entry whose value is implied by its position in the list.
+    #[inline]
+    pub fn new(key: String) -> Self {
+        Self { key, value: None }
+    }
+
+    /// Creates an entry with an explicit value.
+    #[inline]
+    pub fn with_value(key: String, value: i64) -> Self {
+        Self {
+            key,
+            value: Some(value),
+        }
+    }
+}
+
+impl fmt::Display for EnumHintEntry {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key)?;
+        if let Some(value) = self.value {
+            write!(f, ":{value}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Possible hints for integers.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -194,6 +283,7 @@ where
             variant_type: VariantType::I64,
             hint_kind,
             hint_string,
+            usage: PropertyUsage::empty(),
         }
     }
 }
@@ -235,11 +325,26 @@ pub struct ExpEasingHint {
 }
 
 impl ExpEasingHint {
+    /// Creates a new `ExpEasingHint` for a plain ease-in curve.
     #[inline]
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Builder-style method that returns `self` with the curve flipped horizontally.
+    #[inline]
+    pub fn attenuation(mut self) -> Self {
+        self.is_attenuation = true;
+        self
+    }
+
+    /// Builder-style method that returns `self` with in/out easing included.
+    #[inline]
+    pub fn in_out(mut self) -> Self {
+        self.is_in_out = true;
+        self
+    }
+
     /// Formats the hint as a Godot hint string.
     fn to_godot_hint_string(self) -> GodotString {
         let mut s = String::new();
@@ -294,6 +399,7 @@ where
             variant_type: VariantType::F64,
             hint_kind,
             hint_string,
+            usage: PropertyUsage::empty(),
         }
     }
 }
@@ -346,6 +452,64 @@ pub enum StringHint {
 }
 
 impl StringHint {
+    /// Hints that a string property is a path to a file, optionally restricted by filters
+    /// such as `"*.png"` or `"*.tscn, *.scn ; Scenes"`.
+    #[inline]
+    pub fn file<I, S>(filters: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        StringHint::File(Self::filters(filters))
+    }
+
+    /// Hints that a string property is an absolute path to a file outside the project folder,
+    /// optionally restricted by filters. See [`StringHint::file`].
+    #[inline]
+    pub fn global_file<I, S>(filters: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        StringHint::GlobalFile(Self::filters(filters))
+    }
+
+    /// Hints that a string property is a path to a directory. Godot doesn't support filters
+    /// for directories.
+    #[inline]
+    pub fn dir() -> Self {
+        StringHint::Dir
+    }
+
+    /// Hints that a string property is an absolute path to a directory outside the project
+    /// folder.
+    #[inline]
+    pub fn global_dir() -> Self {
+        StringHint::GlobalDir
+    }
+
+    /// Hints that a string property is text with line breaks.
+    #[inline]
+    pub fn multiline() -> Self {
+        StringHint::Multiline
+    }
+
+    /// Hints that a string property should show `placeholder` on its input field while empty.
+    #[inline]
+    pub fn placeholder(placeholder: impl Into<String>) -> Self {
+        StringHint::Placeholder {
+            placeholder: placeholder.into(),
+        }
+    }
+
+    fn filters<I, S>(filters: I) -> EnumHint
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        EnumHint::new(filters.into_iter().map(Into::into).collect())
+    }
+
     #[inline]
     pub fn export_info(self) -> ExportInfo {
         use StringHint as SH;
@@ -370,10 +534,18 @@ impl StringHint {
             variant_type: VariantType::GodotString,
             hint_kind,
             hint_string,
+            usage: PropertyUsage::empty(),
         }
     }
 }
 
+impl From<EnumHint> for StringHint {
+    #[inline]
+    fn from(hint: EnumHint) -> Self {
+        Self::Enum(hint)
+    }
+}
+
 /// Possible hints for `Color`.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -391,6 +563,65 @@ impl ColorHint {
                 ColorHint::NoAlpha => sys::godot_property_hint_GODOT_PROPERTY_HINT_COLOR_NO_ALPHA,
             },
             hint_string: GodotString::new(),
+            usage: PropertyUsage::empty(),
+        }
+    }
+}
+
+/// Hints for properties holding objects, such as `Option<Ref<Texture>>`.
+///
+/// Without a hint, the inspector accepts resources of the exported type. A `ResourceHint` can
+/// restrict the property to one or more subclasses instead, e.g. to accept only
+/// `StreamTexture`s and `ImageTexture`s for a `Ref<Texture>`. The file dialog of the inspector
+/// only lists the files that can be loaded as one of the types.
+///
+/// # Examples
+///
+/// ```rust
+/// use gdnative::api::{ImageTexture, StreamTexture};
+/// use gdnative::export::hint::ResourceHint;
+///
+/// let hint = ResourceHint::of::<StreamTexture>().or_of::<ImageTexture>();
+/// let hint = ResourceHint::new(["StreamTexture", "ImageTexture"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResourceHint {
+    types: Vec<String>,
+}
+
+impl ResourceHint {
+    /// Accepts resources of any of the types named in `types`.
+    #[inline]
+    pub fn new<I, S>(types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ResourceHint {
+            types: types.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Accepts resources of type `T`.
+    #[inline]
+    pub fn of<T: GodotObject>() -> Self {
+        Self::new([T::class_name()])
+    }
+
+    /// Also accepts resources of type `T`.
+    #[inline]
+    pub fn or_of<T: GodotObject>(mut self) -> Self {
+        self.types.push(T::class_name().into());
+        self
+    }
+
+    #[inline]
+    pub fn export_info(self) -> ExportInfo {
+        ExportInfo {
+            variant_type: VariantType::Object,
+            hint_kind: sys::godot_property_hint_GODOT_PROPERTY_HINT_RESOURCE_TYPE,
+            hint_string: self.types.join(",").into(),
+            usage: PropertyUsage::empty(),
         }
     }
 }
@@ -459,12 +690,14 @@ impl ArrayHint {
                 variant_type: VariantType::VariantArray,
                 hint_kind: sys::godot_property_hint_GODOT_PROPERTY_HINT_TYPE_STRING,
                 hint_string,
+                usage: PropertyUsage::empty(),
             }
         } else {
             ExportInfo {
                 variant_type: VariantType::VariantArray,
                 hint_kind: sys::godot_property_hint_GODOT_PROPERTY_HINT_NONE,
                 hint_string: GodotString::new(),
+                usage: PropertyUsage::empty(),
             }
         }
     }
@@ -0,0 +1,174 @@
+//! Property list entries that depend on the state of an instance.
+
+use std::fmt;
+
+use crate::core_types::{Dictionary, OwnedToVariant, Variant, VariantArray, VariantType};
+use crate::export::user_data::Map;
+use crate::export::{ClassBuilder, Method, NativeClass, PropertyUsage, Varargs};
+use crate::object::ownership::Unique;
+use crate::object::TInstance;
+
+// `TInstance` requires `C: NativeClass`, which the builders don't, so closures are stored as
+// trait objects of these traits instead of `dyn Fn`.
+
+trait Source<C>: Send + Sync {
+    fn append(&self, this: &TInstance<'_, C>, list: &VariantArray<Unique>)
+    where
+        C: NativeClass;
+}
+
+impl<C: NativeClass, F> Source<C> for F
+where
+    F: Fn(&TInstance<'_, C>, &VariantArray<Unique>) + Send + Sync,
+{
+    fn append(&self, this: &TInstance<'_, C>, list: &VariantArray<Unique>) {
+        self(this, list)
+    }
+}
+
+trait Test<C>: Send + Sync {
+    fn test(&self, this: &TInstance<'_, C>) -> bool
+    where
+        C: NativeClass;
+}
+
+impl<C: NativeClass, F> Test<C> for F
+where
+    F: Fn(&TInstance<'_, C>) -> bool + Send + Sync,
+{
+    fn test(&self, this: &TInstance<'_, C>) -> bool {
+        self(this)
+    }
+}
+
+/// Sources of the entries returned by `_get_property_list`, collected during registration so
+/// that a single method can be registered for all of them.
+pub(crate) struct PropertyList<C> {
+    sources: Vec<Box<dyn Source<C>>>,
+}
+
+impl<C: NativeClass> PropertyList<C> {
+    pub(crate) fn push<F>(&mut self, source: F)
+    where
+        F: Fn(&TInstance<'_, C>, &VariantArray<Unique>) + Send + Sync + 'static,
+    {
+        self.sources.push(Box::new(source));
+    }
+
+    /// Returns `true` if no entries were added.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Registers `_get_property_list` if there are any sources.
+    pub(crate) fn register(self, builder: &ClassBuilder<C>) {
+        if !self.sources.is_empty() {
+            builder
+                .method(
+                    "_get_property_list",
+                    GetPropertyList {
+                        sources: self.sources,
+                    },
+                )
+                .done();
+        }
+    }
+}
+
+impl<C> Default for PropertyList<C> {
+    fn default() -> Self {
+        PropertyList {
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl<C> fmt::Debug for PropertyList<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyList")
+            .field("sources", &self.sources.len())
+            .finish()
+    }
+}
+
+/// Condition for showing a property in the inspector. See [`PropertyBuilder::with_visible_if`].
+///
+/// [`PropertyBuilder::with_visible_if`]: crate::export::PropertyBuilder::with_visible_if
+pub(crate) struct Condition<C> {
+    test: Box<dyn Test<C>>,
+}
+
+impl<C: NativeClass> Condition<C> {
+    pub(crate) fn new<F>(condition: F) -> Self
+    where
+        C::UserData: Map,
+        F: Fn(&C) -> bool + Send + Sync + 'static,
+    {
+        Condition {
+            test: Box::new(move |this: &TInstance<'_, C>| {
+                this.map(|c, _| condition(c)).unwrap_or_else(|err| {
+                    godot_error!("gdnative-core: cannot evaluate property visibility: {err}");
+                    false
+                })
+            }),
+        }
+    }
+
+    pub(crate) fn and(self, other: Self) -> Self {
+        Condition {
+            test: Box::new(move |this: &TInstance<'_, C>| {
+                self.test.test(this) && other.test.test(this)
+            }),
+        }
+    }
+}
+
+impl<C> fmt::Debug for Condition<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Condition")
+    }
+}
+
+/// Adds the editor entry of a property to the list of `builder`, for instances where
+/// `condition` holds.
+pub(crate) fn push_conditional<C: NativeClass>(
+    builder: &ClassBuilder<C>,
+    name: &str,
+    variant_type: VariantType,
+    hint_kind: sys::godot_property_hint,
+    hint_string: String,
+    usage: PropertyUsage,
+    condition: Condition<C>,
+) {
+    let name = name.to_owned();
+
+    // The registered property is hidden from the inspector, and this entry doesn't have the
+    // storage flags, so that the value is still saved exactly once.
+    let usage = (usage - PropertyUsage::NOEDITOR) | PropertyUsage::EDITOR;
+
+    builder.property_list.borrow_mut().push(move |this, list| {
+        if condition.test.test(this) {
+            let entry = Dictionary::new();
+            entry.insert("name", &name);
+            entry.insert("type", variant_type as u32);
+            entry.insert("hint", hint_kind as u32);
+            entry.insert("hint_string", &hint_string);
+            entry.insert("usage", usage.bits());
+            list.push(entry.owned_to_variant());
+        }
+    });
+}
+
+struct GetPropertyList<C> {
+    sources: Vec<Box<dyn Source<C>>>,
+}
+
+impl<C: NativeClass> Method<C> for GetPropertyList<C> {
+    fn call(&self, this: TInstance<'_, C>, _args: Varargs<'_>) -> Variant {
+        let list = VariantArray::new();
+        for source in &self.sources {
+            source.append(&this, &list);
+        }
+        list.owned_to_variant()
+    }
+}
@@ -0,0 +1,145 @@
+//! Properties described by a schema built at runtime.
+
+use std::rc::Rc;
+
+use crate::core_types::{FromVariant, FromVariantError, ToVariant, Variant};
+use crate::export::user_data::{Map, MapMut};
+use crate::export::{ClassBuilder, Export, ExportInfo, NativeClass, PropertyUsage, RpcMode};
+use crate::object::TRef;
+
+/// Description of a property that is only known at runtime, such as one loaded from a
+/// configuration file. See [`ClassBuilder::properties_from_schema`].
+#[derive(Debug)]
+pub struct PropertyDefinition {
+    /// Property name.
+    pub name: String,
+
+    /// Default value, shown in the inspector and used to decide whether the value is saved.
+    pub default: Variant,
+
+    /// Metadata and UI hints about exporting, e.g. property type.
+    pub export_info: ExportInfo,
+
+    /// In which context the property is used.
+    pub usage: PropertyUsage,
+
+    /// RPC mode of the property setter.
+    pub rpc_mode: RpcMode,
+}
+
+impl PropertyDefinition {
+    /// Creates a property without a default value, using `export_info` for its type and hints.
+    ///
+    /// Usage flags declared by `export_info` are added to [`PropertyUsage::DEFAULT`].
+    #[inline]
+    pub fn new(name: &str, export_info: ExportInfo) -> Self {
+        let usage = PropertyUsage::DEFAULT | export_info.usage;
+        PropertyDefinition {
+            name: name.into(),
+            default: Variant::nil(),
+            export_info,
+            usage,
+            rpc_mode: RpcMode::Disabled,
+        }
+    }
+
+    /// Creates a property of the exported type `T`, with an optional hint.
+    #[inline]
+    pub fn of<T: Export>(name: &str, hint: Option<T::Hint>) -> Self {
+        Self::new(name, T::export_info(hint))
+    }
+
+    /// Sets the default value of the property.
+    #[inline]
+    pub fn with_default(mut self, default: Variant) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// Sets the usage flags of the property.
+    #[inline]
+    pub fn with_usage(mut self, usage: PropertyUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Sets the RPC mode of the property setter.
+    #[inline]
+    pub fn with_rpc_mode(mut self, rpc_mode: RpcMode) -> Self {
+        self.rpc_mode = rpc_mode;
+        self
+    }
+}
+
+/// Registers the properties in `schema`, dispatching accesses to `get` and `set`.
+pub(crate) fn register<C, G, S>(
+    builder: &ClassBuilder<C>,
+    schema: impl IntoIterator<Item = PropertyDefinition>,
+    get: G,
+    set: S,
+) where
+    C: NativeClass,
+    C::UserData: Map + MapMut,
+    G: Fn(&C, TRef<'_, C::Base>, &str) -> Variant + 'static,
+    S: Fn(&mut C, TRef<'_, C::Base>, &str, Variant) + 'static,
+{
+    let get = Rc::new(get);
+    let set = Rc::new(set);
+
+    for definition in schema {
+        let PropertyDefinition {
+            name,
+            default,
+            export_info,
+            usage,
+            rpc_mode,
+        } = definition;
+
+        let get = {
+            let get = get.clone();
+            let name = name.clone();
+            move |this: &C, base: TRef<'_, C::Base>| Dynamic(get(this, base, &name))
+        };
+
+        let set = {
+            let set = set.clone();
+            let name = name.clone();
+            move |this: &mut C, base: TRef<'_, C::Base>, value: Dynamic| {
+                set(this, base, &name, value.0)
+            }
+        };
+
+        builder
+            .property::<Dynamic>(&name)
+            .with_hint(export_info)
+            .with_default(Dynamic(default))
+            .with_usage(usage)
+            .with_rpc_mode(rpc_mode)
+            .with_getter(get)
+            .with_setter(set)
+            .done();
+    }
+}
+
+/// Property value whose type is only known at runtime, from the `ExportInfo` given as hint.
+struct Dynamic(Variant);
+
+impl ToVariant for Dynamic {
+    fn to_variant(&self) -> Variant {
+        self.0.clone()
+    }
+}
+
+impl FromVariant for Dynamic {
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        Ok(Dynamic(variant.clone()))
+    }
+}
+
+impl Export for Dynamic {
+    type Hint = ExportInfo;
+
+    fn export_info(hint: Option<ExportInfo>) -> ExportInfo {
+        hint.expect("export info should be given as hint")
+    }
+}
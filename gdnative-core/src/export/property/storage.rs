@@ -0,0 +1,194 @@
+//! Versioned state that is saved to scenes and resources.
+
+use std::rc::Rc;
+
+use crate::core_types::{Dictionary, FromVariant, FromVariantError, ToVariant, Variant};
+use crate::export::class_registry;
+use crate::export::user_data::{Map, MapMut};
+use crate::export::{ClassBuilder, NativeClass, PropertyUsage};
+use crate::object::ownership::Unique;
+use crate::object::TRef;
+
+/// Name of the hidden property holding the saved state.
+pub const STORAGE_PROPERTY: &str = "__gdnative_storage";
+
+/// Function that converts the saved fields of an older version of a class to the current one.
+///
+/// It's called with the version the fields were saved with, and returns the fields as expected
+/// by the current version. Fields are keyed by their names.
+pub type Migration = fn(from_version: u32, fields: Dictionary<Unique>) -> Dictionary<Unique>;
+
+type Getter<C> = Box<dyn Fn(&C) -> Variant>;
+type Setter<C> = Box<dyn Fn(&mut C, &Variant) -> Result<(), FromVariantError>>;
+
+struct Field<C> {
+    name: String,
+    get: Getter<C>,
+    set: Setter<C>,
+}
+
+/// Builder for the versioned state of a class, returned by [`ClassBuilder::storage`].
+///
+/// The fields are saved together as a single property, along with the version of the class.
+/// When a scene or resource saved with an older version is loaded, the fields are passed
+/// through the migration function first, if any. Fields that are missing or fail to convert
+/// keep their values from the constructor, and are reported as errors instead of being
+/// silently dropped. State saved with a newer version is ignored.
+#[must_use = "StorageBuilder left unbuilt -- did you forget to call done()?"]
+pub struct StorageBuilder<'a, C> {
+    builder: &'a ClassBuilder<C>,
+    version: u32,
+    migration: Option<Migration>,
+    fields: Vec<Field<C>>,
+}
+
+impl<'a, C> StorageBuilder<'a, C>
+where
+    C: NativeClass,
+    C::UserData: Map + MapMut,
+{
+    #[inline]
+    pub(crate) fn new(builder: &'a ClassBuilder<C>, version: u32) -> Self {
+        StorageBuilder {
+            builder,
+            version,
+            migration: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Sets the function that converts fields saved with older versions.
+    #[inline]
+    pub fn with_migration(mut self, migration: Migration) -> Self {
+        self.migration = Some(migration);
+        self
+    }
+
+    /// Adds a field to the saved state. `get` and `get_mut` return the field of an instance.
+    #[inline]
+    pub fn with_field<T>(
+        mut self,
+        name: &str,
+        get: fn(&C) -> &T,
+        get_mut: fn(&mut C) -> &mut T,
+    ) -> Self
+    where
+        T: ToVariant + FromVariant + 'static,
+    {
+        self.fields.push(Field {
+            name: name.to_owned(),
+            get: Box::new(move |this| get(this).to_variant()),
+            set: Box::new(move |this, value| {
+                *get_mut(this) = T::from_variant(value)?;
+                Ok(())
+            }),
+        });
+        self
+    }
+
+    /// Registers the property holding the saved state.
+    #[inline]
+    pub fn done(self) {
+        let StorageBuilder {
+            builder,
+            version,
+            migration,
+            fields,
+        } = self;
+
+        let fields = Rc::new(fields);
+
+        let get = {
+            let fields = fields.clone();
+            move |this: &C, _base: TRef<'_, C::Base>| save(this, version, &fields)
+        };
+
+        let set = move |this: &mut C, _base: TRef<'_, C::Base>, state: Dictionary| {
+            load(this, version, migration, &fields, &state)
+        };
+
+        builder
+            .property::<Dictionary>(STORAGE_PROPERTY)
+            .with_usage(PropertyUsage::storage_only())
+            .with_getter(get)
+            .with_setter(set)
+            .done();
+    }
+}
+
+fn save<C>(this: &C, version: u32, fields: &[Field<C>]) -> Dictionary {
+    let values = fields
+        .iter()
+        .map(|field| (field.name.as_str(), (field.get)(this)))
+        .collect::<Dictionary<Unique>>();
+
+    let state = Dictionary::new();
+    state.insert("version", version);
+    state.insert("fields", values);
+    state.into_shared()
+}
+
+fn load<C: NativeClass>(
+    this: &mut C,
+    version: u32,
+    migration: Option<Migration>,
+    fields: &[Field<C>],
+    state: &Dictionary,
+) {
+    let class = class_registry::class_name_or_default::<C>();
+
+    let saved_version = state
+        .get("version")
+        .and_then(|v| u32::from_variant(&v).ok());
+    let values = state
+        .get("fields")
+        .and_then(|v| Dictionary::from_variant(&v).ok());
+
+    let (Some(saved_version), Some(values)) = (saved_version, values) else {
+        godot_error!("gdnative-core: saved state of {class} is malformed, ignoring it");
+        return;
+    };
+
+    let values = match saved_version.cmp(&version) {
+        std::cmp::Ordering::Equal => values.duplicate(),
+        std::cmp::Ordering::Less => match migration {
+            Some(migration) => migration(saved_version, values.duplicate()),
+            None => {
+                godot_warn!(
+                    "gdnative-core: state of {class} was saved with version {saved_version}, \
+                     but no migration to version {version} is defined; loading it as is"
+                );
+                values.duplicate()
+            }
+        },
+        std::cmp::Ordering::Greater => {
+            godot_error!(
+                "gdnative-core: state of {class} was saved with version {saved_version}, \
+                 which is newer than the current version {version}; ignoring it"
+            );
+            return;
+        }
+    };
+
+    for field in fields {
+        match values.get(field.name.as_str()) {
+            Some(value) => {
+                if let Err(err) = (field.set)(this, &value) {
+                    godot_error!(
+                        "gdnative-core: failed to load field `{}` of {class}: {err}",
+                        field.name
+                    );
+                }
+                values.erase(field.name.as_str());
+            }
+            None => godot_error!(
+                "gdnative-core: saved state of {class} is missing field `{}`",
+                field.name
+            ),
+        }
+    }
+
+    for (name, _) in values.iter() {
+        godot_warn!("gdnative-core: saved state of {class} has unknown field {name}, dropping it");
+    }
+}
@@ -0,0 +1,102 @@
+//! Reporting of values rejected by property setters.
+
+use std::fmt;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::object::InstanceId;
+
+type Handler = Arc<dyn Fn(&ValidationError) + Send + Sync>;
+
+static HANDLER: Lazy<RwLock<Option<Handler>>> = Lazy::new(RwLock::default);
+
+/// Return types accepted from property setters.
+///
+/// Setters can either return `()`, or `Result<(), E>` to reject values that fail validation.
+/// When a setter returns an error, the property is expected to keep its old value, and the
+/// error is reported to the [validation handler](set_validation_handler).
+pub trait SetterResult {
+    #[doc(hidden)]
+    fn into_validation(self) -> Result<(), String>;
+}
+
+impl SetterResult for () {
+    #[inline]
+    fn into_validation(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<E: fmt::Display> SetterResult for Result<(), E> {
+    #[inline]
+    fn into_validation(self) -> Result<(), String> {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// A property value rejected by a setter.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ValidationError {
+    /// Name of the class the property belongs to.
+    pub class_name: String,
+    /// Name of the property.
+    pub property_name: String,
+    /// ID of the instance that rejected the value.
+    pub instance_id: InstanceId,
+    /// The error returned by the setter.
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value for property `{}` of {}: {}",
+            self.property_name, self.class_name, self.message
+        )
+    }
+}
+
+/// Sets the function that is called whenever a property setter rejects a value, replacing any
+/// previous handler. By default, rejected values are logged as warnings.
+///
+/// Tool scripts and editor plugins can use this to surface validation errors in the editor,
+/// for example as notifications:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+/// use gdnative::export::set_validation_handler;
+///
+/// set_validation_handler(|err| {
+///     godot_warn!("{err}");
+///     // e.g. queue a notification for the inspector plugin
+/// });
+/// ```
+///
+/// The handler is called on the thread where the property was set, and is reset when the
+/// library is terminated.
+#[inline]
+pub fn set_validation_handler<F>(handler: F)
+where
+    F: Fn(&ValidationError) + Send + Sync + 'static,
+{
+    *HANDLER.write() = Some(Arc::new(handler));
+}
+
+/// Reports a rejected value to the validation handler.
+pub(crate) fn report(err: ValidationError) {
+    // The handler may replace itself, so the lock can't be held while it runs.
+    let handler = HANDLER.read().clone();
+    match handler {
+        Some(handler) => handler(&err),
+        None => godot_warn!("{err}"),
+    }
+}
+
+pub(crate) fn cleanup() {
+    *HANDLER.write() = None;
+}
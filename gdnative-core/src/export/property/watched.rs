@@ -0,0 +1,272 @@
+//! Change detection for properties with collection values.
+
+use std::ops::Deref;
+
+use crate::core_types::variant::VariantDispatch;
+use crate::core_types::{FromVariant, FromVariantError, ToVariant, Variant};
+use crate::export::{Export, ExportInfo};
+
+/// Property value that remembers a deep copy of its contents, to detect changes to collections
+/// that are modified in place.
+///
+/// `VariantArray` and `Dictionary` are shared by reference. When an element of an exported
+/// collection is edited in the inspector, or modified from GDScript, the collection held by the
+/// Rust instance changes without being replaced, so comparing the old and new values in a setter
+/// doesn't tell whether anything changed. `Watched` compares the contents against a copy taken
+/// when the changes were last taken, including those of nested arrays and dictionaries.
+///
+/// Changes are taken with [`take_changed`](Self::take_changed), or with [`set`](Self::set) in a
+/// custom setter, which the editor calls after editing an element:
+///
+/// ```
+/// use gdnative::export::Watched;
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// struct Spawner {
+///     #[property(set = "Self::set_waypoints")]
+///     waypoints: Watched<VariantArray>,
+/// }
+///
+/// #[methods]
+/// impl Spawner {
+///     fn new(_base: &Node) -> Self {
+///         Spawner {
+///             waypoints: Watched::new(VariantArray::new_shared()),
+///         }
+///     }
+///
+///     fn set_waypoints(&mut self, base: TRef<Node>, waypoints: Watched<VariantArray>) {
+///         if self.waypoints.set(waypoints.into_inner()) {
+///             self.rebuild_path(&base);
+///         }
+///     }
+///
+///     #[method]
+///     fn _process(&mut self, #[base] base: &Node, _delta: f64) {
+///         // Catches modifications that don't go through the setter, e.g. `append` in GDScript.
+///         if self.waypoints.take_changed() {
+///             self.rebuild_path(base);
+///         }
+///     }
+///
+///     fn rebuild_path(&self, _base: &Node) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Other values are compared like the keys of a dictionary, so NaNs are equal to each other.
+/// Objects are compared by identity, so changes to the properties of resources held by the
+/// collection are not detected. Collections that contain themselves are supported.
+///
+/// Since `Variant` is not `Send`, types containing a `Watched` can only be used with
+/// thread-local user data wrappers, such as the default `LocalCellData`.
+#[derive(Debug)]
+pub struct Watched<T> {
+    value: T,
+    snapshot: Snapshot,
+}
+
+impl<T: ToVariant> Watched<T> {
+    /// Wraps `value`, taking a copy of its current contents.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        let snapshot = Snapshot::of(&value.to_variant());
+        Watched { value, snapshot }
+    }
+
+    /// Replaces the value, and returns whether its contents differ from those when the changes
+    /// were last taken. The contents are considered unchanged afterwards.
+    #[inline]
+    pub fn set(&mut self, value: T) -> bool {
+        self.value = value;
+        self.take_changed()
+    }
+
+    /// Returns whether the contents of the value differ from those when the changes were last
+    /// taken. The contents are considered unchanged afterwards.
+    #[inline]
+    pub fn take_changed(&mut self) -> bool {
+        let current = self.value.to_variant();
+        if self.snapshot.matches(&current) {
+            return false;
+        }
+
+        self.snapshot = Snapshot::of(&current);
+        true
+    }
+
+    /// Returns whether the contents of the value differ from those when the changes were last
+    /// taken, without taking them.
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        !self.snapshot.matches(&self.value.to_variant())
+    }
+
+    /// Returns a reference to the value.
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwraps the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Watched<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: ToVariant + Clone> Clone for Watched<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Watched {
+            value: self.value.clone(),
+            snapshot: self.snapshot.clone(),
+        }
+    }
+}
+
+impl<T: ToVariant + Default> Default for Watched<T> {
+    #[inline]
+    fn default() -> Self {
+        Watched::new(T::default())
+    }
+}
+
+impl<T: ToVariant> ToVariant for Watched<T> {
+    #[inline]
+    fn to_variant(&self) -> Variant {
+        self.value.to_variant()
+    }
+}
+
+impl<T: ToVariant + FromVariant> FromVariant for Watched<T> {
+    #[inline]
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        T::from_variant(variant).map(Watched::new)
+    }
+}
+
+impl<T: ToVariant + Export> Export for Watched<T> {
+    type Hint = T::Hint;
+
+    #[inline]
+    fn export_info(hint: Option<Self::Hint>) -> ExportInfo {
+        T::export_info(hint)
+    }
+}
+
+/// Copy of the contents of a value, including those of nested arrays and dictionaries.
+#[derive(Clone, Debug)]
+enum Snapshot {
+    Array(Vec<Snapshot>),
+    Dictionary(Vec<(Variant, Snapshot)>),
+    /// Reference to the collection at the given depth of the path from the root, in collections
+    /// that contain themselves.
+    Cycle(usize),
+    Value(Variant),
+}
+
+impl Snapshot {
+    fn of(value: &Variant) -> Self {
+        Self::of_nested(value, &mut Vec::new())
+    }
+
+    /// `path` contains the identities of the collections from the root to `value`.
+    fn of_nested(value: &Variant, path: &mut Vec<usize>) -> Self {
+        match value.dispatch() {
+            VariantDispatch::VariantArray(array) => {
+                Self::collection(identity(array.sys()), path, |path| {
+                    Snapshot::Array(
+                        array
+                            .iter()
+                            .map(|element| Self::of_nested(&element, path))
+                            .collect(),
+                    )
+                })
+            }
+            VariantDispatch::Dictionary(dict) => {
+                Self::collection(identity(dict.sys()), path, |path| {
+                    Snapshot::Dictionary(
+                        dict.iter()
+                            .map(|(key, value)| (key, Self::of_nested(&value, path)))
+                            .collect(),
+                    )
+                })
+            }
+            _ => Snapshot::Value(value.clone()),
+        }
+    }
+
+    fn collection(
+        id: usize,
+        path: &mut Vec<usize>,
+        contents: impl FnOnce(&mut Vec<usize>) -> Self,
+    ) -> Self {
+        if let Some(depth) = path.iter().position(|&other| other == id) {
+            return Snapshot::Cycle(depth);
+        }
+
+        path.push(id);
+        let snapshot = contents(path);
+        path.pop();
+        snapshot
+    }
+
+    /// Returns whether `value` has the contents of the snapshot.
+    fn matches(&self, value: &Variant) -> bool {
+        self.matches_nested(value, &mut Vec::new())
+    }
+
+    /// The recursion is bounded by the depth of the snapshot, even if `value` contains itself.
+    fn matches_nested(&self, value: &Variant, path: &mut Vec<usize>) -> bool {
+        match (self, value.dispatch()) {
+            (Snapshot::Value(expected), _) => expected.hash_compare(value),
+            (Snapshot::Cycle(depth), VariantDispatch::VariantArray(array)) => {
+                path.get(*depth) == Some(&identity(array.sys()))
+            }
+            (Snapshot::Cycle(depth), VariantDispatch::Dictionary(dict)) => {
+                path.get(*depth) == Some(&identity(dict.sys()))
+            }
+            (Snapshot::Array(expected), VariantDispatch::VariantArray(array)) => {
+                path.push(identity(array.sys()));
+                let matches = expected.len() == array.len() as usize
+                    && expected
+                        .iter()
+                        .zip(array.iter())
+                        .all(|(expected, element)| expected.matches_nested(&element, path));
+                path.pop();
+                matches
+            }
+            (Snapshot::Dictionary(expected), VariantDispatch::Dictionary(dict)) => {
+                path.push(identity(dict.sys()));
+                let matches = expected.len() == dict.len() as usize
+                    && expected.iter().all(|(key, expected)| {
+                        dict.get(key)
+                            .map_or(false, |value| expected.matches_nested(&value, path))
+                    });
+                path.pop();
+                matches
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the address of the data that is shared by the references to an array or dictionary,
+/// which identifies it.
+fn identity<T>(sys: *const T) -> usize {
+    // SAFETY: `godot_array` and `godot_dictionary` consist of a pointer to the shared data.
+    unsafe { std::ptr::read_unaligned(sys as *const usize) }
+}
@@ -1,5 +1,9 @@
+// For the `ConnectFlags` bitflags declaration. The attribute doesn't work above the macro
+// invocation.
+#![allow(clippy::unnecessary_cast)]
+
 use crate::core_types::{GodotString, Variant, VariantType};
-use crate::export::{ClassBuilder, ExportInfo, NativeClass, PropertyUsage};
+use crate::export::{ClassBuilder, Export, ExportInfo, NativeClass, PropertyUsage};
 
 /// Class to construct a signal. Make sure to call [`Self::done()`] in the end.
 ///
@@ -12,6 +16,8 @@ pub struct SignalBuilder<'a, C> {
     class_builder: &'a ClassBuilder<C>,
     name: GodotString,
     args: Vec<SignalParam>,
+    documentation: Option<GodotString>,
+    default_flags: ConnectFlags,
 }
 
 impl<'a, C: NativeClass> SignalBuilder<'a, C> {
@@ -20,6 +26,8 @@ impl<'a, C: NativeClass> SignalBuilder<'a, C> {
             class_builder,
             name: signal_name,
             args: vec![],
+            documentation: None,
+            default_flags: ConnectFlags::empty(),
         }
     }
 
@@ -69,6 +77,15 @@ impl<'a, C: NativeClass> SignalBuilder<'a, C> {
         })
     }
 
+    /// Add a parameter for the signal with a name, using the export metadata of `T`.
+    ///
+    /// Unlike [`Self::with_param`], this also carries property hints such as numeric ranges or
+    /// resource types, which are shown in the editor's node dock.
+    #[inline]
+    pub fn with_param_export<T: Export>(self, parameter_name: &str, hint: Option<T::Hint>) -> Self {
+        self.with_param_custom(SignalParam::new(parameter_name, T::export_info(hint)))
+    }
+
     /// Add a parameter for the signal, manually configured.
     #[inline]
     pub fn with_param_custom(mut self, parameter: SignalParam) -> Self {
@@ -76,12 +93,40 @@ impl<'a, C: NativeClass> SignalBuilder<'a, C> {
         self
     }
 
+    /// Set the documentation shown for the signal in the editor.
+    #[inline]
+    pub fn with_documentation(mut self, documentation: &str) -> Self {
+        self.documentation = Some(documentation.into());
+        self
+    }
+
+    /// Set the flags that listeners are expected to connect to this signal with.
+    ///
+    /// Godot does not apply these flags automatically: they are only listed in the signal's
+    /// documentation, as a hint to users calling `Object.connect`.
+    #[inline]
+    pub fn with_default_flags(mut self, flags: ConnectFlags) -> Self {
+        self.default_flags = flags;
+        self
+    }
+
     /// Finish registering the signal.
     #[inline]
     pub fn done(self) {
+        let documentation = if self.default_flags.is_empty() {
+            self.documentation
+        } else {
+            let flags = format!("Default connect flags: {}.", self.default_flags.names());
+            Some(match self.documentation {
+                Some(doc) => GodotString::from(format!("{doc}\n\n{flags}")),
+                None => GodotString::from(flags),
+            })
+        };
+
         self.class_builder.add_signal(Signal {
             name: self.name,
             args: self.args,
+            documentation,
         });
     }
 }
@@ -89,6 +134,7 @@ impl<'a, C: NativeClass> SignalBuilder<'a, C> {
 pub(crate) struct Signal {
     pub name: GodotString,
     pub args: Vec<SignalParam>,
+    pub documentation: Option<GodotString>,
 }
 
 /// Parameter in a signal declaration.
@@ -107,3 +153,67 @@ pub struct SignalParam {
     /// In which context the signal parameter is used.
     pub usage: PropertyUsage,
 }
+
+impl SignalParam {
+    /// Creates a parameter without a default value, using `export_info` for its type and hints.
+    ///
+    /// Usage flags declared by `export_info` are added to [`PropertyUsage::DEFAULT`].
+    #[inline]
+    pub fn new(name: &str, export_info: ExportInfo) -> Self {
+        let usage = PropertyUsage::DEFAULT | export_info.usage;
+        SignalParam {
+            name: name.into(),
+            default: Variant::nil(),
+            export_info,
+            usage,
+        }
+    }
+
+    /// Sets the default value of the parameter.
+    #[inline]
+    pub fn with_default(mut self, default: Variant) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// Sets the usage flags of the parameter.
+    #[inline]
+    pub fn with_usage(mut self, usage: PropertyUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags used when connecting to a signal, mirroring `Object.ConnectFlags`.
+    pub struct ConnectFlags: u32 {
+        /// Listeners are called at idle time instead of immediately.
+        const DEFERRED = 1;
+        /// The connection is saved along with the scene.
+        const PERSIST = 2;
+        /// The connection is removed after the first emission.
+        const ONESHOT = 4;
+        /// The connection can be made multiple times, and needs as many disconnects.
+        const REFERENCE_COUNTED = 8;
+    }
+}
+
+impl ConnectFlags {
+    /// Returns the GDScript names of the set flags, e.g. `CONNECT_DEFERRED | CONNECT_ONESHOT`.
+    #[inline]
+    pub fn names(self) -> String {
+        let names = [
+            (Self::DEFERRED, "CONNECT_DEFERRED"),
+            (Self::PERSIST, "CONNECT_PERSIST"),
+            (Self::ONESHOT, "CONNECT_ONESHOT"),
+            (Self::REFERENCE_COUNTED, "CONNECT_REFERENCE_COUNTED"),
+        ];
+
+        names
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
@@ -32,6 +32,10 @@
 //! ...or, if you are implementing `NativeScript` manually, set the `UserData` associated type
 //! to the type you choose.
 //!
+//! With `#[user_data(auto)]`, the derive macro chooses between `ArcData<T>`, `MutexData<T>` and
+//! `DefaultUserData<T>` based on whether the fields are `Arc`s. See the documentation of the
+//! `NativeClass` derive macro for details.
+//!
 //! ## Which wrapper to use?
 //!
 //! ### Use a `MutexData<T>` when:
@@ -52,6 +56,15 @@
 //! - All your exported methods take `&self`.
 //! - Your `NativeClass` type is `Send + Sync`.
 //!
+//! ### Use a `OnceData<T>` when:
+//!
+//! - Your `NativeClass` instance is fully configured in its constructor, and never changes
+//!   afterwards.
+//! - Your `NativeClass` type is `Send + Sync`.
+//!
+//! Methods are called without any locking or thread checks. `#[methods]` reports methods that
+//! take `&mut self` as compile errors, as do property setters, which need mutable access too.
+//!
 //! ### Use a `LocalCellData<T>` when:
 //!
 //! - Your `NativeClass` type is not `Send`, and you will only ever use it from the thread where
@@ -525,6 +538,67 @@ impl<T> Clone for ArcData<T> {
     }
 }
 
+/// User-data wrapper for state that is set once in the constructor, and immutable afterwards.
+/// Only implements `Map`.
+///
+/// Mapping is free of locks and thread checks, so methods can be called concurrently from any
+/// thread. Unlike [`ArcData`], the inner `Arc<T>` is never exposed, so all access goes through
+/// `&T`.
+#[derive(Debug)]
+pub struct OnceData<T>(Arc<T>);
+
+unsafe impl<T> UserData for OnceData<T>
+where
+    T: NativeClass + Send + Sync,
+{
+    type Target = T;
+
+    #[inline]
+    fn new(val: Self::Target) -> Self {
+        OnceData(Arc::new(val))
+    }
+
+    #[inline]
+    fn into_user_data(self) -> *const libc::c_void {
+        Arc::into_raw(self.0) as *const libc::c_void
+    }
+
+    #[inline]
+    unsafe fn consume_user_data_unchecked(ptr: *const libc::c_void) -> Self {
+        OnceData(Arc::from_raw(ptr as *const T))
+    }
+
+    #[inline]
+    unsafe fn clone_from_user_data_unchecked(ptr: *const libc::c_void) -> Self {
+        let borrowed = Arc::from_raw(ptr as *const T);
+        let arc = borrowed.clone();
+        mem::forget(borrowed);
+        OnceData(arc)
+    }
+}
+
+impl<T> Map for OnceData<T>
+where
+    T: NativeClass + Send + Sync,
+{
+    type Err = Infallible;
+
+    #[inline]
+    fn map<F, U>(&self, op: F) -> Result<U, Infallible>
+    where
+        F: FnOnce(&T) -> U,
+    {
+        Ok(op(&*self.0))
+    }
+}
+
+impl<T> Clone for OnceData<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        OnceData(self.0.clone())
+    }
+}
+
 /// User-data wrapper analogous to a `Arc<RefCell<T>>`, that is restricted to the thread
 /// where it was originally created. The destructor of `T` is not guaranteed to be run if
 /// this is actually shared across multiple threads.
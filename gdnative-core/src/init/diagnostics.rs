@@ -8,6 +8,7 @@
 mod godot_version_mismatch;
 mod missing_manual_registration;
 mod missing_suggested_diagnostics;
+mod validate_api;
 
 #[doc(inline)]
 pub use godot_version_mismatch::godot_version_mismatch;
@@ -17,3 +18,6 @@ pub use missing_manual_registration::missing_manual_registration;
 
 #[doc(inline)]
 pub use missing_suggested_diagnostics::missing_suggested_diagnostics;
+
+#[doc(inline)]
+pub use validate_api::validate_api;
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::export::class_registry;
+use crate::private::{class_method_arities, os_has_feature, INTERNAL_METHODS};
+
+/// Compares a subset of the API used by godot-rust against the `ClassDB` of the running engine,
+/// and logs a report of any mismatches. Returns `true` if no mismatches were found.
+///
+/// The following are checked for existence and, where the bindings know it, argument count:
+///
+/// - The engine methods that godot-rust calls internally, e.g. to manage reference counts.
+/// - The base classes of all registered `NativeClass` types, and the virtual methods they
+///   declare according to the generated bindings. Virtual methods are only checked in debug
+///   builds of the engine, which include them in `ClassDB`.
+///
+/// Mismatches are usually caused by running the library in a different engine version than the
+/// bindings were generated for. They can lead to panics or undefined behavior once the affected
+/// methods are called, so this is best called at the end of the init callback, after all classes
+/// are registered:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// struct MyLibrary;
+///
+/// #[gdnative::init::callbacks]
+/// impl GDNativeCallbacks for MyLibrary {
+///     fn nativescript_init(handle: InitHandle) {
+///         // handle.add_class::<...>();
+///         gdnative::init::diagnostics::validate_api();
+///     }
+/// }
+/// ```
+#[inline]
+pub fn validate_api() -> bool {
+    let mismatches = find_mismatches();
+    if mismatches.is_empty() {
+        return true;
+    }
+
+    let mut message = format!(
+        "gdnative-core: {} mismatch(es) found between the bindings and the engine API:",
+        mismatches.len()
+    );
+    for mismatch in &mismatches {
+        message.push_str("\n  - ");
+        message.push_str(&mismatch.to_string());
+    }
+    message.push_str(concat!(
+        "\nCalling the affected methods may panic or cause undefined behavior. ",
+        "Make sure the bindings were generated for the running engine version.",
+    ));
+
+    godot_warn!("{}", message);
+    false
+}
+
+#[derive(Debug)]
+enum Mismatch {
+    MissingClass {
+        class: &'static str,
+    },
+    MissingMethod {
+        class: &'static str,
+        method: &'static str,
+    },
+    Arity {
+        class: &'static str,
+        method: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::MissingClass { class } => {
+                write!(f, "class `{class}` does not exist")
+            }
+            Mismatch::MissingMethod { class, method } => {
+                write!(f, "method `{class}::{method}` does not exist")
+            }
+            Mismatch::Arity {
+                class,
+                method,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "method `{class}::{method}` takes {found} argument(s), expected {expected}"
+                )
+            }
+        }
+    }
+}
+
+fn find_mismatches() -> Vec<Mismatch> {
+    let mut expected: Vec<(&'static str, Vec<(&'static str, Option<usize>)>)> = INTERNAL_METHODS
+        .iter()
+        .map(|&(class, methods)| (class, methods.iter().map(|&m| (m, None)).collect()))
+        .collect();
+
+    // Virtual methods are only listed in `ClassDB` by debug builds of the engine.
+    let check_virtual = os_has_feature("debug");
+
+    for base in class_registry::base_classes() {
+        let methods = if check_virtual {
+            base.virtual_methods
+                .iter()
+                .flat_map(|list| list.iter())
+                .map(|method| (method.name(), Some(method.args().len())))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        expected.push((base.name, methods));
+    }
+
+    let mut mismatches = Vec::new();
+    let mut cache = HashMap::new();
+
+    for (class, methods) in expected {
+        let arities = cache
+            .entry(class)
+            .or_insert_with(|| class_method_arities(class));
+
+        let Some(arities) = arities else {
+            mismatches.push(Mismatch::MissingClass { class });
+            continue;
+        };
+
+        for (method, arity) in methods {
+            match (arities.get(method), arity) {
+                (None, _) => mismatches.push(Mismatch::MissingMethod { class, method }),
+                (Some(&found), Some(expected)) if found != expected => {
+                    mismatches.push(Mismatch::Arity {
+                        class,
+                        method,
+                        expected,
+                        found,
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    mismatches
+}
@@ -0,0 +1,345 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::core_types::{GodotString, Variant};
+use crate::export::user_data::Aether;
+use crate::export::{ClassBuilder, Method, NativeClass, NativeClassMethods, Varargs};
+use crate::init::InitHandle;
+use crate::object::ownership::{Shared, Unique};
+use crate::object::{GodotObject, Instance, InstanceId, TInstance, TRef};
+use crate::private::{
+    get_api, EngineMethodTable, NodeMethodTable, NodePlaceholder, ObjectMethodTable,
+    SceneTreeMethodTable,
+};
+
+static HOOKS: Lazy<Mutex<Registry>> = Lazy::new(Mutex::default);
+
+/// Point in the frame at which a [frame hook](register_frame_hook) runs.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Phase {
+    /// Every physics tick, before `_physics_process` is called on any node.
+    PrePhysics,
+    /// Every physics tick, after `_physics_process` has been called on all nodes.
+    PostPhysics,
+    /// Every frame, before `_process` is called on any node.
+    PreProcess,
+    /// Every frame, after `_process` has been called on all nodes.
+    PostProcess,
+}
+
+/// Registers `hook` to be called on the main thread at the given phase of every frame, with
+/// the time step in seconds, as passed to `_process` or `_physics_process`.
+///
+/// This is intended for systems that need to run once per frame independently of any node,
+/// such as ECS schedules:
+///
+/// ```no_run
+/// use gdnative::init::{register_frame_hook, Phase};
+///
+/// register_frame_hook(Phase::PreProcess, |delta| {
+///     // Runs before `_process` of every node.
+/// });
+/// ```
+///
+/// Hooks are driven by two nodes added to the root of the `SceneTree` by the crate: one that
+/// is processed before all other nodes, and one that is processed after them. The nodes are
+/// created at the end of the first frame after a hook is registered, so hooks start running
+/// on the frame after that. Hooks are not run in the editor, or if the main loop is not a
+/// `SceneTree`.
+///
+/// Nodes with the extreme process priorities `i64::MIN` and `i64::MAX` may be processed before
+/// or after the hooks respectively.
+#[inline]
+pub fn register_frame_hook<F>(phase: Phase, hook: F) -> FrameHook
+where
+    F: FnMut(f64) + Send + 'static,
+{
+    let mut hooks = HOOKS.lock();
+    let id = hooks.next_id;
+    hooks.next_id += 1;
+    hooks.entries.push(Entry {
+        id,
+        phase,
+        hook: Arc::new(Mutex::new(Box::new(hook))),
+    });
+
+    FrameHook { id }
+}
+
+/// Handle to a hook registered with [`register_frame_hook`].
+///
+/// Dropping the handle does not remove the hook.
+#[derive(Debug)]
+pub struct FrameHook {
+    id: u64,
+}
+
+impl FrameHook {
+    /// Removes the hook, so that it isn't called on later frames. Returns `false` if the hook
+    /// was already removed because the library was terminated.
+    #[inline]
+    pub fn remove(self) -> bool {
+        let mut hooks = HOOKS.lock();
+        let len = hooks.entries.len();
+        hooks.entries.retain(|entry| entry.id != self.id);
+        hooks.entries.len() != len
+    }
+}
+
+type Hook = Arc<Mutex<Box<dyn FnMut(f64) + Send>>>;
+
+struct Entry {
+    id: u64,
+    phase: Phase,
+    hook: Hook,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    entries: Vec<Entry>,
+    drivers: Option<[InstanceId; 2]>,
+}
+
+/// Runs the hooks of `phase`, in the order they were registered.
+fn run(phase: Phase, delta: f64) {
+    // Hooks may register or remove hooks, so the registry can't be locked while they run.
+    let hooks = HOOKS
+        .lock()
+        .entries
+        .iter()
+        .filter(|entry| entry.phase == phase)
+        .map(|entry| Arc::clone(&entry.hook))
+        .collect::<Vec<_>>();
+
+    for hook in hooks {
+        let mut hook = hook.lock();
+        if let Err(err) = catch_unwind(AssertUnwindSafe(|| hook(delta))) {
+            godot_error!("gdnative-core: frame hook for {phase:?} panicked");
+            crate::private::print_panic_error(err);
+        }
+    }
+}
+
+/// Registers the classes of the driver nodes. Called during `nativescript_init`.
+pub(crate) fn register_drivers(handle: InitHandle) {
+    handle.add_class_as::<Driver<false>>("__GDNATIVE_INTERNAL__FrameHooksEarly".into());
+    handle.add_class_as::<Driver<true>>("__GDNATIVE_INTERNAL__FrameHooksLate".into());
+}
+
+/// Adds the driver nodes to the scene tree if there are hooks to run. Called every frame.
+pub(crate) fn poll() {
+    let mut hooks = HOOKS.lock();
+    if hooks.drivers.is_some() || hooks.entries.is_empty() {
+        return;
+    }
+
+    // SAFETY: This is called from `nativescript_frame`, on the main thread, outside of any
+    // processing of the scene tree.
+    hooks.drivers = unsafe { add_drivers() };
+}
+
+/// Removes the hooks and frees the driver nodes. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    let Registry { drivers, .. } = std::mem::take(&mut *HOOKS.lock());
+
+    for id in drivers.into_iter().flatten() {
+        // SAFETY: Termination happens on the main thread, and the nodes are only referenced by
+        // the scene tree.
+        unsafe {
+            if let Some(node) = NodePlaceholder::try_from_instance_id(id) {
+                node.claim().assume_unique().free();
+            }
+        }
+    }
+}
+
+/// Creates the driver nodes and adds them to the root of the scene tree. Returns `None` if
+/// there is no scene tree, or if running in the editor, in which case the drivers are never
+/// created.
+///
+/// # Safety
+///
+/// Must be called on the main thread, when children can be added to the root node.
+unsafe fn add_drivers() -> Option<[InstanceId; 2]> {
+    let api = get_api();
+    let engine = (api.godot_global_get_singleton)(b"Engine\0".as_ptr() as *mut _);
+    let engine_methods = EngineMethodTable::get(api);
+
+    let mut is_editor_hint = false;
+    (api.godot_method_bind_ptrcall)(
+        engine_methods.is_editor_hint,
+        engine,
+        [].as_mut_ptr(),
+        &mut is_editor_hint as *mut _ as *mut _,
+    );
+
+    let mut main_loop: *mut sys::godot_object = std::ptr::null_mut();
+    (api.godot_method_bind_ptrcall)(
+        engine_methods.get_main_loop,
+        engine,
+        [].as_mut_ptr(),
+        &mut main_loop as *mut _ as *mut _,
+    );
+
+    if is_editor_hint || main_loop.is_null() {
+        return None;
+    }
+
+    let scene_tree = GodotString::from_str("SceneTree");
+    let mut is_scene_tree = false;
+    (api.godot_method_bind_ptrcall)(
+        ObjectMethodTable::get(api).is_class,
+        main_loop,
+        [scene_tree.sys() as *const libc::c_void].as_mut_ptr(),
+        &mut is_scene_tree as *mut _ as *mut _,
+    );
+
+    if !is_scene_tree {
+        return None;
+    }
+
+    let mut root: *mut sys::godot_object = std::ptr::null_mut();
+    (api.godot_method_bind_ptrcall)(
+        SceneTreeMethodTable::get(api).get_root,
+        main_loop,
+        [].as_mut_ptr(),
+        &mut root as *mut _ as *mut _,
+    );
+
+    if root.is_null() {
+        return None;
+    }
+
+    let early = add_driver::<false>(root, "FrameHooksEarly", i64::MIN);
+    let late = add_driver::<true>(root, "FrameHooksLate", i64::MAX);
+    Some([early, late])
+}
+
+/// Creates a driver node with the given name and process priority, and adds it to `root`.
+///
+/// # Safety
+///
+/// `root` must point to a valid `Node` that children can be added to.
+unsafe fn add_driver<const LATE: bool>(
+    root: *mut sys::godot_object,
+    name: &str,
+    priority: i64,
+) -> InstanceId {
+    let api = get_api();
+    let node_methods = NodeMethodTable::get(api);
+
+    let node = Instance::<Driver<LATE>, Unique>::new().into_base();
+    let id = node.instance_id();
+    let node = node.as_ptr();
+
+    let name = GodotString::from_str(name);
+    (api.godot_method_bind_ptrcall)(
+        node_methods.set_name,
+        node,
+        [name.sys() as *const libc::c_void].as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+
+    (api.godot_method_bind_ptrcall)(
+        node_methods.set_process_priority,
+        node,
+        [&priority as *const i64 as *const libc::c_void].as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+
+    let legible_unique_name = false;
+    (api.godot_method_bind_ptrcall)(
+        node_methods.add_child,
+        root,
+        [
+            node as *const libc::c_void,
+            &legible_unique_name as *const bool as *const libc::c_void,
+        ]
+        .as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+
+    id
+}
+
+/// Script of the nodes running the hooks. The early driver runs the `Pre` phases, and the late
+/// driver runs the `Post` phases.
+#[derive(Copy, Clone, Default)]
+struct Driver<const LATE: bool>;
+
+impl<const LATE: bool> NativeClass for Driver<LATE> {
+    type Base = NodePlaceholder;
+    type UserData = Aether<Self>;
+
+    fn nativeclass_init(_owner: TRef<'_, NodePlaceholder, Shared>) -> Self {
+        Driver
+    }
+}
+
+impl<const LATE: bool> NativeClassMethods for Driver<LATE> {
+    fn nativeclass_register(builder: &ClassBuilder<Self>) {
+        let (physics, process) = if LATE {
+            (Phase::PostPhysics, Phase::PostProcess)
+        } else {
+            (Phase::PrePhysics, Phase::PreProcess)
+        };
+
+        builder
+            .method("_physics_process", RunHooks { phase: physics })
+            .done();
+        builder
+            .method("_process", RunHooks { phase: process })
+            .done();
+    }
+}
+
+struct RunHooks {
+    phase: Phase,
+}
+
+impl<C: NativeClass> Method<C> for RunHooks {
+    fn call(&self, _this: TInstance<'_, C>, mut args: Varargs<'_>) -> Variant {
+        match args.read::<f64>().get() {
+            Ok(delta) => run(self.phase, delta),
+            Err(err) => err.log_error(),
+        }
+        Variant::nil()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks_run_in_phase_and_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+
+        let hooks = [
+            (Phase::PreProcess, "first"),
+            (Phase::PostProcess, "post"),
+            (Phase::PreProcess, "second"),
+        ]
+        .map(|(phase, name)| {
+            let calls = calls.clone();
+            register_frame_hook(phase, move |delta| calls.lock().push((name, delta)))
+        });
+
+        run(Phase::PreProcess, 0.5);
+        assert_eq!(vec![("first", 0.5), ("second", 0.5)], *calls.lock());
+
+        let [first, post, second] = hooks;
+        assert!(first.remove());
+        calls.lock().clear();
+        run(Phase::PreProcess, 0.25);
+        assert_eq!(vec![("second", 0.25)], *calls.lock());
+
+        assert!(post.remove());
+        assert!(second.remove());
+    }
+}
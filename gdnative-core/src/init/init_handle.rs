@@ -1,3 +1,4 @@
+use crate::export::mixin_state::InstanceStorage;
 use crate::export::user_data::UserData;
 use crate::export::{
     class_registry, emplace, ClassBuilder, NativeClass, NativeClassMethods, StaticallyNamed,
@@ -8,6 +9,7 @@ use std::borrow::Cow;
 use std::ffi::CString;
 use std::ptr;
 
+use super::report::{self, ClassReport, RegistrationReport};
 use super::InitLevel;
 
 /// A handle that can register new classes to the engine during initialization.
@@ -18,6 +20,7 @@ use super::InitLevel;
 pub struct InitHandle {
     handle: *mut libc::c_void,
     init_level: InitLevel,
+    dry_run: bool,
 }
 
 #[allow(deprecated)] // Remove once init(), register_properties() and register() have been renamed
@@ -25,7 +28,47 @@ impl InitHandle {
     #[doc(hidden)]
     #[inline]
     pub unsafe fn new(handle: *mut libc::c_void, init_level: InitLevel) -> Self {
-        InitHandle { handle, init_level }
+        InitHandle {
+            handle,
+            init_level,
+            dry_run: false,
+        }
+    }
+
+    /// Calls `f` with a handle that records class registrations without performing them, and
+    /// returns what would have been registered.
+    ///
+    /// This allows inspecting the classes, methods, properties, signals and mixins, as well as
+    /// any names registered more than once, before committing to the registration:
+    ///
+    /// ```no_run
+    /// use gdnative::prelude::*;
+    ///
+    /// # #[derive(NativeClass)]
+    /// # #[no_constructor]
+    /// # struct Player;
+    /// # #[methods]
+    /// # impl Player {}
+    /// fn init(handle: InitHandle) {
+    ///     let report = handle.dry_run(|handle| handle.add_class::<Player>());
+    ///     for duplicate in &report.duplicates {
+    ///         godot_warn!("{duplicate}");
+    ///     }
+    ///
+    ///     handle.add_class::<Player>();
+    /// }
+    /// ```
+    ///
+    /// Classes registered for real are recorded too, and can be inspected at any time with
+    /// [`registration_report`](super::registration_report).
+    #[inline]
+    pub fn dry_run(self, f: impl FnOnce(InitHandle)) -> RegistrationReport {
+        let enclosing = report::begin_dry_run();
+        f(InitHandle {
+            dry_run: true,
+            ..self
+        });
+        report::end_dry_run(enclosing)
     }
 
     /// Registers a new class to the engine.
@@ -118,6 +161,29 @@ impl InitHandle {
         self.add_maybe_tool_class_as_with::<C>(Cow::Owned(name), true, f)
     }
 
+    /// Registers members of the class `C` with `f` at the start of the first frame, instead of
+    /// during `nativescript_init`. This allows plugins to expose what they discover at run
+    /// time, such as optional engine features or loaded content.
+    ///
+    /// `f` runs through [`extend_class`](super::extend_class) on the main thread, during the
+    /// `nativescript_frame` callback. Errors are logged. Instances created before then, e.g. by
+    /// autoloads, see the members once they are registered.
+    ///
+    /// Late registrations are not included in [dry runs](Self::dry_run).
+    ///
+    /// # Safety
+    ///
+    /// Instances of `C` must not be used on other threads until the members are registered.
+    #[inline]
+    pub unsafe fn register_late<C>(self, f: impl Fn(&ClassBuilder<C>) + Send + 'static)
+    where
+        C: NativeClass,
+    {
+        if !self.dry_run {
+            super::late::queue(f);
+        }
+    }
+
     #[inline]
     fn add_maybe_tool_class_as_with<C>(
         self,
@@ -127,13 +193,38 @@ impl InitHandle {
     ) where
         C: NativeClassMethods,
     {
+        if !C::nativeclass_should_register() {
+            return;
+        }
+
         let c_class_name = CString::new(&*name).unwrap();
+        let class_report = ClassReport::new(
+            name.to_string(),
+            std::any::type_name::<C>(),
+            C::Base::class_name(),
+            is_tool,
+        );
+
+        if self.dry_run {
+            let builder = ClassBuilder::new(self.handle, c_class_name, true, class_report);
+            Self::register_members::<C>(&builder, f);
+            let (class_report, duplicates) = builder.into_report();
+            report::submit(class_report, duplicates, true);
+            return;
+        }
 
-        match class_registry::register_class_as::<C>(name, self.init_level) {
+        // SAFETY: `InitHandle`s are only valid during `nativescript_init`.
+        let Some(library) = (unsafe { crate::private::library_of_handle(self.handle) }) else {
+            godot_error!("gdnative-core: cannot register {name}: nativescript_init wasn't called");
+            return;
+        };
+
+        match class_registry::register_class_as::<C>(library, name.clone(), self.init_level) {
             Ok(true) => {}
             Ok(false) => return,
             Err(e) => {
                 godot_error!("gdnative-core: ignoring new registration: {e}");
+                report::submit_conflict(&name);
                 return;
             }
         };
@@ -188,8 +279,11 @@ impl InitHandle {
                         }
                     };
 
+                    crate::diagnostics::instance_created::<C>(owner);
+                    crate::export::processing::apply::<C>(owner);
+
                     let wrapper = C::UserData::new(val);
-                    C::UserData::into_user_data(wrapper) as *mut _
+                    InstanceStorage::into_raw(C::UserData::into_user_data(wrapper))
                 }
 
                 sys::godot_instance_create_func {
@@ -201,7 +295,7 @@ impl InitHandle {
 
             let destroy = {
                 unsafe extern "C" fn destructor<C: NativeClass>(
-                    _this: *mut sys::godot_object,
+                    this: *mut sys::godot_object,
                     _method_data: *mut libc::c_void,
                     user_data: *mut libc::c_void,
                 ) {
@@ -213,8 +307,14 @@ impl InitHandle {
                         return;
                     }
 
+                    let (user_data, states) = InstanceStorage::consume(user_data);
                     let wrapper = C::UserData::consume_user_data_unchecked(user_data);
-                    drop(wrapper)
+                    drop(wrapper);
+                    drop(states);
+
+                    if let Some(this) = ptr::NonNull::new(this) {
+                        crate::diagnostics::instance_destroyed::<C>(this);
+                    }
                 }
 
                 sys::godot_instance_destroy_func {
@@ -247,15 +347,25 @@ impl InitHandle {
                 c_class_name.as_ptr() as *const _,
                 crate::export::type_tag::create::<C>(),
             );
+        }
 
-            let builder = ClassBuilder::new(self.handle, c_class_name);
+        let builder = ClassBuilder::new(self.handle, c_class_name, false, class_report);
+        Self::register_members::<C>(&builder, f);
+        let (class_report, duplicates) = builder.into_report();
+        report::submit(class_report, duplicates, false);
+    }
 
-            C::nativeclass_register_properties(&builder);
+    fn register_members<C>(builder: &ClassBuilder<C>, f: impl FnOnce(&ClassBuilder<C>))
+    where
+        C: NativeClassMethods,
+    {
+        C::nativeclass_register_properties(builder);
 
-            // register methods
-            C::nativeclass_register(&builder);
+        // register methods
+        C::nativeclass_register(builder);
 
-            f(&builder);
-        }
+        f(builder);
+
+        builder.register_property_list();
     }
 }
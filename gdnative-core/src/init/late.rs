@@ -0,0 +1,191 @@
+use std::any::type_name;
+use std::ffi::CString;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use super::report::{self, ClassReport};
+use crate::export::{class_registry, ClassBuilder, NativeClass};
+use crate::object::GodotObject;
+
+/// Late registrations that run at the start of the next frame.
+static PENDING: Lazy<Mutex<Vec<Pending>>> = Lazy::new(Mutex::default);
+
+/// Whether `PENDING` is non-empty. Checked before locking, so that frames are not slowed down
+/// once all late registrations have run.
+static HAS_PENDING: AtomicBool = AtomicBool::new(false);
+
+type Pending = Box<dyn FnOnce() + Send>;
+
+/// Registers members of the class `C` with `f` after `nativescript_init`, e.g. for plugins that
+/// only discover what to expose at run time.
+///
+/// `f` is called once for each library that `C` is registered with. The members can be used
+/// from scripts right away, including on instances that already exist:
+///
+/// ```no_run
+/// use gdnative::init::extend_class;
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// # #[no_constructor]
+/// struct Inventory;
+///
+/// #[methods]
+/// impl Inventory {}
+///
+/// fn on_plugin_loaded(plugin_name: &str) {
+///     let signal = format!("{plugin_name}_item_added");
+///     // SAFETY: plugins are loaded on the main thread, and `Inventory` is only used there.
+///     unsafe { extend_class::<Inventory>(|builder| builder.signal(&signal).done()) }
+///         .expect("Inventory should be registered");
+/// }
+/// ```
+///
+/// Members with the same name as existing ones replace them, and are reported as duplicates
+/// in the [registration report](super::registration_report).
+///
+/// # Safety
+///
+/// The engine doesn't synchronize access to the members of a class. This must be called on the
+/// main thread, while no instances of `C` are used on other threads.
+///
+/// # Errors
+///
+/// If `C` isn't registered with any library, either because it wasn't added yet or because
+/// the libraries were terminated, nothing is registered.
+///
+/// Properties with conditional visibility and property bags are listed by the
+/// `_get_property_list` method of the class, which can't be extended once the class is
+/// registered. If `f` adds any, an error is returned, and only the other members are
+/// registered.
+#[inline]
+pub unsafe fn extend_class<C: NativeClass>(
+    f: impl Fn(&ClassBuilder<C>),
+) -> Result<(), LateRegistrationError> {
+    let (name, libraries) = class_registry::libraries::<C>()
+        .filter(|(_, libraries)| !libraries.is_empty())
+        .ok_or(LateRegistrationError::NotRegistered {
+            type_name: type_name::<C>(),
+        })?;
+
+    let c_class_name = CString::new(&*name).unwrap();
+    let new_report = || {
+        ClassReport::new(
+            name.to_string(),
+            type_name::<C>(),
+            C::Base::class_name(),
+            false,
+        )
+    };
+
+    let mut uses_property_list = false;
+    for (index, library) in libraries.into_iter().enumerate() {
+        // The handle passed to `nativescript_init` is only valid during the callback.
+        let Some(handle) = crate::private::registration_handle(library) else {
+            continue;
+        };
+
+        // The report is shared by all libraries, so only the first one is recorded.
+        let class_report = if index == 0 {
+            report::registered_class(&name).unwrap_or_else(&new_report)
+        } else {
+            new_report()
+        };
+
+        let builder = ClassBuilder::new(handle, c_class_name.clone(), false, class_report);
+        f(&builder);
+        uses_property_list |= builder.uses_property_list();
+
+        if index == 0 {
+            let (class_report, duplicates) = builder.into_report();
+            report::submit_late(class_report, duplicates);
+        }
+    }
+
+    if uses_property_list {
+        return Err(LateRegistrationError::PropertyList {
+            class: name.into_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Queues a late registration for `C`, to run at the start of the next frame.
+pub(super) fn queue<C: NativeClass>(f: impl Fn(&ClassBuilder<C>) + Send + 'static) {
+    let pending: Pending = Box::new(move || {
+        // SAFETY: This runs in `nativescript_frame`, on the main thread. The caller of
+        // `register_late` guarantees that `C` isn't used on other threads until then.
+        if let Err(err) = unsafe { extend_class::<C>(f) } {
+            godot_error!("gdnative-core: late registration failed: {err}");
+        }
+    });
+
+    PENDING.lock().push(pending);
+    HAS_PENDING.store(true, Ordering::Release);
+}
+
+/// Runs the queued late registrations. Called from `nativescript_frame`, on the main thread.
+pub(crate) fn run_pending() {
+    if !HAS_PENDING.load(Ordering::Acquire) {
+        return;
+    }
+
+    let pending = {
+        let mut pending = PENDING.lock();
+        HAS_PENDING.store(false, Ordering::Release);
+        std::mem::take(&mut *pending)
+    };
+
+    // The lock isn't held, so that the registrations may queue other ones.
+    for f in pending {
+        if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+            godot_error!("gdnative-core: late registration panicked");
+            crate::private::print_panic_error(e);
+        }
+    }
+}
+
+/// Drops the late registrations that haven't run yet. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    HAS_PENDING.store(false, Ordering::Release);
+    let pending = std::mem::take(&mut *PENDING.lock());
+    drop(pending);
+}
+
+/// Error returned by [`extend_class`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LateRegistrationError {
+    /// The class isn't registered with any library.
+    NotRegistered {
+        /// Rust type name of the class.
+        type_name: &'static str,
+    },
+    /// Entries for `_get_property_list` were added, which can't be registered late.
+    PropertyList {
+        /// Name of the class.
+        class: String,
+    },
+}
+
+impl fmt::Display for LateRegistrationError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LateRegistrationError::NotRegistered { type_name } => {
+                write!(f, "`{type_name}` is not registered with any library")
+            }
+            LateRegistrationError::PropertyList { class } => write!(
+                f,
+                "conditionally visible properties and property bags can't be added to `{class}` after it is registered",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LateRegistrationError {}
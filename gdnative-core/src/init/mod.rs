@@ -1,10 +1,33 @@
 //! Global initialization and termination of the library.
 //!
 //! This module provides all the plumbing required for global initialization and shutdown of godot-rust.
+//!
+//! ## Multiple libraries
+//!
+//! Separate `cdylib`s built with godot-rust are fully isolated from each other, since each of
+//! them contains its own copy of the library's global state.
+//!
+//! If the same binary is loaded through more than one `GDNativeLibrary` resource, however, the
+//! global state is shared. In that case:
+//!
+//! - Classes are registered per library. Registering a class in one library does not prevent it
+//!   from being registered in another, and the registration is dropped when that library's
+//!   NativeScript side is terminated.
+//! - Callbacks such as [`GDNativeCallbacks::nativescript_frame`] run once per loaded library.
+//! - Global state is only torn down once the last library is terminated.
+//! - Process-wide facilities such as the logger can only be installed once. Errors from a
+//!   repeated installation may be ignored.
+//!
+//! On platforms where libraries are linked statically, such as iOS, every library must declare
+//! its callbacks with a unique prefix, e.g. `#[gdnative::init::callbacks(prefix = "my_game_")]`,
+//! matching the `symbol_prefix` property of the corresponding `.gdnlib` resource.
 
+mod frame_hook;
 mod info;
 mod init_handle;
+mod late;
 mod macros;
+pub(crate) mod report;
 mod terminate_handle;
 
 pub mod diagnostics;
@@ -13,8 +36,13 @@ pub mod diagnostics;
 #[doc(hidden)]
 pub mod private;
 
+pub use frame_hook::{register_frame_hook, FrameHook, Phase};
 pub use info::*;
 pub use init_handle::*;
+pub use late::{extend_class, LateRegistrationError};
+pub use report::{
+    registration_report, ClassReport, Duplicate, RegistrationKind, RegistrationReport,
+};
 pub use terminate_handle::*;
 
 /// Trait for declaring library-level GDNative callbacks. See module-level docs for examples.
@@ -93,6 +121,7 @@ pub trait GDNativeCallbacks: private::TheGDNativeCallbacksAttributeIsRequired {
     /// At the time [`Self::gdnative_terminate`] is called, it is guaranteed that:
     ///
     /// - No API callbacks will be invoked after.
+    /// - All [workers](crate::worker) have exited.
     #[inline]
     #[allow(unused)]
     fn gdnative_terminate(info: TerminateInfo) {}
@@ -161,6 +190,10 @@ pub trait GDNativeCallbacks: private::TheGDNativeCallbacksAttributeIsRequired {
     fn nativescript_terminate(handle: TerminateHandle) {}
 
     /// Callback invoked every frame if any NativeScripts are being used.
+    ///
+    /// Messages from [workers](crate::worker) are delivered immediately before this is invoked.
+    /// [Frame hooks](register_frame_hook) registered before this point start running on the next
+    /// frame.
     #[inline]
     fn nativescript_frame() {}
 
@@ -184,6 +217,8 @@ bitflags::bitflags! {
         const AUTO = 1;
         /// Init level for user code
         const USER = 2;
+        /// Init level for classes used internally by the crate
+        const INTERNAL = 4;
     }
 }
 
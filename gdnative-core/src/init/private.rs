@@ -28,12 +28,30 @@ pub unsafe fn gdnative_terminate<C: GDNativeCallbacks>(
         return;
     }
 
+    // Library-wide state is shared by all libraries loading this binary, so it's only torn down
+    // with the last one.
+    let is_last = crate::private::release_library();
+
+    // Workers may depend on state that is torn down by the user callback.
+    if is_last {
+        crate::worker::shutdown();
+        crate::diagnostics::shutdown_watchdog();
+        crate::diagnostics::shutdown_instances();
+        crate::init::frame_hook::shutdown();
+        crate::init::late::shutdown();
+        crate::export::deferred_signal::shutdown();
+        crate::export::processing::shutdown();
+    }
+
     crate::private::report_panics("gdnative_terminate", || {
         let term_info = crate::init::TerminateInfo::new(options);
         C::gdnative_terminate(term_info)
     });
 
-    crate::private::cleanup_internal_state();
+    if is_last {
+        crate::log::shutdown_crash_handler();
+        crate::private::cleanup_internal_state();
+    }
 }
 
 #[inline]
@@ -47,7 +65,17 @@ pub unsafe fn nativescript_init<C: GDNativeCallbacks>(handle: *mut libc::c_void)
         return;
     }
 
+    crate::private::bind_nativescript_handle(handle);
+
     crate::private::report_panics("nativescript_init", || {
+        crate::init::frame_hook::register_drivers(crate::init::InitHandle::new(
+            handle,
+            crate::init::InitLevel::INTERNAL,
+        ));
+        crate::export::deferred_signal::register_marshaller(crate::init::InitHandle::new(
+            handle,
+            crate::init::InitLevel::INTERNAL,
+        ));
         crate::init::auto_register(crate::init::InitHandle::new(
             handle,
             crate::init::InitLevel::AUTO,
@@ -64,10 +92,20 @@ pub unsafe fn nativescript_init<C: GDNativeCallbacks>(handle: *mut libc::c_void)
 #[inline]
 pub unsafe fn nativescript_terminate<C: GDNativeCallbacks>(handle: *mut libc::c_void) {
     C::nativescript_terminate(TerminateHandle::new(handle));
+
+    if crate::private::is_api_bound() {
+        if let Some(library) = crate::private::library_of_handle(handle) {
+            crate::export::class_registry::cleanup_library(library);
+            crate::private::unbind_library(library);
+        }
+    }
 }
 
 #[inline]
 pub unsafe fn nativescript_frame<C: GDNativeCallbacks>() {
+    crate::init::late::run_pending();
+    crate::worker::poll();
+    crate::init::frame_hook::poll();
     C::nativescript_frame();
 }
 
@@ -80,3 +118,16 @@ pub unsafe fn nativescript_thread_enter<C: GDNativeCallbacks>() {
 pub unsafe fn nativescript_thread_exit<C: GDNativeCallbacks>() {
     C::nativescript_thread_exit();
 }
+
+/// Compile-time diagnostics for the target platform. These are referenced by the code generated
+/// by `#[gdnative::init::callbacks]`, so that warnings are reported in the user crate.
+pub mod platform_diagnostics {
+    use gdnative_impl_proc_macros::cfg_ex;
+
+    #[cfg_ex(all(feature = "inventory", gdnative::html5))]
+    #[deprecated = "automatic class registration with the `inventory` feature is unavailable on HTML5 exports. Register all classes manually in `nativescript_init`, and call `gdnative::init::diagnostics::missing_manual_registration` to verify"]
+    pub const INVENTORY: () = ();
+
+    #[cfg_ex(not(all(feature = "inventory", gdnative::html5)))]
+    pub const INVENTORY: () = ();
+}
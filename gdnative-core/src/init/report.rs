@@ -0,0 +1,290 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::log::Site;
+
+/// Everything registered so far, across all libraries sharing this binary.
+static REGISTERED: Lazy<Mutex<RegistrationReport>> = Lazy::new(Mutex::default);
+
+thread_local! {
+    /// Report of the innermost dry run in progress on this thread, if any.
+    static DRY_RUN: RefCell<Option<RegistrationReport>> = const { RefCell::new(None) };
+}
+
+/// Kinds of names that are registered with the engine.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum RegistrationKind {
+    Class,
+    Method,
+    Property,
+    Signal,
+}
+
+impl fmt::Display for RegistrationKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RegistrationKind::Class => "class",
+            RegistrationKind::Method => "method",
+            RegistrationKind::Property => "property",
+            RegistrationKind::Signal => "signal",
+        })
+    }
+}
+
+/// Description of a registered class, as recorded by [`InitHandle`](super::InitHandle).
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ClassReport {
+    /// Name of the class as seen by the engine.
+    pub name: String,
+    /// Name of the Rust type, as returned by [`std::any::type_name`].
+    pub type_name: &'static str,
+    /// Name of the engine base class.
+    pub base: &'static str,
+    /// Whether the class was registered as a tool class.
+    pub is_tool: bool,
+    /// Names of the methods, in registration order.
+    pub methods: Vec<String>,
+    /// Names of the properties, in registration order.
+    pub properties: Vec<String>,
+    /// Names of the signals, in registration order.
+    pub signals: Vec<String>,
+    /// Type names of the applied mixins, in registration order.
+    pub mixins: Vec<&'static str>,
+}
+
+impl ClassReport {
+    pub(crate) fn new(
+        name: String,
+        type_name: &'static str,
+        base: &'static str,
+        is_tool: bool,
+    ) -> Self {
+        ClassReport {
+            name,
+            type_name,
+            base,
+            is_tool,
+            methods: Vec::new(),
+            properties: Vec::new(),
+            signals: Vec::new(),
+            mixins: Vec::new(),
+        }
+    }
+
+    /// Records `name`, returning `false` if it was already registered as the same kind.
+    pub(crate) fn record(&mut self, kind: RegistrationKind, name: &str) -> bool {
+        let names = match kind {
+            RegistrationKind::Method => &mut self.methods,
+            RegistrationKind::Property => &mut self.properties,
+            RegistrationKind::Signal => &mut self.signals,
+            RegistrationKind::Class => return true,
+        };
+
+        if names.iter().any(|existing| existing == name) {
+            false
+        } else {
+            names.push(name.to_owned());
+            true
+        }
+    }
+}
+
+/// A name that was registered more than once. Classes keep their first registration, while
+/// later registrations of members replace earlier ones.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Duplicate {
+    /// What the name refers to.
+    pub kind: RegistrationKind,
+    /// Name of the class the duplicate was registered with. For classes, this is the
+    /// duplicated name itself.
+    pub class: String,
+    /// The duplicated name.
+    pub name: String,
+    /// Where the duplicate was defined, if known.
+    pub site: Option<Site<'static>>,
+}
+
+impl fmt::Display for Duplicate {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.kind == RegistrationKind::Class {
+            write!(f, "class `{}` is registered more than once", self.name)?;
+        } else {
+            write!(
+                f,
+                "{} `{}` of class `{}` is registered more than once",
+                self.kind, self.name, self.class
+            )?;
+        }
+
+        if let Some(site) = &self.site {
+            write!(f, " ({site})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Structured description of class registrations, for diagnosing why classes or their members
+/// don't show up in the engine.
+///
+/// A report of everything registered so far can be obtained with [`registration_report`].
+/// To find out what a set of registrations would do without registering anything, use
+/// [`InitHandle::dry_run`](super::InitHandle::dry_run).
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct RegistrationReport {
+    /// Registered classes, in registration order.
+    pub classes: Vec<ClassReport>,
+    /// Names that were registered more than once.
+    pub duplicates: Vec<Duplicate>,
+}
+
+impl RegistrationReport {
+    /// Returns the class registered under `name`, if any.
+    #[inline]
+    pub fn class(&self, name: &str) -> Option<&ClassReport> {
+        self.classes.iter().find(|class| class.name == name)
+    }
+
+    fn add(&mut self, class: ClassReport, duplicates: Vec<Duplicate>, dry_run: bool) {
+        // Outside of dry runs, the same type can show up again if the binary is loaded by
+        // multiple libraries.
+        let conflicts = self.classes.iter().any(|existing| {
+            existing.name == class.name && (dry_run || existing.type_name != class.type_name)
+        });
+
+        if conflicts {
+            self.duplicates.push(Duplicate {
+                kind: RegistrationKind::Class,
+                class: class.name.clone(),
+                name: class.name.clone(),
+                site: None,
+            });
+        }
+
+        self.duplicates.extend(duplicates);
+        self.classes.push(class);
+    }
+}
+
+/// Returns a report of all classes registered so far, including names registered more than
+/// once.
+#[inline]
+pub fn registration_report() -> RegistrationReport {
+    REGISTERED.lock().clone()
+}
+
+/// Returns the first registration of the class `name`, if any.
+pub(crate) fn registered_class(name: &str) -> Option<ClassReport> {
+    REGISTERED.lock().class(name).cloned()
+}
+
+/// Adds a class to the report of the current dry run, or to the global report if `dry_run` is
+/// `false`, in which case any duplicates are also logged.
+pub(crate) fn submit(class: ClassReport, duplicates: Vec<Duplicate>, dry_run: bool) {
+    if dry_run {
+        DRY_RUN.with(|report| {
+            report
+                .borrow_mut()
+                .as_mut()
+                .expect("dry run should be in progress")
+                .add(class, duplicates, true)
+        });
+        return;
+    }
+
+    let mut registered = REGISTERED.lock();
+    let len = registered.duplicates.len();
+    registered.add(class, duplicates, false);
+    for duplicate in &registered.duplicates[len..] {
+        godot_warn!("gdnative-core: {duplicate}");
+    }
+}
+
+/// Replaces the report of a registered class after members were added to it late.
+pub(crate) fn submit_late(class: ClassReport, duplicates: Vec<Duplicate>) {
+    let mut registered = REGISTERED.lock();
+    for duplicate in &duplicates {
+        godot_warn!("gdnative-core: {duplicate}");
+    }
+    registered.duplicates.extend(duplicates);
+
+    match registered
+        .classes
+        .iter_mut()
+        .find(|existing| existing.name == class.name && existing.type_name == class.type_name)
+    {
+        Some(existing) => *existing = class,
+        None => registered.classes.push(class),
+    }
+}
+
+/// Records a class that could not be registered because of a conflicting registration.
+pub(crate) fn submit_conflict(name: &str) {
+    REGISTERED.lock().duplicates.push(Duplicate {
+        kind: RegistrationKind::Class,
+        class: name.to_owned(),
+        name: name.to_owned(),
+        site: None,
+    });
+}
+
+/// Starts a dry run on the current thread, returning the report of the enclosing one, if any.
+pub(crate) fn begin_dry_run() -> Option<RegistrationReport> {
+    DRY_RUN.with(|report| report.replace(Some(RegistrationReport::default())))
+}
+
+/// Ends the dry run on the current thread, restoring the report of the enclosing one.
+pub(crate) fn end_dry_run(enclosing: Option<RegistrationReport>) -> RegistrationReport {
+    DRY_RUN
+        .with(|report| report.replace(enclosing))
+        .expect("dry run should be in progress")
+}
+
+pub(crate) fn cleanup() {
+    *REGISTERED.lock() = RegistrationReport::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(name: &str, type_name: &'static str) -> ClassReport {
+        ClassReport::new(name.into(), type_name, "Node", false)
+    }
+
+    #[test]
+    fn dry_run_reports_duplicates() {
+        let enclosing = begin_dry_run();
+
+        let mut player = class("Player", "game::Player");
+        assert!(player.record(RegistrationKind::Method, "jump"));
+        assert!(player.record(RegistrationKind::Property, "jump"));
+        assert!(!player.record(RegistrationKind::Method, "jump"));
+        submit(player, Vec::new(), true);
+        submit(class("Player", "game::OtherPlayer"), Vec::new(), true);
+        submit(class("Enemy", "game::Enemy"), Vec::new(), true);
+
+        let report = end_dry_run(enclosing);
+        assert!(begin_dry_run().is_none());
+        end_dry_run(None);
+
+        assert_eq!(3, report.classes.len());
+        assert_eq!(
+            vec!["jump".to_owned()],
+            report.class("Player").unwrap().methods
+        );
+        assert_eq!(1, report.duplicates.len());
+        assert_eq!(RegistrationKind::Class, report.duplicates[0].kind);
+        assert_eq!("Player", report.duplicates[0].name);
+        assert!(registration_report().classes.is_empty());
+    }
+}
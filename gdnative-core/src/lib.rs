@@ -47,6 +47,9 @@ extern crate approx;
 #[doc(inline)]
 pub use gdnative_derive::godot_wrap_method;
 
+#[doc(inline)]
+pub use gdnative_impl_proc_macros::{cfg_attr_ex, cfg_ex};
+
 /// Derive macros and macro attributes.
 #[doc(inline)]
 pub use gdnative_derive as derive;
@@ -56,12 +59,18 @@ mod macros;
 
 pub mod core_types;
 
+pub mod diagnostics;
 pub mod export;
 pub mod globalscope;
 pub mod init;
 pub mod log;
 pub mod object;
 pub mod profiler;
+pub mod services;
+pub mod worker;
+
+#[cfg(feature = "no-engine")]
+mod no_engine;
 
 /// Internal low-level API for use by macros and generated bindings. Not a part of the public API.
 #[doc(hidden)]
@@ -1,12 +1,27 @@
 //! Functions for using the engine's logging system in the editor.
-use std::ffi::CStr;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::ffi::{CStr, CString};
 use std::fmt::{self, Display};
+use std::io::{self, Write as _};
+use std::panic::{self, Location};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Once;
 
 // Collection of macros accessing the Godot engine log/print functionality
 pub use crate::{godot_dbg, godot_error, godot_print, godot_site, godot_warn};
 
 use crate::core_types::GodotString;
-use crate::private;
+use crate::private::{self, try_get_api};
+
+#[cfg(feature = "log")]
+mod logger;
+
+#[cfg(feature = "log")]
+pub use logger::{
+    records_since, set_category_level, set_filters, set_level, FilterParseError, LogRecord,
+    LoggerBuilder, LoggerInstallError,
+};
 
 /// Value representing a call site for errors and warnings. Can be constructed
 /// using the [`godot_site`] macro, or manually.
@@ -23,6 +38,10 @@ impl<'a> Site<'a> {
     pub const fn new(file: &'a CStr, func: &'a CStr, line: u32) -> Self {
         Site { file, func, line }
     }
+
+    pub(crate) fn func(&self) -> &'a CStr {
+        self.func
+    }
 }
 
 impl<'a> Default for Site<'a> {
@@ -104,3 +123,573 @@ pub fn error<S: Display>(site: Site<'_>, msg: S) {
         );
     }
 }
+
+/// Installs a panic hook that reports Rust panics to the Godot error log.
+///
+/// By default, panic messages are only written to the standard error stream, which is easy to
+/// lose when running a headless server build, and doesn't include a backtrace unless
+/// `RUST_BACKTRACE` is set. With this hook, each panic is reported as an error at the source
+/// location of the panic, followed by the name of the thread and a backtrace. Pending output of
+/// the standard streams and of the `log` crate logger is flushed before the report.
+///
+/// The previously installed hook is still called afterwards, so the panic is written to the
+/// standard error stream as well. Calling this function again has no effect.
+///
+/// Panics that happen while the GDNative API isn't initialized are only passed to the previous
+/// hook.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// fn init(handle: InitHandle) {
+///     gdnative::log::install_panic_hook();
+///     // handle.add_class::<...>();
+/// }
+/// ```
+#[inline]
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            report_panic(info.payload(), info.location());
+            previous(info);
+        }));
+    });
+}
+
+fn report_panic(payload: &(dyn Any + Send), location: Option<&Location<'_>>) {
+    let api = match try_get_api() {
+        Some(api) => api,
+        None => return,
+    };
+
+    flush_output();
+
+    let thread = std::thread::current();
+    let thread = thread.name().unwrap_or("<unnamed>");
+    let message = format!(
+        "Rust panic in thread '{thread}': {}\nBacktrace:\n{}",
+        payload_message(payload),
+        Backtrace::force_capture(),
+    );
+
+    let (file, line) = location.map_or(("<unknown>", 0), |loc| (loc.file(), loc.line()));
+
+    // The hook must not panic itself, so NUL bytes are replaced instead of unwrapped.
+    let message = c_string_lossy(&message);
+    let file = c_string_lossy(file);
+    let func = c_string_lossy(thread);
+
+    unsafe {
+        (api.godot_print_error)(
+            message.as_ptr(),
+            func.as_ptr(),
+            file.as_ptr(),
+            line as libc::c_int,
+        );
+    }
+}
+
+/// Signals handled by the crash handler.
+const CRASH_SIGNALS: [libc::c_int; 4] = [libc::SIGINT, libc::SIGTERM, libc::SIGABRT, libc::SIGSEGV];
+
+/// Value of [`PREVIOUS_HANDLERS`] for signals the crash handler isn't installed for.
+const NOT_INSTALLED: libc::sighandler_t = libc::sighandler_t::MAX;
+
+/// Handlers that were installed before the crash handler, in the order of [`CRASH_SIGNALS`].
+static PREVIOUS_HANDLERS: [AtomicUsize; 4] = [
+    AtomicUsize::new(NOT_INSTALLED),
+    AtomicUsize::new(NOT_INSTALLED),
+    AtomicUsize::new(NOT_INSTALLED),
+    AtomicUsize::new(NOT_INSTALLED),
+];
+
+/// Set while pending output is flushed from a signal handler, so that a signal raised during
+/// the flush doesn't start another one.
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+/// Installs [the panic hook](install_panic_hook), and a handler for `SIGINT`, `SIGTERM`,
+/// `SIGABRT` and `SIGSEGV` that flushes pending output before the process exits.
+///
+/// Headless server builds are commonly stopped with Ctrl+C or by a service manager, which
+/// terminates the process without running any destructors. When one of the signals above is
+/// received, the handler flushes the standard streams and the `log` crate logger, and reports
+/// allocations recorded by [`profiler::alloc`](crate::profiler::alloc) that weren't taken yet,
+/// when the `alloc-tracking` feature is enabled. The handler that was installed before, such as
+/// the crash handler of the engine, is called afterwards, or the default action is taken if
+/// there was none. Signals that were ignored stay ignored.
+///
+/// The same data is flushed when the library is terminated, at which point the previous
+/// handlers are restored. Calling this function again while the handler is installed has no
+/// effect.
+///
+/// Flushing isn't async-signal-safe: it's done on a best-effort basis, and may deadlock if the
+/// signal interrupted a thread holding a lock of the flushed data, e.g. after a segmentation
+/// fault in the allocator. Previous handlers are called with the signal number only.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// fn init(handle: InitHandle) {
+///     gdnative::log::install_crash_handler();
+///     // handle.add_class::<...>();
+/// }
+/// ```
+#[inline]
+pub fn install_crash_handler() {
+    install_panic_hook();
+
+    let handler = handle_crash_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for (&signal, previous) in CRASH_SIGNALS.iter().zip(&PREVIOUS_HANDLERS) {
+        if previous.load(Ordering::Acquire) != NOT_INSTALLED {
+            continue;
+        }
+
+        // SAFETY: `handle_crash_signal` is an `extern "C" fn(c_int)`, which is the signature
+        // of signal handlers, and is restored by `shutdown_crash_handler` before the library
+        // can be unloaded.
+        let old = unsafe { libc::signal(signal, handler) };
+        if old == libc::SIG_IGN {
+            // SAFETY: restores the disposition that was just replaced.
+            unsafe { libc::signal(signal, libc::SIG_IGN) };
+        } else {
+            // `SIG_ERR` is the same as `NOT_INSTALLED`, so signals that failed stay unhandled.
+            previous.store(old, Ordering::Release);
+        }
+    }
+}
+
+/// Flushes pending output and restores the signal handlers replaced by
+/// [`install_crash_handler`], if it was called.
+pub(crate) fn shutdown_crash_handler() {
+    let mut installed = false;
+    for (&signal, previous) in CRASH_SIGNALS.iter().zip(&PREVIOUS_HANDLERS) {
+        let old = previous.swap(NOT_INSTALLED, Ordering::AcqRel);
+        if old != NOT_INSTALLED {
+            installed = true;
+            // SAFETY: `old` is the handler that was installed before `handle_crash_signal`.
+            unsafe { libc::signal(signal, old) };
+        }
+    }
+
+    if installed {
+        flush_pending();
+    }
+}
+
+extern "C" fn handle_crash_signal(signal: libc::c_int) {
+    if !FLUSHING.swap(true, Ordering::AcqRel) {
+        flush_pending();
+        FLUSHING.store(false, Ordering::Release);
+    }
+
+    let previous = CRASH_SIGNALS
+        .iter()
+        .position(|&s| s == signal)
+        .map_or(NOT_INSTALLED, |i| {
+            PREVIOUS_HANDLERS[i].load(Ordering::Acquire)
+        });
+
+    match previous {
+        libc::SIG_DFL | libc::SIG_IGN | NOT_INSTALLED => {
+            // SAFETY: resetting to the default action and raising the signal again terminates
+            // the process the way it would have been without the crash handler.
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        }
+        handler => {
+            // SAFETY: `handler` was returned by `signal`, so it's a signal handler function.
+            let handler: extern "C" fn(libc::c_int) = unsafe { std::mem::transmute(handler) };
+            handler(signal);
+        }
+    }
+}
+
+/// Flushes the standard streams and the `log` crate logger.
+fn flush_output() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+    #[cfg(feature = "log")]
+    ::log::logger().flush();
+}
+
+/// Flushes output and profiler data that would otherwise be lost when the process exits.
+fn flush_pending() {
+    #[cfg(feature = "alloc-tracking")]
+    {
+        let report = crate::profiler::alloc::take_report();
+        if !report.is_empty() {
+            if try_get_api().is_some() {
+                print(format_args!("Unreported {report}"));
+            } else {
+                eprintln!("Unreported {report}");
+            }
+        }
+    }
+
+    flush_output();
+}
+
+/// Returns the message of a panic payload, if it's a string.
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&'static str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "<non-string payload>"
+    }
+}
+
+fn c_string_lossy(s: &str) -> CString {
+    CString::new(s.replace('\0', "\u{fffd}")).unwrap_or_default()
+}
+
+/// Writer that redirects formatted output into the Godot console, one batch of complete
+/// lines at a time.
+///
+/// Implements both [`fmt::Write`] and [`io::Write`], so it can be used as a target for the
+/// `write!` and `writeln!` macros, or anywhere a generic writer is expected. Output is buffered
+/// until a line is completed. Incomplete lines are printed when the writer is flushed or dropped.
+///
+/// Bytes written through `io::Write` must be UTF-8, but characters may be split across writes:
+/// the bytes of an incomplete character are kept until the next write. If the writer is flushed
+/// or dropped before the character is completed, they are printed as `U+FFFD`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fmt::Write;
+/// use gdnative::log::GodotPrintWriter;
+///
+/// let mut writer = GodotPrintWriter::new();
+/// writeln!(writer, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// ```
+///
+/// # Panics
+///
+/// Writes may panic if the API isn't initialized.
+#[derive(Debug, Default)]
+pub struct GodotPrintWriter {
+    buffer: LineBuffer,
+}
+
+impl GodotPrintWriter {
+    /// Creates a new writer with an empty buffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Write for GodotPrintWriter {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(lines) = self.buffer.push(s) {
+            print(lines);
+        }
+        Ok(())
+    }
+}
+
+impl io::Write for GodotPrintWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let lines = self
+            .buffer
+            .push_bytes(buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(lines) = lines {
+            print(lines);
+        }
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(rest) = self.buffer.take() {
+            print(rest);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for GodotPrintWriter {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(rest) = self.buffer.take() {
+            print(rest);
+        }
+    }
+}
+
+/// Buffer that collects text until one or more lines are complete.
+#[derive(Debug, Default)]
+struct LineBuffer {
+    pending: String,
+    /// Bytes at the end of the last `push_bytes` that are the start of an incomplete character.
+    incomplete: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Appends `s` to the buffer. Returns all complete lines in the buffer, without the final
+    /// line break, if any.
+    fn push(&mut self, s: &str) -> Option<String> {
+        self.take_incomplete();
+        self.pending.push_str(s);
+        self.split_lines()
+    }
+
+    /// Appends UTF-8 encoded bytes to the buffer, keeping an incomplete character at the end
+    /// until it's completed by the next call. Returns all complete lines like `push`.
+    ///
+    /// Nothing is appended if `buf` isn't valid UTF-8.
+    fn push_bytes(&mut self, buf: &[u8]) -> Result<Option<String>, std::str::Utf8Error> {
+        let len = self.incomplete.len();
+        self.incomplete.extend_from_slice(buf);
+
+        let valid = match std::str::from_utf8(&self.incomplete) {
+            Ok(s) => {
+                self.pending.push_str(s);
+                self.incomplete.len()
+            }
+            // The bytes end in the middle of a character
+            Err(e) if e.error_len().is_none() => {
+                let valid = e.valid_up_to();
+                // SAFETY: the bytes up to `valid_up_to` were checked to be valid UTF-8.
+                let s = unsafe { std::str::from_utf8_unchecked(&self.incomplete[..valid]) };
+                self.pending.push_str(s);
+                valid
+            }
+            Err(e) => {
+                self.incomplete.truncate(len);
+                return Err(e);
+            }
+        };
+
+        self.incomplete.drain(..valid);
+        Ok(self.split_lines())
+    }
+
+    /// Moves the bytes of an incomplete character into the pending text, replacing them with
+    /// `U+FFFD`.
+    fn take_incomplete(&mut self) {
+        if !self.incomplete.is_empty() {
+            self.pending
+                .push_str(&String::from_utf8_lossy(&self.incomplete));
+            self.incomplete.clear();
+        }
+    }
+
+    /// Splits off all complete lines in the buffer.
+    fn split_lines(&mut self) -> Option<String> {
+        let end = self.pending.rfind('\n')?;
+        let rest = self.pending.split_off(end + 1);
+        let mut lines = std::mem::replace(&mut self.pending, rest);
+        lines.truncate(end);
+        if lines.ends_with('\r') {
+            lines.pop();
+        }
+        Some(lines)
+    }
+
+    /// Takes the incomplete line in the buffer, if it's not empty.
+    fn take(&mut self) -> Option<String> {
+        self.take_incomplete();
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.pending))
+        }
+    }
+}
+
+/// Adapter for the [`log`](https://docs.rs/log) crate, which outputs records to the
+/// Godot console.
+///
+/// Records at the `Error` and `Warn` levels are reported as errors and warnings respectively,
+/// with their source locations. Other records are printed as messages, prefixed with their
+/// level and target.
+///
+/// To filter records by category, write them to a JSON file, or show them in a log panel, use
+/// the logger of [`GodotLogger::builder`] instead.
+///
+/// This is only available with the `log` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gdnative::log::GodotLogger;
+///
+/// GodotLogger::install(::log::LevelFilter::Info).expect("no other logger is installed");
+/// ::log::info!("Hello from the log crate!");
+/// ```
+#[cfg(feature = "log")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct GodotLogger;
+
+#[cfg(feature = "log")]
+impl GodotLogger {
+    /// Sets `GodotLogger` as the global logger for the `log` crate, and sets the maximum
+    /// log level to `max_level`.
+    ///
+    /// # Errors
+    ///
+    /// If a global logger was already set.
+    #[inline]
+    pub fn install(max_level: ::log::LevelFilter) -> Result<(), ::log::SetLoggerError> {
+        static LOGGER: GodotLogger = GodotLogger;
+        ::log::set_logger(&LOGGER)?;
+        ::log::set_max_level(max_level);
+        Ok(())
+    }
+
+    /// Returns a builder for a structured logger, which filters records by category and can
+    /// write them to a JSON file as well. See [`LoggerBuilder`].
+    #[inline]
+    pub fn builder() -> LoggerBuilder {
+        LoggerBuilder::new()
+    }
+}
+
+#[cfg(feature = "log")]
+impl ::log::Log for GodotLogger {
+    #[inline]
+    fn enabled(&self, metadata: &::log::Metadata<'_>) -> bool {
+        metadata.level() <= ::log::max_level()
+    }
+
+    #[inline]
+    fn log(&self, record: &::log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            print_record(record);
+        }
+    }
+
+    #[inline]
+    fn flush(&self) {}
+}
+
+/// Outputs a record to the Godot console, as an error, warning or message depending on its level.
+#[cfg(feature = "log")]
+fn print_record(record: &::log::Record<'_>) {
+    use ::log::Level;
+
+    match record.level() {
+        Level::Error | Level::Warn => {
+            let file =
+                ::std::ffi::CString::new(record.file().unwrap_or("<unknown>")).unwrap_or_default();
+            let func = ::std::ffi::CString::new(record.module_path().unwrap_or(record.target()))
+                .unwrap_or_default();
+            let site = Site::new(&file, &func, record.line().unwrap_or(0));
+
+            if record.level() == Level::Error {
+                error(site, record.args());
+            } else {
+                warn(site, record.args());
+            }
+        }
+        level => print(format_args!(
+            "[{level}] {}: {}",
+            record.target(),
+            record.args()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        c_string_lossy, handle_crash_signal, install_crash_handler, payload_message,
+        shutdown_crash_handler, LineBuffer,
+    };
+    use std::any::Any;
+
+    #[test]
+    fn line_buffer_batches_complete_lines() {
+        let mut buffer = LineBuffer::default();
+
+        assert_eq!(None, buffer.push("foo"));
+        assert_eq!(Some("foobar".to_string()), buffer.push("bar\n"));
+        assert_eq!(None, buffer.take());
+
+        assert_eq!(Some("a\nb".to_string()), buffer.push("a\nb\r\nc"));
+        assert_eq!(Some("c".to_string()), buffer.take());
+        assert_eq!(None, buffer.take());
+    }
+
+    #[test]
+    fn line_buffer_empty_lines() {
+        let mut buffer = LineBuffer::default();
+
+        assert_eq!(Some(String::new()), buffer.push("\n"));
+        assert_eq!(Some("\n".to_string()), buffer.push("\n\n"));
+        assert_eq!(None, buffer.take());
+    }
+
+    #[test]
+    fn line_buffer_joins_split_characters() {
+        let mut buffer = LineBuffer::default();
+        let bytes = "é€\n".as_bytes();
+
+        assert_eq!(Ok(None), buffer.push_bytes(&bytes[..1]));
+        assert_eq!(Ok(None), buffer.push_bytes(&bytes[1..3]));
+        assert_eq!(Ok(Some("é€".to_string())), buffer.push_bytes(&bytes[3..]));
+
+        assert_eq!(Ok(None), buffer.push_bytes(&bytes[..3]));
+        assert_eq!(Some("é\u{fffd}".to_string()), buffer.take());
+    }
+
+    #[test]
+    fn line_buffer_rejects_invalid_bytes() {
+        let mut buffer = LineBuffer::default();
+
+        assert_eq!(Ok(None), buffer.push_bytes(b"a\xc3"));
+        assert!(buffer.push_bytes(b"\xffb\n").is_err());
+        assert_eq!(Ok(Some("aé".to_string())), buffer.push_bytes(b"\xa9\n"));
+    }
+
+    #[test]
+    fn panic_payload_messages() {
+        let borrowed: Box<dyn Any + Send> = Box::new("foo");
+        let owned: Box<dyn Any + Send> = Box::new("bar".to_string());
+        let other: Box<dyn Any + Send> = Box::new(42);
+
+        assert_eq!("foo", payload_message(&*borrowed));
+        assert_eq!("bar", payload_message(&*owned));
+        assert_eq!("<non-string payload>", payload_message(&*other));
+    }
+
+    #[test]
+    fn c_string_lossy_replaces_nul() {
+        assert_eq!("a\u{fffd}b", c_string_lossy("a\0b").to_str().unwrap());
+    }
+
+    #[test]
+    fn crash_handler_restores_previous_handlers() {
+        /// Returns the current handler of `SIGTERM`.
+        fn current_handler() -> libc::sighandler_t {
+            // SAFETY: the handler is put back right away.
+            unsafe {
+                let handler = libc::signal(libc::SIGTERM, libc::SIG_DFL);
+                libc::signal(libc::SIGTERM, handler);
+                handler
+            }
+        }
+
+        install_crash_handler();
+        let handler = handle_crash_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        assert_eq!(handler, current_handler());
+
+        shutdown_crash_handler();
+        assert_eq!(libc::SIG_DFL, current_handler());
+    }
+}
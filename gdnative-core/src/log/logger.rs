@@ -0,0 +1,582 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write as _};
+use std::iter;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ::log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use parking_lot::{Mutex, RwLock};
+
+use crate::private::try_get_api;
+
+static LOGGER: OnceCell<StructuredLogger> = OnceCell::new();
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Builder for a logger that filters records by category, and writes them to the Godot console,
+/// a JSON file and an in-memory history.
+///
+/// The category of a record is its `log` target, which is the module path by default, and can be
+/// set explicitly with e.g. `log::info!(target: "net", ...)`. A level set for a category applies
+/// to all categories nested in it, so a level for `my_game::net` also applies to
+/// `my_game::net::sync`. The most specific category wins.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gdnative::log::GodotLogger;
+/// use log::LevelFilter;
+///
+/// GodotLogger::builder()
+///     .level(LevelFilter::Warn)
+///     .category("my_game::net", LevelFilter::Debug)
+///     .json_file("logs/game.jsonl")
+///     .install()
+///     .expect("no other logger is installed");
+/// ```
+///
+/// The levels can be changed at run time with [`set_level`] and [`set_category_level`].
+///
+/// This is only available with the `log` feature.
+#[derive(Debug)]
+#[must_use = "LoggerBuilder left uninstalled -- did you forget to call install()?"]
+pub struct LoggerBuilder {
+    filter: Filter,
+    json_file: Option<PathBuf>,
+    console: bool,
+    history: usize,
+}
+
+impl Default for LoggerBuilder {
+    #[inline]
+    fn default() -> Self {
+        LoggerBuilder {
+            filter: Filter::new(LevelFilter::Info),
+            json_file: None,
+            console: true,
+            history: 1000,
+        }
+    }
+}
+
+impl LoggerBuilder {
+    /// Creates a builder that logs records at the `Info` level and above to the Godot console.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the level for records whose category has no level of its own.
+    #[inline]
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.filter.default = level;
+        self
+    }
+
+    /// Sets the level for records in `category` and the categories nested in it.
+    #[inline]
+    pub fn category(mut self, category: impl Into<String>, level: LevelFilter) -> Self {
+        self.filter.set(category.into(), Some(level));
+        self
+    }
+
+    /// Sets the levels from a list of directives separated by `,`, in the format of
+    /// `RUST_LOG`: `"warn, my_game::net=debug"` sets the default level to `Warn` and the level of
+    /// `my_game::net` to `Debug`.
+    ///
+    /// # Errors
+    ///
+    /// If a directive has an unknown level.
+    #[inline]
+    pub fn filters(mut self, spec: &str) -> Result<Self, FilterParseError> {
+        self.filter.apply(spec)?;
+        Ok(self)
+    }
+
+    /// Also appends each record to the file at `path` as a line of JSON. The path is a file
+    /// system path, so `res://` and `user://` paths must be globalized first.
+    #[inline]
+    pub fn json_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.json_file = Some(path.into());
+        self
+    }
+
+    /// Sets whether records are printed to the Godot console. Enabled by default.
+    ///
+    /// Records at the `Error` and `Warn` levels are reported as errors and warnings respectively,
+    /// with their source locations, and other records are printed as messages.
+    #[inline]
+    pub fn console(mut self, enabled: bool) -> Self {
+        self.console = enabled;
+        self
+    }
+
+    /// Sets how many of the latest records are kept for [`records_since`], e.g. for a log panel.
+    /// Defaults to 1000.
+    #[inline]
+    pub fn history(mut self, capacity: usize) -> Self {
+        self.history = capacity;
+        self
+    }
+
+    /// Sets the logger as the global logger for the `log` crate.
+    ///
+    /// # Errors
+    ///
+    /// If the JSON file can't be opened, or if a global logger was already set.
+    #[inline]
+    pub fn install(self) -> Result<(), LoggerInstallError> {
+        let json = self
+            .json_file
+            .map(|path| OpenOptions::new().create(true).append(true).open(path))
+            .transpose()
+            .map_err(LoggerInstallError::Io)?
+            .map(|file| Mutex::new(LineWriter::new(file)));
+
+        let max_level = self.filter.max_level();
+        let mut logger = Some(StructuredLogger {
+            filter: RwLock::new(self.filter),
+            json,
+            console: self.console,
+            history: Mutex::new(History {
+                next_sequence: 0,
+                capacity: self.history,
+                records: VecDeque::new(),
+            }),
+        });
+
+        let installed = LOGGER.get_or_init(|| logger.take().unwrap());
+        if logger.is_some() {
+            return Err(LoggerInstallError::AlreadyInstalled);
+        }
+
+        ::log::set_logger(installed).map_err(|_| LoggerInstallError::AlreadyInstalled)?;
+        ::log::set_max_level(max_level);
+        INSTALLED.store(true, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Sets the level for records whose category has no level of its own. Returns `false` if the
+/// logger of [`LoggerBuilder`] isn't installed.
+#[inline]
+pub fn set_level(level: LevelFilter) -> bool {
+    update_filter(|filter| filter.default = level)
+}
+
+/// Sets the level for records in `category` and the categories nested in it, or removes it if
+/// `level` is `None`. Returns `false` if the logger of [`LoggerBuilder`] isn't installed.
+#[inline]
+pub fn set_category_level(category: &str, level: Option<LevelFilter>) -> bool {
+    update_filter(|filter| filter.set(category.to_owned(), level))
+}
+
+/// Replaces all levels with the ones in `spec`, in the format of [`LoggerBuilder::filters`].
+/// Returns `Ok(false)` if the logger of [`LoggerBuilder`] isn't installed.
+///
+/// # Errors
+///
+/// If a directive has an unknown level. The levels are left unchanged in that case.
+#[inline]
+pub fn set_filters(spec: &str) -> Result<bool, FilterParseError> {
+    let mut new_filter = Filter::new(LevelFilter::Info);
+    new_filter.apply(spec)?;
+    Ok(update_filter(|filter| *filter = new_filter))
+}
+
+/// Returns the records in the history whose sequence number is `sequence` or greater, oldest
+/// first. Pass the sequence number after the last one seen to only get new records.
+///
+/// Returns an empty list if the logger of [`LoggerBuilder`] isn't installed.
+#[inline]
+pub fn records_since(sequence: u64) -> Vec<LogRecord> {
+    let Some(logger) = installed() else {
+        return Vec::new();
+    };
+
+    let history = logger.history.lock();
+    let start = history
+        .records
+        .partition_point(|record| record.sequence < sequence);
+    history.records.range(start..).cloned().collect()
+}
+
+fn installed() -> Option<&'static StructuredLogger> {
+    LOGGER.get().filter(|_| INSTALLED.load(Ordering::Acquire))
+}
+
+fn update_filter(f: impl FnOnce(&mut Filter)) -> bool {
+    let Some(logger) = installed() else {
+        return false;
+    };
+
+    let mut filter = logger.filter.write();
+    f(&mut filter);
+    ::log::set_max_level(filter.max_level());
+    true
+}
+
+/// A record kept by the logger of [`LoggerBuilder`], returned by [`records_since`].
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    sequence: u64,
+    timestamp: SystemTime,
+    level: Level,
+    category: String,
+    message: String,
+    file: Option<String>,
+    line: Option<u32>,
+}
+
+impl LogRecord {
+    /// Returns the sequence number of the record. Records are numbered in the order they were
+    /// logged, starting at 0.
+    #[inline]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns the time when the record was logged.
+    #[inline]
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Returns the level of the record.
+    #[inline]
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Returns the category of the record, i.e. its `log` target.
+    #[inline]
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// Returns the formatted message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the source file where the record was logged, if known.
+    #[inline]
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the line in the source file where the record was logged, if known.
+    #[inline]
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+}
+
+/// Error returned by [`LoggerBuilder::install`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoggerInstallError {
+    /// The JSON file couldn't be opened.
+    Io(io::Error),
+    /// A global logger was already set.
+    AlreadyInstalled,
+}
+
+impl Display for LoggerInstallError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoggerInstallError::Io(err) => write!(f, "failed to open the JSON log file: {err}"),
+            LoggerInstallError::AlreadyInstalled => write!(f, "a global logger was already set"),
+        }
+    }
+}
+
+impl std::error::Error for LoggerInstallError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoggerInstallError::Io(err) => Some(err),
+            LoggerInstallError::AlreadyInstalled => None,
+        }
+    }
+}
+
+/// Error returned when a filter directive can't be parsed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FilterParseError {
+    directive: String,
+}
+
+impl Display for FilterParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid log filter directive `{}`, expected `level` or `category=level`",
+            self.directive
+        )
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+/// Levels of the categories, and the default level.
+#[derive(Clone, Debug)]
+struct Filter {
+    default: LevelFilter,
+    /// Sorted by decreasing length, so that the first matching category is the most specific.
+    categories: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    fn new(default: LevelFilter) -> Self {
+        Filter {
+            default,
+            categories: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, category: String, level: Option<LevelFilter>) {
+        self.categories.retain(|(c, _)| *c != category);
+        if let Some(level) = level {
+            self.categories.push((category, level));
+            self.categories
+                .sort_by_key(|(c, _)| std::cmp::Reverse(c.len()));
+        }
+    }
+
+    fn level(&self, target: &str) -> LevelFilter {
+        self.categories
+            .iter()
+            .find(|(category, _)| is_in_category(target, category))
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.categories
+            .iter()
+            .map(|(_, level)| *level)
+            .chain(iter::once(self.default))
+            .max()
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    /// Applies the directives in `spec`. Nothing is applied if any directive is invalid.
+    fn apply(&mut self, spec: &str) -> Result<(), FilterParseError> {
+        let mut filter = self.clone();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let err = || FilterParseError {
+                directive: directive.to_owned(),
+            };
+
+            match directive.split_once('=') {
+                Some((category, level)) => {
+                    let category = category.trim();
+                    if category.is_empty() {
+                        return Err(err());
+                    }
+                    let level = LevelFilter::from_str(level.trim()).map_err(|_| err())?;
+                    filter.set(category.to_owned(), Some(level));
+                }
+                None => filter.default = LevelFilter::from_str(directive).map_err(|_| err())?,
+            }
+        }
+
+        *self = filter;
+        Ok(())
+    }
+}
+
+/// Returns `true` if `target` is `category` or nested in it.
+fn is_in_category(target: &str, category: &str) -> bool {
+    target
+        .strip_prefix(category)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+struct History {
+    next_sequence: u64,
+    capacity: usize,
+    records: VecDeque<LogRecord>,
+}
+
+struct StructuredLogger {
+    filter: RwLock<Filter>,
+    json: Option<Mutex<LineWriter<File>>>,
+    console: bool,
+    history: Mutex<History>,
+}
+
+impl Log for StructuredLogger {
+    #[inline]
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.filter.read().level(metadata.target())
+    }
+
+    #[inline]
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Records may be logged before the library is initialized, or after it's terminated.
+        if self.console && try_get_api().is_some() {
+            super::print_record(record);
+        }
+
+        let mut history = self.history.lock();
+        let record = LogRecord {
+            sequence: history.next_sequence,
+            timestamp: SystemTime::now(),
+            level: record.level(),
+            category: record.target().to_owned(),
+            message: record.args().to_string(),
+            file: record.file().map(ToOwned::to_owned),
+            line: record.line(),
+        };
+        history.next_sequence += 1;
+
+        if let Some(json) = &self.json {
+            // There is nowhere to report write errors to.
+            let _ = writeln!(json.lock(), "{}", Json(&record));
+        }
+
+        if history.capacity > 0 {
+            if history.records.len() == history.capacity {
+                history.records.pop_front();
+            }
+            history.records.push_back(record);
+        }
+    }
+
+    #[inline]
+    fn flush(&self) {
+        if let Some(json) = &self.json {
+            let _ = json.lock().flush();
+        }
+    }
+}
+
+/// Formats a record as a JSON object.
+struct Json<'a>(&'a LogRecord);
+
+impl Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let record = self.0;
+        let timestamp = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        write!(
+            f,
+            r#"{{"sequence":{},"timestamp":{timestamp:.3},"level":"{}","category":{},"message":{}"#,
+            record.sequence,
+            record.level,
+            JsonStr(&record.category),
+            JsonStr(&record.message),
+        )?;
+        if let Some(file) = &record.file {
+            write!(f, r#","file":{}"#, JsonStr(file))?;
+        }
+        if let Some(line) = record.line {
+            write!(f, r#","line":{line}"#)?;
+        }
+        f.write_char('}')
+    }
+}
+
+/// Formats a string as a JSON string literal.
+struct JsonStr<'a>(&'a str);
+
+impl Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_category_wins() {
+        let mut filter = Filter::new(LevelFilter::Warn);
+        filter
+            .apply("game=info, game::net=trace, game::net::sync=off")
+            .unwrap();
+
+        assert_eq!(LevelFilter::Warn, filter.level("other"));
+        assert_eq!(LevelFilter::Warn, filter.level("gameplay"));
+        assert_eq!(LevelFilter::Info, filter.level("game"));
+        assert_eq!(LevelFilter::Info, filter.level("game::ai"));
+        assert_eq!(LevelFilter::Trace, filter.level("game::net::lobby"));
+        assert_eq!(LevelFilter::Off, filter.level("game::net::sync"));
+        assert_eq!(LevelFilter::Trace, filter.max_level());
+
+        filter.set("game::net".into(), None);
+        assert_eq!(LevelFilter::Info, filter.level("game::net::lobby"));
+        assert_eq!(LevelFilter::Info, filter.max_level());
+    }
+
+    #[test]
+    fn invalid_directives_are_rejected() {
+        let mut filter = Filter::new(LevelFilter::Warn);
+
+        assert_eq!(
+            Err(FilterParseError {
+                directive: "game=loud".into()
+            }),
+            filter.apply("debug, game=loud")
+        );
+        assert!(filter.apply("=info").is_err());
+        assert_eq!(LevelFilter::Warn, filter.default);
+
+        filter.apply(" ERROR ,, ").unwrap();
+        assert_eq!(LevelFilter::Error, filter.default);
+    }
+
+    #[test]
+    fn records_as_json() {
+        let mut record = LogRecord {
+            sequence: 3,
+            timestamp: UNIX_EPOCH + std::time::Duration::from_millis(1500),
+            level: Level::Info,
+            category: "game::net".into(),
+            message: "say \"hi\"\n\tback\\slash \u{1}".into(),
+            file: Some("src/net.rs".into()),
+            line: Some(42),
+        };
+
+        assert_eq!(
+            r#"{"sequence":3,"timestamp":1.500,"level":"INFO","category":"game::net","message":"say \"hi\"\n\tback\\slash \u0001","file":"src/net.rs","line":42}"#,
+            Json(&record).to_string()
+        );
+
+        record.file = None;
+        record.line = None;
+        assert!(Json(&record)
+            .to_string()
+            .ends_with(r#""message":"say \"hi\"\n\tback\\slash \u0001"}"#));
+    }
+}
@@ -35,6 +35,154 @@ macro_rules! godot_dbg {
     };
 }
 
+/// Creates a [`VariantArray`][crate::core_types::VariantArray] containing the arguments, each
+/// converted with [`OwnedToVariant`][crate::core_types::OwnedToVariant].
+///
+/// The array is returned with `Unique` access, so it can still be modified before it is shared.
+/// Arrays and dictionaries can be nested:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// let path = varray![Vector2::ZERO, Vector2::new(10.0, 0.0)];
+/// let waves = varray![
+///     varray!["slime", 3],
+///     dict! { "enemy": "bat", "count": 5, "path": path },
+/// ];
+/// ```
+#[macro_export]
+macro_rules! varray {
+    () => {
+        $crate::core_types::VariantArray::new()
+    };
+    ($($value:expr),+ $(,)?) => {{
+        let array = $crate::core_types::VariantArray::new();
+        $(
+            array.push($value);
+        )+
+        array
+    }};
+}
+
+/// Creates a [`Dictionary`][crate::core_types::Dictionary] from `key: value` pairs, each
+/// converted with [`OwnedToVariant`][crate::core_types::OwnedToVariant].
+///
+/// The dictionary is returned with `Unique` access, so it can still be modified before it is
+/// shared. Keys must be literals, identifiers or expressions in parentheses. Values can be any
+/// expression, including nested [`varray!`] and `dict!` invocations:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// let hp = 100;
+/// let player = dict! {
+///     "name": "Ferris",
+///     "hp": hp,
+///     "tags": varray!["red", "fast"],
+///     "stats": dict! { "speed": 2.5, "armor": 3 },
+///     (format!("slot_{}", 1)): "sword",
+/// };
+/// ```
+#[macro_export]
+macro_rules! dict {
+    () => {
+        $crate::core_types::Dictionary::new()
+    };
+    ($($key:tt : $value:expr),+ $(,)?) => {{
+        let dict = $crate::core_types::Dictionary::new();
+        $(
+            // Keys that are expressions are passed in parentheses.
+            #[allow(unused_parens)]
+            let key = $key;
+            dict.insert(key, $value);
+        )+
+        dict
+    }};
+}
+
+/// Exports a type generated by [`bitflags!`](https://docs.rs/bitflags) as a Flags property,
+/// which is shown as a set of checkboxes in the inspector.
+///
+/// The macro takes the type, followed by the flags to show, and implements
+/// [`ToVariant`][crate::core_types::ToVariant], [`FromVariant`][crate::core_types::FromVariant]
+/// and [`Export`][crate::export::Export] for it. The flags are converted to and from `i64`
+/// bitmasks. Converting a bitmask with bits that don't correspond to a flag of the type fails,
+/// so unknown bits are never stored in the field.
+///
+/// Flags should be listed with their single-bit values. Combinations of flags are accepted,
+/// but not shown in the inspector. Both `bitflags` 1.x and 2.x are supported.
+///
+/// The default hint can be replaced with an [`EnumHint`][crate::export::hint::EnumHint], e.g.
+/// to show different names in the inspector.
+///
+/// # Examples
+///
+/// ```ignore
+/// use gdnative::prelude::*;
+///
+/// bitflags::bitflags! {
+///     pub struct Layers: u32 {
+///         const GROUND = 1 << 0;
+///         const WATER = 1 << 1;
+///         const AIR = 1 << 3;
+///     }
+/// }
+///
+/// gdnative::export_flags!(Layers, GROUND, WATER, AIR);
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Unit {
+///     #[property]
+///     layers: Layers,
+/// }
+/// ```
+#[macro_export]
+macro_rules! export_flags {
+    ($ty:ty, $($flag:ident),+ $(,)?) => {
+        impl $crate::core_types::ToVariant for $ty {
+            #[inline]
+            fn to_variant(&self) -> $crate::core_types::Variant {
+                $crate::core_types::ToVariant::to_variant(&(self.bits() as i64))
+            }
+        }
+
+        impl $crate::core_types::FromVariant for $ty {
+            #[inline]
+            fn from_variant(
+                variant: &$crate::core_types::Variant,
+            ) -> ::std::result::Result<Self, $crate::core_types::FromVariantError> {
+                let bits = <i64 as $crate::core_types::FromVariant>::from_variant(variant)?;
+                ::std::convert::TryFrom::try_from(bits)
+                    .ok()
+                    .and_then(<$ty>::from_bits)
+                    .ok_or_else(|| {
+                        $crate::core_types::FromVariantError::Custom(::std::format!(
+                            "bitmask {:#x} contains unknown flags of {}",
+                            bits,
+                            ::std::stringify!($ty),
+                        ))
+                    })
+            }
+        }
+
+        impl $crate::export::Export for $ty {
+            type Hint = $crate::export::hint::EnumHint;
+
+            #[inline]
+            fn export_info(hint: ::std::option::Option<Self::Hint>) -> $crate::export::ExportInfo {
+                let hint = hint.unwrap_or_else(|| {
+                    $crate::export::hint::EnumHint::from_flags(&[
+                        $((::std::stringify!($flag), <$ty>::$flag.bits() as u64)),+
+                    ])
+                });
+                $crate::export::hint::IntHint::<i64>::Flags(hint).export_info()
+            }
+        }
+    };
+}
+
 /// Creates a [`Site`][crate::log::Site] value from the current position in code,
 /// optionally with a function path for identification.
 ///
@@ -0,0 +1,381 @@
+//! Fallbacks for `VariantArray`. Arrays are reference-counted and shared between copies.
+
+use std::cell::UnsafeCell;
+use std::ptr;
+use std::sync::Arc;
+
+use crate::sys::{self, godot_bool, godot_int};
+
+use super::variant::{self, Slot, Value};
+use super::{drop_arc, hash_djb2_one_32, load, sort_by_less, wrap_arc, HASH_SEED};
+
+/// Contents of an array.
+///
+/// Like in the engine, access is not synchronized. The collection types in `gdnative-core` only
+/// allow mutation through unique or thread-local references.
+#[derive(Default)]
+pub(super) struct ArrayData(UnsafeCell<Vec<Slot>>);
+
+unsafe impl Send for ArrayData {}
+unsafe impl Sync for ArrayData {}
+
+impl ArrayData {
+    pub(super) fn from_items(items: Vec<Slot>) -> Self {
+        ArrayData(UnsafeCell::new(items))
+    }
+
+    /// Returns the elements of the array.
+    ///
+    /// # Safety
+    ///
+    /// The array must not be modified while the slice is alive.
+    pub(super) unsafe fn items(&self) -> &[Slot] {
+        &*self.0.get()
+    }
+
+    /// # Safety
+    ///
+    /// The array must not be accessed through other references while the vector is alive.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn items_mut(&self) -> &mut Vec<Slot> {
+        &mut *self.0.get()
+    }
+
+    /// The engine's array hash function.
+    pub(super) unsafe fn hash(&self) -> u32 {
+        self.items()
+            .iter()
+            .fold(hash_djb2_one_32(0, HASH_SEED), |h, item| {
+                variant::hash_into(item.get(), h)
+            })
+    }
+
+    /// Copies the array. Nested arrays and dictionaries are copied as well if `deep` is set.
+    pub(super) unsafe fn duplicate(&self, deep: bool) -> Self {
+        let items = self
+            .items()
+            .iter()
+            .map(|item| Slot::new(duplicate_value(item.get(), deep)))
+            .collect();
+        ArrayData::from_items(items)
+    }
+}
+
+/// Copies a value for `duplicate`.
+pub(super) unsafe fn duplicate_value(value: &Value, deep: bool) -> Value {
+    match value {
+        Value::Array(arr) if deep => Value::Array(Arc::new(arr.duplicate(true))),
+        Value::Dictionary(dict) if deep => Value::Dictionary(Arc::new(dict.duplicate(true))),
+        value => value.clone(),
+    }
+}
+
+/// Creates an array holding a reference to `data`.
+pub(super) fn to_sys(data: ArrayData) -> sys::godot_array {
+    wrap_arc(Arc::new(data))
+}
+
+/// Returns the contents of an array.
+pub(super) unsafe fn get<'a>(arr: *const sys::godot_array) -> &'a ArrayData {
+    let data = load::<_, ArrayData>(arr);
+    debug_assert!(!data.is_null(), "array used before construction");
+    &*data
+}
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_array_new,
+        godot_array_new_copy,
+        godot_array_destroy,
+        godot_array_set,
+        godot_array_get,
+        godot_array_operator_index,
+        godot_array_operator_index_const,
+        godot_array_append,
+        godot_array_push_back,
+        godot_array_push_front,
+        godot_array_insert,
+        godot_array_remove,
+        godot_array_resize,
+        godot_array_clear,
+        godot_array_size,
+        godot_array_count,
+        godot_array_empty,
+        godot_array_erase,
+        godot_array_front,
+        godot_array_back,
+        godot_array_pop_back,
+        godot_array_pop_front,
+        godot_array_invert,
+        godot_array_find,
+        godot_array_find_last,
+        godot_array_has,
+        godot_array_rfind,
+        godot_array_hash,
+        godot_array_sort,
+        godot_array_duplicate,
+    );
+}
+
+unsafe fn slot<'a>(function: &str, arr: *const sys::godot_array, idx: godot_int) -> &'a mut Slot {
+    let items = get(arr).items_mut();
+    let len = items.len();
+    match usize::try_from(idx).ok().and_then(|i| items.get_mut(i)) {
+        Some(slot) => slot,
+        None => super::index_out_of_bounds(function, idx, len),
+    }
+}
+
+unsafe extern "C" fn godot_array_new(dest: *mut sys::godot_array) {
+    ptr::write(dest, to_sys(ArrayData::default()));
+}
+
+unsafe extern "C" fn godot_array_new_copy(
+    dest: *mut sys::godot_array,
+    src: *const sys::godot_array,
+) {
+    ptr::write(dest, wrap_arc::<_, ArrayData>(super::get_arc(src)));
+}
+
+unsafe extern "C" fn godot_array_destroy(arr: *mut sys::godot_array) {
+    drop_arc::<_, ArrayData>(arr);
+}
+
+unsafe extern "C" fn godot_array_set(
+    arr: *mut sys::godot_array,
+    idx: godot_int,
+    value: *const sys::godot_variant,
+) {
+    let value = variant::get(value).clone();
+    *slot("Array::set", arr, idx) = Slot::new(value);
+}
+
+unsafe extern "C" fn godot_array_get(
+    arr: *const sys::godot_array,
+    idx: godot_int,
+) -> sys::godot_variant {
+    variant::to_sys(slot("Array::get", arr, idx).get().clone())
+}
+
+unsafe extern "C" fn godot_array_operator_index(
+    arr: *mut sys::godot_array,
+    idx: godot_int,
+) -> *mut sys::godot_variant {
+    slot("Array::operator[]", arr, idx).as_mut_ptr()
+}
+
+unsafe extern "C" fn godot_array_operator_index_const(
+    arr: *const sys::godot_array,
+    idx: godot_int,
+) -> *const sys::godot_variant {
+    slot("Array::operator[]", arr, idx).as_ptr()
+}
+
+unsafe extern "C" fn godot_array_append(
+    arr: *mut sys::godot_array,
+    value: *const sys::godot_variant,
+) {
+    godot_array_push_back(arr, value);
+}
+
+unsafe extern "C" fn godot_array_push_back(
+    arr: *mut sys::godot_array,
+    value: *const sys::godot_variant,
+) {
+    let value = Slot::copy_from(value);
+    get(arr).items_mut().push(value);
+}
+
+unsafe extern "C" fn godot_array_push_front(
+    arr: *mut sys::godot_array,
+    value: *const sys::godot_variant,
+) {
+    let value = Slot::copy_from(value);
+    get(arr).items_mut().insert(0, value);
+}
+
+unsafe extern "C" fn godot_array_insert(
+    arr: *mut sys::godot_array,
+    pos: godot_int,
+    value: *const sys::godot_variant,
+) {
+    let value = Slot::copy_from(value);
+    let items = get(arr).items_mut();
+    match usize::try_from(pos) {
+        Ok(pos) if pos <= items.len() => items.insert(pos, value),
+        _ => super::log::error(
+            "Array::insert",
+            &format!("Index {pos} is out of bounds (size = {}).", items.len() + 1),
+        ),
+    }
+}
+
+unsafe extern "C" fn godot_array_remove(arr: *mut sys::godot_array, idx: godot_int) {
+    let items = get(arr).items_mut();
+    match usize::try_from(idx) {
+        Ok(idx) if idx < items.len() => drop(items.remove(idx)),
+        _ => super::log::error(
+            "Array::remove",
+            &format!("Index {idx} is out of bounds (size = {}).", items.len()),
+        ),
+    }
+}
+
+unsafe extern "C" fn godot_array_resize(arr: *mut sys::godot_array, size: godot_int) {
+    match usize::try_from(size) {
+        Ok(size) => get(arr)
+            .items_mut()
+            .resize_with(size, || Slot::new(Value::Nil)),
+        Err(_) => super::log::error("Array::resize", "Condition \"p_size < 0\" is true."),
+    }
+}
+
+unsafe extern "C" fn godot_array_clear(arr: *mut sys::godot_array) {
+    let items = std::mem::take(get(arr).items_mut());
+    drop(items);
+}
+
+unsafe extern "C" fn godot_array_size(arr: *const sys::godot_array) -> godot_int {
+    get(arr).items().len() as godot_int
+}
+
+unsafe extern "C" fn godot_array_count(
+    arr: *const sys::godot_array,
+    value: *const sys::godot_variant,
+) -> godot_int {
+    let value = variant::get(value);
+    get(arr)
+        .items()
+        .iter()
+        .filter(|item| variant::strict_eq(item.get(), value))
+        .count() as godot_int
+}
+
+unsafe extern "C" fn godot_array_empty(arr: *const sys::godot_array) -> godot_bool {
+    get(arr).items().is_empty()
+}
+
+unsafe extern "C" fn godot_array_erase(
+    arr: *mut sys::godot_array,
+    value: *const sys::godot_variant,
+) {
+    let idx = godot_array_find(arr, value, 0);
+    if idx >= 0 {
+        let removed = get(arr).items_mut().remove(idx as usize);
+        drop(removed);
+    }
+}
+
+unsafe fn end(function: &str, arr: *const sys::godot_array, back: bool) -> sys::godot_variant {
+    let items = get(arr).items();
+    let item = if back { items.last() } else { items.first() };
+    match item {
+        Some(item) => variant::to_sys(item.get().clone()),
+        None => {
+            super::log::error(function, "Can't take value from empty array.");
+            variant::to_sys(Value::Nil)
+        }
+    }
+}
+
+unsafe extern "C" fn godot_array_front(arr: *const sys::godot_array) -> sys::godot_variant {
+    end("Array::front", arr, false)
+}
+
+unsafe extern "C" fn godot_array_back(arr: *const sys::godot_array) -> sys::godot_variant {
+    end("Array::back", arr, true)
+}
+
+unsafe extern "C" fn godot_array_pop_back(arr: *mut sys::godot_array) -> sys::godot_variant {
+    let item = get(arr).items_mut().pop();
+    variant::to_sys(item.map_or(Value::Nil, |item| item.get().clone()))
+}
+
+unsafe extern "C" fn godot_array_pop_front(arr: *mut sys::godot_array) -> sys::godot_variant {
+    let items = get(arr).items_mut();
+    let item = (!items.is_empty()).then(|| items.remove(0));
+    variant::to_sys(item.map_or(Value::Nil, |item| item.get().clone()))
+}
+
+unsafe extern "C" fn godot_array_invert(arr: *mut sys::godot_array) {
+    get(arr).items_mut().reverse();
+}
+
+unsafe extern "C" fn godot_array_find(
+    arr: *const sys::godot_array,
+    what: *const sys::godot_variant,
+    from: godot_int,
+) -> godot_int {
+    let Ok(from) = usize::try_from(from) else {
+        return -1;
+    };
+
+    let what = variant::get(what);
+    let items = get(arr).items();
+    (from..items.len())
+        .find(|&i| variant::strict_eq(items[i].get(), what))
+        .map_or(-1, |i| i as godot_int)
+}
+
+unsafe extern "C" fn godot_array_find_last(
+    arr: *const sys::godot_array,
+    what: *const sys::godot_variant,
+) -> godot_int {
+    godot_array_rfind(arr, what, -1)
+}
+
+unsafe extern "C" fn godot_array_has(
+    arr: *const sys::godot_array,
+    value: *const sys::godot_variant,
+) -> godot_bool {
+    godot_array_find(arr, value, 0) != -1
+}
+
+unsafe extern "C" fn godot_array_rfind(
+    arr: *const sys::godot_array,
+    what: *const sys::godot_variant,
+    from: godot_int,
+) -> godot_int {
+    let items = get(arr).items();
+    let len = items.len() as godot_int;
+    if len == 0 {
+        return -1;
+    }
+
+    // Negative offsets are relative to the end.
+    let mut from = if from < 0 { len + from } else { from };
+    if from < 0 || from >= len {
+        from = len - 1;
+    }
+
+    let what = variant::get(what);
+    (0..=from as usize)
+        .rev()
+        .find(|&i| variant::strict_eq(items[i].get(), what))
+        .map_or(-1, |i| i as godot_int)
+}
+
+unsafe extern "C" fn godot_array_hash(arr: *const sys::godot_array) -> godot_int {
+    get(arr).hash() as godot_int
+}
+
+unsafe extern "C" fn godot_array_sort(arr: *mut sys::godot_array) {
+    let data = get(arr);
+    // Taken out of the array, so that comparisons can't observe a partially sorted array.
+    let items = std::mem::take(data.items_mut());
+    let sorted = sort_by_less(items, &|a: &Slot, b: &Slot| {
+        variant::evaluate_bool(
+            sys::godot_variant_operator_GODOT_VARIANT_OP_LESS,
+            a.get(),
+            b.get(),
+        )
+    });
+    *data.items_mut() = sorted;
+}
+
+unsafe extern "C" fn godot_array_duplicate(
+    arr: *const sys::godot_array,
+    deep: godot_bool,
+) -> sys::godot_array {
+    to_sys(get(arr).duplicate(deep))
+}
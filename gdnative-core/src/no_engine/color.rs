@@ -0,0 +1,153 @@
+//! Fallbacks for the `Color` methods that are implemented by the engine.
+
+use crate::core_types::Color;
+use crate::sys::{self, godot_bool, godot_real};
+
+use super::string;
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_color_get_h,
+        godot_color_get_s,
+        godot_color_get_v,
+        godot_color_from_hsv,
+        godot_color_blend,
+        godot_color_contrasted,
+        godot_color_darkened,
+        godot_color_lightened,
+        godot_color_to_html,
+    );
+}
+
+unsafe fn get(color: *const sys::godot_color) -> Color {
+    Color::from_sys(*color)
+}
+
+fn min_max(c: Color) -> (f32, f32) {
+    (c.r.min(c.g).min(c.b), c.r.max(c.g).max(c.b))
+}
+
+unsafe extern "C" fn godot_color_get_h(color: *const sys::godot_color) -> godot_real {
+    let c = get(color);
+    let (min, max) = min_max(c);
+    let delta = max - min;
+    if delta == 0.0 {
+        return 0.0;
+    }
+
+    let h = if c.r == max {
+        (c.g - c.b) / delta
+    } else if c.g == max {
+        2.0 + (c.b - c.r) / delta
+    } else {
+        4.0 + (c.r - c.g) / delta
+    } / 6.0;
+
+    if h < 0.0 {
+        h + 1.0
+    } else {
+        h
+    }
+}
+
+unsafe extern "C" fn godot_color_get_s(color: *const sys::godot_color) -> godot_real {
+    let (min, max) = min_max(get(color));
+    if max == 0.0 {
+        0.0
+    } else {
+        (max - min) / max
+    }
+}
+
+unsafe extern "C" fn godot_color_get_v(color: *const sys::godot_color) -> godot_real {
+    min_max(get(color)).1
+}
+
+unsafe extern "C" fn godot_color_from_hsv(
+    _color: *const sys::godot_color,
+    h: godot_real,
+    s: godot_real,
+    v: godot_real,
+    a: godot_real,
+) -> sys::godot_color {
+    if s == 0.0 {
+        return Color::from_rgba(v, v, v, a).to_sys();
+    }
+
+    let h = (h * 6.0) % 6.0;
+    let i = h.floor();
+    let f = h - i;
+    let p = v * (1.0 - s);
+    let q = v * (1.0 - s * f);
+    let t = v * (1.0 - s * (1.0 - f));
+
+    let (r, g, b) = match i as i32 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+
+    Color::from_rgba(r, g, b, a).to_sys()
+}
+
+unsafe extern "C" fn godot_color_blend(
+    color: *const sys::godot_color,
+    over: *const sys::godot_color,
+) -> sys::godot_color {
+    let (c, over) = (get(color), get(over));
+    let sa = 1.0 - over.a;
+    let a = c.a * sa + over.a;
+    if a == 0.0 {
+        return Color::from_rgba(0.0, 0.0, 0.0, 0.0).to_sys();
+    }
+
+    let blend = |c1: f32, c2: f32| (c1 * c.a * sa + c2 * over.a) / a;
+    Color::from_rgba(
+        blend(c.r, over.r),
+        blend(c.g, over.g),
+        blend(c.b, over.b),
+        a,
+    )
+    .to_sys()
+}
+
+unsafe extern "C" fn godot_color_contrasted(color: *const sys::godot_color) -> sys::godot_color {
+    let c = get(color);
+    let contrast = |x: f32| (x + 0.5) % 1.0;
+    Color::from_rgba(contrast(c.r), contrast(c.g), contrast(c.b), c.a).to_sys()
+}
+
+unsafe extern "C" fn godot_color_darkened(
+    color: *const sys::godot_color,
+    amount: godot_real,
+) -> sys::godot_color {
+    let c = get(color);
+    let darken = |x: f32| x * (1.0 - amount);
+    Color::from_rgba(darken(c.r), darken(c.g), darken(c.b), c.a).to_sys()
+}
+
+unsafe extern "C" fn godot_color_lightened(
+    color: *const sys::godot_color,
+    amount: godot_real,
+) -> sys::godot_color {
+    let c = get(color);
+    let lighten = |x: f32| x + (1.0 - x) * amount;
+    Color::from_rgba(lighten(c.r), lighten(c.g), lighten(c.b), c.a).to_sys()
+}
+
+unsafe extern "C" fn godot_color_to_html(
+    color: *const sys::godot_color,
+    with_alpha: godot_bool,
+) -> sys::godot_string {
+    let c = get(color);
+    let hex = |x: f32| format!("{:02x}", ((x * 255.0 + 0.5).floor() as i32).clamp(0, 255));
+
+    let mut html = format!("{}{}{}", hex(c.r), hex(c.g), hex(c.b));
+    if with_alpha {
+        html.insert_str(0, &hex(c.a));
+    }
+    string::from_str(&html)
+}
@@ -0,0 +1,361 @@
+//! Fallbacks for `Dictionary`. Dictionaries are reference-counted and shared between copies, and
+//! keep their keys in insertion order.
+
+use std::cell::UnsafeCell;
+use std::hash::{Hash, Hasher};
+use std::ptr;
+use std::sync::Arc;
+
+use indexmap::{Equivalent, IndexMap};
+
+use crate::sys::{self, godot_bool, godot_int};
+
+use super::array::{self, duplicate_value, ArrayData};
+use super::string;
+use super::variant::{self, Slot, Value};
+use super::{drop_arc, hash_djb2_one_32, load, sort_by_less, wrap_arc, HASH_SEED};
+
+/// Dictionary key, compared like the engine's `VariantComparator`.
+#[repr(transparent)]
+struct Key(Slot);
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(variant::hash(self.0.get()));
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        variant::hash_compare(self.0.get(), other.0.get())
+    }
+}
+
+impl Eq for Key {}
+
+/// Borrowed key for lookups.
+struct KeyRef<'a>(&'a Value);
+
+impl Hash for KeyRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(variant::hash(self.0));
+    }
+}
+
+impl Equivalent<Key> for KeyRef<'_> {
+    fn equivalent(&self, key: &Key) -> bool {
+        variant::hash_compare(self.0, key.0.get())
+    }
+}
+
+/// Contents of a dictionary.
+///
+/// Like in the engine, access is not synchronized. See `ArrayData`.
+#[derive(Default)]
+pub(super) struct DictData(UnsafeCell<IndexMap<Key, Slot, ahash::RandomState>>);
+
+unsafe impl Send for DictData {}
+unsafe impl Sync for DictData {}
+
+impl DictData {
+    unsafe fn map(&self) -> &IndexMap<Key, Slot, ahash::RandomState> {
+        &*self.0.get()
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn map_mut(&self) -> &mut IndexMap<Key, Slot, ahash::RandomState> {
+        &mut *self.0.get()
+    }
+
+    pub(super) unsafe fn len(&self) -> usize {
+        self.map().len()
+    }
+
+    /// Returns the value for `key`.
+    ///
+    /// # Safety
+    ///
+    /// The dictionary must not be modified while the reference is alive.
+    pub(super) unsafe fn get(&self, key: &Value) -> Option<&Slot> {
+        self.map().get(&KeyRef(key))
+    }
+
+    /// Returns the entries in insertion order.
+    ///
+    /// # Safety
+    ///
+    /// The dictionary must not be modified while the iterator is alive.
+    pub(super) unsafe fn entries(&self) -> impl Iterator<Item = (&Slot, &Slot)> {
+        self.map().iter().map(|(key, value)| (&key.0, value))
+    }
+
+    /// Returns the value for `key`, inserting `Nil` if it is missing.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn entry(&self, key: &Value) -> &mut Slot {
+        let map = self.map_mut();
+        let index = match map.get_index_of(&KeyRef(key)) {
+            Some(index) => index,
+            None => {
+                let key = Key(Slot::new(key.clone()));
+                map.insert_full(key, Slot::new(Value::Nil)).0
+            }
+        };
+        &mut map[index]
+    }
+
+    /// The engine's dictionary hash function.
+    pub(super) unsafe fn hash(&self) -> u32 {
+        let seed = hash_djb2_one_32(
+            sys::godot_variant_type_GODOT_VARIANT_TYPE_DICTIONARY,
+            HASH_SEED,
+        );
+        self.entries().fold(seed, |h, (key, value)| {
+            variant::hash_into(value.get(), variant::hash_into(key.get(), h))
+        })
+    }
+
+    /// Copies the dictionary. Nested arrays and dictionaries in values are copied as well if
+    /// `deep` is set.
+    pub(super) unsafe fn duplicate(&self, deep: bool) -> Self {
+        let map = self
+            .map()
+            .iter()
+            .map(|(key, value)| {
+                (
+                    Key(key.0.clone()),
+                    Slot::new(duplicate_value(value.get(), deep)),
+                )
+            })
+            .collect();
+        DictData(UnsafeCell::new(map))
+    }
+}
+
+/// Returns the contents of a dictionary.
+unsafe fn get<'a>(dict: *const sys::godot_dictionary) -> &'a DictData {
+    let data = load::<_, DictData>(dict);
+    debug_assert!(!data.is_null(), "dictionary used before construction");
+    &*data
+}
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_dictionary_new,
+        godot_dictionary_new_copy,
+        godot_dictionary_destroy,
+        godot_dictionary_size,
+        godot_dictionary_empty,
+        godot_dictionary_clear,
+        godot_dictionary_has,
+        godot_dictionary_has_all,
+        godot_dictionary_erase,
+        godot_dictionary_erase_with_return,
+        godot_dictionary_get,
+        godot_dictionary_get_with_default,
+        godot_dictionary_set,
+        godot_dictionary_operator_index,
+        godot_dictionary_operator_index_const,
+        godot_dictionary_next,
+        godot_dictionary_hash,
+        godot_dictionary_keys,
+        godot_dictionary_values,
+        godot_dictionary_operator_equal,
+        godot_dictionary_duplicate,
+        godot_dictionary_to_json,
+    );
+}
+
+unsafe extern "C" fn godot_dictionary_new(dest: *mut sys::godot_dictionary) {
+    ptr::write(dest, wrap_arc(Arc::new(DictData::default())));
+}
+
+unsafe extern "C" fn godot_dictionary_new_copy(
+    dest: *mut sys::godot_dictionary,
+    src: *const sys::godot_dictionary,
+) {
+    ptr::write(dest, wrap_arc::<_, DictData>(super::get_arc(src)));
+}
+
+unsafe extern "C" fn godot_dictionary_destroy(dict: *mut sys::godot_dictionary) {
+    drop_arc::<_, DictData>(dict);
+}
+
+unsafe extern "C" fn godot_dictionary_size(dict: *const sys::godot_dictionary) -> godot_int {
+    get(dict).len() as godot_int
+}
+
+unsafe extern "C" fn godot_dictionary_empty(dict: *const sys::godot_dictionary) -> godot_bool {
+    get(dict).len() == 0
+}
+
+unsafe extern "C" fn godot_dictionary_clear(dict: *mut sys::godot_dictionary) {
+    let map = std::mem::take(get(dict).map_mut());
+    drop(map);
+}
+
+unsafe extern "C" fn godot_dictionary_has(
+    dict: *const sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> godot_bool {
+    get(dict).get(variant::get(key)).is_some()
+}
+
+unsafe extern "C" fn godot_dictionary_has_all(
+    dict: *const sys::godot_dictionary,
+    keys: *const sys::godot_array,
+) -> godot_bool {
+    let dict = get(dict);
+    array::get(keys)
+        .items()
+        .iter()
+        .all(|key| dict.get(key.get()).is_some())
+}
+
+unsafe extern "C" fn godot_dictionary_erase(
+    dict: *mut sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) {
+    godot_dictionary_erase_with_return(dict, key);
+}
+
+unsafe extern "C" fn godot_dictionary_erase_with_return(
+    dict: *mut sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> bool {
+    let removed = get(dict)
+        .map_mut()
+        .shift_remove_entry(&KeyRef(variant::get(key)));
+    removed.is_some()
+}
+
+unsafe extern "C" fn godot_dictionary_get(
+    dict: *const sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> sys::godot_variant {
+    variant::to_sys(get(dict).entry(variant::get(key)).get().clone())
+}
+
+unsafe extern "C" fn godot_dictionary_get_with_default(
+    dict: *const sys::godot_dictionary,
+    key: *const sys::godot_variant,
+    default: *const sys::godot_variant,
+) -> sys::godot_variant {
+    let value = match get(dict).get(variant::get(key)) {
+        Some(value) => value.get(),
+        None => variant::get(default),
+    };
+    variant::to_sys(value.clone())
+}
+
+unsafe extern "C" fn godot_dictionary_set(
+    dict: *mut sys::godot_dictionary,
+    key: *const sys::godot_variant,
+    value: *const sys::godot_variant,
+) {
+    let value = Slot::copy_from(value);
+    *get(dict).entry(variant::get(key)) = value;
+}
+
+unsafe extern "C" fn godot_dictionary_operator_index(
+    dict: *mut sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> *mut sys::godot_variant {
+    get(dict).entry(variant::get(key)).as_mut_ptr()
+}
+
+unsafe extern "C" fn godot_dictionary_operator_index_const(
+    dict: *const sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> *const sys::godot_variant {
+    get(dict).entry(variant::get(key)).as_ptr()
+}
+
+unsafe extern "C" fn godot_dictionary_next(
+    dict: *const sys::godot_dictionary,
+    key: *const sys::godot_variant,
+) -> *mut sys::godot_variant {
+    let map = get(dict).map();
+    let next = if key.is_null() {
+        Some(0)
+    } else {
+        map.get_index_of(&KeyRef(variant::get(key)))
+            .map(|index| index + 1)
+    };
+
+    match next.and_then(|index| map.get_index(index)) {
+        Some((key, _)) => key.0.as_ptr() as *mut _,
+        None => ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn godot_dictionary_hash(dict: *const sys::godot_dictionary) -> godot_int {
+    get(dict).hash() as godot_int
+}
+
+unsafe extern "C" fn godot_dictionary_keys(dict: *const sys::godot_dictionary) -> sys::godot_array {
+    let keys = get(dict).entries().map(|(key, _)| key.clone()).collect();
+    array::to_sys(ArrayData::from_items(keys))
+}
+
+unsafe extern "C" fn godot_dictionary_values(
+    dict: *const sys::godot_dictionary,
+) -> sys::godot_array {
+    let values = get(dict)
+        .entries()
+        .map(|(_, value)| value.clone())
+        .collect();
+    array::to_sys(ArrayData::from_items(values))
+}
+
+unsafe extern "C" fn godot_dictionary_operator_equal(
+    dict: *const sys::godot_dictionary,
+    b: *const sys::godot_dictionary,
+) -> godot_bool {
+    ptr::eq(get(dict), get(b))
+}
+
+unsafe extern "C" fn godot_dictionary_duplicate(
+    dict: *const sys::godot_dictionary,
+    deep: godot_bool,
+) -> sys::godot_dictionary {
+    wrap_arc(Arc::new(get(dict).duplicate(deep)))
+}
+
+unsafe extern "C" fn godot_dictionary_to_json(
+    dict: *const sys::godot_dictionary,
+) -> sys::godot_string {
+    let dict = Value::Dictionary(super::get_arc(dict));
+    string::from_str(&to_json(&dict))
+}
+
+/// Formats a value like `JSON::print`, without indentation and with sorted keys.
+fn to_json(value: &Value) -> String {
+    match value {
+        Value::Nil => "null".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Real(r) => variant::real(*r),
+        Value::Array(arr) => {
+            let items: Vec<String> = unsafe { arr.items() }
+                .iter()
+                .map(|item| to_json(item.get()))
+                .collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Dictionary(dict) => {
+            let entries: Vec<(&Slot, &Slot)> = unsafe { dict.entries() }.collect();
+            let entries = sort_by_less(entries, &|a: &(&Slot, &Slot), b: &(&Slot, &Slot)| {
+                variant::strict_less(a.0.get(), b.0.get())
+            });
+            let entries: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = string::json_escape(&variant::stringify(key.get()));
+                    format!("\"{key}\":{}", to_json(value.get()))
+                })
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        value => format!("\"{}\"", string::json_escape(&variant::stringify(value))),
+    }
+}
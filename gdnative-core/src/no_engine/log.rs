@@ -0,0 +1,62 @@
+//! Fallbacks for the logging functions, which write to the standard streams.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
+use crate::sys;
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_print,
+        godot_print_warning,
+        godot_print_error,
+    );
+}
+
+/// Prints an error reported by one of the fallbacks, where the engine would use `ERR_PRINT`.
+#[cold]
+pub(super) fn error(function: &str, description: &str) {
+    eprintln!("ERROR: {description}\n   at: {function}");
+}
+
+unsafe fn str_or_empty(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+unsafe extern "C" fn godot_print(message: *const sys::godot_string) {
+    println!("{}", super::string::to_rust(message));
+}
+
+unsafe extern "C" fn godot_print_warning(
+    description: *const c_char,
+    function: *const c_char,
+    file: *const c_char,
+    line: c_int,
+) {
+    eprintln!(
+        "WARNING: {}\n   at: {} ({}:{})",
+        str_or_empty(description),
+        str_or_empty(function),
+        str_or_empty(file),
+        line,
+    );
+}
+
+unsafe extern "C" fn godot_print_error(
+    description: *const c_char,
+    function: *const c_char,
+    file: *const c_char,
+    line: c_int,
+) {
+    eprintln!(
+        "ERROR: {}\n   at: {} ({}:{})",
+        str_or_empty(description),
+        str_or_empty(function),
+        str_or_empty(file),
+        line,
+    );
+}
@@ -0,0 +1,179 @@
+//! Pure Rust fallbacks for the core type API, used when the engine is not running.
+//!
+//! With the `no-engine` feature, `get_api` returns the API struct built by [`api`] as long as the
+//! library has not been loaded by the engine. It implements the functions behind `GodotString`,
+//! `StringName`, `NodePath`, `Rid`, `Variant`, `VariantArray`, `Dictionary` and `Color` in Rust,
+//! following the behavior of Godot 3.5. Everything else, e.g. pool arrays, objects, or the few
+//! string functions that need the engine's hashing or networking code, prints the name of the
+//! function and aborts the process.
+//!
+//! The opaque sys types store a pointer to Rust data in their first bytes. For strings, node
+//! paths and variants, the null pointer stands for the empty value, so zero-initialized values
+//! of these types are valid. Arrays and dictionaries are always allocated by their constructors.
+
+use std::ptr;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+
+use crate::sys;
+
+/// Replaces API functions with the functions of the same name in the current module.
+macro_rules! bind {
+    ($api:ident: $($name:ident),* $(,)?) => {
+        $($api.$name = $name;)*
+    };
+}
+
+mod array;
+mod color;
+mod dictionary;
+mod log;
+mod node_path;
+mod rid;
+mod string;
+mod variant;
+
+#[cfg(test)]
+mod tests;
+
+/// Returns the API struct with the fallback implementations.
+pub(crate) fn api() -> &'static sys::GodotApi {
+    static API: Lazy<sys::GodotApi> = Lazy::new(|| {
+        let mut api = sys::GodotApi::no_engine();
+        array::bind(&mut api);
+        color::bind(&mut api);
+        dictionary::bind(&mut api);
+        log::bind(&mut api);
+        node_path::bind(&mut api);
+        rid::bind(&mut api);
+        string::bind(&mut api);
+        variant::bind(&mut api);
+        api
+    });
+
+    &API
+}
+
+/// Reads the data pointer stored in an opaque sys value.
+unsafe fn load<S, T>(sys: *const S) -> *mut T {
+    debug_assert!(std::mem::size_of::<S>() >= std::mem::size_of::<*mut T>());
+    ptr::read_unaligned(sys as *const *mut T)
+}
+
+/// Stores a data pointer in an opaque sys value, without releasing the previous one.
+unsafe fn store<S, T>(sys: *mut S, data: *mut T) {
+    debug_assert!(std::mem::size_of::<S>() >= std::mem::size_of::<*mut T>());
+    ptr::write_unaligned(sys as *mut *mut T, data)
+}
+
+/// Creates an opaque sys value holding a data pointer.
+fn wrap<S: Default, T>(data: *mut T) -> S {
+    let mut sys = S::default();
+    unsafe { store(&mut sys, data) };
+    sys
+}
+
+/// Creates an opaque sys value owning `data`.
+fn wrap_box<S: Default, T>(data: T) -> S {
+    wrap(Box::into_raw(Box::new(data)))
+}
+
+/// Returns a reference to the boxed data stored in `sys`, or `None` for the null pointer.
+unsafe fn get_box<'a, S, T>(sys: *const S) -> Option<&'a T> {
+    load::<S, T>(sys).as_ref()
+}
+
+/// Releases the boxed data stored in `sys`, and resets it to the null pointer.
+unsafe fn drop_box<S, T>(sys: *mut S) {
+    let data = load::<S, T>(sys);
+    if !data.is_null() {
+        drop(Box::from_raw(data));
+    }
+    store(sys, ptr::null_mut::<T>());
+}
+
+/// Creates an opaque sys value holding a reference to `data`.
+fn wrap_arc<S: Default, T>(data: Arc<T>) -> S {
+    wrap(Arc::into_raw(data) as *mut T)
+}
+
+/// Returns a new reference to the shared data stored in `sys`, which must not be null.
+unsafe fn get_arc<S, T>(sys: *const S) -> Arc<T> {
+    let data = load::<S, T>(sys);
+    debug_assert!(!data.is_null(), "value used before construction");
+    Arc::increment_strong_count(data);
+    Arc::from_raw(data)
+}
+
+/// Releases the reference stored in `sys`, and resets it to the null pointer.
+unsafe fn drop_arc<S, T>(sys: *mut S) {
+    let data = load::<S, T>(sys);
+    if !data.is_null() {
+        drop(Arc::from_raw(data));
+    }
+    store(sys, ptr::null_mut::<T>());
+}
+
+/// Aborts on an out-of-bounds access, like the engine.
+#[cold]
+fn index_out_of_bounds(function: &str, index: sys::godot_int, size: usize) -> ! {
+    eprintln!("FATAL: {function}: Index {index} is out of bounds (size = {size}).");
+    std::process::abort()
+}
+
+/// Hash combination function used by the engine.
+fn hash_djb2_one_32(value: u32, prev: u32) -> u32 {
+    (prev << 5).wrapping_add(prev).wrapping_add(value)
+}
+
+/// Integer hash function used by the engine.
+fn hash_one_u64(value: u64) -> u32 {
+    let mut v = value;
+    v = (!v).wrapping_add(v << 18);
+    v ^= v >> 31;
+    v = v.wrapping_mul(21);
+    v ^= v >> 11;
+    v = v.wrapping_add(v << 6);
+    v ^= v >> 22;
+    v as u32
+}
+
+/// Floating point hash function used by the engine. Signed zeroes and NaNs hash the same.
+fn hash_djb2_one_float(value: f64, prev: u32) -> u32 {
+    let value = if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        f64::NAN
+    } else {
+        value
+    };
+
+    hash_djb2_one_32(hash_one_u64(value.to_bits()), prev)
+}
+
+/// Initial value of djb2 hashes.
+const HASH_SEED: u32 = 5381;
+
+/// Initial value of the engine's hashes for math types.
+const MATH_HASH_SEED: u32 = 5831;
+
+/// Stable merge sort using a "less than" predicate, which doesn't need to be a total order.
+fn sort_by_less<T>(mut items: Vec<T>, less: &impl Fn(&T, &T) -> bool) -> Vec<T> {
+    if items.len() <= 1 {
+        return items;
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let mut left = sort_by_less(items, less).into_iter().peekable();
+    let mut right = sort_by_less(right, less).into_iter().peekable();
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let next = if less(r, l) { &mut right } else { &mut left };
+        merged.extend(next.next());
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
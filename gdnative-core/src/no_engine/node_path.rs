@@ -0,0 +1,223 @@
+//! Fallbacks for `NodePath`. Paths are parsed once and shared between copies.
+
+use std::ptr;
+use std::sync::Arc;
+
+use crate::sys::{self, godot_bool, godot_int, wchar_t};
+
+use super::string::{self, Text};
+use super::{drop_arc, load, wrap_arc};
+
+/// Contents of a non-empty node path.
+#[derive(PartialEq)]
+pub(super) struct Path {
+    absolute: bool,
+    names: Vec<Text>,
+    subnames: Vec<Text>,
+}
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_node_path_new,
+        godot_node_path_new_copy,
+        godot_node_path_destroy,
+        godot_node_path_as_string,
+        godot_node_path_is_absolute,
+        godot_node_path_is_empty,
+        godot_node_path_get_name_count,
+        godot_node_path_get_name,
+        godot_node_path_get_subname_count,
+        godot_node_path_get_subname,
+        godot_node_path_get_concatenated_subnames,
+        godot_node_path_get_as_property_path,
+        godot_node_path_operator_equal,
+    );
+}
+
+impl Path {
+    /// Parses a node path like the engine. Returns `None` for empty or invalid paths.
+    pub(super) fn parse(text: &[wchar_t]) -> Option<Arc<Path>> {
+        let path = string::decode(text);
+        let absolute = path.starts_with('/');
+
+        let (path, subnames) = match path.split_once(':') {
+            Some((path, subpath)) => {
+                let mut subnames: Vec<&str> = subpath.split(':').collect();
+                // A trailing colon is allowed.
+                if subnames.last() == Some(&"") {
+                    subnames.pop();
+                }
+                if subnames.iter().any(|subname| subname.is_empty()) {
+                    super::log::error("NodePath", &format!("Invalid NodePath '{path}:{subpath}'."));
+                    return None;
+                }
+                (path, subnames)
+            }
+            None => (path.as_str(), Vec::new()),
+        };
+
+        let names: Vec<Text> = path
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .map(string::encode)
+            .collect();
+
+        if names.is_empty() && subnames.is_empty() && !absolute {
+            return None;
+        }
+
+        Some(Arc::new(Path {
+            absolute,
+            names,
+            subnames: subnames.into_iter().map(string::encode).collect(),
+        }))
+    }
+
+    /// Formats the path like the engine.
+    pub(super) fn to_text(&self) -> Text {
+        let mut text = Text::new();
+        if self.absolute {
+            text.extend(string::encode("/"));
+        }
+        for (i, name) in self.names.iter().enumerate() {
+            if i > 0 {
+                text.extend(string::encode("/"));
+            }
+            text.extend_from_slice(name);
+        }
+        for subname in &self.subnames {
+            text.extend(string::encode(":"));
+            text.extend_from_slice(subname);
+        }
+        text
+    }
+
+    /// The engine's node path hash function.
+    pub(super) fn hash(&self) -> u32 {
+        self.names
+            .iter()
+            .chain(&self.subnames)
+            .fold(u32::from(self.absolute), |h, name| h ^ string::hash(name))
+    }
+}
+
+/// Returns a new reference to the path stored in `np`, or `None` for the empty path.
+pub(super) unsafe fn get(np: *const sys::godot_node_path) -> Option<Arc<Path>> {
+    path(np).map(|_| super::get_arc(np))
+}
+
+/// Creates a node path holding a reference to `path`.
+pub(super) fn to_sys(path: Option<Arc<Path>>) -> sys::godot_node_path {
+    path.map_or_else(sys::godot_node_path::default, wrap_arc)
+}
+
+unsafe fn path<'a>(np: *const sys::godot_node_path) -> Option<&'a Path> {
+    load::<_, Path>(np).as_ref()
+}
+
+unsafe fn names<'a>(np: *const sys::godot_node_path) -> &'a [Text] {
+    path(np).map_or(&[], |path| &path.names)
+}
+
+unsafe fn subnames<'a>(np: *const sys::godot_node_path) -> &'a [Text] {
+    path(np).map_or(&[], |path| &path.subnames)
+}
+
+unsafe fn name_at(function: &str, names: &[Text], idx: godot_int) -> sys::godot_string {
+    match usize::try_from(idx).ok().and_then(|i| names.get(i)) {
+        Some(name) => string::to_sys(name.clone()),
+        None => {
+            super::log::error(
+                function,
+                &format!("Index {idx} is out of bounds (size = {}).", names.len()),
+            );
+            sys::godot_string::default()
+        }
+    }
+}
+
+unsafe extern "C" fn godot_node_path_new(
+    dest: *mut sys::godot_node_path,
+    from: *const sys::godot_string,
+) {
+    ptr::write(dest, to_sys(Path::parse(string::text(from))));
+}
+
+unsafe extern "C" fn godot_node_path_new_copy(
+    dest: *mut sys::godot_node_path,
+    src: *const sys::godot_node_path,
+) {
+    ptr::write(dest, to_sys(get(src)));
+}
+
+unsafe extern "C" fn godot_node_path_destroy(np: *mut sys::godot_node_path) {
+    drop_arc::<_, Path>(np);
+}
+
+unsafe extern "C" fn godot_node_path_as_string(
+    np: *const sys::godot_node_path,
+) -> sys::godot_string {
+    string::to_sys(path(np).map(Path::to_text).unwrap_or_default())
+}
+
+unsafe extern "C" fn godot_node_path_is_absolute(np: *const sys::godot_node_path) -> godot_bool {
+    path(np).is_some_and(|path| path.absolute)
+}
+
+unsafe extern "C" fn godot_node_path_is_empty(np: *const sys::godot_node_path) -> godot_bool {
+    path(np).is_none()
+}
+
+unsafe extern "C" fn godot_node_path_get_name_count(np: *const sys::godot_node_path) -> godot_int {
+    names(np).len() as godot_int
+}
+
+unsafe extern "C" fn godot_node_path_get_name(
+    np: *const sys::godot_node_path,
+    idx: godot_int,
+) -> sys::godot_string {
+    name_at("NodePath::get_name", names(np), idx)
+}
+
+unsafe extern "C" fn godot_node_path_get_subname_count(
+    np: *const sys::godot_node_path,
+) -> godot_int {
+    subnames(np).len() as godot_int
+}
+
+unsafe extern "C" fn godot_node_path_get_subname(
+    np: *const sys::godot_node_path,
+    idx: godot_int,
+) -> sys::godot_string {
+    name_at("NodePath::get_subname", subnames(np), idx)
+}
+
+unsafe extern "C" fn godot_node_path_get_concatenated_subnames(
+    np: *const sys::godot_node_path,
+) -> sys::godot_string {
+    string::to_sys(subnames(np).join(&string::encode(":")[..]))
+}
+
+unsafe extern "C" fn godot_node_path_get_as_property_path(
+    np: *const sys::godot_node_path,
+) -> sys::godot_node_path {
+    match path(np) {
+        Some(path) if !path.names.is_empty() => {
+            let mut subnames = vec![path.names.join(&string::encode("/")[..])];
+            subnames.extend(path.subnames.iter().cloned());
+            to_sys(Some(Arc::new(Path {
+                absolute: false,
+                names: Vec::new(),
+                subnames,
+            })))
+        }
+        _ => to_sys(get(np)),
+    }
+}
+
+unsafe extern "C" fn godot_node_path_operator_equal(
+    np: *const sys::godot_node_path,
+    b: *const sys::godot_node_path,
+) -> godot_bool {
+    path(np) == path(b)
+}
@@ -0,0 +1,38 @@
+//! Fallbacks for `Rid`. Without the engine, there are no resources, so only null RIDs exist.
+
+use std::ptr;
+
+use crate::sys::{self, godot_bool, godot_int};
+
+use super::load;
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_rid_new,
+        godot_rid_get_id,
+        godot_rid_operator_equal,
+        godot_rid_operator_less,
+    );
+}
+
+unsafe extern "C" fn godot_rid_new(dest: *mut sys::godot_rid) {
+    ptr::write(dest, sys::godot_rid::default());
+}
+
+unsafe extern "C" fn godot_rid_get_id(_rid: *const sys::godot_rid) -> godot_int {
+    0
+}
+
+unsafe extern "C" fn godot_rid_operator_equal(
+    rid: *const sys::godot_rid,
+    b: *const sys::godot_rid,
+) -> godot_bool {
+    load::<_, u8>(rid) == load::<_, u8>(b)
+}
+
+unsafe extern "C" fn godot_rid_operator_less(
+    rid: *const sys::godot_rid,
+    b: *const sys::godot_rid,
+) -> godot_bool {
+    load::<_, u8>(rid) < load::<_, u8>(b)
+}
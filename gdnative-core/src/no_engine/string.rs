@@ -0,0 +1,1184 @@
+//! Fallbacks for `GodotString`, `StringName` and the UTF-8 strings used for conversion.
+//!
+//! Strings are stored as vectors of `wchar_t`, like in the engine. Indices and lengths refer to
+//! these code units. The remaining functions work on decoded Rust strings, and are ports of the
+//! engine's implementations.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_double};
+use std::{ptr, slice};
+
+use crate::sys::{self, godot_bool, godot_int, godot_real, wchar_t};
+
+use super::variant::{self, Value};
+use super::{drop_box, get_box, hash_djb2_one_32, wrap_box, HASH_SEED};
+
+/// Contents of a string.
+pub(super) type Text = Vec<wchar_t>;
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_string_new,
+        godot_string_new_copy,
+        godot_string_destroy,
+        godot_string_chars_to_utf8_with_len,
+        godot_string_length,
+        godot_string_empty,
+        godot_string_operator_index,
+        godot_string_operator_index_const,
+        godot_string_operator_equal,
+        godot_string_operator_less,
+        godot_string_operator_plus,
+        godot_string_utf8,
+        godot_char_string_length,
+        godot_char_string_get_data,
+        godot_char_string_destroy,
+        godot_string_begins_with,
+        godot_string_begins_with_char_array,
+        godot_string_ends_with,
+        godot_string_find,
+        godot_string_find_from,
+        godot_string_find_last,
+        godot_string_substr,
+        godot_string_format,
+        godot_string_format_with_custom_placeholder,
+        godot_string_to_int,
+        godot_string_to_int64,
+        godot_string_to_float,
+        godot_string_to_double,
+        godot_string_hash,
+        godot_string_hash64,
+        godot_string_hex_to_int,
+        godot_string_hex_to_int_without_prefix,
+        godot_string_is_valid_hex_number,
+        godot_string_is_numeric,
+        godot_string_is_valid_float,
+        godot_string_is_valid_integer,
+        godot_string_is_valid_identifier,
+        godot_string_is_valid_html_color,
+        godot_string_is_abs_path,
+        godot_string_is_rel_path,
+        godot_string_is_resource_file,
+        godot_string_to_lower,
+        godot_string_to_upper,
+        godot_string_camelcase_to_underscore,
+        godot_string_camelcase_to_underscore_lowercased,
+        godot_string_capitalize,
+        godot_string_get_extension,
+        godot_string_get_basename,
+        godot_string_get_file,
+        godot_string_get_base_dir,
+        godot_string_simplify_path,
+        godot_string_c_escape,
+        godot_string_c_escape_multiline,
+        godot_string_c_unescape,
+        godot_string_json_escape,
+        godot_string_xml_escape,
+        godot_string_xml_escape_with_quotes,
+        godot_string_xml_unescape,
+        godot_string_http_escape,
+        godot_string_http_unescape,
+        godot_string_percent_encode,
+        godot_string_percent_decode,
+        godot_string_name_new,
+        godot_string_name_new_data,
+        godot_string_name_destroy,
+        godot_string_name_get_name,
+        godot_string_name_get_hash,
+        godot_string_name_operator_equal,
+        godot_string_name_operator_less,
+    );
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Conversions
+
+/// Encodes a Rust string in the platform's `wchar_t` encoding.
+#[allow(clippy::unnecessary_cast)] // wchar_t is platform-dependent
+pub(super) fn encode(s: &str) -> Text {
+    if std::mem::size_of::<wchar_t>() == 2 {
+        s.encode_utf16().map(|c| c as wchar_t).collect()
+    } else {
+        s.chars().map(|c| c as u32 as wchar_t).collect()
+    }
+}
+
+/// Decodes text in the platform's `wchar_t` encoding, replacing invalid code points.
+#[allow(clippy::unnecessary_cast)] // wchar_t is platform-dependent
+pub(super) fn decode(text: &[wchar_t]) -> String {
+    if std::mem::size_of::<wchar_t>() == 2 {
+        char::decode_utf16(text.iter().map(|&c| c as u16))
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    } else {
+        text.iter()
+            .map(|&c| char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+}
+
+/// Creates a string owning `text`.
+pub(super) fn to_sys(text: Text) -> sys::godot_string {
+    if text.is_empty() {
+        sys::godot_string::default()
+    } else {
+        wrap_box(text)
+    }
+}
+
+/// Creates a string from a Rust string.
+pub(super) fn from_str(s: &str) -> sys::godot_string {
+    to_sys(encode(s))
+}
+
+/// Returns the contents of a string.
+pub(super) unsafe fn text<'a>(s: *const sys::godot_string) -> &'a [wchar_t] {
+    get_box::<_, Text>(s).map_or(&[], Vec::as_slice)
+}
+
+/// Returns the contents of a string as a Rust string.
+pub(super) unsafe fn to_rust(s: *const sys::godot_string) -> String {
+    decode(text(s))
+}
+
+/// Returns `true` if `c` is the ASCII character `ascii`.
+#[allow(clippy::unnecessary_cast)] // wchar_t is platform-dependent
+fn is(c: wchar_t, ascii: u8) -> bool {
+    c as u32 == ascii as u32
+}
+
+/// Finds the first occurrence of `what` in `text`, starting at `from`. Empty patterns are never
+/// found.
+pub(super) fn find(text: &[wchar_t], what: &[wchar_t], from: usize) -> Option<usize> {
+    if what.is_empty() || text.len() < what.len() {
+        return None;
+    }
+
+    (from..=text.len() - what.len()).find(|&i| text[i..].starts_with(what))
+}
+
+/// Finds the last occurrence of `what` in `text`. Empty patterns are never found.
+fn rfind(text: &[wchar_t], what: &[wchar_t]) -> Option<usize> {
+    if what.is_empty() || text.len() < what.len() {
+        return None;
+    }
+
+    (0..=text.len() - what.len())
+        .rev()
+        .find(|&i| text[i..].starts_with(what))
+}
+
+fn index_or_minus_one(index: Option<usize>) -> godot_int {
+    index.map_or(-1, |i| i as godot_int)
+}
+
+/// Maps a string to a new string through a Rust function.
+unsafe fn map(s: *const sys::godot_string, f: impl FnOnce(&str) -> String) -> sys::godot_string {
+    from_str(&f(&to_rust(s)))
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Construction and basic operations
+
+unsafe extern "C" fn godot_string_new(dest: *mut sys::godot_string) {
+    ptr::write(dest, sys::godot_string::default());
+}
+
+unsafe extern "C" fn godot_string_new_copy(
+    dest: *mut sys::godot_string,
+    src: *const sys::godot_string,
+) {
+    ptr::write(dest, to_sys(text(src).to_vec()));
+}
+
+unsafe extern "C" fn godot_string_destroy(s: *mut sys::godot_string) {
+    drop_box::<_, Text>(s);
+}
+
+unsafe extern "C" fn godot_string_chars_to_utf8_with_len(
+    utf8: *const c_char,
+    len: godot_int,
+) -> sys::godot_string {
+    if utf8.is_null() || len <= 0 {
+        return sys::godot_string::default();
+    }
+
+    let bytes = slice::from_raw_parts(utf8 as *const u8, len as usize);
+    from_str(&String::from_utf8_lossy(bytes))
+}
+
+unsafe extern "C" fn godot_string_length(s: *const sys::godot_string) -> godot_int {
+    text(s).len() as godot_int
+}
+
+unsafe extern "C" fn godot_string_empty(s: *const sys::godot_string) -> godot_bool {
+    text(s).is_empty()
+}
+
+unsafe extern "C" fn godot_string_operator_index(
+    s: *mut sys::godot_string,
+    idx: godot_int,
+) -> *const wchar_t {
+    let text = text(s);
+    match usize::try_from(idx).ok().and_then(|i| text.get(i)) {
+        Some(c) => c,
+        None => super::index_out_of_bounds("String::operator[]", idx, text.len()),
+    }
+}
+
+unsafe extern "C" fn godot_string_operator_index_const(
+    s: *const sys::godot_string,
+    idx: godot_int,
+) -> wchar_t {
+    let text = text(s);
+    usize::try_from(idx)
+        .ok()
+        .and_then(|i| text.get(i))
+        .copied()
+        .unwrap_or_default()
+}
+
+unsafe extern "C" fn godot_string_operator_equal(
+    s: *const sys::godot_string,
+    b: *const sys::godot_string,
+) -> godot_bool {
+    text(s) == text(b)
+}
+
+unsafe extern "C" fn godot_string_operator_less(
+    s: *const sys::godot_string,
+    b: *const sys::godot_string,
+) -> godot_bool {
+    text(s) < text(b)
+}
+
+unsafe extern "C" fn godot_string_operator_plus(
+    s: *const sys::godot_string,
+    b: *const sys::godot_string,
+) -> sys::godot_string {
+    to_sys([text(s), text(b)].concat())
+}
+
+unsafe extern "C" fn godot_string_utf8(s: *const sys::godot_string) -> sys::godot_char_string {
+    let mut bytes = to_rust(s).into_bytes();
+    bytes.push(0);
+    wrap_box(bytes)
+}
+
+unsafe extern "C" fn godot_char_string_length(cs: *const sys::godot_char_string) -> godot_int {
+    get_box::<_, Vec<u8>>(cs).map_or(0, |bytes| bytes.len() as godot_int - 1)
+}
+
+unsafe extern "C" fn godot_char_string_get_data(
+    cs: *const sys::godot_char_string,
+) -> *const c_char {
+    get_box::<_, Vec<u8>>(cs).map_or(b"\0".as_ptr(), |bytes| bytes.as_ptr()) as *const c_char
+}
+
+unsafe extern "C" fn godot_char_string_destroy(cs: *mut sys::godot_char_string) {
+    drop_box::<_, Vec<u8>>(cs);
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Searching and slicing
+
+unsafe extern "C" fn godot_string_begins_with(
+    s: *const sys::godot_string,
+    string: *const sys::godot_string,
+) -> godot_bool {
+    text(s).starts_with(text(string))
+}
+
+unsafe extern "C" fn godot_string_begins_with_char_array(
+    s: *const sys::godot_string,
+    char_array: *const c_char,
+) -> godot_bool {
+    let text = text(s);
+    if text.is_empty() || char_array.is_null() {
+        return false;
+    }
+
+    let prefix = CStr::from_ptr(char_array).to_bytes();
+    prefix.len() <= text.len() && prefix.iter().zip(text).all(|(&b, &c)| is(c, b))
+}
+
+unsafe extern "C" fn godot_string_ends_with(
+    s: *const sys::godot_string,
+    string: *const sys::godot_string,
+) -> godot_bool {
+    let suffix = text(string);
+    !suffix.is_empty() && text(s).ends_with(suffix)
+}
+
+unsafe extern "C" fn godot_string_find(
+    s: *const sys::godot_string,
+    what: sys::godot_string,
+) -> godot_int {
+    index_or_minus_one(find(text(s), text(&what), 0))
+}
+
+unsafe extern "C" fn godot_string_find_from(
+    s: *const sys::godot_string,
+    what: sys::godot_string,
+    from: godot_int,
+) -> godot_int {
+    match usize::try_from(from) {
+        Ok(from) => index_or_minus_one(find(text(s), text(&what), from)),
+        Err(_) => -1,
+    }
+}
+
+unsafe extern "C" fn godot_string_find_last(
+    s: *const sys::godot_string,
+    what: sys::godot_string,
+) -> godot_int {
+    index_or_minus_one(rfind(text(s), text(&what)))
+}
+
+unsafe extern "C" fn godot_string_substr(
+    s: *const sys::godot_string,
+    from: godot_int,
+    chars: godot_int,
+) -> sys::godot_string {
+    to_sys(substr(text(s), from, chars).to_vec())
+}
+
+fn substr(text: &[wchar_t], from: godot_int, chars: godot_int) -> &[wchar_t] {
+    let len = text.len() as i64;
+    let from = from as i64;
+    let chars = if chars == -1 {
+        len - from
+    } else {
+        chars as i64
+    };
+
+    if from < 0 || from >= len || chars <= 0 {
+        return &[];
+    }
+
+    &text[from as usize..(from + chars).min(len) as usize]
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Formatting
+
+unsafe extern "C" fn godot_string_format(
+    s: *const sys::godot_string,
+    values: *const sys::godot_variant,
+) -> sys::godot_string {
+    from_str(&format(&to_rust(s), variant::get(values), "{_}"))
+}
+
+unsafe extern "C" fn godot_string_format_with_custom_placeholder(
+    s: *const sys::godot_string,
+    values: *const sys::godot_variant,
+    placeholder: *const c_char,
+) -> sys::godot_string {
+    let placeholder = CStr::from_ptr(placeholder).to_string_lossy();
+    from_str(&format(&to_rust(s), variant::get(values), &placeholder))
+}
+
+fn format(s: &str, values: &Value, placeholder: &str) -> String {
+    fn unquote(s: String) -> String {
+        if s.starts_with('"') && s.ends_with('"') {
+            s.get(1..s.len() - 1).unwrap_or_default().to_owned()
+        } else {
+            s
+        }
+    }
+
+    let mut formatted = s.to_owned();
+    match values {
+        Value::Array(values) => {
+            for (i, value) in unsafe { values.items() }.iter().enumerate() {
+                match value.get() {
+                    Value::Array(pair) => {
+                        let pair = unsafe { pair.items() };
+                        if let [key, value] = pair {
+                            let key = unquote(variant::stringify(key.get()));
+                            let value = unquote(variant::stringify(value.get()));
+                            formatted = formatted.replace(&placeholder.replace('_', &key), &value);
+                        } else {
+                            super::log::error("format", "STRING.format Inner Array size != 2");
+                        }
+                    }
+                    value => {
+                        let value = unquote(variant::stringify(value));
+                        if placeholder.contains('_') {
+                            let key = placeholder.replace('_', &i.to_string());
+                            formatted = formatted.replace(&key, &value);
+                        } else {
+                            formatted = formatted.replacen(placeholder, &value, 1);
+                        }
+                    }
+                }
+            }
+        }
+        Value::Dictionary(values) => {
+            for (key, value) in unsafe { values.entries() } {
+                let key = unquote(variant::stringify(key.get()));
+                let value = unquote(variant::stringify(value.get()));
+                formatted = formatted.replace(&placeholder.replace('_', &key), &value);
+            }
+        }
+        _ => super::log::error("format", "Invalid type: use Array or Dictionary."),
+    }
+    formatted
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Parsing
+
+unsafe extern "C" fn godot_string_to_int(s: *const sys::godot_string) -> godot_int {
+    to_int(&to_rust(s), i32::MIN.into(), i32::MAX.into()) as godot_int
+}
+
+unsafe extern "C" fn godot_string_to_int64(s: *const sys::godot_string) -> i64 {
+    to_int64(&to_rust(s))
+}
+
+unsafe extern "C" fn godot_string_to_float(s: *const sys::godot_string) -> godot_real {
+    to_double(&to_rust(s)) as godot_real
+}
+
+unsafe extern "C" fn godot_string_to_double(s: *const sys::godot_string) -> c_double {
+    to_double(&to_rust(s))
+}
+
+/// Parses the digits before the first `.`, ignoring other characters. A `-` before the first
+/// non-zero digit flips the sign. Values outside of the range are clamped.
+pub(super) fn to_int64(s: &str) -> i64 {
+    to_int(s, i64::MIN, i64::MAX)
+}
+
+fn to_int(s: &str, min: i64, max: i64) -> i64 {
+    let mut integer: i64 = 0;
+    let mut sign = 1;
+
+    for c in s.chars().take_while(|&c| c != '.') {
+        if let Some(digit) = c.to_digit(10) {
+            let digit = i64::from(digit);
+            let overflow = integer > max / 10
+                || (integer == max / 10
+                    && ((sign == 1 && digit > max % 10) || (sign == -1 && digit > -(min % 10))));
+            if overflow {
+                return if sign == 1 { max } else { min };
+            }
+            integer = integer.wrapping_mul(10).wrapping_add(digit);
+        } else if integer == 0 && c == '-' {
+            sign = -sign;
+        }
+    }
+
+    integer.wrapping_mul(sign)
+}
+
+/// Parses the longest prefix that is a valid decimal number, after leading whitespace. Returns
+/// `0.0` if there is none.
+pub(super) fn to_double(s: &str) -> f64 {
+    let s = s.trim_start();
+    let bytes = s.as_bytes();
+    let digits_from = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let int_end = digits_from(end);
+    let mut has_digits = int_end > end;
+    end = int_end;
+
+    if bytes.get(end) == Some(&b'.') {
+        let frac_end = digits_from(end + 1);
+        has_digits |= frac_end > end + 1;
+        end = frac_end;
+    }
+
+    if !has_digits {
+        return 0.0;
+    }
+
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let exp_from = end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exp_end = digits_from(exp_from);
+        if exp_end > exp_from {
+            end = exp_end;
+        }
+    }
+
+    s[..end].parse().unwrap_or(0.0)
+}
+
+unsafe extern "C" fn godot_string_hash(s: *const sys::godot_string) -> u32 {
+    hash(text(s))
+}
+
+unsafe extern "C" fn godot_string_hash64(s: *const sys::godot_string) -> u64 {
+    text(s)
+        .iter()
+        .take_while(|&&c| c != 0)
+        .fold(u64::from(HASH_SEED), |h, &c| {
+            (h << 5).wrapping_add(h).wrapping_add(c as u64)
+        })
+}
+
+/// The engine's string hash function.
+pub(super) fn hash(text: &[wchar_t]) -> u32 {
+    text.iter()
+        .take_while(|&&c| c != 0)
+        .fold(HASH_SEED, |h, &c| hash_djb2_one_32(c as u32, h))
+}
+
+unsafe extern "C" fn godot_string_hex_to_int(s: *const sys::godot_string) -> godot_int {
+    hex_to_int(&to_rust(s), true)
+}
+
+unsafe extern "C" fn godot_string_hex_to_int_without_prefix(
+    s: *const sys::godot_string,
+) -> godot_int {
+    hex_to_int(&to_rust(s), false)
+}
+
+fn hex_to_int(s: &str, with_prefix: bool) -> i32 {
+    if with_prefix && s.chars().count() < 3 {
+        return 0;
+    }
+
+    let (sign, mut s) = match s.strip_prefix('-') {
+        Some(s) => (-1, s),
+        None => (1, s),
+    };
+
+    if with_prefix {
+        match s.strip_prefix("0x") {
+            Some(digits) => s = digits,
+            None => return 0,
+        }
+    }
+
+    let mut hex: i32 = 0;
+    for c in s.chars() {
+        let Some(n) = c.to_digit(16) else {
+            return 0;
+        };
+        if hex > i32::MAX / 16 {
+            return if sign == 1 { i32::MAX } else { i32::MIN };
+        }
+        hex = hex * 16 + n as i32;
+    }
+
+    hex * sign
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Validation
+
+/// Returns the index after an optional leading sign, if the string has more than one character.
+fn skip_sign(chars: &[char]) -> usize {
+    usize::from(chars.len() != 1 && matches!(chars[0], '+' | '-'))
+}
+
+unsafe extern "C" fn godot_string_is_valid_hex_number(
+    s: *const sys::godot_string,
+    with_prefix: godot_bool,
+) -> godot_bool {
+    let chars: Vec<char> = to_rust(s).chars().collect();
+    if chars.is_empty() {
+        return false;
+    }
+
+    let mut from = skip_sign(&chars);
+    if with_prefix {
+        if chars.len() < 3 || chars.get(from) != Some(&'0') || chars.get(from + 1) != Some(&'x') {
+            return false;
+        }
+        from += 2;
+    }
+
+    chars[from.min(chars.len())..]
+        .iter()
+        .all(char::is_ascii_hexdigit)
+}
+
+unsafe extern "C" fn godot_string_is_numeric(s: *const sys::godot_string) -> godot_bool {
+    let s = to_rust(s);
+    if s.is_empty() {
+        return false;
+    }
+
+    // Like in the engine, numbers with a decimal point are rejected.
+    let digits = s.strip_prefix('-').unwrap_or(&s);
+    digits.chars().all(|c| c.is_ascii_digit())
+}
+
+unsafe extern "C" fn godot_string_is_valid_float(s: *const sys::godot_string) -> godot_bool {
+    let s = to_rust(s);
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(&s);
+
+    let mut exponent_found = false;
+    let mut period_found = false;
+    let mut sign_found = false;
+    let mut exponent_values_found = false;
+    let mut numbers_found = false;
+
+    for c in digits.chars() {
+        if c.is_ascii_digit() {
+            if exponent_found {
+                exponent_values_found = true;
+            } else {
+                numbers_found = true;
+            }
+        } else if numbers_found && !exponent_found && c == 'e' {
+            exponent_found = true;
+        } else if !period_found && !exponent_found && c == '.' {
+            period_found = true;
+        } else if matches!(c, '+' | '-') && exponent_found && !exponent_values_found && !sign_found
+        {
+            sign_found = true;
+        } else {
+            return false;
+        }
+    }
+
+    numbers_found
+}
+
+unsafe extern "C" fn godot_string_is_valid_integer(s: *const sys::godot_string) -> godot_bool {
+    let chars: Vec<char> = to_rust(s).chars().collect();
+    !chars.is_empty() && chars[skip_sign(&chars)..].iter().all(char::is_ascii_digit)
+}
+
+unsafe extern "C" fn godot_string_is_valid_identifier(s: *const sys::godot_string) -> godot_bool {
+    let s = to_rust(s);
+    match s.chars().next() {
+        Some(first) => {
+            !first.is_ascii_digit() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn godot_string_is_valid_html_color(s: *const sys::godot_string) -> godot_bool {
+    let s = to_rust(s);
+    let hex = s.strip_prefix('#').unwrap_or(&s);
+    matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+unsafe extern "C" fn godot_string_is_abs_path(s: *const sys::godot_string) -> godot_bool {
+    is_abs_path(&to_rust(s))
+}
+
+unsafe extern "C" fn godot_string_is_rel_path(s: *const sys::godot_string) -> godot_bool {
+    !is_abs_path(&to_rust(s))
+}
+
+fn is_abs_path(s: &str) -> bool {
+    s.starts_with(['/', '\\']) || s.contains(":/") || s.contains(":\\")
+}
+
+unsafe extern "C" fn godot_string_is_resource_file(s: *const sys::godot_string) -> godot_bool {
+    let s = to_rust(s);
+    s.starts_with("res://") && !s.contains("::")
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Case conversion
+
+unsafe extern "C" fn godot_string_to_lower(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| s.chars().map(to_lower).collect())
+}
+
+unsafe extern "C" fn godot_string_to_upper(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| s.chars().map(to_upper).collect())
+}
+
+/// Converts a character to lowercase, if it maps to a single character.
+fn to_lower(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// Converts a character to uppercase, if it maps to a single character.
+fn to_upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+unsafe extern "C" fn godot_string_camelcase_to_underscore(
+    s: *const sys::godot_string,
+) -> sys::godot_string {
+    map(s, |s| camelcase_to_underscore(s, false))
+}
+
+unsafe extern "C" fn godot_string_camelcase_to_underscore_lowercased(
+    s: *const sys::godot_string,
+) -> sys::godot_string {
+    map(s, |s| camelcase_to_underscore(s, true))
+}
+
+fn camelcase_to_underscore(s: &str, lowercase: bool) -> String {
+    // Trailing NUL like in the engine, which is accessed by the lookahead.
+    let chars: Vec<char> = s.chars().chain(Some('\0')).collect();
+    let size = chars.len();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+
+    let mut new_string = String::new();
+    let mut start_index = 0;
+
+    for i in 1..size {
+        let is_upper = at(i).is_ascii_uppercase();
+        let is_number = at(i).is_ascii_digit();
+        let was_precedent_upper = at(i - 1).is_ascii_uppercase();
+        let was_precedent_number = at(i - 1).is_ascii_digit();
+        let are_next_2_lower =
+            i + 2 < size && at(i + 1).is_ascii_lowercase() && at(i + 2).is_ascii_lowercase();
+        let is_next_lower = i + 1 < size && at(i + 1).is_ascii_lowercase();
+        let is_next_number = i + 1 < size && at(i + 1).is_ascii_digit();
+
+        let cond_a = is_upper && !was_precedent_upper && !was_precedent_number;
+        let cond_b = was_precedent_upper && is_upper && are_next_2_lower;
+        let can_break_number_letter = is_number && !was_precedent_number && is_next_lower;
+        let can_break_letter_number =
+            !is_number && was_precedent_number && (is_next_lower || is_next_number);
+
+        if cond_a || cond_b || can_break_number_letter || can_break_letter_number {
+            new_string.extend(&chars[start_index..i]);
+            new_string.push('_');
+            start_index = i;
+        }
+    }
+
+    new_string.extend(chars[start_index..size - 1].iter());
+    if lowercase {
+        new_string.chars().map(to_lower).collect()
+    } else {
+        new_string
+    }
+}
+
+unsafe extern "C" fn godot_string_capitalize(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let aux = camelcase_to_underscore(s, true).replace('_', " ");
+        let aux = aux.trim_matches(|c| c <= ' ');
+
+        let mut cap = String::new();
+        for (i, slice) in aux.split(' ').enumerate() {
+            let mut chars = slice.chars();
+            if let Some(first) = chars.next() {
+                if i > 0 {
+                    cap.push(' ');
+                }
+                cap.push(to_upper(first));
+                cap.extend(chars);
+            }
+        }
+        cap
+    })
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Paths
+
+/// Returns the index of the last path separator.
+fn last_separator(s: &str) -> Option<usize> {
+    s.rfind(['/', '\\'])
+}
+
+/// Returns the index of the extension dot, if it belongs to the file name.
+fn extension_dot(s: &str) -> Option<usize> {
+    let dot = s.rfind('.')?;
+    match last_separator(s) {
+        Some(sep) if dot < sep => None,
+        _ => Some(dot),
+    }
+}
+
+unsafe extern "C" fn godot_string_get_extension(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        extension_dot(s).map_or("", |dot| &s[dot + 1..]).to_owned()
+    })
+}
+
+unsafe extern "C" fn godot_string_get_basename(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        extension_dot(s).map_or(s, |dot| &s[..dot]).to_owned()
+    })
+}
+
+unsafe extern "C" fn godot_string_get_file(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        last_separator(s).map_or(s, |sep| &s[sep + 1..]).to_owned()
+    })
+}
+
+fn is_network_share_path(s: &str) -> bool {
+    s.starts_with("//") || s.starts_with("\\\\")
+}
+
+unsafe extern "C" fn godot_string_get_base_dir(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let find_sep = |from: usize| {
+            let rest = s.get(from..).unwrap_or_default();
+            rest.find('/').or_else(|| rest.find('\\')).map(|i| i + from)
+        };
+
+        // URL scheme, Windows drive, network share or Unix root
+        let end = if let Some(pos) = s.find("://") {
+            pos + 3
+        } else if let Some(pos) = s.find(":/").or_else(|| s.find(":\\")) {
+            pos + 2
+        } else if is_network_share_path(s) {
+            find_sep(2)
+                .and_then(|share| find_sep(share + 1))
+                .map_or(0, |server| server + 1)
+        } else {
+            usize::from(s.starts_with('/'))
+        };
+
+        let (base, rest) = s.split_at(end);
+        match last_separator(rest) {
+            Some(sep) => format!("{base}{}", &rest[..sep]),
+            None => base.to_owned(),
+        }
+    })
+}
+
+unsafe extern "C" fn godot_string_simplify_path(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let (drive, rest) = if let Some(prefix) = ["local://", "res://", "user://"]
+            .into_iter()
+            .find(|prefix| s.starts_with(prefix))
+        {
+            s.split_at(prefix.len())
+        } else if is_network_share_path(s) {
+            s.split_at(2)
+        } else if s.starts_with(['/', '\\']) {
+            s.split_at(1)
+        } else {
+            match s.find(":/").or_else(|| s.find(":\\")) {
+                Some(p) if !matches!(s.find('/'), Some(slash) if slash < p) => s.split_at(p + 2),
+                _ => s.split_at(0),
+            }
+        };
+
+        let rest = rest.replace('\\', "/");
+        let mut dirs: Vec<&str> = Vec::new();
+        for dir in rest.split('/').filter(|dir| !dir.is_empty()) {
+            match dir {
+                "." => {}
+                ".." => {
+                    dirs.pop();
+                }
+                dir => dirs.push(dir),
+            }
+        }
+
+        format!("{drive}{}", dirs.join("/"))
+    })
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Escaping
+
+/// Applies string replacements in order.
+fn replace_all(s: &str, replacements: &[(&str, &str)]) -> String {
+    replacements
+        .iter()
+        .fold(s.to_owned(), |s, (from, to)| s.replace(from, to))
+}
+
+unsafe extern "C" fn godot_string_c_escape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        replace_all(
+            s,
+            &[
+                ("\\", "\\\\"),
+                ("\x07", "\\a"),
+                ("\x08", "\\b"),
+                ("\x0c", "\\f"),
+                ("\n", "\\n"),
+                ("\r", "\\r"),
+                ("\t", "\\t"),
+                ("\x0b", "\\v"),
+                ("'", "\\'"),
+                ("?", "\\?"),
+                ("\"", "\\\""),
+            ],
+        )
+    })
+}
+
+unsafe extern "C" fn godot_string_c_escape_multiline(
+    s: *const sys::godot_string,
+) -> sys::godot_string {
+    map(s, |s| replace_all(s, &[("\\", "\\\\"), ("\"", "\\\"")]))
+}
+
+unsafe extern "C" fn godot_string_c_unescape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        replace_all(
+            s,
+            &[
+                ("\\a", "\x07"),
+                ("\\b", "\x08"),
+                ("\\f", "\x0c"),
+                ("\\n", "\n"),
+                ("\\r", "\r"),
+                ("\\t", "\t"),
+                ("\\v", "\x0b"),
+                ("\\'", "'"),
+                ("\\\"", "\""),
+                ("\\?", "?"),
+                ("\\\\", "\\"),
+            ],
+        )
+    })
+}
+
+unsafe extern "C" fn godot_string_json_escape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, json_escape)
+}
+
+pub(super) fn json_escape(s: &str) -> String {
+    replace_all(
+        s,
+        &[
+            ("\\", "\\\\"),
+            ("\x08", "\\b"),
+            ("\x0c", "\\f"),
+            ("\n", "\\n"),
+            ("\r", "\\r"),
+            ("\t", "\\t"),
+            ("\x0b", "\\v"),
+            ("\"", "\\\""),
+        ],
+    )
+}
+
+unsafe extern "C" fn godot_string_xml_escape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| xml_escape(s, false))
+}
+
+unsafe extern "C" fn godot_string_xml_escape_with_quotes(
+    s: *const sys::godot_string,
+) -> sys::godot_string {
+    map(s, |s| xml_escape(s, true))
+}
+
+fn xml_escape(s: &str, escape_quotes: bool) -> String {
+    let s = replace_all(s, &[("&", "&amp;"), ("<", "&lt;"), (">", "&gt;")]);
+    if escape_quotes {
+        replace_all(&s, &[("'", "&apos;"), ("\"", "&quot;")])
+    } else {
+        s
+    }
+}
+
+unsafe extern "C" fn godot_string_xml_unescape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        const ENTITIES: [(&str, char); 5] = [
+            ("gt;", '>'),
+            ("lt;", '<'),
+            ("amp;", '&'),
+            ("quot;", '"'),
+            ("apos;", '\''),
+        ];
+
+        let mut unescaped = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(amp) = rest.find('&') {
+            unescaped.push_str(&rest[..amp]);
+            rest = &rest[amp + 1..];
+
+            let hex_entity = rest.strip_prefix("#x").and_then(|hex| {
+                let end = hex.find(';')?;
+                let c = u32::from_str_radix(&hex[..end], 16).ok()?;
+                Some((char::from_u32(c)?, &hex[end + 1..]))
+            });
+            let entity = hex_entity.or_else(|| {
+                ENTITIES
+                    .iter()
+                    .find_map(|(name, c)| rest.strip_prefix(name).map(|after| (*c, after)))
+            });
+
+            match entity {
+                Some((c, after)) => {
+                    unescaped.push(c);
+                    rest = after;
+                }
+                None => unescaped.push('&'),
+            }
+        }
+        unescaped.push_str(rest);
+        unescaped
+    })
+}
+
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'~' | b'.')
+}
+
+unsafe extern "C" fn godot_string_http_escape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let mut escaped = String::new();
+        for &b in s.as_bytes() {
+            if is_unreserved(b) {
+                escaped.push(b as char);
+            } else {
+                // Like the engine, which uses `%hhX` without padding.
+                escaped.push_str(&format!("%{b:X}"));
+            }
+        }
+        escaped
+    })
+}
+
+unsafe extern "C" fn godot_string_http_unescape(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let is_digit_or_upper = |c: char| c.is_ascii_digit() || c.is_ascii_uppercase();
+        let parse_hex = |digits: &[char]| {
+            digits
+                .iter()
+                .map_while(|c| c.to_digit(16))
+                .fold(0u32, |n, d| n * 16 + d)
+        };
+
+        let chars: Vec<char> = s.chars().collect();
+        let mut bytes = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '%' && i + 2 < chars.len() {
+                if is_digit_or_upper(chars[i + 1]) {
+                    if is_digit_or_upper(chars[i + 2]) {
+                        bytes.push(parse_hex(&chars[i + 1..i + 3]) as u8);
+                        i += 2;
+                    }
+                } else {
+                    bytes.push(c as u8);
+                }
+            } else {
+                bytes.push(c as u8);
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+unsafe extern "C" fn godot_string_percent_encode(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let mut encoded = String::new();
+        for &b in s.as_bytes() {
+            if is_unreserved(b) {
+                encoded.push(b as char);
+            } else {
+                encoded.push_str(&format!("%{b:02x}"));
+            }
+        }
+        encoded
+    })
+}
+
+unsafe extern "C" fn godot_string_percent_decode(s: *const sys::godot_string) -> sys::godot_string {
+    map(s, |s| {
+        let len = s.chars().count();
+        let bytes = s.as_bytes();
+        let mut decoded = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let mut b = bytes[i];
+            if b == b'%' && i + 2 < len {
+                let hex = |b: u8| (b as char).to_digit(16);
+                match (hex(bytes[i + 1]), bytes.get(i + 2).copied().and_then(hex)) {
+                    (Some(hi), Some(lo)) => {
+                        b = (hi * 16 + lo) as u8;
+                        i += 2;
+                    }
+                    // Invalid escapes are dropped.
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                }
+            }
+            decoded.push(b);
+            i += 1;
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    })
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// StringName
+
+unsafe extern "C" fn godot_string_name_new(
+    dest: *mut sys::godot_string_name,
+    name: *const sys::godot_string,
+) {
+    let text = text(name);
+    ptr::write(
+        dest,
+        if text.is_empty() {
+            sys::godot_string_name::default()
+        } else {
+            wrap_box(text.to_vec())
+        },
+    );
+}
+
+unsafe extern "C" fn godot_string_name_new_data(
+    dest: *mut sys::godot_string_name,
+    name: *const c_char,
+) {
+    // Like in the engine, the bytes are interpreted as Latin-1.
+    let name: String = CStr::from_ptr(name)
+        .to_bytes()
+        .iter()
+        .map(|&b| b as char)
+        .collect();
+    let name = from_str(&name);
+    godot_string_name_new(dest, &name);
+    godot_string_destroy(&name as *const _ as *mut _);
+}
+
+unsafe extern "C" fn godot_string_name_destroy(s: *mut sys::godot_string_name) {
+    drop_box::<_, Text>(s);
+}
+
+/// Returns the contents of a string name.
+unsafe fn name_text<'a>(s: *const sys::godot_string_name) -> &'a [wchar_t] {
+    get_box::<_, Text>(s).map_or(&[], Vec::as_slice)
+}
+
+unsafe extern "C" fn godot_string_name_get_name(
+    s: *const sys::godot_string_name,
+) -> sys::godot_string {
+    to_sys(name_text(s).to_vec())
+}
+
+unsafe extern "C" fn godot_string_name_get_hash(s: *const sys::godot_string_name) -> u32 {
+    hash(name_text(s))
+}
+
+unsafe extern "C" fn godot_string_name_operator_equal(
+    s: *const sys::godot_string_name,
+    other: *const sys::godot_string_name,
+) -> godot_bool {
+    name_text(s) == name_text(other)
+}
+
+unsafe extern "C" fn godot_string_name_operator_less(
+    s: *const sys::godot_string_name,
+    other: *const sys::godot_string_name,
+) -> godot_bool {
+    name_text(s) < name_text(other)
+}
@@ -0,0 +1,400 @@
+use crate::core_types::*;
+use crate::object::NewRef;
+
+#[test]
+fn string_basics() {
+    let s = GodotString::from_str("Hello, world");
+    assert_eq!(12, s.len());
+    assert_eq!("Hello, world", s.to_string());
+    assert_eq!(7, s.find(&"world".into()));
+    assert_eq!(-1, s.find(&"".into()));
+    assert_eq!(8, s.find_last(&"o".into()));
+    assert_eq!("world", s.sub_string(7..12).to_string());
+    assert_eq!("", s.sub_string(12..20).to_string());
+    assert!(s.begins_with(&"Hello".into()));
+    assert!(s.ends_with(&"world".into()));
+    assert_eq!(s, GodotString::from("Hello, world"));
+    assert!(GodotString::from("a") < GodotString::from("b"));
+    assert_eq!("HELLO, WORLD", s.to_uppercase().to_string());
+    assert!(GodotString::new().is_empty());
+}
+
+#[test]
+fn string_parsing() {
+    assert_eq!(-42, GodotString::from("-42").to_i32());
+    assert_eq!(12, GodotString::from("12.75").to_i32());
+    assert_eq!(i32::MAX, GodotString::from("99999999999").to_i32());
+    assert_eq!(2.5, GodotString::from("  2.5e0xyz").to_f64());
+    assert_eq!(255, GodotString::from("0xff").hex_to_int());
+    assert_eq!(0, GodotString::from("ff").hex_to_int());
+    assert!(GodotString::from("-12").is_valid_integer());
+    assert!(GodotString::from("1.5e-3").is_valid_float());
+    assert!(!GodotString::from("1.5e").is_numeric());
+    assert!(GodotString::from("_name2").is_valid_identifier());
+    assert!(GodotString::from("#ff00ff").is_valid_html_color());
+}
+
+#[test]
+fn string_transforms() {
+    assert_eq!(
+        "Snake Case Name",
+        GodotString::from("snake_case_name")
+            .capitalize()
+            .to_string()
+    );
+    assert_eq!(
+        "Camel Case Name",
+        GodotString::from("camelCaseName").capitalize().to_string()
+    );
+    assert_eq!(
+        "camel_case",
+        GodotString::from("CamelCase")
+            .camelcase_to_underscore_lowercased()
+            .to_string()
+    );
+    assert_eq!("a\\nb", GodotString::from("a\nb").c_escape().to_string());
+    assert_eq!(
+        "&lt;a&gt; &amp; &quot;b&quot;",
+        GodotString::from("<a> & \"b\"")
+            .xml_escape_with_quotes()
+            .to_string()
+    );
+    assert_eq!(
+        "<a> & é",
+        GodotString::from("&lt;a&gt; &amp; &#xe9;")
+            .xml_unescape()
+            .to_string()
+    );
+    assert_eq!(
+        "a%20b%C3%A9",
+        GodotString::from("a bé").http_escape().to_string()
+    );
+    assert_eq!(
+        "a bé",
+        GodotString::from("a%20b%c3%a9")
+            .percent_decode()
+            .to_string()
+    );
+}
+
+#[test]
+fn string_paths() {
+    let path = GodotString::from("res://assets/sprites/player.png");
+    assert!(path.is_resource_file());
+    assert!(path.is_absolute_path());
+    assert_eq!("player.png", path.get_file().to_string());
+    assert_eq!("png", path.get_extension().to_string());
+    assert_eq!(
+        "res://assets/sprites/player",
+        path.get_basename().to_string()
+    );
+    assert_eq!("res://assets/sprites", path.get_base_dir().to_string());
+    assert_eq!(
+        "res://b/d",
+        GodotString::from("res://a/../b/./c/../d")
+            .simplify_path()
+            .to_string()
+    );
+    assert_eq!("/", GodotString::from("/file").get_base_dir().to_string());
+}
+
+#[test]
+fn string_format() {
+    let values = VariantArray::new();
+    values.push("foo");
+    values.push(42);
+    let formatted = GodotString::from("{0} and {1}").format(&values.into_shared().to_variant());
+    assert_eq!("foo and 42", formatted.to_string());
+
+    let values = Dictionary::new();
+    values.insert("name", "Godot");
+    let formatted = GodotString::from("Hello, {name}!").format(&values.into_shared().to_variant());
+    assert_eq!("Hello, Godot!", formatted.to_string());
+}
+
+#[test]
+fn string_name() {
+    let name = StringName::from_str("position");
+    assert_eq!("position", name.to_godot_string().to_string());
+    assert_eq!(name, StringName::from_str("position"));
+    assert_eq!(GodotString::from("position").u32_hash(), name.get_hash());
+}
+
+#[test]
+fn variant_conversions() {
+    assert_eq!(VariantType::Nil, Variant::nil().get_type());
+    assert_eq!(Some(42), Variant::new(42).to::<i64>());
+    assert_eq!(Some(1.5), Variant::new(1.5).to::<f64>());
+    assert_eq!(None, Variant::new(1.5).to::<i64>());
+    assert_eq!(
+        Some(GodotString::from("text")),
+        Variant::new("text").to::<GodotString>()
+    );
+    assert_eq!(
+        Some(Vector3::new(1.0, 2.0, 3.0)),
+        Variant::new(Vector3::new(1.0, 2.0, 3.0)).to::<Vector3>()
+    );
+
+    assert_eq!(12, Variant::new("12").coerce_to::<i64>());
+    assert_eq!(3, Variant::new(3.9).coerce_to::<i64>());
+    assert!(Variant::new(3).coerce_to::<bool>());
+    assert!(!Variant::new("").coerce_to::<bool>());
+    assert_eq!(
+        Vector2::new(1.0, 2.0),
+        Variant::new(Vector3::new(1.0, 2.0, 3.0)).coerce_to::<Vector2>()
+    );
+    assert_eq!(
+        Color::from_rgb(1.0, 0.0, 0.0),
+        Variant::new("ff0000").coerce_to::<Color>()
+    );
+}
+
+#[test]
+fn variant_display() {
+    assert_eq!("Null", Variant::nil().to_string());
+    assert_eq!("True", Variant::new(true).to_string());
+    assert_eq!("-7", Variant::new(-7).to_string());
+    assert_eq!("0.1", Variant::new(0.1).to_string());
+    assert_eq!("2", Variant::new(2.0).to_string());
+    assert_eq!("(1, 2.5)", Variant::new(Vector2::new(1.0, 2.5)).to_string());
+
+    let arr = VariantArray::new();
+    arr.push(1);
+    arr.push("two");
+    arr.push(Vector2::ZERO);
+    assert_eq!(
+        "[1, two, (0, 0)]",
+        arr.into_shared().to_variant().to_string()
+    );
+}
+
+#[test]
+fn variant_equality_and_hash() {
+    assert_eq!(Variant::new(1), Variant::new(1));
+    assert_ne!(Variant::new(1), Variant::new(1.0));
+    assert_ne!(Variant::new(1), Variant::nil());
+
+    let a = Variant::new("key");
+    let b = Variant::new(GodotString::from("key"));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn variant_godot_hash() {
+    let dict = Dictionary::new();
+    dict.insert("k", Vector3::new(1.0, -0.0, 3.0));
+    let arr = VariantArray::new();
+    arr.push(2.5);
+    arr.push(dict.into_shared());
+
+    let values = [
+        Variant::new(-7),
+        Variant::new(0.25),
+        Variant::new("key"),
+        Variant::new(Vector2::new(1.0, 2.0)),
+        Variant::new(Transform2D::IDENTITY),
+        Variant::new(Color::from_rgb(0.5, 0.25, 1.0)),
+        arr.into_shared().to_variant(),
+    ];
+    for value in &values {
+        let expected = unsafe { super::variant::hash(super::variant::get(value.sys())) };
+        assert_eq!(expected, value.godot_hash(), "{value:?}");
+    }
+
+    assert!(Variant::new(f64::NAN).hash_compare(&Variant::new(f64::NAN)));
+    assert_eq!(
+        Some(std::cmp::Ordering::Equal),
+        Variant::new(1).script_cmp(&Variant::new(1.0))
+    );
+}
+
+#[test]
+fn variant_evaluate() {
+    use crate::core_types::variant::VariantOperator as Op;
+
+    let eval = |op, a: Variant, b: Variant| a.evaluate(op, &b).ok();
+
+    assert_eq!(
+        Some(Variant::new(5)),
+        eval(Op::Add, 2.to_variant(), 3.to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(2.5)),
+        eval(Op::Divide, 5.to_variant(), 2.0.to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(2)),
+        eval(Op::Divide, 5.to_variant(), 2.to_variant())
+    );
+    assert_eq!(None, eval(Op::Divide, 5.to_variant(), 0.to_variant()));
+    assert_eq!(
+        Some(Variant::new(true)),
+        eval(Op::Equal, 1.to_variant(), 1.0.to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(false)),
+        eval(Op::Equal, 1.to_variant(), Variant::nil())
+    );
+    assert_eq!(None, eval(Op::Equal, 1.to_variant(), "1".to_variant()));
+    assert_eq!(
+        Some(Variant::new("ab")),
+        eval(Op::Add, "a".to_variant(), "b".to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(true)),
+        eval(Op::In, "ell".to_variant(), "hello".to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(Vector2::new(2.0, 4.0))),
+        eval(
+            Op::Multiply,
+            Vector2::new(1.0, 2.0).to_variant(),
+            2.to_variant()
+        )
+    );
+    assert_eq!(
+        Some(Variant::new(true)),
+        eval(
+            Op::Less,
+            Vector2::new(1.0, 5.0).to_variant(),
+            Vector2::new(2.0, 0.0).to_variant()
+        )
+    );
+    assert_eq!(
+        Some(Variant::new(8)),
+        eval(Op::ShiftLeft, 1.to_variant(), 3.to_variant())
+    );
+    assert_eq!(
+        Some(Variant::new(true)),
+        eval(Op::And, 1.to_variant(), "x".to_variant())
+    );
+}
+
+#[test]
+fn array_operations() {
+    let arr = VariantArray::new();
+    for i in [3, 1, 2] {
+        arr.push(i);
+    }
+    arr.push_front("first");
+    assert_eq!(4, arr.len());
+    assert_eq!(Variant::new("first"), arr.get(0));
+
+    arr.erase("first");
+    assert!(arr.contains(2));
+    assert_eq!(2, arr.find(2, 0));
+    assert_eq!(-1, arr.find(2, 3));
+    assert_eq!(1, arr.count(1));
+
+    arr.sort();
+    let items: Vec<i64> = arr.iter().map(|v| v.to::<i64>().unwrap()).collect();
+    assert_eq!(vec![1, 2, 3], items);
+
+    arr.invert();
+    assert_eq!(Variant::new(3), arr.pop_front());
+    assert_eq!(Variant::new(1), arr.pop());
+    assert_eq!(1, arr.len());
+
+    arr.resize(3);
+    assert_eq!(Variant::nil(), arr.get(2));
+
+    let shared = arr.into_shared();
+    let copy = shared.new_ref();
+    unsafe { copy.assume_unique() }.push(4);
+    assert_eq!(4, shared.len());
+    assert_eq!("[2, Null, Null, 4]", shared.to_variant().to_string());
+}
+
+#[test]
+fn array_duplicate() {
+    let inner = VariantArray::new();
+    inner.push(1);
+    let inner = inner.into_shared();
+
+    let outer = VariantArray::new();
+    outer.push(inner.new_ref());
+    let outer = outer.into_shared();
+
+    let shallow = outer.duplicate();
+    let deep = outer.duplicate_deep();
+    unsafe { inner.assume_unique() }.push(2);
+
+    assert_eq!("[[1, 2]]", shallow.into_shared().to_variant().to_string());
+    assert_eq!("[[1]]", deep.into_shared().to_variant().to_string());
+}
+
+#[test]
+fn dictionary_operations() {
+    let dict = Dictionary::new();
+    dict.insert("b", 2);
+    dict.insert("a", 1);
+    dict.insert(3, "three");
+    assert_eq!(3, dict.len());
+    assert!(dict.contains("a"));
+    assert_eq!(Some(Variant::new(2)), dict.get("b"));
+    assert_eq!(None, dict.get("c"));
+
+    dict.insert("b", 20);
+    assert_eq!(Some(Variant::new(20)), dict.get("b"));
+
+    let keys: Vec<String> = dict.iter().map(|(k, _)| k.to_string()).collect();
+    assert_eq!(vec!["b", "a", "3"], keys);
+
+    dict.erase("a");
+    assert_eq!(2, dict.len());
+    assert_eq!(
+        VariantArray::from_iter(["b".to_variant(), 3.to_variant()]).len(),
+        dict.keys().len()
+    );
+
+    assert_eq!("{\"3\":\"three\",\"b\":20}", dict.to_json().to_string());
+
+    let shared = dict.into_shared();
+    assert_eq!(shared.to_variant(), shared.new_ref().to_variant());
+    assert_ne!(
+        shared.to_variant(),
+        shared.duplicate().into_shared().to_variant()
+    );
+}
+
+#[test]
+fn node_path() {
+    let mut path = NodePath::from_str("/root/Level/Player:position:x");
+    assert!(path.is_absolute());
+    assert!(!path.is_empty());
+    assert_eq!(3, path.name_count());
+    assert_eq!("Player", path.get_name(2).to_string());
+    assert_eq!(2, path.get_subname_count());
+    assert_eq!("position:x", path.get_concatenated_subnames().to_string());
+    assert_eq!("/root/Level/Player:position:x", path.to_string());
+
+    assert!(NodePath::from_str("").is_empty());
+    assert_eq!(NodePath::from_str("a//b"), NodePath::from_str("a/b"));
+
+    let variant = Variant::new("Player:position");
+    assert_eq!(
+        NodePath::from_str("Player:position"),
+        variant.coerce_to::<NodePath>()
+    );
+}
+
+#[test]
+fn color() {
+    let red = Color::from_rgb(1.0, 0.0, 0.0);
+    assert_eq!("ff0000", red.to_html(false).to_string());
+    assert_eq!("ffff0000", red.to_html(true).to_string());
+    assert_eq!(0.0, red.h());
+    assert_eq!(1.0, red.s());
+    assert_eq!(1.0, red.v());
+
+    let blue = Color::from_hsv(2.0 / 3.0, 1.0, 1.0);
+    assert!((blue.b - 1.0).abs() < 1e-6 && blue.r.abs() < 1e-6);
+    assert_eq!(Color::from_rgb(0.5, 0.0, 0.0), red.darkened(0.5));
+}
+
+#[test]
+fn rid() {
+    let rid = Rid::new();
+    assert!(!rid.is_occupied());
+    assert_eq!(rid, Rid::new());
+}
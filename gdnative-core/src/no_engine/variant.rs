@@ -0,0 +1,1229 @@
+//! Fallbacks for `Variant`, including conversions, hashing, formatting and operators.
+//!
+//! Like in the engine, arrays and dictionaries are shared between copies of a variant, while all
+//! other types are copied.
+
+use std::ptr;
+use std::sync::Arc;
+
+use crate::core_types::{
+    Aabb, Basis, Color, Plane, Quat, Rect2, Transform, Transform2D, Vector2, Vector3,
+};
+use crate::sys::{self, godot_bool, godot_int, godot_variant_operator, godot_variant_type};
+
+use sys::{
+    godot_variant_operator_GODOT_VARIANT_OP_ADD as OP_ADD,
+    godot_variant_operator_GODOT_VARIANT_OP_AND as OP_AND,
+    godot_variant_operator_GODOT_VARIANT_OP_BIT_AND as OP_BIT_AND,
+    godot_variant_operator_GODOT_VARIANT_OP_BIT_NEGATE as OP_BIT_NEGATE,
+    godot_variant_operator_GODOT_VARIANT_OP_BIT_OR as OP_BIT_OR,
+    godot_variant_operator_GODOT_VARIANT_OP_BIT_XOR as OP_BIT_XOR,
+    godot_variant_operator_GODOT_VARIANT_OP_DIVIDE as OP_DIVIDE,
+    godot_variant_operator_GODOT_VARIANT_OP_EQUAL as OP_EQUAL,
+    godot_variant_operator_GODOT_VARIANT_OP_GREATER as OP_GREATER,
+    godot_variant_operator_GODOT_VARIANT_OP_GREATER_EQUAL as OP_GREATER_EQUAL,
+    godot_variant_operator_GODOT_VARIANT_OP_IN as OP_IN,
+    godot_variant_operator_GODOT_VARIANT_OP_LESS as OP_LESS,
+    godot_variant_operator_GODOT_VARIANT_OP_LESS_EQUAL as OP_LESS_EQUAL,
+    godot_variant_operator_GODOT_VARIANT_OP_MODULE as OP_MODULE,
+    godot_variant_operator_GODOT_VARIANT_OP_MULTIPLY as OP_MULTIPLY,
+    godot_variant_operator_GODOT_VARIANT_OP_NEGATE as OP_NEGATE,
+    godot_variant_operator_GODOT_VARIANT_OP_NOT as OP_NOT,
+    godot_variant_operator_GODOT_VARIANT_OP_NOT_EQUAL as OP_NOT_EQUAL,
+    godot_variant_operator_GODOT_VARIANT_OP_OR as OP_OR,
+    godot_variant_operator_GODOT_VARIANT_OP_POSITIVE as OP_POSITIVE,
+    godot_variant_operator_GODOT_VARIANT_OP_SHIFT_LEFT as OP_SHIFT_LEFT,
+    godot_variant_operator_GODOT_VARIANT_OP_SHIFT_RIGHT as OP_SHIFT_RIGHT,
+    godot_variant_operator_GODOT_VARIANT_OP_STRING_CONCAT as OP_STRING_CONCAT,
+    godot_variant_operator_GODOT_VARIANT_OP_SUBTRACT as OP_SUBTRACT,
+    godot_variant_operator_GODOT_VARIANT_OP_XOR as OP_XOR,
+};
+
+use super::array::ArrayData;
+use super::dictionary::DictData;
+use super::node_path::{self, Path};
+use super::string::{self, Text};
+use super::{drop_box, get_box, hash_djb2_one_32, hash_djb2_one_float, wrap_box};
+use super::{HASH_SEED, MATH_HASH_SEED};
+
+/// Contents of a variant.
+#[derive(Clone)]
+pub(super) enum Value {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    String(Text),
+    Vector2(Vector2),
+    Rect2(Rect2),
+    Vector3(Vector3),
+    Transform2D(Transform2D),
+    Plane(Plane),
+    Quat(Quat),
+    Aabb(Aabb),
+    Basis(Basis),
+    Transform(Transform),
+    Color(Color),
+    NodePath(Option<Arc<Path>>),
+    Rid(usize),
+    Object(ObjectPtr),
+    Dictionary(Arc<DictData>),
+    Array(Arc<ArrayData>),
+}
+
+/// Object pointer stored in a variant. Objects are never accessed by the fallbacks.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+pub(super) struct ObjectPtr(*mut sys::godot_object);
+
+unsafe impl Send for ObjectPtr {}
+unsafe impl Sync for ObjectPtr {}
+
+static NIL: Value = Value::Nil;
+
+pub(super) fn bind(api: &mut sys::GodotApi) {
+    bind!(api:
+        godot_variant_get_type,
+        godot_variant_new_copy,
+        godot_variant_new_nil,
+        godot_variant_new_bool,
+        godot_variant_new_uint,
+        godot_variant_new_int,
+        godot_variant_new_real,
+        godot_variant_new_string,
+        godot_variant_new_vector2,
+        godot_variant_new_rect2,
+        godot_variant_new_vector3,
+        godot_variant_new_transform2d,
+        godot_variant_new_plane,
+        godot_variant_new_quat,
+        godot_variant_new_aabb,
+        godot_variant_new_basis,
+        godot_variant_new_transform,
+        godot_variant_new_color,
+        godot_variant_new_node_path,
+        godot_variant_new_rid,
+        godot_variant_new_object,
+        godot_variant_new_dictionary,
+        godot_variant_new_array,
+        godot_variant_as_bool,
+        godot_variant_as_uint,
+        godot_variant_as_int,
+        godot_variant_as_real,
+        godot_variant_as_string,
+        godot_variant_as_vector2,
+        godot_variant_as_rect2,
+        godot_variant_as_vector3,
+        godot_variant_as_transform2d,
+        godot_variant_as_plane,
+        godot_variant_as_quat,
+        godot_variant_as_aabb,
+        godot_variant_as_basis,
+        godot_variant_as_transform,
+        godot_variant_as_color,
+        godot_variant_as_node_path,
+        godot_variant_as_rid,
+        godot_variant_as_object,
+        godot_variant_as_dictionary,
+        godot_variant_as_array,
+        godot_variant_call,
+        godot_variant_has_method,
+        godot_variant_operator_equal,
+        godot_variant_operator_less,
+        godot_variant_hash_compare,
+        godot_variant_booleanize,
+        godot_variant_evaluate,
+        godot_variant_destroy,
+    );
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Storage
+
+/// Creates a variant owning `value`.
+pub(super) fn to_sys(value: Value) -> sys::godot_variant {
+    match value {
+        Value::Nil => sys::godot_variant::default(),
+        value => wrap_box(value),
+    }
+}
+
+/// Returns the contents of a variant.
+pub(super) unsafe fn get<'a>(v: *const sys::godot_variant) -> &'a Value {
+    get_box(v).unwrap_or(&NIL)
+}
+
+/// Replaces the contents of an initialized variant.
+pub(super) unsafe fn assign(dest: *mut sys::godot_variant, value: Value) {
+    drop_box::<_, Value>(dest);
+    ptr::write(dest, to_sys(value));
+}
+
+/// Variant owned by a container. Cloning a slot copies the variant.
+#[repr(transparent)]
+pub(super) struct Slot(sys::godot_variant);
+
+impl Slot {
+    pub(super) fn new(value: Value) -> Self {
+        Slot(to_sys(value))
+    }
+
+    /// Copies the variant at `v`.
+    pub(super) unsafe fn copy_from(v: *const sys::godot_variant) -> Self {
+        Slot::new(get(v).clone())
+    }
+
+    pub(super) fn get(&self) -> &Value {
+        unsafe { get(&self.0) }
+    }
+
+    pub(super) fn as_ptr(&self) -> *const sys::godot_variant {
+        &self.0
+    }
+
+    pub(super) fn as_mut_ptr(&mut self) -> *mut sys::godot_variant {
+        &mut self.0
+    }
+}
+
+impl Clone for Slot {
+    fn clone(&self) -> Self {
+        Slot::new(self.get().clone())
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        unsafe { drop_box::<_, Value>(&mut self.0) }
+    }
+}
+
+/// Reads a math type from its sys representation.
+unsafe fn read<S, T>(sys: *const S) -> T {
+    debug_assert_eq!(std::mem::size_of::<S>(), std::mem::size_of::<T>());
+    ptr::read_unaligned(sys as *const T)
+}
+
+/// Converts a math type to its sys representation.
+fn write<T, S>(value: T) -> S {
+    debug_assert_eq!(std::mem::size_of::<S>(), std::mem::size_of::<T>());
+    unsafe { ptr::read_unaligned(&value as *const T as *const S) }
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Construction
+
+unsafe extern "C" fn godot_variant_get_type(v: *const sys::godot_variant) -> godot_variant_type {
+    get(v).type_id()
+}
+
+unsafe extern "C" fn godot_variant_new_copy(
+    dest: *mut sys::godot_variant,
+    src: *const sys::godot_variant,
+) {
+    ptr::write(dest, to_sys(get(src).clone()));
+}
+
+unsafe extern "C" fn godot_variant_new_nil(dest: *mut sys::godot_variant) {
+    ptr::write(dest, to_sys(Value::Nil));
+}
+
+unsafe extern "C" fn godot_variant_new_bool(dest: *mut sys::godot_variant, b: godot_bool) {
+    ptr::write(dest, to_sys(Value::Bool(b)));
+}
+
+unsafe extern "C" fn godot_variant_new_uint(dest: *mut sys::godot_variant, i: u64) {
+    ptr::write(dest, to_sys(Value::Int(i as i64)));
+}
+
+unsafe extern "C" fn godot_variant_new_int(dest: *mut sys::godot_variant, i: i64) {
+    ptr::write(dest, to_sys(Value::Int(i)));
+}
+
+unsafe extern "C" fn godot_variant_new_real(dest: *mut sys::godot_variant, r: f64) {
+    ptr::write(dest, to_sys(Value::Real(r)));
+}
+
+unsafe extern "C" fn godot_variant_new_string(
+    dest: *mut sys::godot_variant,
+    s: *const sys::godot_string,
+) {
+    ptr::write(dest, to_sys(Value::String(string::text(s).to_vec())));
+}
+
+macro_rules! new_math {
+    ($($name:ident($sys:ident) => $variant:ident,)*) => {
+        $(
+            unsafe extern "C" fn $name(dest: *mut sys::godot_variant, value: *const sys::$sys) {
+                ptr::write(dest, to_sys(Value::$variant(read(value))));
+            }
+        )*
+    };
+}
+
+new_math! {
+    godot_variant_new_vector2(godot_vector2) => Vector2,
+    godot_variant_new_rect2(godot_rect2) => Rect2,
+    godot_variant_new_vector3(godot_vector3) => Vector3,
+    godot_variant_new_transform2d(godot_transform2d) => Transform2D,
+    godot_variant_new_plane(godot_plane) => Plane,
+    godot_variant_new_quat(godot_quat) => Quat,
+    godot_variant_new_aabb(godot_aabb) => Aabb,
+    godot_variant_new_basis(godot_basis) => Basis,
+    godot_variant_new_transform(godot_transform) => Transform,
+    godot_variant_new_color(godot_color) => Color,
+}
+
+unsafe extern "C" fn godot_variant_new_node_path(
+    dest: *mut sys::godot_variant,
+    np: *const sys::godot_node_path,
+) {
+    ptr::write(dest, to_sys(Value::NodePath(node_path::get(np))));
+}
+
+unsafe extern "C" fn godot_variant_new_rid(
+    dest: *mut sys::godot_variant,
+    rid: *const sys::godot_rid,
+) {
+    ptr::write(dest, to_sys(Value::Rid(super::load::<_, u8>(rid) as usize)));
+}
+
+unsafe extern "C" fn godot_variant_new_object(
+    dest: *mut sys::godot_variant,
+    obj: *const sys::godot_object,
+) {
+    ptr::write(dest, to_sys(Value::Object(ObjectPtr(obj as *mut _))));
+}
+
+unsafe extern "C" fn godot_variant_new_dictionary(
+    dest: *mut sys::godot_variant,
+    dict: *const sys::godot_dictionary,
+) {
+    ptr::write(dest, to_sys(Value::Dictionary(super::get_arc(dict))));
+}
+
+unsafe extern "C" fn godot_variant_new_array(
+    dest: *mut sys::godot_variant,
+    arr: *const sys::godot_array,
+) {
+    ptr::write(dest, to_sys(Value::Array(super::get_arc(arr))));
+}
+
+unsafe extern "C" fn godot_variant_destroy(v: *mut sys::godot_variant) {
+    drop_box::<_, Value>(v);
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Conversion
+
+unsafe extern "C" fn godot_variant_as_bool(v: *const sys::godot_variant) -> godot_bool {
+    !get(v).is_zero()
+}
+
+unsafe extern "C" fn godot_variant_as_uint(v: *const sys::godot_variant) -> u64 {
+    get(v).to_int() as u64
+}
+
+unsafe extern "C" fn godot_variant_as_int(v: *const sys::godot_variant) -> i64 {
+    get(v).to_int()
+}
+
+unsafe extern "C" fn godot_variant_as_real(v: *const sys::godot_variant) -> f64 {
+    get(v).to_real()
+}
+
+unsafe extern "C" fn godot_variant_as_string(v: *const sys::godot_variant) -> sys::godot_string {
+    match get(v) {
+        Value::String(text) => string::to_sys(text.clone()),
+        value => string::from_str(&stringify(value)),
+    }
+}
+
+unsafe extern "C" fn godot_variant_as_vector2(v: *const sys::godot_variant) -> sys::godot_vector2 {
+    write(match get(v) {
+        Value::Vector2(v) => *v,
+        Value::Vector3(v) => Vector2::new(v.x, v.y),
+        _ => Vector2::ZERO,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_rect2(v: *const sys::godot_variant) -> sys::godot_rect2 {
+    write(match get(v) {
+        Value::Rect2(r) => *r,
+        _ => Rect2::new(Vector2::ZERO, Vector2::ZERO),
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_vector3(v: *const sys::godot_variant) -> sys::godot_vector3 {
+    write(match get(v) {
+        Value::Vector3(v) => *v,
+        Value::Vector2(v) => Vector3::new(v.x, v.y, 0.0),
+        _ => Vector3::ZERO,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_transform2d(
+    v: *const sys::godot_variant,
+) -> sys::godot_transform2d {
+    write(match get(v) {
+        Value::Transform2D(t) => *t,
+        Value::Transform(t) => {
+            let b = &t.basis.elements;
+            Transform2D {
+                a: Vector2::new(b[0].x, b[1].x),
+                b: Vector2::new(b[0].y, b[1].y),
+                origin: Vector2::new(t.origin.x, t.origin.y),
+            }
+        }
+        _ => Transform2D::IDENTITY,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_plane(v: *const sys::godot_variant) -> sys::godot_plane {
+    write(match get(v) {
+        Value::Plane(p) => *p,
+        _ => Plane::new(Vector3::ZERO, 0.0),
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_quat(v: *const sys::godot_variant) -> sys::godot_quat {
+    write(match get(v) {
+        Value::Quat(q) => *q,
+        Value::Basis(b) => b.to_quat(),
+        Value::Transform(t) => t.basis.to_quat(),
+        _ => Quat::IDENTITY,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_aabb(v: *const sys::godot_variant) -> sys::godot_aabb {
+    write(match get(v) {
+        Value::Aabb(aabb) => *aabb,
+        _ => Aabb::default(),
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_basis(v: *const sys::godot_variant) -> sys::godot_basis {
+    write(match get(v) {
+        Value::Basis(b) => *b,
+        Value::Quat(q) => Basis::from_quat(*q),
+        Value::Vector3(euler) => Basis::from_euler(*euler),
+        Value::Transform(t) => t.basis,
+        _ => Basis::IDENTITY,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_transform(
+    v: *const sys::godot_variant,
+) -> sys::godot_transform {
+    write(match get(v) {
+        Value::Transform(t) => *t,
+        Value::Transform2D(t) => {
+            let mut basis = Basis::IDENTITY;
+            basis.elements[0].x = t.a.x;
+            basis.elements[1].x = t.a.y;
+            basis.elements[0].y = t.b.x;
+            basis.elements[1].y = t.b.y;
+            Transform {
+                basis,
+                origin: Vector3::new(t.origin.x, t.origin.y, 0.0),
+            }
+        }
+        Value::Basis(basis) => Transform {
+            basis: *basis,
+            origin: Vector3::ZERO,
+        },
+        Value::Quat(q) => Transform {
+            basis: Basis::from_quat(*q),
+            origin: Vector3::ZERO,
+        },
+        _ => Transform::IDENTITY,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_color(v: *const sys::godot_variant) -> sys::godot_color {
+    let black = Color::from_rgb(0.0, 0.0, 0.0);
+    write(match get(v) {
+        Value::Color(c) => *c,
+        Value::String(text) => {
+            let html = string::decode(text);
+            Color::from_html(&html).unwrap_or_else(|| {
+                super::log::error("Color::html", &format!("Invalid color code: {html}."));
+                black
+            })
+        }
+        Value::Int(i) => Color::from_rgba_u32(*i as u32),
+        _ => black,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_node_path(
+    v: *const sys::godot_variant,
+) -> sys::godot_node_path {
+    node_path::to_sys(match get(v) {
+        Value::NodePath(path) => path.clone(),
+        Value::String(text) => Path::parse(text),
+        _ => None,
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_rid(v: *const sys::godot_variant) -> sys::godot_rid {
+    match get(v) {
+        Value::Rid(rid) => super::wrap(*rid as *mut u8),
+        _ => sys::godot_rid::default(),
+    }
+}
+
+unsafe extern "C" fn godot_variant_as_object(
+    v: *const sys::godot_variant,
+) -> *mut sys::godot_object {
+    match get(v) {
+        Value::Object(obj) => obj.0,
+        _ => ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn godot_variant_as_dictionary(
+    v: *const sys::godot_variant,
+) -> sys::godot_dictionary {
+    super::wrap_arc(match get(v) {
+        Value::Dictionary(dict) => dict.clone(),
+        _ => Arc::default(),
+    })
+}
+
+unsafe extern "C" fn godot_variant_as_array(v: *const sys::godot_variant) -> sys::godot_array {
+    super::wrap_arc(match get(v) {
+        Value::Array(arr) => arr.clone(),
+        _ => Arc::default(),
+    })
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Methods and comparison
+
+unsafe extern "C" fn godot_variant_call(
+    _v: *mut sys::godot_variant,
+    _method: *const sys::godot_string,
+    _args: *mut *const sys::godot_variant,
+    _argcount: godot_int,
+    r_error: *mut sys::godot_variant_call_error,
+) -> sys::godot_variant {
+    // Built-in methods are not available without the engine.
+    if let Some(r_error) = r_error.as_mut() {
+        r_error.error =
+            sys::godot_variant_call_error_error_GODOT_CALL_ERROR_CALL_ERROR_INVALID_METHOD;
+    }
+    to_sys(Value::Nil)
+}
+
+unsafe extern "C" fn godot_variant_has_method(
+    _v: *const sys::godot_variant,
+    _method: *const sys::godot_string,
+) -> godot_bool {
+    false
+}
+
+unsafe extern "C" fn godot_variant_operator_equal(
+    v: *const sys::godot_variant,
+    other: *const sys::godot_variant,
+) -> godot_bool {
+    strict_eq(get(v), get(other))
+}
+
+unsafe extern "C" fn godot_variant_operator_less(
+    v: *const sys::godot_variant,
+    other: *const sys::godot_variant,
+) -> godot_bool {
+    strict_less(get(v), get(other))
+}
+
+unsafe extern "C" fn godot_variant_hash_compare(
+    v: *const sys::godot_variant,
+    other: *const sys::godot_variant,
+) -> godot_bool {
+    hash_compare(get(v), get(other))
+}
+
+unsafe extern "C" fn godot_variant_booleanize(v: *const sys::godot_variant) -> godot_bool {
+    !get(v).is_zero()
+}
+
+unsafe extern "C" fn godot_variant_evaluate(
+    op: godot_variant_operator,
+    a: *const sys::godot_variant,
+    b: *const sys::godot_variant,
+    r_ret: *mut sys::godot_variant,
+    r_valid: *mut godot_bool,
+) {
+    match evaluate(op, get(a), get(b)) {
+        Some(value) => {
+            assign(r_ret, value);
+            *r_valid = true;
+        }
+        None => *r_valid = false,
+    }
+}
+
+/// `Variant::operator==`: values of different types are never equal.
+pub(super) fn strict_eq(a: &Value, b: &Value) -> bool {
+    a.type_id() == b.type_id() && evaluate_bool(OP_EQUAL, a, b)
+}
+
+/// `Variant::operator<`: values are ordered by type first.
+pub(super) fn strict_less(a: &Value, b: &Value) -> bool {
+    if a.type_id() != b.type_id() {
+        return a.type_id() < b.type_id();
+    }
+    evaluate_bool(OP_LESS, a, b)
+}
+
+/// Compares values for use as dictionary keys.
+pub(super) fn hash_compare(a: &Value, b: &Value) -> bool {
+    if a.type_id() != b.type_id() {
+        return false;
+    }
+
+    match (a, b) {
+        (Value::Real(a), Value::Real(b)) => same_float(*a, *b),
+        (Value::Array(a), Value::Array(b)) => {
+            let (a, b) = unsafe { (a.items(), b.items()) };
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| hash_compare(a.get(), b.get()))
+        }
+        _ => match (a.floats(), b.floats()) {
+            (Some(a), Some(b)) => a.iter().zip(&b).all(|(&a, &b)| same_float(a, b)),
+            _ => evaluate_bool(OP_EQUAL, a, b),
+        },
+    }
+}
+
+fn same_float(a: impl Into<f64>, b: impl Into<f64>) -> bool {
+    let (a, b) = (a.into(), b.into());
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+impl Value {
+    pub(super) fn type_id(&self) -> godot_variant_type {
+        match self {
+            Value::Nil => sys::godot_variant_type_GODOT_VARIANT_TYPE_NIL,
+            Value::Bool(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_BOOL,
+            Value::Int(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_INT,
+            Value::Real(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_REAL,
+            Value::String(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_STRING,
+            Value::Vector2(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_VECTOR2,
+            Value::Rect2(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_RECT2,
+            Value::Vector3(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_VECTOR3,
+            Value::Transform2D(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_TRANSFORM2D,
+            Value::Plane(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_PLANE,
+            Value::Quat(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_QUAT,
+            Value::Aabb(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_AABB,
+            Value::Basis(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_BASIS,
+            Value::Transform(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_TRANSFORM,
+            Value::Color(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_COLOR,
+            Value::NodePath(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_NODE_PATH,
+            Value::Rid(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_RID,
+            Value::Object(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_OBJECT,
+            Value::Dictionary(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_DICTIONARY,
+            Value::Array(_) => sys::godot_variant_type_GODOT_VARIANT_TYPE_ARRAY,
+        }
+    }
+
+    /// `Variant::is_zero`, which is the inverse of the conversion to `bool`.
+    fn is_zero(&self) -> bool {
+        match self {
+            Value::Nil => true,
+            Value::Bool(b) => !b,
+            Value::Int(i) => *i == 0,
+            Value::Real(r) => *r == 0.0,
+            Value::String(text) => text.is_empty(),
+            Value::Vector2(v) => *v == Vector2::ZERO,
+            Value::Rect2(r) => r.position == Vector2::ZERO && r.size == Vector2::ZERO,
+            Value::Vector3(v) => *v == Vector3::ZERO,
+            Value::Transform2D(t) => *t == Transform2D::IDENTITY,
+            Value::Plane(p) => p.normal == Vector3::ZERO && p.d == 0.0,
+            Value::Quat(q) => *q == Quat::IDENTITY,
+            Value::Aabb(aabb) => *aabb == Aabb::default(),
+            Value::Basis(b) => *b == Basis::IDENTITY,
+            Value::Transform(t) => *t == Transform::IDENTITY,
+            Value::Color(c) => *c == Color::from_rgb(0.0, 0.0, 0.0),
+            Value::NodePath(path) => path.is_none(),
+            Value::Rid(rid) => *rid == 0,
+            Value::Object(obj) => obj.0.is_null(),
+            Value::Dictionary(dict) => unsafe { dict.len() == 0 },
+            Value::Array(arr) => unsafe { arr.items().is_empty() },
+        }
+    }
+
+    fn to_int(&self) -> i64 {
+        match self {
+            Value::Bool(b) => i64::from(*b),
+            Value::Int(i) => *i,
+            Value::Real(r) => *r as i64,
+            Value::String(text) => string::to_int64(&string::decode(text)),
+            _ => 0,
+        }
+    }
+
+    fn to_real(&self) -> f64 {
+        match self {
+            Value::Bool(b) => f64::from(u8::from(*b)),
+            Value::Int(i) => *i as f64,
+            Value::Real(r) => *r,
+            Value::String(text) => string::to_double(&string::decode(text)),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the components of floating point math types.
+    fn floats(&self) -> Option<Vec<f32>> {
+        let v2 = |v: &Vector2| [v.x, v.y];
+        let v3 = |v: &Vector3| [v.x, v.y, v.z];
+
+        Some(match self {
+            Value::Vector2(v) => v2(v).to_vec(),
+            Value::Rect2(r) => [v2(&r.position), v2(&r.size)].concat(),
+            Value::Vector3(v) => v3(v).to_vec(),
+            Value::Transform2D(t) => [v2(&t.a), v2(&t.b), v2(&t.origin)].concat(),
+            Value::Plane(p) => [&v3(&p.normal)[..], &[p.d]].concat(),
+            Value::Quat(q) => vec![q.x, q.y, q.z, q.w],
+            Value::Aabb(aabb) => [v3(&aabb.position), v3(&aabb.size)].concat(),
+            Value::Basis(b) => b.elements.iter().flat_map(v3).collect(),
+            Value::Transform(t) => t
+                .basis
+                .elements
+                .iter()
+                .chain(Some(&t.origin))
+                .flat_map(v3)
+                .collect(),
+            Value::Color(c) => vec![c.r, c.g, c.b, c.a],
+            _ => return None,
+        })
+    }
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Hashing
+
+/// The engine's variant hash function.
+pub(super) fn hash(value: &Value) -> u32 {
+    match value {
+        Value::Nil => 0,
+        Value::Bool(b) => u32::from(*b),
+        Value::Int(i) => *i as u32,
+        Value::Real(r) => hash_djb2_one_float(*r, HASH_SEED),
+        Value::String(text) => string::hash(text),
+        Value::Transform2D(_) | Value::Aabb(_) | Value::Basis(_) | Value::Transform(_) => {
+            let floats = value.floats().unwrap_or_default();
+            floats
+                .into_iter()
+                .fold(MATH_HASH_SEED, |h, f| hash_djb2_one_float(f.into(), h))
+        }
+        Value::Vector2(_)
+        | Value::Rect2(_)
+        | Value::Vector3(_)
+        | Value::Plane(_)
+        | Value::Quat(_)
+        | Value::Color(_) => {
+            let floats = value.floats().unwrap_or_default();
+            floats
+                .into_iter()
+                .fold(HASH_SEED, |h, f| hash_djb2_one_float(f.into(), h))
+        }
+        Value::NodePath(path) => path.as_ref().map_or(0, |path| path.hash()),
+        Value::Rid(rid) => hash_djb2_one_64(*rid as u64),
+        Value::Object(obj) => hash_djb2_one_64(obj.0 as u64),
+        Value::Dictionary(dict) => unsafe { dict.hash() },
+        Value::Array(arr) => unsafe { arr.hash() },
+    }
+}
+
+fn hash_djb2_one_64(value: u64) -> u32 {
+    let prev = u64::from(HASH_SEED);
+    (prev << 5).wrapping_add(prev).wrapping_add(value) as u32
+}
+
+/// Chains the hash of a variant into `prev`.
+pub(super) fn hash_into(value: &Value, prev: u32) -> u32 {
+    hash_djb2_one_32(hash(value), prev)
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Formatting
+
+/// Formats a floating point number like `String::num`, with the precision of the type.
+pub(super) fn num(value: f64, digits: i32) -> String {
+    if value.is_nan() {
+        return "nan".into();
+    }
+    if value.is_infinite() {
+        return if value < 0.0 { "-inf" } else { "inf" }.into();
+    }
+
+    let mut decimals = digits;
+    if value.abs() > 10.0 {
+        decimals -= value.abs().log10().floor() as i32;
+    }
+
+    let s = format!("{:.*}", decimals.max(0) as usize, value);
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        s
+    }
+}
+
+/// Formats a 64-bit real.
+pub(super) fn real(value: f64) -> String {
+    num(value, 14)
+}
+
+/// Formats a component of a math type.
+fn float(value: f32) -> String {
+    num(value.into(), 6)
+}
+
+fn fmt_vector2(v: &Vector2) -> String {
+    format!("({}, {})", float(v.x), float(v.y))
+}
+
+fn fmt_vector3(v: &Vector3) -> String {
+    format!("({}, {}, {})", float(v.x), float(v.y), float(v.z))
+}
+
+/// Converts a value to a string like `Variant::operator String`.
+pub(super) fn stringify(value: &Value) -> String {
+    stringify_nested(value, &mut Vec::new())
+}
+
+fn stringify_nested(value: &Value, stack: &mut Vec<*const ()>) -> String {
+    match value {
+        Value::Nil => "Null".into(),
+        Value::Bool(b) => if *b { "True" } else { "False" }.into(),
+        Value::Int(i) => i.to_string(),
+        Value::Real(r) => real(*r),
+        Value::String(text) => string::decode(text),
+        Value::Vector2(v) => fmt_vector2(v),
+        Value::Rect2(r) => format!("{}, {}", fmt_vector2(&r.position), fmt_vector2(&r.size)),
+        Value::Vector3(v) => fmt_vector3(v),
+        Value::Transform2D(t) => format!(
+            "({}, {}, {})",
+            fmt_vector2(&t.a),
+            fmt_vector2(&t.b),
+            fmt_vector2(&t.origin)
+        ),
+        Value::Plane(p) => format!("{}, {}", fmt_vector3(&p.normal), float(p.d)),
+        Value::Quat(q) => format!(
+            "{}, {}, {}, {}",
+            float(q.x),
+            float(q.y),
+            float(q.z),
+            float(q.w)
+        ),
+        Value::Aabb(aabb) => format!(
+            "{} - {}",
+            fmt_vector3(&aabb.position),
+            fmt_vector3(&aabb.size)
+        ),
+        Value::Basis(b) => format!(
+            "({}, {}, {})",
+            fmt_vector3(&b.elements[0]),
+            fmt_vector3(&b.elements[1]),
+            fmt_vector3(&b.elements[2])
+        ),
+        Value::Transform(t) => {
+            let e = &t.basis.elements;
+            let columns = [
+                [e[0].x, e[1].x, e[2].x],
+                [e[0].y, e[1].y, e[2].y],
+                [e[0].z, e[1].z, e[2].z],
+            ];
+            let basis: Vec<String> = columns.iter().flatten().map(|&f| float(f)).collect();
+            format!("{} - {}", basis.join(", "), fmt_vector3(&t.origin))
+        }
+        Value::Color(c) => format!(
+            "{}, {}, {}, {}",
+            float(c.r),
+            float(c.g),
+            float(c.b),
+            float(c.a)
+        ),
+        Value::NodePath(path) => {
+            string::decode(&path.as_ref().map(|path| path.to_text()).unwrap_or_default())
+        }
+        Value::Rid(_) => "[RID]".into(),
+        Value::Object(obj) => if obj.0.is_null() {
+            "[Object:null]"
+        } else {
+            "[Object]"
+        }
+        .into(),
+        Value::Dictionary(dict) => {
+            let id = Arc::as_ptr(dict) as *const ();
+            if stack.contains(&id) {
+                return "{...}".into();
+            }
+
+            stack.push(id);
+            let mut pairs: Vec<(String, String)> = unsafe { dict.entries() }
+                .map(|(key, value)| {
+                    (
+                        stringify_nested(key.get(), stack),
+                        stringify_nested(value.get(), stack),
+                    )
+                })
+                .collect();
+            stack.pop();
+
+            pairs.sort_by(|a, b| a.0.cmp(&b.0));
+            let pairs: Vec<String> = pairs.into_iter().map(|(k, v)| format!("{k}:{v}")).collect();
+            format!("{{{}}}", pairs.join(", "))
+        }
+        Value::Array(arr) => {
+            let id = Arc::as_ptr(arr) as *const ();
+            if stack.contains(&id) {
+                return "[...]".into();
+            }
+
+            stack.push(id);
+            let items: Vec<String> = unsafe { arr.items() }
+                .iter()
+                .map(|item| stringify_nested(item.get(), stack))
+                .collect();
+            stack.pop();
+
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Operators
+
+/// Evaluates an operator, returning `false` if it is not valid for the operands.
+pub(super) fn evaluate_bool(op: godot_variant_operator, a: &Value, b: &Value) -> bool {
+    matches!(evaluate(op, a, b), Some(Value::Bool(true)))
+}
+
+/// Evaluates an operator like `Variant::evaluate`. Returns `None` if the operator is not valid
+/// for the operands.
+pub(super) fn evaluate(op: godot_variant_operator, a: &Value, b: &Value) -> Option<Value> {
+    use Value as V;
+
+    let value = match op {
+        OP_EQUAL => V::Bool(equal(a, b)?),
+        OP_NOT_EQUAL => V::Bool(!equal(a, b)?),
+        OP_LESS | OP_LESS_EQUAL | OP_GREATER | OP_GREATER_EQUAL => V::Bool(compare(op, a, b)?),
+        OP_ADD | OP_SUBTRACT => {
+            let sub = op == OP_SUBTRACT;
+            match (a, b) {
+                (V::String(a), V::String(b)) if !sub => V::String([&a[..], b].concat()),
+                (V::Array(a), V::Array(b)) if !sub => {
+                    let items = unsafe { [a.items(), b.items()].concat() };
+                    V::Array(Arc::new(ArrayData::from_items(items)))
+                }
+                _ => arithmetic(a, b, if sub { Arith::Sub } else { Arith::Add })?,
+            }
+        }
+        OP_MULTIPLY => multiply(a, b)?,
+        OP_DIVIDE => divide(a, b)?,
+        OP_MODULE => match (a, b) {
+            (V::Int(_), V::Int(0)) => return None,
+            (V::Int(a), V::Int(b)) => V::Int(a.wrapping_rem(*b)),
+            (V::String(_), _) => unsupported("String::sprintf"),
+            _ => return None,
+        },
+        OP_NEGATE => match a {
+            V::Int(i) => V::Int(i.wrapping_neg()),
+            V::Real(r) => V::Real(-r),
+            V::Vector2(v) => V::Vector2(-*v),
+            V::Vector3(v) => V::Vector3(-*v),
+            V::Quat(q) => V::Quat(-*q),
+            V::Plane(p) => V::Plane(Plane::new(-p.normal, -p.d)),
+            V::Color(c) => V::Color(Color::from_rgba(1.0 - c.r, 1.0 - c.g, 1.0 - c.b, 1.0 - c.a)),
+            _ => return None,
+        },
+        OP_POSITIVE => match a {
+            V::Int(_)
+            | V::Real(_)
+            | V::Vector2(_)
+            | V::Vector3(_)
+            | V::Quat(_)
+            | V::Plane(_)
+            | V::Color(_) => a.clone(),
+            _ => return None,
+        },
+        OP_STRING_CONCAT => V::String(string::encode(&(stringify(a) + &stringify(b)))),
+        OP_SHIFT_LEFT | OP_SHIFT_RIGHT | OP_BIT_AND | OP_BIT_OR | OP_BIT_XOR => {
+            let (&V::Int(a), &V::Int(b)) = (a, b) else {
+                return None;
+            };
+            V::Int(match op {
+                OP_SHIFT_LEFT | OP_SHIFT_RIGHT if !(0..64).contains(&b) => return None,
+                OP_SHIFT_LEFT => a << b,
+                OP_SHIFT_RIGHT => a >> b,
+                OP_BIT_AND => a & b,
+                OP_BIT_OR => a | b,
+                _ => a ^ b,
+            })
+        }
+        OP_BIT_NEGATE => match a {
+            V::Int(i) => V::Int(!i),
+            _ => return None,
+        },
+        OP_AND => V::Bool(!a.is_zero() && !b.is_zero()),
+        OP_OR => V::Bool(!a.is_zero() || !b.is_zero()),
+        OP_XOR => V::Bool(a.is_zero() != b.is_zero()),
+        OP_NOT => V::Bool(a.is_zero()),
+        OP_IN => V::Bool(match b {
+            V::String(haystack) => match a {
+                V::String(needle) => string::find(haystack, needle, 0).is_some(),
+                _ => return None,
+            },
+            V::Array(arr) => unsafe { arr.items() }
+                .iter()
+                .any(|item| strict_eq(item.get(), a)),
+            V::Dictionary(dict) => unsafe { dict.get(a) }.is_some(),
+            _ => return None,
+        }),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+#[cold]
+fn unsupported(function: &str) -> ! {
+    eprintln!("`{function}` is not available without a running Godot engine");
+    std::process::abort()
+}
+
+fn equal(a: &Value, b: &Value) -> Option<bool> {
+    use Value as V;
+
+    Some(match (a, b) {
+        (V::Nil, V::Nil) => true,
+        (V::Nil, V::Object(obj)) | (V::Object(obj), V::Nil) => obj.0.is_null(),
+        (V::Nil, _) | (_, V::Nil) => false,
+        (V::Bool(a), V::Bool(b)) => a == b,
+        (V::Int(a), V::Int(b)) => a == b,
+        (V::Int(a), V::Real(b)) => *a as f64 == *b,
+        (V::Real(a), V::Int(b)) => *a == *b as f64,
+        (V::Real(a), V::Real(b)) => a == b,
+        (V::String(_) | V::NodePath(_), V::String(_) | V::NodePath(_)) => {
+            path_or_string(a) == path_or_string(b)
+        }
+        (V::Rid(a), V::Rid(b)) => a == b,
+        (V::Object(a), V::Object(b)) => a == b,
+        (V::Dictionary(a), V::Dictionary(b)) => Arc::ptr_eq(a, b),
+        (V::Array(a), V::Array(b)) => {
+            let (a, b) = unsafe { (a.items(), b.items()) };
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| strict_eq(a.get(), b.get()))
+        }
+        _ if a.type_id() == b.type_id() => a.floats()? == b.floats()?,
+        _ => return None,
+    })
+}
+
+/// Returns the text of strings and node paths, which can be compared with each other.
+fn path_or_string(value: &Value) -> Text {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::NodePath(path) => path.as_ref().map(|path| path.to_text()).unwrap_or_default(),
+        _ => Text::new(),
+    }
+}
+
+fn compare(op: godot_variant_operator, a: &Value, b: &Value) -> Option<bool> {
+    use Value as V;
+
+    fn cmp<T: PartialOrd>(op: godot_variant_operator, a: T, b: T) -> bool {
+        match op {
+            OP_LESS => a < b,
+            OP_LESS_EQUAL => a <= b,
+            OP_GREATER => a > b,
+            _ => a >= b,
+        }
+    }
+
+    /// Lexicographic comparison of vectors, deciding by the first component that differs.
+    fn lex(op: godot_variant_operator, a: &[f32], b: &[f32]) -> bool {
+        let last = a.len() - 1;
+        let i = (0..last).find(|&i| a[i] != b[i]).unwrap_or(last);
+        cmp(op, a[i], b[i])
+    }
+
+    Some(match (a, b) {
+        (V::Int(a), V::Int(b)) => cmp(op, a, b),
+        (V::Int(a), V::Real(b)) => cmp(op, *a as f64, *b),
+        (V::Real(a), V::Int(b)) => cmp(op, *a, *b as f64),
+        (V::Real(a), V::Real(b)) => cmp(op, a, b),
+        (V::String(_), V::String(_) | V::NodePath(_)) => {
+            cmp(op, path_or_string(a), path_or_string(b))
+        }
+        (V::Vector2(_), V::Vector2(_)) | (V::Vector3(_), V::Vector3(_)) => {
+            lex(op, &a.floats()?, &b.floats()?)
+        }
+        (V::Rid(a), V::Rid(b)) => cmp(op, a, b),
+        (V::Object(a), V::Object(b)) => cmp(op, a, b),
+        (V::Bool(a), V::Bool(b)) => match op {
+            OP_LESS => !a && *b,
+            OP_GREATER => *a && !b,
+            _ => return None,
+        },
+        (V::Array(a), V::Array(b)) => {
+            let (a, b) = unsafe { (a.items(), b.items()) };
+            // Like the engine, which doesn't implement a lexicographic order.
+            match op {
+                OP_LESS => {
+                    b.len() >= a.len()
+                        && a.iter().zip(b).any(|(a, b)| !strict_less(a.get(), b.get()))
+                }
+                OP_GREATER => {
+                    b.len() <= a.len()
+                        && !a.iter().zip(b).any(|(a, b)| strict_less(a.get(), b.get()))
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+#[derive(Copy, Clone)]
+enum Arith {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Arith {
+    fn f32(self, a: f32, b: f32) -> f32 {
+        match self {
+            Arith::Add => a + b,
+            Arith::Sub => a - b,
+            Arith::Mul => a * b,
+            Arith::Div => a / b,
+        }
+    }
+
+    fn f64(self, a: f64, b: f64) -> f64 {
+        match self {
+            Arith::Add => a + b,
+            Arith::Sub => a - b,
+            Arith::Mul => a * b,
+            Arith::Div => a / b,
+        }
+    }
+
+    fn i64(self, a: i64, b: i64) -> i64 {
+        match self {
+            Arith::Add => a.wrapping_add(b),
+            Arith::Sub => a.wrapping_sub(b),
+            Arith::Mul => a.wrapping_mul(b),
+            Arith::Div => a.wrapping_div(b),
+        }
+    }
+}
+
+/// Returns the value of a number operand.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(i) => Some(*i as f64),
+        Value::Real(r) => Some(*r),
+        _ => None,
+    }
+}
+
+/// Componentwise arithmetic on numbers, vectors, quaternions and colors.
+fn arithmetic(a: &Value, b: &Value, op: Arith) -> Option<Value> {
+    use Value as V;
+
+    let v2 = |a: Vector2, b: Vector2| Vector2::new(op.f32(a.x, b.x), op.f32(a.y, b.y));
+    let v3 =
+        |a: Vector3, b: Vector3| Vector3::new(op.f32(a.x, b.x), op.f32(a.y, b.y), op.f32(a.z, b.z));
+    let quat = |a: Quat, b: Quat| {
+        Quat::new(
+            op.f32(a.x, b.x),
+            op.f32(a.y, b.y),
+            op.f32(a.z, b.z),
+            op.f32(a.w, b.w),
+        )
+    };
+    let color = |a: Color, b: Color| {
+        Color::from_rgba(
+            op.f32(a.r, b.r),
+            op.f32(a.g, b.g),
+            op.f32(a.b, b.b),
+            op.f32(a.a, b.a),
+        )
+    };
+
+    Some(match (a, b) {
+        (V::Int(a), V::Int(b)) => V::Int(op.i64(*a, *b)),
+        (V::Int(_) | V::Real(_), V::Int(_) | V::Real(_)) => V::Real(op.f64(number(a)?, number(b)?)),
+        (V::Vector2(a), V::Vector2(b)) => V::Vector2(v2(*a, *b)),
+        (V::Vector3(a), V::Vector3(b)) => V::Vector3(v3(*a, *b)),
+        (V::Quat(a), V::Quat(b)) => V::Quat(quat(*a, *b)),
+        (V::Color(a), V::Color(b)) => V::Color(color(*a, *b)),
+        _ => return None,
+    })
+}
+
+/// Scales a vector, quaternion or color by a number.
+fn scale(value: &Value, s: f32, op: Arith) -> Option<Value> {
+    use Value as V;
+
+    Some(match value {
+        V::Vector2(v) => V::Vector2(Vector2::new(op.f32(v.x, s), op.f32(v.y, s))),
+        V::Vector3(v) => V::Vector3(Vector3::new(op.f32(v.x, s), op.f32(v.y, s), op.f32(v.z, s))),
+        V::Quat(q) => V::Quat(Quat::new(
+            op.f32(q.x, s),
+            op.f32(q.y, s),
+            op.f32(q.z, s),
+            op.f32(q.w, s),
+        )),
+        V::Color(c) => V::Color(Color::from_rgba(
+            op.f32(c.r, s),
+            op.f32(c.g, s),
+            op.f32(c.b, s),
+            op.f32(c.a, s),
+        )),
+        _ => return None,
+    })
+}
+
+fn multiply(a: &Value, b: &Value) -> Option<Value> {
+    use Value as V;
+
+    Some(match (a, b) {
+        (V::Quat(a), V::Quat(b)) => V::Quat(*a * *b),
+        (V::Quat(q), V::Vector3(v)) => V::Vector3(*q * *v),
+        (V::Basis(a), V::Basis(b)) => V::Basis(*a * *b),
+        (V::Basis(basis), V::Vector3(v)) => V::Vector3(basis.xform(*v)),
+        (V::Transform(a), V::Transform(b)) => V::Transform(*a * *b),
+        (V::Transform(t), V::Vector3(v)) => V::Vector3(t.xform(*v)),
+        (V::Transform2D(a), V::Transform2D(b)) => V::Transform2D(*a * *b),
+        (V::Transform2D(t), V::Vector2(v)) => V::Vector2(t.xform(*v)),
+        (V::Vector2(_) | V::Vector3(_) | V::Color(_), V::Int(_) | V::Real(_)) => {
+            scale(a, number(b)? as f32, Arith::Mul)?
+        }
+        (V::Int(_) | V::Real(_), V::Vector2(_) | V::Vector3(_) | V::Quat(_) | V::Color(_)) => {
+            scale(b, number(a)? as f32, Arith::Mul)?
+        }
+        (V::Quat(_), V::Int(_) | V::Real(_)) => scale(a, number(b)? as f32, Arith::Mul)?,
+        (V::Quat(_), _) => return None,
+        _ => arithmetic(a, b, Arith::Mul)?,
+    })
+}
+
+fn divide(a: &Value, b: &Value) -> Option<Value> {
+    use Value as V;
+
+    Some(match (a, b) {
+        (V::Int(_) | V::Real(_), V::Int(_) | V::Real(_)) if number(b)? == 0.0 => return None,
+        (V::Vector2(_) | V::Vector3(_) | V::Quat(_) | V::Color(_), V::Int(_) | V::Real(_)) => {
+            scale(a, number(b)? as f32, Arith::Div)?
+        }
+        (V::Quat(_), _) => return None,
+        _ => arithmetic(a, b, Arith::Div)?,
+    })
+}
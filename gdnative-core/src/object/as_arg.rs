@@ -13,10 +13,25 @@ use crate::object::{GodotObject, Instance, Null, Ref, SubClass, TInstance, TRef}
 ///   method.
 /// - Owned and borrowed `Shared` references, including temporary ones (`TRef`).
 ///
+/// The same forms of `Instance` and `TInstance` are accepted where their base class, or one of
+/// its base classes, is expected. Any of these can be wrapped in an `Option`, with `None` being
+/// passed as a null reference.
+///
+/// | Argument                             | Passed as `AsArg<U>` if               |
+/// |--------------------------------------|---------------------------------------|
+/// | `Ref<T, Unique>`, `Ref<T, Shared>`   | `T: SubClass<U>`                      |
+/// | `&Ref<T, Shared>`                    | `T: SubClass<U>`                      |
+/// | `TRef<T, Shared>`, `&TRef<T, Shared>`| `T: SubClass<U>`                      |
+/// | `Instance<T, Unique>`, `Instance<T, Shared>`, `&Instance<T, Shared>` | `T::Base: SubClass<U>` |
+/// | `TInstance<T, Shared>`, `&TInstance<T, Shared>` | `T::Base: SubClass<U>`     |
+/// | `Option<A>`                          | `A: AsArg<U>`                         |
+/// | `Null<U>`                            | always                                |
+///
 /// It's unsound to pass `ThreadLocal` references to the engine because there is no guarantee
 /// that the reference will stay on the same thread.
 ///
 /// To explicitly pass a null reference to the engine, use `Null::null` or `GodotObject::null`.
+/// A plain `None` can't be passed, since the type of reference can't be inferred from it.
 pub trait AsArg<T>: private::Sealed {
     #[doc(hidden)]
     fn as_arg_ptr(&self) -> *mut sys::godot_object;
@@ -48,14 +63,19 @@ impl<T> private::Sealed for Null<T> {}
 
 // Temporary references (shared ownership)
 impl<'a, T: GodotObject> private::Sealed for TRef<'a, T, Shared> {}
+impl<'a, 'r, T: GodotObject> private::Sealed for &'r TRef<'a, T, Shared> {}
 impl<'a, T: GodotObject> private::Sealed for &'a Ref<T, Shared> {}
 impl<'a, T: NativeClass> private::Sealed for TInstance<'a, T, Shared> {}
+impl<'a, 'r, T: NativeClass> private::Sealed for &'r TInstance<'a, T, Shared> {}
 impl<'a, T: NativeClass> private::Sealed for &'a Instance<T, Shared> {}
 
 // Persistent references (any ownership)
 impl<T: GodotObject, Own: Ownership> private::Sealed for Ref<T, Own> {}
 impl<T: NativeClass, Own: Ownership> private::Sealed for Instance<T, Own> {}
 
+// Nullable references
+impl<A: private::Sealed> private::Sealed for Option<A> {}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 // Null
 
@@ -88,6 +108,21 @@ impl<'a, T: GodotObject> AsVariant for TRef<'a, T, Shared> {
     type Target = T;
 }
 
+impl<'a, 'r, T, U> AsArg<U> for &'r TRef<'a, T, Shared>
+where
+    T: GodotObject + SubClass<U>,
+    U: GodotObject,
+{
+    #[inline]
+    fn as_arg_ptr(&self) -> *mut sys::godot_object {
+        self.as_ptr()
+    }
+}
+
+impl<'a, 'r, T: GodotObject> AsVariant for &'r TRef<'a, T, Shared> {
+    type Target = T;
+}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 // Ref
 
@@ -152,10 +187,39 @@ where
     }
 }
 
+impl<'a, 'r, T, U> AsArg<U> for &'r TInstance<'a, T, Shared>
+where
+    T: NativeClass,
+    T::Base: GodotObject + SubClass<U>,
+    T::UserData: Map,
+    U: GodotObject,
+{
+    #[inline]
+    fn as_arg_ptr(&self) -> *mut sys::godot_object {
+        self.as_base_ptr()
+    }
+}
+
+impl<'a, T> AsVariant for TInstance<'a, T, Shared>
+where
+    T: NativeClass,
+    T::UserData: Map,
+{
+    type Target = T::Base;
+}
+
+impl<'a, 'r, T> AsVariant for &'r TInstance<'a, T, Shared>
+where
+    T: NativeClass,
+    T::UserData: Map,
+{
+    type Target = T::Base;
+}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 // Instance
 
-impl<T, U, Own: Ownership> AsArg<U> for Instance<T, Own>
+impl<T, U> AsArg<U> for Instance<T, Shared>
 where
     T: NativeClass,
     T::Base: GodotObject + SubClass<U>,
@@ -168,6 +232,35 @@ where
     }
 }
 
+impl<T, U> AsArg<U> for Instance<T, Unique>
+where
+    T: NativeClass,
+    T::Base: GodotObject + SubClass<U>,
+    T::UserData: Map,
+    U: GodotObject,
+{
+    #[inline]
+    fn as_arg_ptr(&self) -> *mut sys::godot_object {
+        self.as_base_ptr()
+    }
+}
+
+impl<T> AsVariant for Instance<T, Shared>
+where
+    T: NativeClass,
+    T::UserData: Map,
+{
+    type Target = T::Base;
+}
+
+impl<T> AsVariant for Instance<T, Unique>
+where
+    T: NativeClass,
+    T::UserData: Map,
+{
+    type Target = T::Base;
+}
+
 impl<'a, T, U> AsArg<U> for &'a Instance<T, Shared>
 where
     T: NativeClass,
@@ -180,3 +273,31 @@ where
         self.as_base_ptr()
     }
 }
+
+impl<'a, T> AsVariant for &'a Instance<T, Shared>
+where
+    T: NativeClass,
+    T::UserData: Map,
+{
+    type Target = T::Base;
+}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+// Option
+
+impl<A, U> AsArg<U> for Option<A>
+where
+    A: AsArg<U>,
+{
+    #[inline]
+    fn as_arg_ptr(&self) -> *mut sys::godot_object {
+        match self {
+            Some(arg) => arg.as_arg_ptr(),
+            None => std::ptr::null_mut(),
+        }
+    }
+}
+
+impl<A: AsVariant> AsVariant for Option<A> {
+    type Target = A::Target;
+}
@@ -16,6 +16,10 @@ pub trait MemorySpec: Sized {
     #[doc(hidden)]
     type PtrWrapper: PtrWrapper;
 
+    /// Whether objects with this memory policy are reference-counted.
+    #[doc(hidden)]
+    const IS_REF_COUNTED: bool;
+
     #[doc(hidden)]
     unsafe fn impl_from_maybe_ref_counted<T: GodotObject<Memory = Self>>(
         ptr: NonNull<sys::godot_object>,
@@ -50,6 +54,7 @@ pub trait MemorySpec: Sized {
 
 impl MemorySpec for ManuallyManaged {
     type PtrWrapper = Forget;
+    const IS_REF_COUNTED: bool = false;
 
     #[inline(always)]
     unsafe fn impl_from_maybe_ref_counted<T: GodotObject<Memory = Self>>(
@@ -100,6 +105,7 @@ impl MemorySpec for ManuallyManaged {
 
 impl MemorySpec for RefCounted {
     type PtrWrapper = UnRef;
+    const IS_REF_COUNTED: bool = true;
 
     #[inline(always)]
     unsafe fn impl_from_maybe_ref_counted<T: GodotObject<Memory = Self>>(
@@ -0,0 +1,76 @@
+use std::fmt::{self, Display};
+
+/// Compile-time ID of a Godot class, derived from a hash of its name.
+///
+/// Every engine class has its ID as the [`GodotObject::CLASS_ID`](super::GodotObject::CLASS_ID)
+/// constant. Since IDs are plain integers, they can be used as keys in data tables, or as
+/// patterns in `match` expressions:
+///
+/// ```no_run
+/// use gdnative::object::ClassId;
+/// use gdnative::prelude::*;
+///
+/// fn describe(node: &Node) -> &'static str {
+///     match ClassId::from_name(&node.get_class().to_string()) {
+///         Spatial::CLASS_ID => "3D node",
+///         Node2D::CLASS_ID => "2D node",
+///         Control::CLASS_ID => "UI node",
+///         _ => "other node",
+///     }
+/// }
+/// ```
+///
+/// Note that IDs identify exact classes. Use [`RawObject::is_class`](super::RawObject::is_class)
+/// or `cast` to check for subclasses as well. Since IDs are hashes, different names could in
+/// theory have the same ID, so compare class names where a collision would be unsound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClassId(u64);
+
+impl ClassId {
+    /// Returns the ID of the class named `name`.
+    #[inline]
+    pub const fn from_name(name: &str) -> Self {
+        // 64-bit FNV-1a
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        let bytes = name.as_bytes();
+        let mut hash = OFFSET_BASIS;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(PRIME);
+            i += 1;
+        }
+
+        ClassId(hash)
+    }
+
+    /// Returns the raw ID.
+    #[inline]
+    pub const fn to_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for ClassId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#018x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_stable_and_distinct() {
+        const NODE: ClassId = ClassId::from_name("Node");
+
+        assert_eq!(NODE, ClassId::from_name("Node"));
+        assert_ne!(NODE, ClassId::from_name("Node2D"));
+        assert_ne!(NODE, ClassId::from_name("node"));
+        assert_eq!(0xcbf2_9ce4_8422_2325, ClassId::from_name("").to_u64());
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt::{self, Debug};
+use std::ops::Deref;
+
+use crate::object::memory::ManuallyManaged;
+use crate::object::ownership::{Shared, Unique};
+use crate::object::{GodotObject, Ref, TRef};
+
+/// A `Unique` reference to a manually-managed object that is freed when the guard is dropped,
+/// unless it is [released](FreeGuard::release) first.
+///
+/// Newly created nodes are leaked if a function returns early, or panics, before they are added
+/// to the scene tree. Guarding them until ownership is handed over prevents this:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+///
+/// fn add_label(parent: &Node, text: &str) -> Result<(), String> {
+///     let label = Label::new().into_free_guard();
+///
+///     if text.is_empty() {
+///         // `label` is freed here.
+///         return Err("text should not be empty".into());
+///     }
+///
+///     label.set_text(text);
+///     parent.add_child(label.release(), false);
+///     Ok(())
+/// }
+/// ```
+///
+/// Guards can be obtained with [`Ref::into_free_guard`], or with [`FreeGuard::new`].
+pub struct FreeGuard<T: GodotObject<Memory = ManuallyManaged>> {
+    // Always `Some`, until released or dropped.
+    obj: Option<Ref<T, Unique>>,
+}
+
+impl<T: GodotObject<Memory = ManuallyManaged>> FreeGuard<T> {
+    /// Guards `obj`, freeing it when the guard is dropped.
+    #[inline]
+    pub fn new(obj: Ref<T, Unique>) -> Self {
+        FreeGuard { obj: Some(obj) }
+    }
+
+    /// Returns a reference to the guarded object.
+    #[inline]
+    pub fn as_ref(&self) -> TRef<'_, T, Unique> {
+        self.get().as_ref()
+    }
+
+    /// Returns the object without freeing it, for example to hand it over to the scene tree with
+    /// `add_child`.
+    #[inline]
+    pub fn release(mut self) -> Ref<T, Unique> {
+        self.obj.take().expect("object should not be released yet")
+    }
+
+    /// Returns the object as a `Shared` reference without freeing it.
+    #[inline]
+    pub fn release_shared(self) -> Ref<T, Shared> {
+        self.release().into_shared()
+    }
+
+    fn get(&self) -> &Ref<T, Unique> {
+        self.obj
+            .as_ref()
+            .expect("object should not be released yet")
+    }
+}
+
+impl<T: GodotObject<Memory = ManuallyManaged>> Deref for FreeGuard<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: GodotObject<Memory = ManuallyManaged>> Drop for FreeGuard<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(obj) = self.obj.take() {
+            obj.free();
+        }
+    }
+}
+
+impl<T: GodotObject<Memory = ManuallyManaged>> Debug for FreeGuard<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FreeGuard").field(self.get()).finish()
+    }
+}
+
+impl<T: GodotObject<Memory = ManuallyManaged>> From<Ref<T, Unique>> for FreeGuard<T> {
+    #[inline]
+    fn from(obj: Ref<T, Unique>) -> Self {
+        FreeGuard::new(obj)
+    }
+}
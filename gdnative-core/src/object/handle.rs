@@ -0,0 +1,237 @@
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::private::get_api;
+use crate::sys;
+
+use super::ownership::{Ownership, Shared};
+use super::{GodotObject, InstanceId, RawObject, Ref, TRef};
+
+/// Weak reference to a Godot object that stores only its [`InstanceId`].
+///
+/// Unlike `Ref`, a handle never dangles: every access looks the ID up in the engine's object
+/// database and checks the class, so an object that has been freed in the meantime is reported
+/// as missing instead of causing undefined behavior. A handle doesn't keep reference-counted
+/// objects alive either. This makes it a good fit for long-lived cross-references between game
+/// entities, e.g. the target of a homing missile, that may be freed at any time.
+///
+/// Since instance IDs are never reused, a handle is invalidated for good the first time its
+/// object is found missing, and later accesses return `None` without a lookup.
+///
+/// What happens when the object is gone is controlled by a [`HandlePolicy`]:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+/// use gdnative::object::{HandlePolicy, ObjectHandle};
+///
+/// fn follow(target: &ObjectHandle<Node2D>, owner: &Node2D) {
+///     // SAFETY: `target` is only used on the main thread
+///     if let Some(target) = unsafe { target.get() } {
+///         owner.look_at(target.global_position());
+///     }
+/// }
+///
+/// fn track(target: TRef<Node2D>) -> ObjectHandle<Node2D> {
+///     ObjectHandle::from(target).with_policy(HandlePolicy::Silent)
+/// }
+/// ```
+pub struct ObjectHandle<T: GodotObject> {
+    id: InstanceId,
+    policy: HandlePolicy,
+    invalid: AtomicBool,
+    reported: AtomicBool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Behavior of an [`ObjectHandle`] when its object is no longer alive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum HandlePolicy {
+    /// Panic on access.
+    Panic,
+    /// Print an error on the first failed access through the handle, and return `None`.
+    #[default]
+    LogOnce,
+    /// Return `None` without reporting anything.
+    Silent,
+}
+
+impl<T: GodotObject> ObjectHandle<T> {
+    /// Creates a handle to `obj`, using the default policy, [`HandlePolicy::LogOnce`].
+    #[inline]
+    pub fn new(obj: &T) -> Self {
+        Self::from_instance_id(obj.as_raw().instance_id())
+    }
+
+    /// Creates a handle from an instance ID previously returned by `Object::get_instance_id`.
+    ///
+    /// The ID is not checked until the handle is accessed.
+    #[inline]
+    pub fn from_instance_id(id: impl Into<InstanceId>) -> Self {
+        ObjectHandle {
+            id: id.into(),
+            policy: HandlePolicy::default(),
+            invalid: AtomicBool::new(false),
+            reported: AtomicBool::new(false),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the policy used when the object is no longer alive.
+    #[inline]
+    pub fn with_policy(mut self, policy: HandlePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the instance ID of the object.
+    #[inline]
+    pub fn instance_id(&self) -> InstanceId {
+        self.id
+    }
+
+    /// Returns the policy used when the object is no longer alive.
+    #[inline]
+    pub fn policy(&self) -> HandlePolicy {
+        self.policy
+    }
+
+    /// Returns `true` if the object is still alive and of class `T`. This never panics or
+    /// reports errors, regardless of the policy.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.lookup().is_some()
+    }
+
+    /// Returns a reference to the object if it is still alive and of class `T`. Otherwise,
+    /// follows the policy of the handle and returns `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is no longer alive and the policy is [`HandlePolicy::Panic`].
+    ///
+    /// # Safety
+    ///
+    /// During the entirety of `'a`, the thread from which `get` is called must have exclusive
+    /// access to the underlying object. See `Ref::assume_safe` for more information.
+    #[inline]
+    pub unsafe fn get<'a>(&self) -> Option<TRef<'a, T, Shared>> {
+        match self.lookup() {
+            Some(raw) => Some(TRef::new(T::cast_ref(raw))),
+            None => {
+                self.report();
+                None
+            }
+        }
+    }
+
+    /// Returns a persistent reference to the object if it is still alive and of class `T`.
+    /// Otherwise, follows the policy of the handle and returns `None`.
+    ///
+    /// For reference-counted objects, the returned `Ref` keeps the object alive until it is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is no longer alive and the policy is [`HandlePolicy::Panic`].
+    ///
+    /// # Safety
+    ///
+    /// Handles can be sent to other threads, but checking whether the object is alive is not
+    /// synchronized with `free`. The object must not be freed on another thread while `upgrade`
+    /// is called, e.g. because objects of this class are only freed on the calling thread.
+    #[inline]
+    pub unsafe fn upgrade(&self) -> Option<Ref<T, Shared>> {
+        match self.lookup() {
+            Some(raw) => Some(Ref::from_sys(raw.sys())),
+            None => {
+                self.report();
+                None
+            }
+        }
+    }
+
+    fn lookup<'a>(&self) -> Option<&'a RawObject<T>> {
+        if self.invalid.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let raw = unsafe {
+            NonNull::new((get_api().godot_instance_from_id)(
+                self.id.to_i64() as sys::godot_int
+            ))
+            .and_then(|ptr| RawObject::try_from_sys_ref(ptr))
+        };
+
+        if raw.is_none() {
+            self.invalid.store(true, Ordering::Relaxed);
+        }
+        raw
+    }
+
+    fn report(&self) {
+        match self.policy {
+            HandlePolicy::Panic => panic!(
+                "object handle {} does not refer to a live `{}`",
+                self.id,
+                T::class_name(),
+            ),
+            HandlePolicy::LogOnce => {
+                if !self.reported.swap(true, Ordering::Relaxed) {
+                    godot_error!(
+                        "gdnative-core: object handle {} does not refer to a live `{}`",
+                        self.id,
+                        T::class_name(),
+                    );
+                }
+            }
+            HandlePolicy::Silent => {}
+        }
+    }
+}
+
+impl<'a, T: GodotObject, Own: Ownership> From<TRef<'a, T, Own>> for ObjectHandle<T> {
+    #[inline]
+    fn from(obj: TRef<'a, T, Own>) -> Self {
+        ObjectHandle::new(obj.as_ref())
+    }
+}
+
+impl<T: GodotObject> Clone for ObjectHandle<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ObjectHandle {
+            id: self.id,
+            policy: self.policy,
+            invalid: AtomicBool::new(self.invalid.load(Ordering::Relaxed)),
+            reported: AtomicBool::new(self.reported.load(Ordering::Relaxed)),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: GodotObject> PartialEq for ObjectHandle<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: GodotObject> Eq for ObjectHandle<T> {}
+
+impl<T: GodotObject> Hash for ObjectHandle<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<T: GodotObject> Debug for ObjectHandle<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ObjectHandle<{}>({})", T::class_name(), self.id)
+    }
+}
@@ -1,9 +1,12 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::ptr::NonNull;
 
 use crate::core_types::{
     FromVariant, FromVariantError, GodotString, OwnedToVariant, ToVariant, Variant,
 };
+use crate::diagnostics::{format_stack, CallFrame};
+use crate::export::mixin_state::InstanceStorage;
 use crate::export::user_data::{Map, MapMut, MapOwned, UserData};
 use crate::export::{class_registry, emplace, NativeClass};
 use crate::object::bounds::{
@@ -39,6 +42,52 @@ pub struct TInstance<'a, T: NativeClass, Own: Ownership = Shared> {
     script: T::UserData,
 }
 
+/// Error returned by [`TRef::with`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum WithInstanceError {
+    /// The object is not an instance of the expected class.
+    NotAnInstance {
+        /// Name of the expected `NativeClass`.
+        expected: Cow<'static, str>,
+        /// Name of the engine class of the object.
+        found: String,
+    },
+    /// The instance couldn't be borrowed, usually because one of its methods is running.
+    Borrow {
+        /// Name of the `NativeClass`.
+        class: Cow<'static, str>,
+        /// The error returned by the user data wrapper.
+        error: String,
+        /// The exported methods running on the current thread when the borrow failed.
+        call_stack: Vec<CallFrame>,
+    },
+}
+
+impl std::fmt::Display for WithInstanceError {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WithInstanceError::NotAnInstance { expected, found } => {
+                write!(f, "expected an instance of {expected}, found {found}")
+            }
+            WithInstanceError::Borrow {
+                class,
+                error,
+                call_stack,
+            } => {
+                write!(f, "failed to borrow instance of {class}: {error}")?;
+                if !call_stack.is_empty() {
+                    write!(f, "\nscript call stack:\n{}", format_stack(call_stack))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for WithInstanceError {}
+
 impl<T: NativeClass> Instance<T, Unique> {
     /// Creates a `T::Base` with the script `T` attached. Both `T::Base` and `T` must have zero
     /// argument constructors.
@@ -105,113 +154,272 @@ impl<T: NativeClass> Instance<T, Unique> {
         Self::maybe_emplace(Some(script))
     }
 
+    /// Attaches the script `T` to an existing `base` object. `T` must have a zero-argument
+    /// constructor.
+    ///
+    /// This allows configuring the base object before the script is constructed, e.g. to set
+    /// its name or to use a node instanced from a scene:
+    ///
+    /// ```ignore
+    /// let node = Node2D::new();
+    /// node.set_name("Player");
+    /// node.set_position(Vector2::new(100.0, 50.0));
+    ///
+    /// let player = Instance::<Player, _>::new_with_base(node);
+    /// ```
+    ///
+    /// Any script already attached to `base` is replaced. If `T::Base` is manually-managed,
+    /// then the resulting `Instance` must be passed to the engine or manually freed with
+    /// `Instance::free`. Otherwise, the base object will be leaked.
+    ///
+    /// Must be called after the library is initialized.
+    ///
+    /// # Panics
+    ///
+    /// If `T` isn't registered, or if its constructor fails.
+    #[inline]
+    pub fn new_with_base(base: Ref<T::Base, Unique>) -> Self {
+        Self::maybe_emplace_with_base(None, base)
+    }
+
+    /// Attaches a given instance of the script `T` to an existing `base` object. This combines
+    /// [`Instance::emplace`] and [`Instance::new_with_base`]:
+    ///
+    /// ```ignore
+    /// let node = Node2D::new();
+    /// node.set_name("Enemy");
+    ///
+    /// let enemy = Instance::emplace_with_base(Enemy::with_health(30), node);
+    /// ```
+    ///
+    /// Any script already attached to `base` is replaced. If `T::Base` is manually-managed,
+    /// then the resulting `Instance` must be passed to the engine or manually freed with
+    /// `Instance::free`. Otherwise, the base object will be leaked.
+    ///
+    /// Must be called after the library is initialized.
+    ///
+    /// # Panics
+    ///
+    /// If `T` isn't registered, or if its constructor fails.
+    #[inline]
+    pub fn emplace_with_base(script: T, base: Ref<T::Base, Unique>) -> Self {
+        Self::maybe_emplace_with_base(Some(script), base)
+    }
+
     fn maybe_emplace(script: Option<T>) -> Self
     where
         T::Base: Instanciable,
     {
         unsafe {
-            let gd_api = get_api();
-            let nativescript_methods = crate::private::NativeScriptMethodTable::get(gd_api);
-
-            assert_ne!(
-                std::ptr::null(),
-                nativescript_methods.set_class_name,
-                "NativeScript::set_class_name must be available"
-            );
-            assert_ne!(
-                std::ptr::null(),
-                nativescript_methods.set_library,
-                "NativeScript::set_library must be available"
-            );
-            assert_ne!(
-                std::ptr::null(),
-                nativescript_methods.new,
-                "NativeScript::new must be available"
-            );
-
-            // The API functions take NUL-terminated C strings. &CStr is not used for its runtime cost.
-            let class_name = b"NativeScript\0".as_ptr() as *const libc::c_char;
-            let ctor = (gd_api.godot_get_class_constructor)(class_name).unwrap();
-
-            let native_script =
-                NonNull::new(ctor()).expect("NativeScript constructor should not return null");
-            let native_script =
-                RawObject::<ReferenceCountedClassPlaceholder>::from_sys_ref_unchecked(
-                    native_script,
-                );
-            native_script.init_ref_count();
-
-            // `set_library` should be called before `set_class_name` to trigger class registration
-            // before trying to fetch the class name, in case the first NativeScript instance of this
-            // library is being constructed from Rust.
-            let mut args: [*const libc::c_void; 1] = [crate::private::get_gdnative_library_sys()];
-            (gd_api.godot_method_bind_ptrcall)(
-                nativescript_methods.set_library,
-                native_script.sys().as_ptr(),
-                args.as_mut_ptr(),
-                std::ptr::null_mut(),
-            );
-
-            let script_class_name = class_registry::class_name::<T>()
-                .map(GodotString::from)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "`{type_name}` must be registered before it can be used; call `handle.add_class::<{type_name}>()` in your `nativescript_init` callback",
-                        type_name = std::any::type_name::<T>(),
-                    );
-                });
-
-            let mut args: [*const libc::c_void; 1] = [script_class_name.sys() as *const _];
-            (gd_api.godot_method_bind_ptrcall)(
-                nativescript_methods.set_class_name,
-                native_script.sys().as_ptr(),
-                args.as_mut_ptr(),
-                std::ptr::null_mut(),
-            );
-
-            if let Some(script) = script {
-                emplace::place(script);
-            }
-
-            let mut args: [*const sys::godot_variant; 0] = [];
-            let variant = (gd_api.godot_method_bind_call)(
-                nativescript_methods.new,
-                native_script.sys().as_ptr(),
-                args.as_mut_ptr(),
-                0,
-                std::ptr::null_mut(),
-            );
-
-            assert!(
-                emplace::take::<T>().is_none(),
-                "emplacement value should be taken by the constructor wrapper (this is a bug in the bindings)",
-            );
-
-            let variant = Variant::from_sys(variant);
+            let variant = new_script_object(
+                || Some(Self::registered_class_name()),
+                || {
+                    if let Some(script) = script {
+                        emplace::place(script);
+                    }
+                },
+            )
+            .expect("the class name should be returned");
+
+            Self::assert_emplaced();
 
             let owner = variant
                 .to_object::<T::Base>()
                 .expect("the engine should return a base object of the correct type")
                 .assume_unique();
 
-            let script_ptr =
-                (gd_api.godot_nativescript_get_userdata)(owner.sys()) as *const libc::c_void;
+            Self::from_constructed(owner)
+        }
+    }
+
+    fn maybe_emplace_with_base(script: Option<T>, base: Ref<T::Base, Unique>) -> Self {
+        unsafe {
+            attach_script_object(
+                base.as_ptr(),
+                || Some(Self::registered_class_name()),
+                || {
+                    if let Some(script) = script {
+                        emplace::place(script);
+                    }
+                },
+            )
+            .expect("the class name should be returned");
+
+            Self::assert_emplaced();
+            Self::from_constructed(base)
+        }
+    }
+
+    fn registered_class_name() -> GodotString {
+        class_registry::class_name::<T>()
+            .map(GodotString::from)
+            .unwrap_or_else(|| {
+                panic!(
+                    "`{type_name}` must be registered before it can be used; call `handle.add_class::<{type_name}>()` in your `nativescript_init` callback",
+                    type_name = std::any::type_name::<T>(),
+                );
+            })
+    }
 
-            assert_ne!(
-                std::ptr::null(),
-                script_ptr,
-                "script instance should not be null (did the constructor fail?)"
-            );
+    fn assert_emplaced() {
+        assert!(
+            emplace::take::<T>().is_none(),
+            "emplacement value should be taken by the constructor wrapper (this is a bug in the bindings)",
+        );
+    }
 
-            let script = T::UserData::clone_from_user_data_unchecked(script_ptr);
+    /// Wraps `owner`, whose script instance was just constructed.
+    unsafe fn from_constructed(owner: Ref<T::Base, Unique>) -> Self {
+        let storage =
+            (get_api().godot_nativescript_get_userdata)(owner.sys()) as *const libc::c_void;
 
-            native_script.unref();
+        assert_ne!(
+            std::ptr::null(),
+            storage,
+            "script instance should not be null (did the constructor fail?)"
+        );
 
-            Instance { owner, script }
-        }
+        let script_ptr = InstanceStorage::user_data(storage);
+        let script = T::UserData::clone_from_user_data_unchecked(script_ptr);
+
+        Instance { owner, script }
     }
 }
 
+/// Creates an object with a script of this library attached, by calling `NativeScript::new`.
+///
+/// `class_name` is called after the library of the script is set, which triggers class
+/// registration if no script of this library has been constructed yet. If it returns `None`,
+/// no object is created. `before_new` is called right before the script is instantiated.
+pub(crate) unsafe fn new_script_object(
+    class_name: impl FnOnce() -> Option<GodotString>,
+    before_new: impl FnOnce(),
+) -> Option<Variant> {
+    with_native_script(class_name, |native_script| {
+        let gd_api = get_api();
+        let nativescript_methods = crate::private::NativeScriptMethodTable::get(gd_api);
+
+        assert_ne!(
+            std::ptr::null(),
+            nativescript_methods.new,
+            "NativeScript::new must be available"
+        );
+
+        before_new();
+
+        let mut args: [*const sys::godot_variant; 0] = [];
+        let variant = (gd_api.godot_method_bind_call)(
+            nativescript_methods.new,
+            native_script.sys().as_ptr(),
+            args.as_mut_ptr(),
+            0,
+            std::ptr::null_mut(),
+        );
+
+        Variant::from_sys(variant)
+    })
+}
+
+/// Attaches a script of this library to an existing object, by calling `Object::set_script`,
+/// which instantiates the script.
+///
+/// `class_name` and `before_attach` behave like the arguments of [`new_script_object`]. Returns
+/// `None` if `class_name` does.
+pub(crate) unsafe fn attach_script_object(
+    object: *mut sys::godot_object,
+    class_name: impl FnOnce() -> Option<GodotString>,
+    before_attach: impl FnOnce(),
+) -> Option<()> {
+    with_native_script(class_name, |native_script| {
+        let gd_api = get_api();
+        let set_script = crate::private::ObjectMethodTable::get(gd_api).set_script;
+
+        assert_ne!(
+            std::ptr::null(),
+            set_script,
+            "Object::set_script must be available"
+        );
+
+        before_attach();
+
+        let script = Variant::from_object_ptr(native_script.sys().as_ptr());
+        let mut args: [*const sys::godot_variant; 1] = [script.sys()];
+        let ret = (gd_api.godot_method_bind_call)(
+            set_script,
+            object,
+            args.as_mut_ptr(),
+            1,
+            std::ptr::null_mut(),
+        );
+
+        drop(Variant::from_sys(ret));
+    })
+}
+
+/// Creates a `NativeScript` for the class `class_name` of this library and passes it to `f`.
+/// The script is released after `f` returns, unless `f` has stored another reference to it.
+unsafe fn with_native_script<R>(
+    class_name: impl FnOnce() -> Option<GodotString>,
+    f: impl FnOnce(&RawObject<ReferenceCountedClassPlaceholder>) -> R,
+) -> Option<R> {
+    let gd_api = get_api();
+    let nativescript_methods = crate::private::NativeScriptMethodTable::get(gd_api);
+
+    assert_ne!(
+        std::ptr::null(),
+        nativescript_methods.set_class_name,
+        "NativeScript::set_class_name must be available"
+    );
+    assert_ne!(
+        std::ptr::null(),
+        nativescript_methods.set_library,
+        "NativeScript::set_library must be available"
+    );
+
+    // The API functions take NUL-terminated C strings. &CStr is not used for its runtime cost.
+    let ctor_class_name = b"NativeScript\0".as_ptr() as *const libc::c_char;
+    let ctor = (gd_api.godot_get_class_constructor)(ctor_class_name).unwrap();
+
+    let native_script =
+        NonNull::new(ctor()).expect("NativeScript constructor should not return null");
+    let native_script =
+        RawObject::<ReferenceCountedClassPlaceholder>::from_sys_ref_unchecked(native_script);
+    native_script.init_ref_count();
+
+    // `set_library` should be called before `set_class_name` to trigger class registration
+    // before trying to fetch the class name, in case the first NativeScript instance of this
+    // library is being constructed from Rust.
+    let mut args: [*const libc::c_void; 1] = [crate::private::get_gdnative_library_sys()];
+    (gd_api.godot_method_bind_ptrcall)(
+        nativescript_methods.set_library,
+        native_script.sys().as_ptr(),
+        args.as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+
+    let script_class_name = match class_name() {
+        Some(name) => name,
+        None => {
+            native_script.unref();
+            return None;
+        }
+    };
+
+    let mut args: [*const libc::c_void; 1] = [script_class_name.sys() as *const _];
+    (gd_api.godot_method_bind_ptrcall)(
+        nativescript_methods.set_class_name,
+        native_script.sys().as_ptr(),
+        args.as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+
+    let ret = f(native_script);
+
+    native_script.unref();
+
+    Some(ret)
+}
+
 impl<T: NativeClass, Own: Ownership> Instance<T, Own> {
     /// Returns the base object, dropping the script wrapper.
     #[inline]
@@ -575,6 +783,7 @@ where
     Ref<T::Base, Own>: ToVariant,
 {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn to_variant(&self) -> Variant {
         self.owner.to_variant()
     }
@@ -585,6 +794,7 @@ where
     T: NativeClass,
 {
     #[inline]
+    #[cfg_attr(feature = "alloc-tracking", track_caller)]
     fn owned_to_variant(self) -> Variant {
         self.into_base().owned_to_variant()
     }
@@ -605,6 +815,19 @@ where
 }
 
 fn try_get_user_data_ptr<T: NativeClass>(owner: &RawObject<T::Base>) -> Option<*mut libc::c_void> {
+    let storage = try_get_storage_ptr::<T>(owner)?;
+    if storage.is_null() {
+        return Some(storage);
+    }
+
+    // SAFETY: the storage of a live instance is valid until its destructor is called.
+    unsafe { Some(InstanceStorage::user_data(storage) as *mut _) }
+}
+
+/// Returns the storage the engine keeps as the user data of `owner`, if it's an instance of `T`.
+pub(crate) fn try_get_storage_ptr<T: NativeClass>(
+    owner: &RawObject<T::Base>,
+) -> Option<*mut libc::c_void> {
     unsafe {
         let api = get_api();
 
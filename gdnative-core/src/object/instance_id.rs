@@ -0,0 +1,66 @@
+use std::fmt::{self, Display};
+
+use crate::core_types::{FromVariant, FromVariantError, ToVariant, Variant};
+
+/// Unique ID of a Godot object, as returned by `Object::get_instance_id`.
+///
+/// IDs are never reused while the engine is running, so an ID that refers to a freed object stays
+/// invalid forever. This makes them suitable for referring to objects that may be freed at any
+/// time. See [`ObjectHandle`][super::ObjectHandle] for a typed wrapper that checks the class of
+/// the object on access.
+///
+/// `InstanceId` is converted to and from variants as an integer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct InstanceId(i64);
+
+impl InstanceId {
+    /// Creates an `InstanceId` from a raw ID, e.g. the return value of `Object::get_instance_id`.
+    ///
+    /// The ID is not checked. Looking up an ID that was never assigned simply fails.
+    #[inline]
+    pub const fn from_i64(id: i64) -> Self {
+        InstanceId(id)
+    }
+
+    /// Returns the raw ID.
+    #[inline]
+    pub const fn to_i64(self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for InstanceId {
+    #[inline]
+    fn from(id: i64) -> Self {
+        InstanceId(id)
+    }
+}
+
+impl From<InstanceId> for i64 {
+    #[inline]
+    fn from(id: InstanceId) -> Self {
+        id.0
+    }
+}
+
+impl Display for InstanceId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl ToVariant for InstanceId {
+    #[inline]
+    fn to_variant(&self) -> Variant {
+        self.0.to_variant()
+    }
+}
+
+impl FromVariant for InstanceId {
+    #[inline]
+    fn from_variant(variant: &Variant) -> Result<Self, FromVariantError> {
+        i64::from_variant(variant).map(InstanceId)
+    }
+}
@@ -21,23 +21,36 @@ use bounds::{
 use memory::{ManuallyManaged, Memory, RefCounted};
 use ownership::{NonUniqueOwnership, Ownership, Shared, ThreadLocal, Unique};
 
-use crate::export::NativeClass;
+use crate::export::user_data::MapMut;
+use crate::export::{class_registry, NativeClass};
 use crate::private::{get_api, ManuallyManagedClassPlaceholder, ReferenceCountedClassPlaceholder};
 use crate::sys;
 
 pub use as_arg::*;
+pub use class_id::ClassId;
+pub use free_guard::FreeGuard;
+pub use handle::{HandlePolicy, ObjectHandle};
 pub use instance::*;
+pub use instance_id::InstanceId;
 pub use new_ref::NewRef;
 pub use raw::RawObject;
+pub use typed_property::TypedProperty;
+pub use virtual_method::VirtualMethod;
 
 pub mod bounds;
 pub mod memory;
 pub mod ownership;
 
 mod as_arg;
+mod class_id;
+mod free_guard;
+mod handle;
 mod instance;
+mod instance_id;
 mod new_ref;
 mod raw;
+mod typed_property;
+pub(crate) mod virtual_method;
 
 /// Trait for Godot API objects. This trait is sealed, and implemented for generated wrapper
 /// types.
@@ -59,7 +72,24 @@ pub unsafe trait GodotObject: Sized + crate::private::godot_object::Sealed {
     /// information.
     type Memory: Memory;
 
-    fn class_name() -> &'static str;
+    /// Virtual methods that scripts can override, grouped by the class that declares them,
+    /// starting with `Self` and followed by its base classes.
+    ///
+    /// This is used to check the signatures of exported methods at compile time.
+    const VIRTUAL_METHODS: &'static [&'static [VirtualMethod]] = &[];
+
+    /// Name of the class in the engine.
+    const CLASS_NAME: &'static str;
+
+    /// ID of the class, derived from [`CLASS_NAME`](Self::CLASS_NAME). See [`ClassId`].
+    const CLASS_ID: ClassId = ClassId::from_name(Self::CLASS_NAME);
+
+    /// Returns the name of the class in the engine. This is the same as
+    /// [`CLASS_NAME`](Self::CLASS_NAME).
+    #[inline]
+    fn class_name() -> &'static str {
+        Self::CLASS_NAME
+    }
 
     /// Creates an explicitly null reference of `Self` as a method argument. This makes type
     /// inference easier for the compiler compared to `Option`.
@@ -172,6 +202,12 @@ pub unsafe trait GodotObject: Sized + crate::private::godot_object::Sealed {
         Ref::from_sys(self.as_raw().sys())
     }
 
+    /// Returns the instance ID of the object. See also `Object::get_instance_id`.
+    #[inline]
+    fn instance_id(&self) -> InstanceId {
+        self.as_raw().instance_id()
+    }
+
     /// Recovers a instance ID previously returned by `Object::get_instance_id` if the object is
     /// still alive. See also `TRef::try_from_instance_id`.
     ///
@@ -180,7 +216,7 @@ pub unsafe trait GodotObject: Sized + crate::private::godot_object::Sealed {
     /// During the entirety of `'a`, the thread from which `try_from_instance_id` is called must
     /// have exclusive access to the underlying object, if it is still alive.
     #[inline]
-    unsafe fn try_from_instance_id<'a>(id: i64) -> Option<TRef<'a, Self, Shared>> {
+    unsafe fn try_from_instance_id<'a>(id: impl Into<InstanceId>) -> Option<TRef<'a, Self, Shared>> {
         TRef::try_from_instance_id(id)
     }
 
@@ -198,7 +234,7 @@ pub unsafe trait GodotObject: Sized + crate::private::godot_object::Sealed {
     /// During the entirety of `'a`, the thread from which `try_from_instance_id` is called must
     /// have exclusive access to the underlying object, if it is still alive.
     #[inline]
-    unsafe fn from_instance_id<'a>(id: i64) -> TRef<'a, Self, Shared> {
+    unsafe fn from_instance_id<'a>(id: impl Into<InstanceId>) -> TRef<'a, Self, Shared> {
         TRef::from_instance_id(id)
     }
 }
@@ -227,6 +263,35 @@ pub trait QueueFree: GodotObject {
     unsafe fn godot_queue_free(sys: *mut sys::godot_object);
 }
 
+/// Marker trait for engine singletons that are safe to use from any thread, such as
+/// `ResourceLoader` or `OS`. This trait is implemented by the bindings generator, following the
+/// official [thread-safety guidelines][thread-safety]. Users should not attempt to implement
+/// this trait.
+///
+/// See [`worker::par_call`](crate::worker::par_call) for a helper that uses it.
+///
+/// [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
+pub unsafe trait ThreadSafeSingleton: GodotObject + Send + Sync + 'static {
+    /// Returns a reference to the singleton instance.
+    fn singleton() -> &'static Self;
+}
+
+/// Marker trait for engine servers, such as `VisualServer` or `AudioServer`. This trait is
+/// implemented by the bindings generator. Users should not attempt to implement this trait.
+///
+/// Servers may be used from other threads, but some of them, like `VisualServer` and the
+/// physics servers, only if thread-safe operation is enabled in the project settings. Those
+/// don't implement [`ThreadSafeSingleton`].
+pub unsafe trait ServerClass: GodotObject + 'static {
+    /// Returns a reference to the server instance.
+    ///
+    /// # Safety
+    ///
+    /// If the server doesn't implement [`ThreadSafeSingleton`], it may only be used from outside
+    /// the main thread if thread-safe operation is enabled for it in the project settings.
+    unsafe fn server() -> &'static Self;
+}
+
 /// A polymorphic smart pointer for Godot objects whose behavior changes depending on the
 /// memory management method of the underlying type and the thread access status.
 ///
@@ -440,21 +505,6 @@ where
         self.try_cast().ok()
     }
 
-    /// Performs a static reference upcast to a supertype, keeping the reference count.
-    /// This is guaranteed to be valid.
-    ///
-    /// This is only possible between types with the same `Memory`s, since otherwise the
-    /// reference can get leaked. Casting between `Object` and `Reference` is possible on
-    /// `TRef` and bare references.
-    #[inline]
-    pub fn upcast<U>(self) -> Ref<U, Own>
-    where
-        U: GodotObject<Memory = T::Memory>,
-        T: SubClass<U>,
-    {
-        unsafe { self.cast_unchecked() }
-    }
-
     /// Performs a dynamic reference cast to target type, keeping the reference count.
     ///
     /// This is only possible between types with the same `Memory`s, since otherwise the
@@ -476,16 +526,6 @@ where
         }
     }
 
-    /// Performs an unchecked cast.
-    unsafe fn cast_unchecked<U>(self) -> Ref<U, Own>
-    where
-        U: GodotObject<Memory = T::Memory>,
-    {
-        let ret = Ref::move_from_sys(self.ptr.as_non_null());
-        std::mem::forget(self);
-        ret
-    }
-
     /// Performs a downcast to a `NativeClass` instance, keeping the reference count.
     /// Shorthand for `try_cast_instance().ok()`.
     ///
@@ -512,6 +552,37 @@ where
     }
 }
 
+/// Static casts, which don't access the object, and are available for all `Ref`s.
+impl<T: GodotObject, Own: Ownership> Ref<T, Own> {
+    /// Performs a static reference upcast to a supertype, keeping the reference count.
+    /// This is guaranteed to be valid, and a no-op at runtime.
+    ///
+    /// Since the object isn't accessed, this is also possible for `Shared` references to
+    /// manually-managed objects.
+    ///
+    /// This is only possible between types with the same `Memory`s, since otherwise the
+    /// reference can get leaked. Casting between `Object` and `Reference` is possible on
+    /// `TRef` and bare references.
+    #[inline]
+    pub fn upcast<U>(self) -> Ref<U, Own>
+    where
+        U: GodotObject<Memory = T::Memory>,
+        T: SubClass<U>,
+    {
+        unsafe { self.cast_unchecked() }
+    }
+
+    /// Performs an unchecked cast.
+    unsafe fn cast_unchecked<U>(self) -> Ref<U, Own>
+    where
+        U: GodotObject<Memory = T::Memory>,
+    {
+        let ret = Ref::move_from_sys(self.ptr.as_non_null());
+        std::mem::forget(self);
+        ret
+    }
+}
+
 /// Methods for references that can't be used directly, and have to be assumed safe `unsafe`ly.
 impl<T: GodotObject> Ref<T, Shared> {
     /// Assume that `self` is safe to use, returning a reference that can be used to call API
@@ -665,6 +736,21 @@ impl<T: GodotObject> Ref<T, Unique> {
     }
 }
 
+/// Converts a `Unique` reference to a `Shared` reference to the same type or a base class, so
+/// that newly created objects can be passed where a `Ref<Base>` is expected with `.into()`.
+///
+/// This is guaranteed to be a no-op at runtime.
+impl<T, U> From<Ref<T, Unique>> for Ref<U, Shared>
+where
+    T: GodotObject + SubClass<U>,
+    U: GodotObject<Memory = T::Memory>,
+{
+    #[inline(always)]
+    fn from(obj: Ref<T, Unique>) -> Self {
+        obj.into_shared().upcast()
+    }
+}
+
 /// Methods for freeing `Unique` references to manually-managed objects.
 impl<T: GodotObject<Memory = ManuallyManaged>> Ref<T, Unique> {
     /// Manually frees the object.
@@ -681,6 +767,31 @@ impl<T: GodotObject<Memory = ManuallyManaged>> Ref<T, Unique> {
             self.as_raw().free();
         }
     }
+
+    /// Wraps the object in a [`FreeGuard`], which frees it when dropped unless it is released
+    /// first.
+    #[inline]
+    pub fn into_free_guard(self) -> FreeGuard<T> {
+        FreeGuard::new(self)
+    }
+
+    /// Calls `f` with the object, then frees it. The object is also freed if `f` panics.
+    ///
+    /// This is useful for objects that are only needed temporarily:
+    ///
+    /// ```no_run
+    /// use gdnative::prelude::*;
+    ///
+    /// let is_visible = Node2D::new().scoped(|node| {
+    ///     node.set_position(Vector2::new(10.0, 0.0));
+    ///     node.is_visible()
+    /// });
+    /// ```
+    #[inline]
+    pub fn scoped<R>(self, f: impl FnOnce(TRef<'_, T, Unique>) -> R) -> R {
+        let guard = self.into_free_guard();
+        f(guard.as_ref())
+    }
 }
 
 /// Methods for freeing `Unique` references to manually-managed objects.
@@ -942,6 +1053,69 @@ impl<'a, T: GodotObject, Own: Ownership> TRef<'a, T, Own> {
     {
         TInstance::try_from_base(self)
     }
+
+    /// Calls `op` with the `C` attached to this object and its base, borrowing `C` mutably.
+    ///
+    /// This is the typed alternative to calling an exported method through `Object::call`,
+    /// for when both the caller and the callee are Rust classes:
+    ///
+    /// ```no_run
+    /// use gdnative::prelude::*;
+    ///
+    /// #[derive(NativeClass)]
+    /// #[inherit(Node)]
+    /// #[no_constructor]
+    /// struct Enemy {
+    ///     health: i64,
+    /// }
+    ///
+    /// #[methods]
+    /// impl Enemy {
+    ///     #[method]
+    ///     fn take_damage(&mut self, amount: i64) -> i64 {
+    ///         self.health -= amount;
+    ///         self.health
+    ///     }
+    /// }
+    ///
+    /// fn hit(other: TRef<Node>) {
+    ///     match other.with::<Enemy, _>(|enemy, _base| enemy.take_damage(10)) {
+    ///         Ok(health) => godot_print!("enemy health: {health}"),
+    ///         Err(err) => godot_error!("{err}"),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Fails if this object is not an instance of `C`, or if `C` is already borrowed, e.g. when
+    /// one of its methods is currently running further up the call stack. In the latter case,
+    /// the error includes the script calls on the current thread, so that the call borrowing
+    /// the instance can be identified.
+    #[inline]
+    pub fn with<C, R>(
+        self,
+        op: impl FnOnce(&mut C, TRef<'_, C::Base, Own>) -> R,
+    ) -> Result<R, WithInstanceError>
+    where
+        C: NativeClass,
+        C::Base: SubClass<T>,
+        C::UserData: MapMut,
+    {
+        let instance = self
+            .cast::<C::Base>()
+            .and_then(TInstance::<C, Own>::try_from_base)
+            .ok_or_else(|| WithInstanceError::NotAnInstance {
+                expected: class_registry::class_name_or_default::<C>(),
+                found: self.obj.as_raw().class_name(),
+            })?;
+
+        instance
+            .map_mut(op)
+            .map_err(|err| WithInstanceError::Borrow {
+                class: class_registry::class_name_or_default::<C>(),
+                error: err.to_string(),
+                call_stack: crate::diagnostics::call_stack(),
+            })
+    }
 }
 
 impl<'a, Kind, T, Own> TRef<'a, T, Own>
@@ -968,8 +1142,9 @@ impl<'a, T: GodotObject> TRef<'a, T, Shared> {
     /// During the entirety of `'a`, the thread from which `try_from_instance_id` is called must
     /// have exclusive access to the underlying object, if it is still alive.
     #[inline]
-    pub unsafe fn try_from_instance_id(id: i64) -> Option<Self> {
+    pub unsafe fn try_from_instance_id(id: impl Into<InstanceId>) -> Option<Self> {
         let api = get_api();
+        let id = id.into().to_i64();
         let ptr = NonNull::new((api.godot_instance_from_id)(id as sys::godot_int))?;
         let raw = RawObject::try_from_sys_ref(ptr)?;
         Some(TRef::new(T::cast_ref(raw)))
@@ -989,7 +1164,7 @@ impl<'a, T: GodotObject> TRef<'a, T, Shared> {
     /// During the entirety of `'a`, the thread from which `try_from_instance_id` is called must
     /// have exclusive access to the underlying object, if it is still alive.
     #[inline]
-    pub unsafe fn from_instance_id(id: i64) -> Self {
+    pub unsafe fn from_instance_id(id: impl Into<InstanceId>) -> Self {
         Self::try_from_instance_id(id).expect("instance should be alive")
     }
 }
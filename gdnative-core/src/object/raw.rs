@@ -1,13 +1,17 @@
+use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::marker::PhantomData;
 use std::ptr::{self, NonNull};
 
-use crate::core_types::GodotString;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::core_types::{GodotString, StringName};
 use crate::object::memory::RefCounted;
 use crate::private::get_api;
 use crate::sys;
 
-use super::GodotObject;
+use super::{GodotObject, InstanceId};
 
 /// An opaque struct representing Godot objects. This should never be created on the stack.
 ///
@@ -39,7 +43,7 @@ impl<T: GodotObject> RawObject<T> {
     /// The `obj` pointer must be pointing to a valid Godot object during the entirety of `'a`.
     #[inline]
     pub unsafe fn try_from_sys_ref<'a>(obj: NonNull<sys::godot_object>) -> Option<&'a Self> {
-        if ptr_is_class(obj.as_ptr(), T::class_name()) {
+        if ptr_is_class_of::<T>(obj.as_ptr()) {
             Some(Self::from_sys_ref_unchecked(obj))
         } else {
             None
@@ -56,7 +60,7 @@ impl<T: GodotObject> RawObject<T> {
     /// Checks whether the object is of a certain Godot class.
     #[inline]
     pub fn is_class<U: GodotObject>(&self) -> bool {
-        self.is_class_by_name(U::class_name())
+        unsafe { ptr_is_class_of::<U>(self.sys().as_ptr()) }
     }
 
     /// Checks whether the object is of a certain Godot class by name.
@@ -87,6 +91,27 @@ impl<T: GodotObject> RawObject<T> {
         string.to_string()
     }
 
+    /// Returns the instance ID of this object using `Object::get_instance_id`.
+    #[inline]
+    pub fn instance_id(&self) -> InstanceId {
+        let api = crate::private::get_api();
+        let get_instance_id_method = crate::private::ObjectMethodTable::get(api).get_instance_id;
+        let mut argument_buffer: [*const libc::c_void; 0] = [];
+        let mut id: i64 = 0;
+        let ret_ptr = &mut id as *mut i64;
+
+        unsafe {
+            (api.godot_method_bind_ptrcall)(
+                get_instance_id_method,
+                self.sys().as_ptr(),
+                argument_buffer.as_mut_ptr() as *mut _,
+                ret_ptr as *mut _,
+            );
+        }
+
+        InstanceId::from_i64(id)
+    }
+
     /// Attempt to cast a Godot object to a different class type.
     #[inline]
     pub fn cast<U>(&self) -> Option<&RawObject<U>>
@@ -222,6 +247,38 @@ impl<T: GodotObject> Debug for RawObject<T> {
     }
 }
 
+/// Engine class tags by class name. Classes unknown to the engine are cached as `0`.
+static CLASS_TAGS: Lazy<RwLock<HashMap<&'static str, usize>>> = Lazy::new(RwLock::default);
+
+/// Returns the engine class tag of `T`, or null if the class is unknown to the engine. Tags are
+/// looked up once per class, and cached afterwards.
+fn class_tag<T: GodotObject>() -> *mut libc::c_void {
+    if let Some(&tag) = CLASS_TAGS.read().get(T::CLASS_NAME) {
+        return tag as *mut libc::c_void;
+    }
+
+    let class_name = StringName::from_str(T::CLASS_NAME);
+    let tag = unsafe { (crate::private::get_api().godot_get_class_tag)(class_name.sys()) };
+    CLASS_TAGS.write().insert(T::CLASS_NAME, tag as usize);
+    tag
+}
+
+/// Checks whether the raw object pointer is of the Godot class `T`, using the class tag of `T`
+/// if available. This avoids the string conversions of [`ptr_is_class`].
+///
+/// # Safety
+///
+/// The `obj` pointer must be pointing to a valid Godot object.
+#[inline]
+unsafe fn ptr_is_class_of<T: GodotObject>(obj: *mut sys::godot_object) -> bool {
+    let tag = class_tag::<T>();
+    if tag.is_null() {
+        return ptr_is_class(obj, T::CLASS_NAME);
+    }
+
+    !(crate::private::get_api().godot_object_cast_to)(obj, tag).is_null()
+}
+
 /// Checks whether the raw object pointer is of a certain Godot class.
 ///
 /// # Safety
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Name of a property of the class `C`, whose values have the type `T`.
+///
+/// Constants of this type are generated for the properties of all API classes, in the
+/// `properties` module next to each class, e.g. `gdnative::api::node_2d::properties::POSITION`.
+/// Using them instead of strings turns typos in property names into compile errors, and allows
+/// helpers such as those in `gdnative::animation` to check the types of the values. Since
+/// `TypedProperty` converts into `NodePath`, it can also be passed to any API method that
+/// takes a property path.
+///
+/// To use a property with an object of a subclass of `C`, require `SubClass<C>` for the object:
+///
+/// ```
+/// use gdnative::api::canvas_item;
+/// use gdnative::object::TypedProperty;
+/// use gdnative::prelude::*;
+///
+/// fn property_path<O: SubClass<C>, C: GodotObject, T>(
+///     _object: &O,
+///     property: TypedProperty<C, T>,
+/// ) -> NodePath {
+///     property.into()
+/// }
+///
+/// # fn check(node: &Node2D) {
+/// let path = property_path(node, canvas_item::properties::MODULATE);
+/// # }
+/// assert_eq!("modulate", canvas_item::properties::MODULATE.name());
+/// ```
+pub struct TypedProperty<C, T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> (C, T)>,
+}
+
+impl<C, T> TypedProperty<C, T> {
+    /// Creates a typed property from its name. The name and type are not checked, which makes
+    /// this suitable for properties of scripts as well.
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        TypedProperty {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the name of the property.
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+}
+
+impl<C, T> Copy for TypedProperty<C, T> {}
+
+impl<C, T> Clone for TypedProperty<C, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C, T> AsRef<str> for TypedProperty<C, T> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl<C, T> fmt::Debug for TypedProperty<C, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedProperty").field(&self.name).finish()
+    }
+}
+
+impl<C, T> fmt::Display for TypedProperty<C, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
@@ -0,0 +1,163 @@
+/// Signature of a virtual method that scripts can override, as listed in
+/// [`GodotObject::VIRTUAL_METHODS`][super::GodotObject::VIRTUAL_METHODS].
+///
+/// Types are spelled as in the Godot documentation, e.g. `"float"` or `"Vector2"`, except that
+/// enums are listed as `"int"` and all engine classes as `"Object"`. Methods that don't return
+/// anything have the return type `"void"`.
+#[derive(Copy, Clone, Debug)]
+pub struct VirtualMethod {
+    name: &'static str,
+    args: &'static [&'static str],
+    return_type: &'static str,
+}
+
+impl VirtualMethod {
+    /// Creates a new signature. Used by generated bindings.
+    #[inline]
+    pub const fn new(
+        name: &'static str,
+        args: &'static [&'static str],
+        return_type: &'static str,
+    ) -> Self {
+        VirtualMethod {
+            name,
+            args,
+            return_type,
+        }
+    }
+
+    /// Returns the name of the method.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the types of the arguments.
+    #[inline]
+    pub const fn args(&self) -> &'static [&'static str] {
+        self.args
+    }
+
+    /// Returns the return type.
+    #[inline]
+    pub const fn return_type(&self) -> &'static str {
+        self.return_type
+    }
+}
+
+/// Checks an exported method named `name` against the virtual methods in `lists`, panicking
+/// with `message` if it overrides one with a different signature. Evaluated at compile time.
+///
+/// `args` and `return_type` use the same spelling as `VirtualMethod`, with an empty string for
+/// types that can't be checked. Only the first `required` arguments are mandatory.
+pub(crate) const fn check_override(
+    lists: &[&[VirtualMethod]],
+    name: &str,
+    args: &[&str],
+    required: usize,
+    return_type: &str,
+    message: &str,
+) {
+    let virtual_method = match find(lists, name) {
+        Some(virtual_method) => virtual_method,
+        None => return,
+    };
+
+    let expected = virtual_method.args;
+    if required > expected.len() || args.len() < expected.len() {
+        panic!("{}", message);
+    }
+
+    let mut i = 0;
+    while i < expected.len() {
+        if !type_matches(args[i], expected[i]) {
+            panic!("{}", message);
+        }
+        i += 1;
+    }
+
+    // Return values of `void` methods are ignored by the engine.
+    if !str_eq(virtual_method.return_type, "void")
+        && (str_eq(return_type, "void") || !type_matches(return_type, virtual_method.return_type))
+    {
+        panic!("{}", message);
+    }
+}
+
+/// Returns the first method named `name`, i.e. the one of the most derived class.
+const fn find(lists: &[&[VirtualMethod]], name: &str) -> Option<VirtualMethod> {
+    let mut i = 0;
+    while i < lists.len() {
+        let mut j = 0;
+        while j < lists[i].len() {
+            if str_eq(lists[i][j].name, name) {
+                return Some(lists[i][j]);
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+const fn type_matches(actual: &str, expected: &str) -> bool {
+    actual.is_empty()
+        || str_eq(actual, "Variant")
+        || str_eq(expected, "Variant")
+        || str_eq(actual, expected)
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBJECT: &[VirtualMethod] = &[VirtualMethod::new("_to_string", &[], "String")];
+    const NODE: &[VirtualMethod] = &[
+        VirtualMethod::new("_process", &["float"], "void"),
+        VirtualMethod::new("_input", &["Object"], "void"),
+    ];
+    const LISTS: &[&[VirtualMethod]] = &[NODE, OBJECT];
+
+    fn check(name: &str, args: &[&str], required: usize, return_type: &str) -> bool {
+        std::panic::catch_unwind(|| {
+            check_override(LISTS, name, args, required, return_type, "mismatch")
+        })
+        .is_ok()
+    }
+
+    #[test]
+    fn matching_overrides() {
+        assert!(check("_process", &["float"], 1, "void"));
+        assert!(check("_process", &["Variant"], 1, "bool"));
+        assert!(check("_input", &[""], 1, "void"));
+        assert!(check("_input", &["Object", "int"], 1, "void"));
+        assert!(check("_to_string", &[], 0, "String"));
+        assert!(check("_to_string", &[], 0, ""));
+        assert!(check("_on_timeout", &["int"], 1, "void"));
+    }
+
+    #[test]
+    fn mismatched_overrides() {
+        assert!(!check("_process", &[], 0, "void"));
+        assert!(!check("_process", &["int"], 1, "void"));
+        assert!(!check("_input", &["Object", "int"], 2, "void"));
+        assert!(!check("_to_string", &[], 0, "void"));
+        assert!(!check("_to_string", &[], 0, "int"));
+    }
+}
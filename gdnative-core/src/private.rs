@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::panic::{catch_unwind, UnwindSafe};
 
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
 use crate::sys;
 
 // ----------------------------------------------------------------------------------------------------------------------------------------------
@@ -8,6 +12,34 @@ use crate::sys;
 
 static mut GODOT_API: Option<sys::GodotApi> = None;
 static mut GDNATIVE_LIBRARY_SYS: Option<*mut sys::godot_object> = None;
+static mut ANDROID_API: Option<&'static sys::godot_gdnative_ext_android_api_struct> = None;
+
+/// Whether the engine has bound the API at some point. The pure Rust fallbacks must not be used
+/// afterwards, because values allocated by the engine may still be dropped after `terminate`.
+#[cfg(feature = "no-engine")]
+static ENGINE_BOUND: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// `GDNativeLibrary` objects that share this copy of the crate, in the order they were
+/// initialized. There is more than one if the same binary is loaded by multiple libraries.
+static LIBRARIES: Lazy<Mutex<Libraries>> = Lazy::new(Mutex::default);
+
+#[derive(Default)]
+struct Libraries {
+    /// Number of libraries for which `gdnative_init` succeeded, but `gdnative_terminate` hasn't
+    /// been called yet.
+    bound: usize,
+    entries: Vec<LibraryEntry>,
+}
+
+struct LibraryEntry {
+    /// The `GDNativeLibrary` object, which identifies the library.
+    library: usize,
+    /// Copy of the library path that the NativeScript handle points to, once `nativescript_init`
+    /// is called. NativeScript handles are pointers to local variables of the engine that
+    /// differ between callbacks, so libraries are looked up by the path instead. Members
+    /// registered after `nativescript_init` are registered with a pointer to the copy.
+    path: Option<Box<crate::core_types::GodotString>>,
+}
 
 /// Binds the API struct from `gdnative_init_options`. Returns `true` on success.
 ///
@@ -30,12 +62,29 @@ pub unsafe fn bind_api(options: *mut sys::godot_gdnative_init_options) -> bool {
     };
 
     GODOT_API = Some(api);
-    GDNATIVE_LIBRARY_SYS = Some((*options).gd_native_library);
+    #[cfg(feature = "no-engine")]
+    ENGINE_BOUND.store(true, std::sync::atomic::Ordering::Release);
+    ANDROID_API = sys::find_android_api((*options).api_struct);
+
+    let mut libraries = LIBRARIES.lock();
+    libraries.bound += 1;
+    libraries.entries.push(LibraryEntry {
+        library: (*options).gd_native_library as usize,
+        path: None,
+    });
+    if libraries.entries.len() == 1 {
+        GDNATIVE_LIBRARY_SYS = Some((*options).gd_native_library);
+    }
+    drop(libraries);
 
     ObjectMethodTable::get(get_api());
     ReferenceMethodTable::get(get_api());
     NativeScriptMethodTable::get(get_api());
     EngineMethodTable::get(get_api());
+    SceneTreeMethodTable::get(get_api());
+    NodeMethodTable::get(get_api());
+    OSMethodTable::get(get_api());
+    ClassDBMethodTable::get(get_api());
 
     true
 }
@@ -106,6 +155,18 @@ pub fn get_api() -> &'static sys::GodotApi {
     need to use the godot_test! macro, and invoke the test functions in test/src/lib.rs.
     ";
 
+    // Without the engine, fall back to the pure Rust implementations of the core types. Once
+    // the engine has bound the API, the fallback is never used, even after `terminate`.
+    #[cfg(feature = "no-engine")]
+    unsafe {
+        if let Some(api) = GODOT_API.as_ref() {
+            return api;
+        }
+        if !ENGINE_BOUND.load(std::sync::atomic::Ordering::Acquire) {
+            return crate::no_engine::api();
+        }
+    }
+
     // Unwinding during tests should be safe and provide more ergonomic UI.
     #[cfg(any(test, feature = "gd-test"))]
     unsafe {
@@ -136,6 +197,14 @@ pub fn is_api_bound() -> bool {
     unsafe { GODOT_API.is_some() }
 }
 
+/// Returns the Android extension API, if provided by the engine.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub fn get_android_api() -> Option<&'static sys::godot_gdnative_ext_android_api_struct> {
+    unsafe { ANDROID_API }
+}
+
 /// Returns a pointer to the `GDNativeLibrary` object for the current library.
 ///
 /// This is intended to be an internal interface.
@@ -144,6 +213,82 @@ pub fn get_gdnative_library_sys() -> *mut sys::godot_object {
     unsafe { GDNATIVE_LIBRARY_SYS.expect("GDNativeLibrary not bound") }
 }
 
+/// Associates the NativeScript `handle` with the library that was initialized last, since
+/// Godot calls `nativescript_init` right after `gdnative_init` of the same library.
+///
+/// # Safety
+///
+/// `handle` must be the NativeScript handle passed to `nativescript_init`.
+pub(crate) unsafe fn bind_nativescript_handle(handle: *mut libc::c_void) {
+    let path = nativescript_path(handle);
+
+    let mut libraries = LIBRARIES.lock();
+    if let Some(entry) = libraries
+        .entries
+        .iter_mut()
+        .rev()
+        .find(|e| e.path.is_none())
+    {
+        entry.path = Some(Box::new(path));
+    }
+}
+
+/// Returns the `GDNativeLibrary` object identifying the library of the NativeScript `handle`,
+/// or `None` if `nativescript_init` wasn't called for it.
+///
+/// # Safety
+///
+/// `handle` must be a NativeScript handle passed to a callback that is still running.
+pub(crate) unsafe fn library_of_handle(handle: *mut libc::c_void) -> Option<usize> {
+    let path = nativescript_path(handle);
+    LIBRARIES
+        .lock()
+        .entries
+        .iter()
+        .find(|e| e.path.as_deref() == Some(&path))
+        .map(|e| e.library)
+}
+
+/// Returns a handle that can be used to register members with `library` after
+/// `nativescript_init` has returned, or `None` if the library is not bound.
+///
+/// The returned handle is valid until the library is unbound in `nativescript_terminate`.
+pub(crate) fn registration_handle(library: usize) -> Option<*mut libc::c_void> {
+    LIBRARIES
+        .lock()
+        .entries
+        .iter()
+        .find(|e| e.library == library)
+        .and_then(|e| e.path.as_ref())
+        .map(|path| path.sys() as *mut libc::c_void)
+}
+
+/// Forgets `library`, so that new instances are created with the remaining libraries.
+///
+/// # Safety
+///
+/// Must be called on the main thread, during `nativescript_terminate`.
+pub(crate) unsafe fn unbind_library(library: usize) {
+    let mut libraries = LIBRARIES.lock();
+    libraries.entries.retain(|e| e.library != library);
+    if let Some(entry) = libraries.entries.first() {
+        GDNATIVE_LIBRARY_SYS = Some(entry.library as *mut sys::godot_object);
+    }
+}
+
+/// Copies the library path that a NativeScript handle points to, as a `godot_string`.
+unsafe fn nativescript_path(handle: *mut libc::c_void) -> crate::core_types::GodotString {
+    crate::core_types::GodotString::clone_from_sys(*(handle as *const sys::godot_string))
+}
+
+/// Releases a library during `gdnative_terminate`. Returns `true` if it was the last library
+/// sharing this copy of the crate, in which case library-wide state should be cleaned up.
+pub(crate) fn release_library() -> bool {
+    let mut libraries = LIBRARIES.lock();
+    libraries.bound = libraries.bound.saturating_sub(1);
+    libraries.bound == 0
+}
+
 /// Performs library-wide cleanup during `terminate`.
 ///
 /// # Safety
@@ -151,12 +296,54 @@ pub fn get_gdnative_library_sys() -> *mut sys::godot_object {
 /// This is intended to be an internal interface.
 #[inline]
 pub unsafe fn cleanup_internal_state() {
+    crate::services::cleanup();
     crate::export::type_tag::cleanup();
     crate::export::class_registry::cleanup();
+    crate::init::report::cleanup();
+    crate::export::property::validation::cleanup();
 
+    *LIBRARIES.lock() = Libraries::default();
+    GDNATIVE_LIBRARY_SYS = None;
+    ANDROID_API = None;
     GODOT_API = None;
 }
 
+/// Reports an error returned by a method exported with `#[method(err)]`, by logging it and
+/// emitting the `script_error` signal on `base` if it has one.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub fn report_method_error<T: crate::object::GodotObject>(
+    base: crate::object::TRef<'_, T, crate::object::ownership::Shared>,
+    site: crate::log::Site<'static>,
+    method: &str,
+    message: String,
+) {
+    use crate::core_types::{FromVariant, ToVariant};
+
+    crate::log::error(site, &message);
+
+    let mut base = base.to_variant();
+    let signal = "script_error".to_variant();
+
+    // SAFETY: `has_signal` and `emit_signal` are safe to call, although the signal may run
+    // arbitrary code, as is the case for any method call.
+    unsafe {
+        let has_signal = base
+            .call("has_signal", std::slice::from_ref(&signal))
+            .ok()
+            .and_then(|ret| bool::from_variant(&ret).ok())
+            .unwrap_or(false);
+
+        if has_signal {
+            let args = [signal, method.to_variant(), message.to_variant()];
+            if let Err(err) = base.call("emit_signal", &args) {
+                godot_error!("gdnative-core: failed to emit `script_error`: {err}");
+            }
+        }
+    }
+}
+
 /// Reports an `InitError` to Godot.
 #[inline]
 unsafe fn report_init_error(
@@ -209,6 +396,73 @@ pub(crate) fn print_panic_error(err: Box<dyn std::any::Any + Send>) {
     }
 }
 
+/// Checks an exported method against the virtual methods of `B`, failing compilation with
+/// `message` if it overrides one with a different signature.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub const fn check_virtual_override<B: crate::object::GodotObject>(
+    name: &str,
+    args: &[&str],
+    required: usize,
+    return_type: &str,
+    message: &str,
+) {
+    crate::object::virtual_method::check_override(
+        B::VIRTUAL_METHODS,
+        name,
+        args,
+        required,
+        return_type,
+        message,
+    );
+}
+
+/// Used by `#[methods]` to check the types of `#[base]` parameters. A type error in a call of
+/// this function means that the parameter doesn't have the type in `#[inherit]`.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub const fn base_parameter_must_have_inherited_type<Base>(_: std::marker::PhantomData<Base>) {}
+
+/// Used by `#[methods]` to check that classes with `&mut self` methods use a user-data wrapper
+/// that can be mapped mutably. A type error in a call of this function means that the wrapper
+/// in `#[user_data]`, such as `OnceData`, is read-only.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub const fn mut_self_requires_map_mut_user_data<U: crate::export::user_data::MapMut>() {}
+
+/// Logs the script call stack of the current thread, if it is not empty.
+///
+/// This is intended to be an internal interface.
+#[inline]
+pub fn log_call_stack() {
+    crate::diagnostics::log_call_stack();
+}
+
+/// Calls `method` on the instance of `C` attached to `this`, for the C ABI exports generated by
+/// `#[method(c_export)]`.
+///
+/// # Safety
+///
+/// This is intended to be an internal interface. `this` must be null or point to a live object,
+/// and `args` must point to `num_args` valid variants.
+#[inline]
+pub unsafe fn call_c_export<C, F>(
+    method: F,
+    name: &str,
+    this: *mut sys::godot_object,
+    num_args: libc::c_int,
+    args: *mut *mut sys::godot_variant,
+) -> sys::godot_variant
+where
+    C: crate::export::NativeClass,
+    F: crate::export::Method<C>,
+{
+    crate::export::call_c_export(method, name, this, num_args, args)
+}
+
 /// Plugin type to be used by macros for auto class registration.
 pub struct AutoInitPlugin {
     pub f: fn(init_handle: crate::init::InitHandle),
@@ -250,9 +504,7 @@ pub(crate) struct ManuallyManagedClassPlaceholder;
 unsafe impl crate::object::GodotObject for ManuallyManagedClassPlaceholder {
     type Memory = crate::object::memory::ManuallyManaged;
 
-    fn class_name() -> &'static str {
-        "Object"
-    }
+    const CLASS_NAME: &'static str = "Object";
 }
 
 impl godot_object::Sealed for ManuallyManagedClassPlaceholder {}
@@ -262,13 +514,46 @@ pub(crate) struct ReferenceCountedClassPlaceholder;
 unsafe impl crate::object::GodotObject for ReferenceCountedClassPlaceholder {
     type Memory = crate::object::memory::RefCounted;
 
-    fn class_name() -> &'static str {
-        "Reference"
-    }
+    const CLASS_NAME: &'static str = "Reference";
 }
 
 impl godot_object::Sealed for ReferenceCountedClassPlaceholder {}
 
+impl crate::object::Instanciable for ReferenceCountedClassPlaceholder {
+    fn construct() -> crate::object::Ref<Self, crate::object::ownership::Unique> {
+        unsafe {
+            let ctor = (get_api().godot_get_class_constructor)(b"Reference\0".as_ptr() as *const _)
+                .expect("Reference should have a constructor");
+            let ptr = std::ptr::NonNull::new(ctor())
+                .expect("Reference constructor should not return null");
+            crate::object::Ref::init_from_sys(ptr)
+        }
+    }
+}
+
+/// Stand-in for the `Node` class, for scripts managed by the crate.
+pub(crate) struct NodePlaceholder;
+
+unsafe impl crate::object::GodotObject for NodePlaceholder {
+    type Memory = crate::object::memory::ManuallyManaged;
+
+    const CLASS_NAME: &'static str = "Node";
+}
+
+impl godot_object::Sealed for NodePlaceholder {}
+
+impl crate::object::Instanciable for NodePlaceholder {
+    fn construct() -> crate::object::Ref<Self, crate::object::ownership::Unique> {
+        unsafe {
+            let ctor = (get_api().godot_get_class_constructor)(b"Node\0".as_ptr() as *const _)
+                .expect("Node should have a constructor");
+            let ptr =
+                std::ptr::NonNull::new(ctor()).expect("Node constructor should not return null");
+            crate::object::Ref::init_from_sys(ptr)
+        }
+    }
+}
+
 macro_rules! make_method_table {
     (struct $tablename:ident for $class:ident { $($methods:ident,)* }) => {
         pub(crate) struct $tablename {
@@ -276,6 +561,10 @@ macro_rules! make_method_table {
         }
 
         impl $tablename {
+            /// Name of the class in the engine, and of the bound methods.
+            pub(crate) const BOUND: (&'static str, &'static [&'static str]) =
+                (stringify!($class), &[$(stringify!($methods),)*]);
+
             unsafe fn get_mut() -> &'static mut Self {
                 static mut TABLE: $tablename = $tablename {
                     $($methods: std::ptr::null_mut(),)*
@@ -311,8 +600,13 @@ macro_rules! make_method_table {
 }
 
 make_method_table!(struct ObjectMethodTable for Object {
+    call_deferred,
+    emit_signal,
     get_class,
+    get_instance_id,
     is_class,
+    property_list_changed_notify,
+    set_script,
 });
 
 make_method_table!(struct ReferenceMethodTable for Reference {
@@ -332,4 +626,180 @@ make_method_table!(struct NativeScriptMethodTable for NativeScript {
 // `Engine` is known to the engine as `_Engine`.
 make_method_table!(struct EngineMethodTable for _Engine {
     get_version_info,
+    get_main_loop,
+    is_editor_hint,
+});
+
+make_method_table!(struct SceneTreeMethodTable for SceneTree {
+    get_root,
+});
+
+make_method_table!(struct NodeMethodTable for Node {
+    add_child,
+    set_name,
+    set_physics_process,
+    set_process,
+    set_process_priority,
+});
+
+// `OS` is known to the engine as `_OS`.
+make_method_table!(struct OSMethodTable for _OS {
+    has_feature,
 });
+
+// `ClassDB` is known to the engine as `_ClassDB`.
+make_method_table!(struct ClassDBMethodTable for _ClassDB {
+    class_exists,
+    class_get_method_list,
+    class_get_property_list,
+});
+
+/// Classes and methods bound by the tables above, as `(class, methods)`.
+pub(crate) const INTERNAL_METHODS: &[(&str, &[&str])] = &[
+    ObjectMethodTable::BOUND,
+    ReferenceMethodTable::BOUND,
+    NativeScriptMethodTable::BOUND,
+    EngineMethodTable::BOUND,
+    SceneTreeMethodTable::BOUND,
+    NodeMethodTable::BOUND,
+    OSMethodTable::BOUND,
+    ClassDBMethodTable::BOUND,
+];
+
+/// Returns the names of all properties of the engine class `class`, including inherited ones.
+pub(crate) fn class_property_names(class: &str) -> Vec<String> {
+    use crate::core_types::{Dictionary, FromVariant, GodotString, VariantArray};
+    use crate::object::ownership::Unique;
+
+    let class = GodotString::from_str(class);
+    let no_inheritance: sys::godot_bool = false;
+    let mut ret = sys::godot_array::default();
+
+    let properties = unsafe {
+        let api = get_api();
+        let class_db = (api.godot_global_get_singleton)(b"ClassDB\0".as_ptr() as *mut _);
+
+        let mut args = [
+            class.sys() as *const libc::c_void,
+            &no_inheritance as *const _ as *const libc::c_void,
+        ];
+
+        (api.godot_method_bind_ptrcall)(
+            ClassDBMethodTable::get(api).class_get_property_list,
+            class_db,
+            args.as_mut_ptr(),
+            &mut ret as *mut _ as *mut _,
+        );
+
+        VariantArray::<Unique>::from_sys(ret)
+    };
+
+    properties
+        .iter()
+        .filter_map(|property| {
+            let property = Dictionary::from_variant(&property).ok()?;
+            String::from_variant(&property.get("name")?).ok()
+        })
+        .collect()
+}
+
+/// Returns the argument counts of all methods of the engine class `class` by name, including
+/// inherited ones, or `None` if the class doesn't exist or can't be queried.
+pub(crate) fn class_method_arities(class: &str) -> Option<HashMap<String, usize>> {
+    use crate::core_types::{Dictionary, FromVariant, GodotString, VariantArray};
+    use crate::object::ownership::Unique;
+
+    let api = get_api();
+    let table = ClassDBMethodTable::get(api);
+    if table.class_exists.is_null() || table.class_get_method_list.is_null() {
+        return None;
+    }
+
+    let class = GodotString::from_str(class);
+    let no_inheritance: sys::godot_bool = false;
+    let mut exists: sys::godot_bool = false;
+    let mut ret = sys::godot_array::default();
+
+    let methods = unsafe {
+        let class_db = (api.godot_global_get_singleton)(b"ClassDB\0".as_ptr() as *mut _);
+
+        let mut args = [class.sys() as *const libc::c_void];
+        (api.godot_method_bind_ptrcall)(
+            table.class_exists,
+            class_db,
+            args.as_mut_ptr(),
+            &mut exists as *mut _ as *mut _,
+        );
+
+        if !exists {
+            return None;
+        }
+
+        let mut args = [
+            class.sys() as *const libc::c_void,
+            &no_inheritance as *const _ as *const libc::c_void,
+        ];
+        (api.godot_method_bind_ptrcall)(
+            table.class_get_method_list,
+            class_db,
+            args.as_mut_ptr(),
+            &mut ret as *mut _ as *mut _,
+        );
+
+        VariantArray::<Unique>::from_sys(ret)
+    };
+
+    let arities = methods
+        .iter()
+        .filter_map(|method| {
+            let method = Dictionary::from_variant(&method).ok()?;
+            let name = String::from_variant(&method.get("name")?).ok()?;
+            let args = VariantArray::from_variant(&method.get("args")?).ok()?;
+            Some((name, args.len() as usize))
+        })
+        .collect();
+
+    Some(arities)
+}
+
+/// Calls `Object::property_list_changed_notify` on `obj`, so the editor updates the inspector.
+///
+/// # Safety
+///
+/// `obj` must point to a valid object.
+pub(crate) unsafe fn property_list_changed_notify(obj: *mut sys::godot_object) {
+    let api = get_api();
+    (api.godot_method_bind_ptrcall)(
+        ObjectMethodTable::get(api).property_list_changed_notify,
+        obj,
+        [].as_mut_ptr(),
+        std::ptr::null_mut(),
+    );
+}
+
+/// Returns `true` if the running engine supports the feature `tag`, as in `OS.has_feature`.
+///
+/// Used by `#[cfg_godot]` to decide whether to register classes and methods.
+#[inline]
+pub fn os_has_feature(tag: &str) -> bool {
+    use crate::core_types::GodotString;
+
+    let tag = GodotString::from_str(tag);
+    let mut ret: sys::godot_bool = false;
+
+    unsafe {
+        let api = get_api();
+        let os = (api.godot_global_get_singleton)(b"OS\0".as_ptr() as *mut _);
+
+        let mut args = [tag.sys() as *const libc::c_void];
+
+        (api.godot_method_bind_ptrcall)(
+            OSMethodTable::get(api).has_feature,
+            os,
+            args.as_mut_ptr(),
+            &mut ret as *mut _ as *mut _,
+        );
+    }
+
+    ret
+}
@@ -1,4 +1,8 @@
 //! Interface to Godot's built-in profiler.
+//!
+//! With the `no-profiling` feature, which is enabled by the `strip-tools` feature of `gdnative`,
+//! no data is sent to the profiler: [`profile`] only calls the closure, [`add_data`] does nothing,
+//! and [`profile_sig!`] doesn't build the signature string.
 
 use std::borrow::Cow;
 use std::ffi::{CStr, CString};
@@ -6,6 +10,9 @@ use std::time::{Duration, Instant};
 
 use crate::private::try_get_api;
 
+#[cfg(feature = "alloc-tracking")]
+pub mod alloc;
+
 /// A string encoding information about the code being profiled for Godot's built-in profiler.
 ///
 /// The string should be in the form of `{file}::{line_number}::{tag}`, where `tag` is an
@@ -87,6 +94,13 @@ impl<'a> Signature<'a> {
 }
 
 impl Signature<'static> {
+    /// Returns an empty signature, used by [`profile_sig!`] when profiling is disabled.
+    #[doc(hidden)]
+    #[inline]
+    pub fn empty() -> Self {
+        Signature::from_raw(unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") })
+    }
+
     /// Creates a `Signature` in the correct form using the given variables. The format is
     /// checked at runtime.
     ///
@@ -132,6 +146,10 @@ impl Signature<'static> {
 /// If the number of microseconds in `time` exceeds the range of `u64`.
 #[inline]
 pub fn add_data(signature: Signature<'_>, time: Duration) {
+    if cfg!(feature = "no-profiling") {
+        return;
+    }
+
     if let Some(api) = try_get_api() {
         let time_in_usec = u64::try_from(time.as_micros())
             .expect("microseconds in `time` should not exceed the range of u64");
@@ -149,6 +167,10 @@ pub fn profile<F, R>(signature: Signature<'_>, f: F) -> R
 where
     F: FnOnce() -> R,
 {
+    if cfg!(feature = "no-profiling") {
+        return f();
+    }
+
     let start = Instant::now();
     let ret = f();
     add_data(signature, Instant::now() - start);
@@ -174,10 +196,54 @@ where
 /// ```
 #[macro_export]
 macro_rules! _profile_sig {
+    ($tag:expr) => {
+        $crate::_profile_sig_impl!($tag)
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "no-profiling"))]
+#[macro_export]
+macro_rules! _profile_sig_impl {
     ($tag:expr) => {
         $crate::profiler::Signature::new(file!(), line!(), $tag)
     };
 }
 
+#[doc(hidden)]
+#[cfg(feature = "no-profiling")]
+#[macro_export]
+macro_rules! _profile_sig_impl {
+    ($tag:expr) => {{
+        // Type-check the tag without evaluating it.
+        if false {
+            let _: &str = $tag;
+        }
+        $crate::profiler::Signature::empty()
+    }};
+}
+
 // Export macro in this module
 pub use _profile_sig as profile_sig;
+
+/// Records an allocation of a core type in [`alloc`] if the `alloc-tracking` feature is enabled.
+/// Expands to nothing otherwise.
+macro_rules! track_alloc {
+    ($kind:ident, $bytes:expr) => {
+        #[cfg(feature = "alloc-tracking")]
+        $crate::profiler::alloc::record($crate::profiler::alloc::AllocKind::$kind, $bytes);
+    };
+}
+
+pub(crate) use track_alloc;
+
+/// Records the creation of a `Variant` in [`alloc`] if the `alloc-tracking` feature is enabled
+/// and the engine allocates storage for its type. Expands to nothing otherwise.
+macro_rules! track_variant_alloc {
+    ($variant:expr) => {
+        #[cfg(feature = "alloc-tracking")]
+        $crate::profiler::alloc::record_variant($variant);
+    };
+}
+
+pub(crate) use track_variant_alloc;
@@ -0,0 +1,382 @@
+//! Tracking of allocations of core types made from Rust.
+//!
+//! This module is only available with the `alloc-tracking` feature. When enabled, constructors
+//! of the tracked core types record the number of values created, an estimate of the memory
+//! allocated for them, and the location in the source code they were created from. This can
+//! help to find conversion hot spots that cause frame hitches.
+//!
+//! The counted allocations follow the way the engine stores the data:
+//!
+//! - A `Variant` only allocates for `Transform2D`, `Aabb`, `Basis` and `Transform`, which don't
+//!   fit into the variant itself. Other types are either stored inline, or reference counted,
+//!   so wrapping an existing string, array, dictionary or object in a variant is free.
+//! - Strings, node paths, pool arrays, arrays and dictionaries allocate when their data is
+//!   created from Rust, e.g. by [`GodotString::from_str`][crate::core_types::GodotString::from_str]
+//!   or [`PoolArray::from_vec`][crate::core_types::PoolArray::from_vec].
+//!
+//! Counters accumulate until [`take_report`] is called, so calling it once per frame, e.g. at
+//! the end of a `_process` callback, produces a per-frame report:
+//!
+//! ```no_run
+//! use gdnative::prelude::*;
+//! use gdnative::profiler::alloc;
+//!
+//! fn end_of_frame() {
+//!     let report = alloc::take_report();
+//!     if report.total().count > 1000 {
+//!         godot_warn!("{}", report);
+//!     }
+//! }
+//! ```
+//!
+//! Values returned from the engine or cloned by reference counting are not counted.
+
+use std::fmt;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ahash::AHashMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::core_types::{Variant, VariantType};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static COUNTERS: Lazy<Mutex<AHashMap<(AllocKind, &'static Location<'static>), AllocStats>>> =
+    Lazy::new(|| Mutex::new(AHashMap::new()));
+
+/// Kinds of values whose allocations are tracked.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum AllocKind {
+    /// The storage of a `Variant` holding a `Transform2D`, `Aabb`, `Basis` or `Transform`.
+    Variant,
+    /// A `GodotString`.
+    GodotString,
+    /// A `NodePath`.
+    NodePath,
+    /// The data of a `PoolArray`.
+    PoolArray,
+    /// A `VariantArray`.
+    VariantArray,
+    /// A `Dictionary`.
+    Dictionary,
+}
+
+impl AllocKind {
+    const ALL: [AllocKind; 6] = [
+        AllocKind::Variant,
+        AllocKind::GodotString,
+        AllocKind::NodePath,
+        AllocKind::PoolArray,
+        AllocKind::VariantArray,
+        AllocKind::Dictionary,
+    ];
+}
+
+/// Number of allocations and estimated allocated bytes.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct AllocStats {
+    /// Number of values created.
+    pub count: u64,
+    /// Estimated number of bytes allocated for the values.
+    pub bytes: u64,
+}
+
+impl AllocStats {
+    fn add(&mut self, other: AllocStats) {
+        self.count += other.count;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Allocations of a single kind from a single location in the source code.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AllocSite {
+    /// Kind of the allocated values.
+    pub kind: AllocKind,
+    /// Location the values were created from.
+    pub location: &'static Location<'static>,
+    /// Allocation statistics for this site.
+    pub stats: AllocStats,
+}
+
+/// Report of allocations since the last call to [`take_report`].
+///
+/// The `Display` implementation prints a summary including the top 10 sites.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct AllocReport {
+    sites: Vec<AllocSite>,
+}
+
+impl AllocReport {
+    /// Returns the total statistics for all kinds.
+    #[inline]
+    pub fn total(&self) -> AllocStats {
+        let mut total = AllocStats::default();
+        for site in &self.sites {
+            total.add(site.stats);
+        }
+        total
+    }
+
+    /// Returns the total statistics for `kind`.
+    #[inline]
+    pub fn total_of(&self, kind: AllocKind) -> AllocStats {
+        let mut total = AllocStats::default();
+        for site in self.sites.iter().filter(|site| site.kind == kind) {
+            total.add(site.stats);
+        }
+        total
+    }
+
+    /// Returns all sites, sorted by number of allocations in descending order.
+    #[inline]
+    pub fn sites(&self) -> &[AllocSite] {
+        &self.sites
+    }
+
+    /// Returns up to `n` sites with the most allocations.
+    #[inline]
+    pub fn top_sites(&self, n: usize) -> &[AllocSite] {
+        &self.sites[..n.min(self.sites.len())]
+    }
+
+    /// Returns `true` if no allocations were recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+}
+
+impl fmt::Display for AllocReport {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const TOP_SITES: usize = 10;
+
+        write!(f, "allocations:")?;
+        for (i, kind) in AllocKind::ALL.into_iter().enumerate() {
+            let stats = self.total_of(kind);
+            let separator = if i == 0 { "" } else { "," };
+            write!(
+                f,
+                "{separator} {} {kind:?} ({} bytes)",
+                stats.count, stats.bytes
+            )?;
+        }
+
+        for site in self.top_sites(TOP_SITES) {
+            write!(
+                f,
+                "\n  {}: {} {:?} ({} bytes)",
+                site.location, site.stats.count, site.kind, site.stats.bytes,
+            )?;
+        }
+
+        if self.sites.len() > TOP_SITES {
+            write!(f, "\n  ... and {} more sites", self.sites.len() - TOP_SITES)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Takes the allocations recorded since the last call, resetting all counters.
+#[inline]
+pub fn take_report() -> AllocReport {
+    let counters = std::mem::take(&mut *COUNTERS.lock());
+
+    let mut sites = counters
+        .into_iter()
+        .map(|((kind, location), stats)| AllocSite {
+            kind,
+            location,
+            stats,
+        })
+        .collect::<Vec<_>>();
+
+    sites.sort_by(|a, b| {
+        b.stats
+            .count
+            .cmp(&a.stats.count)
+            .then_with(|| b.stats.bytes.cmp(&a.stats.bytes))
+    });
+
+    AllocReport { sites }
+}
+
+/// Enables or disables recording of allocations. Recording is enabled by default.
+///
+/// Counters recorded so far are kept until the next call to [`take_report`].
+#[inline]
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if allocations are currently recorded.
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Estimated size of the data the engine allocates for a new `VariantArray` on 64-bit
+/// platforms: a reference count and an empty `Vector<Variant>`.
+pub(crate) const ARRAY_PRIVATE_SIZE: usize = 16;
+
+/// Estimated size of the data the engine allocates for a new `Dictionary` on 64-bit platforms:
+/// a reference count and an empty `OrderedHashMap<Variant, Variant>`.
+pub(crate) const DICTIONARY_PRIVATE_SIZE: usize = 32;
+
+/// Records an allocation of `bytes` bytes at the caller's location.
+#[track_caller]
+#[inline]
+pub(crate) fn record(kind: AllocKind, bytes: usize) {
+    if !is_enabled() {
+        return;
+    }
+
+    let location = Location::caller();
+    COUNTERS
+        .lock()
+        .entry((kind, location))
+        .or_default()
+        .add(AllocStats {
+            count: 1,
+            bytes: bytes as u64,
+        });
+}
+
+/// Records the creation of `variant` at the caller's location, if the engine allocates storage
+/// for its type.
+#[track_caller]
+#[inline]
+pub(crate) fn record_variant(variant: &Variant) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Some(bytes) = variant_storage_size(variant.get_type()) {
+        record(AllocKind::Variant, bytes);
+    }
+}
+
+/// Returns the size of the storage that the engine allocates when a value of `ty` is stored in
+/// a variant, or `None` if it's stored inline or reference counted.
+fn variant_storage_size(ty: VariantType) -> Option<usize> {
+    use crate::core_types::{Aabb, Basis, Transform, Transform2D};
+    use std::mem::size_of;
+
+    match ty {
+        VariantType::Transform2D => Some(size_of::<Transform2D>()),
+        VariantType::Aabb => Some(size_of::<Aabb>()),
+        VariantType::Basis => Some(size_of::<Basis>()),
+        VariantType::Transform => Some(size_of::<Transform>()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_types::VariantType as V;
+
+    #[test]
+    fn report_groups_and_sorts_sites() {
+        // Counters are global, so everything is tested in one function to avoid interference.
+        drop(take_report());
+
+        for _ in 0..3 {
+            record(AllocKind::Variant, 24);
+        }
+        for _ in 0..2 {
+            record(AllocKind::GodotString, 8);
+        }
+
+        let report = take_report();
+        assert_eq!(2, report.sites().len());
+        assert_eq!(AllocKind::Variant, report.sites()[0].kind);
+        assert_eq!(
+            AllocStats {
+                count: 3,
+                bytes: 72
+            },
+            report.total_of(AllocKind::Variant)
+        );
+        assert_eq!(
+            AllocStats {
+                count: 2,
+                bytes: 16
+            },
+            report.total_of(AllocKind::GodotString)
+        );
+        assert_eq!(1, report.top_sites(1).len());
+        assert_eq!(5, report.total().count);
+
+        assert!(take_report().is_empty());
+
+        set_enabled(false);
+        record(AllocKind::Variant, 24);
+        set_enabled(true);
+        assert!(take_report().is_empty());
+    }
+
+    #[test]
+    fn only_boxed_math_types_allocate_variant_storage() {
+        for ty in [V::Transform2D, V::Aabb, V::Basis, V::Transform] {
+            assert!(variant_storage_size(ty).is_some(), "{ty:?}");
+        }
+        assert_eq!(Some(48), variant_storage_size(V::Transform));
+
+        // Stored inline in the variant
+        for ty in [
+            V::Nil,
+            V::Bool,
+            V::I64,
+            V::F64,
+            V::Vector2,
+            V::Vector3,
+            V::Rect2,
+            V::Quat,
+            V::Plane,
+            V::Color,
+            V::Rid,
+        ] {
+            assert!(variant_storage_size(ty).is_none(), "{ty:?}");
+        }
+
+        // Reference counted, and tracked when their data is created instead
+        for ty in [
+            V::GodotString,
+            V::NodePath,
+            V::VariantArray,
+            V::Dictionary,
+            V::Object,
+            V::ByteArray,
+            V::Vector3Array,
+        ] {
+            assert!(variant_storage_size(ty).is_none(), "{ty:?}");
+        }
+    }
+
+    #[test]
+    fn report_lists_all_kinds() {
+        let report = AllocReport {
+            sites: vec![AllocSite {
+                kind: AllocKind::PoolArray,
+                location: Location::caller(),
+                stats: AllocStats {
+                    count: 1,
+                    bytes: 40,
+                },
+            }],
+        };
+
+        let summary = report.to_string();
+        let summary = summary.lines().next().unwrap();
+        assert_eq!(
+            "allocations: 0 Variant (0 bytes), 0 GodotString (0 bytes), 0 NodePath (0 bytes), \
+             1 PoolArray (40 bytes), 0 VariantArray (0 bytes), 0 Dictionary (0 bytes)",
+            summary
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Library-wide registry of shared Rust values.
+//!
+//! Services are values that need to be reachable from many `NativeClass`es, such as game
+//! settings, asset caches or connections to external systems. Instead of a `lazy_static` or
+//! `OnceCell` per value, they can be stored here, keyed by their type:
+//!
+//! ```no_run
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//!
+//! use gdnative::prelude::*;
+//! use gdnative::services;
+//!
+//! #[derive(Default)]
+//! struct Score(AtomicU64);
+//!
+//! #[gdnative::init::callbacks]
+//! impl GDNativeCallbacks for MyLibrary {
+//!     fn nativescript_init(handle: InitHandle) {
+//!         services::provide(Score::default());
+//!         // register classes...
+//!     }
+//! }
+//!
+//! fn on_coin_collected() {
+//!     let score = services::get::<Score>().expect("score service should be provided");
+//!     score.0.fetch_add(10, Ordering::Relaxed);
+//! }
+//! # struct MyLibrary;
+//! ```
+//!
+//! Unlike statics, services are tied to the lifetime of the library: they are dropped when it is
+//! terminated, after `GDNativeCallbacks::gdnative_terminate` but while the engine API is still
+//! available. Services are dropped in the reverse order in which they were provided, so a
+//! service may safely use the ones provided before it during its destruction.
+
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+static SERVICES: Lazy<Mutex<Registry>> = Lazy::new(Mutex::default);
+
+/// Stores `value` as the service of type `T`. Returns the previous service of the same type, if
+/// any.
+///
+/// A service that replaces an existing one counts as provided last, and is dropped first.
+#[inline]
+pub fn provide<T>(value: T) -> Option<Arc<T>>
+where
+    T: Any + Send + Sync,
+{
+    provide_shared(Arc::new(value))
+}
+
+/// Stores a shared `value` as the service of type `T`. Returns the previous service of the same
+/// type, if any.
+///
+/// See [`provide`].
+#[inline]
+pub fn provide_shared<T>(value: Arc<T>) -> Option<Arc<T>>
+where
+    T: Any + Send + Sync,
+{
+    // The previous service is dropped by the caller, outside the lock.
+    let old = SERVICES.lock().insert(value);
+    old.map(downcast)
+}
+
+/// Returns the service of type `T`, or `None` if it has not been provided.
+#[inline]
+pub fn get<T>() -> Option<Arc<T>>
+where
+    T: Any + Send + Sync,
+{
+    SERVICES.lock().get(TypeId::of::<T>()).map(downcast)
+}
+
+/// Returns `true` if a service of type `T` has been provided.
+#[inline]
+pub fn contains<T>() -> bool
+where
+    T: Any + Send + Sync,
+{
+    SERVICES.lock().get(TypeId::of::<T>()).is_some()
+}
+
+/// Removes the service of type `T` and returns it, or `None` if it has not been provided.
+///
+/// The service is dropped once all `Arc`s to it are gone.
+#[inline]
+pub fn remove<T>() -> Option<Arc<T>>
+where
+    T: Any + Send + Sync,
+{
+    let old = SERVICES.lock().remove(TypeId::of::<T>());
+    old.map(downcast)
+}
+
+/// Drops all services in reverse order of provision. Called during `gdnative_terminate`.
+pub(crate) fn cleanup() {
+    let services = std::mem::take(&mut *SERVICES.lock());
+    services.clear();
+}
+
+fn downcast<T: Any + Send + Sync>(service: Arc<dyn Any + Send + Sync>) -> Arc<T> {
+    service
+        .downcast()
+        .unwrap_or_else(|_| unreachable!("services are keyed by their type"))
+}
+
+#[derive(Default)]
+struct Registry {
+    services: IndexMap<TypeId, Arc<dyn Any + Send + Sync>, ahash::RandomState>,
+}
+
+impl Registry {
+    fn insert<T: Any + Send + Sync>(
+        &mut self,
+        value: Arc<T>,
+    ) -> Option<Arc<dyn Any + Send + Sync>> {
+        let old = self.remove(TypeId::of::<T>());
+        self.services.insert(TypeId::of::<T>(), value);
+        old
+    }
+
+    fn get(&self, type_id: TypeId) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.services.get(&type_id).cloned()
+    }
+
+    fn remove(&mut self, type_id: TypeId) -> Option<Arc<dyn Any + Send + Sync>> {
+        self.services.shift_remove(&type_id)
+    }
+
+    /// Drops the services in reverse order of insertion, one at a time, so that their `Drop`
+    /// implementations don't observe services provided after them.
+    fn clear(mut self) {
+        while let Some((_, service)) = self.services.pop() {
+            drop(service);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct Recorder(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+    impl Drop for Recorder {
+        fn drop(&mut self) {
+            self.1.lock().unwrap().push(self.0);
+        }
+    }
+
+    // The recorders are only kept for their `Drop` implementation.
+    #[allow(dead_code)]
+    struct First(Recorder);
+    #[allow(dead_code)]
+    struct Second(Recorder);
+    #[allow(dead_code)]
+    struct Third(Recorder);
+
+    #[test]
+    fn registry_drops_in_reverse_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = Registry::default();
+
+        registry.insert(Arc::new(First(Recorder("first", log.clone()))));
+        registry.insert(Arc::new(Second(Recorder("second", log.clone()))));
+        registry.insert(Arc::new(Third(Recorder("third", log.clone()))));
+
+        // Replacing a service moves it to the end.
+        let old = registry.insert(Arc::new(First(Recorder("first again", log.clone()))));
+        drop(old);
+        assert_eq!(vec!["first"], *log.lock().unwrap());
+
+        registry.clear();
+        assert_eq!(
+            vec!["first", "first again", "third", "second"],
+            *log.lock().unwrap(),
+        );
+    }
+
+    #[test]
+    fn services_by_type() {
+        struct Counter(u32);
+        struct Unused;
+
+        assert!(provide(Counter(1)).is_none());
+        assert_eq!(1, get::<Counter>().unwrap().0);
+        assert!(contains::<Counter>());
+        assert!(get::<Unused>().is_none());
+
+        let old = provide(Counter(2)).expect("previous service");
+        assert_eq!(1, old.0);
+        assert_eq!(2, get::<Counter>().unwrap().0);
+
+        assert_eq!(2, remove::<Counter>().unwrap().0);
+        assert!(!contains::<Counter>());
+    }
+}
@@ -0,0 +1,516 @@
+//! Background threads running Rust code, with results delivered on the main thread.
+//!
+//! Most of the engine API may only be used from the main thread, so the results of work done
+//! on other threads have to be brought back before they can be applied to the scene. A worker
+//! is a thread that sends messages of a single type through an [`Outbox`]. The messages are
+//! passed to a callback on the main thread once per frame:
+//!
+//! ```no_run
+//! use gdnative::prelude::*;
+//! use gdnative::worker;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Node)]
+//! struct Terrain;
+//!
+//! #[methods]
+//! impl Terrain {
+//!     fn new(_base: &Node) -> Self {
+//!         Terrain
+//!     }
+//!
+//!     #[method]
+//!     fn _ready(&self, #[base] base: TRef<Node>) {
+//!         let base = base.claim();
+//!         worker::spawn(
+//!             "terrain generator",
+//!             |outbox| {
+//!                 for chunk in 0..64 {
+//!                     if !outbox.send(chunk) {
+//!                         // The worker was stopped, or the library is being unloaded.
+//!                         return;
+//!                     }
+//!                 }
+//!             },
+//!             move |chunk: i64| {
+//!                 // Called on the main thread.
+//!                 let base = unsafe { base.assume_safe() };
+//!                 base.emit_signal("chunk_generated", &[chunk.to_variant()]);
+//!             },
+//!         );
+//!     }
+//! }
+//! ```
+//!
+//! Threads that only need to emit signals, e.g. to report progress, can use a
+//! [`DeferredSignalEmitter`](crate::export::DeferredSignalEmitter) instead.
+//!
+//! Messages are delivered during the `nativescript_frame` callback, which Godot only invokes if
+//! any NativeScripts are in use. [`poll`] can be called to deliver them at other times.
+//!
+//! When the library is terminated, all workers are stopped, and the main thread waits for them
+//! to exit before `GDNativeCallbacks::gdnative_terminate` is invoked. Long-running workers
+//! should check [`Outbox::is_stopped`] regularly to make this quick.
+
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::panic::{self, catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::object::ThreadSafeSingleton;
+
+static WORKERS: Lazy<Mutex<Registry>> = Lazy::new(Mutex::default);
+
+/// Spawns a worker thread named `name`, which runs `work`. Messages sent through the
+/// [`Outbox`] are passed to `on_message` on the main thread, in the order they were sent.
+///
+/// # Panics
+///
+/// If the thread can't be created.
+#[inline]
+pub fn spawn<T, F, H>(name: &str, work: F, on_message: H) -> Worker
+where
+    T: Send + 'static,
+    F: FnOnce(Outbox<T>) + Send + 'static,
+    H: FnMut(T) + Send + 'static,
+{
+    let (worker, entry) = start(name, work, on_message);
+    WORKERS.lock().entries.push(entry);
+    worker
+}
+
+/// Delivers the messages sent by workers so far, and cleans up workers that have exited.
+///
+/// This is called automatically every frame, and should only be called on the main thread.
+/// Workers spawned by the callbacks are polled for the first time on the next call.
+#[inline]
+pub fn poll() {
+    let mut polled = std::mem::take(&mut *WORKERS.lock());
+    polled.poll();
+
+    // Keeps the workers spawned in the meantime after the existing ones.
+    let mut workers = WORKERS.lock();
+    polled.entries.append(&mut workers.entries);
+    *workers = polled;
+}
+
+/// Calls `f` with the singleton `T` for each of `items` on multiple threads, and returns the
+/// results in the order of `items`.
+///
+/// The items are split into one batch per available core. Unlike [`spawn`], this blocks the
+/// calling thread until all calls have returned. Only singletons that are safe to use from any
+/// thread can be passed, e.g. to load resources in parallel:
+///
+/// ```no_run
+/// use gdnative::api::ResourceLoader;
+/// use gdnative::worker;
+///
+/// let paths = vec!["res://a.tres", "res://b.tres", "res://c.tres"];
+/// let resources = worker::par_call(paths, |loader: &ResourceLoader, path| {
+///     loader.load(path, "", false)
+/// });
+/// ```
+///
+/// # Panics
+///
+/// If a call to `f` panics, the panic is propagated after all threads have finished.
+#[inline]
+pub fn par_call<T, I, R, F>(items: Vec<I>, f: F) -> Vec<R>
+where
+    T: ThreadSafeSingleton,
+    I: Send,
+    R: Send,
+    F: Fn(&T, I) -> R + Sync,
+{
+    let singleton = T::singleton();
+    let threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .clamp(1, items.len().max(1));
+    let batch_size = (items.len() + threads - 1) / threads;
+
+    let mut items = items.into_iter();
+    let batches = (0..threads)
+        .map(|_| items.by_ref().take(batch_size).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = batches
+            .into_iter()
+            .map(|batch| {
+                scope.spawn(move || {
+                    batch
+                        .into_iter()
+                        .map(|item| f(singleton, item))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+/// Stops all workers and waits for them to exit. Called during `gdnative_terminate`.
+pub(crate) fn shutdown() {
+    let workers = std::mem::take(&mut *WORKERS.lock());
+    workers.shutdown();
+}
+
+/// Spawns the thread of a worker, returning the entry to be polled on the main thread.
+fn start<T, F, H>(name: &str, work: F, on_message: H) -> (Worker, Entry)
+where
+    T: Send + 'static,
+    F: FnOnce(Outbox<T>) + Send + 'static,
+    H: FnMut(T) + Send + 'static,
+{
+    let state = Arc::new(State {
+        name: name.into(),
+        stopped: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    });
+
+    let (sender, receiver) = mpsc::channel();
+    let outbox = Outbox {
+        sender,
+        state: state.clone(),
+    };
+
+    let thread = thread::Builder::new()
+        .name(name.into())
+        .spawn({
+            let state = state.clone();
+            move || {
+                let _finished = FinishGuard(state);
+                work(outbox);
+            }
+        })
+        .expect("failed to spawn worker thread");
+
+    let entry = Entry {
+        state: state.clone(),
+        thread: Some(thread),
+        inbox: Box::new(Inbox {
+            receiver,
+            on_message,
+        }),
+    };
+
+    (Worker { state }, entry)
+}
+
+/// Handle to a worker thread.
+///
+/// Dropping the handle does not stop the worker.
+pub struct Worker {
+    state: Arc<State>,
+}
+
+impl Worker {
+    /// Returns the name of the worker thread.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// Requests the worker to stop. Messages that have not been delivered yet are discarded,
+    /// and later calls to [`Outbox::send`] fail.
+    ///
+    /// This doesn't interrupt the thread, which is expected to check [`Outbox::is_stopped`].
+    #[inline]
+    pub fn stop(&self) {
+        self.state.stopped.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if [`stop`][Self::stop] has been called, or the library is being
+    /// terminated.
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.state.is_stopped()
+    }
+
+    /// Returns `true` if the worker has exited, whether normally or by panicking. Messages sent
+    /// before that may still be pending.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.state.finished.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for Worker {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker")
+            .field("name", &self.state.name)
+            .field("stopped", &self.is_stopped())
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+/// Sending end of a worker's messages, passed to the worker thread.
+pub struct Outbox<T> {
+    sender: Sender<T>,
+    state: Arc<State>,
+}
+
+impl<T> Outbox<T> {
+    /// Queues `message` for delivery on the main thread. Returns `false` if the worker has been
+    /// stopped, in which case the message is discarded.
+    #[inline]
+    pub fn send(&self, message: T) -> bool {
+        !self.state.is_stopped() && self.sender.send(message).is_ok()
+    }
+
+    /// Returns `true` if the worker has been stopped, either with [`Worker::stop`] or because
+    /// the library is being terminated. The worker should exit as soon as possible.
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.state.is_stopped()
+    }
+}
+
+impl<T> Clone for Outbox<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Outbox {
+            sender: self.sender.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Outbox<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Outbox")
+            .field("name", &self.state.name)
+            .finish()
+    }
+}
+
+struct State {
+    name: String,
+    stopped: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl State {
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+}
+
+/// Marks the worker as finished when the thread exits, including by panicking.
+struct FinishGuard(Arc<State>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.finished.store(true, Ordering::Release);
+    }
+}
+
+/// Receiving end of a worker's messages, type-erased so workers can be stored together.
+trait Deliver: Send {
+    /// Delivers the pending messages. Returns `false` once all senders have been dropped.
+    fn deliver(&mut self) -> bool;
+}
+
+struct Inbox<T, H> {
+    receiver: Receiver<T>,
+    on_message: H,
+}
+
+impl<T: Send, H: FnMut(T) + Send> Deliver for Inbox<T, H> {
+    fn deliver(&mut self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => (self.on_message)(message),
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        }
+    }
+}
+
+struct Entry {
+    state: Arc<State>,
+    thread: Option<JoinHandle<()>>,
+    inbox: Box<dyn Deliver>,
+}
+
+impl Entry {
+    /// Delivers the pending messages. Returns `false` if the worker is done and can be removed.
+    fn poll(&mut self) -> bool {
+        if self.state.is_stopped() {
+            // Dropping the receiver makes further sends fail, so the thread can't block the
+            // registry from cleaning up.
+            self.inbox = Box::new(Stopped);
+        } else {
+            match catch_unwind(AssertUnwindSafe(|| self.inbox.deliver())) {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(err) => {
+                    godot_error!(
+                        "gdnative-core: message callback of worker `{}` panicked",
+                        self.state.name,
+                    );
+                    crate::private::print_panic_error(err);
+                    self.state.stopped.store(true, Ordering::Release);
+                    self.inbox = Box::new(Stopped);
+                }
+            }
+        }
+
+        let finished = self.state.finished.load(Ordering::Acquire);
+        if finished {
+            self.join();
+        }
+        !finished
+    }
+
+    fn join(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            if let Err(err) = thread.join() {
+                godot_error!("gdnative-core: worker `{}` panicked", self.state.name);
+                crate::private::print_panic_error(err);
+            }
+        }
+    }
+}
+
+/// Inbox of a stopped worker, which discards all messages.
+struct Stopped;
+
+impl Deliver for Stopped {
+    fn deliver(&mut self) -> bool {
+        true
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    fn poll(&mut self) {
+        self.entries.retain_mut(Entry::poll);
+    }
+
+    fn shutdown(mut self) {
+        for entry in &self.entries {
+            entry.state.stopped.store(true, Ordering::Release);
+        }
+        for entry in &mut self.entries {
+            entry.inbox = Box::new(Stopped);
+        }
+        for entry in &mut self.entries {
+            entry.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::sync_channel;
+
+    use super::*;
+
+    fn wait_until_finished(worker: &Worker) {
+        while !worker.is_finished() {
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn messages_delivered_in_order_on_poll() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (worker, entry) = start(
+            "test worker",
+            |outbox| {
+                for i in 0..10 {
+                    assert!(outbox.send(i));
+                }
+            },
+            {
+                let received = received.clone();
+                move |i: i32| received.lock().push(i)
+            },
+        );
+        let mut registry = Registry {
+            entries: vec![entry],
+        };
+
+        assert_eq!("test worker", worker.name());
+        wait_until_finished(&worker);
+        assert!(received.lock().is_empty());
+
+        registry.poll();
+        assert_eq!((0..10).collect::<Vec<_>>(), *received.lock());
+        assert!(registry.entries.is_empty());
+    }
+
+    #[test]
+    fn stopped_worker_discards_messages() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (go, wait) = sync_channel(0);
+        let (worker, entry) = start(
+            "stopped worker",
+            move |outbox| {
+                wait.recv().unwrap();
+                assert!(outbox.is_stopped());
+                assert!(!outbox.send(1));
+            },
+            {
+                let received = received.clone();
+                move |i: i32| received.lock().push(i)
+            },
+        );
+        let mut registry = Registry {
+            entries: vec![entry],
+        };
+
+        worker.stop();
+        assert!(worker.is_stopped());
+        go.send(()).unwrap();
+        wait_until_finished(&worker);
+
+        registry.poll();
+        assert!(received.lock().is_empty());
+        assert!(registry.entries.is_empty());
+    }
+
+    #[test]
+    fn shutdown_stops_and_joins_workers() {
+        let (worker, entry) = start(
+            "long-running worker",
+            |outbox| {
+                while outbox.send(()) {
+                    thread::yield_now();
+                }
+            },
+            |()| {},
+        );
+        let registry = Registry {
+            entries: vec![entry],
+        };
+
+        registry.shutdown();
+        assert!(worker.is_stopped());
+        assert!(worker.is_finished());
+    }
+}
@@ -24,7 +24,17 @@ pub(crate) fn derive_callbacks(
     }
 
     let prefix = match prefix {
-        Some(Lit::Str(s)) => s.value(),
+        Some(Lit::Str(s)) => {
+            let value = s.value();
+            if !is_symbol_prefix(&value) {
+                return Err(syn::Error::new(
+                    s.span(),
+                    "prefix must consist of ASCII letters, digits and underscores, and not start with a digit, \
+                    since it's part of the exported symbol names",
+                ));
+            }
+            value
+        }
         Some(lit) => return Err(syn::Error::new(lit.span(), "expecting string literal")),
         None => "godot_".into(),
     };
@@ -55,6 +65,11 @@ pub(crate) fn derive_callbacks(
         Span::call_site(),
     );
 
+    // Spanned to user code, so deprecation warnings aren't suppressed as coming from a macro.
+    let platform_diagnostics = quote_spanned! { self_ty.span() =>
+        const _: () = #gdnative_core::init::private::platform_diagnostics::INVENTORY;
+    };
+
     Ok(quote! {
         #item_impl
 
@@ -62,6 +77,8 @@ pub(crate) fn derive_callbacks(
         const _: () = {
             impl #gdnative_core::init::private::TheGDNativeCallbacksAttributeIsRequired for #self_ty {}
 
+            #platform_diagnostics
+
             #[no_mangle]
             #[doc(hidden)]
             #[allow(unused_unsafe)]
@@ -128,3 +145,26 @@ pub(crate) fn derive_callbacks(
         };
     })
 }
+
+/// Returns `true` if `prefix` can start the name of an exported C symbol.
+fn is_symbol_prefix(prefix: &str) -> bool {
+    !prefix.starts_with(|c: char| c.is_ascii_digit())
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_prefix() {
+        assert!(is_symbol_prefix("godot_"));
+        assert!(is_symbol_prefix("my_game2_"));
+        assert!(is_symbol_prefix(""));
+        assert!(!is_symbol_prefix("2d_"));
+        assert!(!is_symbol_prefix("my-game_"));
+        assert!(!is_symbol_prefix("jeu_é_"));
+    }
+}
@@ -14,6 +14,7 @@ mod init;
 mod methods;
 mod native_script;
 mod profiled;
+mod reserved;
 mod syntax;
 mod utils;
 mod varargs;
@@ -48,10 +49,103 @@ mod variant;
 /// they are registered to. Any number of mix-ins can be applied to any number of compatible
 /// types. This can be useful for reusing generics `impl`s, or organizing code for big interfaces.
 ///
+/// ## Traits: `#[methods(mixin = "Name")]` on a trait definition
+///
+/// Applied to a trait definition, `#[methods]` creates a mix-in that exports the methods of the
+/// trait marked with `#[method]` on every class implementing it, so that the trait defines a
+/// script interface shared by all of them. Both required methods and methods with a default
+/// implementation can be exported, and only the marked ones are. The mix-in must be named, and
+/// is registered to each class with `builder.mixin::<Name>()`. Exported methods are called
+/// through the trait, using the implementation of the class.
+///
+/// The trait must not be generic. Classes the mix-in is registered to need a user data type
+/// that supports the receivers of the exported methods, e.g. `MapMut` for `&mut self`.
+///
 /// Additionally, the attribute accepts the following arguments:
 ///
 /// - `#[methods(pub)]`<br>
-/// Mix-in types are private by default. The `pub` argument makes them public instead.
+///   Mix-in types are private by default. The `pub` argument makes them public instead.
+///
+/// - `#[methods(state = "Type")]`<br>
+///   Declares per-instance state for a named mix-in, stored alongside the user data of each
+///   instance of the classes it's registered to. The state is created with `Default` on first
+///   access through [`MixinState`](../gdnative/export/struct.MixinState.html).
+///
+/// - `#[methods(rename_all = "camelCase")]`<br>
+///   Exports the methods of the block under names following a naming convention, instead of
+///   their Rust names. Supported conventions are `"camelCase"`, `"PascalCase"`, `"snake_case"`,
+///   `"SCREAMING_SNAKE_CASE"`, `"lowercase"` and `"UPPERCASE"`. Methods with an explicit
+///   `#[method(name = "...")]` keep that name, and methods starting with an underscore, such as
+///   `_ready`, are never renamed.
+///
+/// ## Signals: `#[signal]`
+///
+/// A function with an empty body marked with `#[signal]` declares a signal instead of a
+/// method. The signal is named after the function and has its parameters, with the types and
+/// hints of their [`Export`](../gdnative/export/trait.Export.html) implementations. Doc comments
+/// on the function are shown as the documentation of the signal in the editor. The function
+/// itself is left as is and can't be called from scripts.
+///
+/// The attribute accepts the following arguments:
+///
+/// - `#[signal(name = "name")]`<br>
+///   Registers the signal under `name` instead of the name of the function.
+///
+/// - `#[signal(flags = "DEFERRED | ONESHOT")]`<br>
+///   The [`ConnectFlags`](../gdnative/export/struct.ConnectFlags.html) that listeners are expected
+///   to connect with, which are listed in the documentation of the signal.
+///
+/// Parameters accept `#[hint(expr)]` for the export hint of their type, `#[default(expr)]` for
+/// their default value and `#[usage(expr)]` for their `PropertyUsage`:
+///
+/// ```
+/// use gdnative::export::hint::{FloatHint, RangeHint};
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Rocket;
+///
+/// #[methods]
+/// impl Rocket {
+///     /// Emitted when touching the ground.
+///     #[signal(flags = "ONESHOT")]
+///     fn landed(
+///         #[hint(FloatHint::Range(RangeHint::new(0.0, 100.0)))] speed: f64,
+///         #[default(GodotString::from("Kerosene"))] fuel_type: GodotString,
+///     ) {
+///     }
+/// }
+/// ```
+///
+/// ## Strict typing: `#[deny(unconverted_variants)]`
+///
+/// Parameters and return values of type `Variant` are passed to and from the engine as is,
+/// and show up untyped in the script interface. Adding `#[deny(unconverted_variants)]` to a
+/// `#[methods]` block rejects exported methods that use `Variant` in their parameter or return
+/// types, including inside other types such as `Option<Variant>` or `Vec<Variant>`. Single
+/// methods can be exempted with `#[allow(unconverted_variants)]`.
+///
+/// The check is syntactic, so type aliases of `Variant` are not detected.
+///
+/// ```compile_fail
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Reference)]
+/// #[no_constructor]
+/// struct Foo {}
+///
+/// #[methods]
+/// #[deny(unconverted_variants)]
+/// impl Foo {
+///     #[method]
+///     fn foo(&self, bar: Variant) -> i64 {
+///         bar.to().unwrap_or_default()
+///     }
+/// }
+/// ```
 ///
 /// ## Example
 ///
@@ -98,6 +192,44 @@ mod variant;
 ///     }
 /// }
 /// ```
+///
+/// ### Trait
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[methods(mixin = "DamageableMethods")]
+/// trait Damageable {
+///     #[method]
+///     fn take_damage(&mut self, amount: i64);
+///
+///     #[method]
+///     fn is_destructible(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Reference)]
+/// #[register_with(register_crate)]
+/// #[no_constructor]
+/// struct Crate {
+///     health: i64,
+/// }
+///
+/// fn register_crate(builder: &ClassBuilder<Crate>) {
+///     builder.mixin::<DamageableMethods>();
+/// }
+///
+/// #[methods]
+/// impl Crate {}
+///
+/// impl Damageable for Crate {
+///     fn take_damage(&mut self, amount: i64) {
+///         self.health -= amount;
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn methods(meta: TokenStream, input: TokenStream) -> TokenStream {
     let args =
@@ -108,6 +240,14 @@ pub fn methods(meta: TokenStream, input: TokenStream) -> TokenStream {
             Err(err) => return error_with_input(input, err),
         };
 
+    // Trait definitions declare methods that are exported by all implementing classes.
+    if let Ok(item_trait) = syn::parse::<syn::ItemTrait>(input.clone()) {
+        return match methods::derive_trait_methods(args, item_trait) {
+            Ok(ts) => ts.into(),
+            Err(err) => error_with_input(input, err),
+        };
+    }
+
     let impl_block = match syn::parse::<ItemImpl>(input.clone()) {
         Ok(impl_block) => impl_block,
         Err(err) => return error_with_input(input, err),
@@ -194,6 +334,43 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 /// Use the given type as the user-data wrapper. See the module-level docs on
 /// `gdnative::user_data` for more information.
 ///
+/// With `#[user_data(auto)]`, the wrapper is chosen from the types of the fields, which is
+/// convenient for classes that share state with threads outside of Godot through `Arc`s:
+///
+/// - `ArcData<Self>` if all fields are `Arc`s. Methods can only take `&self`.
+/// - `MutexData<Self>` if some fields are `Arc`s.
+/// - `DefaultUserData<Self>` otherwise.
+///
+/// Fields that aren't `Send`, or `Sync` for `ArcData`, are reported as errors at the field.
+///
+/// ```
+/// use std::sync::Arc;
+/// use gdnative::prelude::*;
+///
+/// struct GameState {
+///     seed: u64,
+/// }
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[user_data(auto)] // `ArcData<Self>`
+/// #[no_constructor]
+/// struct World {
+///     state: Arc<GameState>,
+/// }
+///
+/// #[methods]
+/// impl World {
+///     #[method]
+///     fn seed(&self) -> u64 {
+///         self.state.seed
+///     }
+/// }
+/// ```
+///
+/// NativeClass types can't have lifetime parameters, since their instances are owned by Godot.
+/// Store shared state in an `Arc` instead of borrowing it.
+///
 /// ### `#[register_with(path::to::function)]`
 ///
 /// Use a custom function to register signals, properties or methods, in addition
@@ -231,6 +408,107 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// See documentation on `Instance::emplace` for an example on how this can be used.
 ///
+/// ### `#[cfg_godot(predicate)]`
+///
+/// Only registers the type with the engine if `predicate` holds on the platform the game is
+/// running on. Unlike `#[cfg]`, the predicate is checked at init time through `OS.has_feature`,
+/// so a single library can contain platform-specific classes without failing to register them,
+/// or panicking, on other platforms. It can be combined with `#[cfg]` to also exclude code from
+/// the binary.
+///
+/// The following predicates are supported:
+///
+/// - `platform = "name"`, where `name` is one of `android`, `ios`, `html5`, `macos`, `windows`,
+///   `linux`, `uwp` or `server`. This is true if the library is compiled for the platform, and
+///   the engine reports the corresponding feature tag (e.g. `"Android"`).
+/// - `feature = "tag"`, which is true if `OS.has_feature("tag")` returns `true`. This can be used
+///   for any feature tag, including custom ones defined in the export presets.
+/// - `any(...)`, `all(...)` and `not(...)`, which work like their `#[cfg]` counterparts.
+///
+/// If multiple `#[cfg_godot]` attributes are present, all of them must hold. The attribute can
+/// also be placed on exported methods, in which case only the method is skipped:
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[cfg_godot(any(platform = "android", platform = "ios"))]
+/// struct TouchTools;
+///
+/// #[methods]
+/// impl TouchTools {
+///     fn new(_base: &Node) -> Self {
+///         TouchTools
+///     }
+///
+///     #[method]
+///     #[cfg_godot(feature = "debug")]
+///     fn dump_touches(&self) {}
+/// }
+/// ```
+///
+/// Skipped classes are not registered at all, so scripts referring to them fail to load.
+///
+/// ### `#[native_class(rename = "Name", rename_all = "camelCase")]`
+///
+/// `rename` registers the class under a different name than the Rust type. It can't be used
+/// with generic types, which are registered with
+/// [`InitHandle::add_class_as`][gdnative::init::InitHandle::add_class_as] instead.
+///
+/// `rename_all` exports properties under names following a naming convention, accepting the
+/// same conventions as [`#[methods(rename_all)]`](attr.methods.html). Properties with an
+/// explicit `name` or `path` keep it. Together, these help match the style of existing
+/// GDScript code:
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[native_class(rename = "PlayerStats", rename_all = "camelCase")]
+/// #[no_constructor]
+/// struct Stats {
+///     #[property]
+///     max_health: i64, // `maxHealth`
+///     #[property(name = "armor_class")]
+///     armor: i64, // `armor_class`
+/// }
+///
+/// #[methods(rename_all = "camelCase")]
+/// impl Stats {
+///     #[method]
+///     fn take_damage(&mut self, amount: i64) {} // `takeDamage`
+///
+///     #[method]
+///     fn _ready(&self) {} // `_ready`
+/// }
+/// ```
+///
+///
+/// ## Struct shapes
+///
+/// `NativeClass` can be derived for structs with named fields, tuple structs and unit structs.
+///
+/// Unit structs (`struct Foo;`) have no fields, and thus cannot declare properties through
+/// `#[property]`. They are useful for stateless marker or controller classes. Properties can
+/// still be registered manually with `#[register_with]`.
+///
+/// Fields of tuple structs have no names that could be used for the exported properties.
+/// Each `#[property]` on a tuple struct field must thus name the property explicitly, using
+/// either the `name` or `path` argument:
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Speed(#[property(name = "speed", default = 10.0)] f32);
+///
+/// #[methods]
+/// impl Speed {}
+/// ```
 ///
 /// ## Field attributes
 ///
@@ -246,11 +524,40 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///   Puts the property under the `my_category` category and renames it to
 ///   `my_property_name` in the inspector and for GDScript.
 ///
+/// - `name = "my_property_name"`
+///
+///   Alias for `path`. Required for fields of tuple structs, which are otherwise unnamed.
+///
 /// - `default = 42.0`
 ///
 ///   Sets the default value *in the inspector* for this property. The setter is *not*
 ///   guaranteed to be called by the engine with the value.
 ///
+///   For `Option<T>` properties, the default is an `Option<T>` too, e.g. `default = Some(42)`
+///   or `default = None`.
+///
+/// - `hint = "path::to::function"`
+///
+///   Sets the editor hint for this property. The function takes no arguments and returns
+///   the hint type of the property (e.g. [`FloatHint`][gdnative::export::hint::FloatHint] for
+///   `f32`), or anything that converts into it, such as a
+///   [`RangeHint`][gdnative::export::hint::RangeHint] or an
+///   [`ExpEasingHint`][gdnative::export::hint::ExpEasingHint]. See the
+///   [`hint`][gdnative::export::hint] module for all available hints.
+///
+/// - `hint_resource = "Type, OtherType"`
+///
+///   Restricts an object property, such as `Option<Ref<Texture>>`, to resources of the given
+///   Godot classes in the inspector. The file dialog of the inspector then only lists the
+///   files that can be loaded as one of them. Shorthand for a `hint` returning a
+///   [`ResourceHint`][gdnative::export::hint::ResourceHint].
+///
+/// - `hint_file = "*.png, *.jpg"`
+///
+///   Lets a string property be picked with a file dialog in the inspector, showing only the
+///   files matching the filters. Shorthand for a `hint` returning
+///   [`StringHint::file`][gdnative::export::hint::StringHint::file].
+///
 /// - `get` / `get_ref` / `set`
 ///
 ///   Configure getter/setter for property. All of them can accept a path to specify a custom
@@ -262,6 +569,37 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///   `get_ref` use `with_ref_getter` to register getter. In this case, your custom getter
 ///   should return a shared reference `&T`.
 ///
+///   Custom setters can validate the value by returning `Result<(), E>` where `E: Display`.
+///   When an error is returned, the setter should leave the old value in place. The error is
+///   reported through [`set_validation_handler`][gdnative::export::set_validation_handler],
+///   which logs a warning by default:
+///
+///   ```
+///   use gdnative::prelude::*;
+///
+///   #[derive(NativeClass)]
+///   #[no_constructor]
+///   struct Unit {
+///       #[property(get = "Self::health", set = "Self::set_health")]
+///       health: i64,
+///   }
+///
+///   #[methods]
+///   impl Unit {
+///       fn health(&self, _owner: TRef<Reference>) -> i64 {
+///           self.health
+///       }
+///
+///       fn set_health(&mut self, _owner: TRef<Reference>, health: i64) -> Result<(), String> {
+///           if health < 0 {
+///               return Err(format!("health can't be negative, got {health}"));
+///           }
+///           self.health = health;
+///           Ok(())
+///       }
+///   }
+///   ```
+///
 ///   Situations with custom getters/setters and no backing fields require the use of the
 ///   type [`Property<T>`][gdnative::export::Property]. Consult its documentation for
 ///   a deeper elaboration of property exporting.
@@ -270,11 +608,128 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///
 ///   Hides the property from the editor. Does not prevent it from being sent over network or saved in storage.
 ///
+/// - `usage = "FLAG | FLAG"`
+///
+///   Sets the [`PropertyUsage`][gdnative::export::PropertyUsage] flags of the property, separated
+///   by `|`, replacing the default `STORAGE | EDITOR | NETWORK`. For example, `usage = "STORAGE"`
+///   saves the property to scenes, but hides it from the inspector. Can't be combined with
+///   `no_editor`.
+///
 /// - `rpc = "selected_rpc"`
 ///
 ///   Sets the [Multiplayer API RPC Mode](https://docs.godotengine.org/en/stable/classes/class_multiplayerapi.html?highlight=RPC#enumerations) for the property.
 ///   See the `#[method]` documentation below for possible values and their semantics.
 ///
+/// - `visible_if = "path::to::function"`
+///
+///   Only shows the property in the inspector while the function returns `true`. The function
+///   must have the signature `fn(&Self) -> bool`. The property is still saved and accessible
+///   from scripts when hidden.
+///
+/// - `refresh_inspector`
+///
+///   Updates the inspector when the property is changed in the editor. Should be set on the
+///   properties that `visible_if` conditions depend on.
+///
+/// - `group_toggle`
+///
+///   Makes a `bool` property the toggle of the group it is in: the other properties whose
+///   `path` starts with the same group are only shown in the inspector while the toggle is
+///   `true`. Implies `refresh_inspector`. The `path` of the toggle must contain a group, e.g.
+///   `path = "shadows/enabled"`.
+///
+///   ```
+///   # use gdnative::prelude::*;
+///   #[derive(NativeClass)]
+///   #[inherit(Node)]
+///   #[no_constructor]
+///   struct Light {
+///       #[property(path = "shadows/enabled", group_toggle)]
+///       shadows: bool,
+///       #[property(path = "shadows/color")]
+///       shadow_color: Color,
+///   }
+///   ```
+///
+/// Conditionally visible properties are listed by a `_get_property_list` method registered for
+/// the class, which must not be defined by the class itself.
+///
+/// - `storage`
+///
+///   Saves the field as part of the versioned state of the class instead of as its own property,
+///   see [`ClassBuilder::storage`][gdnative::export::ClassBuilder::storage]. The field is still
+///   shown in the inspector and accessible from scripts. Unavailable for `Property<T>` fields.
+///
+/// ### `#[storage(version = 1, migrate = "path::to::function")]`
+///
+/// Sets the version of the state saved by `storage` fields, and the
+/// [`Migration`][gdnative::export::Migration] applied to state saved with older versions. Both
+/// arguments are optional; the version defaults to `1`.
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[storage(version = 2, migrate = "Self::migrate")]
+/// #[no_constructor]
+/// struct Enemy {
+///     #[property(storage)]
+///     health: i64,
+/// }
+///
+/// impl Enemy {
+///     // Version 1 called the field `hp`
+///     fn migrate(_from: u32, fields: Dictionary<Unique>) -> Dictionary<Unique> {
+///         if let Some(hp) = fields.get("hp") {
+///             fields.insert("health", hp);
+///             fields.erase("hp");
+///         }
+///         fields
+///     }
+/// }
+/// ```
+///
+/// ### `PropertyBag` fields
+///
+/// A field of type [`PropertyBag`][gdnative::export::PropertyBag] without a `#[property]`
+/// attribute is registered as the class's dynamic property storage. Properties set from Godot
+/// that are not otherwise known to the class are stored in the bag. At most one such field is
+/// allowed per class.
+///
+/// ### `#[node("path")]`
+///
+/// Declares a field of type `Option<Ref<T>>` that refers to the node at `path` relative to the
+/// base object, which must be a `Node`. The path may use scene unique node syntax, such as
+/// `"%Score"`. The fields are set by
+/// [`ResolveNodes::resolve_nodes`][gdnative::api::utils::ResolveNodes::resolve_nodes], which
+/// is implemented for classes with `#[node]` fields, and returns an error if a node doesn't
+/// exist or isn't a `T`:
+///
+/// ```
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Control)]
+/// #[no_constructor]
+/// struct Hud {
+///     #[node("%Score")]
+///     score: Option<Ref<Label>>,
+///     #[node("Panel/Health")]
+///     health: Option<Ref<ProgressBar>>,
+/// }
+///
+/// #[methods]
+/// impl Hud {
+///     #[method]
+///     fn _ready(&mut self, #[base] base: &Control) {
+///         if let Err(err) = unsafe { self.resolve_nodes(base.upcast()) } {
+///             godot_error!("{}", err);
+///         }
+///     }
+/// }
+/// ```
+///
 /// ### `#[methods]`
 /// Adds the necessary information to a an `impl` block to register the properties and methods with Godot.
 ///
@@ -290,7 +745,9 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// A valid function signature must have:
 /// - Up to one receiver parameter as the first parameter. This can be one of:
-///     - `self`, `&self` or `&mut self`.
+///     - `self`, `&self` or `&mut self`. `&mut self` requires a `user_data` wrapper that can be
+///       mapped mutably. Read-only wrappers such as `OnceData<Self>` are reported as an error at
+///       the receiver.
 ///     - `self: Instance<Self>` or `self: TInstance<Self>`, when the `arbitrary_self_types` feature
 ///       is available. Additionally, `self: Arc<Self>` is allowed when the `user_data` wrapper is
 ///       specified to be `ArcData<Self>`.
@@ -298,11 +755,18 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///       `arbitrary_self_types` is unavailable.
 /// - Up of one of each of the following special arguments, in any order, denoted by the attributes:
 ///     - `#[base]` - A reference to the base/owner object. This may be `&T` or `TRef<T>`m where `T` refers to
-///       the type declared in `#[inherit(T)]` attribute for the `NativeClass` type.
+///       the type declared in `#[inherit(T)]` attribute for the `NativeClass` type. A different type is reported
+///       as an error at the parameter, e.g. when declaring `base: &Label` in a class that inherits `Node`.
 ///     - `#[async_ctx]` - The [async context](gdnative::tasks::Context), for async methods. See the `async` argument
 ///       below.
 /// - Any number of required parameters, which must have the type `Variant` or must implement the `FromVariant` trait.
-///  `FromVariant` is implemented for most common types.
+///   `FromVariant` is implemented for most common types.
+///   Engine objects can also be borrowed as `&T` or `TRef<T>`, e.g. `body: &Node` in a handler
+///   connected to the `body_entered` signal of an `Area`. The arguments are converted like `Ref<T>`, so an
+///   object of the wrong class is reported as an error naming the parameter, and the object must not be
+///   freed during the call. Borrowed objects are unavailable in async methods and as optional parameters.
+///   Similarly, strings can be borrowed as [`GodotStr<'_>`](gdnative::core_types::GodotStr), which avoids
+///   converting them to a `String` on every call. Borrowed strings are unavailable in async methods.
 /// - Any number of optional parameters annotated with `#[opt]`. Same rules as for required parameters apply.
 ///   Optional parameters must appear at the end of the parameter list.
 /// - Return values must implement the `OwnedToVariant` trait (automatically implemented by `ToVariant`)
@@ -381,6 +845,25 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///   }
 ///   ```
 ///
+/// - `err`
+///
+///   Allows the method to return `Result<T, E>` where `E: Display`, so that `?` can be used in
+///   its body. `Ok` values are returned to the caller as usual. Errors are logged along with
+///   the location of the method, and `null` is returned instead. If the object has a
+///   `script_error` signal, it is also emitted with the name of the method and the error
+///   message as arguments, so that errors can be handled from GDScript:
+///
+///   ```ignore
+///   #[method(err)]
+///   fn load_level(&self, path: String) -> Result<i64, std::io::Error> {
+///      let data = std::fs::read(path)?;
+///      Ok(data.len() as i64)
+///   }
+///   ```
+///
+///   Without `err`, `Result` is converted like any other return value, i.e. into a `Dictionary`.
+///   This is not supported for async methods.
+///
 /// - `async`
 ///
 ///   Marks the function as async. This is used for functions that aren't `async` themselves, but return `Future`s instead.
@@ -404,6 +887,56 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///
 ///   ```
 ///
+/// - `c_export` / `c_export = "symbol_name"`
+///
+///   Additionally exports the method as a C ABI function from the GDNative library, so C/C++ code
+///   in the same project can call it directly instead of going through `Object::call`. The
+///   exported function has the following signature, using types from `gdnative_api_struct.gen.h`:
+///
+///   ```c
+///   godot_variant symbol_name(godot_object *owner, int num_args, godot_variant **args);
+///   ```
+///
+///   `owner` must be the base object of an instance of the class. Arguments are checked in the
+///   same way as calls from the engine, and the returned variant must be destroyed by the caller.
+///   If `owner` isn't an instance of the class, an error is printed and `null` is returned.
+///
+///   By default, the symbol is named `gdnative_<Type>_<method>`, where `<Type>` is the name of
+///   the Rust type and `<method>` is the name the method is registered with in Godot. For
+///   example, `#[method(c_export)] fn add(&self, a: i64, b: i64)` on `struct Calc` is exported as
+///   `gdnative_Calc_add`. A custom symbol name can be specified with `c_export = "symbol_name"`.
+///
+///   This is not supported for async methods, or on generic types.
+///
+/// - `no_virtual_check`
+///
+///   Skips checking the signature of the method against the virtual method it overrides. See
+///   below.
+///
+/// - `process` / `physics_process`
+///
+///   Exports the method as `_process` or `_physics_process`, and enables the callback on each
+///   instance when it is constructed, with `set_process(true)` or `set_physics_process(true)`.
+///   The method can have any name, but its signature must match the virtual method:
+///
+///   ```ignore
+///   #[method(physics_process)]
+///   fn move_body(&mut self, #[base] base: &KinematicBody2D, delta: f64) {
+///      base.move_and_slide(self.velocity * delta as f32, Vector2::UP, false, 4, 0.785398, true);
+///   }
+///   ```
+///
+///   This can't be combined with `name`. Bases that don't inherit `Node` are not affected.
+///
+/// #### Reserved names
+///
+/// Exporting a method or property under a name that is a GDScript keyword (like `match` or
+/// `class`), or that is already used by `Object` (like `free`, `get`, `set`, `call` or `script`),
+/// produces a warning, since such members either can't be accessed normally from GDScript, or
+/// are only called some of the time. The same goes for methods named `_init`, which is not called
+/// for NativeScript classes. Renaming the member with `#[method(name = "...")]` or
+/// `#[property(name = "...")]` resolves the warning.
+///
 ///
 /// #### `Node` virtual functions
 ///
@@ -411,6 +944,12 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// It is assumed that every method is exported via `#[method]` attribute. The parameter `#[base] base: &Node` can be omitted if you don't need it.
 ///
+/// Methods whose names start with an underscore are checked against the virtual methods of the
+/// base class at compile time. If the name matches a virtual method, but the number or types of
+/// the arguments, or the return type don't, compilation fails. Types that can't be mapped to a
+/// Godot type, such as custom `FromVariant` types, are not checked. The check can be disabled
+/// for a single method with `#[method(no_virtual_check)]`.
+///
 /// ```ignore
 /// fn _ready(&self, #[base] base: &Node);
 /// ```
@@ -507,7 +1046,17 @@ pub fn profiled(meta: TokenStream, input: TokenStream) -> TokenStream {
 /// <br><br>
 #[proc_macro_derive(
     NativeClass,
-    attributes(inherit, register_with, no_constructor, user_data, property)
+    attributes(
+        inherit,
+        register_with,
+        no_constructor,
+        user_data,
+        property,
+        storage,
+        cfg_godot,
+        native_class,
+        node
+    )
 )]
 pub fn derive_native_class(input: TokenStream) -> TokenStream {
     // Converting the proc_macro::TokenStream into non proc_macro types so that tests
@@ -631,7 +1180,11 @@ pub fn derive_from_varargs(input: TokenStream) -> TokenStream {
 /// the symbols generated by this macro. This is used by Godot on platforms where dynamic linking
 /// is unavailable. The value of this argument should match the
 /// [`GDNativeLibrary::symbol_prefix`][symbol-prefix] property for the library on the Godot side,
-/// defaulting to `godot_`.
+/// defaulting to `godot_`. Since it becomes part of the exported symbol names, the prefix may
+/// only contain ASCII letters, digits and underscores, and must not start with a digit.
+///
+/// When several libraries are statically linked into the same executable, each of them needs
+/// a distinct prefix.
 ///
 /// [symbol-prefix]: https://docs.godotengine.org/en/stable/classes/class_gdnativelibrary.html
 #[proc_macro_attribute]
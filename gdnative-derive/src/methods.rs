@@ -1,21 +1,30 @@
 use syn::{
-    spanned::Spanned, visit::Visit, visit_mut::VisitMut, FnArg, Generics, ImplItem, ItemImpl, Meta,
-    NestedMeta, Pat, PatIdent, Signature, Type,
+    ext::IdentExt, spanned::Spanned, visit::Visit, visit_mut::VisitMut, FnArg, Generics, Ident,
+    ImplItem, ItemImpl, ItemTrait, Meta, NestedMeta, Pat, PatIdent, Signature, TraitItem, Type,
+    TypePath,
 };
 
 use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 
+use crate::syntax::cfg_godot::CfgGodot;
 use crate::syntax::rpc_mode::RpcMode;
 use crate::utils::find_non_concrete;
 
 use self::mixin_args::{MixinArgsBuilder, MixinKind};
+use self::signal::ExportSignal;
 
+mod base_param;
 mod mixin_args;
+mod receiver;
+mod signal;
+mod unconverted_variants;
+mod virtuals;
 
 pub(crate) struct ClassMethodExport {
     pub(crate) class_ty: Box<Type>,
     pub(crate) methods: Vec<ExportMethod>,
+    pub(crate) signals: Vec<ExportSignal>,
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -24,6 +33,8 @@ pub(crate) struct ExportMethod {
     pub(crate) sig: Signature,
     pub(crate) export_args: ExportArgs,
     pub(crate) arg_kind: Vec<ArgKind>,
+    /// `#[cfg_godot]` predicate deciding whether the method is registered
+    pub(crate) cfg_godot: Option<CfgGodot>,
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -259,6 +270,13 @@ impl ExportMethod {
 
             if let FnArg::Typed(arg) = &mut arg {
                 sanitize_self_type(&mut arg.ty, class_name);
+
+                if matches!(kind, ArgKind::Base) {
+                    if let Err(err) = base_param::validate(&arg.ty) {
+                        errors.push(err);
+                        fail = true;
+                    }
+                }
             }
 
             if let ArgKind::Regular { optional } = &kind {
@@ -321,6 +339,7 @@ impl ExportMethod {
                 sig: sig.clone(),
                 export_args,
                 arg_kind,
+                cfg_godot: None,
             })
         }
     }
@@ -332,19 +351,230 @@ pub(crate) struct ExportArgs {
     pub(crate) rpc_mode: Option<RpcMode>,
     pub(crate) name_override: Option<String>,
     pub(crate) is_deref_return: bool,
+    pub(crate) is_err: bool,
     pub(crate) is_async: bool,
+    pub(crate) is_c_export: bool,
+    pub(crate) c_export_symbol: Option<String>,
+    pub(crate) no_virtual_check: bool,
+    pub(crate) processing: Option<Processing>,
+}
+
+/// Processing callback that a method is exported as, with `#[method(process)]` or
+/// `#[method(physics_process)]`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) enum Processing {
+    Idle,
+    Physics,
+}
+
+impl Processing {
+    fn parse(path: &syn::Path) -> Option<Self> {
+        if path.is_ident("process") {
+            Some(Processing::Idle)
+        } else if path.is_ident("physics_process") {
+            Some(Processing::Physics)
+        } else {
+            None
+        }
+    }
+
+    /// Name of the virtual method that the callback is exported as.
+    fn method_name(self) -> &'static str {
+        match self {
+            Processing::Idle => "_process",
+            Processing::Physics => "_physics_process",
+        }
+    }
+
+    fn variant(self) -> Ident {
+        let name = match self {
+            Processing::Idle => "Idle",
+            Processing::Physics => "Physics",
+        };
+        Ident::new(name, Span::call_site())
+    }
 }
 
 pub(crate) fn derive_methods(
     args: Vec<NestedMeta>,
     item_impl: ItemImpl,
+) -> Result<TokenStream2, syn::Error> {
+    expand_methods(args, item_impl, None)
+}
+
+/// Expands `#[methods]` on a trait definition. The exported methods are collected from an impl
+/// block for a type parameter standing in for the implementing classes, which is registered as a
+/// generic mixin.
+pub(crate) fn derive_trait_methods(
+    args: Vec<NestedMeta>,
+    item_trait: ItemTrait,
+) -> Result<TokenStream2, syn::Error> {
+    let gdnative_core = crate::crate_gdnative_core();
+
+    if !item_trait.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            item_trait.generics.span(),
+            "`#[methods]` is not supported on generic traits",
+        ));
+    }
+
+    let class_param = Ident::new("__GdnativeClass", Span::call_site());
+    let trait_ident = &item_trait.ident;
+
+    let mut where_clause: syn::WhereClause = parse_quote! {
+        where #class_param: #gdnative_core::export::NativeClass + #trait_ident
+    };
+
+    // Receivers are only supported by classes whose user data allows the access they need.
+    let (mut map, mut map_mut, mut map_owned) = (false, false, false);
+    let mut items = Vec::new();
+    for item in &item_trait.items {
+        let TraitItem::Method(method) = item else {
+            continue;
+        };
+
+        let is_exported = method
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("method") || attr.path.is_ident("export"));
+
+        if let (true, Some(FnArg::Receiver(receiver))) = (is_exported, method.sig.inputs.first()) {
+            match (&receiver.reference, &receiver.mutability) {
+                (Some(_), None) => map = true,
+                (Some(_), Some(_)) => map_mut = true,
+                (None, _) => map_owned = true,
+            }
+        }
+
+        items.push(ImplItem::Method(syn::ImplItemMethod {
+            attrs: method.attrs.clone(),
+            vis: syn::Visibility::Inherited,
+            defaultness: None,
+            sig: method.sig.clone(),
+            block: parse_quote!({}),
+        }));
+    }
+
+    let user_data_bounds = [(map, "Map"), (map_mut, "MapMut"), (map_owned, "MapOwned")];
+    for (_, bound) in user_data_bounds.into_iter().filter(|(used, _)| *used) {
+        let bound = Ident::new(bound, Span::call_site());
+        where_clause.predicates.push(parse_quote! {
+            <#class_param as #gdnative_core::export::NativeClass>::UserData:
+                #gdnative_core::export::user_data::#bound
+        });
+    }
+
+    let item_impl = ItemImpl {
+        attrs: item_trait.attrs.clone(),
+        defaultness: None,
+        unsafety: None,
+        impl_token: Default::default(),
+        generics: Generics {
+            lt_token: Some(Default::default()),
+            params: std::iter::once(syn::GenericParam::Type(class_param.clone().into())).collect(),
+            gt_token: Some(Default::default()),
+            where_clause: Some(where_clause),
+        },
+        trait_: Some((None, trait_ident.clone().into(), Default::default())),
+        self_ty: Box::new(parse_quote!(#class_param)),
+        brace_token: Default::default(),
+        items,
+    };
+
+    expand_methods(args, item_impl, Some(item_trait))
+}
+
+/// Removes the attributes used by `#[methods]` from a trait definition, as they were removed from
+/// the stand-in impl block. Signatures are taken from the trait, since `Self` is replaced in the
+/// ones of the impl block.
+fn strip_trait(mut item_trait: ItemTrait, impl_block: &ItemImpl) -> TokenStream2 {
+    item_trait.attrs = impl_block.attrs.clone();
+
+    let mut stripped = impl_block.items.iter().filter_map(|item| match item {
+        ImplItem::Method(method) => Some(method),
+        _ => None,
+    });
+
+    let errors = impl_block.items.iter().filter_map(|item| match item {
+        ImplItem::Verbatim(error) => Some(error),
+        _ => None,
+    });
+
+    for item in &mut item_trait.items {
+        let TraitItem::Method(method) = item else {
+            continue;
+        };
+        let stripped = stripped
+            .next()
+            .expect("all trait methods should be in the impl block");
+
+        method.attrs = stripped.attrs.clone();
+        for (arg, stripped_arg) in method.sig.inputs.iter_mut().zip(&stripped.sig.inputs) {
+            match (arg, stripped_arg) {
+                (FnArg::Receiver(arg), FnArg::Receiver(stripped_arg)) => {
+                    arg.attrs = stripped_arg.attrs.clone();
+                }
+                (FnArg::Typed(arg), FnArg::Typed(stripped_arg)) => {
+                    arg.attrs = stripped_arg.attrs.clone();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    quote!(#item_trait #(#errors)*)
+}
+
+fn expand_methods(
+    args: Vec<NestedMeta>,
+    item_impl: ItemImpl,
+    item_trait: Option<ItemTrait>,
 ) -> Result<TokenStream2, syn::Error> {
     let derived = crate::automatically_derived();
     let gdnative_core = crate::crate_gdnative_core();
-    let (impl_block, export) = impl_gdnative_expose(item_impl);
+
+    // `unconverted_variants` is not a rustc lint, so it's removed before the item is emitted.
+    let mut item_impl = item_impl;
+    let deny_unconverted =
+        unconverted_variants::strip_lint(&mut item_impl.attrs, "deny")?.is_some();
+    let mut allow_unconverted = std::collections::HashSet::new();
+    for item in &mut item_impl.items {
+        if let ImplItem::Method(method) = item {
+            if unconverted_variants::strip_lint(&mut method.attrs, "allow")?.is_some() {
+                allow_unconverted.insert(method.sig.ident.clone());
+            }
+        }
+    }
+
+    let (impl_block, mut export) = impl_gdnative_expose(item_impl);
+
+    let trait_span = item_trait
+        .as_ref()
+        .map(|item_trait| item_trait.ident.span());
+    let emitted = match item_trait {
+        Some(item_trait) => strip_trait(item_trait, &impl_block),
+        None => impl_block.to_token_stream(),
+    };
+
+    if deny_unconverted {
+        let mut errors = export
+            .methods
+            .iter()
+            .filter(|method| !allow_unconverted.contains(&method.sig.ident))
+            .flat_map(unconverted_variants::check);
+
+        if let Some(mut error) = errors.next() {
+            error.extend(errors);
+            // The stripped impl block is emitted as well, so that the lint isn't reported as
+            // unknown in addition to the errors.
+            let error = error.to_compile_error();
+            return Ok(quote!(#error #emitted));
+        }
+    }
     let (impl_generics, _, where_clause) = impl_block.generics.split_for_impl();
 
     let class_name = export.class_ty;
+    let trait_path = impl_block.trait_.as_ref().map(|(_, path, _)| path);
 
     let builder = syn::Ident::new("builder", proc_macro2::Span::call_site());
 
@@ -365,6 +595,42 @@ pub(crate) fn derive_methods(
         attr_args_builder.done()?
     };
 
+    // The class of a trait mixin is a type parameter, so the shims are generic functions of the
+    // mixin type instead of associated functions of the class.
+    let trait_mixin = match (trait_span, &args.mixin) {
+        (None, _) => None,
+        (Some(_), Some(MixinKind::Named(ident))) => Some(ident.clone()),
+        (Some(span), _) => {
+            return Err(syn::Error::new(
+                span,
+                "`#[methods]` on a trait must declare a named mixin, e.g. `#[methods(mixin = \"Name\")]`",
+            ))
+        }
+    };
+
+    let (shim_generics, shim_where_clause, shim_owner) = match &trait_mixin {
+        Some(mixin_name) => (Some(&impl_generics), where_clause, quote!(#mixin_name)),
+        None => (None, None, quote!(<#class_name>)),
+    };
+
+    // Processing callbacks are exported under the name of the virtual method.
+    for method in &mut export.methods {
+        if let Some(processing) = method.export_args.processing {
+            method.export_args.name_override = Some(processing.method_name().into());
+        }
+    }
+
+    // Methods without an explicit `name` follow the naming convention of the block
+    if let Some(rule) = args.rename_all {
+        for method in &mut export.methods {
+            let ident = method.sig.ident.to_string();
+            method
+                .export_args
+                .name_override
+                .get_or_insert_with(|| rule.apply(&ident));
+        }
+    }
+
     let non_concrete = find_non_concrete::with_visitor(&impl_block.generics, |v| {
         v.visit_type(&impl_block.self_ty)
     });
@@ -386,6 +652,12 @@ pub(crate) fn derive_methods(
         }
     }
 
+    let mut c_exports = Vec::new();
+    let mut shims = Vec::new();
+
+    // Each method is registered by its own hidden associated function, instead of one large
+    // registration function for the whole impl block. This keeps the generated functions small,
+    // so that changing one method doesn't require recompiling the registration code of all others.
     let methods = export
         .methods
         .into_iter()
@@ -393,6 +665,7 @@ pub(crate) fn derive_methods(
             let ExportMethod {
                 sig,
                 export_args,
+                cfg_godot,
                 ..
             } = &export_method;
 
@@ -424,6 +697,11 @@ pub(crate) fn derive_methods(
                 None
             };
 
+            let warn_reserved_name = crate::reserved::check_method_name(&name_string).map(|message| {
+                let warning = crate::emit_warning(sig_span, "reserved_method_name", message);
+                quote_spanned!(sig_span=>#warning;)
+            });
+
             // See gdnative-core::export::deprecated_reference_return!()
             let warn_deprecated_ref_return = if let syn::ReturnType::Type(_, ty) = &sig.output {
                 if !is_deref_return && matches!(**ty, syn::Type::Reference(_)) {
@@ -441,22 +719,80 @@ pub(crate) fn derive_methods(
                 quote_spanned!(ret_span=>)
             };
 
-            let method = wrap_method(&class_name, &impl_block.generics, &export_method)
+            let method = wrap_method(&class_name, trait_path, &impl_block.generics, &export_method)
                 .unwrap_or_else(|err| err.to_compile_error());
 
-            quote_spanned!( sig_span=>
-                {
-                    #builder.method(#name_string, #method)
-                        .with_rpc_mode(#rpc)
-                        .done_stateless();
+            // The base class and user data can only be named for concrete types.
+            let (check_override, check_base, check_user_data) = if non_concrete.is_none() {
+                (
+                    virtuals::check_override(&class_name, &name_string, &export_method),
+                    Some(base_param::check_types(&class_name, &export_method)),
+                    Some(receiver::check_user_data(&class_name, &export_method)),
+                )
+            } else {
+                (None, None, None)
+            };
+
+            if export_args.is_c_export {
+                c_exports.push(
+                    wrap_c_export(&class_name, sig, &name_string, export_args, non_concrete, &method)
+                        .unwrap_or_else(|err| err.to_compile_error()),
+                );
+            }
+
+            let enable_processing = export_args.processing.map(|processing| {
+                let variant = processing.variant();
+                quote_spanned!( sig_span=>
+                    #builder.enable_processing(#gdnative_core::export::Processing::#variant);
+                )
+            });
+
+            let register = quote_spanned!( sig_span=>
+                #builder.method(#name_string, #method)
+                    .with_rpc_mode(#gdnative_core::export::#rpc)
+                    .done_stateless();
+                #enable_processing
+            );
+
+            let register = match cfg_godot {
+                Some(cfg_godot) => quote_spanned!( sig_span=>
+                    if #cfg_godot {
+                        #register
+                    }
+                ),
+                None => register,
+            };
+
+            let shim = format_ident!("__gdnative_register_{}", name.unraw());
+
+            shims.push(quote! {
+                #[doc(hidden)]
+                #[inline(never)]
+                #[allow(non_snake_case)]
+                fn #shim #shim_generics (#builder: &#gdnative_core::export::ClassBuilder<#class_name>) #shim_where_clause {
+                    #check_base
+                    #check_user_data
+                    #check_override
+                    #register
 
                     #warn_deprecated_export
                     #warn_deprecated_ref_return
+                    #warn_reserved_name
                 }
+            });
+
+            quote_spanned!( sig_span=>
+                #shim_owner::#shim(#builder);
             )
         })
         .collect::<Vec<_>>();
 
+    let signals = export
+        .signals
+        .iter()
+        .map(|signal| signal.register(&builder))
+        .collect::<Vec<_>>();
+
     match args.mixin {
         Some(mixin_kind) => {
             let vis = args.pub_.then(|| quote!(pub));
@@ -471,6 +807,14 @@ pub(crate) fn derive_methods(
                 }
             };
 
+            let state = args.state.map_or_else(|| quote!(()), |ty| quote!(#ty));
+
+            let shim_impl = if trait_mixin.is_some() {
+                quote!(impl #mixin_name)
+            } else {
+                quote!(impl #impl_generics #class_name #where_clause)
+            };
+
             let body = quote! {
                 #derived
                 #vis struct #mixin_name {
@@ -479,14 +823,23 @@ pub(crate) fn derive_methods(
 
                 #derived
                 impl #gdnative_core::private::mixin::Sealed for #mixin_name {}
-                #derived
-                impl #impl_generics #gdnative_core::export::Mixin<#class_name> for #mixin_name #where_clause {
-                    fn register(#builder: &#gdnative_core::export::ClassBuilder<#class_name>) {
-                        use #gdnative_core::export::*;
 
-                        #(#methods)*
+                const _: () = {
+                    #derived
+                    #shim_impl {
+                        #(#shims)*
                     }
-                }
+
+                    #derived
+                    impl #impl_generics #gdnative_core::export::Mixin<#class_name> for #mixin_name #where_clause {
+                        type State = #state;
+
+                        fn register(#builder: &#gdnative_core::export::ClassBuilder<#class_name>) {
+                            #(#methods)*
+                            #(#signals)*
+                        }
+                    }
+                };
             };
 
             let body = match &mixin_kind {
@@ -499,24 +852,109 @@ pub(crate) fn derive_methods(
             };
 
             Ok(quote::quote!(
-                #impl_block
+                #emitted
                 #body
+                #(#c_exports)*
             ))
         }
         None => Ok(quote::quote!(
-            #impl_block
+            #emitted
+            #(#c_exports)*
+
+            const _: () = {
+                #derived
+                impl #impl_generics #class_name #where_clause {
+                    #(#shims)*
+                }
+
+                #derived
+                impl #impl_generics #gdnative_core::export::NativeClassMethods for #class_name #where_clause {
+                    fn nativeclass_register(#builder: &#gdnative_core::export::ClassBuilder<Self>) {
+                        #(#methods)*
+                        #(#signals)*
+                    }
+                }
+            };
+
+        )),
+    }
+}
+
+/// Generates a C ABI entry point for a method marked with `#[method(c_export)]`. See the
+/// documentation of `#[method]` in `lib.rs` for the symbol naming scheme.
+fn wrap_c_export(
+    class_name: &Type,
+    sig: &Signature,
+    name_string: &str,
+    export_args: &ExportArgs,
+    non_concrete: Option<Span>,
+    method: &TokenStream2,
+) -> Result<TokenStream2, syn::Error> {
+    let gdnative_core = crate::crate_gdnative_core();
+
+    if let Some(span) = non_concrete {
+        return Err(syn::Error::new(
+            span,
+            "`c_export` is not supported for methods of non-concrete types",
+        ));
+    }
+
+    if export_args.is_async || sig.asyncness.is_some() {
+        return Err(syn::Error::new(
+            sig.ident.span(),
+            "`c_export` is not supported for async methods",
+        ));
+    }
+
+    let symbol = match &export_args.c_export_symbol {
+        Some(symbol) => symbol.clone(),
+        None => {
+            let class_ident = match class_name {
+                Type::Path(ty) => ty.path.segments.last().map(|seg| &seg.ident),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                syn::Error::new(
+                    class_name.span(),
+                    "cannot derive a symbol name for this type, specify one with `c_export = \"symbol\"`",
+                )
+            })?;
+
+            format!("gdnative_{class_ident}_{name_string}")
+        }
+    };
+
+    let derived = crate::automatically_derived();
+
+    Ok(quote! {
+        const _: () = {
+            struct __CExport;
 
             #derived
-            impl #impl_generics #gdnative_core::export::NativeClassMethods for #class_name #where_clause {
-                fn nativeclass_register(#builder: &#gdnative_core::export::ClassBuilder<Self>) {
+            impl __CExport {
+                fn method() -> impl #gdnative_core::export::Method<#class_name> {
                     use #gdnative_core::export::*;
 
-                    #(#methods)*
+                    #method
                 }
             }
 
-        )),
-    }
+            #[export_name = #symbol]
+            unsafe extern "C" fn __gdnative_c_export(
+                this: *mut #gdnative_core::sys::godot_object,
+                num_args: #gdnative_core::libc::c_int,
+                args: *mut *mut #gdnative_core::sys::godot_variant,
+            ) -> #gdnative_core::sys::godot_variant {
+                #gdnative_core::private::call_c_export::<#class_name, _>(
+                    __CExport::method(),
+                    #name_string,
+                    this,
+                    num_args,
+                    args,
+                )
+            }
+        };
+    })
 }
 
 /// Extract the data to export from the impl block.
@@ -534,6 +972,7 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
     let mut export = ClassMethodExport {
         class_ty: ast.self_ty,
         methods: vec![],
+        signals: vec![],
     };
 
     let mut methods_to_export: Vec<ExportMethod> = Vec::new();
@@ -542,8 +981,21 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
     // add all items back to the impl block again.
     for func in ast.items {
         let items = match func {
+            ImplItem::Method(mut method) if ExportSignal::is_signal(&method) => {
+                let mut errors = vec![];
+                export
+                    .signals
+                    .extend(ExportSignal::strip_parse(&mut method, &mut errors));
+
+                errors
+                    .into_iter()
+                    .map(|err| ImplItem::Verbatim(err.to_compile_error()))
+                    .chain(std::iter::once(ImplItem::Method(method)))
+                    .collect()
+            }
             ImplItem::Method(mut method) => {
                 let mut export_args = None;
+                let mut cfg_godot_attrs = vec![];
                 let mut errors = vec![];
 
                 // only allow the "outer" style, aka #[thing] item.
@@ -556,6 +1008,11 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
                             .last()
                             .map(|i| i.ident.to_string());
 
+                        if let Some("cfg_godot") = last_seg.as_deref() {
+                            cfg_godot_attrs.push(attr.clone());
+                            return false;
+                        }
+
                         let (is_export, is_old_syntax, macro_name) =
                             if let Some("export") = last_seg.as_deref() {
                                 (true, true, "export")
@@ -684,6 +1141,21 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
                                     } else {
                                         export_args.is_deref_return = true;
                                     }
+                                } else if path.is_ident("err") {
+                                    // report errors returned by the method
+                                    if lit.is_some() {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`err` does not take any values",
+                                        ));
+                                    } else if export_args.is_err {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`err` was set more than once",
+                                        ));
+                                    } else {
+                                        export_args.is_err = true;
+                                    }
                                 } else if path.is_ident("async") {
                                     // deref return value
                                     if lit.is_some() {
@@ -699,6 +1171,59 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
                                     } else {
                                         export_args.is_async = true;
                                     }
+                                } else if path.is_ident("c_export") {
+                                    // C ABI export, with optional symbol name
+                                    if export_args.is_c_export {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`c_export` was set more than once",
+                                        ));
+                                    }
+                                    export_args.is_c_export = true;
+
+                                    match lit {
+                                        None => {}
+                                        Some(Lit::Str(str)) => {
+                                            export_args.c_export_symbol = Some(str.value());
+                                        }
+                                        _ => {
+                                            errors.push(syn::Error::new(
+                                                nested_meta.span(),
+                                                "unexpected type for `c_export` value, expected string",
+                                            ));
+                                        }
+                                    }
+                                } else if path.is_ident("no_virtual_check") {
+                                    // skip checking the signature against virtual methods
+                                    if lit.is_some() {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`no_virtual_check` does not take any values",
+                                        ));
+                                    } else if export_args.no_virtual_check {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`no_virtual_check` was set more than once",
+                                        ));
+                                    } else {
+                                        export_args.no_virtual_check = true;
+                                    }
+                                } else if let Some(processing) = Processing::parse(path) {
+                                    // export as a processing callback that is enabled on construction
+                                    if lit.is_some() {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            format!(
+                                                "`{}` does not take any values",
+                                                path.to_token_stream()
+                                            ),
+                                        ));
+                                    } else if export_args.processing.replace(processing).is_some() {
+                                        errors.push(syn::Error::new(
+                                            nested_meta.span(),
+                                            "`process` or `physics_process` was set more than once",
+                                        ));
+                                    }
                                 } else {
                                     let msg = format!(
                                         "unknown option for #[{}]: `{}`",
@@ -708,6 +1233,13 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
                                     errors.push(syn::Error::new(nested_meta.span(), msg));
                                 }
                             }
+
+                            if export_args.processing.is_some() && export_args.name_override.is_some() {
+                                errors.push(syn::Error::new(
+                                    attr.span(),
+                                    "`name` can't be combined with `process` or `physics_process`, which export the method as `_process` or `_physics_process`",
+                                ));
+                            }
                             return false;
                         }
                     }
@@ -715,12 +1247,28 @@ fn impl_gdnative_expose(ast: ItemImpl) -> (ItemImpl, ClassMethodExport) {
                     true
                 });
 
+                let cfg_godot = CfgGodot::parse_attrs(&cfg_godot_attrs).unwrap_or_else(|err| {
+                    errors.push(err);
+                    None
+                });
+
                 if let Some(export_args) = export_args.take() {
-                    methods_to_export.extend(ExportMethod::strip_parse(
-                        &export.class_ty,
-                        &mut method.sig,
-                        export_args,
-                        &mut errors,
+                    methods_to_export.extend(
+                        ExportMethod::strip_parse(
+                            &export.class_ty,
+                            &mut method.sig,
+                            export_args,
+                            &mut errors,
+                        )
+                        .map(|export_method| ExportMethod {
+                            cfg_godot,
+                            ..export_method
+                        }),
+                    );
+                } else if let Some(attr) = cfg_godot_attrs.first() {
+                    errors.push(syn::Error::new(
+                        attr.span(),
+                        "#[cfg_godot] can only be used on exported methods",
                     ));
                 }
 
@@ -852,7 +1400,12 @@ pub(crate) fn expand_godot_wrap_method(
         rpc_mode: None,
         name_override: None,
         is_deref_return: is_deref_return.value,
+        is_err: false,
         is_async: false,
+        is_c_export: false,
+        c_export_symbol: None,
+        no_virtual_check: false,
+        processing: None,
     };
 
     let mut errors = Vec::new();
@@ -865,14 +1418,37 @@ pub(crate) fn expand_godot_wrap_method(
 
     wrap_method(
         &class_name,
+        None,
         &Generics::default(),
         &export_method.expect("ExportMethod is valid"),
     )
     .map_err(|e| vec![e])
 }
 
+/// Returns `T` if `ty` is `&T` or `TRef<'_, T>`, for regular parameters that borrow an object
+/// passed to the method, e.g. by a signal.
+fn borrowed_object_class(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Reference(reference) if reference.mutability.is_some() => None,
+        _ => base_param::base_class(ty),
+    }
+}
+
+/// Returns `true` if `ty` is `GodotStr<'_>`, for parameters that borrow a string passed to the
+/// method.
+fn is_godot_str(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "GodotStr"),
+        _ => false,
+    }
+}
+
 fn wrap_method(
     class_name: &Type,
+    trait_path: Option<&syn::Path>,
     generics: &Generics,
     export_method: &ExportMethod,
 ) -> Result<TokenStream2, syn::Error> {
@@ -890,6 +1466,7 @@ fn wrap_method(
                     .unwrap_or_else(|err| {
                         #gdnative_core::godot_error!("gdnative-core: method call failed with error: {}", err);
                         #gdnative_core::godot_error!("gdnative-core: check module level documentation on gdnative::user_data for more information");
+                        #gdnative_core::private::log_call_stack();
                         #err_value
                     })
             }
@@ -902,6 +1479,7 @@ fn wrap_method(
         sig,
         export_args,
         arg_kind,
+        ..
     } = &export_method;
 
     let gdnative_core = crate::crate_gdnative_core();
@@ -927,6 +1505,22 @@ fn wrap_method(
 
     let method_name = &sig.ident;
 
+    // Methods of trait impls are called through the trait, so that it doesn't need to be in
+    // scope where the method is registered.
+    let callee = match trait_path {
+        Some(trait_path) => quote!(<#class_name as #trait_path>),
+        None => quote!(<#class_name>),
+    };
+
+    let is_async = export_args.is_async || sig.asyncness.is_some();
+
+    if export_args.is_err && is_async {
+        return Err(syn::Error::new(
+            sig_span,
+            "`err` is not supported for async methods",
+        ));
+    }
+
     let declare_arg_list = arg_kind
         .iter()
         .zip(&sig.inputs)
@@ -939,7 +1533,50 @@ fn wrap_method(
                     } else {
                         None
                     };
-                    Some(quote_spanned!(span => #maybe_opt #arg))
+
+                    // Borrowed objects are read as `Ref`s, and borrowed for the duration of the
+                    // call in `invoke_arg_list`.
+                    if let Some(class) = borrowed_object_class(&arg.ty) {
+                        if *optional {
+                            return Some(Err(syn::Error::new(
+                                arg.ty.span(),
+                                "optional parameters can't borrow objects\n\
+                                help: take an `Option<Ref<T>>` instead",
+                            )));
+                        }
+
+                        if is_async {
+                            return Some(Err(syn::Error::new(
+                                arg.ty.span(),
+                                "async methods can't take borrowed objects, since they may outlive the call\n\
+                                help: take a `Ref<T>` instead",
+                            )));
+                        }
+
+                        let pat = &arg.pat;
+                        return Some(Ok(quote_spanned! { span =>
+                            #maybe_opt #pat: #gdnative_core::object::Ref<#class>
+                        }));
+                    }
+
+                    // Borrowed strings are read as `GodotString`s, which only increments the
+                    // reference count, and borrowed for the duration of the call.
+                    if is_godot_str(&arg.ty) {
+                        if is_async {
+                            return Some(Err(syn::Error::new(
+                                arg.ty.span(),
+                                "async methods can't take borrowed strings, since they may outlive the call\n\
+                                help: take a `GodotString` instead",
+                            )));
+                        }
+
+                        let pat = &arg.pat;
+                        return Some(Ok(quote_spanned! { span =>
+                            #maybe_opt #pat: #gdnative_core::core_types::GodotString
+                        }));
+                    }
+
+                    Some(Ok(quote_spanned!(span => #maybe_opt #arg)))
                 } else {
                     unreachable!("regular arguments should always be FnArg::Typed")
                 }
@@ -947,7 +1584,7 @@ fn wrap_method(
                 None
             }
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, syn::Error>>()?;
 
     let destructure_arg_list = arg_kind
         .iter()
@@ -965,8 +1602,6 @@ fn wrap_method(
         })
         .collect::<Vec<_>>();
 
-    let is_async = export_args.is_async || sig.asyncness.is_some();
-
     let mut receiver = None;
     let mut maybe_extract_base = None;
 
@@ -1005,7 +1640,16 @@ fn wrap_method(
                 }
                 FnArg::Typed(arg) => {
                     let pat = &arg.pat;
-                    Ok(quote_spanned! { sig_span => #pat })
+                    match (borrowed_object_class(&arg.ty), &*arg.ty) {
+                        (Some(_), Type::Reference(_)) => {
+                            Ok(quote_spanned! { sig_span => #pat.assume_safe().as_ref() })
+                        }
+                        (Some(_), _) => Ok(quote_spanned! { sig_span => #pat.assume_safe() }),
+                        (None, ty) if is_godot_str(ty) => Ok(quote_spanned! { sig_span =>
+                            #gdnative_core::core_types::GodotStr::new(&#pat)
+                        }),
+                        (None, _) => Ok(quote_spanned! { sig_span => #pat }),
+                    }
                 }
             },
         })
@@ -1028,7 +1672,7 @@ fn wrap_method(
 
                 #[allow(unused_unsafe)]
                 unsafe {
-                    Some(<#class_name>::#method_name(
+                    Some(#callee::#method_name(
                         #(#invoke_arg_list,)*
                     ))
                 }
@@ -1070,20 +1714,62 @@ fn wrap_method(
             }))
         }
     } else {
-        let body = wrap_maybe_receiver(
-            receiver.as_ref(),
-            sig_span,
+        let body = if export_args.is_err {
+            let name = export_args
+                .name_override
+                .clone()
+                .unwrap_or_else(|| method_name.to_string());
+
+            // Errors are reported after the instance is released, since the `script_error`
+            // signal may call back into it.
+            let body = wrap_maybe_receiver(
+                receiver.as_ref(),
+                sig_span,
+                quote_spanned! { sig_span =>
+                    #[allow(unused_unsafe)]
+                    unsafe {
+                        match #callee::#method_name(
+                            #(#invoke_arg_list,)*
+                        ) {
+                            Ok(ret) => Ok(#gdnative_core::core_types::OwnedToVariant::owned_to_variant(#recover)),
+                            Err(err) => Err(::std::string::ToString::to_string(&err)),
+                        }
+                    }
+                },
+                quote_spanned! { sig_span => Ok(#gdnative_core::core_types::Variant::nil()) },
+            );
+
             quote_spanned! { sig_span =>
-                #[allow(unused_unsafe)]
-                unsafe {
-                    let ret = <#class_name>::#method_name(
-                        #(#invoke_arg_list,)*
-                    );
-                    #gdnative_core::core_types::OwnedToVariant::owned_to_variant(#recover)
+                let __err_base = __this.base();
+                match #body {
+                    Ok(ret) => ret,
+                    Err(err) => {
+                        #gdnative_core::private::report_method_error(
+                            __err_base,
+                            #gdnative_core::godot_site!(#class_name::#method_name),
+                            #name,
+                            err,
+                        );
+                        #gdnative_core::core_types::Variant::nil()
+                    }
                 }
-            },
-            quote_spanned! { sig_span => #gdnative_core::core_types::Variant::nil() },
-        );
+            }
+        } else {
+            wrap_maybe_receiver(
+                receiver.as_ref(),
+                sig_span,
+                quote_spanned! { sig_span =>
+                    #[allow(unused_unsafe)]
+                    unsafe {
+                        let ret = #callee::#method_name(
+                            #(#invoke_arg_list,)*
+                        );
+                        #gdnative_core::core_types::OwnedToVariant::owned_to_variant(#recover)
+                    }
+                },
+                quote_spanned! { sig_span => #gdnative_core::core_types::Variant::nil() },
+            )
+        };
 
         quote_spanned! { sig_span =>
             #automatically_derived
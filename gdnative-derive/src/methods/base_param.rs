@@ -0,0 +1,106 @@
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{spanned::Spanned, FnArg, GenericArgument, PathArguments, Type};
+
+use super::{ArgKind, ExportMethod};
+
+/// Returns an error for `#[base]` parameter types that can never be used, such as `Ref<T>`.
+pub(super) fn validate(ty: &Type) -> Result<(), syn::Error> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            let name = segment.ident.to_string();
+            if matches!(name.as_str(), "Ref" | "Instance" | "TInstance") {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    format!(
+                        "`#[base]` parameters must be `&T` or `TRef<T>`, where `T` is the type in `#[inherit]`, not `{name}<T>`\n\
+                        help: take `base: TRef<T>` and call `base.claim()` to obtain a `Ref<T>`"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates compile-time checks that the `#[base]` parameters of `export_method` have the
+/// type in `#[inherit]`, so that mismatches are reported at the parameter.
+pub(super) fn check_types(class_name: &Type, export_method: &ExportMethod) -> TokenStream2 {
+    let gdnative_core = crate::crate_gdnative_core();
+
+    export_method
+        .arg_kind
+        .iter()
+        .zip(&export_method.sig.inputs)
+        .filter_map(|(kind, arg)| match (kind, arg) {
+            (ArgKind::Base, FnArg::Typed(arg)) => base_class(&arg.ty),
+            _ => None,
+        })
+        .map(|base| {
+            quote_spanned! { base.span() =>
+                const _: () = #gdnative_core::private::base_parameter_must_have_inherited_type::<
+                    <#class_name as #gdnative_core::export::NativeClass>::Base,
+                >(::core::marker::PhantomData::<#base>);
+            }
+        })
+        .collect()
+}
+
+/// Returns `T` if `ty` is `&T`, `&mut T` or `TRef<'_, T, ...>`.
+pub(super) fn base_class(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Reference(reference) => Some(&reference.elem),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            if segment.ident != "TRef" {
+                return None;
+            }
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn base_class_of(ty: Type) -> Option<String> {
+        base_class(&ty).map(|ty| ty.to_token_stream().to_string())
+    }
+
+    #[test]
+    fn base_class_from_param_type() {
+        assert_eq!(Some("Node".into()), base_class_of(parse_quote!(&Node)));
+        assert_eq!(
+            Some("Label".into()),
+            base_class_of(parse_quote!(&mut Label))
+        );
+        assert_eq!(
+            Some("Node2D".into()),
+            base_class_of(parse_quote!(TRef<'_, Node2D>))
+        );
+        assert_eq!(
+            Some("Node".into()),
+            base_class_of(parse_quote!(gdnative::object::TRef<'a, Node, Shared>))
+        );
+        assert_eq!(None, base_class_of(parse_quote!(Owner<'a>)));
+    }
+
+    #[test]
+    fn invalid_param_types() {
+        assert!(validate(&parse_quote!(&Node)).is_ok());
+        assert!(validate(&parse_quote!(TRef<Node>)).is_ok());
+        assert!(validate(&parse_quote!(Ref<Node>)).is_err());
+        assert!(validate(&parse_quote!(Instance<Foo>)).is_err());
+    }
+}
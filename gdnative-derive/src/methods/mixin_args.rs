@@ -2,9 +2,13 @@ use proc_macro2::{Ident, Span};
 use std::fmt::Debug;
 use syn::spanned::Spanned;
 
+use crate::syntax::rename_rule::RenameRule;
+
 pub struct MixinArgs {
     pub mixin: Option<MixinKind>,
+    pub state: Option<syn::Type>,
     pub pub_: bool,
+    pub rename_all: Option<RenameRule>,
 }
 
 #[derive(Debug)]
@@ -15,14 +19,18 @@ pub enum MixinKind {
 
 pub struct MixinArgsBuilder {
     mixin: Option<MixinKind>,
+    state: Option<syn::Type>,
     pub_: Option<Span>,
+    rename_all: Option<RenameRule>,
 }
 
 impl MixinArgsBuilder {
     pub fn new() -> Self {
         Self {
             mixin: None,
+            state: None,
             pub_: None,
+            rename_all: None,
         }
     }
 
@@ -77,6 +85,23 @@ impl MixinArgsBuilder {
                 let name = Ident::new(&name.value(), name.span());
                 update_prop!(mixin, MixinKind::Named(name));
             }
+            "state" => {
+                let ty = Self::extract_lit_str(&pair.lit)
+                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), "state"))?
+                    .parse::<syn::Type>()?;
+                if self.state.replace(ty).is_some() {
+                    return Err(syn::Error::new(
+                        pair.span(),
+                        "there is already a 'state' attribute",
+                    ));
+                }
+            }
+            "rename_all" => {
+                let rule = Self::extract_lit_str(&pair.lit)
+                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), "rename_all"))
+                    .and_then(RenameRule::parse_lit)?;
+                update_prop!(rename_all, rule);
+            }
             _ => {
                 return Err(syn::Error::new(
                     pair.span(),
@@ -119,9 +144,20 @@ impl MixinArgsBuilder {
             }
         }
 
+        if let Some(state) = &self.state {
+            if !matches!(self.mixin, Some(MixinKind::Named(_))) {
+                return Err(syn::Error::new(
+                    state.span(),
+                    "states are only applicable to named mixins",
+                ));
+            }
+        }
+
         Ok(MixinArgs {
             mixin: self.mixin,
+            state: self.state,
             pub_: self.pub_.is_some(),
+            rename_all: self.rename_all,
         })
     }
 }
@@ -0,0 +1,54 @@
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{spanned::Spanned, FnArg, Type};
+
+use super::ExportMethod;
+
+/// Generates a compile-time check that the user-data wrapper of `class_name` implements
+/// `MapMut` if `export_method` takes `&mut self`, so that read-only wrappers such as `OnceData`
+/// are reported at the receiver.
+pub(super) fn check_user_data(class_name: &Type, export_method: &ExportMethod) -> TokenStream2 {
+    let gdnative_core = crate::crate_gdnative_core();
+
+    export_method
+        .sig
+        .inputs
+        .iter()
+        .filter(|arg| is_mut_ref_receiver(arg))
+        .map(|arg| {
+            quote_spanned! { arg.span() =>
+                const _: () = #gdnative_core::private::mut_self_requires_map_mut_user_data::<
+                    <#class_name as #gdnative_core::export::NativeClass>::UserData,
+                >();
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if `arg` is `&mut self`.
+fn is_mut_ref_receiver(arg: &FnArg) -> bool {
+    matches!(
+        arg,
+        FnArg::Receiver(syn::Receiver {
+            reference: Some(_),
+            mutability: Some(_),
+            ..
+        })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn mut_ref_receivers() {
+        assert!(is_mut_ref_receiver(&parse_quote!(&mut self)));
+        assert!(is_mut_ref_receiver(&parse_quote!(&'a mut self)));
+        assert!(!is_mut_ref_receiver(&parse_quote!(&self)));
+        assert!(!is_mut_ref_receiver(&parse_quote!(self)));
+        assert!(!is_mut_ref_receiver(&parse_quote!(mut self)));
+        assert!(!is_mut_ref_receiver(&parse_quote!(this: &mut Self)));
+    }
+}
@@ -0,0 +1,352 @@
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::ToTokens;
+use syn::{spanned::Spanned, Expr, FnArg, ImplItemMethod, Lit, Meta, NestedMeta, Pat, Type};
+
+/// Names of the `ConnectFlags` accepted in `#[signal(flags = "...")]`.
+const CONNECT_FLAGS: &[&str] = &["DEFERRED", "PERSIST", "ONESHOT", "REFERENCE_COUNTED"];
+
+/// A signal declared with `#[signal]` on a function with an empty body.
+pub(crate) struct ExportSignal {
+    name: String,
+    span: Span,
+    params: Vec<SignalParam>,
+    documentation: Option<String>,
+    flags: Vec<Ident>,
+}
+
+struct SignalParam {
+    name: String,
+    ty: Type,
+    /// `#[hint(expr)]`
+    hint: Option<Expr>,
+    /// `#[default(expr)]`
+    default: Option<Expr>,
+    /// `#[usage(expr)]`
+    usage: Option<Expr>,
+}
+
+impl ExportSignal {
+    /// Returns whether `method` is marked with `#[signal]`.
+    pub(crate) fn is_signal(method: &ImplItemMethod) -> bool {
+        method.attrs.iter().any(|attr| attr.path.is_ident("signal"))
+    }
+
+    /// Parses a signal declaration, removing the attributes used by `#[signal]` from `method`.
+    /// The function itself is kept, so that doc comments and the parameter list stay in place.
+    pub(crate) fn strip_parse(
+        method: &mut ImplItemMethod,
+        errors: &mut Vec<syn::Error>,
+    ) -> Option<Self> {
+        let mut name = None;
+        let mut flags = Vec::new();
+        let mut documentation = Vec::new();
+
+        let mut signal_attr = None;
+        method.attrs.retain(|attr| {
+            if attr.path.is_ident("signal") {
+                if signal_attr.replace(attr.span()).is_some() {
+                    errors.push(syn::Error::new(attr.span(), "duplicate attribute"));
+                }
+                parse_signal_args(attr, &mut name, &mut flags, errors);
+                return false;
+            }
+
+            if attr.path.is_ident("doc") {
+                if let Ok(Meta::NameValue(doc)) = attr.parse_meta() {
+                    if let Lit::Str(doc) = doc.lit {
+                        let doc = doc.value();
+                        documentation.push(doc.strip_prefix(' ').unwrap_or(&doc).to_owned());
+                    }
+                }
+            }
+
+            true
+        });
+
+        method
+            .attrs
+            .push(parse_quote!(#[allow(dead_code, unused_variables)]));
+
+        let sig = &mut method.sig;
+        let mut fail = false;
+
+        if !method.block.stmts.is_empty() {
+            fail = true;
+            errors.push(syn::Error::new(
+                method.block.span(),
+                "signal declarations must have an empty body",
+            ));
+        }
+
+        if !sig.generics.params.is_empty() || sig.asyncness.is_some() {
+            fail = true;
+            errors.push(syn::Error::new(
+                sig.ident.span(),
+                "signal declarations can't be generic or async",
+            ));
+        }
+
+        let mut params = Vec::new();
+        for arg in &mut sig.inputs {
+            let arg = match arg {
+                FnArg::Typed(arg) => arg,
+                FnArg::Receiver(receiver) => {
+                    fail = true;
+                    errors.push(syn::Error::new(
+                        receiver.span(),
+                        "signal declarations can't take `self`",
+                    ));
+                    continue;
+                }
+            };
+
+            let mut hint = None;
+            let mut default = None;
+            let mut usage = None;
+            arg.attrs.retain(|attr| {
+                let slot = if attr.path.is_ident("hint") {
+                    &mut hint
+                } else if attr.path.is_ident("default") {
+                    &mut default
+                } else if attr.path.is_ident("usage") {
+                    &mut usage
+                } else {
+                    return true;
+                };
+
+                match attr.parse_args::<Expr>() {
+                    Ok(expr) => {
+                        if slot.replace(expr).is_some() {
+                            errors.push(syn::Error::new(attr.span(), "duplicate attribute"));
+                        }
+                    }
+                    Err(err) => errors.push(err),
+                }
+                false
+            });
+
+            let name = match &*arg.pat {
+                Pat::Ident(pat) => pat.ident.to_string(),
+                pat => {
+                    fail = true;
+                    errors.push(syn::Error::new(
+                        pat.span(),
+                        "signal parameters must be plain identifiers",
+                    ));
+                    continue;
+                }
+            };
+
+            params.push(SignalParam {
+                name,
+                ty: (*arg.ty).clone(),
+                hint,
+                default,
+                usage,
+            });
+        }
+
+        if fail {
+            return None;
+        }
+
+        let documentation = (!documentation.is_empty()).then(|| documentation.join("\n"));
+
+        Some(ExportSignal {
+            name: name.unwrap_or_else(|| sig.ident.to_string()),
+            span: sig.ident.span(),
+            params,
+            documentation,
+            flags,
+        })
+    }
+
+    /// Generates the registration of the signal with `builder`.
+    pub(crate) fn register(&self, builder: &Ident) -> TokenStream2 {
+        let gdnative_core = crate::crate_gdnative_core();
+        let name = &self.name;
+
+        let params = self.params.iter().map(|param| {
+            let SignalParam {
+                name,
+                ty,
+                hint,
+                default,
+                usage,
+            } = param;
+
+            let hint = match hint {
+                Some(hint) => quote_spanned!(hint.span()=> Some(#hint)),
+                None => quote!(None),
+            };
+            let default = default.as_ref().map(|default| {
+                quote_spanned!(default.span()=>
+                    .with_default(#gdnative_core::core_types::OwnedToVariant::owned_to_variant(#default))
+                )
+            });
+            let usage = usage
+                .as_ref()
+                .map(|usage| quote_spanned!(usage.span()=> .with_usage(#usage)));
+
+            quote_spanned!(ty.span()=>
+                .with_param_custom(
+                    #gdnative_core::export::SignalParam::new(
+                        #name,
+                        <#ty as #gdnative_core::export::Export>::export_info(#hint),
+                    )
+                    #default
+                    #usage
+                )
+            )
+        });
+
+        let documentation = self
+            .documentation
+            .as_ref()
+            .map(|doc| quote!(.with_documentation(#doc)));
+
+        let flags = (!self.flags.is_empty()).then(|| {
+            let flags = &self.flags;
+            quote!(.with_default_flags(#(#gdnative_core::export::ConnectFlags::#flags)|*))
+        });
+
+        quote_spanned!(self.span=>
+            #builder
+                .signal(#name)
+                #(#params)*
+                #documentation
+                #flags
+                .done();
+        )
+    }
+}
+
+/// Parses `#[signal]`, `#[signal(name = "...")]` and `#[signal(flags = "ONESHOT | DEFERRED")]`.
+fn parse_signal_args(
+    attr: &syn::Attribute,
+    name: &mut Option<String>,
+    flags: &mut Vec<Ident>,
+    errors: &mut Vec<syn::Error>,
+) {
+    let nested = match attr.parse_meta() {
+        Ok(Meta::Path(_)) => return,
+        Ok(Meta::List(list)) => list.nested,
+        Ok(Meta::NameValue(meta)) => {
+            errors.push(syn::Error::new(
+                meta.span(),
+                "NameValue syntax is not valid",
+            ));
+            return;
+        }
+        Err(err) => {
+            errors.push(err);
+            return;
+        }
+    };
+
+    for meta in nested {
+        let pair = match &meta {
+            NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+            _ => {
+                errors.push(syn::Error::new(meta.span(), "expected `name = \"value\"`"));
+                continue;
+            }
+        };
+
+        let value = match &pair.lit {
+            Lit::Str(value) => value,
+            lit => {
+                errors.push(syn::Error::new(lit.span(), "expected a string literal"));
+                continue;
+            }
+        };
+
+        if pair.path.is_ident("name") {
+            if name.replace(value.value()).is_some() {
+                errors.push(syn::Error::new(
+                    pair.span(),
+                    "`name` was set more than once",
+                ));
+            }
+        } else if pair.path.is_ident("flags") {
+            for flag in value.value().split('|').map(str::trim) {
+                if CONNECT_FLAGS.contains(&flag) {
+                    flags.push(Ident::new(flag, value.span()));
+                } else {
+                    errors.push(syn::Error::new(
+                        value.span(),
+                        format!(
+                            "unknown connect flag `{flag}`, expected one of: {}",
+                            CONNECT_FLAGS.join(", ")
+                        ),
+                    ));
+                }
+            }
+        } else {
+            errors.push(syn::Error::new(
+                pair.path.span(),
+                format!(
+                    "unknown option for #[signal]: `{}`",
+                    pair.path.to_token_stream()
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn signal_attributes_are_stripped() {
+        let mut method: ImplItemMethod = parse_quote! {
+            /// Emitted when touching the ground.
+            #[signal(name = "touched_down", flags = "ONESHOT | DEFERRED")]
+            fn landed(#[hint(FloatHint::Range(RangeHint::new(0.0, 100.0)))] speed: f64, #[default(1)] count: i64) {}
+        };
+
+        let mut errors = vec![];
+        let signal = ExportSignal::strip_parse(&mut method, &mut errors).unwrap();
+        assert!(errors.is_empty());
+
+        assert_eq!("touched_down", signal.name);
+        assert_eq!(
+            Some("Emitted when touching the ground."),
+            signal.documentation.as_deref()
+        );
+        assert_eq!(
+            vec!["ONESHOT", "DEFERRED"],
+            signal
+                .flags
+                .iter()
+                .map(Ident::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, signal.params.len());
+        assert!(signal.params[0].hint.is_some());
+        assert!(signal.params[1].default.is_some());
+
+        assert!(!method.attrs.iter().any(|attr| attr.path.is_ident("signal")));
+        assert!(method.sig.inputs.iter().all(|arg| match arg {
+            FnArg::Typed(arg) => arg.attrs.is_empty(),
+            FnArg::Receiver(_) => false,
+        }));
+    }
+
+    #[test]
+    fn signals_are_not_methods() {
+        let mut method: ImplItemMethod = parse_quote! {
+            #[signal(flags = "ONCE")]
+            fn landed(&self) {
+                println!("landed");
+            }
+        };
+
+        let mut errors = vec![];
+        assert!(ExportSignal::strip_parse(&mut method, &mut errors).is_none());
+        assert_eq!(3, errors.len());
+    }
+}
@@ -0,0 +1,109 @@
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Attribute, FnArg, Meta, NestedMeta, ReturnType, Token, Type, TypePath};
+
+use super::{ArgKind, ExportMethod};
+
+const LINT: &str = "unconverted_variants";
+
+/// Removes `unconverted_variants` from `#[<level>(...)]` attributes, since it isn't known to
+/// rustc. Returns the span of the lint if it was found.
+pub(super) fn strip_lint(
+    attrs: &mut Vec<Attribute>,
+    level: &str,
+) -> Result<Option<Span>, syn::Error> {
+    let mut found = None;
+
+    for attr in attrs.iter_mut() {
+        if !attr.path.is_ident(level) {
+            continue;
+        }
+
+        let Meta::List(mut list) = attr.parse_meta()? else {
+            continue;
+        };
+
+        let before = list.nested.len();
+        list.nested = std::mem::take(&mut list.nested)
+            .into_iter()
+            .filter(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident(LINT) => {
+                    found = Some(path.span());
+                    false
+                }
+                _ => true,
+            })
+            .collect::<Punctuated<NestedMeta, Token![,]>>();
+
+        if list.nested.len() != before {
+            let nested = &list.nested;
+            attr.tokens = quote::quote!((#nested));
+        }
+    }
+
+    // Attributes that only contained the lint are removed entirely.
+    attrs.retain(|attr| !(attr.path.is_ident(level) && attr.tokens.to_string() == "()"));
+
+    Ok(found)
+}
+
+/// Returns errors for the argument and return types of `export_method` that are converted to
+/// and from `Variant` as is, instead of through a typed conversion.
+pub(super) fn check(export_method: &ExportMethod) -> Vec<syn::Error> {
+    let ExportMethod { sig, arg_kind, .. } = export_method;
+
+    let args = arg_kind
+        .iter()
+        .zip(&sig.inputs)
+        .filter_map(|(kind, arg)| match (kind, arg) {
+            (ArgKind::Regular { .. }, FnArg::Typed(arg)) => Some(&*arg.ty),
+            _ => None,
+        });
+
+    let ret = match &sig.output {
+        ReturnType::Type(_, ty) => Some(&**ty),
+        ReturnType::Default => None,
+    };
+
+    args.chain(ret)
+        .filter_map(find_variant)
+        .map(|span| {
+            syn::Error::new(
+                span,
+                "`Variant` is passed without a typed conversion (denied by `unconverted_variants`)",
+            )
+        })
+        .collect()
+}
+
+/// Returns the span of the first `Variant` in `ty`, including in generic arguments such as
+/// `Option<Variant>` or `impl Future<Output = Variant>`.
+fn find_variant(ty: &Type) -> Option<Span> {
+    struct Visitor(Option<Span>);
+
+    impl<'ast> Visit<'ast> for Visitor {
+        fn visit_type_path(&mut self, i: &'ast TypePath) {
+            if self.0.is_some() {
+                return;
+            }
+
+            let is_variant = i.qself.is_none()
+                && i.path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Variant");
+
+            if is_variant {
+                self.0 = Some(i.span());
+            } else {
+                syn::visit::visit_type_path(self, i);
+            }
+        }
+    }
+
+    let mut visitor = Visitor(None);
+    visitor.visit_type(ty);
+    visitor.0
+}
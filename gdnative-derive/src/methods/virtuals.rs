@@ -0,0 +1,124 @@
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{FnArg, GenericArgument, PathArguments, ReturnType, Type};
+
+use super::{ArgKind, ExportMethod};
+
+/// Generates a compile-time check of an exported method against the virtual method of the same
+/// name in the base class, if there is one. Only names starting with an underscore are checked.
+pub(super) fn check_override(
+    class_name: &Type,
+    name: &str,
+    export_method: &ExportMethod,
+) -> Option<TokenStream2> {
+    let ExportMethod {
+        sig,
+        export_args,
+        arg_kind,
+        ..
+    } = export_method;
+
+    if !name.starts_with('_')
+        || export_args.no_virtual_check
+        || export_args.is_async
+        || sig.asyncness.is_some()
+    {
+        return None;
+    }
+
+    let gdnative_core = crate::crate_gdnative_core();
+
+    let mut args = Vec::new();
+    let mut required = 0_usize;
+    for (kind, arg) in arg_kind.iter().zip(&sig.inputs) {
+        if let (ArgKind::Regular { optional }, FnArg::Typed(arg)) = (kind, arg) {
+            args.push(godot_type_name(&arg.ty));
+            if !optional {
+                required += 1;
+            }
+        }
+    }
+
+    let return_type = match &sig.output {
+        ReturnType::Default => "void",
+        ReturnType::Type(_, ty) => godot_type_name(ty),
+    };
+
+    let message = format!(
+        "the signature of `{name}` does not match the virtual method it overrides in the base class\n\n\
+        Check the argument and return types in the Godot documentation, or use \
+        #[method(no_virtual_check)] to skip this check."
+    );
+
+    Some(quote_spanned! { sig.ident.span() =>
+        const _: () = #gdnative_core::private::check_virtual_override::<
+            <#class_name as #gdnative_core::export::NativeClass>::Base,
+        >(#name, &[#(#args),*], #required, #return_type, #message);
+    })
+}
+
+/// Returns the name of the Godot type corresponding to `ty`, spelled as in
+/// `gdnative_core::object::VirtualMethod`, or an empty string if it can't be determined.
+fn godot_type_name(ty: &Type) -> &'static str {
+    let path = match ty {
+        Type::Reference(reference) => return godot_type_name(&reference.elem),
+        Type::Paren(paren) => return godot_type_name(&paren.elem),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => return "void",
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return "",
+    };
+
+    let segment = match path.segments.last() {
+        Some(segment) => segment,
+        None => return "",
+    };
+
+    let first_generic_arg = || match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    match segment.ident.to_string().as_str() {
+        "Option" => first_generic_arg().map_or("", godot_type_name),
+        "Ref" | "TRef" | "Instance" | "TInstance" => "Object",
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => "int",
+        "f32" | "f64" => "float",
+        "bool" => "bool",
+        "String" | "GodotString" | "str" => "String",
+        "Variant" => "Variant",
+        "VariantArray" => "Array",
+        "Aabb" => "AABB",
+        "Rid" => "RID",
+        "Basis" => "Basis",
+        "Color" => "Color",
+        "Dictionary" => "Dictionary",
+        "NodePath" => "NodePath",
+        "Plane" => "Plane",
+        "Quat" => "Quat",
+        "Rect2" => "Rect2",
+        "Transform" => "Transform",
+        "Transform2D" => "Transform2D",
+        "Vector2" => "Vector2",
+        "Vector3" => "Vector3",
+        "PoolArray" => match first_generic_arg().and_then(last_ident).as_deref() {
+            Some("u8") => "PoolByteArray",
+            Some("i32") => "PoolIntArray",
+            Some("f32") => "PoolRealArray",
+            Some("GodotString") => "PoolStringArray",
+            Some("Vector2") => "PoolVector2Array",
+            Some("Vector3") => "PoolVector3Array",
+            Some("Color") => "PoolColorArray",
+            _ => "",
+        },
+        _ => "",
+    }
+}
+
+fn last_ident(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|seg| seg.ident.to_string()),
+        _ => None,
+    }
+}
@@ -1,15 +1,20 @@
 use proc_macro2::TokenStream as TokenStream2;
 
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{
-    AttributeArgs, Data, DeriveInput, Expr, Fields, Ident, ItemType, Meta, MetaList, NestedMeta,
-    Path, Stmt, Type,
+    AttributeArgs, Data, DeriveInput, Expr, Fields, Ident, ItemType, Member, Meta, MetaList,
+    NestedMeta, Path, Type,
 };
 
 mod property_args;
-use property_args::{PropertyAttrArgs, PropertyAttrArgsBuilder, PropertyGet, PropertySet};
+use property_args::{
+    PropertyArg, PropertyAttrArgs, PropertyAttrArgsBuilder, PropertyGet, PropertyHint, PropertySet,
+};
 
+use crate::syntax::cfg_godot::CfgGodot;
+use crate::syntax::rename_rule::RenameRule;
 use crate::utils::extend_bounds;
 
 pub(crate) struct DeriveData {
@@ -18,8 +23,119 @@ pub(crate) struct DeriveData {
     pub(crate) base: Type,
     pub(crate) register_callback: Option<Path>,
     pub(crate) user_data: Type,
-    pub(crate) properties: Vec<(Ident, PropertyAttrArgs)>,
+    /// Compile-time checks that the fields satisfy the bounds of an automatically chosen
+    /// `user_data` wrapper, reported at the offending field.
+    pub(crate) user_data_checks: TokenStream2,
+    pub(crate) properties: Vec<(Member, PropertyAttrArgs)>,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) property_bag: Option<Member>,
+    /// Fields with a `#[node("path")]` attribute.
+    pub(crate) nodes: Vec<(Member, syn::LitStr)>,
+    pub(crate) storage: Option<StorageArgs>,
     pub(crate) no_constructor: bool,
+    pub(crate) cfg_godot: Option<CfgGodot>,
+}
+
+/// Arguments of `#[storage(version = 2, migrate = "Self::migrate")]`.
+#[derive(Default)]
+pub(crate) struct StorageArgs {
+    pub(crate) version: Option<syn::LitInt>,
+    pub(crate) migrate: Option<Path>,
+}
+
+impl StorageArgs {
+    fn parse(attr: &syn::Attribute) -> Result<Self, syn::Error> {
+        let mut args = StorageArgs::default();
+
+        let nested = match attr.parse_meta()? {
+            Meta::List(MetaList { nested, .. }) => nested,
+            Meta::Path(_) => return Ok(args),
+            meta => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected `#[storage(version = 1, migrate = \"path\")]`",
+                ))
+            }
+        };
+
+        for arg in nested {
+            let pair = match arg {
+                NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                arg => return Err(syn::Error::new(arg.span(), "expected `key = value`")),
+            };
+
+            match (
+                pair.path.get_ident().map(|i| i.to_string()).as_deref(),
+                &pair.lit,
+            ) {
+                (Some("version"), syn::Lit::Int(lit)) if args.version.is_none() => {
+                    lit.base10_parse::<u32>()?;
+                    args.version = Some(lit.clone());
+                }
+                (Some("migrate"), syn::Lit::Str(lit)) if args.migrate.is_none() => {
+                    args.migrate = Some(lit.parse()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        pair.span(),
+                        "expected a single `version = <integer>` and `migrate = \"path\"`",
+                    ))
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Arguments of `#[native_class(rename = "Name", rename_all = "camelCase")]`.
+#[derive(Default)]
+pub(crate) struct NativeClassArgs {
+    pub(crate) rename: Option<syn::LitStr>,
+    pub(crate) rename_all: Option<RenameRule>,
+}
+
+impl NativeClassArgs {
+    fn parse(attr: &syn::Attribute) -> Result<Self, syn::Error> {
+        let mut args = NativeClassArgs::default();
+
+        let nested = match attr.parse_meta()? {
+            Meta::List(MetaList { nested, .. }) => nested,
+            meta => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "expected `#[native_class(rename = \"Name\", rename_all = \"camelCase\")]`",
+                ))
+            }
+        };
+
+        for arg in nested {
+            let pair = match arg {
+                NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                arg => return Err(syn::Error::new(arg.span(), "expected `key = value`")),
+            };
+
+            match (
+                pair.path.get_ident().map(|i| i.to_string()).as_deref(),
+                &pair.lit,
+            ) {
+                (Some("rename"), syn::Lit::Str(lit)) if args.rename.is_none() => {
+                    args.rename = Some(lit.clone());
+                }
+                (Some("rename_all"), syn::Lit::Str(lit)) if args.rename_all.is_none() => {
+                    args.rename_all = Some(RenameRule::parse_lit(lit)?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        pair.span(),
+                        "expected a single `rename = \"Name\"` and `rename_all = \"convention\"`",
+                    ))
+                }
+            }
+        }
+
+        Ok(args)
+    }
 }
 
 pub(crate) fn impl_empty_nativeclass(derive_input: &DeriveInput) -> TokenStream2 {
@@ -28,6 +144,12 @@ pub(crate) fn impl_empty_nativeclass(derive_input: &DeriveInput) -> TokenStream2
     let gdnative_bindings = crate::crate_gdnative_bindings();
     let name = &derive_input.ident;
 
+    // `NativeClass` can't be implemented at all for types with lifetimes, which is already
+    // reported by `derive_native_class`.
+    if derive_input.generics.lifetimes().next().is_some() {
+        return TokenStream2::new();
+    }
+
     let generics = extend_bounds::with_visitor(
         derive_input.generics.clone(),
         None,
@@ -66,6 +188,35 @@ pub(crate) fn impl_empty_nativeclass(derive_input: &DeriveInput) -> TokenStream2
     }
 }
 
+/// Returns `true` if the last segment of the path type `ty` is `PropertyBag`.
+fn is_property_bag_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "PropertyBag"),
+        _ => false,
+    }
+}
+
+/// Returns `T` if the last segment of the path type `ty` is `ident<T>`.
+fn generic_argument_of<'a>(ty: &'a Type, ident: &str) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(path) => path,
+        _ => return None,
+    };
+
+    let seg = path.path.segments.last().filter(|seg| seg.ident == ident)?;
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(params) => match params.args.first() {
+            Some(syn::GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStream2, syn::Error> {
     let derived = crate::automatically_derived();
     let gdnative_core = crate::crate_gdnative_core();
@@ -87,40 +238,138 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
         let name = data.name;
         let base = data.base;
         let user_data = data.user_data;
+        let user_data_checks = data.user_data_checks;
         let register_callback = data
             .register_callback
             .map(|function_path| quote!(#function_path(builder);))
             .unwrap_or(quote!({}));
+        // Properties in the group of a `group_toggle` are only shown while the toggle is `true`
+        let group_toggles = data
+            .properties
+            .iter()
+            .filter(|(_, config)| config.group_toggle)
+            .map(|(member, config)| {
+                let group = config
+                    .path
+                    .as_deref()
+                    .and_then(|path| path.rfind('/').map(|index| &path[..=index]))
+                    .ok_or_else(|| {
+                        syn::Error::new(
+                            member.span(),
+                            "`group_toggle` properties must be inside a group, e.g. `#[property(path = \"group/enabled\", group_toggle)]`",
+                        )
+                    })?;
+                Ok((group.to_owned(), member.clone()))
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
+
+        let storage_fields = data
+            .properties
+            .iter()
+            .filter(|(_, config)| config.storage)
+            .map(|(member, config)| {
+                if generic_argument_of(&config.ty, "Property").is_some() {
+                    return Err(syn::Error::new(
+                        member.span(),
+                        "`storage` properties must store their value in the field, not as `Property<T>`",
+                    ));
+                }
+                let name = match (&config.path, member) {
+                    (Some(path), _) => path.clone(),
+                    (None, Member::Named(ident)) => rename_property(data.rename_all, ident),
+                    // Reported below, along with the other properties
+                    (None, Member::Unnamed(_)) => String::new(),
+                };
+                Ok(quote!(.with_field(#name, |this: &Self| &this.#member, |this: &mut Self| &mut this.#member)))
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
+
+        let storage = if data.storage.is_some() || !storage_fields.is_empty() {
+            let args = data.storage.unwrap_or_default();
+            let version = args
+                .version
+                .map_or_else(|| quote!(1), |version| quote!(#version));
+            let with_migration = args.migrate.map(|path| quote!(.with_migration(#path)));
+            Some(quote! {
+                builder.storage(#version)
+                    #with_migration
+                    #(#storage_fields)*
+                    .done();
+            })
+        } else {
+            None
+        };
+
         let properties = data
             .properties
             .into_iter()
-            .map(|(ident, config)| {
+            .map(|(member, config)| {
+                let label = match (config.path, &member) {
+                    (Some(path), _) => path,
+                    (None, Member::Named(ident)) => rename_property(data.rename_all, ident),
+                    (None, Member::Unnamed(_)) => {
+                        return Err(syn::Error::new(
+                            member.span(),
+                            "Properties on tuple struct fields must be named explicitly, e.g. `#[property(name = \"my_property\")]`",
+                        ));
+                    }
+                };
+
+                let warn_reserved_name = crate::reserved::check_property_name(&label).map(|message| {
+                    let warning = crate::emit_warning(member.span(), "reserved_property_name", message);
+                    quote_spanned!(member.span()=>#warning;)
+                });
+
                 let with_default = config
                     .default
                     .map(|default_value| quote!(.with_default(#default_value)));
-                let with_hint = config.hint.map(|hint_fn| quote!(.with_hint(#hint_fn())));
-                let with_usage = config.no_editor.then(|| quote!(.with_usage(#gdnative_core::export::PropertyUsage::NOEDITOR)));
+                let with_hint = config.hint.map(|hint| {
+                    let hint = match hint {
+                        PropertyHint::Path(hint_fn) => quote!(#hint_fn()),
+                        PropertyHint::Resource(types) => {
+                            quote!(#gdnative_core::export::hint::ResourceHint::new([#(#types),*]))
+                        }
+                        PropertyHint::File(filters) => {
+                            quote!(#gdnative_core::export::hint::StringHint::file([#(#filters),*]))
+                        }
+                    };
+                    quote!(.with_hint(::std::convert::Into::into(#hint)))
+                });
+                let refresh_inspector = (config.group_toggle || config.refresh_inspector)
+                    .then(|| quote!(| #gdnative_core::export::PropertyUsage::UPDATE_ALL_IF_MODIFIED));
+                let usage = if let Some(flags) = &config.usage {
+                    Some(quote!(#gdnative_core::export::PropertyUsage::empty() #(| #gdnative_core::export::PropertyUsage::#flags)* #refresh_inspector))
+                } else {
+                    (config.no_editor || refresh_inspector.is_some() || config.storage).then(|| {
+                        let usage = if config.no_editor { quote!(NOEDITOR) } else { quote!(DEFAULT) };
+                        quote!(#gdnative_core::export::PropertyUsage::#usage #refresh_inspector)
+                    })
+                };
+                // Storage fields are saved through the versioned state instead of individually
+                let with_usage = usage.map(|usage| {
+                    if config.storage {
+                        quote!(.with_usage((#usage) - #gdnative_core::export::PropertyUsage::STORAGE))
+                    } else {
+                        quote!(.with_usage(#usage))
+                    }
+                });
+                let visible_if = config
+                    .visible_if
+                    .iter()
+                    .map(|path| quote!(#path(this)))
+                    .chain(
+                        group_toggles
+                            .iter()
+                            .filter(|(group, toggle)| label.starts_with(group.as_str()) && *toggle != member)
+                            .map(|(_, toggle)| quote!(this.#toggle)),
+                    )
+                    .collect::<Vec<_>>();
+                let with_visible_if = (!visible_if.is_empty())
+                    .then(|| quote!(.with_visible_if(|this: &Self| #(#visible_if)&&*)));
                 let with_rpc_mode = config.rpc_mode.map(|rpc_mode| quote!(.with_rpc_mode(#gdnative_core::export::#rpc_mode)));
 
                 // check whether this property type is `Property<T>`. if so, extract T from it.
-                let property_ty = match config.ty {
-                    Type::Path(ref path) => path
-                        .path
-                        .segments
-                        .iter()
-                        .last()
-                        .filter(|seg| seg.ident == "Property")
-                        .and_then(|seg| match seg.arguments {
-                            syn::PathArguments::AngleBracketed(ref params) => params.args.first(),
-                            _ => None,
-                        })
-                        .and_then(|arg| match arg {
-                            syn::GenericArgument::Type(ref ty) => Some(ty),
-                            _ => None,
-                        })
-                        .map(|ty| quote!(::<#ty>)),
-                    _ => None,
-                };
+                let property_ty = generic_argument_of(&config.ty, "Property").map(|ty| quote!(::<#ty>));
 
                 // Attribute is #[property] (or has other arguments which are not relevant here)
                 let is_standalone_attribute = config.get.is_none() && config.set.is_none();
@@ -134,7 +383,7 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
                     && (is_standalone_attribute || has_default_getter || has_default_setter)
                 {
                     return Err(syn::Error::new(
-                        ident.span(),
+                        member.span(),
                         "The `#[property]` attribute requires explicit paths for `get` and `set` argument; \
                         the defaults #[property], #[property(get)] and #[property(set)] are not allowed."
                     ));
@@ -152,7 +401,7 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
                         _ => quote!(with_ref_getter),
                     };
                     let get: Expr = match get {
-                        PropertyGet::Default => parse_quote!(&this.#ident),
+                        PropertyGet::Default => parse_quote!(&this.#member),
                         PropertyGet::Owned(path_expr) | PropertyGet::Ref(path_expr) => parse_quote!(#path_expr(this, _owner))
                     };
                     quote!(
@@ -162,9 +411,10 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
                     )
                 });
                 let with_setter = set.map(|set| {
-                    let set: Stmt = match set {
-                        PropertySet::Default => parse_quote!(this.#ident = v;),
-                        PropertySet::WithPath(path_expr) => parse_quote!(#path_expr(this, _owner, v);),
+                    // Custom setters may return a `Result` to reject the value
+                    let set: Expr = match set {
+                        PropertySet::Default => parse_quote!({ this.#member = v; }),
+                        PropertySet::WithPath(path_expr) => parse_quote!(#path_expr(this, _owner, v)),
                     };
                     quote!(
                     .with_setter(|this: &mut Self, _owner: #gdnative_core::object::TRef<Self::Base>, v| {
@@ -172,16 +422,17 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
                     }))
                 });
 
-                let label = config.path.unwrap_or_else(|| format!("{ident}"));
                 Ok(quote!({
                     builder.property #property_ty(#label)
                         #with_default
                         #with_hint
                         #with_usage
                         #with_rpc_mode
+                        #with_visible_if
                         #with_getter
                         #with_setter
                         .done();
+                    #warn_reserved_name
                 }))
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -204,6 +455,40 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
             }
         });
 
+        let property_bag = data.property_bag.map(|member| {
+            quote! {
+                builder.property_bag(|this: &Self| &this.#member, |this: &mut Self| &mut this.#member);
+            }
+        });
+
+        let resolve_nodes = (!data.nodes.is_empty()).then(|| {
+            let gdnative_bindings = crate::crate_gdnative_bindings();
+            let fields = data.nodes.iter().map(|(member, path)| {
+                let field = match member {
+                    Member::Named(ident) => ident.to_string(),
+                    Member::Unnamed(index) => index.index.to_string(),
+                };
+                quote_spanned!(path.span()=>
+                    self.#member = ::std::option::Option::Some(
+                        #gdnative_bindings::utils::resolve_node_field(base, #path, #field)?,
+                    );
+                )
+            });
+
+            quote! {
+                #derived
+                impl #impl_generics #gdnative_bindings::utils::ResolveNodes for #name #ty_generics #where_clause {
+                    unsafe fn resolve_nodes(
+                        &mut self,
+                        base: &#gdnative_bindings::Node,
+                    ) -> ::std::result::Result<(), #gdnative_bindings::utils::NodeFieldError> {
+                        #(#fields)*
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            }
+        });
+
         let init = if data.no_constructor {
             None
         } else {
@@ -214,6 +499,14 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
             })
         };
 
+        let should_register = data.cfg_godot.map(|cfg_godot| {
+            quote! {
+                fn nativeclass_should_register() -> bool {
+                    #cfg_godot
+                }
+            }
+        });
+
         quote!(
             #derived
             impl #impl_generics #gdnative_core::export::NativeClass for #name #ty_generics #where_clause {
@@ -221,14 +514,19 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
                 type UserData = #user_data;
 
                 #init
+                #should_register
 
                 fn nativeclass_register_properties(builder: &#gdnative_core::export::ClassBuilder<Self>) {
                     #(#properties)*;
+                    #property_bag
+                    #storage
                     #register_callback
                 }
             }
 
             #maybe_statically_named
+            #resolve_nodes
+            #user_data_checks
         )
     };
 
@@ -236,6 +534,15 @@ pub(crate) fn derive_native_class(derive_input: &DeriveInput) -> Result<TokenStr
     Ok(trait_impl)
 }
 
+/// Returns the exported name of a property that isn't named explicitly.
+fn rename_property(rule: Option<RenameRule>, ident: &Ident) -> String {
+    let name = ident.to_string();
+    match rule {
+        Some(rule) => rule.apply(&name),
+        None => name,
+    }
+}
+
 fn parse_derive_input(input: &DeriveInput) -> Result<DeriveData, syn::Error> {
     let span = proc_macro2::Span::call_site();
     let gdnative_core = crate::crate_gdnative_core();
@@ -243,6 +550,18 @@ fn parse_derive_input(input: &DeriveInput) -> Result<DeriveData, syn::Error> {
 
     let ident = input.ident.clone();
 
+    if let Some(param) = input.generics.lifetimes().next() {
+        return Err(syn::Error::new(
+            param.span(),
+            "NativeClass types can't have lifetime parameters, since their instances are owned \
+             by Godot and may outlive any borrow\n\
+             \n\
+             help: to share state with other code, store it in an `Arc` instead, e.g. \
+             `state: Arc<GameState>`, and consider `#[user_data(auto)]` to choose a thread-safe \
+             user-data wrapper",
+        ));
+    }
+
     let inherit_attr = input.attrs.iter().find(|a| a.path.is_ident("inherit"));
 
     // read base class
@@ -252,8 +571,27 @@ fn parse_derive_input(input: &DeriveInput) -> Result<DeriveData, syn::Error> {
         syn::parse2::<Type>(quote! { #gdnative_bindings::Reference }).unwrap()
     };
 
+    let native_class = input
+        .attrs
+        .iter()
+        .find(|a| a.path.is_ident("native_class"))
+        .map(NativeClassArgs::parse)
+        .transpose()?
+        .unwrap_or_default();
+
     let godot_name = if input.generics.params.is_empty() {
-        Some(ident.to_string())
+        Some(
+            native_class
+                .rename
+                .map_or_else(|| ident.to_string(), |name| name.value()),
+        )
+    } else if let Some(name) = native_class.rename {
+        return Err(syn::Error::new(
+            name.span(),
+            "generic NativeClass types can't be renamed, since they have no single class name\n\
+             \n\
+             help: register each monomorphization with `InitHandle::add_class_as` instead",
+        ));
     } else {
         None
     };
@@ -265,23 +603,27 @@ fn parse_derive_input(input: &DeriveInput) -> Result<DeriveData, syn::Error> {
         .map(|attr| attr.parse_args::<Path>())
         .transpose()?;
 
-    let user_data = input
+    let user_data_attr = input
         .attrs
         .iter()
         .find(|a| a.path.is_ident("user_data"))
         .map(|attr| attr.parse_args::<Type>())
-        .unwrap_or_else(|| {
-            Ok(syn::parse2::<Type>(
-                quote! { #gdnative_core::export::user_data::DefaultUserData<Self> },
-            )
-            .expect("quoted tokens for default userdata should be a valid type"))
-        })?;
+        .transpose()?;
 
     let no_constructor = input
         .attrs
         .iter()
         .any(|a| a.path.is_ident("no_constructor"));
 
+    let cfg_godot = CfgGodot::parse_attrs(&input.attrs)?;
+
+    let storage = input
+        .attrs
+        .iter()
+        .find(|a| a.path.is_ident("storage"))
+        .map(StorageArgs::parse)
+        .transpose()?;
+
     // make sure it's a struct
     let struct_data = if let Data::Struct(data) = &input.data {
         data
@@ -292,68 +634,177 @@ fn parse_derive_input(input: &DeriveInput) -> Result<DeriveData, syn::Error> {
         ));
     };
 
-    // Find all fields with a `#[property]` attribute
+    // Find all fields with a `#[property]` or `#[node]` attribute, and the `PropertyBag` field
+    // if any
     let mut properties = Vec::new();
+    let mut property_bag = None;
+    let mut nodes = Vec::new();
+
+    // Unit structs have no fields, and thus no properties
+    let fields = match &struct_data.fields {
+        Fields::Named(names) => Some(&names.named),
+        Fields::Unnamed(unnamed) => Some(&unnamed.unnamed),
+        Fields::Unit => None,
+    };
 
-    if let Fields::Named(names) = &struct_data.fields {
-        for field in &names.named {
+    if let Some(fields) = fields {
+        for (index, field) in fields.iter().enumerate() {
             let mut property_args = None;
+            let mut node_path = None;
 
             for attr in field.attrs.iter() {
+                if attr.path.is_ident("node") {
+                    let path = attr.parse_args::<syn::LitStr>()?;
+                    if node_path.replace(path).is_some() {
+                        return Err(syn::Error::new(
+                            attr.span(),
+                            "duplicate `#[node]` attribute",
+                        ));
+                    }
+                    continue;
+                }
+
                 if !attr.path.is_ident("property") {
                     continue;
                 }
 
-                let meta = attr.parse_meta()?;
-
-                match meta {
-                    Meta::List(MetaList { nested, .. }) => {
-                        let attr_args_builder = property_args
-                            .get_or_insert_with(|| PropertyAttrArgsBuilder::new(&field.ty));
-
-                        for arg in nested {
-                            if let NestedMeta::Meta(Meta::NameValue(ref pair)) = arg {
-                                attr_args_builder.add_pair(pair)?;
-                            } else if let NestedMeta::Meta(Meta::Path(ref path)) = arg {
-                                attr_args_builder.add_path(path)?;
-                            } else {
-                                let msg = format!("Unexpected argument: {arg:?}");
-                                return Err(syn::Error::new(arg.span(), msg));
-                            }
-                        }
-                    }
-                    Meta::Path(_) => {
-                        property_args
-                            .get_or_insert_with(|| PropertyAttrArgsBuilder::new(&field.ty));
-                    }
-                    m => {
-                        let msg = format!("Unexpected meta variant: {m:?}");
-                        return Err(syn::Error::new(m.span(), msg));
+                let attr_args_builder =
+                    property_args.get_or_insert_with(|| PropertyAttrArgsBuilder::new(&field.ty));
+
+                // `#[property]` without arguments
+                if attr.tokens.is_empty() {
+                    continue;
+                }
+
+                // Not parsed as `Meta`, since `default` takes an expression instead of a literal
+                let args =
+                    attr.parse_args_with(Punctuated::<PropertyArg, Token![,]>::parse_terminated)?;
+                for arg in args {
+                    match arg {
+                        PropertyArg::Path(path) => attr_args_builder.add_path(&path)?,
+                        PropertyArg::Pair(pair) => attr_args_builder.add_pair(&pair)?,
+                        PropertyArg::Default(expr) => attr_args_builder.add_default(expr)?,
                     }
                 }
             }
 
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(syn::Index {
+                    index: index as u32,
+                    span: field.span(),
+                }),
+            };
+
+            if let Some(path) = node_path {
+                nodes.push((member.clone(), path));
+            }
+
             if let Some(builder) = property_args {
-                let ident = field
-                    .ident
-                    .clone()
-                    .ok_or_else(|| syn::Error::new(field.ident.span(), "Fields should be named"))?;
-                properties.push((ident, builder.done()));
+                properties.push((member, builder.done()));
+            } else if is_property_bag_type(&field.ty) {
+                if property_bag.is_some() {
+                    return Err(syn::Error::new(
+                        field.ty.span(),
+                        "only one `PropertyBag` field is allowed per NativeClass",
+                    ));
+                }
+
+                property_bag = Some(member);
             }
         }
     };
 
+    let (user_data, user_data_checks) = match user_data_attr {
+        Some(Type::Path(path)) if path.qself.is_none() && path.path.is_ident("auto") => {
+            auto_user_data(input, fields.into_iter().flatten())
+        }
+        Some(ty) => (ty, TokenStream2::new()),
+        None => (
+            syn::parse2::<Type>(
+                quote! { #gdnative_core::export::user_data::DefaultUserData<Self> },
+            )
+            .expect("quoted tokens for default userdata should be a valid type"),
+            TokenStream2::new(),
+        ),
+    };
+
     Ok(DeriveData {
         name: ident,
         godot_name,
         base,
         register_callback,
         user_data,
+        user_data_checks,
         properties,
+        rename_all: native_class.rename_all,
+        property_bag,
+        nodes,
+        storage,
         no_constructor,
+        cfg_godot,
     })
 }
 
+/// Chooses the user-data wrapper for `#[user_data(auto)]` from the field types:
+///
+/// - `ArcData` if all fields are `Arc`s, since the state is then shared immutably anyway.
+/// - `MutexData` if some fields are `Arc`s, since the state is then likely used from other
+///   threads as well.
+/// - `DefaultUserData` otherwise.
+///
+/// Also returns assertions that the fields satisfy the `Send` and `Sync` bounds of the chosen
+/// wrapper, so that violations are reported at the field instead of the derive.
+fn auto_user_data<'a>(
+    input: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+) -> (Type, TokenStream2) {
+    let gdnative_core = crate::crate_gdnative_core();
+    let fields = fields.into_iter().collect::<Vec<_>>();
+    let is_arc = |field: &&syn::Field| generic_argument_of(&field.ty, "Arc").is_some();
+
+    let (user_data, bound) = if fields.is_empty() || !fields.iter().any(is_arc) {
+        return (
+            parse_quote!(#gdnative_core::export::user_data::DefaultUserData<Self>),
+            TokenStream2::new(),
+        );
+    } else if fields.iter().all(is_arc) {
+        (
+            parse_quote!(#gdnative_core::export::user_data::ArcData<Self>),
+            quote!(Send + Sync),
+        )
+    } else {
+        (
+            parse_quote!(#gdnative_core::export::user_data::MutexData<Self>),
+            quote!(Send),
+        )
+    };
+
+    // Generic field types can't be named outside of an impl, but the bounds on the wrapper are
+    // still checked there, if less legibly.
+    if !input.generics.params.is_empty() {
+        return (user_data, TokenStream2::new());
+    }
+
+    let assertions = fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote_spanned!(ty.span()=> __assert_thread_safe::<#ty>();)
+    });
+
+    let checks = quote! {
+        const _: () = {
+            fn __assert_thread_safe<T: ?Sized + #bound>() {}
+
+            #[allow(dead_code)]
+            fn __assert_fields() {
+                #(#assertions)*
+            }
+        };
+    };
+
+    (user_data, checks)
+}
+
 pub(crate) fn derive_monomorphize(
     args: AttributeArgs,
     mut item_type: ItemType,
@@ -437,6 +888,39 @@ mod tests {
         parse_derive_input(&input).unwrap();
     }
 
+    #[test]
+    fn derive_property_usage() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(usage = "STORAGE | NETWORK")]
+                bar: String,
+            }
+        };
+        parse_derive_input(&input).unwrap();
+    }
+
+    #[test]
+    fn derive_property_usage_invalid() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(usage = "STORAGE |")]
+                bar: String,
+            }
+        };
+        assert!(parse_derive_input(&input).is_err());
+
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(no_editor, usage = "STORAGE")]
+                bar: String,
+            }
+        };
+        assert!(parse_derive_input(&input).is_err());
+    }
+
     #[test]
     fn derive_property_get_set() {
         let input = parse_quote! {
@@ -473,6 +957,253 @@ mod tests {
         parse_derive_input(&input).unwrap();
     }
 
+    #[test]
+    fn derive_property_option_default() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(default = Some(42))]
+                bar: Option<i64>,
+                #[property(default = None)]
+                baz: Option<i64>,
+            }
+        };
+        let tokens = derive_native_class(&input).unwrap().to_string();
+        assert!(tokens.contains("with_default (Some (42))"));
+        assert!(tokens.contains("with_default (None)"));
+    }
+
+    #[test]
+    fn derive_node_fields() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[node("%Player")]
+                player: Option<Ref<Node2D>>,
+                #[node("Hud/Score")]
+                score: Option<Ref<Label>>,
+                bar: i64,
+            }
+        };
+        let data = parse_derive_input(&input).unwrap();
+        assert_eq!(
+            vec!["%Player".to_owned(), "Hud/Score".to_owned()],
+            data.nodes
+                .iter()
+                .map(|(_, path)| path.value())
+                .collect::<Vec<_>>()
+        );
+
+        let tokens = derive_native_class(&input).unwrap().to_string();
+        assert!(tokens.contains("ResolveNodes for Foo"));
+
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[node("Player")]
+                #[node("Enemy")]
+                player: Option<Ref<Node2D>>,
+            }
+        };
+        assert!(parse_derive_input(&input).is_err());
+    }
+
+    #[test]
+    fn derive_property_tuple_struct() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo(
+                #[property(name = "bar")]
+                String,
+                i64,
+                #[property(name = "baz", default = 42)]
+                i64,
+            );
+        };
+        let data = parse_derive_input(&input).unwrap();
+        assert_eq!(2, data.properties.len());
+        assert_eq!(Member::Unnamed(parse_quote!(0)), data.properties[0].0);
+        assert_eq!(Member::Unnamed(parse_quote!(2)), data.properties[1].0);
+        derive_native_class(&input).unwrap();
+    }
+
+    #[test]
+    fn derive_property_tuple_struct_unnamed() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo(#[property] String);
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("must be named explicitly"));
+    }
+
+    #[test]
+    fn derive_unit_struct() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo;
+        };
+        let data = parse_derive_input(&input).unwrap();
+        assert!(data.properties.is_empty());
+    }
+
+    #[test]
+    fn derive_lifetime() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo<'a> {
+                bar: &'a str,
+            }
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("can't have lifetime parameters"));
+        assert!(impl_empty_nativeclass(&input).is_empty());
+    }
+
+    #[test]
+    fn derive_user_data_auto() {
+        let user_data = |input: DeriveInput| {
+            let data = parse_derive_input(&input).unwrap();
+            let ty = &data.user_data;
+            quote!(#ty).to_string()
+        };
+
+        let all_arc = parse_quote! {
+            #[user_data(auto)]
+            struct Foo {
+                state: std::sync::Arc<GameState>,
+                log: Arc<Log>,
+            }
+        };
+        assert!(user_data(all_arc).contains("ArcData"));
+
+        let some_arc = parse_quote! {
+            #[user_data(auto)]
+            struct Foo(Arc<GameState>, u64);
+        };
+        assert!(user_data(some_arc).contains("MutexData"));
+
+        let no_arc = parse_quote! {
+            #[user_data(auto)]
+            struct Foo {
+                ticks: u64,
+            }
+        };
+        assert!(user_data(no_arc).contains("DefaultUserData"));
+
+        let unit = parse_quote! {
+            #[user_data(auto)]
+            struct Foo;
+        };
+        assert!(user_data(unit).contains("DefaultUserData"));
+    }
+
+    #[test]
+    fn derive_cfg_godot() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            #[cfg_godot(any(platform = "android", not(feature = "editor")))]
+            #[cfg_godot(platform = "html5")]
+            struct Foo;
+        };
+        let data = parse_derive_input(&input).unwrap();
+        assert!(matches!(data.cfg_godot, Some(CfgGodot::All(ref p)) if p.len() == 2));
+
+        let input = parse_quote! {
+            #[inherit(Node)]
+            #[cfg_godot(platform = "beos")]
+            struct Foo;
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("unknown platform `beos`"));
+    }
+
+    #[test]
+    fn derive_native_class_rename() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            #[native_class(rename = "PlayerStats", rename_all = "camelCase")]
+            struct Foo {
+                #[property]
+                max_health: i64,
+                #[property(name = "armor_class")]
+                armor: i64,
+            }
+        };
+        let tokens = derive_native_class(&input).unwrap().to_string();
+        assert!(tokens.contains("\"PlayerStats\""));
+        assert!(tokens.contains("\"maxHealth\""));
+        assert!(tokens.contains("\"armor_class\""));
+
+        let input = parse_quote! {
+            #[native_class(rename = "Bar")]
+            struct Foo<T>(T);
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("can't be renamed"));
+
+        let input = parse_quote! {
+            #[native_class(rename_all = "kebab-case")]
+            struct Foo;
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("unknown naming convention"));
+    }
+
+    #[test]
+    fn derive_property_bag() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property]
+                bar: i64,
+                extra: gdnative::export::PropertyBag,
+            }
+        };
+        let data = parse_derive_input(&input).unwrap();
+        assert_eq!(1, data.properties.len());
+        assert_eq!(Some(Member::Named(parse_quote!(extra))), data.property_bag);
+
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                a: PropertyBag,
+                b: PropertyBag,
+            }
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("only one `PropertyBag` field"));
+    }
+
+    #[test]
+    fn derive_property_group_toggle() {
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(path = "shadows/enabled", group_toggle)]
+                shadows: bool,
+                #[property(path = "shadows/size", visible_if = "Self::is_large")]
+                shadow_size: i64,
+                #[property(path = "shadowsize")]
+                unrelated: i64,
+            }
+        };
+        let tokens = derive_native_class(&input).unwrap().to_string();
+        assert!(tokens.contains("UPDATE_ALL_IF_MODIFIED"));
+        assert!(tokens.contains("Self :: is_large (this) && this . shadows"));
+        assert_eq!(1, tokens.matches("with_visible_if").count());
+
+        let input = parse_quote! {
+            #[inherit(Node)]
+            struct Foo {
+                #[property(group_toggle)]
+                shadows: bool,
+            }
+        };
+        let err = derive_native_class(&input).unwrap_err();
+        assert!(err.to_string().contains("must be inside a group"));
+    }
+
     #[test]
     fn derive_property_combinations() {
         let attr_none = quote! {       #[property]                          };
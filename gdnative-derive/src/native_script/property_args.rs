@@ -1,5 +1,6 @@
 use proc_macro2::Span;
 use std::fmt::Debug;
+use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 
 use crate::syntax::rpc_mode::RpcMode;
@@ -11,32 +12,80 @@ pub enum PropertyGet {
     Ref(syn::Path),
 }
 
+#[derive(Debug)]
+pub enum PropertyHint {
+    /// `hint = "path::to::function"`
+    Path(syn::Path),
+    /// `hint_resource = "Type, OtherType"`
+    Resource(Vec<String>),
+    /// `hint_file = "*.png, *.jpg"`
+    File(Vec<String>),
+}
+
 #[derive(Debug)]
 pub enum PropertySet {
     Default,
     WithPath(syn::Path),
 }
 
+/// An argument of `#[property(...)]`. Values are literals, except for `default`, which takes
+/// any expression, e.g. `default = Some(42)`.
+pub enum PropertyArg {
+    Path(syn::Path),
+    Pair(syn::MetaNameValue),
+    Default(syn::Expr),
+}
+
+impl Parse for PropertyArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.call(syn::Path::parse_mod_style)?;
+        if !input.peek(Token![=]) {
+            return Ok(PropertyArg::Path(path));
+        }
+
+        let eq_token = input.parse()?;
+        if path.is_ident("default") {
+            Ok(PropertyArg::Default(input.parse()?))
+        } else {
+            Ok(PropertyArg::Pair(syn::MetaNameValue {
+                path,
+                eq_token,
+                lit: input.parse()?,
+            }))
+        }
+    }
+}
+
 pub struct PropertyAttrArgs {
     pub ty: syn::Type,
     pub path: Option<String>,
-    pub default: Option<syn::Lit>,
-    pub hint: Option<syn::Path>,
+    pub default: Option<syn::Expr>,
+    pub hint: Option<PropertyHint>,
     pub get: Option<PropertyGet>,
     pub set: Option<PropertySet>,
     pub rpc_mode: Option<RpcMode>,
     pub no_editor: bool,
+    pub usage: Option<Vec<syn::Ident>>,
+    pub visible_if: Option<syn::Path>,
+    pub group_toggle: bool,
+    pub refresh_inspector: bool,
+    pub storage: bool,
 }
 
 pub struct PropertyAttrArgsBuilder {
     ty: syn::Type,
     path: Option<String>,
-    default: Option<syn::Lit>,
-    hint: Option<syn::Path>,
+    default: Option<syn::Expr>,
+    hint: Option<PropertyHint>,
     get: Option<PropertyGet>,
     set: Option<PropertySet>,
     rpc_mode: Option<RpcMode>,
     no_editor: bool,
+    usage: Option<Vec<syn::Ident>>,
+    visible_if: Option<syn::Path>,
+    group_toggle: bool,
+    refresh_inspector: bool,
+    storage: bool,
 }
 
 impl PropertyAttrArgsBuilder {
@@ -50,6 +99,11 @@ impl PropertyAttrArgsBuilder {
             set: None,
             rpc_mode: None,
             no_editor: false,
+            usage: None,
+            visible_if: None,
+            group_toggle: false,
+            refresh_inspector: false,
+            storage: false,
         }
     }
 
@@ -67,6 +121,70 @@ impl PropertyAttrArgsBuilder {
         syn::Error::new(span, format!("'{attr}' value is not a string literal"))
     }
 
+    // Error returned when both `usage` and `no_editor` are set
+    fn err_usage_with_no_editor(span: Span) -> syn::Error {
+        syn::Error::new(
+            span,
+            "'usage' and 'no_editor' can't be used together, add EDITOR to 'usage' instead",
+        )
+    }
+
+    /// Parses a list of `PropertyUsage` flags separated by `|`, e.g. `"STORAGE | NETWORK"`
+    fn parse_usage(lit: &syn::LitStr) -> Result<Vec<syn::Ident>, syn::Error> {
+        lit.value()
+            .split('|')
+            .map(|flag| {
+                let flag = flag.trim();
+                syn::parse_str::<syn::Ident>(flag)
+                    .map(|_| syn::Ident::new(flag, lit.span()))
+                    .map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "expected `PropertyUsage` flags separated by '|', found {flag:?}"
+                            ),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Parses a list of Godot class names separated by `,`, e.g. `"StreamTexture, ImageTexture"`
+    fn parse_resource_types(lit: &syn::LitStr) -> Result<Vec<String>, syn::Error> {
+        lit.value()
+            .split(',')
+            .map(|ty| {
+                let ty = ty.trim();
+                syn::parse_str::<syn::Ident>(ty)
+                    .map(|_| ty.to_owned())
+                    .map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            format!("expected class names separated by ',', found {ty:?}"),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Parses a list of file filters separated by `,`, e.g. `"*.png, *.jpg"`
+    fn parse_file_filters(lit: &syn::LitStr) -> Result<Vec<String>, syn::Error> {
+        let filters = lit
+            .value()
+            .split(',')
+            .map(|filter| filter.trim().to_owned())
+            .collect::<Vec<_>>();
+
+        if filters.iter().any(String::is_empty) {
+            return Err(syn::Error::new(
+                lit.span(),
+                "expected file filters separated by ',', e.g. \"*.png, *.jpg\"",
+            ));
+        }
+
+        Ok(filters)
+    }
+
     /// Convert `Lit` to `LitStr`
     fn extract_lit_str(lit: &syn::Lit) -> Option<&syn::LitStr> {
         if let syn::Lit::Str(lit_str) = lit {
@@ -120,16 +238,35 @@ impl PropertyAttrArgsBuilder {
             .expect("should be single identifier")
             .to_string();
         match name.as_str() {
-            "default" => update_prop!(default, pair.lit.clone()),
-            "path" => {
+            "path" | "name" => {
                 let path = Self::extract_lit_str(&pair.lit)
-                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), "path"))?;
+                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), &name))?;
                 update_prop!(path, path.value());
             }
-            "hint" => process_path_input!(hint),
+            "hint" => process_path_input!(hint, PropertyHint::Path),
+            "hint_resource" | "hint_file" => {
+                let lit = Self::extract_lit_str(&pair.lit)
+                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), &name))?;
+                let hint = if name == "hint_resource" {
+                    PropertyHint::Resource(Self::parse_resource_types(lit)?)
+                } else {
+                    PropertyHint::File(Self::parse_file_filters(lit)?)
+                };
+                update_prop!(hint, hint)
+            }
             "get" => process_path_input!(get, PropertyGet::Owned),
             "get_ref" => process_path_input!(get, PropertyGet::Ref),
             "set" => process_path_input!(set, PropertySet::WithPath),
+            "visible_if" => process_path_input!(visible_if),
+            "usage" => {
+                if self.no_editor {
+                    return Err(Self::err_usage_with_no_editor(pair.span()));
+                }
+                let usage = Self::extract_lit_str(&pair.lit)
+                    .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), "usage"))?;
+                let usage = Self::parse_usage(usage)?;
+                update_prop!(usage, usage)
+            }
             "rpc" => {
                 let rpc = Self::extract_lit_str(&pair.lit)
                     .ok_or_else(|| Self::err_attr_not_a_string_literal(pair.span(), "rpc"))?;
@@ -153,9 +290,26 @@ impl PropertyAttrArgsBuilder {
         Ok(())
     }
 
+    pub fn add_default(&mut self, default: syn::Expr) -> Result<(), syn::Error> {
+        let span = default.span();
+        if let Some(old) = self.default.replace(default) {
+            return Err(Self::err_prop_already_set(span, "default", &old));
+        }
+        Ok(())
+    }
+
     pub fn add_path(&mut self, path: &syn::Path) -> Result<(), syn::Error> {
         if path.is_ident("no_editor") {
+            if self.usage.is_some() {
+                return Err(Self::err_usage_with_no_editor(path.span()));
+            }
             self.no_editor = true;
+        } else if path.is_ident("group_toggle") {
+            self.group_toggle = true;
+        } else if path.is_ident("refresh_inspector") {
+            self.refresh_inspector = true;
+        } else if path.is_ident("storage") {
+            self.storage = true;
         } else if path.is_ident("get") {
             if let Some(get) = self.get.replace(PropertyGet::Default) {
                 return Err(Self::err_prop_already_set(path.span(), "get", &get));
@@ -186,6 +340,11 @@ impl PropertyAttrArgsBuilder {
             set: self.set,
             rpc_mode: self.rpc_mode,
             no_editor: self.no_editor,
+            usage: self.usage,
+            visible_if: self.visible_if,
+            group_toggle: self.group_toggle,
+            refresh_inspector: self.refresh_inspector,
+            storage: self.storage,
         }
     }
 }
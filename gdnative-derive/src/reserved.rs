@@ -0,0 +1,174 @@
+//! Names that exported methods and properties should avoid, because they collide with GDScript
+//! syntax or with members every Godot object already has.
+
+/// Keywords and reserved constants of GDScript (Godot 3.x).
+const GDSCRIPT_KEYWORDS: &[&str] = &[
+    "if",
+    "elif",
+    "else",
+    "for",
+    "while",
+    "match",
+    "break",
+    "continue",
+    "pass",
+    "return",
+    "class",
+    "class_name",
+    "extends",
+    "is",
+    "in",
+    "as",
+    "self",
+    "tool",
+    "signal",
+    "func",
+    "static",
+    "const",
+    "enum",
+    "var",
+    "onready",
+    "export",
+    "setget",
+    "breakpoint",
+    "preload",
+    "yield",
+    "assert",
+    "remote",
+    "master",
+    "puppet",
+    "remotesync",
+    "mastersync",
+    "puppetsync",
+    "PI",
+    "TAU",
+    "INF",
+    "NAN",
+];
+
+/// Methods of `Object`, which are available on all Godot objects.
+const OBJECT_METHODS: &[&str] = &[
+    "free",
+    "get",
+    "set",
+    "call",
+    "callv",
+    "call_deferred",
+    "set_deferred",
+    "get_indexed",
+    "set_indexed",
+    "connect",
+    "disconnect",
+    "is_connected",
+    "emit_signal",
+    "add_user_signal",
+    "has_signal",
+    "has_user_signal",
+    "get_signal_list",
+    "get_signal_connection_list",
+    "get_incoming_connections",
+    "get_class",
+    "is_class",
+    "get_script",
+    "set_script",
+    "get_instance_id",
+    "get_property_list",
+    "get_method_list",
+    "has_method",
+    "get_meta",
+    "set_meta",
+    "has_meta",
+    "remove_meta",
+    "get_meta_list",
+    "notification",
+    "property_list_changed_notify",
+    "is_queued_for_deletion",
+    "set_block_signals",
+    "is_blocking_signals",
+    "set_message_translation",
+    "can_translate_messages",
+    "tr",
+    "to_string",
+];
+
+/// Properties of `Object`, which are available on all Godot objects.
+const OBJECT_PROPERTIES: &[&str] = &["script"];
+
+/// Returns a warning message if `name` is a problematic name for an exported method.
+pub(crate) fn check_method_name(name: &str) -> Option<String> {
+    if name == "_init" {
+        return Some(
+            "`_init` is not the constructor of NativeScript classes, and is not called when an instance is created.\n\n\
+            Initialize the instance in `new` instead, or rename the method."
+                .to_owned(),
+        );
+    }
+
+    if GDSCRIPT_KEYWORDS.contains(&name) {
+        return Some(format!(
+            "`{name}` is a GDScript keyword, so the method can't be called by name from GDScript.\n\n\
+            Rename the method, or register it under a different name with #[method(name = \"...\")]."
+        ));
+    }
+
+    if OBJECT_METHODS.contains(&name) {
+        return Some(format!(
+            "`{name}` is a method of `Object`. The exported method shadows it for some calls but not for others, \
+            such as calls made by the engine itself.\n\n\
+            Rename the method, or register it under a different name with #[method(name = \"...\")]."
+        ));
+    }
+
+    None
+}
+
+/// Returns a warning message if `name` is a problematic name for an exported property.
+///
+/// Only top-level names are checked, since properties inside groups can't be accessed with the
+/// member syntax anyway.
+pub(crate) fn check_property_name(name: &str) -> Option<String> {
+    if name.contains('/') {
+        return None;
+    }
+
+    if GDSCRIPT_KEYWORDS.contains(&name) {
+        return Some(format!(
+            "`{name}` is a GDScript keyword, so the property can only be accessed with `get` and `set` from GDScript.\n\n\
+            Rename the field, or register it under a different name with #[property(name = \"...\")]."
+        ));
+    }
+
+    if OBJECT_PROPERTIES.contains(&name) {
+        return Some(format!(
+            "`{name}` is a property of `Object`, which the exported property conflicts with.\n\n\
+            Rename the field, or register it under a different name with #[property(name = \"...\")]."
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_names() {
+        assert!(check_method_name("_init").is_some());
+        assert!(check_method_name("match").is_some());
+        assert!(check_method_name("free").is_some());
+        assert!(check_method_name("get").is_some());
+        assert!(check_method_name("_ready").is_none());
+        assert!(check_method_name("get_value").is_none());
+        assert!(check_method_name("script").is_none());
+    }
+
+    #[test]
+    fn property_names() {
+        assert!(check_property_name("class").is_some());
+        assert!(check_property_name("script").is_some());
+        assert!(check_property_name("group/class").is_none());
+        assert!(check_property_name("free").is_none());
+        assert!(check_property_name("speed").is_none());
+    }
+}
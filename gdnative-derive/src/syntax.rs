@@ -1 +1,3 @@
+pub mod cfg_godot;
+pub mod rename_rule;
 pub mod rpc_mode;
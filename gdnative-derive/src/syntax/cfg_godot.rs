@@ -0,0 +1,179 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{spanned::Spanned, Attribute, Lit, Meta, NestedMeta};
+
+/// Predicate of a `#[cfg_godot(...)]` attribute, evaluated at init time.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CfgGodot {
+    /// `platform = "..."`: compile-time target check combined with the runtime feature tag.
+    Platform {
+        cfg: &'static str,
+        tag: &'static str,
+    },
+    /// `feature = "..."`: arbitrary runtime feature tag.
+    Feature(String),
+    Any(Vec<CfgGodot>),
+    All(Vec<CfgGodot>),
+    Not(Box<CfgGodot>),
+}
+
+/// Supported values for `platform`, with the corresponding `cfg!` predicates and the feature
+/// tags used by `OS.has_feature`.
+const PLATFORMS: &[(&str, &str, &str)] = &[
+    ("android", "target_os = \"android\"", "Android"),
+    ("ios", "target_os = \"ios\"", "iOS"),
+    (
+        "html5",
+        "all(target_arch = \"wasm32\", target_os = \"emscripten\")",
+        "HTML5",
+    ),
+    ("macos", "target_os = \"macos\"", "OSX"),
+    ("windows", "target_os = \"windows\"", "Windows"),
+    ("linux", "target_os = \"linux\"", "X11"),
+    (
+        "uwp",
+        "all(target_os = \"windows\", target_vendor = \"uwp\")",
+        "UWP",
+    ),
+    ("server", "target_os = \"linux\"", "Server"),
+];
+
+impl CfgGodot {
+    /// Parses all `#[cfg_godot]` attributes in `attrs`. Multiple attributes must all be satisfied.
+    /// Returns `None` if there are none.
+    pub fn parse_attrs<'a>(
+        attrs: impl IntoIterator<Item = &'a Attribute>,
+    ) -> Result<Option<Self>, syn::Error> {
+        let mut predicates = attrs
+            .into_iter()
+            .filter(|attr| attr.path.is_ident("cfg_godot"))
+            .map(Self::parse_attr)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(match predicates.len() {
+            0 => None,
+            1 => predicates.pop(),
+            _ => Some(CfgGodot::All(predicates)),
+        })
+    }
+
+    pub fn parse_attr(attr: &Attribute) -> Result<Self, syn::Error> {
+        match attr.parse_meta()? {
+            Meta::List(list) if list.nested.len() == 1 => {
+                Self::parse_nested(list.nested.first().expect("length checked"))
+            }
+            meta => Err(syn::Error::new(
+                meta.span(),
+                "#[cfg_godot] expects exactly one predicate, e.g. `#[cfg_godot(platform = \"android\")]`",
+            )),
+        }
+    }
+
+    fn parse_nested(nested: &NestedMeta) -> Result<Self, syn::Error> {
+        let meta = match nested {
+            NestedMeta::Meta(meta) => meta,
+            NestedMeta::Lit(lit) => {
+                return Err(syn::Error::new(lit.span(), "unexpected literal predicate"))
+            }
+        };
+
+        match meta {
+            Meta::NameValue(pair) => {
+                let value = match &pair.lit {
+                    Lit::Str(lit) => lit.value(),
+                    lit => return Err(syn::Error::new(lit.span(), "expected string value")),
+                };
+
+                if pair.path.is_ident("platform") {
+                    let &(_, cfg, tag) = PLATFORMS
+                        .iter()
+                        .find(|(name, _, _)| name.eq_ignore_ascii_case(&value))
+                        .ok_or_else(|| {
+                            let names = PLATFORMS
+                                .iter()
+                                .map(|(name, _, _)| format!("`{name}`"))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+
+                            syn::Error::new(
+                                pair.lit.span(),
+                                format!("unknown platform `{value}`, expected one of {names}"),
+                            )
+                        })?;
+
+                    Ok(CfgGodot::Platform { cfg, tag })
+                } else if pair.path.is_ident("feature") {
+                    Ok(CfgGodot::Feature(value))
+                } else {
+                    Err(syn::Error::new(
+                        pair.path.span(),
+                        format!(
+                            "unknown predicate `{}`, expected `platform` or `feature`",
+                            pair.path.to_token_stream(),
+                        ),
+                    ))
+                }
+            }
+            Meta::List(list) => {
+                let predicates = list
+                    .nested
+                    .iter()
+                    .map(Self::parse_nested)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if list.path.is_ident("any") {
+                    Ok(CfgGodot::Any(predicates))
+                } else if list.path.is_ident("all") {
+                    Ok(CfgGodot::All(predicates))
+                } else if list.path.is_ident("not") {
+                    match <[CfgGodot; 1]>::try_from(predicates) {
+                        Ok([predicate]) => Ok(CfgGodot::Not(Box::new(predicate))),
+                        Err(_) => Err(syn::Error::new(
+                            list.span(),
+                            "`not` expects exactly one predicate",
+                        )),
+                    }
+                } else {
+                    Err(syn::Error::new(
+                        list.path.span(),
+                        format!(
+                            "unknown combinator `{}`, expected `any`, `all` or `not`",
+                            list.path.to_token_stream(),
+                        ),
+                    ))
+                }
+            }
+            Meta::Path(path) => Err(syn::Error::new(
+                path.span(),
+                "expected a predicate like `platform = \"android\"`",
+            )),
+        }
+    }
+}
+
+impl ToTokens for CfgGodot {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let gdnative_core = crate::crate_gdnative_core();
+
+        match self {
+            CfgGodot::Platform { cfg, tag } => {
+                let cfg: TokenStream2 = cfg.parse().expect("predicates in PLATFORMS are valid");
+                tokens.extend(quote! {
+                    (::std::cfg!(#cfg) && #gdnative_core::private::os_has_feature(#tag))
+                });
+            }
+            CfgGodot::Feature(tag) => tokens.extend(quote! {
+                #gdnative_core::private::os_has_feature(#tag)
+            }),
+            CfgGodot::Any(predicates) => tokens.extend(quote! {
+                (false #(|| #predicates)*)
+            }),
+            CfgGodot::All(predicates) => tokens.extend(quote! {
+                (true #(&& #predicates)*)
+            }),
+            CfgGodot::Not(predicate) => tokens.extend(quote! {
+                !#predicate
+            }),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/// Naming convention applied to exported names by `rename_all`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RenameRule {
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+}
+
+impl RenameRule {
+    const ALL: &'static [(&'static str, RenameRule)] = &[
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("camelCase", RenameRule::Camel),
+        ("PascalCase", RenameRule::Pascal),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, rule)| *rule)
+    }
+
+    /// Parses a rule from a string literal, with an error listing the supported rules.
+    pub fn parse_lit(lit: &syn::LitStr) -> Result<Self, syn::Error> {
+        Self::parse(&lit.value()).ok_or_else(|| {
+            let names = Self::ALL
+                .iter()
+                .map(|(name, _)| format!("\"{name}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            syn::Error::new(
+                lit.span(),
+                format!("unknown naming convention, expected one of: {names}"),
+            )
+        })
+    }
+
+    /// Applies the rule to a `snake_case` Rust identifier. Identifiers starting with an
+    /// underscore are left unchanged, since they are used for engine callbacks such as
+    /// `_unhandled_input`, whose names are fixed.
+    pub fn apply(self, ident: &str) -> String {
+        let ident = ident.strip_prefix("r#").unwrap_or(ident);
+        if ident.starts_with('_') {
+            return ident.to_owned();
+        }
+
+        let words = ident.split('_').filter(|word| !word.is_empty());
+
+        match self {
+            RenameRule::Lower => words.collect::<String>().to_lowercase(),
+            RenameRule::Upper => words.collect::<String>().to_uppercase(),
+            RenameRule::Snake => words.collect::<Vec<_>>().join("_").to_lowercase(),
+            RenameRule::ScreamingSnake => words.collect::<Vec<_>>().join("_").to_uppercase(),
+            RenameRule::Pascal => words.map(capitalize).collect(),
+            RenameRule::Camel => words
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_rules() {
+        let cases = [
+            (RenameRule::Lower, "max_health", "maxhealth"),
+            (RenameRule::Upper, "max_health", "MAXHEALTH"),
+            (RenameRule::Camel, "max_health", "maxHealth"),
+            (RenameRule::Pascal, "max_health", "MaxHealth"),
+            (RenameRule::Snake, "max_health", "max_health"),
+            (RenameRule::ScreamingSnake, "max_health", "MAX_HEALTH"),
+            (RenameRule::Camel, "get_2d_position", "get2dPosition"),
+            (RenameRule::Camel, "_unhandled_input", "_unhandled_input"),
+            (RenameRule::Pascal, "r#type", "Type"),
+            (RenameRule::Camel, "single", "single"),
+        ];
+
+        for (rule, input, expected) in cases {
+            assert_eq!(rule.apply(input), expected, "{rule:?} of {input}");
+        }
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(RenameRule::parse("camelCase"), Some(RenameRule::Camel));
+        assert_eq!(RenameRule::parse("camelcase"), None);
+    }
+}
@@ -8,11 +8,13 @@ use super::AttrBuilder;
 #[derive(Clone, Debug)]
 pub struct ItemAttr {
     pub enum_repr_kind: Option<(EnumReprKind, Span)>,
+    pub transparent: Option<Span>,
 }
 
 #[derive(Debug, Default)]
 pub struct ItemAttrBuilder {
     enum_repr_kind: Option<syn::Ident>,
+    transparent: Option<Span>,
 
     errors: Vec<syn::Error>,
 }
@@ -42,6 +44,16 @@ impl ItemAttrBuilder {
     }
 
     fn try_set_flag(&mut self, flag: &syn::Path) -> Result<(), syn::Error> {
+        if flag.is_ident("transparent") {
+            if self.transparent.replace(flag.span()).is_some() {
+                return Err(syn::Error::new(
+                    flag.span(),
+                    "the argument transparent is already set",
+                ));
+            }
+            return Ok(());
+        }
+
         Err(generate_error_with_docs(
             flag.span(),
             "Unknown flag, or missing macro arguments",
@@ -122,7 +134,10 @@ impl AttrBuilder for ItemAttrBuilder {
                 })
                 .transpose()?;
 
-            Ok(ItemAttr { enum_repr_kind })
+            Ok(ItemAttr {
+                enum_repr_kind,
+                transparent: self.transparent,
+            })
         } else {
             let first_error = self.errors.remove(0);
             let errors = self
@@ -3,7 +3,7 @@ use syn::visit::Visit;
 use syn::{GenericParam, Generics};
 
 use crate::utils::extend_bounds::{with_visitor, BoundsVisitor};
-use crate::variant::repr::StructRepr;
+use crate::variant::repr::{StructRepr, TransparentRepr};
 
 use super::repr::{EnumRepr, Field, Repr, VariantRepr};
 use super::Direction;
@@ -49,6 +49,9 @@ pub(crate) fn extend_bounds(
             Repr::Struct(StructRepr(var_repr)) => {
                 visit_var_repr(visitor, var_repr, dir);
             }
+            Repr::Transparent(TransparentRepr { field, .. }) => {
+                visitor.visit_type(&field.ty);
+            }
         }
     })
 }
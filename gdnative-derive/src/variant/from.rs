@@ -31,6 +31,7 @@ pub(crate) fn expand_from_variant(derive_data: DeriveData) -> Result<TokenStream
                 }
             }
         }
+        Repr::Transparent(transparent) => transparent.make_from_variant_expr(&ident, &input_ident),
         Repr::Enum(EnumRepr {
             variants,
             kind,
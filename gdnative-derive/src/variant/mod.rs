@@ -12,7 +12,7 @@ use repr::Repr;
 
 use self::{
     attr::{AttrBuilder, ItemAttrBuilder},
-    repr::{EnumRepr, StructRepr},
+    repr::{EnumRepr, StructRepr, TransparentRepr},
 };
 
 pub(crate) struct DeriveData {
@@ -90,9 +90,14 @@ pub(crate) fn parse_derive_input(
     let item_attr = parse_attrs::<ItemAttrBuilder, _>(&input.attrs)?;
 
     let repr = match input.data {
-        Data::Struct(struct_data) => {
-            Repr::Struct(StructRepr::repr_for(item_attr, &struct_data.fields)?)
-        }
+        Data::Struct(struct_data) => match item_attr.transparent {
+            Some(span) => Repr::Transparent(TransparentRepr::repr_for(
+                item_attr,
+                span,
+                &struct_data.fields,
+            )?),
+            None => Repr::Struct(StructRepr::repr_for(item_attr, &struct_data.fields)?),
+        },
         Data::Enum(enum_data) => {
             let primitive_repr = input.attrs.iter().find_map(|attr| {
                 if !attr.path.is_ident("repr") {
@@ -175,4 +180,37 @@ mod tests {
 
         derive_from_variant(input).unwrap();
     }
+
+    #[test]
+    fn derive_transparent() {
+        let input: DeriveInput = parse_quote! {
+            #[variant(transparent)]
+            struct Health {
+                value: u32,
+                #[variant(skip)]
+                cache: Option<u32>,
+            }
+        };
+        derive_from_variant(input).unwrap();
+
+        let input: DeriveInput = parse_quote! {
+            #[variant(transparent)]
+            struct Position(f32, f32);
+        };
+        assert!(derive_from_variant(input).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            #[variant(transparent)]
+            struct Empty;
+        };
+        assert!(derive_from_variant(input).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            #[variant(transparent)]
+            enum Health {
+                Alive(u32),
+            }
+        };
+        assert!(derive_from_variant(input).is_err());
+    }
 }
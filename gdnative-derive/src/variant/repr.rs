@@ -10,6 +10,7 @@ use super::{parse_attrs, ToVariantTrait};
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) enum Repr {
     Struct(StructRepr),
+    Transparent(TransparentRepr),
     Enum(EnumRepr),
 }
 
@@ -31,6 +32,14 @@ pub(crate) struct Field {
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct StructRepr(pub VariantRepr);
 
+/// A struct represented as its only non-skipped field.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct TransparentRepr {
+    pub member: syn::Member,
+    pub field: Field,
+    pub skipped: Vec<syn::Member>,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct EnumRepr {
     pub kind: EnumReprKind,
@@ -54,6 +63,13 @@ impl EnumRepr {
         primitive_repr: Option<syn::Type>,
         enum_data: &DataEnum,
     ) -> Result<Self, syn::Error> {
+        if let Some(span) = attr.transparent {
+            return Err(syn::Error::new(
+                span,
+                "`transparent` representation can only be used for structs",
+            ));
+        }
+
         let variants = enum_data
             .variants
             .iter()
@@ -92,6 +108,97 @@ impl StructRepr {
     }
 }
 
+impl TransparentRepr {
+    pub(crate) fn repr_for(
+        attr: ItemAttr,
+        span: Span,
+        fields: &Fields,
+    ) -> Result<Self, syn::Error> {
+        if let Some((_, span)) = attr.enum_repr_kind {
+            return Err(syn::Error::new(
+                span,
+                "`enum` representation can only be set for enums",
+            ));
+        }
+
+        let members = fields
+            .iter()
+            .enumerate()
+            .map(|(n, f)| match &f.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(n)),
+            })
+            .collect::<Vec<_>>();
+
+        let fields = match VariantRepr::repr_for(fields)? {
+            VariantRepr::Struct(fields) | VariantRepr::Tuple(fields) => fields,
+            VariantRepr::Unit(_) => Vec::new(),
+        };
+
+        let mut inner = None;
+        let mut skipped = Vec::new();
+        for (member, field) in members.into_iter().zip(fields) {
+            if field.attr.skip_to_variant && field.attr.skip_from_variant {
+                skipped.push(member);
+            } else if field.attr.skip_to_variant || field.attr.skip_from_variant {
+                return Err(syn::Error::new(
+                    field.ident.span(),
+                    "the field of a `transparent` struct cannot be skipped in only one direction",
+                ));
+            } else if inner.replace((member, field)).is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    "`transparent` structs must have exactly one field that isn't skipped",
+                ));
+            }
+        }
+
+        let (member, field) = inner.ok_or_else(|| {
+            syn::Error::new(
+                span,
+                "`transparent` structs must have exactly one field that isn't skipped",
+            )
+        })?;
+
+        Ok(TransparentRepr {
+            member,
+            field,
+            skipped,
+        })
+    }
+
+    pub(crate) fn make_to_variant_expr(
+        &self,
+        ident: &Ident,
+        trait_kind: ToVariantTrait,
+    ) -> TokenStream2 {
+        let TransparentRepr { member, field, .. } = self;
+        let binding = &field.ident;
+        let to_variant = field.make_to_variant_expr(trait_kind);
+        quote! {
+            {
+                let #ident { #member: #binding, .. } = self;
+                #to_variant
+            }
+        }
+    }
+
+    pub(crate) fn make_from_variant_expr(&self, ident: &Ident, variant: &Ident) -> TokenStream2 {
+        let TransparentRepr {
+            member,
+            field,
+            skipped,
+        } = self;
+        let expr = field.make_from_variant_expr(&quote!(#variant));
+        quote! {
+            #expr.map(|__value| #ident {
+                #member: __value,
+                #( #skipped: std::default::Default::default(), )*
+            })
+        }
+    }
+}
+
 impl VariantRepr {
     pub(crate) fn repr_for(fields: &Fields) -> Result<Self, syn::Error> {
         let this = match fields {
@@ -36,6 +36,7 @@ pub(crate) fn expand_to_variant(
                 }
             }
         }
+        Repr::Transparent(transparent) => transparent.make_to_variant_expr(&ident, trait_kind),
         Repr::Enum(EnumRepr {
             variants,
             primitive_repr,
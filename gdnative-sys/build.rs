@@ -499,12 +499,14 @@ mod api_wrapper {
 
         let struct_fields = godot_api_functions(&api_root);
         let impl_constructor = api_constructor(&api_root);
+        let impl_no_engine_constructor = no_engine_constructor(&api_root);
         let wrapper = quote! {
             pub struct GodotApi{
                 #struct_fields
             }
             impl GodotApi {
                 #impl_constructor
+                #impl_no_engine_constructor
             }
         };
         let mut wrapper_file = File::create(to.join(file_name))
@@ -566,6 +568,40 @@ mod api_wrapper {
         }
     }
 
+    fn no_engine_constructor(api: &ApiRoot) -> TokenStream {
+        let mut stubs = TokenStream::new();
+        let mut constructed_struct_fields = TokenStream::new();
+        for api in api.all_apis() {
+            for function in &api.functions {
+                let function_name = function.rust_name();
+                let arg_types = function.arguments.iter().map(Argument::rust_type);
+                let return_type = function.rust_return_type();
+                let name = function.name.as_str();
+
+                stubs.extend(quote! {
+                    unsafe extern "C" fn #function_name(#(_: #arg_types),*) -> #return_type {
+                        no_engine_unsupported(#name)
+                    }
+                });
+                constructed_struct_fields.extend(quote! {
+                    #function_name,
+                });
+            }
+        }
+        quote! {
+            /// Creates an API struct that can be used without a running engine. All functions
+            /// print an error and abort the process, unless they are replaced with fallback
+            /// implementations.
+            #[cfg(feature = "no-engine")]
+            pub fn no_engine() -> Self {
+                #stubs
+                GodotApi{
+                    #constructed_struct_fields
+                }
+            }
+        }
+    }
+
     fn parse_c_type(mut c_type: &str) -> (bool, i8, &str) {
         c_type = c_type.trim();
         let is_const = c_type.starts_with("const ");
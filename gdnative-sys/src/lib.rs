@@ -28,6 +28,13 @@ pub enum InitError {
     },
 }
 
+#[cfg(feature = "no-engine")]
+#[cold]
+fn no_engine_unsupported(function: &str) -> ! {
+    eprintln!("`{function}` is not available without a running Godot engine");
+    std::process::abort()
+}
+
 fn map_option_to_init_error<T>(t: Option<T>, message: &'static str) -> Result<T, InitError> {
     match t {
         Some(t) => Ok(t),
@@ -113,3 +120,26 @@ unsafe fn find_api_ptr(
         message: format!("Couldn't find API struct with type {}", api_type),
     }))
 }
+
+/// Looks up the Android extension API. It is not part of `GodotApi`, since its interface changed
+/// incompatibly between Godot 3.1 and 3.2 (see #296), so only version 1.1 is accepted here.
+///
+/// The extension is present on all platforms, but its functions only return meaningful values
+/// on Android.
+///
+/// # Safety
+///
+/// `core_api_struct` must point to the core API struct passed to `gdnative_init`.
+#[inline]
+pub unsafe fn find_android_api(
+    core_api_struct: *const godot_gdnative_core_api_struct,
+) -> Option<&'static godot_gdnative_ext_android_api_struct> {
+    find_api_ptr(
+        core_api_struct,
+        GDNATIVE_API_TYPES_GDNATIVE_EXT_ANDROID,
+        1,
+        1,
+    )
+    .ok()
+    .map(|api| &*(api as *const godot_gdnative_ext_android_api_struct))
+}
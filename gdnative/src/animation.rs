@@ -0,0 +1,178 @@
+//! Typed helpers for animating properties with `Tween`, `SceneTreeTween` and `Animation`.
+//!
+//! The engine identifies animated properties by name, so a typo in a property path or a value
+//! of the wrong type silently results in nothing being animated. The functions in this module
+//! take [`TypedProperty`] constants instead, which are generated for every API class in the
+//! `properties` module next to the class. The object must be an instance of the class that
+//! declares the property, or of one of its subclasses, and the values must have the type of the
+//! property:
+//!
+//! ```no_run
+//! use gdnative::animation;
+//! use gdnative::api::{node_2d, Tween};
+//! use gdnative::prelude::*;
+//!
+//! fn slide_in(tween: &Tween, node: TRef<Node2D>) {
+//!     animation::tween_property(
+//!         tween,
+//!         node,
+//!         node_2d::properties::POSITION,
+//!         Vector2::new(-100.0, 0.0),
+//!         Vector2::ZERO,
+//!         0.5,
+//!     );
+//!     tween.start();
+//! }
+//! ```
+//!
+//! Using a property that doesn't exist, a property of an unrelated class, or a value of another
+//! type fails to compile.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::api::animation::TrackType;
+use crate::api::tween::{EaseType, TransitionType};
+use crate::api::{Animation, Object, PropertyTweener, SceneTreeTween, Tween};
+use crate::core_types::{NodePath, OwnedToVariant};
+use crate::object::ownership::Shared;
+use crate::object::{GodotObject, Ref, SubClass, TRef, TypedProperty};
+
+/// Animates `property` of `object` from `from` to `to` over `duration` seconds, with a linear
+/// transition. Returns `true` on success, like `Tween::interpolate_property`.
+///
+/// The tween still has to be started with `Tween::start`.
+#[inline]
+pub fn tween_property<O, C, T>(
+    tween: &Tween,
+    object: TRef<'_, O, Shared>,
+    property: TypedProperty<C, T>,
+    from: T,
+    to: T,
+    duration: f64,
+) -> bool
+where
+    O: SubClass<C> + SubClass<Object>,
+    C: GodotObject,
+    T: OwnedToVariant,
+{
+    tween_property_eased(
+        tween,
+        object,
+        property,
+        from,
+        to,
+        duration,
+        TransitionType::LINEAR,
+        EaseType::IN_OUT,
+        0.0,
+    )
+}
+
+/// Animates `property` of `object` like [`tween_property`], with the given easing, starting
+/// `delay` seconds later.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn tween_property_eased<O, C, T>(
+    tween: &Tween,
+    object: TRef<'_, O, Shared>,
+    property: TypedProperty<C, T>,
+    from: T,
+    to: T,
+    duration: f64,
+    transition: TransitionType,
+    ease: EaseType,
+    delay: f64,
+) -> bool
+where
+    O: SubClass<C> + SubClass<Object>,
+    C: GodotObject,
+    T: OwnedToVariant,
+{
+    tween.interpolate_property(
+        object,
+        property,
+        from,
+        to,
+        duration,
+        transition.into(),
+        ease.into(),
+        delay,
+    )
+}
+
+/// Appends a step to `tween` that animates `property` of `object` from its current value to
+/// `to` over `duration` seconds. Returns the tweener of the step, to customize it further.
+#[inline]
+pub fn tween_property_to<O, C, T>(
+    tween: &SceneTreeTween,
+    object: TRef<'_, O, Shared>,
+    property: TypedProperty<C, T>,
+    to: T,
+    duration: f64,
+) -> Option<Ref<PropertyTweener, Shared>>
+where
+    O: SubClass<C> + SubClass<Object>,
+    C: GodotObject,
+    T: OwnedToVariant,
+{
+    tween.tween_property(object, property, to, duration)
+}
+
+/// A value track of an [`Animation`] that animates a property with values of type `T`.
+pub struct PropertyTrack<T> {
+    index: i64,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for PropertyTrack<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PropertyTrack<T> {}
+
+impl<T> fmt::Debug for PropertyTrack<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PropertyTrack").field(&self.index).finish()
+    }
+}
+
+impl<T: OwnedToVariant> PropertyTrack<T> {
+    /// Adds a value track to `animation` that animates `property` of the node at `node`,
+    /// relative to the root node of the `AnimationPlayer`.
+    ///
+    /// `node` is not checked to have the class `C`, since it is only resolved when the
+    /// animation is played.
+    #[inline]
+    pub fn add<C>(
+        animation: &Animation,
+        node: impl Into<NodePath>,
+        property: TypedProperty<C, T>,
+    ) -> Self {
+        let index = animation.add_track(TrackType::VALUE.into(), -1);
+        let path = format!("{}:{}", node.into().to_string(), property.name());
+        animation.track_set_path(index, path);
+
+        PropertyTrack {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the index of the track in the animation.
+    #[inline]
+    pub fn index(self) -> i64 {
+        self.index
+    }
+
+    /// Inserts a key with `value` at `time` seconds. `animation` must be the animation the track
+    /// was added to.
+    #[inline]
+    pub fn insert_key(self, animation: &Animation, time: f64, value: T) {
+        animation.track_insert_key(self.index, time, value, 1.0);
+    }
+}
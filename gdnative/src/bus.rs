@@ -0,0 +1,280 @@
+//! Main-thread event bus for communication between Rust classes.
+//!
+//! Godot signals can be used to connect Rust classes, but require converting every argument to
+//! `Variant` and refer to signals and methods by name. The bus instead delivers plain Rust
+//! values, typed by their Rust type, to handler methods of the subscribed instances:
+//!
+//! ```no_run
+//! use gdnative::bus;
+//! use gdnative::prelude::*;
+//!
+//! struct Damage {
+//!     amount: i64,
+//! }
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Node)]
+//! struct HealthBar {
+//!     health: i64,
+//! }
+//!
+//! #[methods]
+//! impl HealthBar {
+//!     fn new(_base: &Node) -> Self {
+//!         HealthBar { health: 100 }
+//!     }
+//!
+//!     #[method]
+//!     fn _ready(&self, #[base] base: TRef<Node>) {
+//!         bus::subscribe(base, Self::on_damage);
+//!     }
+//!
+//!     fn on_damage(&mut self, _base: TRef<Node>, event: &Damage) {
+//!         self.health -= event.amount;
+//!     }
+//! }
+//!
+//! // Anywhere else, e.g. in the player class:
+//! fn hit() {
+//!     bus::publish(Damage { amount: 10 });
+//! }
+//! ```
+//!
+//! Published events are queued, and delivered at the start of the next frame, before `_process`
+//! is called on any node, by a [frame hook](crate::init::register_frame_hook). Events are
+//! delivered in the order they were published, and to subscribers in the order they subscribed.
+//! Events published while delivering are delivered on the frame after. [`flush`] delivers the
+//! queued events immediately, e.g. when frame hooks are not running in the editor.
+//!
+//! Subscriptions of an instance are removed automatically once its base object is freed. They
+//! can also be removed explicitly with [`Subscription::unsubscribe`].
+//!
+//! The bus is thread-local, and must only be used on the main thread. Events published on other
+//! threads are not delivered.
+
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use crate::export::user_data::MapMut;
+use crate::export::NativeClass;
+use crate::init::{register_frame_hook, FrameHook, Phase};
+use crate::log::godot_error;
+use crate::object::{HandlePolicy, ObjectHandle, TRef, WithInstanceError};
+
+thread_local! {
+    static BUS: RefCell<Bus> = RefCell::default();
+}
+
+/// Handler of a subscriber. Returns `false` if the subscriber is gone, and should be removed.
+type Handler = Rc<dyn Fn(&dyn Any) -> bool>;
+
+#[derive(Default)]
+struct Bus {
+    next_id: u64,
+    subscribers: HashMap<TypeId, Vec<Subscriber>>,
+    queue: VecDeque<(TypeId, Box<dyn Any>)>,
+    hook: Option<FrameHook>,
+}
+
+struct Subscriber {
+    id: u64,
+    handler: Handler,
+    active: Rc<Cell<bool>>,
+}
+
+impl Bus {
+    fn ensure_hook(&mut self) {
+        if self.hook.is_none() {
+            self.hook = Some(register_frame_hook(Phase::PreProcess, |_| flush()));
+        }
+    }
+}
+
+/// Subscribes the instance of `T` attached to `base` to events of type `E`. `handler` is
+/// called with the instance, its base, and each event.
+///
+/// The subscription is removed once `base` is freed, or if no instance of `T` is attached to it
+/// anymore. If the instance is borrowed when an event is delivered, e.g. because one of its
+/// methods is running and called [`flush`], the event is skipped for it and an error is
+/// logged.
+#[inline]
+pub fn subscribe<T, E, F>(base: TRef<'_, T::Base>, handler: F) -> Subscription
+where
+    T: NativeClass,
+    T::UserData: MapMut,
+    E: 'static,
+    F: Fn(&mut T, TRef<'_, T::Base>, &E) + 'static,
+{
+    let handle = ObjectHandle::from(base).with_policy(HandlePolicy::Silent);
+    insert::<E>(Rc::new(move |event: &dyn Any| {
+        let event = event
+            .downcast_ref::<E>()
+            .expect("events should be delivered to subscribers of their type");
+
+        // SAFETY: the bus is only used on the main thread.
+        let base = match unsafe { handle.get() } {
+            Some(base) => base,
+            None => return false,
+        };
+
+        match base.with::<T, _>(|script, base| handler(script, base, event)) {
+            Ok(()) => true,
+            Err(WithInstanceError::NotAnInstance { .. }) => false,
+            Err(err) => {
+                godot_error!(
+                    "gdnative::bus: failed to deliver `{}`: {err}",
+                    type_name::<E>()
+                );
+                true
+            }
+        }
+    }))
+}
+
+/// Subscribes `handler` to events of type `E`. Unlike [`subscribe`], the subscription is not
+/// tied to an instance, and is only removed with [`Subscription::unsubscribe`].
+#[inline]
+pub fn subscribe_fn<E, F>(handler: F) -> Subscription
+where
+    E: 'static,
+    F: Fn(&E) + 'static,
+{
+    insert::<E>(Rc::new(move |event: &dyn Any| {
+        handler(
+            event
+                .downcast_ref::<E>()
+                .expect("events should be delivered to subscribers of their type"),
+        );
+        true
+    }))
+}
+
+fn insert<E: 'static>(handler: Handler) -> Subscription {
+    BUS.with(|bus| {
+        let mut bus = bus.borrow_mut();
+        bus.ensure_hook();
+
+        let id = bus.next_id;
+        bus.next_id += 1;
+
+        let active = Rc::new(Cell::new(true));
+        bus.subscribers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(Subscriber {
+                id,
+                handler,
+                active: Rc::clone(&active),
+            });
+
+        Subscription {
+            id,
+            event: TypeId::of::<E>(),
+        }
+    })
+}
+
+/// Queues `event` for delivery to the subscribers of its type at the start of the next frame.
+#[inline]
+pub fn publish<E: 'static>(event: E) {
+    BUS.with(|bus| {
+        let mut bus = bus.borrow_mut();
+        bus.ensure_hook();
+        bus.queue.push_back((TypeId::of::<E>(), Box::new(event)));
+    });
+}
+
+/// Delivers the events queued so far. Events published during delivery stay queued.
+///
+/// Panics in handlers are caught and logged.
+#[inline]
+pub fn flush() {
+    let queue = BUS.with(|bus| std::mem::take(&mut bus.borrow_mut().queue));
+    for (event_type, event) in queue {
+        deliver(event_type, &*event);
+    }
+}
+
+fn deliver(event_type: TypeId, event: &dyn Any) {
+    // Handlers may subscribe or unsubscribe, so the bus can't be borrowed while they run.
+    let subscribers = BUS.with(|bus| {
+        bus.borrow()
+            .subscribers
+            .get(&event_type)
+            .map(|subscribers| {
+                subscribers
+                    .iter()
+                    .map(|sub| (sub.id, Rc::clone(&sub.handler), Rc::clone(&sub.active)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    });
+
+    for (id, handler, active) in subscribers {
+        if !active.get() {
+            continue;
+        }
+
+        match catch_unwind(AssertUnwindSafe(|| handler(event))) {
+            Ok(true) => {}
+            Ok(false) => {
+                remove(event_type, id);
+            }
+            Err(_) => {
+                godot_error!("gdnative::bus: event handler panicked (check stderr for output)");
+            }
+        }
+    }
+}
+
+fn remove(event_type: TypeId, id: u64) -> bool {
+    BUS.with(|bus| {
+        let mut bus = bus.borrow_mut();
+        let subscribers = match bus.subscribers.get_mut(&event_type) {
+            Some(subscribers) => subscribers,
+            None => return false,
+        };
+
+        match subscribers.iter().position(|sub| sub.id == id) {
+            Some(index) => {
+                subscribers.remove(index).active.set(false);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Returns the number of subscriptions to events of type `E`. Subscriptions of freed instances
+/// are only counted until the next event of type `E` is delivered.
+#[inline]
+pub fn subscriber_count<E: 'static>() -> usize {
+    BUS.with(|bus| {
+        bus.borrow()
+            .subscribers
+            .get(&TypeId::of::<E>())
+            .map_or(0, Vec::len)
+    })
+}
+
+/// Handle to a subscription returned by [`subscribe`] or [`subscribe_fn`].
+///
+/// Dropping the handle does not remove the subscription.
+#[derive(Debug)]
+pub struct Subscription {
+    id: u64,
+    event: TypeId,
+}
+
+impl Subscription {
+    /// Removes the subscription, so that its handler isn't called anymore, even for events
+    /// that are being delivered. Returns `false` if the subscription was already removed because
+    /// its instance is gone.
+    #[inline]
+    pub fn unsubscribe(self) -> bool {
+        remove(self.event, self.id)
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use crate::core_types::{GodotString, NodePath, ToVariant, Variant, Vector2, Vector3};
+
+/// Arguments of a console command, parsed from the words following the command name.
+///
+/// Implemented for `()` and tuples of up to eight [`CommandArg`]s. Structs can implement it by
+/// reading their fields from [`Args`] in order:
+///
+/// ```no_run
+/// use gdnative::console::{ArgError, Args, CommandArgs};
+///
+/// struct SpawnArgs {
+///     kind: String,
+///     count: u32,
+/// }
+///
+/// impl CommandArgs for SpawnArgs {
+///     fn parse(args: &mut Args<'_>) -> Result<Self, ArgError> {
+///         Ok(SpawnArgs {
+///             kind: args.next()?,
+///             count: args.next::<Option<u32>>()?.unwrap_or(1),
+///         })
+///     }
+///
+///     fn usage() -> String {
+///         "<kind> [count]".into()
+///     }
+/// }
+/// ```
+pub trait CommandArgs: Sized {
+    /// Reads the arguments from `args`. Arguments that are left over are reported as an error
+    /// by the console.
+    ///
+    /// # Errors
+    ///
+    /// If an argument is missing or cannot be parsed.
+    fn parse(args: &mut Args<'_>) -> Result<Self, ArgError>;
+
+    /// Returns a description of the arguments, shown by `help` and in error messages.
+    #[inline]
+    fn usage() -> String {
+        String::new()
+    }
+}
+
+/// Type that can be parsed from a single word of a console command.
+pub trait CommandArg: Sized {
+    /// Name of the type in usage and error messages.
+    const NAME: &'static str;
+
+    /// Whether the argument may be left out.
+    const OPTIONAL: bool = false;
+
+    /// Parses the argument, returning `None` if `arg` is not a valid value.
+    fn parse(arg: &str) -> Option<Self>;
+
+    /// Returns the value used when the argument is left out, or `None` if it is required.
+    #[inline]
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+/// Remaining words of a console command, from which [`CommandArgs`] are read.
+#[derive(Debug)]
+pub struct Args<'a> {
+    words: &'a [String],
+    idx: usize,
+}
+
+impl<'a> Args<'a> {
+    pub(crate) fn new(words: &'a [String]) -> Self {
+        Args { words, idx: 0 }
+    }
+
+    /// Returns the number of words left.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.words.len() - self.idx
+    }
+
+    /// Returns `true` if all words have been read.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads the next argument.
+    ///
+    /// # Errors
+    ///
+    /// If there are no words left and `T` is not optional, or if the word cannot be parsed as
+    /// `T`.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T: CommandArg>(&mut self) -> Result<T, ArgError> {
+        let index = self.idx;
+        let Some(word) = self.words.get(index) else {
+            return T::missing().ok_or(ArgError::Missing {
+                index,
+                expected: T::NAME,
+            });
+        };
+
+        self.idx += 1;
+        T::parse(word).ok_or_else(|| ArgError::Invalid {
+            index,
+            value: word.clone(),
+            expected: T::NAME,
+        })
+    }
+
+    /// Reads all remaining words as arguments of type `T`.
+    ///
+    /// # Errors
+    ///
+    /// If a word cannot be parsed as `T`.
+    #[inline]
+    pub fn rest<T: CommandArg>(&mut self) -> Result<Vec<T>, ArgError> {
+        let mut rest = Vec::with_capacity(self.len());
+        while !self.is_empty() {
+            rest.push(self.next()?);
+        }
+        Ok(rest)
+    }
+
+    pub(crate) fn done(&self) -> Result<(), ArgError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(ArgError::Excess { count: self.len() })
+        }
+    }
+}
+
+/// Error in the arguments of a console command.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ArgError {
+    /// A required argument was not given.
+    Missing {
+        /// 0-based index of the argument.
+        index: usize,
+        /// Name of the expected type.
+        expected: &'static str,
+    },
+    /// An argument could not be parsed.
+    Invalid {
+        /// 0-based index of the argument.
+        index: usize,
+        /// The word that was given.
+        value: String,
+        /// Name of the expected type.
+        expected: &'static str,
+    },
+    /// More arguments were given than the command accepts.
+    Excess {
+        /// Number of arguments left over.
+        count: usize,
+    },
+}
+
+impl fmt::Display for ArgError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::Missing { index, expected } => {
+                write!(f, "missing argument {} ({expected})", index + 1)
+            }
+            ArgError::Invalid {
+                index,
+                value,
+                expected,
+            } => write!(
+                f,
+                "argument {} should be {expected}, found `{value}`",
+                index + 1
+            ),
+            ArgError::Excess { count } => write!(f, "{count} unexpected argument(s)"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+impl CommandArgs for () {
+    #[inline]
+    fn parse(_args: &mut Args<'_>) -> Result<Self, ArgError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_command_args_for_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty: CommandArg),+> CommandArgs for ($($ty,)+) {
+            #[inline]
+            fn parse(args: &mut Args<'_>) -> Result<Self, ArgError> {
+                Ok(($(args.next::<$ty>()?,)+))
+            }
+
+            #[inline]
+            fn usage() -> String {
+                let args: &[String] = &[$(usage_of::<$ty>()),+];
+                args.join(" ")
+            }
+        }
+    };
+}
+
+impl_command_args_for_tuple!(A);
+impl_command_args_for_tuple!(A, B);
+impl_command_args_for_tuple!(A, B, C);
+impl_command_args_for_tuple!(A, B, C, D);
+impl_command_args_for_tuple!(A, B, C, D, E);
+impl_command_args_for_tuple!(A, B, C, D, E, F);
+impl_command_args_for_tuple!(A, B, C, D, E, F, G);
+impl_command_args_for_tuple!(A, B, C, D, E, F, G, H);
+
+fn usage_of<T: CommandArg>() -> String {
+    if T::OPTIONAL {
+        format!("[{}]", T::NAME)
+    } else {
+        format!("<{}>", T::NAME)
+    }
+}
+
+macro_rules! impl_command_arg_from_str {
+    ($($ty:ty),+) => {
+        $(
+            impl CommandArg for $ty {
+                const NAME: &'static str = stringify!($ty);
+
+                #[inline]
+                fn parse(arg: &str) -> Option<Self> {
+                    arg.parse().ok()
+                }
+            }
+        )+
+    };
+}
+
+impl_command_arg_from_str!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl CommandArg for bool {
+    const NAME: &'static str = "bool";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        match arg {
+            "true" | "on" | "yes" | "1" => Some(true),
+            "false" | "off" | "no" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl CommandArg for String {
+    const NAME: &'static str = "String";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        Some(arg.to_owned())
+    }
+}
+
+impl CommandArg for GodotString {
+    const NAME: &'static str = "String";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        Some(arg.into())
+    }
+}
+
+impl CommandArg for NodePath {
+    const NAME: &'static str = "NodePath";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        Some(arg.into())
+    }
+}
+
+/// Parsed from comma-separated components, e.g. `1.5,-2`.
+impl CommandArg for Vector2 {
+    const NAME: &'static str = "Vector2";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        match parse_components(arg)?[..] {
+            [x, y] => Some(Vector2::new(x, y)),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed from comma-separated components, e.g. `1.5,-2,0`.
+impl CommandArg for Vector3 {
+    const NAME: &'static str = "Vector3";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        match parse_components(arg)?[..] {
+            [x, y, z] => Some(Vector3::new(x, y, z)),
+            _ => None,
+        }
+    }
+}
+
+fn parse_components(arg: &str) -> Option<Vec<f32>> {
+    arg.split(',')
+        .map(|component| component.trim().parse().ok())
+        .collect()
+}
+
+/// Parsed as `null`, a `bool`, an integer or a float if possible, and as a string otherwise.
+impl CommandArg for Variant {
+    const NAME: &'static str = "Variant";
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        let variant = match arg {
+            "null" => Variant::nil(),
+            "true" => true.to_variant(),
+            "false" => false.to_variant(),
+            _ => {
+                if let Ok(int) = arg.parse::<i64>() {
+                    int.to_variant()
+                } else if let Ok(float) = arg.parse::<f64>() {
+                    float.to_variant()
+                } else {
+                    arg.to_variant()
+                }
+            }
+        };
+        Some(variant)
+    }
+}
+
+impl<T: CommandArg> CommandArg for Option<T> {
+    const NAME: &'static str = T::NAME;
+    const OPTIONAL: bool = true;
+
+    #[inline]
+    fn parse(arg: &str) -> Option<Self> {
+        T::parse(arg).map(Some)
+    }
+
+    #[inline]
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// Splits a command line into words at whitespace. Words can be quoted with `"` or `'` to
+/// contain whitespace, and `\` escapes the next character inside quotes.
+///
+/// Returns the words, and whether the line ends within a word, i.e. the last word is
+/// incomplete.
+pub(crate) fn split_words(line: &str) -> Result<(Vec<String>, bool), String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let quote = c;
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(c) if c == quote => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => word.push(c),
+                            None => return Err("unterminated quote".into()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated quote".into()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    let incomplete = word.is_some();
+    words.extend(word);
+    Ok((words, incomplete))
+}
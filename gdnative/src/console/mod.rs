@@ -0,0 +1,493 @@
+//! Developer console for commands written in Rust.
+//!
+//! Commands are closures taking typed arguments, registered under a name with [`command`] or
+//! the [`Command`] builder. Arguments are parsed from the words following the command name,
+//! through the [`CommandArgs`] trait, which is implemented for tuples of common types and can be
+//! implemented for structs:
+//!
+//! ```no_run
+//! use gdnative::console::{self, Command};
+//! use gdnative::prelude::*;
+//!
+//! fn init(_handle: InitHandle) {
+//!     console::command("greet", |(name,): (String,)| format!("Hello, {name}!"));
+//!
+//!     Command::new("gravity", |(value,): (f64,)| {
+//!         gdnative::settings::set_setting("physics/2d/default_gravity", value);
+//!     })
+//!     .with_help("Changes the 2D gravity")
+//!     .register();
+//! }
+//! ```
+//!
+//! Command lines are executed with [`execute`], which also records them in the [`history`].
+//! [`complete`] suggests completions for partially typed lines. Besides the registered commands,
+//! three commands are built in:
+//!
+//! * `help [command]` lists the commands, or shows the usage of a command.
+//! * `call <node> <method> [args...]` calls a method on the node at the given path, relative to
+//!   the scene tree root. Method names are completed from the methods of registered
+//!   `NativeClass`es.
+//! * `dump <node> [dot|json]` dumps the subtree of the node at the given path with
+//!   [`diagnostics::dump_tree`](crate::diagnostics::dump_tree), as JSON by default.
+//!
+//! [`ConsoleUi`] provides an in-game console on a `CanvasLayer`, which can be toggled at
+//! runtime.
+//!
+//! Commands are registered per thread, and are usually registered and executed on the main
+//! thread.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::api::{Engine, NativeScript, Node, SceneTree};
+use crate::core_types::{GodotString, Variant};
+use crate::diagnostics::{dump_tree, DumpFormat};
+use crate::export::class_db;
+
+mod args;
+mod ui;
+
+pub use args::{ArgError, Args, CommandArg, CommandArgs};
+pub use ui::ConsoleUi;
+
+const HISTORY_LIMIT: usize = 100;
+
+type Handler = dyn Fn(&mut Args<'_>) -> Result<Result<String, String>, ArgError>;
+
+struct Entry {
+    help: Option<String>,
+    usage: String,
+    handler: Rc<Handler>,
+}
+
+struct State {
+    commands: BTreeMap<String, Entry>,
+    history: VecDeque<String>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::with_builtins());
+}
+
+/// Builder for a console command.
+#[must_use = "Command left unregistered -- did you forget to call register()?"]
+pub struct Command {
+    name: String,
+    help: Option<String>,
+    usage: String,
+    handler: Rc<Handler>,
+}
+
+impl Command {
+    /// Creates a command named `name`, that calls `handler` with the parsed arguments. The
+    /// returned value is printed as the output of the command.
+    #[inline]
+    pub fn new<A, R, F>(name: impl Into<String>, handler: F) -> Self
+    where
+        A: CommandArgs,
+        R: CommandOutput,
+        F: Fn(A) -> R + 'static,
+    {
+        Command {
+            name: name.into(),
+            help: None,
+            usage: A::usage(),
+            handler: Rc::new(move |args: &mut Args<'_>| {
+                let parsed = A::parse(args)?;
+                args.done()?;
+                Ok(handler(parsed).into_output())
+            }),
+        }
+    }
+
+    /// Sets a one-line description shown by `help`.
+    #[inline]
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Registers the command, replacing any command with the same name.
+    #[inline]
+    pub fn register(self) {
+        let entry = Entry {
+            help: self.help,
+            usage: self.usage,
+            handler: self.handler,
+        };
+        STATE.with(|state| state.borrow_mut().commands.insert(self.name, entry));
+    }
+}
+
+impl fmt::Debug for Command {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("help", &self.help)
+            .field("usage", &self.usage)
+            .finish()
+    }
+}
+
+/// Registers a command named `name`. Shorthand for `Command::new(name, handler).register()`.
+#[inline]
+pub fn command<A, R, F>(name: impl Into<String>, handler: F)
+where
+    A: CommandArgs,
+    R: CommandOutput,
+    F: Fn(A) -> R + 'static,
+{
+    Command::new(name, handler).register();
+}
+
+/// Removes the command named `name`. Returns `true` if it was registered.
+#[inline]
+pub fn remove_command(name: &str) -> bool {
+    STATE.with(|state| state.borrow_mut().commands.remove(name).is_some())
+}
+
+/// Returns the names of all commands, sorted.
+#[inline]
+pub fn command_names() -> Vec<String> {
+    STATE.with(|state| state.borrow().commands.keys().cloned().collect())
+}
+
+/// Value returned from a command handler, which is printed as the output of the command.
+///
+/// Implemented for `()`, strings, and `Result`s whose errors are reported as failures.
+pub trait CommandOutput {
+    /// Converts the value into the output text, or an error message.
+    ///
+    /// # Errors
+    ///
+    /// If the command failed.
+    fn into_output(self) -> Result<String, String>;
+}
+
+impl CommandOutput for () {
+    #[inline]
+    fn into_output(self) -> Result<String, String> {
+        Ok(String::new())
+    }
+}
+
+impl CommandOutput for String {
+    #[inline]
+    fn into_output(self) -> Result<String, String> {
+        Ok(self)
+    }
+}
+
+impl CommandOutput for &str {
+    #[inline]
+    fn into_output(self) -> Result<String, String> {
+        Ok(self.to_owned())
+    }
+}
+
+impl CommandOutput for GodotString {
+    #[inline]
+    fn into_output(self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
+impl<T: CommandOutput, E: fmt::Display> CommandOutput for Result<T, E> {
+    #[inline]
+    fn into_output(self) -> Result<String, String> {
+        self.map_err(|err| err.to_string())?.into_output()
+    }
+}
+
+/// Error returned by [`execute`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ConsoleError {
+    /// The line could not be split into words, e.g. because of an unterminated quote.
+    Syntax(String),
+    /// No command is registered under the name.
+    UnknownCommand(String),
+    /// The arguments didn't match the command.
+    Args {
+        /// Name of the command.
+        command: String,
+        /// Usage of the command.
+        usage: String,
+        /// The error in the arguments.
+        error: ArgError,
+    },
+    /// The command returned an error.
+    Failed {
+        /// Name of the command.
+        command: String,
+        /// The error message.
+        message: String,
+    },
+}
+
+impl fmt::Display for ConsoleError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsoleError::Syntax(message) => write!(f, "syntax error: {message}"),
+            ConsoleError::UnknownCommand(name) => {
+                write!(f, "unknown command `{name}`, try `help`")
+            }
+            ConsoleError::Args {
+                command,
+                usage,
+                error,
+            } => write!(f, "{command}: {error} (usage: {command} {usage})"),
+            ConsoleError::Failed { command, message } => write!(f, "{command}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConsoleError {}
+
+/// Executes a command line, and returns the output of the command.
+///
+/// Non-empty lines are added to the [`history`], even if the command fails. Empty lines do
+/// nothing.
+///
+/// # Errors
+///
+/// If the command doesn't exist, its arguments don't match, or it fails.
+#[inline]
+pub fn execute(line: &str) -> Result<String, ConsoleError> {
+    let (words, _) = args::split_words(line).map_err(ConsoleError::Syntax)?;
+    let Some((name, words)) = words.split_first() else {
+        return Ok(String::new());
+    };
+
+    // The handler is called without borrowing the state, so that it can use the console.
+    let (handler, usage) = STATE
+        .with(|state| {
+            let mut state = state.borrow_mut();
+            state.push_history(line.trim());
+            state
+                .commands
+                .get(name)
+                .map(|entry| (entry.handler.clone(), entry.usage.clone()))
+        })
+        .ok_or_else(|| ConsoleError::UnknownCommand(name.clone()))?;
+
+    match handler(&mut Args::new(words)) {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(message)) => Err(ConsoleError::Failed {
+            command: name.clone(),
+            message,
+        }),
+        Err(error) => Err(ConsoleError::Args {
+            command: name.clone(),
+            usage,
+            error,
+        }),
+    }
+}
+
+/// Returns the command lines executed so far, oldest first. At most the last 100 lines are kept.
+#[inline]
+pub fn history() -> Vec<String> {
+    STATE.with(|state| state.borrow().history.iter().cloned().collect())
+}
+
+/// Clears the [`history`].
+#[inline]
+pub fn clear_history() {
+    STATE.with(|state| state.borrow_mut().history.clear());
+}
+
+/// Returns the completions for a partially typed command line, as complete lines, sorted.
+///
+/// The first word is completed from the command names. For `help`, the argument is completed
+/// from the command names as well. For `call`, the method is completed from the methods of the
+/// node's class if it's a registered `NativeClass`, and from the methods of all registered
+/// classes otherwise.
+#[inline]
+pub fn complete(line: &str) -> Vec<String> {
+    let Ok((mut words, incomplete)) = args::split_words(line) else {
+        return Vec::new();
+    };
+    if !incomplete {
+        words.push(String::new());
+    }
+
+    let partial = words.pop().unwrap_or_default();
+    let candidates = match words.as_slice() {
+        [] => command_names(),
+        [command] if command == "help" => command_names(),
+        [command, node] if command == "call" => method_names(node),
+        _ => Vec::new(),
+    };
+
+    let prefix = if line.ends_with(partial.as_str()) {
+        &line[..line.len() - partial.len()]
+    } else {
+        // The partial word was quoted, so it's replaced as a whole.
+        line.trim_end_matches(|c: char| !c.is_whitespace())
+    };
+
+    let mut completions = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(partial.as_str()))
+        .filter(|candidate| partial.starts_with('_') || !candidate.starts_with('_'))
+        .map(|candidate| format!("{prefix}{candidate}"))
+        .collect::<Vec<_>>();
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+impl State {
+    fn with_builtins() -> Self {
+        let mut state = State {
+            commands: BTreeMap::new(),
+            history: VecDeque::new(),
+        };
+
+        let builtins = [
+            Command::new("help", |(name,): (Option<String>,)| help(name))
+                .with_help("Lists the commands, or shows the usage of a command"),
+            Command::new("call", call)
+                .with_help("Calls a method on the node at a path relative to the scene tree root"),
+            Command::new("dump", dump).with_help(
+                "Dumps the subtree of the node at a path relative to the scene tree root",
+            ),
+        ];
+
+        for command in builtins {
+            let entry = Entry {
+                help: command.help,
+                usage: command.usage,
+                handler: command.handler,
+            };
+            state.commands.insert(command.name, entry);
+        }
+
+        state
+    }
+
+    fn push_history(&mut self, line: &str) {
+        if line.is_empty() || self.history.back().is_some_and(|last| last == line) {
+            return;
+        }
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(line.to_owned());
+    }
+}
+
+fn help(name: Option<String>) -> Result<String, String> {
+    STATE.with(|state| {
+        let state = state.borrow();
+        let describe = |name: &str, entry: &Entry| {
+            let mut line = format!("{name} {}", entry.usage).trim_end().to_owned();
+            if let Some(help) = &entry.help {
+                line.push_str(" -- ");
+                line.push_str(help);
+            }
+            line
+        };
+
+        match name {
+            Some(name) => state
+                .commands
+                .get(&name)
+                .map(|entry| describe(&name, entry))
+                .ok_or_else(|| format!("unknown command `{name}`")),
+            None => Ok(state
+                .commands
+                .iter()
+                .map(|(name, entry)| describe(name, entry))
+                .collect::<Vec<_>>()
+                .join("\n")),
+        }
+    })
+}
+
+struct CallArgs {
+    node: String,
+    method: String,
+    args: Vec<Variant>,
+}
+
+impl CommandArgs for CallArgs {
+    #[inline]
+    fn parse(args: &mut Args<'_>) -> Result<Self, ArgError> {
+        Ok(CallArgs {
+            node: args.next()?,
+            method: args.next()?,
+            args: args.rest()?,
+        })
+    }
+
+    #[inline]
+    fn usage() -> String {
+        "<node> <method> [args...]".into()
+    }
+}
+
+fn call(args: CallArgs) -> Result<String, String> {
+    with_node(&args.node, |node| {
+        if !node.has_method(&args.method) {
+            return Err(format!("node has no method `{}`", args.method));
+        }
+
+        // SAFETY: the arguments are converted by the engine, like calls from GDScript.
+        let result = unsafe { node.call(&args.method, &args.args) };
+        Ok(if result.is_nil() {
+            String::new()
+        } else {
+            result.to_string()
+        })
+    })
+    .ok_or_else(|| format!("no node at `{}`", args.node))?
+}
+
+fn dump((node, format): (String, Option<String>)) -> Result<String, String> {
+    let format = match format {
+        Some(format) => format.parse::<DumpFormat>()?,
+        None => DumpFormat::Json,
+    };
+    with_node(&node, |node| dump_tree(node, format)).ok_or_else(|| format!("no node at `{node}`"))
+}
+
+/// Calls `f` with the node at `path` relative to the scene tree root, if any.
+fn with_node<R>(path: &str, f: impl FnOnce(&Node) -> R) -> Option<R> {
+    let main_loop = Engine::godot_singleton().get_main_loop()?;
+    let tree = unsafe { main_loop.assume_safe() }.cast::<SceneTree>()?;
+    let root = tree.root()?;
+    let root = unsafe { root.assume_safe() };
+    let node = root.get_node_or_null(path)?;
+    let node = unsafe { node.assume_safe() };
+    Some(f(&node))
+}
+
+fn method_names(node: &str) -> Vec<String> {
+    let db = class_db();
+
+    let class = with_node(node, |node| {
+        let script = node.get_script()?;
+        let script = unsafe { script.assume_safe() }.cast::<NativeScript>()?;
+        Some(script.class_name().to_string())
+    })
+    .flatten();
+
+    let classes = match class {
+        Some(class) if db.contains(&class) => vec![class],
+        _ => db.class_names(),
+    };
+
+    classes
+        .iter()
+        .filter_map(|class| db.class(class))
+        .flat_map(|report| report.methods)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
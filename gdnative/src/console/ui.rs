@@ -0,0 +1,293 @@
+use crate::api::control::{LayoutPreset, SizeFlags};
+use crate::api::{
+    CanvasLayer, GlobalConstants, InputEvent, InputEventKey, LineEdit, Node, PanelContainer,
+    RichTextLabel, VBoxContainer,
+};
+use crate::core_types::{Color, Vector2};
+use crate::object::ownership::Shared;
+use crate::object::{Ref, TRef};
+
+/// In-game console UI on a `CanvasLayer`, for running [commands](super).
+///
+/// The console consists of an output log and an input line at the top of the screen, and is
+/// hidden initially. It's toggled with the backtick key by default. While open, `Enter` runs
+/// the input line, `Up` and `Down` browse the history, `Tab` completes the input, and
+/// `Escape` closes the console.
+///
+/// Key presses are handled by passing input events to [`handle_input`](Self::handle_input),
+/// usually from `_input` of the node the console is attached to:
+///
+/// ```no_run
+/// use gdnative::console::ConsoleUi;
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(Node)]
+/// #[no_constructor]
+/// struct Game {
+///     console: Option<ConsoleUi>,
+/// }
+///
+/// #[methods]
+/// impl Game {
+///     #[method]
+///     fn _ready(&mut self, #[base] base: &Node) {
+///         self.console = Some(ConsoleUi::attach(base));
+///     }
+///
+///     #[method]
+///     fn _input(&mut self, event: Ref<InputEvent>) {
+///         if let Some(console) = &mut self.console {
+///             console.handle_input(&unsafe { event.assume_safe() });
+///         }
+///     }
+/// }
+/// ```
+///
+/// `ConsoleUi` holds references to the nodes it created, which are freed along with the node
+/// the console is attached to. Afterwards, all methods do nothing.
+#[derive(Debug)]
+pub struct ConsoleUi {
+    layer: Ref<CanvasLayer, Shared>,
+    output: Ref<RichTextLabel, Shared>,
+    input: Ref<LineEdit, Shared>,
+    toggle_key: i64,
+    history_index: Option<usize>,
+}
+
+impl ConsoleUi {
+    /// Creates the console nodes as children of `parent`.
+    #[inline]
+    pub fn attach(parent: &Node) -> Self {
+        let layer = CanvasLayer::new();
+        layer.set_name("Console");
+        layer.set_layer(128);
+        layer.set_visible(false);
+
+        let panel = PanelContainer::new();
+        panel.set_anchors_and_margins_preset(LayoutPreset::TOP_WIDE.0, 0, 0);
+        panel.set_custom_minimum_size(Vector2::new(0.0, 240.0));
+
+        let column = VBoxContainer::new();
+
+        let output = RichTextLabel::new();
+        output.set_v_size_flags(SizeFlags::EXPAND_FILL.0);
+        output.set_scroll_follow(true);
+        output.set_selection_enabled(true);
+        let output = output.into_shared();
+
+        let input = LineEdit::new();
+        input.set_placeholder("help");
+        let input = input.into_shared();
+
+        column.add_child(&output, false);
+        column.add_child(&input, false);
+        panel.add_child(column, false);
+        layer.add_child(panel, false);
+
+        let layer = layer.into_shared();
+        parent.add_child(&layer, false);
+
+        ConsoleUi {
+            layer,
+            output,
+            input,
+            toggle_key: GlobalConstants::KEY_QUOTELEFT,
+            history_index: None,
+        }
+    }
+
+    /// Sets the key that toggles the console, as a `GlobalConstants::KEY_*` scancode.
+    #[inline]
+    pub fn with_toggle_key(mut self, scancode: i64) -> Self {
+        self.toggle_key = scancode;
+        self
+    }
+
+    /// Returns `true` if the console is visible.
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.nodes().is_some_and(|(layer, _, _)| layer.is_visible())
+    }
+
+    /// Shows or hides the console. The input line is focused when the console is shown.
+    #[inline]
+    pub fn set_open(&mut self, open: bool) {
+        let Some((layer, _, input)) = self.nodes() else {
+            return;
+        };
+
+        layer.set_visible(open);
+        if !input.is_inside_tree() {
+            return;
+        }
+        if open {
+            input.grab_focus();
+        } else {
+            input.release_focus();
+        }
+    }
+
+    /// Shows the console if it's hidden, and hides it otherwise.
+    #[inline]
+    pub fn toggle(&mut self) {
+        self.set_open(!self.is_open());
+    }
+
+    /// Appends a line of text to the output log.
+    #[inline]
+    pub fn print(&self, text: &str) {
+        if let Some((_, output, _)) = self.nodes() {
+            output.add_text(text);
+            output.newline();
+        }
+    }
+
+    /// Appends a line of text to the output log, highlighted as an error.
+    #[inline]
+    pub fn print_error(&self, text: &str) {
+        if let Some((_, output, _)) = self.nodes() {
+            output.push_color(Color::from_rgb(1.0, 0.4, 0.4));
+            output.add_text(text);
+            output.pop();
+            output.newline();
+        }
+    }
+
+    /// Runs `line` with [`execute`](super::execute), and prints the line along with the output
+    /// or error.
+    #[inline]
+    pub fn submit(&mut self, line: &str) {
+        self.history_index = None;
+        self.print(&format!("> {line}"));
+
+        match super::execute(line) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => self.print(&output),
+            Err(err) => self.print_error(&err.to_string()),
+        }
+    }
+
+    /// Handles an input event, and returns `true` if it was used by the console. Used events
+    /// are marked as handled, so they don't reach the rest of the game.
+    #[inline]
+    pub fn handle_input(&mut self, event: &InputEvent) -> bool {
+        let Some(key) = event.cast::<InputEventKey>() else {
+            return false;
+        };
+        if !key.is_pressed() {
+            return false;
+        }
+
+        let scancode = key.scancode();
+        let handled = if scancode == self.toggle_key {
+            if !key.is_echo() {
+                self.toggle();
+            }
+            true
+        } else if !self.is_open() {
+            false
+        } else if scancode == GlobalConstants::KEY_ENTER
+            || scancode == GlobalConstants::KEY_KP_ENTER
+        {
+            if let Some((_, _, input)) = self.nodes() {
+                let line = input.text().to_string();
+                input.clear();
+                self.submit(&line);
+            }
+            true
+        } else if scancode == GlobalConstants::KEY_UP {
+            self.browse_history(true);
+            true
+        } else if scancode == GlobalConstants::KEY_DOWN {
+            self.browse_history(false);
+            true
+        } else if scancode == GlobalConstants::KEY_TAB {
+            self.complete();
+            true
+        } else if scancode == GlobalConstants::KEY_ESCAPE {
+            self.set_open(false);
+            true
+        } else {
+            false
+        };
+
+        if handled {
+            if let Some(viewport) = self.nodes().and_then(|(layer, _, _)| layer.get_viewport()) {
+                unsafe { viewport.assume_safe() }.set_input_as_handled();
+            }
+        }
+
+        handled
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        let history = super::history();
+        let index = match (self.history_index, older) {
+            (None, true) => history.len().checked_sub(1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < history.len() => Some(index + 1),
+            _ => None,
+        };
+
+        self.history_index = index;
+        let line = index.and_then(|index| history.get(index));
+        self.set_input(line.map_or("", String::as_str));
+    }
+
+    fn complete(&mut self) {
+        let Some((_, _, input)) = self.nodes() else {
+            return;
+        };
+
+        let line = input.text().to_string();
+        let completions = super::complete(&line);
+
+        match completions.as_slice() {
+            [] => {}
+            [completion] => self.set_input(&format!("{completion} ")),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.as_str(), |common, completion| {
+                    let len = common
+                        .char_indices()
+                        .zip(completion.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(common.len().min(completion.len()), |((idx, _), _)| idx);
+                    &common[..len]
+                });
+
+                let start = common.trim_end_matches(|c: char| !c.is_whitespace()).len();
+                let options = completions
+                    .iter()
+                    .map(|completion| &completion[start..])
+                    .collect::<Vec<_>>();
+
+                self.print(&options.join("  "));
+                self.set_input(common);
+            }
+        }
+    }
+
+    fn set_input(&self, text: &str) {
+        if let Some((_, _, input)) = self.nodes() {
+            input.set_text(text);
+            input.set_cursor_position(text.chars().count() as i64);
+        }
+    }
+
+    fn nodes(
+        &self,
+    ) -> Option<(
+        TRef<'_, CanvasLayer>,
+        TRef<'_, RichTextLabel>,
+        TRef<'_, LineEdit>,
+    )> {
+        unsafe {
+            Some((
+                self.layer.assume_safe_if_sane()?,
+                self.output.assume_safe_if_sane()?,
+                self.input.assume_safe_if_sane()?,
+            ))
+        }
+    }
+}
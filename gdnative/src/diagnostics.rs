@@ -0,0 +1,364 @@
+//! Information about the state of script calls and instances, for debugging.
+//!
+//! Besides the items of [`gdnative_core::diagnostics`], this module can dump the scene tree and
+//! the live instances of a class as [Graphviz DOT](https://graphviz.org/doc/info/lang.html) or
+//! JSON, e.g. from a console command or in test assertions. Classes that
+//! implement [`DebugState`] and are registered with [`register_debug_state`] add selected
+//! fields to the dumps:
+//!
+//! ```no_run
+//! use gdnative::diagnostics::{self, DebugFields, DebugState, DumpFormat};
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Node)]
+//! # #[no_constructor]
+//! struct Player {
+//!     health: u32,
+//!     target: Option<Vector2>,
+//! }
+//!
+//! #[methods]
+//! impl Player {}
+//!
+//! impl DebugState for Player {
+//!     fn debug_state(&self, fields: &mut DebugFields) {
+//!         fields.field("health", self.health).field("target", self.target);
+//!     }
+//! }
+//!
+//! fn init(handle: InitHandle) {
+//!     handle.add_class::<Player>();
+//!     diagnostics::register_debug_state::<Player>();
+//!     diagnostics::track_instances(true);
+//! }
+//!
+//! fn print_level(level: &Node) {
+//!     godot_print!("{}", diagnostics::dump_tree(level, DumpFormat::Dot));
+//!     godot_print!("{}", diagnostics::dump_instances::<Player>(DumpFormat::Json));
+//! }
+//! ```
+
+use std::any::TypeId;
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::api::{NativeScript, Node, Object, Resource};
+use crate::export::user_data::Map;
+use crate::export::NativeClass;
+use crate::object::ownership::Shared;
+use crate::object::{GodotObject, SubClass, TRef};
+
+#[doc(inline)]
+pub use gdnative_core::diagnostics::*;
+
+/// Reads the debug state of an object, if it is an instance of the registered class.
+type StateFn = fn(TRef<'_, Object, Shared>) -> Option<Vec<(String, String)>>;
+
+/// Registered classes, in the order they were registered in.
+static STATE_FNS: Mutex<Vec<(TypeId, StateFn)>> = Mutex::new(Vec::new());
+
+/// Output format of [`dump_tree`] and [`dump_instances`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DumpFormat {
+    /// A Graphviz `digraph`, with an edge from each node to its children. Nodes with Rust
+    /// scripts are filled.
+    Dot,
+    /// A JSON object for each node, with its children in the `children` array.
+    Json,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(DumpFormat::Dot),
+            "json" => Ok(DumpFormat::Json),
+            _ => Err(format!(
+                "unknown dump format `{s}`, expected `dot` or `json`"
+            )),
+        }
+    }
+}
+
+/// Classes with fields that are included in dumps, once registered with
+/// [`register_debug_state`].
+pub trait DebugState: NativeClass {
+    /// Adds the fields of `self` that are worth showing in dumps.
+    fn debug_state(&self, fields: &mut DebugFields);
+}
+
+/// Fields recorded by [`DebugState::debug_state`].
+#[derive(Debug, Default)]
+pub struct DebugFields {
+    fields: Vec<(String, String)>,
+}
+
+impl DebugFields {
+    /// Records a field, formatted with `Debug`.
+    #[inline]
+    pub fn field(&mut self, name: &str, value: impl fmt::Debug) -> &mut Self {
+        self.fields.push((name.to_owned(), format!("{value:?}")));
+        self
+    }
+}
+
+/// Includes the fields of `C` in dumps. Registering a class more than once has no effect.
+///
+/// If an instance is borrowed mutably while it is dumped, its state is replaced by an `error`
+/// field.
+#[inline]
+pub fn register_debug_state<C>()
+where
+    C: DebugState,
+    C::UserData: Map,
+    C::Base: SubClass<Object>,
+{
+    let mut state_fns = STATE_FNS.lock().unwrap();
+    if !state_fns.iter().any(|(ty, _)| *ty == TypeId::of::<C>()) {
+        state_fns.push((TypeId::of::<C>(), read_state::<C>));
+    }
+}
+
+fn read_state<C>(object: TRef<'_, Object, Shared>) -> Option<Vec<(String, String)>>
+where
+    C: DebugState,
+    C::UserData: Map,
+    C::Base: SubClass<Object>,
+{
+    let instance = object.cast::<C::Base>()?.cast_instance::<C>()?;
+    let mut fields = DebugFields::default();
+    if let Err(err) = instance.map(|this, _| this.debug_state(&mut fields)) {
+        fields.fields = vec![("error".into(), err.to_string())];
+    }
+    Some(fields.fields)
+}
+
+fn debug_state(object: TRef<'_, Object, Shared>) -> Vec<(String, String)> {
+    let state_fns = STATE_FNS.lock().unwrap().clone();
+    state_fns
+        .into_iter()
+        .find_map(|(_, state_fn)| state_fn(object))
+        .unwrap_or_default()
+}
+
+/// Dumps the subtree of `root`, with the name, class and script of each node, and the fields
+/// of instances of classes registered with [`register_debug_state`].
+///
+/// The subtree must not be modified while it is dumped, e.g. by the `debug_state` methods.
+#[inline]
+pub fn dump_tree(root: &Node, format: DumpFormat) -> String {
+    // SAFETY: the node is borrowed, and only accessed through shared references while dumping.
+    let root = unsafe { root.assume_shared().assume_safe() };
+    let tree = DumpedNode::capture(root);
+
+    let mut out = String::new();
+    match format {
+        DumpFormat::Dot => {
+            out.push_str("digraph {\n");
+            tree.write_dot(&mut out);
+            out.push_str("}\n");
+        }
+        DumpFormat::Json => tree.write_json(&mut out),
+    }
+    out
+}
+
+/// Dumps the live instances of `C`, with the fields of [`DebugState`]. Only instances created
+/// while [tracking](track_instances) is enabled are included.
+///
+/// This must be called on the main thread, while the instances aren't used on other threads.
+#[inline]
+pub fn dump_instances<C>(format: DumpFormat) -> String
+where
+    C: DebugState,
+    C::UserData: Map,
+    C::Base: SubClass<Object>,
+{
+    let instances = live_instances::<C>().into_iter().filter_map(|id| {
+        // SAFETY: the caller doesn't use the instances on other threads while dumping.
+        let object = unsafe { Object::try_from_instance_id(id) }?;
+        let state = read_state::<C>(object)?;
+        Some((object, state))
+    });
+
+    let mut out = String::new();
+    match format {
+        DumpFormat::Dot => {
+            out.push_str("digraph {\n");
+            for (object, state) in instances {
+                let label = label(&object.get_class().to_string(), None, &state);
+                let id = object.get_instance_id();
+                writeln!(out, "  n{id} [label=\"{}\"];", escape_dot(&label)).unwrap();
+            }
+            out.push_str("}\n");
+        }
+        DumpFormat::Json => {
+            out.push('[');
+            for (index, (object, state)) in instances.enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write!(
+                    out,
+                    "{{\"instance_id\":{},\"class\":{},\"state\":",
+                    object.get_instance_id(),
+                    json_string(&object.get_class().to_string()),
+                )
+                .unwrap();
+                write_json_state(&mut out, &state);
+                out.push('}');
+            }
+            out.push(']');
+        }
+    }
+    out
+}
+
+struct DumpedNode {
+    instance_id: i64,
+    name: String,
+    class: String,
+    /// Class name of a NativeScript, or path of another script.
+    script: Option<String>,
+    rust: bool,
+    state: Vec<(String, String)>,
+    children: Vec<DumpedNode>,
+}
+
+impl DumpedNode {
+    fn capture(node: TRef<'_, Node, Shared>) -> Self {
+        let (script, rust) = match node.get_script() {
+            Some(script) => {
+                // SAFETY: the script is kept alive by the node, which is borrowed.
+                let script = unsafe { script.assume_safe() };
+                if let Some(native_script) = script.cast::<NativeScript>() {
+                    (Some(native_script.class_name().to_string()), true)
+                } else {
+                    let path = script.cast::<Resource>().map(|res| res.path().to_string());
+                    (path, false)
+                }
+            }
+            None => (None, false),
+        };
+
+        let children = (0..node.get_child_count())
+            .filter_map(|idx| node.get_child(idx))
+            .map(|child| {
+                // SAFETY: children are kept alive by their parent, which is borrowed and not
+                // modified while dumping.
+                let child = unsafe { child.assume_safe() };
+                Self::capture(child)
+            })
+            .collect();
+
+        DumpedNode {
+            instance_id: node.get_instance_id(),
+            name: node.name().to_string(),
+            class: node.get_class().to_string(),
+            script,
+            rust,
+            state: debug_state(node.upcast::<Object>()),
+            children,
+        }
+    }
+
+    fn write_dot(&self, out: &mut String) {
+        let label = label(&self.name, Some(&self.class), &self.state);
+        let label = match &self.script {
+            Some(script) => format!("{label}\nscript: {script}"),
+            None => label,
+        };
+        let style = if self.rust {
+            ", style=filled, fillcolor=\"#f4a261\""
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  n{} [label=\"{}\"{style}];",
+            self.instance_id,
+            escape_dot(&label),
+        )
+        .unwrap();
+
+        for child in &self.children {
+            child.write_dot(out);
+            writeln!(out, "  n{} -> n{};", self.instance_id, child.instance_id).unwrap();
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        write!(
+            out,
+            "{{\"name\":{},\"class\":{},\"instance_id\":{},\"script\":{},\"rust\":{},\"state\":",
+            json_string(&self.name),
+            json_string(&self.class),
+            self.instance_id,
+            self.script
+                .as_deref()
+                .map_or_else(|| "null".into(), json_string),
+            self.rust,
+        )
+        .unwrap();
+        write_json_state(out, &self.state);
+
+        out.push_str(",\"children\":[");
+        for (index, child) in self.children.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+}
+
+fn label(name: &str, class: Option<&str>, state: &[(String, String)]) -> String {
+    let mut label = match class {
+        Some(class) => format!("{name} ({class})"),
+        None => name.to_owned(),
+    };
+    for (field, value) in state {
+        write!(label, "\n{field}: {value}").unwrap();
+    }
+    label
+}
+
+fn write_json_state(out: &mut String, state: &[(String, String)]) {
+    out.push('{');
+    for (index, (field, value)) in state.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write!(out, "{}:{}", json_string(field), json_string(value)).unwrap();
+    }
+    out.push('}');
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,397 @@
+//! Batched custom drawing for `CanvasItem`s.
+//!
+//! Custom-drawn widgets typically issue many `draw_*` calls in `_draw`, each of which is a
+//! separate call into the engine. [`Draw2D`] instead records shapes into Rust-side buffers,
+//! merging consecutive shapes of the same kind into a single batch, and submits each batch with
+//! a single call. Since the geometry is retained between redraws, it only has to be rebuilt
+//! when it changes:
+//!
+//! ```no_run
+//! use gdnative::draw::Draw2D;
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Control)]
+//! struct Gauge {
+//!     value: f32,
+//!     geometry: Draw2D,
+//! }
+//!
+//! #[methods]
+//! impl Gauge {
+//!     fn new(_owner: &Control) -> Self {
+//!         Gauge { value: 0.0, geometry: Draw2D::new() }
+//!     }
+//!
+//!     #[method]
+//!     fn set_value(&mut self, #[base] owner: &Control, value: f32) {
+//!         self.value = value;
+//!
+//!         let size = owner.size();
+//!         self.geometry.clear();
+//!         self.geometry
+//!             .rect(Rect2::new(Vector2::ZERO, size), Color::from_rgb(0.1, 0.1, 0.1))
+//!             .rect(
+//!                 Rect2::new(Vector2::ZERO, Vector2::new(size.x * value, size.y)),
+//!                 Color::from_rgb(0.2, 0.8, 0.3),
+//!             )
+//!             .rect_outline(Rect2::new(Vector2::ZERO, size), Color::from_rgb(1.0, 1.0, 1.0), 1.0);
+//!
+//!         owner.update();
+//!     }
+//!
+//!     #[method]
+//!     fn _draw(&self, #[base] owner: &Control) {
+//!         self.geometry.draw(owner);
+//!     }
+//! }
+//! ```
+//!
+//! Shapes are drawn in the order they were added, so later shapes are drawn on top of earlier
+//! ones, the same as with individual `draw_*` calls.
+
+use std::f32::consts::TAU;
+
+use crate::api::visual_server::TextureRid;
+use crate::api::{CanvasItem, Texture, VisualServer};
+use crate::core_types::{Color, PoolArray, Rect2, Vector2};
+use crate::object::{Ref, SubClass};
+
+/// Handle to a texture added to a [`Draw2D`] with [`Draw2D::add_texture`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TextureId(usize);
+
+/// Retained, batched geometry for drawing on a `CanvasItem`. See the
+/// [module-level docs](self) for an example.
+#[derive(Debug, Default)]
+pub struct Draw2D {
+    batches: Vec<Batch>,
+    textures: Vec<(Ref<Texture>, Vector2)>,
+    antialiased: bool,
+}
+
+#[derive(Debug)]
+enum Batch {
+    Triangles {
+        texture: Option<TextureId>,
+        points: Vec<Vector2>,
+        colors: Vec<Color>,
+        uvs: Vec<Vector2>,
+        indices: Vec<i32>,
+    },
+    Lines {
+        width: f32,
+        points: Vec<Vector2>,
+        colors: Vec<Color>,
+    },
+}
+
+impl Draw2D {
+    /// Creates empty geometry.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether lines are antialiased. Defaults to `false`.
+    #[inline]
+    pub fn set_antialiased(&mut self, antialiased: bool) {
+        self.antialiased = antialiased;
+    }
+
+    /// Adds a texture that can then be used by textured shapes. Adding the same texture again
+    /// returns the same handle.
+    ///
+    /// Handles remain valid when the geometry is cleared, and are specific to this `Draw2D`.
+    #[inline]
+    pub fn add_texture(&mut self, texture: Ref<Texture>) -> TextureId {
+        if let Some(index) = self
+            .textures
+            .iter()
+            .position(|(existing, _)| existing.as_ptr() == texture.as_ptr())
+        {
+            return TextureId(index);
+        }
+
+        // SAFETY: textures are only read from, as is the case for all drawing methods.
+        let size = unsafe { texture.assume_safe() }.get_size();
+        self.textures.push((texture, size));
+        TextureId(self.textures.len() - 1)
+    }
+
+    /// Removes all shapes, keeping the added textures.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.batches.clear();
+    }
+
+    /// Returns `true` if no shapes were added since creation or the last [`Self::clear`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Returns the number of calls into the engine [`Self::draw`] makes for the current shapes.
+    ///
+    /// ```
+    /// use gdnative::draw::Draw2D;
+    /// use gdnative::prelude::*;
+    ///
+    /// let red = Color::from_rgb(1.0, 0.0, 0.0);
+    /// let mut geometry = Draw2D::new();
+    /// for i in 0..100 {
+    ///     geometry.rect(Rect2::new(Vector2::new(i as f32 * 10.0, 0.0), Vector2::ONE), red);
+    /// }
+    /// geometry.line(Vector2::ZERO, Vector2::new(100.0, 0.0), red, 2.0);
+    ///
+    /// assert_eq!(2, geometry.batch_count());
+    /// ```
+    #[inline]
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Adds a filled rectangle.
+    #[inline]
+    pub fn rect(&mut self, rect: Rect2, color: Color) -> &mut Self {
+        self.quad(rect, color, None, Rect2::new(Vector2::ZERO, Vector2::ONE))
+    }
+
+    /// Adds the outline of a rectangle, with lines of the given width.
+    #[inline]
+    pub fn rect_outline(&mut self, rect: Rect2, color: Color, width: f32) -> &mut Self {
+        let [a, b, c, d] = corners(rect);
+        self.polyline(&[a, b, c, d, a], color, width)
+    }
+
+    /// Adds a filled convex polygon. Concave polygons are not drawn correctly.
+    #[inline]
+    pub fn convex_polygon(&mut self, points: &[Vector2], color: Color) -> &mut Self {
+        if points.len() >= 3 {
+            let uvs = vec![Vector2::ZERO; points.len()];
+            self.triangle_fan(None, points, &uvs, color);
+        }
+        self
+    }
+
+    /// Adds a filled circle, approximated by a polygon.
+    #[inline]
+    pub fn circle(&mut self, center: Vector2, radius: f32, color: Color) -> &mut Self {
+        let segments = ((radius * 0.5) as usize).clamp(8, 64);
+        let points = (0..segments)
+            .map(|i| {
+                let angle = TAU * i as f32 / segments as f32;
+                center + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect::<Vec<_>>();
+
+        self.convex_polygon(&points, color)
+    }
+
+    /// Adds a line from `from` to `to`.
+    #[inline]
+    pub fn line(&mut self, from: Vector2, to: Vector2, color: Color, width: f32) -> &mut Self {
+        self.polyline(&[from, to], color, width)
+    }
+
+    /// Adds connected lines through `points`.
+    #[inline]
+    pub fn polyline(&mut self, points: &[Vector2], color: Color, width: f32) -> &mut Self {
+        if points.len() < 2 {
+            return self;
+        }
+
+        let (batch_points, batch_colors) = self.lines(width);
+        for segment in points.windows(2) {
+            batch_points.extend_from_slice(segment);
+            batch_colors.push(color);
+        }
+        self
+    }
+
+    /// Adds a rectangle showing the whole `texture`, tinted by `modulate`.
+    ///
+    /// # Panics
+    ///
+    /// If `texture` was added to another `Draw2D`.
+    #[inline]
+    pub fn texture_rect(&mut self, rect: Rect2, texture: TextureId, modulate: Color) -> &mut Self {
+        self.quad(
+            rect,
+            modulate,
+            Some(texture),
+            Rect2::new(Vector2::ZERO, Vector2::ONE),
+        )
+    }
+
+    /// Adds a rectangle showing the `source` region of `texture` in pixels, tinted by
+    /// `modulate`.
+    ///
+    /// # Panics
+    ///
+    /// If `texture` was added to another `Draw2D`.
+    #[inline]
+    pub fn texture_rect_region(
+        &mut self,
+        rect: Rect2,
+        texture: TextureId,
+        source: Rect2,
+        modulate: Color,
+    ) -> &mut Self {
+        let (_, size) = self
+            .textures
+            .get(texture.0)
+            .expect("texture should be added to this Draw2D");
+        let size = Vector2::new(size.x.max(1.0), size.y.max(1.0));
+        let uv = Rect2::new(source.position / size, source.size / size);
+        self.quad(rect, modulate, Some(texture), uv)
+    }
+
+    /// Draws all shapes on `item`. This must be called from the `_draw` method of `item`.
+    #[inline]
+    pub fn draw<T>(&self, item: &T)
+    where
+        T: SubClass<CanvasItem>,
+    {
+        let item = item.upcast::<CanvasItem>();
+
+        for batch in &self.batches {
+            match batch {
+                Batch::Triangles {
+                    texture,
+                    points,
+                    colors,
+                    uvs,
+                    indices,
+                } => {
+                    let texture = texture.map_or_else(TextureRid::default, |texture| {
+                        // SAFETY: textures are only read from, as is the case for all
+                        // drawing methods.
+                        let texture = unsafe { self.textures[texture.0].0.assume_safe() };
+                        TextureRid::from_rid(texture.get_rid())
+                    });
+
+                    // SAFETY: drawing happens on the main thread, in `_draw` of `item`.
+                    unsafe {
+                        VisualServer::godot_singleton().canvas_item_add_triangle_array(
+                            item.get_canvas_item(),
+                            PoolArray::from_slice(indices),
+                            PoolArray::from_slice(points),
+                            PoolArray::from_slice(colors),
+                            PoolArray::from_slice(uvs),
+                            PoolArray::new(),
+                            PoolArray::new(),
+                            texture,
+                            -1,
+                            TextureRid::default(),
+                            false,
+                            false,
+                        );
+                    }
+                }
+                Batch::Lines {
+                    width,
+                    points,
+                    colors,
+                } => {
+                    item.draw_multiline_colors(
+                        PoolArray::from_slice(points),
+                        PoolArray::from_slice(colors),
+                        *width as f64,
+                        self.antialiased,
+                    );
+                }
+            }
+        }
+    }
+
+    fn quad(
+        &mut self,
+        rect: Rect2,
+        color: Color,
+        texture: Option<TextureId>,
+        uv: Rect2,
+    ) -> &mut Self {
+        if let Some(texture) = texture {
+            assert!(
+                texture.0 < self.textures.len(),
+                "texture should be added to this Draw2D"
+            );
+        }
+
+        self.triangle_fan(texture, &corners(rect), &corners(uv), color);
+        self
+    }
+
+    fn triangle_fan(
+        &mut self,
+        texture: Option<TextureId>,
+        points: &[Vector2],
+        uvs: &[Vector2],
+        color: Color,
+    ) {
+        let merge = matches!(
+            self.batches.last(),
+            Some(Batch::Triangles { texture: last, .. }) if *last == texture
+        );
+
+        if !merge {
+            self.batches.push(Batch::Triangles {
+                texture,
+                points: Vec::new(),
+                colors: Vec::new(),
+                uvs: Vec::new(),
+                indices: Vec::new(),
+            });
+        }
+
+        if let Some(Batch::Triangles {
+            points: batch_points,
+            colors,
+            uvs: batch_uvs,
+            indices,
+            ..
+        }) = self.batches.last_mut()
+        {
+            let first = batch_points.len() as i32;
+            for i in 1..points.len() as i32 - 1 {
+                indices.extend_from_slice(&[first, first + i, first + i + 1]);
+            }
+
+            batch_points.extend_from_slice(points);
+            batch_uvs.extend_from_slice(uvs);
+            colors.extend(std::iter::repeat(color).take(points.len()));
+        }
+    }
+
+    fn lines(&mut self, width: f32) -> (&mut Vec<Vector2>, &mut Vec<Color>) {
+        let merge = matches!(
+            self.batches.last(),
+            Some(Batch::Lines { width: last, .. }) if *last == width
+        );
+
+        if !merge {
+            self.batches.push(Batch::Lines {
+                width,
+                points: Vec::new(),
+                colors: Vec::new(),
+            });
+        }
+
+        match self.batches.last_mut() {
+            Some(Batch::Lines { points, colors, .. }) => (points, colors),
+            _ => unreachable!("a line batch was just pushed"),
+        }
+    }
+}
+
+/// Returns the corners of `rect`, clockwise from the top left.
+fn corners(rect: Rect2) -> [Vector2; 4] {
+    let end = rect.position + rect.size;
+    [
+        rect.position,
+        Vector2::new(end.x, rect.position.y),
+        end,
+        Vector2::new(rect.position.x, end.y),
+    ]
+}
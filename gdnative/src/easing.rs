@@ -0,0 +1,309 @@
+//! Easing curves used by `Tween`.
+//!
+//! This module contains Rust ports of the interpolation equations behind
+//! [`Tween::TransitionType`][TransitionType] and [`Tween::EaseType`][EaseType]. They don't need
+//! a running engine, so animation logic can be precomputed or unit-tested without one.
+//!
+//! All curves map the progress `0.0` to `0.0` and `1.0` to `1.0`, except for a deviation of
+//! `0.001` in some `EXPO` variants that is inherited from the engine. Values in between may lie
+//! outside of this range, e.g. for the overshooting `ELASTIC` and `BACK` transitions. To animate
+//! other values, use the result as the weight of a linear interpolation:
+//!
+//! ```
+//! use gdnative::api::tween::{EaseType, TransitionType};
+//! use gdnative::easing;
+//! use gdnative::prelude::*;
+//!
+//! let from = Vector2::new(0.0, 0.0);
+//! let to = Vector2::new(100.0, 50.0);
+//!
+//! let weight = easing::interpolate(TransitionType::QUAD, EaseType::IN, 0.5);
+//! assert_eq!(from.linear_interpolate(to, weight), Vector2::new(25.0, 12.5));
+//! ```
+
+use std::f32::consts::PI;
+
+use crate::api::tween::{EaseType, TransitionType};
+
+/// Returns the eased progress for `t`, using the same equations as `Tween`.
+///
+/// `t` is the normalized progress of the animation, i.e. the elapsed time divided by the
+/// duration. It is clamped to `0.0..=1.0`. Unknown transition types are treated as `LINEAR`, and
+/// unknown ease types as `IN_OUT`.
+///
+/// ```
+/// use gdnative::api::tween::{EaseType, TransitionType};
+/// use gdnative::easing::interpolate;
+///
+/// assert_eq!(interpolate(TransitionType::LINEAR, EaseType::IN, 0.25), 0.25);
+/// assert_eq!(interpolate(TransitionType::CUBIC, EaseType::OUT, 0.5), 0.875);
+/// assert_eq!(interpolate(TransitionType::SINE, EaseType::IN_OUT, 1.0), 1.0);
+///
+/// // Overshoots before settling on the final value
+/// assert!(interpolate(TransitionType::BACK, EaseType::OUT, 0.6) > 1.0);
+/// ```
+#[inline]
+pub fn interpolate(transition: TransitionType, ease: EaseType, t: f32) -> f32 {
+    let curve = match transition {
+        TransitionType::SINE => Curve::SINE,
+        TransitionType::QUINT => Curve::QUINT,
+        TransitionType::QUART => Curve::QUART,
+        TransitionType::QUAD => Curve::QUAD,
+        TransitionType::EXPO => Curve::EXPO,
+        TransitionType::ELASTIC => Curve::ELASTIC,
+        TransitionType::CUBIC => Curve::CUBIC,
+        TransitionType::CIRC => Curve::CIRC,
+        TransitionType::BOUNCE => Curve::BOUNCE,
+        TransitionType::BACK => Curve::BACK,
+        _ => return t.clamp(0.0, 1.0),
+    };
+
+    curve.ease(ease, t.clamp(0.0, 1.0))
+}
+
+/// Interpolates between `from` and `to` like `Tween.interpolate_property`, given the `elapsed`
+/// time and the total `duration` of the animation.
+///
+/// Returns `to` if `duration` is not positive.
+///
+/// ```
+/// use gdnative::api::tween::{EaseType, TransitionType};
+/// use gdnative::easing::interpolate_value;
+///
+/// let value = interpolate_value(10.0, 20.0, 1.0, 2.0, TransitionType::QUAD, EaseType::IN);
+/// assert_eq!(value, 12.5);
+/// ```
+#[inline]
+pub fn interpolate_value(
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+    transition: TransitionType,
+    ease: EaseType,
+) -> f32 {
+    if duration <= 0.0 {
+        return to;
+    }
+
+    from + (to - from) * interpolate(transition, ease, elapsed / duration)
+}
+
+/// In and out variants of a transition. The in-out and out-in variants are derived from them,
+/// except where the engine uses a dedicated equation.
+struct Curve {
+    ease_in: fn(f32) -> f32,
+    ease_out: fn(f32) -> f32,
+    ease_in_out: Option<fn(f32) -> f32>,
+}
+
+impl Curve {
+    const SINE: Self = Curve {
+        ease_in: |t| 1.0 - (t * (PI / 2.0)).cos(),
+        ease_out: |t| (t * (PI / 2.0)).sin(),
+        ease_in_out: Some(|t| -0.5 * ((PI * t).cos() - 1.0)),
+    };
+
+    const QUINT: Self = Curve {
+        ease_in: |t| t.powi(5),
+        ease_out: |t| (t - 1.0).powi(5) + 1.0,
+        ease_in_out: Some(|t| {
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * t.powi(5)
+            } else {
+                0.5 * ((t - 2.0).powi(5) + 2.0)
+            }
+        }),
+    };
+
+    const QUART: Self = Curve {
+        ease_in: |t| t.powi(4),
+        ease_out: |t| -((t - 1.0).powi(4) - 1.0),
+        ease_in_out: Some(|t| {
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * t.powi(4)
+            } else {
+                -0.5 * ((t - 2.0).powi(4) - 2.0)
+            }
+        }),
+    };
+
+    const QUAD: Self = Curve {
+        ease_in: |t| t * t,
+        ease_out: |t| -t * (t - 2.0),
+        ease_in_out: Some(|t| {
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * t * t
+            } else {
+                -0.5 * ((t - 1.0) * (t - 3.0) - 1.0)
+            }
+        }),
+    };
+
+    const EXPO: Self = Curve {
+        ease_in: |t| {
+            if t == 0.0 {
+                0.0
+            } else {
+                2f32.powf(10.0 * (t - 1.0)) - 0.001
+            }
+        },
+        ease_out: |t| {
+            if t == 1.0 {
+                1.0
+            } else {
+                1.001 * (1.0 - 2f32.powf(-10.0 * t))
+            }
+        },
+        ease_in_out: Some(|t| {
+            if t == 0.0 {
+                return 0.0;
+            }
+            if t == 1.0 {
+                return 1.0;
+            }
+
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * 2f32.powf(10.0 * (t - 1.0)) - 0.0005
+            } else {
+                0.5 * 1.0005 * (2.0 - 2f32.powf(-10.0 * (t - 1.0)))
+            }
+        }),
+    };
+
+    const ELASTIC: Self = Curve {
+        ease_in: |t| {
+            if t == 0.0 || t == 1.0 {
+                return t;
+            }
+
+            let p = 0.3;
+            let s = p / 4.0;
+            let t = t - 1.0;
+            -(2f32.powf(10.0 * t) * ((t - s) * (2.0 * PI) / p).sin())
+        },
+        ease_out: |t| {
+            if t == 0.0 || t == 1.0 {
+                return t;
+            }
+
+            let p = 0.3;
+            let s = p / 4.0;
+            2f32.powf(-10.0 * t) * ((t - s) * (2.0 * PI) / p).sin() + 1.0
+        },
+        ease_in_out: Some(|t| {
+            if t == 0.0 || t == 1.0 {
+                return t;
+            }
+
+            let p = 0.3 * 1.5;
+            let s = p / 4.0;
+            let t = t * 2.0 - 1.0;
+            if t < 0.0 {
+                -0.5 * (2f32.powf(10.0 * t) * ((t - s) * (2.0 * PI) / p).sin())
+            } else {
+                2f32.powf(-10.0 * t) * ((t - s) * (2.0 * PI) / p).sin() * 0.5 + 1.0
+            }
+        }),
+    };
+
+    const CUBIC: Self = Curve {
+        ease_in: |t| t * t * t,
+        ease_out: |t| {
+            let t = t - 1.0;
+            t * t * t + 1.0
+        },
+        ease_in_out: Some(|t| {
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * t * t * t
+            } else {
+                let t = t - 2.0;
+                0.5 * (t * t * t + 2.0)
+            }
+        }),
+    };
+
+    const CIRC: Self = Curve {
+        ease_in: |t| -((1.0 - t * t).sqrt() - 1.0),
+        ease_out: |t| {
+            let t = t - 1.0;
+            (1.0 - t * t).sqrt()
+        },
+        ease_in_out: Some(|t| {
+            let t = t * 2.0;
+            if t < 1.0 {
+                -0.5 * ((1.0 - t * t).sqrt() - 1.0)
+            } else {
+                let t = t - 2.0;
+                0.5 * ((1.0 - t * t).sqrt() + 1.0)
+            }
+        }),
+    };
+
+    const BOUNCE: Self = Curve {
+        ease_in: |t| 1.0 - bounce_out(1.0 - t),
+        ease_out: bounce_out,
+        ease_in_out: None,
+    };
+
+    const BACK: Self = Curve {
+        ease_in: |t| {
+            let s = 1.70158;
+            t * t * ((s + 1.0) * t - s)
+        },
+        ease_out: |t| {
+            let s = 1.70158;
+            let t = t - 1.0;
+            t * t * ((s + 1.0) * t + s) + 1.0
+        },
+        ease_in_out: Some(|t| {
+            let s = 1.70158 * 1.525;
+            let t = t * 2.0;
+            if t < 1.0 {
+                0.5 * (t * t * ((s + 1.0) * t - s))
+            } else {
+                let t = t - 2.0;
+                0.5 * (t * t * ((s + 1.0) * t + s) + 2.0)
+            }
+        }),
+    };
+
+    fn ease(&self, ease: EaseType, t: f32) -> f32 {
+        match ease {
+            EaseType::IN => (self.ease_in)(t),
+            EaseType::OUT => (self.ease_out)(t),
+            EaseType::OUT_IN => {
+                if t < 0.5 {
+                    0.5 * (self.ease_out)(t * 2.0)
+                } else {
+                    0.5 + 0.5 * (self.ease_in)(t * 2.0 - 1.0)
+                }
+            }
+            _ => match self.ease_in_out {
+                Some(ease_in_out) => ease_in_out(t),
+                None if t < 0.5 => 0.5 * (self.ease_in)(t * 2.0),
+                None => 0.5 + 0.5 * (self.ease_out)(t * 2.0 - 1.0),
+            },
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    if t < 1.0 / 2.75 {
+        7.5625 * t * t
+    } else if t < 2.0 / 2.75 {
+        let t = t - 1.5 / 2.75;
+        7.5625 * t * t + 0.75
+    } else if t < 2.5 / 2.75 {
+        let t = t - 2.25 / 2.75;
+        7.5625 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / 2.75;
+        7.5625 * t * t + 0.984375
+    }
+}
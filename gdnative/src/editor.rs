@@ -0,0 +1,561 @@
+//! Editor extensions implemented in Rust.
+//!
+//! Currently, this module contains support for custom 3D gizmos. Gizmos are drawn by an
+//! [`EditorSpatialGizmoPlugin`], which is a Godot class with a number of virtual methods. To
+//! write one in Rust, derive `NativeClass` for a type inheriting `EditorSpatialGizmoPlugin`,
+//! implement [`SpatialGizmoPlugin`] for it and register the virtual methods with
+//! [`register_gizmo_plugin`]:
+//!
+//! ```no_run
+//! use gdnative::api::{EditorSpatialGizmoPlugin, Spatial};
+//! use gdnative::editor::{register_gizmo_plugin, GizmoDrawer, MaterialBuilder, SpatialGizmoPlugin};
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(EditorSpatialGizmoPlugin)]
+//! #[register_with(register_gizmo_plugin)]
+//! struct RangeGizmos;
+//!
+//! #[methods]
+//! impl RangeGizmos {
+//!     fn new(base: &EditorSpatialGizmoPlugin) -> Self {
+//!         MaterialBuilder::new(base, "range", Color::from_rgb(1.0, 0.5, 0.0))
+//!             .with_on_top(true)
+//!             .done();
+//!
+//!         RangeGizmos
+//!     }
+//! }
+//!
+//! impl SpatialGizmoPlugin for RangeGizmos {
+//!     fn name(&self) -> String {
+//!         "Range".into()
+//!     }
+//!
+//!     fn has_gizmo(&self, spatial: TRef<Spatial>) -> bool {
+//!         spatial.has_meta("range")
+//!     }
+//!
+//!     fn redraw(&self, drawer: &GizmoDrawer) {
+//!         drawer.clear();
+//!         drawer.add_lines(
+//!             &[Vector3::ZERO, Vector3::new(0.0, 0.0, -10.0)],
+//!             "range",
+//!             false,
+//!             Color::from_rgb(1.0, 1.0, 1.0),
+//!         );
+//!     }
+//! }
+//! ```
+//!
+//! The class must be registered with `InitHandle::add_tool_class`, since editor classes are only
+//! available in the editor. An instance of it can then be added by an `EditorPlugin`:
+//!
+//! ```gdscript
+//! tool
+//! extends EditorPlugin
+//!
+//! var gizmos = preload("res://range_gizmos.gdns").new()
+//!
+//! func _enter_tree():
+//!     add_spatial_gizmo_plugin(gizmos)
+//!
+//! func _exit_tree():
+//!     remove_spatial_gizmo_plugin(gizmos)
+//! ```
+
+use crate::api::{
+    Camera, EditorSpatialGizmo, EditorSpatialGizmoPlugin, Material, Mesh, SkinReference, Spatial,
+    SpatialMaterial, Texture, TriangleMesh,
+};
+use crate::core_types::{Color, PoolArray, ToVariant, Variant, Vector2, Vector3};
+use crate::export::user_data::Map;
+use crate::export::{ClassBuilder, Method, NativeClass, Varargs, VarargsError};
+use crate::log::godot_error;
+use crate::object::ownership::Shared;
+use crate::object::{AsArg, GodotObject, Null, Ref, TInstance, TRef};
+
+/// Interface of a custom 3D gizmo plugin, corresponding to the virtual methods of
+/// `EditorSpatialGizmoPlugin` in Godot.
+///
+/// Implementations must be registered with [`register_gizmo_plugin`] to be called by the editor.
+/// Handles are identified by their index in the order they were added with
+/// [`GizmoDrawer::add_handles`].
+pub trait SpatialGizmoPlugin: NativeClass<Base = EditorSpatialGizmoPlugin> {
+    /// Returns the name of the gizmo, as shown in the "View > Gizmos" menu.
+    fn name(&self) -> String;
+
+    /// Returns whether `spatial` should get a gizmo from this plugin.
+    fn has_gizmo(&self, spatial: TRef<'_, Spatial>) -> bool;
+
+    /// Draws the gizmo. Called whenever the node's gizmo needs to be updated, which usually
+    /// requires clearing the previous contents with [`GizmoDrawer::clear`] first.
+    fn redraw(&self, drawer: &GizmoDrawer<'_>);
+
+    /// Returns the name of a handle, shown while it is being dragged. The default
+    /// implementation returns an empty string.
+    #[inline]
+    fn handle_name(&self, _gizmo: TRef<'_, EditorSpatialGizmo>, _index: i64) -> String {
+        String::new()
+    }
+
+    /// Returns the current value of the property edited by a handle. This is passed back to
+    /// [`SpatialGizmoPlugin::commit_handle`] as the value to restore. The default implementation
+    /// returns `null`.
+    #[inline]
+    fn handle_value(&self, _gizmo: TRef<'_, EditorSpatialGizmo>, _index: i64) -> Variant {
+        Variant::nil()
+    }
+
+    /// Called while a handle is being dragged, with the camera of the viewport and the cursor
+    /// position in it. The default implementation does nothing.
+    #[inline]
+    fn set_handle(
+        &self,
+        _gizmo: TRef<'_, EditorSpatialGizmo>,
+        _index: i64,
+        _camera: TRef<'_, Camera>,
+        _point: Vector2,
+    ) {
+    }
+
+    /// Called when a handle is released. `restore` is the value returned from
+    /// [`SpatialGizmoPlugin::handle_value`] before the drag started, which should be used to
+    /// create an undo action, or to reset the property if `cancel` is `true`. The default
+    /// implementation does nothing.
+    #[inline]
+    fn commit_handle(
+        &self,
+        _gizmo: TRef<'_, EditorSpatialGizmo>,
+        _index: i64,
+        _restore: Variant,
+        _cancel: bool,
+    ) {
+    }
+
+    /// Returns whether a handle should be highlighted. The default implementation returns
+    /// `false`.
+    #[inline]
+    fn is_handle_highlighted(&self, _gizmo: TRef<'_, EditorSpatialGizmo>, _index: i64) -> bool {
+        false
+    }
+
+    /// Returns the priority of the plugin. Plugins with higher priorities are preferred when
+    /// multiple plugins want to handle the same node. The default implementation returns `0`.
+    #[inline]
+    fn priority(&self) -> i64 {
+        0
+    }
+
+    /// Returns whether the gizmo can be hidden from the "View > Gizmos" menu. The default
+    /// implementation returns `true`.
+    #[inline]
+    fn can_be_hidden(&self) -> bool {
+        true
+    }
+
+    /// Returns whether nodes can still be selected by clicking on the gizmo when it's hidden.
+    /// The default implementation returns `false`.
+    #[inline]
+    fn is_selectable_when_hidden(&self) -> bool {
+        false
+    }
+}
+
+/// Drawing interface for a gizmo, passed to [`SpatialGizmoPlugin::redraw`].
+///
+/// Materials are referred to by the names they were created with on the plugin, e.g. using
+/// [`MaterialBuilder`]. If a material doesn't exist, an error is printed and nothing is drawn.
+#[derive(Copy, Clone, Debug)]
+pub struct GizmoDrawer<'a> {
+    plugin: TRef<'a, EditorSpatialGizmoPlugin>,
+    gizmo: TRef<'a, EditorSpatialGizmo>,
+}
+
+impl<'a> GizmoDrawer<'a> {
+    /// Returns the gizmo being drawn.
+    #[inline]
+    pub fn gizmo(&self) -> TRef<'a, EditorSpatialGizmo> {
+        self.gizmo
+    }
+
+    /// Returns the plugin drawing the gizmo.
+    #[inline]
+    pub fn plugin(&self) -> TRef<'a, EditorSpatialGizmoPlugin> {
+        self.plugin
+    }
+
+    /// Returns the node the gizmo belongs to.
+    #[inline]
+    pub fn spatial_node(&self) -> Option<TRef<'a, Spatial>> {
+        // SAFETY: gizmos are owned by their node, and are only redrawn on the main thread.
+        self.gizmo
+            .get_spatial_node()
+            .map(|node| unsafe { node.assume_safe() })
+    }
+
+    /// Returns the material `name` of the plugin, in the variant suitable for the gizmo's
+    /// current state, e.g. selected or editable.
+    #[inline]
+    pub fn material(&self, name: &str) -> Option<Ref<SpatialMaterial, Shared>> {
+        self.plugin.get_material(name, self.gizmo)
+    }
+
+    /// Removes everything drawn so far.
+    #[inline]
+    pub fn clear(&self) {
+        self.gizmo.clear();
+    }
+
+    /// Hides or shows the gizmo.
+    #[inline]
+    pub fn set_hidden(&self, hidden: bool) {
+        self.gizmo.set_hidden(hidden);
+    }
+
+    /// Draws lines between each pair of points in `lines`.
+    #[inline]
+    pub fn add_lines(&self, lines: &[Vector3], material: &str, billboard: bool, modulate: Color) {
+        if let Some(material) = self.material(material) {
+            self.gizmo
+                .add_lines(PoolArray::from_slice(lines), material, billboard, modulate);
+        }
+    }
+
+    /// Adds handles at the given positions. Secondary handles are used for less important
+    /// properties and don't show up in the handle list of the editor.
+    #[inline]
+    pub fn add_handles(
+        &self,
+        handles: &[Vector3],
+        material: &str,
+        billboard: bool,
+        secondary: bool,
+    ) {
+        if let Some(material) = self.material(material) {
+            self.gizmo.add_handles(
+                PoolArray::from_slice(handles),
+                material,
+                billboard,
+                secondary,
+            );
+        }
+    }
+
+    /// Draws a mesh, optionally overriding its material.
+    #[inline]
+    pub fn add_mesh(&self, mesh: impl AsArg<Mesh>, billboard: bool, material: Option<&str>) {
+        let skeleton = Null::<SkinReference>::null();
+
+        match material {
+            Some(name) => {
+                if let Some(material) = self.material(name) {
+                    self.gizmo.add_mesh(mesh, billboard, skeleton, material);
+                }
+            }
+            None => self
+                .gizmo
+                .add_mesh(mesh, billboard, skeleton, Null::<Material>::null()),
+        }
+    }
+
+    /// Draws a billboard that keeps its size on screen regardless of distance.
+    #[inline]
+    pub fn add_unscaled_billboard(&self, material: &str, default_scale: f64, modulate: Color) {
+        if let Some(material) = self.material(material) {
+            self.gizmo
+                .add_unscaled_billboard(material, default_scale, modulate);
+        }
+    }
+
+    /// Adds line segments between each pair of points in `segments`, which can be clicked to
+    /// select the node.
+    #[inline]
+    pub fn add_collision_segments(&self, segments: &[Vector3]) {
+        self.gizmo
+            .add_collision_segments(PoolArray::from_slice(segments));
+    }
+
+    /// Adds triangles which can be clicked to select the node.
+    #[inline]
+    pub fn add_collision_triangles(&self, triangles: impl AsArg<TriangleMesh>) {
+        self.gizmo.add_collision_triangles(triangles);
+    }
+}
+
+/// Builder for a colored material of a gizmo plugin, corresponding to `create_material`.
+#[derive(Debug)]
+#[must_use = "MaterialBuilder left unbuilt -- did you forget to call done()?"]
+pub struct MaterialBuilder<'a> {
+    plugin: &'a EditorSpatialGizmoPlugin,
+    name: &'a str,
+    color: Color,
+    billboard: bool,
+    on_top: bool,
+    use_vertex_color: bool,
+}
+
+impl<'a> MaterialBuilder<'a> {
+    /// Creates a builder for a material called `name` on `plugin`.
+    #[inline]
+    pub fn new(plugin: &'a EditorSpatialGizmoPlugin, name: &'a str, color: Color) -> Self {
+        MaterialBuilder {
+            plugin,
+            name,
+            color,
+            billboard: false,
+            on_top: false,
+            use_vertex_color: false,
+        }
+    }
+
+    /// Makes the material always face the camera.
+    #[inline]
+    pub fn with_billboard(mut self, billboard: bool) -> Self {
+        self.billboard = billboard;
+        self
+    }
+
+    /// Makes the material render on top of other geometry.
+    #[inline]
+    pub fn with_on_top(mut self, on_top: bool) -> Self {
+        self.on_top = on_top;
+        self
+    }
+
+    /// Makes the material multiply its color with vertex colors.
+    #[inline]
+    pub fn with_vertex_color(mut self, use_vertex_color: bool) -> Self {
+        self.use_vertex_color = use_vertex_color;
+        self
+    }
+
+    /// Creates the material.
+    #[inline]
+    pub fn done(self) {
+        self.plugin.create_material(
+            self.name,
+            self.color,
+            self.billboard,
+            self.on_top,
+            self.use_vertex_color,
+        );
+    }
+}
+
+/// Builder for a handle material of a gizmo plugin, corresponding to `create_handle_material`.
+#[derive(Debug)]
+#[must_use = "HandleMaterialBuilder left unbuilt -- did you forget to call done()?"]
+pub struct HandleMaterialBuilder<'a> {
+    plugin: &'a EditorSpatialGizmoPlugin,
+    name: &'a str,
+    billboard: bool,
+    texture: Option<Ref<Texture, Shared>>,
+}
+
+impl<'a> HandleMaterialBuilder<'a> {
+    /// Creates a builder for a handle material called `name` on `plugin`.
+    #[inline]
+    pub fn new(plugin: &'a EditorSpatialGizmoPlugin, name: &'a str) -> Self {
+        HandleMaterialBuilder {
+            plugin,
+            name,
+            billboard: false,
+            texture: None,
+        }
+    }
+
+    /// Makes the handles always face the camera.
+    #[inline]
+    pub fn with_billboard(mut self, billboard: bool) -> Self {
+        self.billboard = billboard;
+        self
+    }
+
+    /// Uses a custom texture for the handles, instead of the editor's default handle icon.
+    #[inline]
+    pub fn with_texture(mut self, texture: Ref<Texture, Shared>) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    /// Creates the material.
+    #[inline]
+    pub fn done(self) {
+        match self.texture {
+            Some(texture) => self
+                .plugin
+                .create_handle_material(self.name, self.billboard, texture),
+            None => self.plugin.create_handle_material(
+                self.name,
+                self.billboard,
+                Null::<Texture>::null(),
+            ),
+        }
+    }
+}
+
+/// Registers the virtual methods of `EditorSpatialGizmoPlugin` for `C`, so they are forwarded
+/// to its [`SpatialGizmoPlugin`] implementation. Intended to be used with `#[register_with]`.
+#[inline]
+pub fn register_gizmo_plugin<C>(builder: &ClassBuilder<C>)
+where
+    C: SpatialGizmoPlugin,
+    C::UserData: Map,
+{
+    builder
+        .method(
+            "get_name",
+            Virtual::<C>::new(|c, _, _| Ok(c.name().to_variant())),
+        )
+        .done();
+
+    builder
+        .method(
+            "has_gizmo",
+            Virtual::<C>::new(|c, _, args| {
+                let spatial = object_arg::<Spatial>(&args, 0)?;
+                let spatial = unsafe { spatial.assume_safe() };
+                Ok(c.has_gizmo(spatial).to_variant())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "redraw",
+            Virtual::<C>::new(|c, plugin, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                c.redraw(&GizmoDrawer { plugin, gizmo });
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "get_handle_name",
+            Virtual::<C>::new(|c, _, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                Ok(c.handle_name(gizmo, args.get(1)?).to_variant())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "get_handle_value",
+            Virtual::<C>::new(|c, _, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                Ok(c.handle_value(gizmo, args.get(1)?))
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "set_handle",
+            Virtual::<C>::new(|c, _, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                let camera = object_arg::<Camera>(&args, 2)?;
+                let camera = unsafe { camera.assume_safe() };
+                c.set_handle(gizmo, args.get(1)?, camera, args.get(3)?);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "commit_handle",
+            Virtual::<C>::new(|c, _, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                let cancel = args.get_opt(3)?.unwrap_or(false);
+                c.commit_handle(gizmo, args.get(1)?, args.get(2)?, cancel);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "is_handle_highlighted",
+            Virtual::<C>::new(|c, _, args| {
+                let gizmo = object_arg::<EditorSpatialGizmo>(&args, 0)?;
+                let gizmo = unsafe { gizmo.assume_safe() };
+                Ok(c.is_handle_highlighted(gizmo, args.get(1)?).to_variant())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "get_priority",
+            Virtual::<C>::new(|c, _, _| Ok(c.priority().to_variant())),
+        )
+        .done();
+
+    builder
+        .method(
+            "can_be_hidden",
+            Virtual::<C>::new(|c, _, _| Ok(c.can_be_hidden().to_variant())),
+        )
+        .done();
+
+    builder
+        .method(
+            "is_selectable_when_hidden",
+            Virtual::<C>::new(|c, _, _| Ok(c.is_selectable_when_hidden().to_variant())),
+        )
+        .done();
+}
+
+type VirtualFn<C> =
+    fn(&C, TRef<'_, EditorSpatialGizmoPlugin>, Varargs<'_>) -> Result<Variant, VarargsError>;
+
+/// Method forwarding a virtual call to a [`SpatialGizmoPlugin`].
+struct Virtual<C> {
+    f: VirtualFn<C>,
+}
+
+impl<C> Virtual<C> {
+    fn new(f: VirtualFn<C>) -> Self {
+        Virtual { f }
+    }
+}
+
+impl<C> Method<C> for Virtual<C>
+where
+    C: SpatialGizmoPlugin,
+    C::UserData: Map,
+{
+    fn call(&self, this: TInstance<'_, C>, args: Varargs<'_>) -> Variant {
+        match this.map(|c, plugin| (self.f)(c, plugin, args)) {
+            Ok(Ok(ret)) => ret,
+            Ok(Err(err)) => {
+                godot_error!("gdnative: invalid arguments for gizmo plugin method: {err}");
+                Variant::nil()
+            }
+            Err(err) => {
+                godot_error!("gdnative: could not access gizmo plugin: {err}");
+                Variant::nil()
+            }
+        }
+    }
+}
+
+/// Reads an object argument passed by the editor.
+///
+/// Objects passed to virtual methods by the editor are valid for the duration of the call, and
+/// editor callbacks are made on the main thread, so they can be assumed safe within the call.
+fn object_arg<T: GodotObject>(
+    args: &Varargs<'_>,
+    index: usize,
+) -> Result<Ref<T, Shared>, VarargsError> {
+    args.get::<Ref<T, Shared>>(index)
+}
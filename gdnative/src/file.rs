@@ -0,0 +1,202 @@
+//! Chunked reading and writing of large binary files through [`File`].
+//!
+//! Reading a file with a single `get_buffer` call allocates a `PoolByteArray` of the whole
+//! file, which is then copied again into a `Vec<u8>`, and gives no opportunity to report
+//! progress. The functions in this module instead transfer the data in chunks, copying each
+//! chunk once between the engine and Rust, and call a progress callback after each chunk.
+//! Since files are accessed through Godot, `res://` and `user://` paths work as usual:
+//!
+//! ```no_run
+//! use gdnative::file;
+//! use gdnative::prelude::*;
+//!
+//! fn save_game(data: &[u8]) {
+//!     let result = file::save("user://save.bin", data, |progress| {
+//!         godot_print!("saving: {:.0}%", progress.fraction() * 100.0);
+//!     });
+//!
+//!     if let Err(err) = result {
+//!         godot_error!("failed to save: {}", err);
+//!     }
+//! }
+//! ```
+//!
+//! Files that are already open, e.g. with compression or encryption, can be used with
+//! [`read_chunks`], [`read_to_vec`] and [`write_chunks`]. Memory-mapping is not supported by
+//! Godot's file API, so the data is always copied once.
+
+use crate::api::file::ModeFlags;
+use crate::api::File;
+use crate::core_types::{GodotError, GodotResult, PoolArray};
+
+/// Chunk size used by [`load`] and [`save`], in bytes.
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// Progress of a chunked transfer, passed to the progress callbacks.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Progress {
+    /// Number of bytes transferred so far.
+    pub done: u64,
+    /// Total number of bytes to transfer.
+    pub total: u64,
+}
+
+impl Progress {
+    /// Returns the transferred fraction between `0.0` and `1.0`. Empty transfers are complete.
+    #[inline]
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f64 / self.total as f64
+        }
+    }
+
+    /// Returns `true` if all bytes have been transferred.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.done >= self.total
+    }
+}
+
+/// Reads `file` from the current position to the end in chunks of `chunk_size` bytes, and
+/// passes each chunk to `f` along with the progress after the chunk.
+///
+/// Chunks are borrowed directly from the buffers returned by the engine, without copying.
+///
+/// # Errors
+///
+/// If the file ends early or cannot be read.
+///
+/// # Panics
+///
+/// If `chunk_size` is zero.
+#[inline]
+pub fn read_chunks(
+    file: &File,
+    chunk_size: usize,
+    mut f: impl FnMut(&[u8], Progress),
+) -> GodotResult {
+    assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+    let start = file.get_position();
+    let total = (file.get_len() - start).max(0) as u64;
+    let mut progress = Progress { done: 0, total };
+
+    while !progress.is_complete() {
+        let len = (total - progress.done).min(chunk_size as u64);
+        let chunk = file.get_buffer(len as i64);
+        if chunk.len() as u64 != len {
+            return Err(file.get_error().err().unwrap_or(GodotError::FileEof));
+        }
+
+        progress.done += len;
+        f(&chunk.read(), progress);
+    }
+
+    Ok(())
+}
+
+/// Reads `file` from the current position to the end into a `Vec<u8>`, calling `progress` after
+/// each chunk of `chunk_size` bytes.
+///
+/// The `Vec` is allocated once with the final size, and each chunk is copied into it once.
+///
+/// # Errors
+///
+/// If the file ends early or cannot be read.
+///
+/// # Panics
+///
+/// If `chunk_size` is zero.
+#[inline]
+pub fn read_to_vec(
+    file: &File,
+    chunk_size: usize,
+    mut progress: impl FnMut(Progress),
+) -> Result<Vec<u8>, GodotError> {
+    let remaining = (file.get_len() - file.get_position()).max(0) as usize;
+    let mut data = Vec::with_capacity(remaining);
+
+    read_chunks(file, chunk_size, |chunk, current| {
+        data.extend_from_slice(chunk);
+        progress(current);
+    })?;
+
+    Ok(data)
+}
+
+/// Writes `data` to `file` at the current position in chunks of `chunk_size` bytes, calling
+/// `progress` after each chunk.
+///
+/// A single chunk buffer is reused for the whole transfer, so memory use is bounded by
+/// `chunk_size` regardless of the size of `data`.
+///
+/// # Errors
+///
+/// If the file cannot be written.
+///
+/// # Panics
+///
+/// If `chunk_size` is zero.
+#[inline]
+pub fn write_chunks(
+    file: &File,
+    data: &[u8],
+    chunk_size: usize,
+    mut progress: impl FnMut(Progress),
+) -> GodotResult {
+    assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+    let total = data.len() as u64;
+    let mut buffer = PoolArray::<u8>::new();
+
+    for (i, chunk) in data.chunks(chunk_size).enumerate() {
+        if buffer.len() as usize != chunk.len() {
+            buffer.resize(i32::try_from(chunk.len()).expect("chunk size should fit in i32"));
+        }
+        buffer.write().copy_from_slice(chunk);
+
+        // Passing a new reference leaves `buffer` as the only owner after the call, so the
+        // next write doesn't trigger copy-on-write.
+        file.store_buffer(buffer.clone());
+        file.get_error()?;
+
+        progress(Progress {
+            done: (i * chunk_size + chunk.len()) as u64,
+            total,
+        });
+    }
+
+    Ok(())
+}
+
+/// Opens the file at `path` and reads its contents in chunks of [`DEFAULT_CHUNK_SIZE`],
+/// calling `progress` after each chunk.
+///
+/// # Errors
+///
+/// If the file cannot be opened or read.
+#[inline]
+pub fn load(path: &str, progress: impl FnMut(Progress)) -> Result<Vec<u8>, GodotError> {
+    let file = File::new();
+    file.open(path, ModeFlags::READ.0)?;
+    let data = read_to_vec(&file, DEFAULT_CHUNK_SIZE, progress);
+    file.close();
+    data
+}
+
+/// Creates or truncates the file at `path` and writes `data` in chunks of
+/// [`DEFAULT_CHUNK_SIZE`], calling `progress` after each chunk.
+///
+/// # Errors
+///
+/// If the file cannot be opened or written.
+#[inline]
+pub fn save(path: &str, data: &[u8], progress: impl FnMut(Progress)) -> GodotResult {
+    let file = File::new();
+    file.open(path, ModeFlags::WRITE.0)?;
+    let result = write_chunks(&file, data, DEFAULT_CHUNK_SIZE, progress);
+    file.close();
+    result
+}
@@ -0,0 +1,629 @@
+//! Extraction of message keys from Rust sources, for use in build scripts.
+//!
+//! [`Catalog`] collects the keys of all [`tr!`](super::tr) invocations in a source tree, and
+//! writes them in the formats supported by Godot's translation workflow:
+//!
+//! * a gettext template (POT), from which translators create PO files for each locale, or
+//! * a CSV file with a column per locale, which is merged with an existing file so that
+//!   translations already filled in are kept.
+//!
+//! ```no_run
+//! // build.rs
+//! use gdnative::i18n::extract::Catalog;
+//!
+//! fn main() {
+//!     println!("cargo:rerun-if-changed=src");
+//!
+//!     let catalog = Catalog::scan_dir("src").expect("failed to scan sources");
+//!     catalog
+//!         .write_pot("../godot/translations/messages.pot")
+//!         .expect("failed to write POT");
+//!     catalog
+//!         .merge_csv("../godot/translations/messages.csv", &["en", "de"])
+//!         .expect("failed to write CSV");
+//! }
+//! ```
+//!
+//! Sources are scanned for `tr!(...)` invocations, including ones with a path such as
+//! `i18n::tr!(...)`, and the first string literal of each invocation is taken as the key.
+//! Invocations in comments, including doc comments, are ignored. Files are only written if
+//! their contents change, to avoid needless reimports in the editor.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Message keys collected from Rust sources.
+#[derive(Clone, Default, Debug)]
+pub struct Catalog {
+    messages: BTreeMap<String, Vec<Location>>,
+}
+
+/// Location of a message key in the sources.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Location {
+    /// Path of the source file.
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+impl Catalog {
+    /// Creates an empty catalog.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the keys from all `.rs` files in `dir` and its subdirectories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directory or file cannot be read.
+    #[inline]
+    pub fn scan_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut catalog = Self::new();
+        catalog.add_dir(dir.as_ref())?;
+        Ok(catalog)
+    }
+
+    /// Collects the keys from all `.rs` files in `dir` and its subdirectories. Files are
+    /// visited in a sorted order, so the output is stable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a directory or file cannot be read.
+    #[inline]
+    pub fn add_dir(&mut self, dir: &Path) -> io::Result<()> {
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self.add_dir(&path)?;
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                self.add_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collects the keys from the source file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    #[inline]
+    pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
+        let source = fs::read_to_string(path)?;
+        self.add_source(path, &source);
+        Ok(())
+    }
+
+    /// Collects the keys from `source`, recording `path` as their location.
+    #[inline]
+    pub fn add_source(&mut self, path: &Path, source: &str) {
+        for (key, line) in find_keys(source) {
+            self.messages.entry(key).or_default().push(Location {
+                path: path.to_owned(),
+                line,
+            });
+        }
+    }
+
+    /// Returns the collected keys in sorted order, with the locations they were found at.
+    #[inline]
+    pub fn messages(&self) -> impl Iterator<Item = (&str, &[Location])> {
+        self.messages
+            .iter()
+            .map(|(key, locations)| (key.as_str(), locations.as_slice()))
+    }
+
+    /// Returns the number of distinct keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if no keys were found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the catalog as a gettext template (POT).
+    #[inline]
+    pub fn to_pot(&self) -> String {
+        let mut pot = String::from(
+            "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+        );
+
+        for (key, locations) in &self.messages {
+            pot.push('\n');
+            for location in locations {
+                let path = location.path.to_string_lossy().replace('\\', "/");
+                writeln!(pot, "#: {}:{}", path, location.line).unwrap();
+            }
+            writeln!(pot, "msgid \"{}\"\nmsgstr \"\"", escape_po(key)).unwrap();
+        }
+
+        pot
+    }
+
+    /// Writes the catalog as a gettext template (POT) to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    #[inline]
+    pub fn write_pot(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_if_changed(path.as_ref(), &self.to_pot())
+    }
+
+    /// Merges the catalog into the translation CSV `existing`, and returns the result.
+    ///
+    /// All rows of `existing` are kept, including keys not found in the sources, since they
+    /// may be used by scenes or scripts. New keys are appended with empty translations. The
+    /// columns of `existing` are kept, and columns for `locales` that are missing are added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `existing` is not a valid translation CSV.
+    #[inline]
+    pub fn merge_csv_str(&self, existing: &str, locales: &[&str]) -> Result<String, CsvError> {
+        let mut rows = parse_csv(existing)?;
+        if rows.is_empty() {
+            rows.push(vec!["keys".to_owned()]);
+        }
+
+        let header = &mut rows[0];
+        for locale in locales {
+            if !header.iter().skip(1).any(|column| column == locale) {
+                header.push((*locale).to_owned());
+            }
+        }
+        let columns = header.len();
+
+        let known = rows
+            .iter()
+            .skip(1)
+            .filter_map(|row| row.first().cloned())
+            .collect::<HashSet<_>>();
+
+        for key in self.messages.keys() {
+            if !known.contains(key) {
+                rows.push(vec![key.clone()]);
+            }
+        }
+
+        let mut csv = String::new();
+        for row in &mut rows {
+            row.resize(columns, String::new());
+            let fields = row
+                .iter()
+                .map(|field| escape_csv(field))
+                .collect::<Vec<_>>();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        Ok(csv)
+    }
+
+    /// Merges the catalog into the translation CSV at `path`, creating it if it doesn't exist.
+    /// See [`merge_csv_str`](Self::merge_csv_str) for how the files are merged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or written, or is not a valid translation CSV.
+    #[inline]
+    pub fn merge_csv(&self, path: impl AsRef<Path>, locales: &[&str]) -> io::Result<()> {
+        let path = path.as_ref();
+        let existing = match fs::read_to_string(path) {
+            Ok(existing) => existing,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let csv = self
+            .merge_csv_str(&existing, locales)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        write_if_changed(path, &csv)
+    }
+}
+
+/// Error returned when a translation CSV cannot be parsed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CsvError {
+    /// 1-based line number of the error.
+    pub line: usize,
+}
+
+impl std::fmt::Display for CsvError {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unterminated quoted field starting on line {}",
+            self.line
+        )
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+fn write_if_changed(path: &Path, contents: &str) -> io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Token of Rust source, as far as needed to find `tr!` invocations.
+#[derive(PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Str(String),
+}
+
+/// Returns the keys of all `tr!` invocations in `source`, with their line numbers.
+fn find_keys(source: &str) -> Vec<(String, usize)> {
+    let tokens = tokenize(source);
+    let mut keys = Vec::new();
+
+    let mut i = 0;
+    while i + 2 < tokens.len() {
+        let is_invocation = tokens[i].0 == Token::Ident("tr".into())
+            && tokens[i + 1].0 == Token::Punct('!')
+            && matches!(tokens[i + 2].0, Token::Punct('(' | '[' | '{'));
+
+        if !is_invocation {
+            i += 1;
+            continue;
+        }
+
+        // The first string literal within the delimiters is the key.
+        let mut depth = 0;
+        for (token, line) in &tokens[i + 2..] {
+            match token {
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Str(key) => {
+                    keys.push((key.clone(), *line));
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        i += 3;
+    }
+
+    keys
+}
+
+/// Splits `source` into tokens with their line numbers, skipping whitespace and comments.
+/// Numbers and other literals are returned as identifiers or punctuation, which is enough to
+/// find the keys.
+fn tokenize(source: &str) -> Vec<(Token, usize)> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    let count_lines = |s: &[char]| s.iter().filter(|&&c| c == '\n').count();
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        match c {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                // Block comments nest in Rust.
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            '"' => {
+                let (value, end) = parse_str(&chars, i + 1);
+                tokens.push((Token::Str(value), line));
+                i = end;
+            }
+            'r' if raw_str_hashes(&chars, i + 1).is_some() => {
+                let hashes = raw_str_hashes(&chars, i + 1).unwrap();
+                let body = i + 2 + hashes;
+                let mut end = body;
+                while end < chars.len()
+                    && !(chars[end] == '"'
+                        && chars[end + 1..].iter().take_while(|&&c| c == '#').count() >= hashes)
+                {
+                    end += 1;
+                }
+                let value = chars[body..end.min(chars.len())].iter().collect();
+                tokens.push((Token::Str(value), line));
+                i = (end + 1 + hashes).min(chars.len());
+            }
+            '\'' => {
+                // Character literal, or lifetime which is skipped like an identifier.
+                match (chars.get(i + 1), chars.get(i + 2)) {
+                    (Some('\\'), _) => {
+                        i += 2;
+                        while i < chars.len() && chars[i] != '\'' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    (Some(_), Some('\'')) => i += 3,
+                    _ => i += 1,
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let ident = chars[start..i].iter().collect::<String>();
+
+                // Byte strings such as b"..." are not keys, but must not be tokenized as code.
+                if (ident == "b" || ident == "br") && chars.get(i) == Some(&'"') {
+                    let (_, end) = parse_str(&chars, i + 1);
+                    i = end;
+                } else {
+                    tokens.push((Token::Ident(ident), line));
+                }
+            }
+            c if c.is_whitespace() => i += 1,
+            c => {
+                tokens.push((Token::Punct(c), line));
+                i += 1;
+            }
+        }
+
+        line += count_lines(&chars[start..i.min(chars.len())]);
+    }
+
+    tokens
+}
+
+/// Returns the number of `#` if a raw string literal starts at `i`, after the `r`.
+fn raw_str_hashes(chars: &[char], i: usize) -> Option<usize> {
+    let hashes = chars[i.min(chars.len())..]
+        .iter()
+        .take_while(|&&c| c == '#')
+        .count();
+    (chars.get(i + hashes) == Some(&'"')).then_some(hashes)
+}
+
+/// Parses the body of a string literal starting at `i`, after the opening quote. Returns the
+/// unescaped value and the index after the closing quote.
+fn parse_str(chars: &[char], mut i: usize) -> (String, usize) {
+    let mut value = String::new();
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return (value, i + 1),
+            '\\' => {
+                i += 1;
+                match chars.get(i) {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('0') => value.push('\0'),
+                    Some('\n') => {
+                        // Line continuation: skip the newline and leading whitespace.
+                        while chars.get(i + 1).is_some_and(|c| c.is_whitespace()) {
+                            i += 1;
+                        }
+                    }
+                    Some('u') => {
+                        let end = chars[i..].iter().position(|&c| c == '}').map(|p| i + p);
+                        if let Some(end) = end {
+                            let hex = chars[i + 2..end].iter().collect::<String>();
+                            if let Some(c) =
+                                u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                            {
+                                value.push(c);
+                            }
+                            i = end;
+                        }
+                    }
+                    Some('x') => {
+                        let hex = chars[i + 1..(i + 3).min(chars.len())]
+                            .iter()
+                            .collect::<String>();
+                        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                            value.push(char::from(byte));
+                        }
+                        i += 2;
+                    }
+                    Some(&c) => value.push(c),
+                    None => {}
+                }
+                i += 1;
+            }
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    (value, i)
+}
+
+fn escape_po(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Parses CSV as written by Godot and spreadsheet applications: fields are separated by commas,
+/// and may be quoted to contain commas, newlines and doubled quotes.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = csv.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(CsvError { line: start }),
+                    }
+                }
+            }
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // Skip blank lines, which Godot ignores as well.
+    rows.retain(|row| !(row.len() == 1 && row[0].is_empty()));
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_keys() {
+        let source = r##"
+            fn f(base: &Node) {
+                label.set_text(tr!("PLAIN"));
+                godot_print!("{}", i18n::tr!(base, "WITH_OBJECT"));
+                let _ = tr! {
+                    "MULTI\nLINE"
+                };
+                let _ = tr!(r#"RAW "QUOTED""#);
+                let _ = ('"', b"tr!(\"BYTES\")", 'a);
+                // tr!("LINE_COMMENT")
+                /* tr!("BLOCK /* nested */ COMMENT") */
+                /// tr!("DOC_COMMENT")
+                let _ = not_tr!("OTHER");
+            }
+        "##;
+
+        let keys = find_keys(source);
+        assert_eq!(
+            vec![
+                ("PLAIN".to_owned(), 3),
+                ("WITH_OBJECT".to_owned(), 4),
+                ("MULTI\nLINE".to_owned(), 6),
+                ("RAW \"QUOTED\"".to_owned(), 8),
+            ],
+            keys,
+        );
+    }
+
+    #[test]
+    fn writes_pot() {
+        let mut catalog = Catalog::new();
+        catalog.add_source(
+            Path::new("src/a.rs"),
+            "tr!(\"B\"); tr!(\"A\");\ntr!(\"B\");",
+        );
+
+        let expected = "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\
+            \n#: src/a.rs:1\nmsgid \"A\"\nmsgstr \"\"\n\
+            \n#: src/a.rs:1\n#: src/a.rs:2\nmsgid \"B\"\nmsgstr \"\"\n";
+        assert_eq!(expected, catalog.to_pot());
+    }
+
+    #[test]
+    fn merges_csv() {
+        let mut catalog = Catalog::new();
+        catalog.add_source(Path::new("a.rs"), "tr!(\"NEW, KEY\"); tr!(\"HELLO\");");
+
+        assert_eq!(
+            "keys,en\nHELLO,\n\"NEW, KEY\",\n",
+            catalog.merge_csv_str("", &["en"]).unwrap(),
+        );
+
+        let existing = "keys,en,de\nHELLO,Hello,Hallo\nUNUSED,\"a \"\"b\"\"\nc\",\n";
+        assert_eq!(
+            "keys,en,de,fr\nHELLO,Hello,Hallo,\nUNUSED,\"a \"\"b\"\"\nc\",,\n\"NEW, KEY\",,,\n",
+            catalog.merge_csv_str(existing, &["en", "fr"]).unwrap(),
+        );
+
+        assert_eq!(
+            Err(CsvError { line: 2 }),
+            catalog.merge_csv_str("keys,en\nHELLO,\"Hello\n", &["en"]),
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Localization of messages through Godot's translation system.
+//!
+//! The [`tr!`] macro is the Rust counterpart of GDScript's `tr()`. It looks up the translation
+//! of a message key for the current locale, and caches the result so repeated lookups in
+//! `_process` or UI code don't cross the FFI boundary each time:
+//!
+//! ```no_run
+//! use gdnative::api::Label;
+//! use gdnative::i18n::tr;
+//!
+//! fn update_label(label: &Label) {
+//!     label.set_text(tr!("MENU_START_GAME"));
+//! }
+//! ```
+//!
+//! When given an object, the object's [`can_translate_messages`][Object::can_translate_messages]
+//! setting is respected, exactly like [`Object::tr`]:
+//!
+//! ```no_run
+//! use gdnative::prelude::*;
+//! use gdnative::i18n::tr;
+//!
+//! fn greet(base: &Node) {
+//!     godot_print!("{}", tr!(base, "GREETING"));
+//! }
+//! ```
+//!
+//! Keys must be string literals, so they can be collected from the sources by the
+//! [`extract`] module and turned into the POT or CSV files Godot imports as translations.
+//!
+//! The cache is invalidated when the locale changes. Translations added or removed while the
+//! locale stays the same are only picked up after calling [`clear_cache`].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::api::{Object, TranslationServer};
+use crate::core_types::GodotString;
+use crate::object::SubClass;
+
+pub mod extract;
+
+/// Translates a message key for the current locale.
+///
+/// `tr!("KEY")` is equivalent to `TranslationServer::translate("KEY")`, and `tr!(object, "KEY")`
+/// to `object.tr("KEY")`, where `object` is a reference to any Godot object, such as the base of
+/// a `NativeClass`. Both forms cache translations. See the [module documentation](self) for
+/// details.
+#[doc(inline)]
+pub use crate::__tr as tr;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tr {
+    ($key:literal $(,)?) => {
+        $crate::i18n::translate($key)
+    };
+    ($object:expr, $key:literal $(,)?) => {
+        $crate::i18n::translate_for(&*$object, $key)
+    };
+}
+
+thread_local! {
+    static CACHE: RefCell<Cache> = RefCell::default();
+}
+
+#[derive(Default)]
+struct Cache {
+    locale: Option<GodotString>,
+    messages: HashMap<&'static str, GodotString>,
+}
+
+/// Translates `key` for the current locale, using cached translations where possible.
+///
+/// This is the function behind [`tr!`]. Returns `key` itself if there is no translation.
+#[inline]
+pub fn translate(key: &'static str) -> GodotString {
+    let server = TranslationServer::godot_singleton();
+    let locale = server.get_locale();
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+
+        if cache.locale.as_ref() != Some(&locale) {
+            cache.messages.clear();
+            cache.locale = Some(locale);
+        }
+
+        cache
+            .messages
+            .entry(key)
+            .or_insert_with(|| server.translate(key))
+            .clone()
+    })
+}
+
+/// Translates `key` for the current locale, if `object` has message translation enabled, and
+/// returns `key` itself otherwise. Like [`Object::tr`], but cached.
+///
+/// This is the function behind the two-argument form of [`tr!`].
+#[inline]
+pub fn translate_for<T>(object: &T, key: &'static str) -> GodotString
+where
+    T: SubClass<Object>,
+{
+    if object.upcast::<Object>().can_translate_messages() {
+        translate(key)
+    } else {
+        GodotString::from_str(key)
+    }
+}
+
+/// Clears the translations cached on the current thread.
+///
+/// Needed after adding or removing translations through [`TranslationServer`], if the locale
+/// remains the same.
+#[inline]
+pub fn clear_cache() {
+    CACHE.with(|cache| *cache.borrow_mut() = Cache::default());
+}
@@ -0,0 +1,348 @@
+//! Typed access to connected gamepads.
+//!
+//! [`Input`] identifies gamepads by integer device IDs, and reports their properties through
+//! separate methods. [`Gamepad`] bundles these methods for one device, and [`Gamepads`] keeps
+//! a directory of the connected devices, reporting connection changes as [`GamepadEvent`]s.
+//!
+//! The directory can be kept up to date by forwarding the `joy_connection_changed` signal of
+//! `Input`, which is emitted when a device is connected or disconnected:
+//!
+//! ```no_run
+//! use gdnative::api::Input;
+//! use gdnative::input::{GamepadEvent, Gamepads};
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(Node)]
+//! struct ControllerMenu {
+//!     gamepads: Gamepads,
+//! }
+//!
+//! #[methods]
+//! impl ControllerMenu {
+//!     fn new(_base: &Node) -> Self {
+//!         let mut gamepads = Gamepads::new();
+//!         gamepads.on_change(|event| match event {
+//!             GamepadEvent::Connected(pad) => godot_print!("connected: {}", pad.name()),
+//!             GamepadEvent::Disconnected(pad) => godot_print!("disconnected: {}", pad.id()),
+//!             _ => {}
+//!         });
+//!         ControllerMenu { gamepads }
+//!     }
+//!
+//!     #[method]
+//!     fn _ready(&self, #[base] base: TRef<Node>) {
+//!         Input::godot_singleton()
+//!             .connect(
+//!                 "joy_connection_changed",
+//!                 base,
+//!                 "on_joy_connection_changed",
+//!                 VariantArray::new_shared(),
+//!                 0,
+//!             )
+//!             .unwrap();
+//!     }
+//!
+//!     #[method]
+//!     fn on_joy_connection_changed(&mut self, device: i64, connected: bool) {
+//!         self.gamepads.handle_connection_changed(device, connected);
+//!     }
+//! }
+//! ```
+//!
+//! Alternatively, [`Gamepads::refresh`] compares the directory with the devices connected
+//! right now. In async methods, the signal can also be awaited with `Context::signal`, and its
+//! arguments converted with [`GamepadEvent::from_signal_args`].
+//!
+//! [`Input`]: crate::api::Input
+
+use std::fmt;
+
+use crate::api::Input;
+use crate::core_types::{FromVariant, Variant, Vector2};
+
+/// A gamepad, identified by its device ID.
+///
+/// The methods of `Gamepad` query [`Input`](crate::api::Input) each time they are called. IDs
+/// of disconnected devices may be reused for devices connected later.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Gamepad {
+    id: i64,
+}
+
+impl Gamepad {
+    /// Refers to the gamepad with the device ID `id`. The device doesn't need to be connected.
+    #[inline]
+    pub const fn from_id(id: i64) -> Self {
+        Gamepad { id }
+    }
+
+    /// Returns the device ID.
+    #[inline]
+    pub const fn id(self) -> i64 {
+        self.id
+    }
+
+    /// Returns `true` if the device is currently connected.
+    #[inline]
+    pub fn is_connected(self) -> bool {
+        connected_ids().contains(&self.id)
+    }
+
+    /// Returns the name of the device, e.g. `"XInput Gamepad"`.
+    #[inline]
+    pub fn name(self) -> String {
+        Input::godot_singleton().get_joy_name(self.id).to_string()
+    }
+
+    /// Returns the SDL-compatible GUID of the device, which identifies its mapping.
+    #[inline]
+    pub fn guid(self) -> String {
+        Input::godot_singleton().get_joy_guid(self.id).to_string()
+    }
+
+    /// Returns `true` if a button and axis mapping is known for the device. Unknown devices
+    /// report raw button and axis indices.
+    #[inline]
+    pub fn is_known(self) -> bool {
+        Input::godot_singleton().is_joy_known(self.id)
+    }
+
+    /// Removes the mapping of the device, if any. This affects all devices with the same GUID.
+    #[inline]
+    pub fn remove_mapping(self) {
+        let input = Input::godot_singleton();
+        input.remove_joy_mapping(input.get_joy_guid(self.id));
+    }
+
+    /// Returns `true` if the button with the index `button` is pressed, e.g.
+    /// `GlobalConstants::JOY_BUTTON_0`.
+    #[inline]
+    pub fn is_button_pressed(self, button: i64) -> bool {
+        Input::godot_singleton().is_joy_button_pressed(self.id, button)
+    }
+
+    /// Returns the value of the axis with the index `axis`, e.g. `GlobalConstants::JOY_AXIS_0`.
+    #[inline]
+    pub fn axis(self, axis: i64) -> f64 {
+        Input::godot_singleton().get_joy_axis(self.id, axis)
+    }
+
+    /// Starts vibrating the device. Magnitudes are between 0 and 1. A `duration` of 0 vibrates
+    /// until [`stop_vibration`][Self::stop_vibration] is called.
+    ///
+    /// Devices that don't support vibration ignore this.
+    #[inline]
+    pub fn vibrate(self, weak_magnitude: f64, strong_magnitude: f64, duration: f64) {
+        Input::godot_singleton().start_joy_vibration(
+            self.id,
+            weak_magnitude,
+            strong_magnitude,
+            duration,
+        );
+    }
+
+    /// Stops vibrating the device.
+    #[inline]
+    pub fn stop_vibration(self) {
+        Input::godot_singleton().stop_joy_vibration(self.id);
+    }
+
+    /// Returns the magnitudes of the current vibration, weak in `x` and strong in `y`.
+    #[inline]
+    pub fn vibration_strength(self) -> Vector2 {
+        Input::godot_singleton().get_joy_vibration_strength(self.id)
+    }
+
+    /// Returns the duration of the current vibration in seconds.
+    #[inline]
+    pub fn vibration_duration(self) -> f64 {
+        Input::godot_singleton().get_joy_vibration_duration(self.id)
+    }
+}
+
+/// A change in the set of connected gamepads.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum GamepadEvent {
+    /// The gamepad was connected.
+    Connected(Gamepad),
+    /// The gamepad was disconnected.
+    Disconnected(Gamepad),
+}
+
+impl GamepadEvent {
+    /// Creates an event from the arguments of the `joy_connection_changed` signal.
+    #[inline]
+    pub fn new(device: i64, connected: bool) -> Self {
+        let gamepad = Gamepad::from_id(device);
+        if connected {
+            GamepadEvent::Connected(gamepad)
+        } else {
+            GamepadEvent::Disconnected(gamepad)
+        }
+    }
+
+    /// Creates an event from the arguments of the `joy_connection_changed` signal, as variants.
+    /// Returns `None` if the arguments have the wrong types.
+    #[inline]
+    pub fn from_signal_args(args: &[Variant]) -> Option<Self> {
+        match args {
+            [device, connected, ..] => Some(Self::new(
+                i64::from_variant(device).ok()?,
+                bool::from_variant(connected).ok()?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns the gamepad that was connected or disconnected.
+    #[inline]
+    pub fn gamepad(&self) -> Gamepad {
+        match *self {
+            GamepadEvent::Connected(gamepad) | GamepadEvent::Disconnected(gamepad) => gamepad,
+        }
+    }
+}
+
+/// Directory of connected gamepads.
+///
+/// The directory is a snapshot that is updated by [`refresh`][Self::refresh] or
+/// [`handle_connection_changed`][Self::handle_connection_changed]. Both report changes to the
+/// callbacks registered with [`on_change`][Self::on_change].
+#[derive(Default)]
+pub struct Gamepads {
+    devices: Vec<Gamepad>,
+    callbacks: Vec<Box<dyn FnMut(GamepadEvent)>>,
+}
+
+impl Gamepads {
+    /// Creates a directory of the gamepads connected right now.
+    #[inline]
+    pub fn new() -> Self {
+        Gamepads {
+            devices: connected_ids().into_iter().map(Gamepad::from_id).collect(),
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Returns the gamepads in the directory, ordered by ID.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Gamepad> + '_ {
+        self.devices.iter().copied()
+    }
+
+    /// Returns the number of gamepads in the directory.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Returns `true` if the directory is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Returns `true` if `gamepad` is in the directory.
+    #[inline]
+    pub fn contains(&self, gamepad: Gamepad) -> bool {
+        self.devices.binary_search(&gamepad).is_ok()
+    }
+
+    /// Registers a callback that is invoked for each change of the directory.
+    #[inline]
+    pub fn on_change(&mut self, callback: impl FnMut(GamepadEvent) + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Updates the directory with the gamepads connected right now. Returns the changes, after
+    /// invoking the callbacks for them.
+    ///
+    /// Disconnections are reported before connections.
+    #[inline]
+    pub fn refresh(&mut self) -> Vec<GamepadEvent> {
+        let current = connected_ids();
+
+        let mut events = Vec::new();
+        for &gamepad in &self.devices {
+            if current.binary_search(&gamepad.id).is_err() {
+                events.push(GamepadEvent::Disconnected(gamepad));
+            }
+        }
+        for &id in &current {
+            let gamepad = Gamepad::from_id(id);
+            if !self.contains(gamepad) {
+                events.push(GamepadEvent::Connected(gamepad));
+            }
+        }
+
+        for &event in &events {
+            self.apply(event);
+        }
+        events
+    }
+
+    /// Updates the directory with the arguments of the `joy_connection_changed` signal.
+    /// Returns the change, after invoking the callbacks for it, or `None` if the directory
+    /// was already up to date.
+    #[inline]
+    pub fn handle_connection_changed(
+        &mut self,
+        device: i64,
+        connected: bool,
+    ) -> Option<GamepadEvent> {
+        let event = GamepadEvent::new(device, connected);
+        if self.contains(event.gamepad()) == connected {
+            return None;
+        }
+
+        self.apply(event);
+        Some(event)
+    }
+
+    /// Adds a mapping in the SDL2 game controller format, which is used for all devices with
+    /// the GUID given in the mapping. If `update_existing` is `true`, connected devices with that
+    /// GUID are updated as well.
+    #[inline]
+    pub fn add_mapping(mapping: &str, update_existing: bool) {
+        Input::godot_singleton().add_joy_mapping(mapping, update_existing);
+    }
+
+    fn apply(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(gamepad) => {
+                if let Err(index) = self.devices.binary_search(&gamepad) {
+                    self.devices.insert(index, gamepad);
+                }
+            }
+            GamepadEvent::Disconnected(gamepad) => {
+                if let Ok(index) = self.devices.binary_search(&gamepad) {
+                    self.devices.remove(index);
+                }
+            }
+        }
+
+        for callback in &mut self.callbacks {
+            callback(event);
+        }
+    }
+}
+
+impl fmt::Debug for Gamepads {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(&self.devices).finish()
+    }
+}
+
+/// Returns the IDs of the connected devices, sorted.
+fn connected_ids() -> Vec<i64> {
+    let mut ids = Input::godot_singleton()
+        .get_connected_joypads()
+        .iter()
+        .filter_map(|id| i64::from_variant(&id).ok())
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids
+}
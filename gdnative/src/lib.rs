@@ -46,17 +46,60 @@
 //! Functionality toggles:
 //!
 //! * **`async`**<br>
-//!   Activates async functionality, see [`tasks`] module for details.
+//!   Activates async functionality, see [`tasks`] module for details. On platforms without thread
+//!   support, such as HTML5, [`tasks::LocalExecutor`] can be used as the global executor.
 //!
 //! * **`serde`**<br>
-//!   Enable for `serde` support of several core types. See also [`Variant`](core_types::Variant).
+//!   Enable for `serde` support of several core types, as well as conversions between variants and
+//!   `serde_json::Value`. See also [`Variant`](core_types::Variant).
+//!
+//! * **`rand`**<br>
+//!   Implements the `RngCore` and `SeedableRng` traits of [`rand_core`](https://docs.rs/rand_core)
+//!   for [`rand::GodotRng`], so engine-backed random numbers can be used with the `rand` crate.
+//!
+//! * **`log`**<br>
+//!   Enables `log::GodotLogger`, an adapter that outputs records from the [`log`](https://docs.rs/log)
+//!   crate to the Godot console, and a structured logger with per-category levels, JSON output
+//!   and an editor panel. See the `logging` module for the project settings and the panel.
+//!
+//! * **`console`**<br>
+//!   Enables the [`console`] module, a developer console for commands written in Rust, with typed
+//!   arguments, history, completion and an in-game UI.
+//!
+//! * **`alloc-tracking`**<br>
+//!   Counts allocations of `Variant`s, strings and collections made from Rust, along with their
+//!   call sites. See [`profiler::alloc`](profiler) for details. This adds overhead to core type
+//!   conversions, so it's intended for diagnostics only.
+//!
+//! * **`c-abi`**<br>
+//!   Exports `extern "C"` functions to list the registered classes, create instances and call
+//!   their methods with variants, so other language runtimes in the same process, or native test
+//!   harnesses, can drive Rust classes directly. See `export::c_abi` for the interface.
+//!
+//! * **`no-engine`**<br>
+//!   Makes `GodotString`, `Variant`, `VariantArray`, `Dictionary`, `NodePath` and the other core
+//!   types usable without a running engine, through pure Rust implementations of the engine
+//!   functions behind them. This allows testing game logic with plain `cargo test`, so it's
+//!   typically enabled only in `[dev-dependencies]`. Calls to anything else, such as objects or
+//!   pool arrays, abort the process. Once the library is loaded by Godot, the engine's
+//!   implementations are used as usual.
+//!
+//! * **`strip-tools`**<br>
+//!   Leaves out everything that is only used in the editor, to reduce the size of release exports:
+//!   the bindings of editor classes such as `EditorPlugin` (along with the methods of other classes
+//!   that use them), the [`editor`] module, and the instrumentation of [`profiler`] and
+//!   `#[profiled]`, which become no-ops. The classes and methods that were left out are listed in
+//!   `stripped.txt` in the output directory of `gdnative-bindings`. Set the `GDNATIVE_STRIP_REPORT`
+//!   environment variable to a path to write the list there as well. Intended to be enabled only
+//!   for release exports, e.g. with `cargo build --release --features gdnative/strip-tools`.
 //!
 //! * **`inventory`**<br>
 //!   Enables automatic class registration via `inventory`.
 //!
 //!   **Attention:** Automatic registration is unsupported on some platforms, notably WASM. `inventory`
 //!   can still be used for iterative development if such platforms are targeted, in which case the
-//!   run-time diagnostic [`init::diagnostics::missing_manual_registration`] may be helpful.
+//!   run-time diagnostic [`init::diagnostics::missing_manual_registration`] may be helpful. When
+//!   targeting HTML5, a warning is also emitted at compile time.
 //!
 //!   Please refer to [the `rust-ctor` README][ctor-repo] for an up-to-date listing of platforms
 //!   that *do* support automatic registration.
@@ -89,6 +132,25 @@
 //!   Cargo features are additive, and as such, it's only necessary to enable this feature for the final
 //!   `cdylib` crates, whenever desired.
 //!
+//! ## Platform-specific code
+//!
+//! The [`cfg_ex`] and [`cfg_attr_ex`] attributes extend `#[cfg]` and `#[cfg_attr]` with options
+//! for platforms that need special treatment, such as `gdnative::html5` for HTML5 exports. Crates
+//! that support multiple platforms can use them to adapt to the target:
+//!
+//! ```
+//! #[gdnative::cfg_ex(gdnative::html5)]
+//! fn max_workers() -> usize {
+//!     0
+//! }
+//!
+//! #[gdnative::cfg_ex(not(gdnative::html5))]
+//! fn max_workers() -> usize {
+//!     4
+//! }
+//! # assert_eq!(4, max_workers());
+//! ```
+//!
 //! [thread-safety]: https://docs.godotengine.org/en/stable/tutorials/threads/thread_safe_apis.html
 //! [gdnative-overview]: https://godot-rust.github.io/book/gdnative-overview.html
 //! [custom-godot]: https://godot-rust.github.io/book/advanced-guides/custom-godot.html
@@ -103,11 +165,33 @@
 // their hidden status. Re-exporting them manually and hiding the wildcard solves this.
 #[doc(inline)]
 pub use gdnative_core::{
-    core_types, derive, export, godot_dbg, godot_error, godot_print, godot_site, init, log, object,
-    profiler,
+    cfg_attr_ex, cfg_ex, core_types, derive, dict, export, export_flags, godot_dbg, godot_error,
+    godot_print, godot_site, init, log, object, profiler, services, varray, worker,
 };
 
+pub mod animation;
+pub mod bus;
+#[cfg(feature = "console")]
+pub mod console;
+pub mod diagnostics;
+pub mod draw;
+pub mod easing;
+#[cfg(not(feature = "strip-tools"))]
+pub mod editor;
+pub mod file;
 pub mod globalscope;
+pub mod i18n;
+pub mod input;
+#[cfg(feature = "log")]
+pub mod logging;
+pub mod main_loop;
+pub mod nav;
+pub mod net;
+pub mod physics;
+pub mod platform;
+pub mod rand;
+pub mod scene;
+pub mod settings;
 
 // Implementation details (e.g. used by macros).
 // However, do not re-export macros (on crate level), thus no wildcard
@@ -0,0 +1,356 @@
+//! Project settings and an editor panel for the structured logger of
+//! [`GodotLogger::builder`](crate::log::GodotLogger::builder).
+//!
+//! The logger can be configured from the project settings dialog, by registering the settings
+//! in this module and building the logger from them, usually in the init function of the
+//! library:
+//!
+//! ```no_run
+//! use gdnative::logging;
+//! use gdnative::prelude::*;
+//!
+//! fn init(_handle: InitHandle) {
+//!     logging::register_settings();
+//!     logging::builder_from_settings()
+//!         .install()
+//!         .expect("no other logger is installed");
+//!
+//!     // register classes...
+//! }
+//! ```
+//!
+//! [`LogPanel`] shows the records of the logger, filtered by level and text, e.g. in the bottom
+//! panel of the editor.
+//!
+//! This module is only available with the `log` feature.
+
+use std::collections::VecDeque;
+
+use ::log::{Level, LevelFilter};
+
+use crate::api::control::SizeFlags;
+use crate::api::{
+    Control, HBoxContainer, LineEdit, Node, OptionButton, ProjectSettings, RichTextLabel,
+    VBoxContainer,
+};
+use crate::core_types::{Color, Vector2};
+use crate::export::hint::StringHint;
+use crate::log::{self, godot_warn, LogRecord, LoggerBuilder};
+use crate::object::ownership::Shared;
+use crate::object::{Ref, TRef};
+use crate::settings::{self, CustomSetting};
+
+/// Setting with the levels of the logger, in the format of [`LoggerBuilder::filters`], e.g.
+/// `"warn, my_game::net=debug"`.
+pub const FILTERS_SETTING: &str = "logging/rust/filters";
+
+/// Setting with the path of the JSON file that records are appended to. Records are not
+/// written to a file if the path is empty.
+pub const JSON_FILE_SETTING: &str = "logging/rust/json_file";
+
+/// Registers [`FILTERS_SETTING`] and [`JSON_FILE_SETTING`], so they show up in the project
+/// settings dialog.
+#[inline]
+pub fn register_settings() {
+    CustomSetting::new(FILTERS_SETTING, String::from("info"))
+        .with_hint(StringHint::placeholder("warn, my_game::net=debug"))
+        .register();
+    CustomSetting::new(JSON_FILE_SETTING, String::new())
+        .with_hint(StringHint::placeholder("user://logs/rust.jsonl"))
+        .register();
+}
+
+/// Returns a logger builder configured from the project settings. Invalid filters are reported
+/// as warnings and ignored.
+#[inline]
+pub fn builder_from_settings() -> LoggerBuilder {
+    let builder = LoggerBuilder::new();
+
+    let builder = match settings::setting::<String>(FILTERS_SETTING) {
+        Ok(spec) => builder.filters(&spec).unwrap_or_else(|err| {
+            godot_warn!("gdnative::logging: {FILTERS_SETTING}: {err}");
+            LoggerBuilder::new()
+        }),
+        Err(_) => builder,
+    };
+
+    match settings::setting::<String>(JSON_FILE_SETTING) {
+        Ok(path) if !path.is_empty() => {
+            let path = ProjectSettings::godot_singleton().globalize_path(path);
+            builder.json_file(path.to_string())
+        }
+        _ => builder,
+    }
+}
+
+/// Applies the levels in [`FILTERS_SETTING`] to the installed logger, e.g. from a
+/// [`SettingsWatcher`](crate::settings::SettingsWatcher) callback. Returns `false` if the logger
+/// isn't installed, or if the filters are invalid, in which case a warning is reported.
+///
+/// The JSON file can't be changed once the logger is installed.
+#[inline]
+pub fn apply_settings() -> bool {
+    let spec = settings::setting::<String>(FILTERS_SETTING).unwrap_or_default();
+    log::set_filters(&spec).unwrap_or_else(|err| {
+        godot_warn!("gdnative::logging: {FILTERS_SETTING}: {err}");
+        false
+    })
+}
+
+/// Levels in the order of the items in the level selector of [`LogPanel`].
+const LEVELS: [(&str, LevelFilter); 5] = [
+    ("Error", LevelFilter::Error),
+    ("Warn", LevelFilter::Warn),
+    ("Info", LevelFilter::Info),
+    ("Debug", LevelFilter::Debug),
+    ("Trace", LevelFilter::Trace),
+];
+
+/// Panel that shows the records of the structured logger, with a level selector and a text
+/// filter.
+///
+/// The panel doesn't update by itself: [`poll`](Self::poll) must be called regularly, e.g. from
+/// `_process`, to show new records. For example, an editor plugin can add the panel to the
+/// bottom panel of the editor:
+///
+/// ```no_run
+/// use gdnative::api::EditorPlugin;
+/// use gdnative::logging::LogPanel;
+/// use gdnative::prelude::*;
+///
+/// #[derive(NativeClass)]
+/// #[inherit(EditorPlugin)]
+/// #[no_constructor]
+/// struct LogPlugin {
+///     panel: Option<LogPanel>,
+/// }
+///
+/// #[methods]
+/// impl LogPlugin {
+///     #[method]
+///     fn _enter_tree(&mut self, #[base] base: &EditorPlugin) {
+///         let panel = LogPanel::new();
+///         base.add_control_to_bottom_panel(panel.control(), "Rust Log");
+///         self.panel = Some(panel);
+///     }
+///
+///     #[method]
+///     fn _exit_tree(&mut self, #[base] base: &EditorPlugin) {
+///         if let Some(panel) = self.panel.take() {
+///             base.remove_control_from_bottom_panel(panel.control());
+///             panel.free();
+///         }
+///     }
+///
+///     #[method]
+///     fn _process(&mut self, _delta: f64) {
+///         if let Some(panel) = &mut self.panel {
+///             panel.poll();
+///         }
+///     }
+/// }
+/// ```
+///
+/// Only records logged by the library that the panel is created in are shown, and only those
+/// still in the history of the logger (see [`LoggerBuilder::history`]).
+#[derive(Debug)]
+pub struct LogPanel {
+    root: Ref<VBoxContainer, Shared>,
+    level: Ref<OptionButton, Shared>,
+    search: Ref<LineEdit, Shared>,
+    output: Ref<RichTextLabel, Shared>,
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    next_sequence: u64,
+    shown_filter: Option<(i64, String)>,
+}
+
+impl LogPanel {
+    /// Creates the panel nodes. The root control isn't added to the tree, see
+    /// [`control`](Self::control) and [`attach`](Self::attach).
+    #[inline]
+    pub fn new() -> Self {
+        let root = VBoxContainer::new();
+        root.set_name("RustLog");
+        root.set_custom_minimum_size(Vector2::new(0.0, 200.0));
+
+        let toolbar = HBoxContainer::new();
+
+        let level = OptionButton::new();
+        for (id, (name, _)) in LEVELS.iter().enumerate() {
+            level.add_item(*name, id as i64);
+        }
+        level.select(LEVELS.len() as i64 - 1);
+        let level = level.into_shared();
+
+        let search = LineEdit::new();
+        search.set_placeholder("Filter by category or message");
+        search.set_h_size_flags(SizeFlags::EXPAND_FILL.0);
+        search.set_clear_button_enabled(true);
+        let search = search.into_shared();
+
+        let output = RichTextLabel::new();
+        output.set_v_size_flags(SizeFlags::EXPAND_FILL.0);
+        output.set_scroll_follow(true);
+        output.set_selection_enabled(true);
+        let output = output.into_shared();
+
+        toolbar.add_child(&level, false);
+        toolbar.add_child(&search, false);
+        root.add_child(toolbar, false);
+        root.add_child(&output, false);
+
+        LogPanel {
+            root: root.into_shared(),
+            level,
+            search,
+            output,
+            records: VecDeque::new(),
+            capacity: 1000,
+            next_sequence: 0,
+            shown_filter: None,
+        }
+    }
+
+    /// Creates the panel nodes as a child of `parent`.
+    #[inline]
+    pub fn attach(parent: &Node) -> Self {
+        let panel = Self::new();
+        parent.add_child(&panel.root, false);
+        panel
+    }
+
+    /// Sets how many records the panel keeps for filtering. Defaults to 1000.
+    #[inline]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the root control of the panel.
+    #[inline]
+    pub fn control(&self) -> Ref<Control, Shared> {
+        self.root.clone().upcast()
+    }
+
+    /// Shows the records logged since the last call, and applies changes to the filters.
+    /// Returns the number of new records.
+    #[inline]
+    pub fn poll(&mut self) -> usize {
+        let Some(filter) = self.nodes().map(|(level, search, _)| {
+            (
+                level.get_selected_id(),
+                search.text().to_string().to_lowercase(),
+            )
+        }) else {
+            return 0;
+        };
+
+        // Not borrowed from `self`, since the records are modified while showing them
+        let output = self.output.clone();
+        let Some(output) = (unsafe { output.assume_safe_if_sane() }) else {
+            return 0;
+        };
+
+        let new_records = log::records_since(self.next_sequence);
+        if let Some(last) = new_records.last() {
+            self.next_sequence = last.sequence() + 1;
+        }
+
+        let redraw = self.shown_filter.as_ref() != Some(&filter);
+        let max_level = usize::try_from(filter.0)
+            .ok()
+            .and_then(|id| LEVELS.get(id))
+            .map_or(LevelFilter::Trace, |(_, level)| *level);
+        let is_shown = |record: &LogRecord| {
+            record.level() <= max_level
+                && (filter.1.is_empty()
+                    || record.category().to_lowercase().contains(&filter.1)
+                    || record.message().to_lowercase().contains(&filter.1))
+        };
+
+        if redraw {
+            output.clear();
+            for record in self.records.iter().filter(|record| is_shown(record)) {
+                Self::show(output, record);
+            }
+        }
+
+        let count = new_records.len();
+        for record in new_records {
+            if is_shown(&record) {
+                Self::show(output, &record);
+            }
+            self.records.push_back(record);
+        }
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+        }
+
+        self.shown_filter = Some(filter);
+        count
+    }
+
+    /// Removes all records from the panel. Records logged afterwards are still shown.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.records.clear();
+        if let Some((_, _, output)) = self.nodes() {
+            output.clear();
+        }
+    }
+
+    /// Frees the panel nodes, unless they were already freed along with their parent.
+    #[inline]
+    pub fn free(self) {
+        if let Some(root) = unsafe { self.root.assume_safe_if_sane() } {
+            root.queue_free();
+        }
+    }
+
+    fn show(output: TRef<'_, RichTextLabel>, record: &LogRecord) {
+        let color = match record.level() {
+            Level::Error => Some(Color::from_rgb(1.0, 0.4, 0.4)),
+            Level::Warn => Some(Color::from_rgb(1.0, 0.8, 0.3)),
+            Level::Info => None,
+            Level::Debug | Level::Trace => Some(Color::from_rgb(0.6, 0.6, 0.6)),
+        };
+
+        if let Some(color) = color {
+            output.push_color(color);
+        }
+        output.add_text(format!(
+            "[{}] {}: {}",
+            record.level(),
+            record.category(),
+            record.message()
+        ));
+        if color.is_some() {
+            output.pop();
+        }
+        output.newline();
+    }
+
+    fn nodes(
+        &self,
+    ) -> Option<(
+        TRef<'_, OptionButton>,
+        TRef<'_, LineEdit>,
+        TRef<'_, RichTextLabel>,
+    )> {
+        unsafe {
+            Some((
+                self.level.assume_safe_if_sane()?,
+                self.search.assume_safe_if_sane()?,
+                self.output.assume_safe_if_sane()?,
+            ))
+        }
+    }
+}
+
+impl Default for LogPanel {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,230 @@
+//! Custom main loops written in Rust.
+//!
+//! Godot drives the game through a [`MainLoop`], which is a [`SceneTree`] by default. Replacing
+//! it is useful for headless simulation servers, or tools that need full control over ticks. To
+//! write one in Rust, derive `NativeClass` for a type inheriting `MainLoop` or `SceneTree`,
+//! implement [`CustomMainLoop`] for it and register its callbacks with [`register_main_loop`]:
+//!
+//! ```no_run
+//! use gdnative::api::MainLoop;
+//! use gdnative::main_loop::{register_main_loop, CustomMainLoop};
+//! use gdnative::prelude::*;
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(MainLoop)]
+//! #[register_with(register_main_loop)]
+//! struct Simulation {
+//!     ticks: u64,
+//! }
+//!
+//! #[methods]
+//! impl Simulation {
+//!     fn new(_base: &MainLoop) -> Self {
+//!         Simulation { ticks: 0 }
+//!     }
+//! }
+//!
+//! impl CustomMainLoop for Simulation {
+//!     fn iteration(&mut self, _base: TRef<MainLoop>, _delta: f64) -> bool {
+//!         self.ticks += 1;
+//!
+//!         // Quits after 1000 physics ticks.
+//!         self.ticks >= 1000
+//!     }
+//! }
+//! ```
+//!
+//! The main loop is selected with the `application/run/main_loop_type` project setting, or
+//! on the command line with `--script`, which also works for headless servers:
+//!
+//! ```text
+//! godot --no-window --script res://simulation.gdns
+//! ```
+//!
+//! Physics ticks happen `Engine.iterations_per_second` times per second, which can be changed
+//! to run the simulation at a custom tick rate.
+//!
+//! [`MainLoop`]: crate::api::MainLoop
+//! [`SceneTree`]: crate::api::SceneTree
+
+use crate::api::{InputEvent, MainLoop};
+use crate::core_types::{GodotString, PoolArray, ToVariant, Variant};
+use crate::export::user_data::MapMut;
+use crate::export::{ClassBuilder, Method, NativeClass, Varargs, VarargsError};
+use crate::log::godot_error;
+use crate::object::ownership::Shared;
+use crate::object::{Ref, SubClass, TInstance, TRef};
+
+/// Interface of a custom main loop, corresponding to the virtual methods of `MainLoop` in
+/// Godot.
+///
+/// Implementations must be registered with [`register_main_loop`] to be called by the engine.
+/// All methods have empty default implementations.
+///
+/// When the base class is `SceneTree`, the callbacks are invoked before the tree processes the
+/// same event, and the return values of [`iteration`][Self::iteration] and
+/// [`idle`][Self::idle] are ignored. Use `SceneTree::quit` to exit instead.
+pub trait CustomMainLoop: NativeClass {
+    /// Called once when the main loop is set up, before the first iteration.
+    #[inline]
+    fn initialize(&mut self, _base: TRef<'_, Self::Base>) {}
+
+    /// Called for each physics tick, with the fixed time step in seconds. Returns `true` to
+    /// quit. The default implementation returns `false`.
+    #[inline]
+    fn iteration(&mut self, _base: TRef<'_, Self::Base>, _delta: f64) -> bool {
+        false
+    }
+
+    /// Called for each rendered frame, with the time since the previous frame in seconds.
+    /// Returns `true` to quit. The default implementation returns `false`.
+    #[inline]
+    fn idle(&mut self, _base: TRef<'_, Self::Base>, _delta: f64) -> bool {
+        false
+    }
+
+    /// Called for each input event.
+    #[inline]
+    fn input_event(&mut self, _base: TRef<'_, Self::Base>, _event: TRef<'_, InputEvent>) {}
+
+    /// Called for text typed through the window system, e.g. with an input method editor.
+    #[inline]
+    fn input_text(&mut self, _base: TRef<'_, Self::Base>, _text: String) {}
+
+    /// Called when files are dragged onto the window from the operating system.
+    #[inline]
+    fn drop_files(&mut self, _base: TRef<'_, Self::Base>, _files: Vec<String>, _from_screen: i64) {}
+
+    /// Called when an item of the global menu is activated. This is only supported on macOS.
+    #[inline]
+    fn global_menu_action(&mut self, _base: TRef<'_, Self::Base>, _id: Variant, _meta: Variant) {}
+
+    /// Called once before the main loop is destroyed, after the last iteration.
+    #[inline]
+    fn finalize(&mut self, _base: TRef<'_, Self::Base>) {}
+}
+
+/// Registers the virtual methods of `MainLoop` for a [`CustomMainLoop`] implementation. Intended
+/// for use with `#[register_with]`.
+#[inline]
+pub fn register_main_loop<C>(builder: &ClassBuilder<C>)
+where
+    C: CustomMainLoop,
+    C::Base: SubClass<MainLoop>,
+    C::UserData: MapMut,
+{
+    builder
+        .method(
+            "_initialize",
+            Virtual::<C>::new(|c, base, _| {
+                c.initialize(base);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "_iteration",
+            Virtual::<C>::new(|c, base, args| Ok(c.iteration(base, args.get(0)?).to_variant())),
+        )
+        .done();
+
+    builder
+        .method(
+            "_idle",
+            Virtual::<C>::new(|c, base, args| Ok(c.idle(base, args.get(0)?).to_variant())),
+        )
+        .done();
+
+    builder
+        .method(
+            "_input_event",
+            Virtual::<C>::new(|c, base, args| {
+                let event = args.get::<Ref<InputEvent, Shared>>(0)?;
+                // Events passed by the engine are valid for the duration of the call.
+                let event = unsafe { event.assume_safe() };
+                c.input_event(base, event);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "_input_text",
+            Virtual::<C>::new(|c, base, args| {
+                c.input_text(base, args.get(0)?);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "_drop_files",
+            Virtual::<C>::new(|c, base, args| {
+                let files = args.get::<PoolArray<GodotString>>(0)?;
+                let files = files.read().iter().map(|file| file.to_string()).collect();
+                c.drop_files(base, files, args.get(1)?);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "_global_menu_action",
+            Virtual::<C>::new(|c, base, args| {
+                let id = args.get(0)?;
+                let meta = args.get_opt(1)?.unwrap_or_default();
+                c.global_menu_action(base, id, meta);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+
+    builder
+        .method(
+            "_finalize",
+            Virtual::<C>::new(|c, base, _| {
+                c.finalize(base);
+                Ok(Variant::nil())
+            }),
+        )
+        .done();
+}
+
+type VirtualFn<C> =
+    fn(&mut C, TRef<'_, <C as NativeClass>::Base>, Varargs<'_>) -> Result<Variant, VarargsError>;
+
+/// Method forwarding a virtual call to a [`CustomMainLoop`].
+struct Virtual<C: NativeClass> {
+    f: VirtualFn<C>,
+}
+
+impl<C: NativeClass> Virtual<C> {
+    fn new(f: VirtualFn<C>) -> Self {
+        Virtual { f }
+    }
+}
+
+impl<C> Method<C> for Virtual<C>
+where
+    C: CustomMainLoop,
+    C::UserData: MapMut,
+{
+    fn call(&self, this: TInstance<'_, C>, args: Varargs<'_>) -> Variant {
+        match this.map_mut(|c, base| (self.f)(c, base, args)) {
+            Ok(Ok(ret)) => ret,
+            Ok(Err(err)) => {
+                godot_error!("gdnative: invalid arguments for main loop method: {err}");
+                Variant::nil()
+            }
+            Err(err) => {
+                godot_error!("gdnative: could not access main loop: {err}");
+                Variant::nil()
+            }
+        }
+    }
+}
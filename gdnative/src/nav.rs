@@ -0,0 +1,665 @@
+//! Typed helpers for navigation and pathfinding.
+//!
+//! The navigation nodes and [`NavigationServer`] return paths as pool arrays, and identify maps,
+//! regions and agents by RIDs. This module converts paths to [`NavPath`], which can be queried
+//! for its length or the point at a distance along it, and wraps server resources in handles
+//! that are freed when dropped:
+//!
+//! ```no_run
+//! use gdnative::api::Navigation;
+//! use gdnative::nav;
+//! use gdnative::prelude::*;
+//!
+//! fn step_along_path(nav: &Navigation, body: &Spatial, target: Vector3, distance: f32) {
+//!     let from = body.translation();
+//!     let path = nav::simple_path(nav, from, target, true);
+//!     if let Some(next) = path.point_at_distance(distance) {
+//!         body.set_translation(next);
+//!     }
+//! }
+//! ```
+//!
+//! Navigation meshes and polygons for procedurally generated levels can be created with
+//! [`NavMeshBuilder`] and [`NavPolygonBuilder`].
+//!
+//! [`NavigationServer`]: crate::api::NavigationServer
+
+use std::collections::HashMap;
+
+use crate::api::navigation_server::{AgentRid, MapRid, RegionRid};
+use crate::api::{
+    Navigation, Navigation2D, NavigationAgent, NavigationAgent2D, NavigationMesh,
+    NavigationPolygon, NavigationServer,
+};
+use crate::core_types::{PoolArray, PoolElement, Transform, Vector2, Vector3};
+use crate::object::ownership::Unique;
+use crate::object::{AsArg, Ref};
+
+/// A point of a [`NavPath`], i.e. `Vector2` or `Vector3`.
+pub trait PathPoint: Copy + private::Sealed {
+    /// Returns the distance between `self` and `other`.
+    fn distance(self, other: Self) -> f32;
+
+    /// Returns the point at `t` between `self` (0) and `other` (1).
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl PathPoint for Vector2 {
+    #[inline]
+    fn distance(self, other: Self) -> f32 {
+        self.distance_to(other)
+    }
+
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.linear_interpolate(other, t)
+    }
+}
+
+impl PathPoint for Vector3 {
+    #[inline]
+    fn distance(self, other: Self) -> f32 {
+        self.distance_to(other)
+    }
+
+    #[inline]
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.linear_interpolate(other, t)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for crate::core_types::Vector2 {}
+    impl Sealed for crate::core_types::Vector3 {}
+}
+
+/// A path through a navigation mesh, as a list of points.
+///
+/// Paths returned by Godot are empty if no path could be found.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NavPath<V> {
+    points: Vec<V>,
+}
+
+impl<V: PathPoint> NavPath<V> {
+    /// Creates a path through `points`.
+    #[inline]
+    pub fn from_points(points: Vec<V>) -> Self {
+        NavPath { points }
+    }
+
+    /// Returns the points of the path.
+    #[inline]
+    pub fn points(&self) -> &[V] {
+        &self.points
+    }
+
+    /// Returns the points of the path as a `Vec`.
+    #[inline]
+    pub fn into_points(self) -> Vec<V> {
+        self.points
+    }
+
+    /// Returns the number of points.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if the path has no points, e.g. because no path was found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the first point, or `None` if the path is empty.
+    #[inline]
+    pub fn start(&self) -> Option<V> {
+        self.points.first().copied()
+    }
+
+    /// Returns the last point, or `None` if the path is empty.
+    #[inline]
+    pub fn end(&self) -> Option<V> {
+        self.points.last().copied()
+    }
+
+    /// Returns an iterator over the points.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = V> + '_ {
+        self.points.iter().copied()
+    }
+
+    /// Returns an iterator over the segments between consecutive points, as `(from, to)`.
+    #[inline]
+    pub fn segments(&self) -> impl Iterator<Item = (V, V)> + '_ {
+        self.points.windows(2).map(|pair| (pair[0], pair[1]))
+    }
+
+    /// Returns the total length of the path.
+    #[inline]
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(from, to)| from.distance(to)).sum()
+    }
+
+    /// Returns the point at `distance` along the path, measured from the start. Distances are
+    /// clamped to the path, so that values beyond its length return the end point.
+    ///
+    /// Returns `None` if the path is empty.
+    #[inline]
+    pub fn point_at_distance(&self, distance: f32) -> Option<V> {
+        let mut remaining = distance.max(0.0);
+        for (from, to) in self.segments() {
+            let length = from.distance(to);
+            if remaining < length {
+                return Some(from.lerp(to, remaining / length));
+            }
+            remaining -= length;
+        }
+        self.end()
+    }
+}
+
+impl<V: PathPoint> IntoIterator for NavPath<V> {
+    type Item = V;
+    type IntoIter = std::vec::IntoIter<V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.into_iter()
+    }
+}
+
+impl<'a, V: PathPoint> IntoIterator for &'a NavPath<V> {
+    type Item = V;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, V>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.iter().copied()
+    }
+}
+
+impl<V: PathPoint> FromIterator<V> for NavPath<V> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        Self::from_points(iter.into_iter().collect())
+    }
+}
+
+impl From<PoolArray<Vector2>> for NavPath<Vector2> {
+    #[inline]
+    fn from(points: PoolArray<Vector2>) -> Self {
+        Self::from_points(points.to_vec())
+    }
+}
+
+impl From<PoolArray<Vector3>> for NavPath<Vector3> {
+    #[inline]
+    fn from(points: PoolArray<Vector3>) -> Self {
+        Self::from_points(points.to_vec())
+    }
+}
+
+/// Returns the path between `start` and `end` on the navigation meshes of `navigation`. If
+/// `optimize` is `true`, the path is smoothed by merging points where possible.
+#[inline]
+pub fn simple_path(
+    navigation: &Navigation,
+    start: Vector3,
+    end: Vector3,
+    optimize: bool,
+) -> NavPath<Vector3> {
+    navigation.get_simple_path(start, end, optimize).into()
+}
+
+/// Returns the path between `start` and `end` on the navigation polygons of `navigation`. If
+/// `optimize` is `true`, the path is smoothed by merging points where possible.
+#[inline]
+pub fn simple_path_2d(
+    navigation: &Navigation2D,
+    start: Vector2,
+    end: Vector2,
+    optimize: bool,
+) -> NavPath<Vector2> {
+    navigation.get_simple_path(start, end, optimize).into()
+}
+
+/// Returns the part of the current path of `agent` that has not been traveled yet, starting
+/// with the next point it is moving towards.
+#[inline]
+pub fn remaining_path(agent: &NavigationAgent) -> NavPath<Vector3> {
+    remaining(agent.get_nav_path(), agent.get_nav_path_index())
+}
+
+/// Returns the part of the current path of `agent` that has not been traveled yet, starting
+/// with the next point it is moving towards.
+#[inline]
+pub fn remaining_path_2d(agent: &NavigationAgent2D) -> NavPath<Vector2> {
+    remaining(agent.get_nav_path(), agent.get_nav_path_index())
+}
+
+fn remaining<V: PathPoint + PoolElement>(path: PoolArray<V>, index: i64) -> NavPath<V> {
+    let points = path.to_vec();
+    let index = usize::try_from(index).unwrap_or(0).min(points.len());
+    NavPath::from_points(points[index..].to_vec())
+}
+
+/// A navigation map created on the [`NavigationServer`], freed when dropped.
+///
+/// Maps are inactive when created. The `Navigation` node creates its own map, which is
+/// available with [`Navigation::get_rid`].
+///
+/// [`NavigationServer`]: crate::api::NavigationServer
+#[derive(Debug)]
+pub struct NavMap {
+    rid: MapRid,
+}
+
+impl NavMap {
+    /// Creates a new map.
+    #[inline]
+    pub fn new() -> Self {
+        NavMap {
+            rid: server().map_create(),
+        }
+    }
+
+    /// Returns the RID of the map. It is valid while `self` is alive.
+    #[inline]
+    pub fn rid(&self) -> MapRid {
+        self.rid
+    }
+
+    /// Returns `true` if the map is active, i.e. updated by the server.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        unsafe { server().map_is_active(self.rid) }
+    }
+
+    /// Sets whether the map is updated by the server.
+    #[inline]
+    pub fn set_active(&self, active: bool) {
+        unsafe { server().map_set_active(self.rid, active) }
+    }
+
+    /// Sets the up direction of the map.
+    #[inline]
+    pub fn set_up(&self, up: Vector3) {
+        unsafe { server().map_set_up(self.rid, up) }
+    }
+
+    /// Sets the cell size, which must match the cell size of the navigation meshes.
+    #[inline]
+    pub fn set_cell_size(&self, cell_size: f64) {
+        unsafe { server().map_set_cell_size(self.rid, cell_size) }
+    }
+
+    /// Sets the cell height, which must match the cell height of the navigation meshes.
+    #[inline]
+    pub fn set_cell_height(&self, cell_height: f64) {
+        unsafe { server().map_set_cell_height(self.rid, cell_height) }
+    }
+
+    /// Sets the maximum distance between edges of different regions that are connected.
+    #[inline]
+    pub fn set_edge_connection_margin(&self, margin: f64) {
+        unsafe { server().map_set_edge_connection_margin(self.rid, margin) }
+    }
+
+    /// Returns the path between `origin` and `destination`, only using regions with any of the
+    /// `navigation_layers`. If `optimize` is `true`, the path is smoothed.
+    #[inline]
+    pub fn path(
+        &self,
+        origin: Vector3,
+        destination: Vector3,
+        optimize: bool,
+        navigation_layers: u32,
+    ) -> NavPath<Vector3> {
+        unsafe {
+            server().map_get_path(
+                self.rid,
+                origin,
+                destination,
+                optimize,
+                i64::from(navigation_layers),
+            )
+        }
+        .into()
+    }
+
+    /// Returns the point on the map closest to `point`.
+    #[inline]
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        unsafe { server().map_get_closest_point(self.rid, point) }
+    }
+
+    /// Returns the normal of the surface at the point on the map closest to `point`.
+    #[inline]
+    pub fn closest_point_normal(&self, point: Vector3) -> Vector3 {
+        unsafe { server().map_get_closest_point_normal(self.rid, point) }
+    }
+
+    /// Applies pending changes to regions and agents immediately, instead of during the next
+    /// physics frame.
+    #[inline]
+    pub fn force_update(&self) {
+        unsafe { server().map_force_update(self.rid) }
+    }
+}
+
+impl Default for NavMap {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NavMap {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { server().free_rid(self.rid.rid()) }
+    }
+}
+
+/// A navigation region created on the [`NavigationServer`], freed when dropped.
+///
+/// A region places a navigation mesh on a map. `NavigationMeshInstance` nodes create their own
+/// regions.
+///
+/// [`NavigationServer`]: crate::api::NavigationServer
+#[derive(Debug)]
+pub struct NavRegion {
+    rid: RegionRid,
+}
+
+impl NavRegion {
+    /// Creates a new region, which is not on any map.
+    #[inline]
+    pub fn new() -> Self {
+        NavRegion {
+            rid: server().region_create(),
+        }
+    }
+
+    /// Returns the RID of the region. It is valid while `self` is alive.
+    #[inline]
+    pub fn rid(&self) -> RegionRid {
+        self.rid
+    }
+
+    /// Places the region on `map`.
+    #[inline]
+    pub fn set_map(&self, map: &NavMap) {
+        unsafe { server().region_set_map(self.rid, map.rid) }
+    }
+
+    /// Places the region on the map of `navigation`.
+    #[inline]
+    pub fn set_navigation(&self, navigation: &Navigation) {
+        unsafe { server().region_set_map(self.rid, navigation.get_rid()) }
+    }
+
+    /// Sets the navigation mesh of the region.
+    #[inline]
+    pub fn set_navigation_mesh(&self, mesh: impl AsArg<NavigationMesh>) {
+        unsafe { server().region_set_navmesh(self.rid, mesh) }
+    }
+
+    /// Sets the transform of the navigation mesh on the map.
+    #[inline]
+    pub fn set_transform(&self, transform: Transform) {
+        unsafe { server().region_set_transform(self.rid, transform) }
+    }
+
+    /// Sets the navigation layers of the region, which determine the paths it is used for.
+    #[inline]
+    pub fn set_navigation_layers(&self, navigation_layers: u32) {
+        unsafe { server().region_set_navigation_layers(self.rid, i64::from(navigation_layers)) }
+    }
+
+    /// Sets the cost of entering the region from another region.
+    #[inline]
+    pub fn set_enter_cost(&self, enter_cost: f64) {
+        unsafe { server().region_set_enter_cost(self.rid, enter_cost) }
+    }
+
+    /// Sets the factor by which distances traveled in the region are multiplied.
+    #[inline]
+    pub fn set_travel_cost(&self, travel_cost: f64) {
+        unsafe { server().region_set_travel_cost(self.rid, travel_cost) }
+    }
+
+    /// Returns `true` if `point` is on the navigation mesh of the region.
+    #[inline]
+    pub fn owns_point(&self, point: Vector3) -> bool {
+        unsafe { server().region_owns_point(self.rid, point) }
+    }
+}
+
+impl Default for NavRegion {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NavRegion {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { server().free_rid(self.rid.rid()) }
+    }
+}
+
+/// A navigation agent created on the [`NavigationServer`], freed when dropped.
+///
+/// Agents on a map avoid each other. `NavigationAgent` nodes create their own agents.
+///
+/// [`NavigationServer`]: crate::api::NavigationServer
+#[derive(Debug)]
+pub struct NavAgent {
+    rid: AgentRid,
+}
+
+impl NavAgent {
+    /// Creates a new agent, which is not on any map.
+    #[inline]
+    pub fn new() -> Self {
+        NavAgent {
+            rid: server().agent_create(),
+        }
+    }
+
+    /// Returns the RID of the agent. It is valid while `self` is alive.
+    #[inline]
+    pub fn rid(&self) -> AgentRid {
+        self.rid
+    }
+
+    /// Places the agent on `map`.
+    #[inline]
+    pub fn set_map(&self, map: &NavMap) {
+        unsafe { server().agent_set_map(self.rid, map.rid) }
+    }
+
+    /// Places the agent on the map of `navigation`.
+    #[inline]
+    pub fn set_navigation(&self, navigation: &Navigation) {
+        unsafe { server().agent_set_map(self.rid, navigation.get_rid()) }
+    }
+
+    /// Sets the position of the agent.
+    #[inline]
+    pub fn set_position(&self, position: Vector3) {
+        unsafe { server().agent_set_position(self.rid, position) }
+    }
+
+    /// Sets the current velocity of the agent.
+    #[inline]
+    pub fn set_velocity(&self, velocity: Vector3) {
+        unsafe { server().agent_set_velocity(self.rid, velocity) }
+    }
+
+    /// Sets the velocity the agent would move at without avoidance.
+    #[inline]
+    pub fn set_target_velocity(&self, target_velocity: Vector3) {
+        unsafe { server().agent_set_target_velocity(self.rid, target_velocity) }
+    }
+
+    /// Sets the radius of the agent.
+    #[inline]
+    pub fn set_radius(&self, radius: f64) {
+        unsafe { server().agent_set_radius(self.rid, radius) }
+    }
+
+    /// Sets the maximum speed of the agent.
+    #[inline]
+    pub fn set_max_speed(&self, max_speed: f64) {
+        unsafe { server().agent_set_max_speed(self.rid, max_speed) }
+    }
+
+    /// Sets the distance within which other agents are avoided.
+    #[inline]
+    pub fn set_neighbor_distance(&self, distance: f64) {
+        unsafe { server().agent_set_neighbor_dist(self.rid, distance) }
+    }
+
+    /// Sets the maximum number of other agents that are avoided.
+    #[inline]
+    pub fn set_max_neighbors(&self, count: i64) {
+        unsafe { server().agent_set_max_neighbors(self.rid, count) }
+    }
+
+    /// Sets how far ahead in time, in seconds, collisions with other agents are avoided.
+    #[inline]
+    pub fn set_time_horizon(&self, time: f64) {
+        unsafe { server().agent_set_time_horizon(self.rid, time) }
+    }
+
+    /// Returns `true` if the map of the agent has changed since the last update.
+    #[inline]
+    pub fn is_map_changed(&self) -> bool {
+        unsafe { server().agent_is_map_changed(self.rid) }
+    }
+}
+
+impl Default for NavAgent {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NavAgent {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { server().free_rid(self.rid.rid()) }
+    }
+}
+
+fn server() -> &'static NavigationServer {
+    NavigationServer::godot_singleton()
+}
+
+/// Builder for a [`NavigationMesh`] from convex polygons.
+///
+/// Vertices shared between polygons are stored once, so that the polygons are connected.
+#[derive(Clone, Debug, Default)]
+pub struct NavMeshBuilder {
+    vertices: Vec<Vector3>,
+    indices: HashMap<[u32; 3], i32>,
+    polygons: Vec<Vec<i32>>,
+}
+
+impl NavMeshBuilder {
+    /// Creates an empty builder.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a convex polygon with the vertices `points`, in clockwise order when viewed from
+    /// above.
+    #[inline]
+    pub fn push_polygon(&mut self, points: &[Vector3]) {
+        let polygon = points
+            .iter()
+            .map(|&point| {
+                let key = [point.x.to_bits(), point.y.to_bits(), point.z.to_bits()];
+                let vertices = &mut self.vertices;
+                *self.indices.entry(key).or_insert_with(|| {
+                    vertices.push(point);
+                    (vertices.len() - 1) as i32
+                })
+            })
+            .collect();
+        self.polygons.push(polygon);
+    }
+
+    /// Returns the distinct vertices of the polygons added so far.
+    #[inline]
+    pub fn vertices(&self) -> &[Vector3] {
+        &self.vertices
+    }
+
+    /// Returns the number of polygons added so far.
+    #[inline]
+    pub fn polygon_count(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Creates the navigation mesh.
+    #[inline]
+    pub fn done(self) -> Ref<NavigationMesh, Unique> {
+        let mesh = NavigationMesh::new();
+        mesh.set_vertices(PoolArray::from_vec(self.vertices));
+        for polygon in self.polygons {
+            mesh.add_polygon(PoolArray::from_vec(polygon));
+        }
+        mesh
+    }
+}
+
+/// Builder for a [`NavigationPolygon`] from outlines.
+///
+/// The first outline is the outer boundary of the walkable area, and later ones cut holes into
+/// it, e.g. around obstacles. The polygons are computed from the outlines when the navigation
+/// polygon is created.
+#[derive(Clone, Debug, Default)]
+pub struct NavPolygonBuilder {
+    outlines: Vec<Vec<Vector2>>,
+}
+
+impl NavPolygonBuilder {
+    /// Creates an empty builder.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an outline with the vertices `points`.
+    #[inline]
+    pub fn push_outline(&mut self, points: &[Vector2]) {
+        self.outlines.push(points.to_vec());
+    }
+
+    /// Returns the number of outlines added so far.
+    #[inline]
+    pub fn outline_count(&self) -> usize {
+        self.outlines.len()
+    }
+
+    /// Creates the navigation polygon.
+    #[inline]
+    pub fn done(self) -> Ref<NavigationPolygon, Unique> {
+        let polygon = NavigationPolygon::new();
+        for outline in self.outlines {
+            polygon.add_outline(PoolArray::from_vec(outline));
+        }
+        polygon.make_polygons_from_outlines();
+        polygon
+    }
+}
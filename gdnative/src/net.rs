@@ -0,0 +1,698 @@
+//! Custom network transports implemented in Rust.
+//!
+//! Godot's high-level multiplayer API can use any [`NetworkedMultiplayerPeer`] as its transport.
+//! The `MultiplayerPeerGDNative`, `PacketPeerGDNative` and `StreamPeerGDNative` classes forward
+//! their virtual interface to native code, which allows transports such as Steam networking,
+//! WebRTC data channels or QUIC to be written in Rust and used from GDScript.
+//!
+//! This module exposes these interfaces as the [`PacketPeer`], [`MultiplayerPeer`] and
+//! [`StreamPeer`] traits. An implementation is bound to an object with [`NetBinding`], and stays
+//! bound for as long as the `NetBinding` is alive. The easiest way to tie both lifetimes together
+//! is to store the binding in a NativeScript attached to the object:
+//!
+//! ```ignore
+//! use gdnative::api::MultiplayerPeerGDNative;
+//! use gdnative::net::{MultiplayerPeer, NetBinding};
+//! use gdnative::prelude::*;
+//!
+//! struct QuicTransport { /* ... */ }
+//!
+//! impl MultiplayerPeer for QuicTransport { /* ... */ }
+//!
+//! #[derive(NativeClass)]
+//! #[inherit(MultiplayerPeerGDNative)]
+//! struct QuicPeer {
+//!     binding: NetBinding<QuicTransport>,
+//! }
+//!
+//! #[methods]
+//! impl QuicPeer {
+//!     fn new(base: &MultiplayerPeerGDNative) -> Self {
+//!         QuicPeer {
+//!             binding: NetBinding::multiplayer_peer(base, QuicTransport::new()),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Engine callbacks are always made on the thread the object is used from, and panics inside
+//! them are caught and reported as errors.
+//!
+//! [`NetworkedMultiplayerPeer`]: crate::api::NetworkedMultiplayerPeer
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::{ptr, slice};
+
+use crate::api::networked_multiplayer_peer::{ConnectionStatus, TransferMode};
+use crate::api::{MultiplayerPeerGDNative, Object, PacketPeerGDNative, StreamPeerGDNative};
+use crate::core_types::{GodotError, GodotResult, ToVariant};
+use crate::libc;
+use crate::log::godot_error;
+use crate::object::{GodotObject, InstanceId};
+use crate::private::get_api;
+use crate::sys;
+
+const NET_API_VERSION: sys::godot_gdnative_api_version =
+    sys::godot_gdnative_api_version { major: 3, minor: 1 };
+
+/// Interface of a packet-based connection, corresponding to `PacketPeer` in Godot.
+pub trait PacketPeer: 'static {
+    /// Removes the next packet from the queue and returns it.
+    ///
+    /// # Errors
+    ///
+    /// If no packet is available, or the packet could not be received.
+    fn get_packet(&mut self) -> Result<Vec<u8>, GodotError>;
+
+    /// Sends a packet.
+    ///
+    /// # Errors
+    ///
+    /// If the packet could not be sent.
+    fn put_packet(&mut self, packet: &[u8]) -> GodotResult;
+
+    /// Returns the number of packets currently available.
+    fn get_available_packet_count(&self) -> i64;
+
+    /// Returns the maximum allowed size of packets in bytes.
+    fn get_max_packet_size(&self) -> i64;
+}
+
+/// Interface of a transport for the high-level multiplayer API, corresponding to
+/// `NetworkedMultiplayerPeer` in Godot.
+///
+/// Packets are sent to the peer set with [`set_target_peer`](Self::set_target_peer), using the
+/// mode set with [`set_transfer_mode`](Self::set_transfer_mode).
+pub trait MultiplayerPeer: PacketPeer {
+    /// Sets the manner in which packets are sent.
+    fn set_transfer_mode(&mut self, mode: TransferMode);
+
+    /// Returns the manner in which packets are sent.
+    fn get_transfer_mode(&self) -> TransferMode;
+
+    /// Sets the peer to which packets are sent. `0` means all peers, `1` the server, and a
+    /// negative value all peers except the one with the negated ID.
+    fn set_target_peer(&mut self, id: i64);
+
+    /// Returns the ID of the peer who sent the packet returned by the next call to
+    /// [`get_packet`](PacketPeer::get_packet).
+    fn get_packet_peer(&self) -> i64;
+
+    /// Returns `true` if this peer is the server.
+    fn is_server(&self) -> bool;
+
+    /// Processes pending network events. This is called by the engine regularly, usually once
+    /// per frame.
+    ///
+    /// Connection changes are reported by pushing to `events`. They are emitted as the
+    /// corresponding signals on the object after this method returns, so that signal handlers
+    /// can use this peer.
+    fn poll(&mut self, events: &mut Vec<PeerEvent>);
+
+    /// Returns the ID of this peer. Must be greater than zero, and `1` for the server.
+    fn get_unique_id(&self) -> i32;
+
+    /// Sets whether new connections should be refused.
+    fn set_refuse_new_connections(&mut self, enable: bool);
+
+    /// Returns `true` if new connections are refused.
+    fn is_refusing_new_connections(&self) -> bool;
+
+    /// Returns the current state of the connection.
+    fn get_connection_status(&self) -> ConnectionStatus;
+}
+
+/// Interface of a stream-based connection, corresponding to `StreamPeer` in Godot.
+pub trait StreamPeer: 'static {
+    /// Fills `buffer` with received data, waiting until enough data is available.
+    ///
+    /// # Errors
+    ///
+    /// If the data could not be received.
+    fn get_data(&mut self, buffer: &mut [u8]) -> GodotResult;
+
+    /// Fills `buffer` with data that is available without waiting, and returns the number of
+    /// bytes received.
+    ///
+    /// # Errors
+    ///
+    /// If the data could not be received.
+    fn get_partial_data(&mut self, buffer: &mut [u8]) -> Result<usize, GodotError>;
+
+    /// Sends all of `data`, waiting as necessary.
+    ///
+    /// # Errors
+    ///
+    /// If the data could not be sent.
+    fn put_data(&mut self, data: &[u8]) -> GodotResult;
+
+    /// Sends as much of `data` as possible without waiting, and returns the number of bytes sent.
+    ///
+    /// # Errors
+    ///
+    /// If the data could not be sent.
+    fn put_partial_data(&mut self, data: &[u8]) -> Result<usize, GodotError>;
+
+    /// Returns the number of bytes that can be received without waiting.
+    fn get_available_bytes(&self) -> i64;
+}
+
+/// Connection changes reported by [`MultiplayerPeer::poll`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum PeerEvent {
+    /// A remote peer has connected. Emits `peer_connected`.
+    PeerConnected(i64),
+    /// A remote peer has disconnected. Emits `peer_disconnected`.
+    PeerDisconnected(i64),
+    /// The connection to the server was established. Emits `connection_succeeded`.
+    ConnectionSucceeded,
+    /// The connection to the server could not be established. Emits `connection_failed`.
+    ConnectionFailed,
+    /// The connection to the server was lost. Emits `server_disconnected`.
+    ServerDisconnected,
+}
+
+impl PeerEvent {
+    fn emit(self, owner: &Object) {
+        match self {
+            PeerEvent::PeerConnected(id) => owner.emit_signal("peer_connected", &[id.to_variant()]),
+            PeerEvent::PeerDisconnected(id) => {
+                owner.emit_signal("peer_disconnected", &[id.to_variant()])
+            }
+            PeerEvent::ConnectionSucceeded => owner.emit_signal("connection_succeeded", &[]),
+            PeerEvent::ConnectionFailed => owner.emit_signal("connection_failed", &[]),
+            PeerEvent::ServerDisconnected => owner.emit_signal("server_disconnected", &[]),
+        };
+    }
+}
+
+impl<P: PacketPeer + ?Sized> PacketPeer for Box<P> {
+    #[inline]
+    fn get_packet(&mut self) -> Result<Vec<u8>, GodotError> {
+        (**self).get_packet()
+    }
+    #[inline]
+    fn put_packet(&mut self, packet: &[u8]) -> GodotResult {
+        (**self).put_packet(packet)
+    }
+    #[inline]
+    fn get_available_packet_count(&self) -> i64 {
+        (**self).get_available_packet_count()
+    }
+    #[inline]
+    fn get_max_packet_size(&self) -> i64 {
+        (**self).get_max_packet_size()
+    }
+}
+
+impl<P: MultiplayerPeer + ?Sized> MultiplayerPeer for Box<P> {
+    #[inline]
+    fn set_transfer_mode(&mut self, mode: TransferMode) {
+        (**self).set_transfer_mode(mode)
+    }
+    #[inline]
+    fn get_transfer_mode(&self) -> TransferMode {
+        (**self).get_transfer_mode()
+    }
+    #[inline]
+    fn set_target_peer(&mut self, id: i64) {
+        (**self).set_target_peer(id)
+    }
+    #[inline]
+    fn get_packet_peer(&self) -> i64 {
+        (**self).get_packet_peer()
+    }
+    #[inline]
+    fn is_server(&self) -> bool {
+        (**self).is_server()
+    }
+    #[inline]
+    fn poll(&mut self, events: &mut Vec<PeerEvent>) {
+        (**self).poll(events)
+    }
+    #[inline]
+    fn get_unique_id(&self) -> i32 {
+        (**self).get_unique_id()
+    }
+    #[inline]
+    fn set_refuse_new_connections(&mut self, enable: bool) {
+        (**self).set_refuse_new_connections(enable)
+    }
+    #[inline]
+    fn is_refusing_new_connections(&self) -> bool {
+        (**self).is_refusing_new_connections()
+    }
+    #[inline]
+    fn get_connection_status(&self) -> ConnectionStatus {
+        (**self).get_connection_status()
+    }
+}
+
+impl<P: StreamPeer + ?Sized> StreamPeer for Box<P> {
+    #[inline]
+    fn get_data(&mut self, buffer: &mut [u8]) -> GodotResult {
+        (**self).get_data(buffer)
+    }
+    #[inline]
+    fn get_partial_data(&mut self, buffer: &mut [u8]) -> Result<usize, GodotError> {
+        (**self).get_partial_data(buffer)
+    }
+    #[inline]
+    fn put_data(&mut self, data: &[u8]) -> GodotResult {
+        (**self).put_data(data)
+    }
+    #[inline]
+    fn put_partial_data(&mut self, data: &[u8]) -> Result<usize, GodotError> {
+        (**self).put_partial_data(data)
+    }
+    #[inline]
+    fn get_available_bytes(&self) -> i64 {
+        (**self).get_available_bytes()
+    }
+}
+
+/// Binding between a Rust implementation of a network interface and a Godot object.
+///
+/// The implementation is unbound from the object when this is dropped, after which calls to the
+/// object fail with an error. The implementation can be accessed from Rust with
+/// [`peer`](Self::peer) and [`peer_mut`](Self::peer_mut).
+pub struct NetBinding<P> {
+    state: Box<State<P>>,
+    interface: Box<Interface>,
+    // Engine callbacks access the state from the thread the object is used from.
+    _marker: PhantomData<*const ()>,
+}
+
+struct State<P> {
+    owner_id: InstanceId,
+    peer: RefCell<P>,
+    // Packets returned to the engine must stay valid until the next call.
+    packet: RefCell<Vec<u8>>,
+}
+
+enum Interface {
+    Packet(sys::godot_net_packet_peer),
+    Multiplayer(sys::godot_net_multiplayer_peer),
+    Stream(sys::godot_net_stream_peer),
+}
+
+impl<P: PacketPeer> NetBinding<P> {
+    /// Binds `peer` to a `PacketPeerGDNative` object.
+    #[inline]
+    pub fn packet_peer(owner: &PacketPeerGDNative, peer: P) -> Self {
+        Self::bind(owner.upcast(), peer, |data| {
+            Interface::Packet(sys::godot_net_packet_peer {
+                version: NET_API_VERSION,
+                data,
+                get_packet: Some(get_packet::<P>),
+                put_packet: Some(put_packet::<P>),
+                get_available_packet_count: Some(get_available_packet_count::<P>),
+                get_max_packet_size: Some(get_max_packet_size::<P>),
+                next: ptr::null_mut(),
+            })
+        })
+    }
+}
+
+impl<P: MultiplayerPeer> NetBinding<P> {
+    /// Binds `peer` to a `MultiplayerPeerGDNative` object, which can then be used as the
+    /// `network_peer` of a `SceneTree`.
+    #[inline]
+    pub fn multiplayer_peer(owner: &MultiplayerPeerGDNative, peer: P) -> Self {
+        Self::bind(owner.upcast(), peer, |data| {
+            Interface::Multiplayer(sys::godot_net_multiplayer_peer {
+                version: NET_API_VERSION,
+                data,
+                get_packet: Some(get_packet::<P>),
+                put_packet: Some(put_packet::<P>),
+                get_available_packet_count: Some(get_available_packet_count::<P>),
+                get_max_packet_size: Some(get_max_packet_size::<P>),
+                set_transfer_mode: Some(set_transfer_mode::<P>),
+                get_transfer_mode: Some(get_transfer_mode::<P>),
+                set_target_peer: Some(set_target_peer::<P>),
+                get_packet_peer: Some(get_packet_peer::<P>),
+                is_server: Some(is_server::<P>),
+                poll: Some(poll::<P>),
+                get_unique_id: Some(get_unique_id::<P>),
+                set_refuse_new_connections: Some(set_refuse_new_connections::<P>),
+                is_refusing_new_connections: Some(is_refusing_new_connections::<P>),
+                get_connection_status: Some(get_connection_status::<P>),
+                next: ptr::null_mut(),
+            })
+        })
+    }
+}
+
+impl<P: StreamPeer> NetBinding<P> {
+    /// Binds `peer` to a `StreamPeerGDNative` object.
+    #[inline]
+    pub fn stream_peer(owner: &StreamPeerGDNative, peer: P) -> Self {
+        Self::bind(owner.upcast(), peer, |data| {
+            Interface::Stream(sys::godot_net_stream_peer {
+                version: NET_API_VERSION,
+                data,
+                get_data: Some(get_data::<P>),
+                get_partial_data: Some(get_partial_data::<P>),
+                put_data: Some(put_data::<P>),
+                put_partial_data: Some(put_partial_data::<P>),
+                get_available_bytes: Some(get_available_bytes::<P>),
+                next: ptr::null_mut(),
+            })
+        })
+    }
+}
+
+impl<P> NetBinding<P> {
+    fn bind(
+        owner: &Object,
+        peer: P,
+        interface: impl FnOnce(*mut sys::godot_object) -> Interface,
+    ) -> Self {
+        let owner_id = owner.instance_id();
+        let state = Box::new(State {
+            owner_id,
+            peer: RefCell::new(peer),
+            packet: RefCell::new(Vec::new()),
+        });
+
+        // Both are boxed, so the pointers stay valid when the binding is moved.
+        let data = &*state as *const State<P> as *mut sys::godot_object;
+        let interface = Box::new(interface(data));
+        unsafe { interface.bind(owner.as_ptr()) };
+
+        NetBinding {
+            state,
+            interface,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the bound implementation.
+    ///
+    /// # Panics
+    ///
+    /// If the implementation is currently borrowed mutably.
+    #[inline]
+    pub fn peer(&self) -> Ref<'_, P> {
+        self.state.peer.borrow()
+    }
+
+    /// Returns a mutable reference to the bound implementation. Calls from the engine fail
+    /// while the reference is held.
+    ///
+    /// # Panics
+    ///
+    /// If the implementation is currently borrowed.
+    #[inline]
+    pub fn peer_mut(&self) -> RefMut<'_, P> {
+        self.state.peer.borrow_mut()
+    }
+}
+
+impl<P> Drop for NetBinding<P> {
+    #[inline]
+    fn drop(&mut self) {
+        // The object might have been freed before the binding.
+        if let Some(owner) = unsafe { Object::try_from_instance_id(self.state.owner_id) } {
+            unsafe { self.interface.unbind(owner.as_ptr()) }
+        }
+    }
+}
+
+impl Interface {
+    unsafe fn bind(&self, owner: *mut sys::godot_object) {
+        let api = get_api();
+        match self {
+            Interface::Packet(i) => (api.godot_net_bind_packet_peer)(owner, i),
+            Interface::Multiplayer(i) => (api.godot_net_bind_multiplayer_peer)(owner, i),
+            Interface::Stream(i) => (api.godot_net_bind_stream_peer)(owner, i),
+        }
+    }
+
+    unsafe fn unbind(&self, owner: *mut sys::godot_object) {
+        let api = get_api();
+        match self {
+            Interface::Packet(_) => (api.godot_net_bind_packet_peer)(owner, ptr::null()),
+            Interface::Multiplayer(_) => (api.godot_net_bind_multiplayer_peer)(owner, ptr::null()),
+            Interface::Stream(_) => (api.godot_net_bind_stream_peer)(owner, ptr::null()),
+        }
+    }
+}
+
+/// Calls `f` with the state behind `user`, catching panics and returning `default` instead.
+unsafe fn with_state<P, R>(
+    user: *const libc::c_void,
+    default: R,
+    f: impl FnOnce(&State<P>) -> R,
+) -> R {
+    let state = &*(user as *const State<P>);
+    catch_unwind(AssertUnwindSafe(|| f(state))).unwrap_or_else(|_| {
+        godot_error!("gdnative: network peer callback panicked");
+        default
+    })
+}
+
+unsafe fn with_peer<P, R>(user: *const libc::c_void, default: R, f: impl FnOnce(&P) -> R) -> R {
+    with_state(user, None, |state: &State<P>| {
+        match state.peer.try_borrow() {
+            Ok(peer) => Some(f(&peer)),
+            Err(_) => {
+                godot_error!("gdnative: network peer is already borrowed mutably");
+                None
+            }
+        }
+    })
+    .unwrap_or(default)
+}
+
+unsafe fn with_peer_mut<P, R>(
+    user: *const libc::c_void,
+    default: R,
+    f: impl FnOnce(&mut P) -> R,
+) -> R {
+    with_state(user, None, |state: &State<P>| {
+        match state.peer.try_borrow_mut() {
+            Ok(mut peer) => Some(f(&mut peer)),
+            Err(_) => {
+                godot_error!("gdnative: network peer is already borrowed");
+                None
+            }
+        }
+    })
+    .unwrap_or(default)
+}
+
+#[allow(clippy::unnecessary_cast)] // False positives: casts necessary for cross-platform
+fn error_to_sys(result: GodotResult) -> sys::godot_error {
+    match result {
+        Ok(()) => sys::godot_error_GODOT_OK,
+        Err(err) => err as u32 as sys::godot_error,
+    }
+}
+
+const FAILED: sys::godot_error = sys::godot_error_GODOT_FAILED;
+
+unsafe fn slice_from_raw<'a>(data: *const u8, len: libc::c_int) -> &'a [u8] {
+    if data.is_null() || len <= 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len as usize)
+    }
+}
+
+unsafe fn slice_from_raw_mut<'a>(data: *mut u8, len: libc::c_int) -> &'a mut [u8] {
+    if data.is_null() || len <= 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(data, len as usize)
+    }
+}
+
+fn to_c_int(value: i64) -> libc::c_int {
+    value.clamp(libc::c_int::MIN as i64, libc::c_int::MAX as i64) as libc::c_int
+}
+
+// PacketPeer
+
+unsafe extern "C" fn get_packet<P: PacketPeer>(
+    user: *mut libc::c_void,
+    r_buffer: *mut *const u8,
+    r_len: *mut libc::c_int,
+) -> sys::godot_error {
+    with_state(user, FAILED, |state: &State<P>| {
+        let packet = match state.peer.try_borrow_mut() {
+            Ok(mut peer) => peer.get_packet(),
+            Err(_) => {
+                godot_error!("gdnative: network peer is already borrowed");
+                return FAILED;
+            }
+        };
+
+        match packet {
+            Ok(packet) => {
+                let mut buffer = state.packet.borrow_mut();
+                *buffer = packet;
+                *r_buffer = buffer.as_ptr();
+                *r_len = to_c_int(buffer.len() as i64);
+                sys::godot_error_GODOT_OK
+            }
+            Err(err) => error_to_sys(Err(err)),
+        }
+    })
+}
+
+unsafe extern "C" fn put_packet<P: PacketPeer>(
+    user: *mut libc::c_void,
+    buffer: *const u8,
+    len: libc::c_int,
+) -> sys::godot_error {
+    with_peer_mut(user, FAILED, |peer: &mut P| {
+        error_to_sys(peer.put_packet(slice_from_raw(buffer, len)))
+    })
+}
+
+unsafe extern "C" fn get_available_packet_count<P: PacketPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_int {
+    with_peer(user, 0, |peer: &P| {
+        to_c_int(peer.get_available_packet_count())
+    })
+}
+
+unsafe extern "C" fn get_max_packet_size<P: PacketPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_int {
+    with_peer(user, 0, |peer: &P| to_c_int(peer.get_max_packet_size()))
+}
+
+// NetworkedMultiplayerPeer
+
+unsafe extern "C" fn set_transfer_mode<P: MultiplayerPeer>(
+    user: *mut libc::c_void,
+    mode: sys::godot_int,
+) {
+    with_peer_mut(user, (), |peer: &mut P| {
+        peer.set_transfer_mode(TransferMode(mode as i64))
+    })
+}
+
+unsafe extern "C" fn get_transfer_mode<P: MultiplayerPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_int {
+    with_peer(user, 0, |peer: &P| to_c_int(peer.get_transfer_mode().0))
+}
+
+unsafe extern "C" fn set_target_peer<P: MultiplayerPeer>(
+    user: *mut libc::c_void,
+    id: sys::godot_int,
+) {
+    with_peer_mut(user, (), |peer: &mut P| peer.set_target_peer(id as i64))
+}
+
+unsafe extern "C" fn get_packet_peer<P: MultiplayerPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_int {
+    with_peer(user, 0, |peer: &P| to_c_int(peer.get_packet_peer()))
+}
+
+unsafe extern "C" fn is_server<P: MultiplayerPeer>(user: *const libc::c_void) -> sys::godot_bool {
+    with_peer(user, false, |peer: &P| peer.is_server())
+}
+
+unsafe extern "C" fn poll<P: MultiplayerPeer>(user: *mut libc::c_void) {
+    let owner_id = with_state(user, InstanceId::from_i64(0), |state: &State<P>| {
+        state.owner_id
+    });
+    let events = with_peer_mut(user, Vec::new(), |peer: &mut P| {
+        let mut events = Vec::new();
+        peer.poll(&mut events);
+        events
+    });
+
+    // Signals are emitted after the borrow is released, so handlers can use the peer.
+    if let Some(owner) = Object::try_from_instance_id(owner_id) {
+        for event in events {
+            event.emit(&owner);
+        }
+    }
+}
+
+unsafe extern "C" fn get_unique_id<P: MultiplayerPeer>(user: *const libc::c_void) -> i32 {
+    with_peer(user, 0, |peer: &P| peer.get_unique_id())
+}
+
+unsafe extern "C" fn set_refuse_new_connections<P: MultiplayerPeer>(
+    user: *mut libc::c_void,
+    enable: sys::godot_bool,
+) {
+    with_peer_mut(user, (), |peer: &mut P| {
+        peer.set_refuse_new_connections(enable)
+    })
+}
+
+unsafe extern "C" fn is_refusing_new_connections<P: MultiplayerPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_bool {
+    with_peer(user, false, |peer: &P| peer.is_refusing_new_connections())
+}
+
+unsafe extern "C" fn get_connection_status<P: MultiplayerPeer>(
+    user: *const libc::c_void,
+) -> sys::godot_int {
+    with_peer(user, 0, |peer: &P| to_c_int(peer.get_connection_status().0))
+}
+
+// StreamPeer
+
+unsafe extern "C" fn get_data<P: StreamPeer>(
+    user: *mut libc::c_void,
+    buffer: *mut u8,
+    len: libc::c_int,
+) -> sys::godot_error {
+    with_peer_mut(user, FAILED, |peer: &mut P| {
+        error_to_sys(peer.get_data(slice_from_raw_mut(buffer, len)))
+    })
+}
+
+unsafe extern "C" fn get_partial_data<P: StreamPeer>(
+    user: *mut libc::c_void,
+    buffer: *mut u8,
+    len: libc::c_int,
+    r_received: *mut libc::c_int,
+) -> sys::godot_error {
+    with_peer_mut(user, FAILED, |peer: &mut P| {
+        let result = peer.get_partial_data(slice_from_raw_mut(buffer, len));
+        *r_received = to_c_int(*result.as_ref().unwrap_or(&0) as i64);
+        error_to_sys(result.map(|_| ()))
+    })
+}
+
+unsafe extern "C" fn put_data<P: StreamPeer>(
+    user: *mut libc::c_void,
+    data: *const u8,
+    len: libc::c_int,
+) -> sys::godot_error {
+    with_peer_mut(user, FAILED, |peer: &mut P| {
+        error_to_sys(peer.put_data(slice_from_raw(data, len)))
+    })
+}
+
+unsafe extern "C" fn put_partial_data<P: StreamPeer>(
+    user: *mut libc::c_void,
+    data: *const u8,
+    len: libc::c_int,
+    r_sent: *mut libc::c_int,
+) -> sys::godot_error {
+    with_peer_mut(user, FAILED, |peer: &mut P| {
+        let result = peer.put_partial_data(slice_from_raw(data, len));
+        *r_sent = to_c_int(*result.as_ref().unwrap_or(&0) as i64);
+        error_to_sys(result.map(|_| ()))
+    })
+}
+
+unsafe extern "C" fn get_available_bytes<P: StreamPeer>(user: *const libc::c_void) -> libc::c_int {
+    with_peer(user, 0, |peer: &P| to_c_int(peer.get_available_bytes()))
+}
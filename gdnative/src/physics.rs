@@ -0,0 +1,526 @@
+//! Typed wrappers for physics space queries.
+//!
+//! The query methods of [`PhysicsDirectSpaceState`] and [`Physics2DDirectSpaceState`] take
+//! exclusion lists as untyped arrays and return their results as dictionaries. The functions in
+//! this module wrap them, using [`QueryFilter`] to describe which objects should be considered,
+//! and returning structs like [`RayHit`] instead:
+//!
+//! ```no_run
+//! use gdnative::api::KinematicBody;
+//! use gdnative::physics::{self, CollisionMask, QueryFilter};
+//! use gdnative::prelude::*;
+//!
+//! fn ground_below(body: &KinematicBody) -> Option<Vector3> {
+//!     let world = body.get_world()?;
+//!     let space = unsafe { world.assume_safe() }.direct_space_state()?;
+//!     let space = unsafe { space.assume_safe() };
+//!
+//!     let from = body.global_transform().origin;
+//!     let filter = QueryFilter::new()
+//!         .with_mask(CollisionMask::layer(1) | CollisionMask::layer(3))
+//!         .with_excluded(body);
+//!
+//!     physics::intersect_ray(&space, from, from - Vector3::UP * 10.0, &filter)
+//!         .map(|hit| hit.position)
+//! }
+//! ```
+//!
+//! Like the underlying methods, these functions may only be called while the physics space is
+//! not being stepped, i.e. usually from `_physics_process`.
+
+use std::ops::{BitOr, BitOrAssign};
+
+use crate::api::{
+    CollisionObject, CollisionObject2D, Object, Physics2DDirectSpaceState,
+    Physics2DShapeQueryParameters, PhysicsDirectSpaceState, PhysicsShapeQueryParameters,
+};
+use crate::core_types::{
+    Dictionary, FromVariant, Rid, ToVariant, Variant, VariantArray, Vector2, Vector3,
+};
+use crate::object::ownership::Shared;
+use crate::object::{AsArg, Ref};
+
+/// Set of physics layers, as used for collision layers and masks.
+///
+/// Layers are numbered from 1 to 32, like in the editor.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CollisionMask(u32);
+
+impl CollisionMask {
+    /// Mask without any layers.
+    pub const NONE: Self = CollisionMask(0);
+
+    /// Mask with all layers that can be set from the editor. This is the default used by the
+    /// engine for queries.
+    pub const ALL: Self = CollisionMask(0x7FFF_FFFF);
+
+    /// Returns a mask with only `layer` set.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is not in the range `1..=32`.
+    #[inline]
+    pub const fn layer(layer: u32) -> Self {
+        assert!(
+            layer >= 1 && layer <= 32,
+            "layer must be in the range 1..=32"
+        );
+        CollisionMask(1 << (layer - 1))
+    }
+
+    /// Creates a mask from its bit representation, where bit 0 corresponds to layer 1.
+    #[inline]
+    pub const fn from_bits(bits: u32) -> Self {
+        CollisionMask(bits)
+    }
+
+    /// Returns the bit representation of the mask, where bit 0 corresponds to layer 1.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns a copy of the mask with `layer` added.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is not in the range `1..=32`.
+    #[inline]
+    pub const fn with_layer(self, layer: u32) -> Self {
+        CollisionMask(self.0 | Self::layer(layer).0)
+    }
+
+    /// Returns a copy of the mask with `layer` removed.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is not in the range `1..=32`.
+    #[inline]
+    pub const fn without_layer(self, layer: u32) -> Self {
+        CollisionMask(self.0 & !Self::layer(layer).0)
+    }
+
+    /// Returns `true` if `layer` is in the mask.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is not in the range `1..=32`.
+    #[inline]
+    pub const fn contains(self, layer: u32) -> bool {
+        self.0 & Self::layer(layer).0 != 0
+    }
+}
+
+impl Default for CollisionMask {
+    #[inline]
+    fn default() -> Self {
+        CollisionMask::ALL
+    }
+}
+
+impl BitOr for CollisionMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        CollisionMask(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for CollisionMask {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// List of collision objects to be ignored by a query, identified by their RIDs.
+#[derive(Clone, Debug, Default)]
+pub struct ExclusionList {
+    rids: Vec<Rid>,
+}
+
+impl ExclusionList {
+    /// Creates an empty list.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the object with the RID `rid`.
+    #[inline]
+    pub fn push_rid(&mut self, rid: Rid) {
+        self.rids.push(rid);
+    }
+
+    /// Adds a 3D collision object.
+    #[inline]
+    pub fn push(&mut self, object: &CollisionObject) {
+        self.push_rid(object.get_rid());
+    }
+
+    /// Adds a 2D collision object.
+    #[inline]
+    pub fn push_2d(&mut self, object: &CollisionObject2D) {
+        self.push_rid(object.get_rid());
+    }
+
+    /// Returns the RIDs in the list.
+    #[inline]
+    pub fn rids(&self) -> &[Rid] {
+        &self.rids
+    }
+
+    /// Returns the number of objects in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rids.len()
+    }
+
+    /// Returns `true` if the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rids.is_empty()
+    }
+
+    fn to_variant_array(&self) -> VariantArray {
+        self.rids
+            .iter()
+            .map(Rid::to_variant)
+            .collect::<VariantArray<_>>()
+            .into_shared()
+    }
+}
+
+impl FromIterator<Rid> for ExclusionList {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Rid>>(iter: I) -> Self {
+        ExclusionList {
+            rids: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Describes which objects are considered by a query.
+///
+/// By default, all bodies on layers that can be set from the editor are considered, and areas
+/// are ignored, like in the engine.
+#[derive(Clone, Debug)]
+pub struct QueryFilter {
+    exclude: ExclusionList,
+    mask: CollisionMask,
+    collide_with_bodies: bool,
+    collide_with_areas: bool,
+}
+
+impl Default for QueryFilter {
+    #[inline]
+    fn default() -> Self {
+        QueryFilter {
+            exclude: ExclusionList::new(),
+            mask: CollisionMask::ALL,
+            collide_with_bodies: true,
+            collide_with_areas: false,
+        }
+    }
+}
+
+impl QueryFilter {
+    /// Creates the default filter.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only considers objects on layers in `mask`.
+    #[inline]
+    pub fn with_mask(mut self, mask: CollisionMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets whether `PhysicsBody`s are considered.
+    #[inline]
+    pub fn with_bodies(mut self, enable: bool) -> Self {
+        self.collide_with_bodies = enable;
+        self
+    }
+
+    /// Sets whether `Area`s are considered.
+    #[inline]
+    pub fn with_areas(mut self, enable: bool) -> Self {
+        self.collide_with_areas = enable;
+        self
+    }
+
+    /// Ignores all objects in `exclude`, replacing the current exclusion list.
+    #[inline]
+    pub fn with_exclusions(mut self, exclude: ExclusionList) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Ignores a 3D collision object, typically the one making the query.
+    #[inline]
+    pub fn with_excluded(mut self, object: &CollisionObject) -> Self {
+        self.exclude.push(object);
+        self
+    }
+
+    /// Ignores a 2D collision object, typically the one making the query.
+    #[inline]
+    pub fn with_excluded_2d(mut self, object: &CollisionObject2D) -> Self {
+        self.exclude.push_2d(object);
+        self
+    }
+
+    /// Returns the collision mask.
+    #[inline]
+    pub fn mask(&self) -> CollisionMask {
+        self.mask
+    }
+
+    /// Returns the exclusion list.
+    #[inline]
+    pub fn exclusions(&self) -> &ExclusionList {
+        &self.exclude
+    }
+
+    /// Applies the filter to the parameters of a 3D shape query.
+    #[inline]
+    pub fn apply(&self, params: &PhysicsShapeQueryParameters) {
+        params.set_collision_mask(self.mask.bits().into());
+        params.set_exclude(self.exclude.to_variant_array());
+        params.set_collide_with_bodies(self.collide_with_bodies);
+        params.set_collide_with_areas(self.collide_with_areas);
+    }
+
+    /// Applies the filter to the parameters of a 2D shape query.
+    #[inline]
+    pub fn apply_2d(&self, params: &Physics2DShapeQueryParameters) {
+        params.set_collision_layer(self.mask.bits().into());
+        params.set_exclude(self.exclude.to_variant_array());
+        params.set_collide_with_bodies(self.collide_with_bodies);
+        params.set_collide_with_areas(self.collide_with_areas);
+    }
+}
+
+/// Result of a 3D ray query.
+#[derive(Clone, Debug)]
+pub struct RayHit {
+    /// Intersection point, in global coordinates.
+    pub position: Vector3,
+    /// Surface normal at the intersection point.
+    pub normal: Vector3,
+    /// The colliding object, or `None` if it's not an object, e.g. a body created through the
+    /// physics server.
+    pub collider: Option<Ref<Object, Shared>>,
+    /// Instance ID of the colliding object.
+    pub collider_id: i64,
+    /// RID of the colliding object.
+    pub rid: Rid,
+    /// Index of the colliding shape within the object.
+    pub shape: i64,
+}
+
+/// Result of a 2D ray query.
+#[derive(Clone, Debug)]
+pub struct RayHit2D {
+    /// Intersection point, in global coordinates.
+    pub position: Vector2,
+    /// Surface normal at the intersection point.
+    pub normal: Vector2,
+    /// The colliding object, or `None` if it's not an object, e.g. a body created through the
+    /// physics server.
+    pub collider: Option<Ref<Object, Shared>>,
+    /// Instance ID of the colliding object.
+    pub collider_id: i64,
+    /// RID of the colliding object.
+    pub rid: Rid,
+    /// Index of the colliding shape within the object.
+    pub shape: i64,
+    /// Metadata of the colliding shape.
+    pub metadata: Variant,
+}
+
+/// Result of a 3D shape or point query.
+#[derive(Clone, Debug)]
+pub struct ShapeHit {
+    /// The colliding object, or `None` if it's not an object.
+    pub collider: Option<Ref<Object, Shared>>,
+    /// Instance ID of the colliding object.
+    pub collider_id: i64,
+    /// RID of the colliding object.
+    pub rid: Rid,
+    /// Index of the colliding shape within the object.
+    pub shape: i64,
+}
+
+/// Result of a 2D shape or point query.
+#[derive(Clone, Debug)]
+pub struct ShapeHit2D {
+    /// The colliding object, or `None` if it's not an object.
+    pub collider: Option<Ref<Object, Shared>>,
+    /// Instance ID of the colliding object.
+    pub collider_id: i64,
+    /// RID of the colliding object.
+    pub rid: Rid,
+    /// Index of the colliding shape within the object.
+    pub shape: i64,
+    /// Metadata of the colliding shape.
+    pub metadata: Variant,
+}
+
+/// Intersects a ray from `from` to `to` with the 3D space, returning the closest hit.
+#[inline]
+pub fn intersect_ray(
+    space: &PhysicsDirectSpaceState,
+    from: Vector3,
+    to: Vector3,
+    filter: &QueryFilter,
+) -> Option<RayHit> {
+    let dict = space.intersect_ray(
+        from,
+        to,
+        filter.exclude.to_variant_array(),
+        filter.mask.bits().into(),
+        filter.collide_with_bodies,
+        filter.collide_with_areas,
+    );
+
+    Some(RayHit {
+        position: field(&dict, "position")?,
+        normal: field(&dict, "normal")?,
+        collider: field(&dict, "collider"),
+        collider_id: field(&dict, "collider_id")?,
+        rid: field(&dict, "rid")?,
+        shape: field(&dict, "shape")?,
+    })
+}
+
+/// Intersects a ray from `from` to `to` with the 2D space, returning the closest hit.
+#[inline]
+pub fn intersect_ray_2d(
+    space: &Physics2DDirectSpaceState,
+    from: Vector2,
+    to: Vector2,
+    filter: &QueryFilter,
+) -> Option<RayHit2D> {
+    let dict = space.intersect_ray(
+        from,
+        to,
+        filter.exclude.to_variant_array(),
+        filter.mask.bits().into(),
+        filter.collide_with_bodies,
+        filter.collide_with_areas,
+    );
+
+    Some(RayHit2D {
+        position: field(&dict, "position")?,
+        normal: field(&dict, "normal")?,
+        collider: field(&dict, "collider"),
+        collider_id: field(&dict, "collider_id")?,
+        rid: field(&dict, "rid")?,
+        shape: field(&dict, "shape")?,
+        metadata: dict.get("metadata").unwrap_or_default(),
+    })
+}
+
+/// Returns up to `max_results` objects whose shapes contain `point`.
+#[inline]
+pub fn intersect_point(
+    space: &PhysicsDirectSpaceState,
+    point: Vector3,
+    max_results: usize,
+    filter: &QueryFilter,
+) -> Vec<ShapeHit> {
+    let results = space.intersect_point(
+        point,
+        max_results as i64,
+        filter.exclude.to_variant_array(),
+        filter.mask.bits().into(),
+        filter.collide_with_bodies,
+        filter.collide_with_areas,
+    );
+
+    shape_hits(&results)
+}
+
+/// Returns up to `max_results` objects whose shapes contain `point`.
+#[inline]
+pub fn intersect_point_2d(
+    space: &Physics2DDirectSpaceState,
+    point: Vector2,
+    max_results: usize,
+    filter: &QueryFilter,
+) -> Vec<ShapeHit2D> {
+    let results = space.intersect_point(
+        point,
+        max_results as i64,
+        filter.exclude.to_variant_array(),
+        filter.mask.bits().into(),
+        filter.collide_with_bodies,
+        filter.collide_with_areas,
+    );
+
+    shape_hits_2d(&results)
+}
+
+/// Returns up to `max_results` objects intersecting the shape described by `params`.
+///
+/// A [`QueryFilter`] can be applied to `params` with [`QueryFilter::apply`].
+#[inline]
+pub fn intersect_shape(
+    space: &PhysicsDirectSpaceState,
+    params: impl AsArg<PhysicsShapeQueryParameters>,
+    max_results: usize,
+) -> Vec<ShapeHit> {
+    shape_hits(&space.intersect_shape(params, max_results as i64))
+}
+
+/// Returns up to `max_results` objects intersecting the shape described by `params`.
+///
+/// A [`QueryFilter`] can be applied to `params` with [`QueryFilter::apply_2d`].
+#[inline]
+pub fn intersect_shape_2d(
+    space: &Physics2DDirectSpaceState,
+    params: impl AsArg<Physics2DShapeQueryParameters>,
+    max_results: usize,
+) -> Vec<ShapeHit2D> {
+    shape_hits_2d(&space.intersect_shape(params, max_results as i64))
+}
+
+fn field<T: FromVariant>(dict: &Dictionary, key: &str) -> Option<T> {
+    dict.get(key).and_then(|v| T::from_variant(&v).ok())
+}
+
+fn shape_hits(results: &VariantArray) -> Vec<ShapeHit> {
+    results
+        .iter()
+        .filter_map(|v| {
+            let dict = Dictionary::from_variant(&v).ok()?;
+            Some(ShapeHit {
+                collider: field(&dict, "collider"),
+                collider_id: field(&dict, "collider_id")?,
+                rid: field(&dict, "rid")?,
+                shape: field(&dict, "shape")?,
+            })
+        })
+        .collect()
+}
+
+fn shape_hits_2d(results: &VariantArray) -> Vec<ShapeHit2D> {
+    results
+        .iter()
+        .filter_map(|v| {
+            let dict = Dictionary::from_variant(&v).ok()?;
+            Some(ShapeHit2D {
+                collider: field(&dict, "collider"),
+                collider_id: field(&dict, "collider_id")?,
+                rid: field(&dict, "rid")?,
+                shape: field(&dict, "shape")?,
+                metadata: dict.get("metadata").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,117 @@
+//! Access to the Java environment of the Android app.
+//!
+//! The handles returned here are raw JNI pointers, to be used with a JNI binding such as the
+//! `jni` crate:
+//!
+//! ```ignore
+//! use gdnative::platform::android;
+//! use jni::objects::JObject;
+//! use jni::JNIEnv;
+//!
+//! fn vibrate() -> Option<()> {
+//!     let env = android::jni_env()?;
+//!     let activity = android::activity()?;
+//!
+//!     // SAFETY: the pointers come from the engine, and `env` is used on the current thread.
+//!     let mut env = unsafe { JNIEnv::from_raw(env.as_ptr().cast()) }.ok()?;
+//!     let activity = unsafe { JObject::from_raw(activity.as_ptr().cast()) };
+//!     // ...
+//!     Some(())
+//! }
+//! ```
+//!
+//! All functions can be called on every platform. Outside of Android, or before the library is
+//! initialized, they return `None` or `false`.
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::libc::c_void;
+use crate::private::get_android_api;
+
+/// The `JNIEnv` of the current thread.
+///
+/// A `JNIEnv` is only valid on the thread it was obtained on, so this type is neither `Send`
+/// nor `Sync`. Obtain a new one with [`jni_env`] on each thread instead.
+#[derive(Copy, Clone, Debug)]
+pub struct JniEnv {
+    ptr: NonNull<c_void>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl JniEnv {
+    /// Returns the raw `JNIEnv*` pointer.
+    #[inline]
+    pub fn as_ptr(self) -> *mut c_void {
+        self.ptr.as_ptr()
+    }
+}
+
+/// A JNI global reference owned by the engine.
+///
+/// Global references are valid on any thread attached to the JVM, for as long as the engine
+/// keeps them alive. They must not be deleted with `DeleteGlobalRef`: create a new global
+/// reference instead if the object has to outlive the engine's.
+#[derive(Copy, Clone, Debug)]
+pub struct GlobalRef {
+    ptr: NonNull<c_void>,
+}
+
+// SAFETY: global references are not tied to a thread.
+unsafe impl Send for GlobalRef {}
+unsafe impl Sync for GlobalRef {}
+
+impl GlobalRef {
+    /// Returns the raw `jobject` pointer.
+    #[inline]
+    pub fn as_ptr(self) -> *mut c_void {
+        self.ptr.as_ptr()
+    }
+}
+
+/// Returns `true` if the engine provides the Android API, i.e. if running on Android.
+#[inline]
+pub fn is_available() -> bool {
+    jni_env().is_some()
+}
+
+/// Returns the `JNIEnv` of the current thread. The engine attaches the thread to the JVM if
+/// needed.
+#[inline]
+pub fn jni_env() -> Option<JniEnv> {
+    let get_env = get_android_api()?.godot_android_get_env?;
+    // SAFETY: the function takes no arguments, and returns null outside of Android.
+    let ptr = NonNull::new(unsafe { get_env() })?;
+    Some(JniEnv {
+        ptr,
+        _not_send: PhantomData,
+    })
+}
+
+/// Returns the `android.app.Activity` the engine is running in.
+#[inline]
+pub fn activity() -> Option<GlobalRef> {
+    let get_activity = get_android_api()?.godot_android_get_activity?;
+    // SAFETY: the function takes no arguments, and returns null outside of Android.
+    let ptr = NonNull::new(unsafe { get_activity() })?;
+    Some(GlobalRef { ptr })
+}
+
+/// Returns the `android.view.Surface` the engine renders to, if it exists yet.
+#[inline]
+pub fn surface() -> Option<GlobalRef> {
+    let get_surface = get_android_api()?.godot_android_get_surface?;
+    // SAFETY: the function takes no arguments, and returns null outside of Android.
+    let ptr = NonNull::new(unsafe { get_surface() })?;
+    Some(GlobalRef { ptr })
+}
+
+/// Returns `true` if the activity is in the resumed state, i.e. in the foreground and
+/// receiving input.
+#[inline]
+pub fn is_activity_resumed() -> bool {
+    get_android_api()
+        .and_then(|api| api.godot_android_is_activity_resumed)
+        // SAFETY: the function takes no arguments, and returns false outside of Android.
+        .is_some_and(|is_resumed| unsafe { is_resumed() })
+}
@@ -0,0 +1,6 @@
+//! Access to platform-specific handles exposed by the engine, for integrating platform SDKs.
+//!
+//! Godot 3 only exposes such handles through GDNative on Android, see the [`android`] module.
+//! There is no equivalent extension for iOS.
+
+pub mod android;
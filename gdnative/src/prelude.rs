@@ -13,17 +13,17 @@ pub use gdnative_core::core_types::{
     FromVariant, FromVariantError, OwnedToVariant, ToVariant, ToVariantEq,
 };
 pub use gdnative_core::export::{
-    ClassBuilder, ExportInfo, Method, MethodBuilder, NativeClass, NativeClassMethods, Property,
-    PropertyUsage, SignalBuilder, SignalParam,
+    ClassBuilder, ConnectFlags, ExportInfo, Method, MethodBuilder, NativeClass, NativeClassMethods,
+    Property, PropertyUsage, SignalBuilder, SignalParam,
 };
 pub use gdnative_core::init::{GDNativeCallbacks, InitHandle};
 pub use gdnative_core::object::{
     memory::{ManuallyManaged, RefCounted},
     ownership::{Shared, ThreadLocal, Unique},
-    AsArg, GodotObject, Instance, Instanciable, NewRef, Null, QueueFree, Ref, SubClass, TInstance,
-    TRef,
+    AsArg, GodotObject, Instance, InstanceId, Instanciable, NewRef, Null, QueueFree, Ref, SubClass,
+    TInstance, TRef,
 };
-pub use gdnative_core::{godot_dbg, godot_error, godot_print, godot_warn};
+pub use gdnative_core::{dict, godot_dbg, godot_error, godot_print, godot_warn, varray};
 #[allow(deprecated)]
 pub use gdnative_core::{
     godot_gdnative_init, godot_gdnative_terminate, godot_init, godot_nativescript_init,
@@ -34,7 +34,7 @@ pub use gdnative_derive::*;
 pub mod user_data {
     // Re-export selected user_data types, but keep qualified due to rather generic names
     pub use gdnative_core::export::user_data::{
-        Aether, ArcData, LocalCellData, MutexData, RwLockData,
+        Aether, ArcData, LocalCellData, MutexData, OnceData, RwLockData,
     };
 }
 #[doc(inline)]
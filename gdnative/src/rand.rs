@@ -0,0 +1,206 @@
+//! Deterministic random numbers backed by the engine.
+//!
+//! [`GodotRng`] wraps a [`RandomNumberGenerator`], so sequences generated from Rust match the
+//! ones GDScript generates with the same seed. Its state can be captured as an [`RngState`] and
+//! restored later, e.g. to roll back a simulation for netcode, or to replay a recorded session:
+//!
+//! ```no_run
+//! use gdnative::rand::GodotRng;
+//!
+//! let mut rng = GodotRng::with_seed(42);
+//! let snapshot = rng.snapshot();
+//! let first = rng.randi_range(1, 6);
+//!
+//! rng.restore(snapshot);
+//! assert_eq!(first, rng.randi_range(1, 6));
+//! ```
+//!
+//! With the `rand` feature, `GodotRng` implements `RngCore` and `SeedableRng` from
+//! [`rand_core`](https://docs.rs/rand_core), so it can be used with the rest of the `rand`
+//! ecosystem. With the `serde` feature, `RngState` can be serialized.
+
+use std::fmt;
+
+use crate::api::RandomNumberGenerator;
+use crate::object::ownership::Unique;
+use crate::object::Ref;
+
+/// Snapshot of the state of a [`GodotRng`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RngState {
+    /// Seed the generator was initialized with.
+    pub seed: u64,
+    /// Current state of the generator.
+    pub state: u64,
+}
+
+/// Random number generator using the engine's PCG32 implementation.
+///
+/// Each `GodotRng` owns its own `RandomNumberGenerator`, so sequences of different generators
+/// are independent of each other and of the global functions like `randi` in GDScript.
+pub struct GodotRng {
+    rng: Ref<RandomNumberGenerator, Unique>,
+}
+
+impl GodotRng {
+    /// Creates a generator with a time-based seed.
+    #[inline]
+    pub fn new() -> Self {
+        let rng = RandomNumberGenerator::new();
+        rng.randomize();
+        GodotRng { rng }
+    }
+
+    /// Creates a generator with the given seed. Generators with the same seed produce the same
+    /// sequences, both in Rust and in GDScript.
+    #[inline]
+    pub fn with_seed(seed: u64) -> Self {
+        let rng = RandomNumberGenerator::new();
+        rng.set_seed(seed as i64);
+        GodotRng { rng }
+    }
+
+    /// Creates a generator from a snapshot taken with [`snapshot`](Self::snapshot).
+    #[inline]
+    pub fn from_state(state: RngState) -> Self {
+        let mut rng = Self::with_seed(state.seed);
+        rng.restore(state);
+        rng
+    }
+
+    /// Returns the seed the generator was initialized with.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.rng.seed() as u64
+    }
+
+    /// Returns a snapshot of the current state, which can be passed to
+    /// [`restore`](Self::restore) to continue the sequence from this point again.
+    #[inline]
+    pub fn snapshot(&self) -> RngState {
+        RngState {
+            seed: self.rng.seed() as u64,
+            state: self.rng.state() as u64,
+        }
+    }
+
+    /// Restores a snapshot taken with [`snapshot`](Self::snapshot). The following values are
+    /// the same that followed the snapshot originally.
+    #[inline]
+    pub fn restore(&mut self, state: RngState) {
+        // Setting the seed resets the state, so it must come first.
+        self.rng.set_seed(state.seed as i64);
+        self.rng.set_state(state.state as i64);
+    }
+
+    /// Returns a uniformly distributed 32-bit integer.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.rng.randi() as u32
+    }
+
+    /// Returns a uniformly distributed float between `0.0` and `1.0`, inclusive.
+    #[inline]
+    pub fn randf(&mut self) -> f64 {
+        self.rng.randf()
+    }
+
+    /// Returns a uniformly distributed float between `from` and `to`, inclusive.
+    #[inline]
+    pub fn randf_range(&mut self, from: f64, to: f64) -> f64 {
+        self.rng.randf_range(from, to)
+    }
+
+    /// Returns a normally distributed float with the given `mean` and `deviation`.
+    #[inline]
+    pub fn randfn(&mut self, mean: f64, deviation: f64) -> f64 {
+        self.rng.randfn(mean, deviation)
+    }
+
+    /// Returns a uniformly distributed integer between `from` and `to`, inclusive.
+    #[inline]
+    pub fn randi_range(&mut self, from: i64, to: i64) -> i64 {
+        self.rng.randi_range(from, to)
+    }
+
+    /// Returns the underlying `RandomNumberGenerator`.
+    #[inline]
+    pub fn as_engine(&self) -> &RandomNumberGenerator {
+        &self.rng
+    }
+
+    /// Returns the underlying `RandomNumberGenerator`, e.g. to pass it to GDScript.
+    #[inline]
+    pub fn into_engine(self) -> Ref<RandomNumberGenerator, Unique> {
+        self.rng
+    }
+}
+
+impl Default for GodotRng {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clones the generator, including its current state. Both generators produce the same values
+/// afterwards.
+impl Clone for GodotRng {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::from_state(self.snapshot())
+    }
+}
+
+impl fmt::Debug for GodotRng {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let RngState { seed, state } = self.snapshot();
+        f.debug_struct("GodotRng")
+            .field("seed", &seed)
+            .field("state", &state)
+            .finish()
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand_core::RngCore for GodotRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        GodotRng::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand_core::SeedableRng for GodotRng {
+    type Seed = [u8; 8];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::with_seed(u64::from_le_bytes(seed))
+    }
+
+    /// Uses `state` as the engine seed directly, so the sequence matches the one of a
+    /// `RandomNumberGenerator` with the same seed.
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::with_seed(state)
+    }
+}
@@ -0,0 +1,241 @@
+//! Utilities for working with the scene tree.
+//!
+//! [`TreeBatch`] collects changes to the tree, such as adding, removing or moving nodes, and
+//! applies them all at once. This is useful when spawning many nodes procedurally:
+//!
+//! ```no_run
+//! use gdnative::prelude::*;
+//! use gdnative::scene::TreeBatch;
+//!
+//! fn spawn(level: &Node, count: usize) {
+//!     let mut batch = TreeBatch::new();
+//!     for _ in 0..count {
+//!         batch.add_child(level, &Node2D::new());
+//!     }
+//!
+//!     // Applied at idle time, when the level is not busy setting up its own children.
+//!     batch.apply_deferred();
+//! }
+//! ```
+//!
+//! [`NodeSnapshot`] captures the structure of a subtree for regression tests of scene
+//! manipulation logic, and compares it against a stored baseline.
+
+use std::collections::HashMap;
+
+use crate::api::Node;
+use crate::core_types::{ToVariant, Variant};
+use crate::object::{GodotObject, HandlePolicy, InstanceId, ObjectHandle, Ref};
+
+mod snapshot;
+
+pub use snapshot::{
+    NodeSnapshot, ParseSnapshotError, SnapshotChange, SnapshotOptions, UPDATE_SNAPSHOTS_VAR,
+};
+
+/// Queue of scene tree changes that are applied at once.
+///
+/// Each node is changed at most once when the batch is applied. Later operations on a node
+/// replace the earlier ones, so that e.g. adding a node and removing it again leaves the tree
+/// untouched. Operations on different nodes are applied in the order they were queued in, which
+/// determines the order of siblings added to the same parent.
+///
+/// The batch only stores instance IDs of the nodes. Nodes that are freed before the batch is
+/// applied are skipped.
+#[derive(Debug, Default)]
+pub struct TreeBatch {
+    ops: Vec<Option<Op>>,
+    index: HashMap<InstanceId, usize>,
+}
+
+#[derive(Debug)]
+struct Op {
+    child: ObjectHandle<Node>,
+    target: Target,
+}
+
+#[derive(Debug)]
+enum Target {
+    Parent {
+        parent: ObjectHandle<Node>,
+        legible_unique_name: bool,
+    },
+    Detached {
+        parent: ObjectHandle<Node>,
+    },
+}
+
+/// An operation resolved against the current state of the tree.
+struct Step {
+    child: Ref<Node>,
+    from: Option<Ref<Node>>,
+    to: Option<(Ref<Node>, bool)>,
+}
+
+impl TreeBatch {
+    /// Creates an empty batch.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues adding `child` to `parent`, like `Node::add_child`.
+    ///
+    /// If `child` has a different parent when the batch is applied, it is removed from that
+    /// parent first.
+    #[inline]
+    pub fn add_child(&mut self, parent: &Node, child: &Node) {
+        self.add_child_with_name(parent, child, false);
+    }
+
+    /// Queues adding `child` to `parent`, with the same meaning of `legible_unique_name` as in
+    /// `Node::add_child`.
+    #[inline]
+    pub fn add_child_with_name(&mut self, parent: &Node, child: &Node, legible: bool) {
+        self.push(
+            child,
+            Target::Parent {
+                parent: handle(parent),
+                legible_unique_name: legible,
+            },
+        );
+    }
+
+    /// Queues removing `child` from `parent`, like `Node::remove_child`. Nothing happens if
+    /// `child` is not a child of `parent` when the batch is applied.
+    ///
+    /// The child is not freed.
+    #[inline]
+    pub fn remove_child(&mut self, parent: &Node, child: &Node) {
+        self.push(
+            child,
+            Target::Detached {
+                parent: handle(parent),
+            },
+        );
+    }
+
+    /// Queues moving `child` from its current parent to `new_parent`.
+    #[inline]
+    pub fn reparent(&mut self, child: &Node, new_parent: &Node) {
+        self.add_child(new_parent, child);
+    }
+
+    /// Returns the number of nodes that will be changed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns `true` if no operations are queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Discards all queued operations.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ops.clear();
+        self.index.clear();
+    }
+
+    /// Applies all queued operations immediately.
+    ///
+    /// This must not be called while one of the affected parents is busy setting up its
+    /// children, e.g. from `_ready` of a sibling. Use [`Self::apply_deferred`] in such cases.
+    #[inline]
+    pub fn apply(self) {
+        for step in self.resolve() {
+            // SAFETY: the nodes were checked to be alive, and scene tree changes are only
+            // allowed on the main thread.
+            unsafe {
+                let child = step.child.assume_safe();
+                if let Some(from) = step.from {
+                    from.assume_safe().remove_child(child);
+                }
+                if let Some((to, legible)) = step.to {
+                    to.assume_safe().add_child(child, legible);
+                }
+            }
+        }
+    }
+
+    /// Applies all queued operations at idle time, using `Object::call_deferred`.
+    ///
+    /// Nodes are looked up when this is called: the current parent of each child is the one it
+    /// will be removed from.
+    #[inline]
+    pub fn apply_deferred(self) {
+        for step in self.resolve() {
+            let child = step.child.to_variant();
+            // SAFETY: the nodes were checked to be alive, and both methods are called with
+            // arguments of the right types.
+            unsafe {
+                if let Some(from) = step.from {
+                    from.assume_safe()
+                        .call_deferred("remove_child", std::slice::from_ref(&child));
+                }
+                if let Some((to, legible)) = step.to {
+                    to.assume_safe()
+                        .call_deferred("add_child", &[child, Variant::new(legible)]);
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, child: &Node, target: Target) {
+        let child = handle(child);
+        if let Some(index) = self.index.insert(child.instance_id(), self.ops.len()) {
+            self.ops[index] = None;
+        }
+        self.ops.push(Some(Op { child, target }));
+    }
+
+    /// Looks up the queued nodes, skipping operations that would not change the tree.
+    fn resolve(self) -> Vec<Step> {
+        self.ops
+            .into_iter()
+            .flatten()
+            .filter_map(|op| {
+                // SAFETY: the references are only used to look up the current parent.
+                let child = unsafe { op.child.get() }?;
+                let current = child.get_parent();
+                let current_id = current
+                    .as_ref()
+                    .map(|parent| unsafe { parent.assume_safe() }.instance_id());
+
+                match op.target {
+                    Target::Parent {
+                        parent,
+                        legible_unique_name,
+                    } => {
+                        let parent_ref = unsafe { parent.get() }?;
+                        if current_id == Some(parent.instance_id()) {
+                            return None;
+                        }
+                        Some(Step {
+                            child: child.claim(),
+                            from: current,
+                            to: Some((parent_ref.claim(), legible_unique_name)),
+                        })
+                    }
+                    Target::Detached { parent } => {
+                        if current_id != Some(parent.instance_id()) {
+                            return None;
+                        }
+                        Some(Step {
+                            child: child.claim(),
+                            from: current,
+                            to: None,
+                        })
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+fn handle(node: &Node) -> ObjectHandle<Node> {
+    ObjectHandle::new(node).with_policy(HandlePolicy::Silent)
+}
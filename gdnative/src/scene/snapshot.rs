@@ -0,0 +1,662 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::api::{NativeScript, Node, Resource};
+
+/// Environment variable that makes [`NodeSnapshot::assert_baseline`] overwrite baselines
+/// instead of comparing against them, when set to any value.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "GDNATIVE_UPDATE_SNAPSHOTS";
+
+/// Options for capturing [`NodeSnapshot`]s.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotOptions {
+    properties: Vec<String>,
+    max_depth: Option<usize>,
+}
+
+impl SnapshotOptions {
+    /// Creates options that capture names, classes and scripts of the whole subtree.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the value of the property `name` for each node that has it.
+    #[inline]
+    pub fn property(mut self, name: impl Into<String>) -> Self {
+        self.properties.push(name.into());
+        self
+    }
+
+    /// Only captures nodes up to `depth` levels below the root. A depth of `0` captures only
+    /// the root.
+    #[inline]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Captures the subtree of `root`.
+    #[inline]
+    pub fn capture(&self, root: &Node) -> NodeSnapshot {
+        self.capture_node(root, 0)
+    }
+
+    fn capture_node(&self, node: &Node, depth: usize) -> NodeSnapshot {
+        let script = node.get_script().and_then(|script| {
+            // SAFETY: the script is kept alive by the node, which is borrowed.
+            let script = unsafe { script.assume_safe() };
+            if let Some(native_script) = script.cast::<NativeScript>() {
+                Some(native_script.class_name().to_string())
+            } else {
+                script
+                    .cast::<Resource>()
+                    .map(|resource| resource.path().to_string())
+            }
+        });
+
+        let properties = self
+            .properties
+            .iter()
+            .filter_map(|name| {
+                let value = node.get(name.as_str());
+                (!value.is_nil()).then(|| (name.clone(), format!("{value:?}")))
+            })
+            .collect();
+
+        let children = if self.max_depth.map_or(true, |max| depth < max) {
+            (0..node.get_child_count())
+                .filter_map(|idx| node.get_child(idx))
+                .map(|child| {
+                    // SAFETY: children are kept alive by their parent, which is borrowed and not
+                    // modified while capturing.
+                    let child = unsafe { child.assume_safe() };
+                    self.capture_node(&child, depth + 1)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        NodeSnapshot {
+            name: node.name().to_string(),
+            class: node.get_class().to_string(),
+            script,
+            properties,
+            children,
+        }
+    }
+}
+
+/// Snapshot of a scene subtree, for comparing it against a baseline in tests.
+///
+/// A snapshot records the name, engine class and script of each node, and optionally the
+/// values of selected properties, which are formatted with `Debug`. Snapshots can be written to
+/// and parsed from an indented text format, which is readable in diffs of stored baselines:
+///
+/// ```text
+/// Level (Node2D)
+///   Player (KinematicBody2D)
+///     .script = Player
+///     .position = Vector2((100, 50))
+///     Sprite (Sprite)
+///   Enemies (Node2D)
+/// ```
+///
+/// Snapshots are compared with [`diff`](Self::diff), which matches children by name and
+/// reports added, removed and reordered nodes, as well as changed classes, scripts and
+/// properties:
+///
+/// ```no_run
+/// use gdnative::prelude::*;
+/// use gdnative::scene::SnapshotOptions;
+///
+/// fn check_level(level: &Node) {
+///     let snapshot = SnapshotOptions::new().property("position").capture(level);
+///     snapshot.assert_baseline("tests/baselines/level.txt");
+/// }
+/// ```
+///
+/// Objects are formatted with their instance IDs, which change between runs, so properties
+/// holding objects should not be recorded.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct NodeSnapshot {
+    /// Name of the node.
+    pub name: String,
+    /// Engine class of the node, e.g. `Node2D`.
+    pub class: String,
+    /// Class name of a NativeScript, or resource path of another script attached to the node.
+    pub script: Option<String>,
+    /// Recorded property values, formatted with `Debug`. Properties that are `null` or that
+    /// the node doesn't have are left out.
+    pub properties: BTreeMap<String, String>,
+    /// Snapshots of the children, in order.
+    pub children: Vec<NodeSnapshot>,
+}
+
+impl NodeSnapshot {
+    /// Captures the names, classes and scripts of the subtree of `root`. Use
+    /// [`SnapshotOptions`] to record property values as well.
+    #[inline]
+    pub fn capture(root: &Node) -> Self {
+        SnapshotOptions::new().capture(root)
+    }
+
+    /// Returns the snapshot of the descendant at `path`, which is relative to this node, e.g.
+    /// `"Enemies/Goblin"`. An empty path refers to this node.
+    #[inline]
+    pub fn find(&self, path: &str) -> Option<&NodeSnapshot> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |node, name| {
+                node.children.iter().find(|child| child.name == name)
+            })
+    }
+
+    /// Returns the differences from `expected` to `self`. The snapshots are equal if the result
+    /// is empty.
+    ///
+    /// Children are matched by name, so a renamed node is reported as one removed and one added
+    /// node.
+    #[inline]
+    pub fn diff(&self, expected: &NodeSnapshot) -> Vec<SnapshotChange> {
+        let mut changes = Vec::new();
+        if self.name == expected.name {
+            diff_node(&self.name, expected, self, &mut changes);
+        } else {
+            changes.push(SnapshotChange::Removed {
+                path: expected.name.clone(),
+            });
+            changes.push(SnapshotChange::Added {
+                path: self.name.clone(),
+            });
+        }
+        changes
+    }
+
+    /// Asserts that the snapshot matches `expected`, given in the text format.
+    ///
+    /// # Panics
+    ///
+    /// If `expected` cannot be parsed, or if the snapshots differ. The message lists the
+    /// differences.
+    #[inline]
+    pub fn assert_matches(&self, expected: &str) {
+        let expected = expected
+            .parse::<NodeSnapshot>()
+            .unwrap_or_else(|err| panic!("invalid expected snapshot: {err}"));
+        self.assert_eq(&expected, "expected snapshot");
+    }
+
+    /// Asserts that the snapshot matches the baseline stored at `path`.
+    ///
+    /// If the file doesn't exist, or if the [`UPDATE_SNAPSHOTS_VAR`] environment variable is
+    /// set, the snapshot is written to `path` instead, creating the parent directories if
+    /// needed. Paths are resolved by the file system, not by Godot, so `res://` paths are not
+    /// supported.
+    ///
+    /// # Panics
+    ///
+    /// If the baseline cannot be read, parsed or written, or if the snapshots differ.
+    #[inline]
+    pub fn assert_baseline(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if !path.exists() || std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .unwrap_or_else(|err| panic!("cannot create {}: {err}", dir.display()));
+            }
+            std::fs::write(path, self.to_string())
+                .unwrap_or_else(|err| panic!("cannot write {}: {err}", path.display()));
+            return;
+        }
+
+        let expected = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("cannot read {}: {err}", path.display()));
+        let expected = expected
+            .parse::<NodeSnapshot>()
+            .unwrap_or_else(|err| panic!("invalid baseline {}: {err}", path.display()));
+        self.assert_eq(&expected, &format!("baseline {}", path.display()));
+    }
+
+    fn assert_eq(&self, expected: &NodeSnapshot, source: &str) {
+        let changes = self.diff(expected);
+        if !changes.is_empty() {
+            let mut message = format!("scene differs from {source}:");
+            for change in &changes {
+                let _ = write!(message, "\n  {change}");
+            }
+            let _ = write!(message, "\n\nactual snapshot:\n{self}");
+            panic!("{}", message);
+        }
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        writeln!(f, "{indent}{} ({})", self.name, self.class)?;
+        if let Some(script) = &self.script {
+            writeln!(f, "{indent}  .script = {}", escape(script))?;
+        }
+        for (name, value) in &self.properties {
+            writeln!(f, "{indent}  .{name} = {}", escape(value))?;
+        }
+        for child in &self.children {
+            child.write_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn diff_node(
+    path: &str,
+    expected: &NodeSnapshot,
+    actual: &NodeSnapshot,
+    changes: &mut Vec<SnapshotChange>,
+) {
+    if expected.class != actual.class {
+        changes.push(SnapshotChange::Class {
+            path: path.to_owned(),
+            expected: expected.class.clone(),
+            actual: actual.class.clone(),
+        });
+    }
+
+    if expected.script != actual.script {
+        changes.push(SnapshotChange::Script {
+            path: path.to_owned(),
+            expected: expected.script.clone(),
+            actual: actual.script.clone(),
+        });
+    }
+
+    let names = expected.properties.keys().chain(
+        actual
+            .properties
+            .keys()
+            .filter(|name| !expected.properties.contains_key(*name)),
+    );
+    for name in names {
+        let expected = expected.properties.get(name);
+        let actual = actual.properties.get(name);
+        if expected != actual {
+            changes.push(SnapshotChange::Property {
+                path: path.to_owned(),
+                property: name.clone(),
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            });
+        }
+    }
+
+    let child_path = |name: &str| format!("{path}/{name}");
+    let find = |children: &'_ [NodeSnapshot], name: &str| {
+        children
+            .iter()
+            .any(|child: &NodeSnapshot| child.name == name)
+    };
+
+    for child in &expected.children {
+        match actual.children.iter().find(|c| c.name == child.name) {
+            Some(actual_child) => diff_node(&child_path(&child.name), child, actual_child, changes),
+            None => changes.push(SnapshotChange::Removed {
+                path: child_path(&child.name),
+            }),
+        }
+    }
+
+    for child in &actual.children {
+        if !find(&expected.children, &child.name) {
+            changes.push(SnapshotChange::Added {
+                path: child_path(&child.name),
+            });
+        }
+    }
+
+    // Order of the children that are in both snapshots
+    let expected_order = expected
+        .children
+        .iter()
+        .filter(|child| find(&actual.children, &child.name))
+        .map(|child| child.name.clone())
+        .collect::<Vec<_>>();
+    let actual_order = actual
+        .children
+        .iter()
+        .filter(|child| find(&expected.children, &child.name))
+        .map(|child| child.name.clone())
+        .collect::<Vec<_>>();
+    if expected_order != actual_order {
+        changes.push(SnapshotChange::Order {
+            path: path.to_owned(),
+            expected: expected_order,
+            actual: actual_order,
+        });
+    }
+}
+
+/// A difference between two [`NodeSnapshot`]s. Paths start at the name of the root node.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SnapshotChange {
+    /// A node is only in the actual snapshot.
+    Added {
+        /// Path of the node.
+        path: String,
+    },
+    /// A node is only in the expected snapshot.
+    Removed {
+        /// Path of the node.
+        path: String,
+    },
+    /// The class of a node differs.
+    Class {
+        /// Path of the node.
+        path: String,
+        /// Expected class.
+        expected: String,
+        /// Actual class.
+        actual: String,
+    },
+    /// The script of a node differs.
+    Script {
+        /// Path of the node.
+        path: String,
+        /// Expected script.
+        expected: Option<String>,
+        /// Actual script.
+        actual: Option<String>,
+    },
+    /// The value of a property differs, or it's only recorded in one snapshot.
+    Property {
+        /// Path of the node.
+        path: String,
+        /// Name of the property.
+        property: String,
+        /// Expected value.
+        expected: Option<String>,
+        /// Actual value.
+        actual: Option<String>,
+    },
+    /// The children of a node that are in both snapshots are in a different order.
+    Order {
+        /// Path of the parent node.
+        path: String,
+        /// Expected order of the children.
+        expected: Vec<String>,
+        /// Actual order of the children.
+        actual: Vec<String>,
+    },
+}
+
+impl fmt::Display for SnapshotChange {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_none(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or("<none>")
+        }
+
+        match self {
+            SnapshotChange::Added { path } => write!(f, "+ {path}"),
+            SnapshotChange::Removed { path } => write!(f, "- {path}"),
+            SnapshotChange::Class {
+                path,
+                expected,
+                actual,
+            } => write!(f, "~ {path}: class {expected} -> {actual}"),
+            SnapshotChange::Script {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "~ {path}: script {} -> {}",
+                or_none(expected),
+                or_none(actual)
+            ),
+            SnapshotChange::Property {
+                path,
+                property,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "~ {path}.{property}: {} -> {}",
+                or_none(expected),
+                or_none(actual)
+            ),
+            SnapshotChange::Order {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "~ {path}: children [{}] -> [{}]",
+                expected.join(", "),
+                actual.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for NodeSnapshot {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+impl FromStr for NodeSnapshot {
+    type Err = ParseSnapshotError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Nodes that are still open, with their depths
+        let mut stack: Vec<(usize, NodeSnapshot)> = Vec::new();
+        let mut root = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let err = |message: &str| ParseSnapshotError {
+                line: index + 1,
+                message: message.to_owned(),
+            };
+
+            let content = line.trim_start_matches(' ');
+            if content.is_empty() {
+                continue;
+            }
+
+            let spaces = line.len() - content.len();
+            if spaces % 2 != 0 {
+                return Err(err("indentation should be a multiple of two spaces"));
+            }
+            let depth = spaces / 2;
+
+            if let Some(property) = content.strip_prefix('.') {
+                let (name, value) = property
+                    .split_once(" = ")
+                    .ok_or_else(|| err("expected `.property = value`"))?;
+                let node = match stack.last_mut() {
+                    Some((node_depth, node)) if *node_depth + 1 == depth => node,
+                    _ => return Err(err("property should be indented below its node")),
+                };
+                let value = unescape(value);
+                if name == "script" {
+                    node.script = Some(value);
+                } else {
+                    node.properties.insert(name.to_owned(), value);
+                }
+                continue;
+            }
+
+            let (name, class) = content
+                .strip_suffix(')')
+                .and_then(|content| content.rsplit_once(" ("))
+                .ok_or_else(|| err("expected `Name (Class)`"))?;
+
+            while stack
+                .last()
+                .is_some_and(|(node_depth, _)| *node_depth >= depth)
+            {
+                close(&mut stack, &mut root);
+            }
+
+            match stack.last() {
+                Some((parent_depth, _)) if parent_depth + 1 != depth => {
+                    return Err(err("node should be indented one level below its parent"));
+                }
+                None if depth != 0 || root.is_some() => {
+                    return Err(err("there should be a single root node"));
+                }
+                _ => {}
+            }
+
+            stack.push((
+                depth,
+                NodeSnapshot {
+                    name: name.to_owned(),
+                    class: class.to_owned(),
+                    ..NodeSnapshot::default()
+                },
+            ));
+        }
+
+        while !stack.is_empty() {
+            close(&mut stack, &mut root);
+        }
+
+        root.ok_or(ParseSnapshotError {
+            line: 0,
+            message: "snapshot is empty".into(),
+        })
+    }
+}
+
+/// Pops the innermost open node and adds it to its parent, or makes it the root.
+fn close(stack: &mut Vec<(usize, NodeSnapshot)>, root: &mut Option<NodeSnapshot>) {
+    if let Some((_, node)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(node),
+            None => *root = Some(node),
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                result.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                result.push('\\');
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Error in the text format of a [`NodeSnapshot`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseSnapshotError {
+    /// 1-based line number of the error, or `0` if the snapshot is empty.
+    pub line: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ParseSnapshotError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseSnapshotError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVEL: &str = "\
+Level (Node2D)
+  Player (KinematicBody2D)
+    .script = Player
+    .position = Vector2((1, 2))
+    Sprite (Sprite)
+  Enemies (Node2D)
+    .note = two\\nlines
+";
+
+    #[test]
+    fn text_roundtrip() {
+        let level = LEVEL.parse::<NodeSnapshot>().unwrap();
+        assert_eq!(2, level.children.len());
+
+        let player = level.find("Player").unwrap();
+        assert_eq!(Some("Player"), player.script.as_deref());
+        assert_eq!("Vector2((1, 2))", player.properties["position"]);
+        assert_eq!("Sprite", level.find("Player/Sprite").unwrap().class);
+        assert_eq!(
+            "two\nlines",
+            level.find("Enemies").unwrap().properties["note"]
+        );
+
+        assert_eq!(LEVEL, level.to_string());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let line = |s: &str| s.parse::<NodeSnapshot>().unwrap_err().line;
+        assert_eq!(0, line(""));
+        assert_eq!(1, line(".position = 1"));
+        assert_eq!(2, line("Root (Node)\n    Child (Node)"));
+        assert_eq!(2, line("Root (Node)\nOther (Node)"));
+        assert_eq!(2, line("Root (Node)\n  Child"));
+    }
+
+    #[test]
+    fn diff_changes() {
+        let expected = LEVEL.parse::<NodeSnapshot>().unwrap();
+        assert!(expected.diff(&expected).is_empty());
+
+        let mut actual = expected.clone();
+        actual.children.reverse();
+        let player = &mut actual.children[1];
+        player.class = "Node2D".into();
+        player
+            .properties
+            .insert("position".into(), "Vector2((3, 4))".into());
+        player.children.clear();
+        actual.children[0].children.push(NodeSnapshot {
+            name: "Goblin".into(),
+            class: "Node2D".into(),
+            ..NodeSnapshot::default()
+        });
+
+        let changes = actual
+            .diff(&expected)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "~ Level/Player: class KinematicBody2D -> Node2D",
+                "~ Level/Player.position: Vector2((1, 2)) -> Vector2((3, 4))",
+                "- Level/Player/Sprite",
+                "+ Level/Enemies/Goblin",
+                "~ Level: children [Player, Enemies] -> [Enemies, Player]",
+            ],
+            changes
+        );
+    }
+}
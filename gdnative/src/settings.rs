@@ -0,0 +1,224 @@
+//! Typed access to project settings.
+//!
+//! [`ProjectSettings`] stores all values as variants. The functions in this module convert them
+//! to Rust types, reporting missing settings and type mismatches as [`SettingError`]s:
+//!
+//! ```no_run
+//! use gdnative::settings;
+//!
+//! let gravity = settings::setting::<f64>("physics/2d/default_gravity").unwrap_or(98.0);
+//! ```
+//!
+//! Plugins can declare their own settings with [`CustomSetting`], usually in the init function
+//! of the library. Custom settings show up in the project settings dialog with the given hint,
+//! and revert to their default value:
+//!
+//! ```no_run
+//! use gdnative::export::hint::{FloatHint, RangeHint};
+//! use gdnative::prelude::*;
+//! use gdnative::settings::CustomSetting;
+//!
+//! fn init(_handle: InitHandle) {
+//!     CustomSetting::new("my_plugin/camera/shake_strength", 0.5)
+//!         .with_hint(FloatHint::Range(RangeHint::new(0.0, 1.0)))
+//!         .register();
+//!
+//!     // register classes...
+//! }
+//! ```
+//!
+//! The engine does not notify scripts when settings change. [`SettingsWatcher`] can be polled
+//! instead, e.g. from `_process` or a timer, to react to changes.
+
+use std::fmt;
+
+use crate::api::ProjectSettings;
+use crate::core_types::{FromVariant, FromVariantError, OwnedToVariant, Variant};
+use crate::export::Export;
+
+/// Returns the value of the setting `name`, converted to `T`.
+///
+/// # Errors
+///
+/// Returns an error if the setting does not exist, or if its value cannot be converted to `T`.
+#[inline]
+pub fn setting<T: FromVariant>(name: &str) -> Result<T, SettingError> {
+    let settings = ProjectSettings::godot_singleton();
+
+    if !settings.has_setting(name) {
+        return Err(SettingError::Missing(name.to_owned()));
+    }
+
+    T::from_variant(&settings.get_setting(name)).map_err(|error| SettingError::Type {
+        name: name.to_owned(),
+        error,
+    })
+}
+
+/// Sets the setting `name` to `value`. Setting a custom setting to `null` erases it.
+///
+/// Changes only affect the running game, unless the settings are saved afterwards.
+#[inline]
+pub fn set_setting(name: &str, value: impl OwnedToVariant) {
+    ProjectSettings::godot_singleton().set_setting(name, value);
+}
+
+/// Returns `true` if the setting `name` exists.
+#[inline]
+pub fn has_setting(name: &str) -> bool {
+    ProjectSettings::godot_singleton().has_setting(name)
+}
+
+/// Error returned by [`setting`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SettingError {
+    /// The setting does not exist.
+    Missing(String),
+    /// The setting exists, but has a value of the wrong type.
+    Type {
+        /// Name of the setting.
+        name: String,
+        /// Error from the conversion.
+        error: FromVariantError,
+    },
+}
+
+impl fmt::Display for SettingError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingError::Missing(name) => write!(f, "project setting `{name}` does not exist"),
+            SettingError::Type { name, error } => {
+                write!(
+                    f,
+                    "project setting `{name}` has an unexpected value: {error}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SettingError::Missing(_) => None,
+            SettingError::Type { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Builder for a custom project setting.
+///
+/// The setting is only created if it does not exist yet, so values saved in `project.godot` are
+/// preserved. The default value is always registered as the value the setting reverts to.
+#[derive(Debug)]
+#[must_use = "CustomSetting left unregistered -- did you forget to call register()?"]
+pub struct CustomSetting<T: Export> {
+    name: String,
+    default: T,
+    hint: Option<T::Hint>,
+}
+
+impl<T: Export> CustomSetting<T> {
+    /// Creates a builder for the setting `name`, with the default value `default`.
+    ///
+    /// Names have the form `category/subcategory/name`, like built-in settings.
+    #[inline]
+    pub fn new(name: impl Into<String>, default: T) -> Self {
+        CustomSetting {
+            name: name.into(),
+            default,
+            hint: None,
+        }
+    }
+
+    /// Sets the hint shown in the project settings dialog.
+    #[inline]
+    pub fn with_hint(mut self, hint: T::Hint) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    /// Registers the setting.
+    #[inline]
+    pub fn register(self) {
+        let settings = ProjectSettings::godot_singleton();
+        let default = self.default.to_variant();
+
+        if !settings.has_setting(&self.name) {
+            settings.set_setting(&self.name, default.clone());
+        }
+
+        settings.set_initial_value(&self.name, default);
+        settings.add_property_info(T::export_info(self.hint).to_property_info(&self.name));
+    }
+}
+
+/// Polls a set of settings for changes.
+///
+/// Each watched setting has a callback, which is invoked by [`poll`][Self::poll] with the new
+/// value whenever the value differs from the one seen last.
+#[derive(Default)]
+pub struct SettingsWatcher {
+    watches: Vec<Watch>,
+}
+
+struct Watch {
+    name: String,
+    last: Variant,
+    callback: Box<dyn FnMut(&Variant)>,
+}
+
+impl SettingsWatcher {
+    /// Creates a watcher without any watched settings.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts watching the setting `name`. The current value is recorded, so `callback` is only
+    /// invoked for later changes.
+    ///
+    /// The callback receives `null` if the setting is erased.
+    #[inline]
+    pub fn watch(&mut self, name: impl Into<String>, callback: impl FnMut(&Variant) + 'static) {
+        let name = name.into();
+        let last = ProjectSettings::godot_singleton().get_setting(&name);
+
+        self.watches.push(Watch {
+            name,
+            last,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Checks all watched settings, invoking the callbacks of those that changed. Returns the
+    /// number of changed settings.
+    #[inline]
+    pub fn poll(&mut self) -> usize {
+        let settings = ProjectSettings::godot_singleton();
+        let mut changed = 0;
+
+        for watch in &mut self.watches {
+            let value = settings.get_setting(&watch.name);
+
+            if value != watch.last {
+                (watch.callback)(&value);
+                watch.last = value;
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+}
+
+impl fmt::Debug for SettingsWatcher {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.watches.iter().map(|watch| &watch.name))
+            .finish()
+    }
+}
@@ -7,16 +7,23 @@ fn ui_tests() {
     t.pass("tests/ui/derive_no_inherit.rs");
     t.pass("tests/ui/derive_pass.rs");
     t.pass("tests/ui/derive_property_basic.rs");
+    t.pass("tests/ui/derive_property_tuple.rs");
+    t.pass("tests/ui/derive_property_option.rs");
+    t.pass("tests/ui/derive_userdata_auto.rs");
     t.compile_fail("tests/ui/derive_fail_inherit_param.rs");
     t.compile_fail("tests/ui/derive_fail_lifetime.rs");
+    t.compile_fail("tests/ui/derive_fail_methods_borrowed_object.rs");
     t.compile_fail("tests/ui/derive_fail_methods_list.rs");
     t.compile_fail("tests/ui/derive_fail_methods_missing_new.rs");
     t.compile_fail("tests/ui/derive_fail_methods_param.rs");
     t.compile_fail("tests/ui/derive_fail_methods_special_args.rs");
+    t.compile_fail("tests/ui/derive_fail_methods_unconverted_variants.rs");
     t.compile_fail("tests/ui/derive_fail_methods.rs");
+    t.compile_fail("tests/ui/derive_fail_once_data_mut.rs");
     t.compile_fail("tests/ui/derive_fail_property_empty_hint.rs");
     t.compile_fail("tests/ui/derive_fail_property_hint.rs");
     t.compile_fail("tests/ui/derive_fail_userdata.rs");
+    t.compile_fail("tests/ui/derive_fail_userdata_auto.rs");
 
     // Variants
     t.pass("tests/ui/variant_pass.rs");
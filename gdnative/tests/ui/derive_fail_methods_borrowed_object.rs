@@ -0,0 +1,16 @@
+use gdnative::prelude::*;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[no_constructor]
+struct Foo;
+
+#[methods]
+impl Foo {
+    #[method]
+    fn on_body_entered(&self, #[opt] body: &Node) {
+        godot_print!("{}", body.name());
+    }
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use gdnative::prelude::*;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct Foo {}
+
+#[methods]
+#[deny(unconverted_variants)]
+impl Foo {
+    fn new(_base: &Node) -> Self {
+        Foo {}
+    }
+
+    #[method]
+    fn typed(&self, #[base] _base: &Node, a: i64, b: Option<String>) -> Vec<f32> {
+        vec![a as f32, b.map_or(0.0, |b| b.len() as f32)]
+    }
+
+    #[method]
+    fn untyped(&self, a: Variant, #[opt] b: Option<Variant>) -> Vec<Variant> {
+        vec![a, b.unwrap_or_default()]
+    }
+
+    #[method]
+    #[allow(unconverted_variants)]
+    fn allowed(&self, a: Variant) -> Variant {
+        a
+    }
+
+    fn not_exported(&self, a: Variant) -> Variant {
+        a
+    }
+}
+
+fn main() {}
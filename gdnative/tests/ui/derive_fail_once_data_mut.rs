@@ -0,0 +1,28 @@
+use gdnative::export::user_data::OnceData;
+use gdnative::prelude::*;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[user_data(OnceData<Self>)]
+struct Foo {
+    count: i64,
+}
+
+#[methods]
+impl Foo {
+    fn new(_base: &Node) -> Self {
+        Foo { count: 0 }
+    }
+
+    #[method]
+    fn count(&self) -> i64 {
+        self.count
+    }
+
+    #[method]
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use gdnative::prelude::*;
+
+struct GameState;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[user_data(auto)]
+#[no_constructor]
+struct Foo {
+    state: Arc<GameState>,
+    cache: Rc<String>,
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use gdnative::export::hint::*;
+use gdnative::prelude::*;
+
+fn test_hint() -> FloatHint<f64> {
+    FloatHint::Range(RangeHint::new(0.0, 1.0))
+}
+
+#[derive(Default, NativeClass)]
+#[inherit(Node)]
+struct Foo {
+    #[property]
+    float: Option<f64>,
+
+    #[property(hint = "test_hint")]
+    float_hint: Option<f64>,
+
+    #[property]
+    string: Option<GodotString>,
+
+    #[property(default = Some(42))]
+    int_default: Option<i64>,
+
+    #[property(default = None)]
+    int_none_default: Option<i64>,
+
+    #[property]
+    object: Option<Ref<Texture>>,
+
+    #[property(hint_resource = "StreamTexture, ImageTexture")]
+    object_hint: Option<Ref<Texture>>,
+
+    #[property(hint_file = "*.png, *.jpg")]
+    path: Option<String>,
+}
+
+#[methods]
+impl Foo {
+    fn new(_owner: &Node) -> Self {
+        Foo::default()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use gdnative::prelude::*;
+
+#[derive(Default, NativeClass)]
+#[inherit(Node)]
+struct Foo(
+    #[property(name = "bar")] String,
+    i64,
+    #[property(path = "group/baz", default = 42)] i64,
+);
+
+#[methods]
+impl Foo {
+    fn new(_owner: &Node) -> Self {
+        Foo::default()
+    }
+}
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct Marker;
+
+#[methods]
+impl Marker {
+    fn new(_owner: &Node) -> Self {
+        Marker
+    }
+}
+
+fn main() {}
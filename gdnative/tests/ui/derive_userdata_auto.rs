@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex};
+
+use gdnative::export::user_data::{ArcData, DefaultUserData, MutexData};
+use gdnative::prelude::*;
+
+struct GameState;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[user_data(auto)]
+#[no_constructor]
+struct World {
+    state: Arc<GameState>,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[user_data(auto)]
+#[no_constructor]
+struct Mixed {
+    state: Arc<GameState>,
+    ticks: u64,
+}
+
+#[methods]
+impl Mixed {
+    #[method]
+    fn tick(&mut self) {
+        self.ticks += 1;
+    }
+}
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[user_data(auto)]
+#[no_constructor]
+struct Local {
+    ticks: u64,
+}
+
+fn assert_user_data<T: NativeClass<UserData = U>, U>() {}
+
+fn main() {
+    assert_user_data::<World, ArcData<World>>();
+    assert_user_data::<Mixed, MutexData<Mixed>>();
+    assert_user_data::<Local, DefaultUserData<Local>>();
+}
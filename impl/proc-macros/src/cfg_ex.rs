@@ -102,6 +102,11 @@ impl VisitMut for CfgExVisitor {
                             )
                         );
                     }
+                    "html5" => {
+                        *i = parse_quote_spanned!(cfg_name.span() =>
+                            all(target_arch = "wasm32", target_os = "emscripten")
+                        );
+                    }
                     _ => {
                         self.errors.push(syn::Error::new(
                             cfg_name.span(),
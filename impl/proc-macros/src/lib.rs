@@ -25,7 +25,25 @@ pub fn decl_typed_array_element(input: TokenStream) -> TokenStream {
         .into()
 }
 
-/// `#[cfg]` but with custom expansion for GDNative-specific conditional compilation options
+/// `#[cfg]` but with custom expansion for GDNative-specific conditional compilation options.
+///
+/// In addition to everything accepted by `#[cfg]`, the predicate may contain the following
+/// options:
+///
+/// - `gdnative::inventory_platform_available`: the target platform supports automatic class
+///   registration with the `inventory` feature.
+/// - `gdnative::html5`: the target is an HTML5 export (`wasm32-unknown-emscripten`), where
+///   threads and automatic class registration are unavailable.
+///
+/// Options are expanded to plain `target_*` predicates, so they can be used in crates without
+/// any additional setup.
+///
+/// ```ignore
+/// #[gdnative::cfg_ex(not(gdnative::html5))]
+/// fn spawn_worker() {
+///     std::thread::spawn(|| { /* ... */ });
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn cfg_ex(meta: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(meta as AttributeArgs);
@@ -34,7 +52,8 @@ pub fn cfg_ex(meta: TokenStream, item: TokenStream) -> TokenStream {
     quote!(#attr #item).into()
 }
 
-/// `#[cfg_attr]` but with custom expansion for GDNative-specific conditional compilation options
+/// `#[cfg_attr]` but with custom expansion for GDNative-specific conditional compilation options.
+/// See [`macro@cfg_ex`] for the available options.
 #[proc_macro_attribute]
 pub fn cfg_attr_ex(meta: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(meta as AttributeArgs);
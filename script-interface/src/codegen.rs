@@ -0,0 +1,274 @@
+//! Generates the Rust interfaces of parsed scripts.
+
+use heck::ToUpperCamelCase;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::parse::{Func, Script};
+
+/// Rust keywords that can't be used as identifiers without the `r#` prefix.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Keywords that can't be used as raw identifiers either.
+const RESERVED: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+/// Returns the name of the trait generated for a script.
+pub(crate) fn trait_name(script: &Script, file_stem: &str) -> String {
+    match &script.class_name {
+        Some(class_name) => class_name.clone(),
+        None => file_stem.to_upper_camel_case(),
+    }
+}
+
+/// Generates the trait and proxy of a script. `source` is shown in the documentation.
+pub(crate) fn script(
+    script: &Script,
+    name: &str,
+    source: &str,
+    include_private: bool,
+) -> TokenStream {
+    let trait_name = format_ident!("{}", name);
+    let proxy_name = format_ident!("{}Proxy", name);
+
+    let funcs = script
+        .funcs
+        .iter()
+        .filter(|func| include_private || !func.name.starts_with('_'))
+        .collect::<Vec<_>>();
+
+    let trait_doc = format!(" Methods of the GDScript `{source}`.");
+    let proxy_doc = format!(
+        " Calls the methods of the GDScript `{source}` on an object, with the conversions of [`{name}`]."
+    );
+
+    let decls = funcs.iter().map(|func| {
+        let doc = format!(
+            " Calls `{}`, declared on line {}.",
+            func.signature, func.line
+        );
+        let sig = signature(func);
+        quote! {
+            #[doc = #doc]
+            #sig;
+        }
+    });
+
+    let impls = funcs.iter().map(|func| {
+        let sig = signature(func);
+        let method = &func.name;
+        let args = func.params.iter().map(|param| {
+            let ident = ident(&param.name);
+            quote!(::gdnative::core_types::OwnedToVariant::owned_to_variant(#ident))
+        });
+
+        let call = quote! {
+            // SAFETY: calls into the script are allowed by the caller of `new`.
+            unsafe { self.base.clone().call(#method, &[#(#args),*]) }
+                .map_err(|err| ScriptCallError::Call(#method, err))
+        };
+
+        let body = match func.ret.as_deref() {
+            Some("void") => quote!(#call.map(|_| ())),
+            None => call,
+            Some(_) => quote! {
+                let ret = #call?;
+                ::gdnative::core_types::FromVariant::from_variant(&ret)
+                    .map_err(|err| ScriptCallError::Return(#method, err))
+            },
+        };
+
+        quote! {
+            #[inline]
+            #sig {
+                #body
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #trait_doc]
+        pub trait #trait_name {
+            #(#decls)*
+        }
+
+        #[doc = #proxy_doc]
+        #[derive(Clone, Debug)]
+        pub struct #proxy_name<'a> {
+            base: ::gdnative::core_types::Variant,
+            _marker: ::std::marker::PhantomData<&'a ()>,
+        }
+
+        impl<'a> #proxy_name<'a> {
+            /// Creates a proxy calling the methods of `base`.
+            ///
+            /// # Safety
+            ///
+            /// Calling script methods runs arbitrary code, with the same requirements as
+            /// `Object::call`. `base` doesn't have to have the script: calls fail with
+            /// `CallError::InvalidMethod` if it doesn't have the method.
+            #[inline]
+            pub unsafe fn new<T>(base: ::gdnative::object::TRef<'a, T>) -> Self
+            where
+                T: ::gdnative::object::GodotObject,
+            {
+                #proxy_name {
+                    base: ::gdnative::core_types::ToVariant::to_variant(&base),
+                    _marker: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<'a> #trait_name for #proxy_name<'a> {
+            #(#impls)*
+        }
+    }
+}
+
+/// Generates the error type shared by all proxies.
+pub(crate) fn error() -> TokenStream {
+    quote! {
+        /// Error returned by the methods of script proxies.
+        #[derive(Debug)]
+        pub enum ScriptCallError {
+            /// The method couldn't be called, e.g. because the object doesn't have it.
+            Call(&'static str, ::gdnative::core_types::CallError),
+            /// The method returned a value of another type than declared.
+            Return(&'static str, ::gdnative::core_types::FromVariantError),
+        }
+
+        impl ::std::fmt::Display for ScriptCallError {
+            #[inline]
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    ScriptCallError::Call(method, err) => {
+                        write!(f, "cannot call `{}`: {}", method, err)
+                    }
+                    ScriptCallError::Return(method, err) => {
+                        write!(f, "unexpected return value of `{}`: {}", method, err)
+                    }
+                }
+            }
+        }
+
+        impl ::std::error::Error for ScriptCallError {}
+    }
+}
+
+fn signature(func: &Func) -> TokenStream {
+    let name = ident(&func.name);
+    let params = func.params.iter().map(|param| {
+        let ident = ident(&param.name);
+        let ty = rust_type(param.ty.as_deref());
+        quote!(#ident: #ty)
+    });
+    let ret = match func.ret.as_deref() {
+        Some("void") => quote!(()),
+        ret => rust_type(ret),
+    };
+    quote! {
+        fn #name(&self, #(#params),*) -> ::std::result::Result<#ret, ScriptCallError>
+    }
+}
+
+/// Returns the Rust type of a GDScript type. Untyped values are `Variant`s, and objects of any
+/// class are `Option<Ref<Object>>`.
+fn rust_type(ty: Option<&str>) -> TokenStream {
+    let Some(ty) = ty else {
+        return quote!(::gdnative::core_types::Variant);
+    };
+
+    // Element types of typed arrays are not checked when converting.
+    let ty = ty.split('[').next().unwrap_or_default();
+
+    let core = |name: &str| {
+        let name = format_ident!("{}", name);
+        quote!(::gdnative::core_types::#name)
+    };
+    let pool = |elem: TokenStream| quote!(::gdnative::core_types::PoolArray<#elem>);
+
+    match ty {
+        "bool" => quote!(bool),
+        "int" => quote!(i64),
+        "float" => quote!(f64),
+        "String" => core("GodotString"),
+        "Vector2" | "Vector3" | "Rect2" | "Transform2D" | "Plane" | "Quat" | "Basis"
+        | "Transform" | "Color" | "NodePath" | "Dictionary" => core(ty),
+        "AABB" => core("Aabb"),
+        "RID" => core("Rid"),
+        "Array" => core("VariantArray"),
+        "PoolByteArray" => pool(quote!(u8)),
+        "PoolIntArray" => pool(quote!(i32)),
+        "PoolRealArray" => pool(quote!(f32)),
+        "PoolStringArray" => pool(core("GodotString")),
+        "PoolVector2Array" => pool(core("Vector2")),
+        "PoolVector3Array" => pool(core("Vector3")),
+        "PoolColorArray" => pool(core("Color")),
+        "Variant" => core("Variant"),
+        _ => quote!(::std::option::Option<::gdnative::object::Ref<::gdnative::api::Object>>),
+    }
+}
+
+fn ident(name: &str) -> Ident {
+    if RESERVED.contains(&name) {
+        format_ident!("{}_", name)
+    } else if KEYWORDS.contains(&name) {
+        Ident::new_raw(name, Span::call_site())
+    } else {
+        format_ident!("{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    #[test]
+    fn types_are_mapped() {
+        let script = parse(
+            "func f(a, b: int, c: String, d: PoolVector2Array, e: Node, type: Array[int]) -> void:\n",
+        )
+        .unwrap();
+        let actual = signature(&script.funcs[0]).to_string();
+        let expected = quote! {
+            fn f(
+                &self,
+                a: ::gdnative::core_types::Variant,
+                b: i64,
+                c: ::gdnative::core_types::GodotString,
+                d: ::gdnative::core_types::PoolArray<::gdnative::core_types::Vector2>,
+                e: ::std::option::Option<::gdnative::object::Ref<::gdnative::api::Object>>,
+                r#type: ::gdnative::core_types::VariantArray
+            ) -> ::std::result::Result<(), ScriptCallError>
+        }
+        .to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn private_methods_are_skipped() {
+        let script = parse("func _ready():\n    pass\nfunc shoot():\n    pass\n").unwrap();
+
+        let output = super::script(&script, "Gun", "gun.gd", false).to_string();
+        assert!(output.contains("fn shoot"));
+        assert!(!output.contains("_ready"));
+
+        let output = super::script(&script, "Gun", "gun.gd", true).to_string();
+        assert!(output.contains("fn _ready"));
+    }
+
+    #[test]
+    fn trait_is_named_after_class_or_file() {
+        let script = parse("class_name Enemy\n").unwrap();
+        assert_eq!("Enemy", trait_name(&script, "enemy_base"));
+
+        let script = parse("extends Node\n").unwrap();
+        assert_eq!("MyGdScript", trait_name(&script, "my_gd_script"));
+    }
+}
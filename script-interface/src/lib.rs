@@ -0,0 +1,274 @@
+//! Generates Rust interfaces for calling GDScript methods, from the declarations in `.gd` files.
+//!
+//! Rust code that calls methods of GDScript code normally uses `Object::call`, which takes the
+//! method name and arguments as variants, so mistakes are only detected when running the game.
+//! This crate reads the `func` declarations of scripts in a build script, and generates a trait
+//! and a proxy type for each script, with a method for each function:
+//!
+//! ```gdscript
+//! # godot/player.gd
+//! class_name Player
+//! extends KinematicBody2D
+//!
+//! func take_damage(amount: int, source: Node = null) -> bool:
+//!     ...
+//! ```
+//!
+//! ```no_run
+//! // main function of build.rs
+//! let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//! gdnative_script_interface::Generator::new()
+//!     .script("godot/player.gd")
+//!     .write(out_dir.join("scripts.rs"))
+//!     .unwrap();
+//! ```
+//!
+//! ```ignore
+//! // src/lib.rs
+//! mod scripts {
+//!     include!(concat!(env!("OUT_DIR"), "/scripts.rs"));
+//! }
+//!
+//! use scripts::{Player, PlayerProxy, ScriptCallError};
+//!
+//! fn hit(player: TRef<KinematicBody2D>) -> Result<bool, ScriptCallError> {
+//!     // SAFETY: `take_damage` doesn't free objects that are in use.
+//!     unsafe { PlayerProxy::new(player) }.take_damage(10, None)
+//! }
+//! ```
+//!
+//! The trait is named after the `class_name` of the script, or its file name otherwise, and the
+//! proxy has the `Proxy` suffix. Rust code can implement the trait too, e.g. to test code that
+//! calls scripts without the engine.
+//!
+//! ## Types
+//!
+//! Parameters and return values of built-in types are converted to the corresponding Rust types,
+//! such as `i64` for `int` and `GodotString` for `String`. Untyped parameters and return values
+//! are `Variant`s, and methods declared with `-> void` return `()`. Objects of any class are
+//! `Option<Ref<Object>>`, since the classes of scripts are not known to Rust. Parameters with
+//! default values have to be passed explicitly.
+//!
+//! Methods return an error if the object doesn't have the method, the number of arguments
+//! doesn't match, or the return value can't be converted. Arguments are converted by the engine
+//! like in calls from GDScript.
+//!
+//! ## Limitations
+//!
+//! Only top-level functions are included, and functions with names starting with an underscore
+//! are skipped by default, as they are virtual or private by convention. Signals, properties and
+//! inner classes are ignored. Function bodies are not parsed, so the scripts aren't validated.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+mod codegen;
+mod parse;
+
+/// Generates the interfaces of GDScript files.
+#[derive(Clone, Debug, Default)]
+pub struct Generator {
+    scripts: Vec<Source>,
+    include_private: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Source {
+    File(PathBuf),
+    Text { name: String, text: String },
+}
+
+impl Generator {
+    /// Creates a generator without any scripts.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the script at `path`.
+    #[inline]
+    pub fn script(mut self, path: impl Into<PathBuf>) -> Self {
+        self.scripts.push(Source::File(path.into()));
+        self
+    }
+
+    /// Adds a script from its source text. `name` is used like the file name of a script, e.g.
+    /// `player.gd`.
+    #[inline]
+    pub fn script_source(mut self, name: impl Into<String>, text: impl Into<String>) -> Self {
+        self.scripts.push(Source::Text {
+            name: name.into(),
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Whether to include functions with names starting with an underscore. Defaults to
+    /// `false`.
+    #[inline]
+    pub fn include_private(mut self, include_private: bool) -> Self {
+        self.include_private = include_private;
+        self
+    }
+
+    /// Generates the Rust code for all scripts.
+    #[inline]
+    pub fn generate(&self) -> Result<String, Error> {
+        let mut output = codegen::error();
+        let mut names = Vec::<(String, String)>::new();
+
+        for source in &self.scripts {
+            let (display, file_name, text) = match source {
+                Source::File(path) => {
+                    let text = fs::read_to_string(path).map_err(|err| Error::Io {
+                        path: path.clone(),
+                        source: err,
+                    })?;
+                    let file_name = path.file_name().unwrap_or_default();
+                    (
+                        path.display().to_string(),
+                        file_name.to_string_lossy().into_owned(),
+                        text,
+                    )
+                }
+                Source::Text { name, text } => (name.clone(), name.clone(), text.clone()),
+            };
+
+            let parse_error = |line, message| Error::Parse {
+                script: display.clone(),
+                line,
+                message,
+            };
+
+            let script = parse::parse(&text).map_err(|err| parse_error(err.line, err.message))?;
+
+            let stem = file_name.strip_suffix(".gd").unwrap_or(&file_name);
+            let name = codegen::trait_name(&script, stem);
+            if !parse::is_ident(&name) {
+                return Err(parse_error(
+                    1,
+                    format!("`{name}` is not a valid type name, add a `class_name`"),
+                ));
+            }
+            if let Some((_, other)) = names.iter().find(|(other, _)| *other == name) {
+                return Err(parse_error(
+                    1,
+                    format!("the interface `{name}` is already generated for `{other}`"),
+                ));
+            }
+
+            output.extend(codegen::script(
+                &script,
+                &name,
+                &file_name,
+                self.include_private,
+            ));
+            names.push((name, display));
+        }
+
+        Ok(output.to_string())
+    }
+
+    /// Writes the Rust code for all scripts to `path`, typically in `OUT_DIR`, to be included
+    /// with `include!`. Cargo is told to run the build script again if the script files change.
+    #[inline]
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        for source in &self.scripts {
+            if let Source::File(script) = source {
+                println!("cargo:rerun-if-changed={}", script.display());
+            }
+        }
+
+        let path = path.as_ref();
+        let output = self.generate()?;
+        fs::write(path, output).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            source: err,
+        })
+    }
+}
+
+/// Error returned by [`Generator`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A file couldn't be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// A function declaration couldn't be parsed.
+    Parse {
+        /// Path or name of the script.
+        script: String,
+        /// 1-based line number.
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Error::Parse {
+                script,
+                line,
+                message,
+            } => write!(f, "{script}:{line}: {message}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_are_generated() {
+        let output = Generator::new()
+            .script_source(
+                "player.gd",
+                "class_name Player\nfunc heal(hp: int) -> int:\n",
+            )
+            .script_source("my_gd_script.gd", "func do_thing(x: int):\n")
+            .generate()
+            .unwrap();
+
+        assert_eq!(1, output.matches("pub enum ScriptCallError").count());
+        assert!(output.contains("pub trait Player"));
+        assert!(output.contains("pub struct PlayerProxy"));
+        assert!(output.contains("pub trait MyGdScript"));
+        assert!(output.contains("pub struct MyGdScriptProxy"));
+    }
+
+    #[test]
+    fn errors_have_locations() {
+        let err = Generator::new()
+            .script_source("broken.gd", "extends Node\nfunc (x):\n")
+            .generate()
+            .unwrap_err();
+        assert_eq!("broken.gd:2: invalid function name ``", err.to_string());
+
+        let err = Generator::new()
+            .script_source("a.gd", "class_name Same\n")
+            .script_source("b.gd", "class_name Same\n")
+            .generate()
+            .unwrap_err();
+        assert_eq!(
+            "b.gd:1: the interface `Same` is already generated for `a.gd`",
+            err.to_string()
+        );
+    }
+}
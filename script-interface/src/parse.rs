@@ -0,0 +1,419 @@
+//! Parser for the declarations of GDScript files that are needed to call their methods.
+//!
+//! Only top-level `class_name` and `func` declarations are read. Everything else, including the
+//! bodies of functions and inner classes, is skipped without being validated.
+
+/// Declarations of a GDScript file.
+#[derive(Debug, Default)]
+pub(crate) struct Script {
+    pub class_name: Option<String>,
+    pub funcs: Vec<Func>,
+}
+
+/// Top-level function of a script.
+#[derive(Debug)]
+pub(crate) struct Func {
+    pub name: String,
+    /// 1-based line number of the `func` keyword.
+    pub line: usize,
+    pub params: Vec<Param>,
+    /// Declared return type, if any.
+    pub ret: Option<String>,
+    /// The declaration from `func` to the final colon, with whitespace normalized.
+    pub signature: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Param {
+    pub name: String,
+    /// Declared type, or `None` if the parameter is untyped or its type is inferred.
+    pub ty: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Keywords that may precede `func`: `static`, and the RPC modes of Godot 3.
+const FUNC_MODIFIERS: &[&str] = &[
+    "static",
+    "remote",
+    "master",
+    "puppet",
+    "remotesync",
+    "mastersync",
+    "puppetsync",
+    "sync",
+    "slave",
+];
+
+/// Parses the GDScript source `text`.
+pub(crate) fn parse(text: &str) -> Result<Script, ParseError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let lines = text.lines().collect::<Vec<_>>();
+
+    let mut script = Script::default();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = index + 1;
+        let raw = lines[index];
+        index += 1;
+
+        // Indented lines belong to function bodies and inner classes.
+        if raw.starts_with([' ', '\t']) {
+            continue;
+        }
+
+        let mut code = strip_comment(raw).trim_end().to_owned();
+        if code.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = keyword(&code, "class_name") {
+            let name = rest
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .next()
+                .unwrap_or_default();
+            if !is_ident(name) {
+                return Err(ParseError {
+                    line,
+                    message: format!("invalid class name `{name}`"),
+                });
+            }
+            script.class_name = Some(name.to_owned());
+            continue;
+        }
+
+        let mut rest = code.as_str();
+        while let Some(word) = FUNC_MODIFIERS.iter().find_map(|m| keyword(rest, m)) {
+            rest = word;
+        }
+        if keyword(rest, "func").is_none() {
+            continue;
+        }
+
+        // Parameter lists may span multiple lines, and lines may be continued with a backslash.
+        while !is_complete(&code) {
+            let Some(next) = lines.get(index) else {
+                return Err(ParseError {
+                    line,
+                    message: "unterminated function declaration".into(),
+                });
+            };
+            index += 1;
+            code = code.trim_end_matches('\\').to_owned();
+            code.push(' ');
+            code.push_str(strip_comment(next).trim());
+        }
+
+        script.funcs.push(parse_func(&code, line)?);
+    }
+
+    Ok(script)
+}
+
+fn parse_func(code: &str, line: usize) -> Result<Func, ParseError> {
+    let error = |message: String| ParseError { line, message };
+
+    let start = code.find("func").unwrap() + "func".len();
+    let open = code[start..]
+        .find('(')
+        .map(|pos| start + pos)
+        .ok_or_else(|| error("expected `(` after the function name".into()))?;
+    let name = code[start..open].trim();
+    if !is_ident(name) {
+        return Err(error(format!("invalid function name `{name}`")));
+    }
+
+    let close = matching_paren(code, open)
+        .ok_or_else(|| error(format!("unterminated parameter list of `{name}`")))?;
+
+    let params = split_top_level(&code[open + 1..close])
+        .into_iter()
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            parse_param(param)
+                .ok_or_else(|| error(format!("invalid parameter `{param}` of `{name}`")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The declaration ends at the first colon after the parameters, and a body may follow it
+    // on the same line.
+    let tail = &code[close + 1..];
+    let colon = tail
+        .find(':')
+        .ok_or_else(|| error(format!("expected `:` after the declaration of `{name}`")))?;
+    let ret = match tail[..colon].trim().strip_prefix("->") {
+        Some(ret) => {
+            Some(parse_type(ret).ok_or_else(|| error(format!("invalid return type of `{name}`")))?)
+        }
+        None if tail[..colon].trim().is_empty() => None,
+        None => {
+            return Err(error(format!(
+                "unexpected `{}` in the declaration of `{name}`",
+                tail[..colon].trim()
+            )))
+        }
+    };
+
+    let declaration = &code[code.find("func").unwrap()..close + 1 + colon];
+    let signature = declaration.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Ok(Func {
+        name: name.to_owned(),
+        line,
+        params,
+        ret,
+        signature,
+    })
+}
+
+/// Parses `name`, `name: Type`, `name := default`, or either of the first two followed by
+/// `= default`. Default values are not passed by proxies, so they are skipped.
+fn parse_param(param: &str) -> Option<Param> {
+    let decl = split_top_level_once(param, '=').map_or(param, |(decl, _)| decl);
+
+    let (name, ty) = match decl.split_once(':') {
+        Some((name, ty)) if ty.trim().is_empty() => (name, None),
+        Some((name, ty)) => (name, Some(parse_type(ty)?)),
+        None => (decl, None),
+    };
+
+    let name = name.trim();
+    is_ident(name).then(|| Param {
+        name: name.to_owned(),
+        ty,
+    })
+}
+
+/// Parses a type name, such as `int`, `Node`, `Array[int]` or `MyClass.Inner`.
+fn parse_type(ty: &str) -> Option<String> {
+    let ty = ty.trim();
+    let base = ty.split('[').next().unwrap_or_default();
+    base.split('.').all(is_ident).then(|| ty.to_owned())
+}
+
+/// Returns the text after `word` if `code` starts with it as a separate word.
+fn keyword<'a>(code: &'a str, word: &str) -> Option<&'a str> {
+    let rest = code.strip_prefix(word)?;
+    match rest.chars().next() {
+        None => Some(rest),
+        Some(c) if c.is_whitespace() => Some(rest.trim_start()),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// Removes a comment from the end of a line, ignoring `#` in strings.
+fn strip_comment(line: &str) -> &str {
+    let mut scanner = Scanner::default();
+    for (pos, c) in line.char_indices() {
+        if c == '#' && !scanner.in_string() {
+            return &line[..pos];
+        }
+        scanner.next(c);
+    }
+    line
+}
+
+/// Returns whether a declaration starting with `func` has a closed parameter list, and doesn't
+/// end with a line continuation.
+fn is_complete(code: &str) -> bool {
+    let Some(open) = code.find('(') else {
+        return !code.ends_with('\\');
+    };
+    matching_paren(code, open).is_some() && !code.ends_with('\\')
+}
+
+/// Returns the position of the parenthesis closing the one at `open`.
+fn matching_paren(code: &str, open: usize) -> Option<usize> {
+    let mut scanner = Scanner::default();
+    for (pos, c) in code[open..].char_indices() {
+        scanner.next(c);
+        if scanner.depth == 0 && !scanner.in_string() {
+            return Some(open + pos);
+        }
+    }
+    None
+}
+
+/// Splits `text` at the commas that are not nested in brackets or strings.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut scanner = Scanner::default();
+    let mut start = 0;
+    for (pos, c) in text.char_indices() {
+        if c == ',' && scanner.depth == 0 && !scanner.in_string() {
+            parts.push(&text[start..pos]);
+            start = pos + 1;
+        }
+        scanner.next(c);
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Splits `text` at the first `sep` that is not nested in brackets or strings.
+fn split_top_level_once(text: &str, sep: char) -> Option<(&str, &str)> {
+    let mut scanner = Scanner::default();
+    for (pos, c) in text.char_indices() {
+        if c == sep && scanner.depth == 0 && !scanner.in_string() {
+            return Some((&text[..pos], &text[pos + c.len_utf8()..]));
+        }
+        scanner.next(c);
+    }
+    None
+}
+
+/// Tracks brackets and string literals, one character at a time.
+#[derive(Default)]
+struct Scanner {
+    depth: usize,
+    quote: Option<char>,
+    escaped: bool,
+}
+
+impl Scanner {
+    fn in_string(&self) -> bool {
+        self.quote.is_some()
+    }
+
+    fn next(&mut self, c: char) {
+        if let Some(quote) = self.quote {
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if c == quote {
+                self.quote = None;
+            }
+            return;
+        }
+
+        match c {
+            '"' | '\'' => self.quote = Some(c),
+            '(' | '[' | '{' => self.depth += 1,
+            ')' | ']' | '}' => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_level_functions_are_parsed() {
+        let text = r#"
+tool
+extends Node2D
+class_name Player, "res://icon.png"
+
+signal hit(damage)
+
+var health := 10
+
+func take_damage(amount: int, source = null) -> bool:
+    health -= amount
+    return health <= 0
+
+static func describe(player: Player, verbose := false) -> String: return "player"
+
+remotesync func set_name_label(text: String = "a, (b)") -> void:
+    pass
+
+func _ready():
+    pass
+
+class Inner:
+    func hidden(x: int) -> int:
+        return x
+"#;
+
+        let script = parse(text).unwrap();
+        assert_eq!(Some("Player"), script.class_name.as_deref());
+
+        let names = script
+            .funcs
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["take_damage", "describe", "set_name_label", "_ready"],
+            names
+        );
+
+        let take_damage = &script.funcs[0];
+        assert_eq!(10, take_damage.line);
+        assert_eq!(Some("bool"), take_damage.ret.as_deref());
+        assert_eq!("amount", take_damage.params[0].name);
+        assert_eq!(Some("int"), take_damage.params[0].ty.as_deref());
+        assert_eq!(None, take_damage.params[1].ty);
+
+        let describe = &script.funcs[1];
+        assert_eq!(Some("Player"), describe.params[0].ty.as_deref());
+        assert_eq!(None, describe.params[1].ty);
+        assert_eq!(
+            "func describe(player: Player, verbose := false) -> String",
+            describe.signature
+        );
+
+        let set_name_label = &script.funcs[2];
+        assert_eq!(1, set_name_label.params.len());
+        assert_eq!(Some("void"), set_name_label.ret.as_deref());
+
+        assert_eq!(None, script.funcs[3].ret);
+    }
+
+    #[test]
+    fn declarations_span_lines() {
+        let text = "
+func spawn(
+    position: Vector2,  # where
+    kind: String = \"#enemy\",
+) -> Node:
+    pass
+
+func long_name(a: int) \\
+        -> int:
+    return a
+";
+
+        let script = parse(text).unwrap();
+        assert_eq!(2, script.funcs.len());
+
+        let spawn = &script.funcs[0];
+        assert_eq!(2, spawn.line);
+        assert_eq!(2, spawn.params.len());
+        assert_eq!(Some("Node"), spawn.ret.as_deref());
+        assert_eq!(
+            "func spawn( position: Vector2, kind: String = \"#enemy\", ) -> Node",
+            spawn.signature
+        );
+
+        assert_eq!(Some("int"), script.funcs[1].ret.as_deref());
+    }
+
+    #[test]
+    fn invalid_declarations_are_reported() {
+        let err = parse("extends Node\n\nfunc broken(a: int:\n    pass\n").unwrap_err();
+        assert_eq!(3, err.line);
+        assert_eq!("unterminated function declaration", err.message);
+
+        let err = parse("func bad(1x):\n    pass\n").unwrap_err();
+        assert_eq!(1, err.line);
+        assert_eq!("invalid parameter `1x` of `bad`", err.message);
+    }
+}
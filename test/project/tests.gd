@@ -21,6 +21,8 @@ func run():
 		status = status && _test_generic_class()
 		status = status && _test_optional_args()
 		status = status && yield(_test_async_resume(), "completed")
+		status = status && yield(_test_deferred_signal(), "completed")
+		status = status && yield(_test_late_registration(), "completed")
 
 		# Godot needs another frame to dispose the executor driver node. Otherwise the process
 		# aborts due to `_process` being called after `terminate` (`get_api` fail, not UB).
@@ -143,6 +145,7 @@ func _test_async_resume():
 
 	yield(fn_state, "resumable")
 	status = status && fn_state.is_valid()
+	status = status && resume.live_tasks() > 0
 	
 	fn_state = fn_state.resume(2)
 	if !fn_state:
@@ -167,6 +170,52 @@ func _get_async_number():
 	yield(get_tree().create_timer(0.1), "timeout")
 	return 39
 
+func _test_deferred_signal():
+	print(" -- _test_deferred_signal")
+
+	var script = NativeScript.new()
+	script.set_library(gdn.library)
+	script.set_class_name("DeferredSignals")
+	var emitter = script.new()
+
+	var received = []
+	emitter.connect("progress", self, "_on_deferred_progress", [received])
+
+	var status = emitter.emit_from_thread(3)
+	status = status && received.empty()
+
+	# Deferred calls are processed at the end of the frame.
+	yield(get_tree().create_timer(0.1), "timeout")
+
+	status = status && received == [0, 1, 2]
+
+	if !status:
+		printerr("   !! _test_deferred_signal failed")
+
+	return status
+
+func _on_deferred_progress(value, received):
+	received.append(value)
+
+func _test_late_registration():
+	print(" -- _test_late_registration")
+
+	var script = NativeScript.new()
+	script.set_library(gdn.library)
+	script.set_class_name("LateMembers")
+	var obj = script.new()
+
+	# Late registrations run at the start of a frame.
+	yield(get_tree().create_timer(0.1), "timeout")
+
+	var status = obj.has_method("late_method") && obj.has_signal("late_signal")
+	status = status && obj.late_method() == 42
+
+	if !status:
+		printerr("   !! _test_late_registration failed")
+
+	return status
+
 func _test_generic_class():
 	print(" -- _test_generic_class")
 
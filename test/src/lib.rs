@@ -4,20 +4,45 @@
 use gdnative::prelude::*;
 use gdnative_core::godot_itest;
 
+mod test_animation;
 mod test_as_arg;
 mod test_async;
+#[cfg(feature = "bench")]
+mod test_bench;
+mod test_bus;
+mod test_console;
 mod test_constructor;
+mod test_deferred_signal;
 mod test_derive;
+mod test_diagnostics;
+mod test_dispatch;
+mod test_draw;
+mod test_easing;
+mod test_enums;
+mod test_file;
 mod test_free_ub;
 mod test_generic_class;
+mod test_i18n;
 mod test_indexed_props;
+mod test_input;
+mod test_main_loop;
 mod test_map_owned;
+mod test_nav;
+mod test_net;
+mod test_object_handle;
+mod test_once_data;
+mod test_physics;
+mod test_platform;
+mod test_rand;
 mod test_register;
 mod test_return_leak;
+mod test_scene;
 mod test_serde;
+mod test_settings;
 mod test_vararray_return;
 mod test_variant_call_args;
 mod test_variant_ops;
+mod test_worker;
 
 #[no_mangle]
 pub extern "C" fn run_tests(
@@ -33,20 +58,48 @@ pub extern "C" fn run_tests(
     status &= test_rust_class_construction();
     status &= test_underscore_method_binding();
 
+    status &= test_animation::run_tests();
     status &= test_as_arg::run_tests();
     status &= test_async::run_tests();
+    status &= test_bus::run_tests();
+    status &= test_console::run_tests();
     status &= test_constructor::run_tests();
+    status &= test_deferred_signal::run_tests();
     status &= test_derive::run_tests();
+    status &= test_diagnostics::run_tests();
+    status &= test_dispatch::run_tests();
+    status &= test_draw::run_tests();
+    status &= test_easing::run_tests();
+    status &= test_enums::run_tests();
+    status &= test_file::run_tests();
     status &= test_free_ub::run_tests();
     status &= test_generic_class::run_tests();
+    status &= test_i18n::run_tests();
     status &= test_indexed_props::run_tests();
+    status &= test_input::run_tests();
+    status &= test_main_loop::run_tests();
     status &= test_map_owned::run_tests();
+    status &= test_nav::run_tests();
+    status &= test_net::run_tests();
+    status &= test_object_handle::run_tests();
+    status &= test_once_data::run_tests();
+    status &= test_physics::run_tests();
+    status &= test_platform::run_tests();
+    status &= test_rand::run_tests();
     status &= test_register::run_tests();
     status &= test_return_leak::run_tests();
+    status &= test_scene::run_tests();
     status &= test_serde::run_tests();
+    status &= test_settings::run_tests();
     status &= test_vararray_return::run_tests();
     status &= test_variant_call_args::run_tests();
     status &= test_variant_ops::run_tests();
+    status &= test_worker::run_tests();
+
+    #[cfg(feature = "bench")]
+    {
+        status &= test_bench::run_tests();
+    }
 
     Variant::new(status).leak()
 }
@@ -160,7 +213,7 @@ impl OptionalArgs {
 godot_itest! { test_from_instance_id {
     assert!(unsafe { Node::try_from_instance_id(22).is_none() });
     assert!(unsafe { Node::try_from_instance_id(42).is_none() });
-    assert!(unsafe { Node::try_from_instance_id(503).is_none() });
+    assert!(unsafe { Node::try_from_instance_id(InstanceId::from_i64(503)).is_none() });
 
     let instance_id;
 
@@ -168,7 +221,8 @@ godot_itest! { test_from_instance_id {
         let foo = unsafe { Node::new().into_shared().assume_safe() };
         foo.set_name("foo");
 
-        instance_id = foo.get_instance_id();
+        instance_id = foo.instance_id();
+        assert_eq!(foo.get_instance_id(), instance_id.to_i64());
 
         assert!(unsafe { Reference::try_from_instance_id(instance_id).is_none() });
 
@@ -187,7 +241,8 @@ godot_itest! { test_from_instance_id {
         let foo = unsafe { foo.assume_safe() };
         foo.set_meta("foo", "bar");
 
-        instance_id = foo.get_instance_id();
+        instance_id = foo.instance_id();
+        assert_eq!(foo.get_instance_id(), instance_id.to_i64());
 
         assert!(unsafe { Node::try_from_instance_id(instance_id).is_none() });
 
@@ -230,12 +285,18 @@ impl GDNativeCallbacks for TestLibrary {
 fn delegate_init(handle: InitHandle) {
     test_as_arg::register(handle);
     test_async::register(handle);
+    test_bus::register(handle);
     test_constructor::register(handle);
+    test_deferred_signal::register(handle);
     test_derive::register(handle);
+    test_diagnostics::register(handle);
+    test_dispatch::register(handle);
     test_free_ub::register(handle);
     test_generic_class::register(handle);
     test_indexed_props::register(handle);
+    test_main_loop::register(handle);
     test_map_owned::register(handle);
+    test_once_data::register(handle);
     test_register::register(handle);
     test_return_leak::register(handle);
     test_vararray_return::register(handle);
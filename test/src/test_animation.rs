@@ -0,0 +1,54 @@
+use gdnative::animation::{self, PropertyTrack};
+use gdnative::api::{canvas_item, node_2d, Animation, Tween};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_tween_typed_property();
+    status &= test_animation_property_track();
+
+    status
+}
+
+crate::godot_itest! { test_tween_typed_property {
+    let tween = Tween::new();
+    let node = Node2D::new().into_shared();
+    let node_ref = unsafe { node.assume_safe() };
+
+    assert!(animation::tween_property(
+        &tween,
+        node_ref,
+        node_2d::properties::POSITION,
+        Vector2::ZERO,
+        Vector2::new(10.0, 20.0),
+        1.0,
+    ));
+    assert!(animation::tween_property(
+        &tween,
+        node_ref,
+        canvas_item::properties::MODULATE,
+        Color::from_rgb(1.0, 1.0, 1.0),
+        Color::from_rgb(1.0, 0.0, 0.0),
+        1.0,
+    ));
+
+    tween.free();
+    unsafe { node.assume_unique().free() };
+}}
+
+crate::godot_itest! { test_animation_property_track {
+    let anim = Animation::new();
+
+    let track = PropertyTrack::add(&anim, "Sprite", node_2d::properties::SCALE);
+    track.insert_key(&anim, 0.0, Vector2::ONE);
+    track.insert_key(&anim, 0.5, Vector2::new(2.0, 2.0));
+
+    assert_eq!(1, anim.get_track_count());
+    assert_eq!("Sprite:scale", anim.track_get_path(track.index()).to_string());
+    assert_eq!(2, anim.track_get_key_count(track.index()));
+    assert_eq!(
+        Some(Vector2::new(2.0, 2.0)),
+        Vector2::from_variant(&anim.track_get_key_value(track.index(), 1)).ok(),
+    );
+}}
@@ -17,6 +17,8 @@ pub(crate) fn run_tests() -> bool {
 
     ok &= test_as_arg_ref();
     ok &= test_as_arg_instance();
+    ok &= test_as_arg_option();
+    ok &= test_upcast();
 
     ok
 }
@@ -51,6 +53,19 @@ crate::godot_itest! { test_as_arg_ref {
 
     // TRef<T, Shared>
     add_node_with(|n: Ref<Node2D, Unique>| unsafe { n.into_shared().assume_safe() });
+
+    // &TRef<T, Shared>
+    let mut keeper: MaybeUninit<TRef<Node2D, Shared>> = MaybeUninit::uninit();
+    add_node_with(|n: Ref<Node2D, Unique>| {
+        keeper.write(unsafe { n.into_shared().assume_safe() });
+        unsafe { keeper.assume_init_ref() }
+    });
+
+    // Option<Ref<T, Unique>>
+    add_node_with(|n: Ref<Node2D, Unique>| Some(n));
+
+    // Option<TRef<T, Shared>>
+    add_node_with(|n: Ref<Node2D, Unique>| Some(unsafe { n.into_shared().assume_safe() }));
 }}
 
 crate::godot_itest! { test_as_arg_instance {
@@ -69,6 +84,52 @@ crate::godot_itest! { test_as_arg_instance {
 
     // TInstance<T, Shared>
     add_instance_with(|n: Instance<MyNode, Unique>| unsafe { n.into_shared().assume_safe() });
+
+    // &TInstance<T, Shared>
+    let mut keeper: MaybeUninit<TInstance<MyNode, Shared>> = MaybeUninit::uninit();
+    add_instance_with(|n: Instance<MyNode, Unique>| {
+        keeper.write(unsafe { n.into_shared().assume_safe() });
+        unsafe { keeper.assume_init_ref() }
+    });
+
+    // Option<Instance<T, Shared>>
+    add_instance_with(|n: Instance<MyNode, Unique>| Some(n.into_shared()));
+}}
+
+crate::godot_itest! { test_as_arg_option {
+    let parent_ref = Node::new().into_shared();
+    let parent = unsafe { parent_ref.assume_safe() };
+    let child = unsafe { Node::new().into_shared().assume_safe() };
+    parent.add_child(child, false);
+
+    child.set_owner(Some(parent));
+    assert_eq!(
+        Some(parent.get_instance_id()),
+        child.get_owner().map(|owner| unsafe { owner.assume_safe() }.get_instance_id()),
+    );
+
+    // `None` is passed as a null reference
+    child.set_owner(Option::<TRef<Node>>::None);
+    assert!(child.get_owner().is_none());
+
+    unsafe { parent_ref.assume_unique() }.free();
+}}
+
+crate::godot_itest! { test_upcast {
+    let spatial = Spatial::new();
+    let id = spatial.get_instance_id();
+
+    let node: &Node = spatial.as_node();
+    assert_eq!(id, node.as_object().get_instance_id());
+
+    // Ref<T, Unique> -> Ref<Base, Shared>
+    let node: Ref<Node> = spatial.into();
+    assert_eq!(id, unsafe { node.assume_safe() }.get_instance_id());
+
+    // Ref<T, Shared> -> Ref<Base, Shared>, for manually-managed objects
+    let object: Ref<Object> = node.upcast();
+    assert_eq!(id, unsafe { object.assume_safe() }.get_instance_id());
+    unsafe { object.assume_unique() }.free();
 }}
 
 fn add_node_with<F, T>(to_arg: F)
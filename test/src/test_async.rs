@@ -1,10 +1,23 @@
-use std::{cell::RefCell, sync::Arc};
-
-use gdnative::{prelude::*, tasks::Context};
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::task::LocalSpawnExt;
+use gdnative::prelude::*;
+use gdnative::profiler::profile_sig;
+use gdnative::tasks::{Budget, Context, LocalExecutor};
 
 pub(crate) fn run_tests() -> bool {
-    // Relevant tests in GDScript
-    true
+    // Most relevant tests in GDScript
+    let mut status = true;
+
+    status &= test_budget();
+    status &= test_local_executor();
+
+    status
 }
 
 thread_local! {
@@ -78,7 +91,7 @@ impl AsyncMethods {
         obj: Ref<Object>,
         name: String,
     ) -> impl std::future::Future<Output = i32> + 'static {
-        async move {
+        gdnative::tasks::profiled(profile_sig!("resume_add"), async move {
             let b = ctx.until_resume().await;
             let b = i32::from_variant(&b).unwrap();
 
@@ -90,6 +103,98 @@ impl AsyncMethods {
             let c = i32::from_variant(&c[0]).unwrap();
 
             a + b + c
+        })
+    }
+
+    #[method]
+    fn live_tasks(&self) -> u64 {
+        gdnative::tasks::live_tasks() as u64
+    }
+}
+
+crate::godot_itest! { test_budget {
+    let budget = Budget::new(Duration::from_millis(5));
+    let count = Rc::new(Cell::new(0));
+
+    for _ in 0..10 {
+        let count = Rc::clone(&count);
+        budget.push(move || {
+            std::thread::sleep(Duration::from_millis(2));
+            count.set(count.get() + 1);
+        });
+    }
+
+    let polls = Rc::new(Cell::new(0));
+    budget.spawn({
+        let budget = budget.clone();
+        let polls = Rc::clone(&polls);
+        async move {
+            for _ in 0..5 {
+                budget.checkpoint().await;
+                std::thread::sleep(Duration::from_millis(2));
+                polls.set(polls.get() + 1);
+            }
         }
+    });
+    assert_eq!(11, budget.len());
+
+    // Work is spread over multiple frames
+    assert!(!budget.run());
+    assert!(!budget.is_empty());
+
+    let mut frames = 1;
+    while !budget.is_empty() {
+        budget.run();
+        frames += 1;
+        assert!(frames < 100, "budget should make progress every frame");
     }
+
+    assert_eq!(10, count.get());
+    assert_eq!(5, polls.get());
+}}
+
+/// Returns a future that wakes itself until it has been polled `count` times, like a task
+/// yielding to the next frame.
+fn yield_times(count: u32, polls: Rc<Cell<u32>>) -> impl Future<Output = ()> {
+    std::future::poll_fn(move |cx| {
+        polls.set(polls.get() + 1);
+        if polls.get() >= count {
+            return Poll::Ready(());
+        }
+
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
 }
+
+crate::godot_itest! { test_local_executor {
+    let executor = LocalExecutor::new();
+
+    let done = Rc::new(Cell::new(false));
+    executor
+        .spawn_local({
+            let done = Rc::clone(&done);
+            async move { done.set(true) }
+        })
+        .unwrap();
+
+    // Tasks aren't polled on spawn
+    assert!(!done.get());
+    assert_eq!(1, executor.len());
+
+    executor.run_until_stalled();
+    assert!(done.get());
+    assert!(executor.is_empty());
+
+    // A task waking itself while polled is polled again on the next call, not the same one
+    let polls = Rc::new(Cell::new(0));
+    executor
+        .spawn_local(yield_times(3, Rc::clone(&polls)))
+        .unwrap();
+
+    for expected in 1..=3 {
+        executor.run_until_stalled();
+        assert_eq!(expected, polls.get());
+    }
+    assert!(executor.is_empty());
+}}
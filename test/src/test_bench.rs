@@ -0,0 +1,55 @@
+//! Timings that are too slow for the default suite. Enabled with the `bench` feature:
+//!
+//! ```text
+//! cargo build --manifest-path test/Cargo.toml --release --features bench
+//! ```
+
+use std::time::{Duration, Instant};
+
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_bench_pool_array_collect();
+
+    status
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+crate::godot_itest! { test_bench_pool_array_collect {
+    const LEN: i32 = 100_000;
+
+    let (pushed, push) = time(|| {
+        let mut arr = PoolArray::<i32>::new();
+        for i in 0..LEN {
+            arr.push(i);
+        }
+        arr
+    });
+
+    let (collected, collect) = time(|| (0..LEN).collect::<PoolArray<i32>>());
+
+    // What `collect` did before it wrote into the array directly
+    let (through_vec, collect_vec) =
+        time(|| PoolArray::from_vec((0..LEN).collect::<Vec<i32>>()));
+
+    let (filtered, filter) = time(|| (0..LEN).filter(|i| i % 2 == 0).collect::<PoolArray<i32>>());
+    let (filtered_vec, filter_vec) = time(|| {
+        PoolArray::from_vec((0..LEN).filter(|i| i % 2 == 0).collect::<Vec<i32>>())
+    });
+
+    assert_eq!(pushed, collected);
+    assert_eq!(pushed, through_vec);
+    assert_eq!(filtered, filtered_vec);
+
+    godot_print!("   pool array of {LEN} elements:");
+    godot_print!("     push:    {push:?}");
+    godot_print!("     collect: {collect:?} (through Vec: {collect_vec:?})");
+    godot_print!("     collect with unknown length: {filter:?} (through Vec: {filter_vec:?})");
+}}
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gdnative::bus;
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_bus_delivery();
+    status &= test_bus_freed_instance();
+
+    status
+}
+
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<BusListener>();
+}
+
+struct Scored(i64);
+
+struct Reset;
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct BusListener {
+    total: i64,
+}
+
+#[methods]
+impl BusListener {
+    fn new(_base: &Node) -> Self {
+        BusListener { total: 0 }
+    }
+
+    fn on_scored(&mut self, _base: TRef<Node>, event: &Scored) {
+        self.total += event.0;
+    }
+
+    fn on_reset(&mut self, _base: TRef<Node>, _event: &Reset) {
+        self.total = 0;
+    }
+}
+
+crate::godot_itest! { test_bus_delivery {
+    let listener = BusListener::new_instance().into_shared();
+    let base = unsafe { listener.base().assume_safe() };
+    let subscription = bus::subscribe(base, BusListener::on_scored);
+
+    let seen = Rc::new(Cell::new(0));
+    let sink = Rc::clone(&seen);
+    let plain = bus::subscribe_fn(move |event: &Scored| sink.set(sink.get() + event.0));
+    assert_eq!(2, bus::subscriber_count::<Scored>());
+
+    bus::publish(Scored(3));
+    bus::publish(Scored(4));
+    assert_eq!(0, seen.get());
+
+    bus::flush();
+    assert_eq!(7, seen.get());
+    assert_eq!(7, base.with::<BusListener, _>(|listener, _| listener.total).unwrap());
+
+    assert!(subscription.unsubscribe());
+    assert!(plain.unsubscribe());
+    assert_eq!(0, bus::subscriber_count::<Scored>());
+
+    bus::publish(Scored(1));
+    bus::flush();
+    assert_eq!(7, seen.get());
+    assert_eq!(7, base.with::<BusListener, _>(|listener, _| listener.total).unwrap());
+
+    unsafe { listener.into_base().assume_unique().free() };
+}}
+
+crate::godot_itest! { test_bus_freed_instance {
+    let listener = BusListener::new_instance().into_shared();
+    let base = unsafe { listener.base().assume_safe() };
+    let _subscription = bus::subscribe(base, BusListener::on_reset);
+    assert_eq!(1, bus::subscriber_count::<Reset>());
+
+    unsafe { listener.into_base().assume_unique().free() };
+
+    bus::publish(Reset);
+    bus::flush();
+    assert_eq!(0, bus::subscriber_count::<Reset>());
+}}
@@ -0,0 +1,154 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gdnative::api::{Engine, GlobalConstants, InputEventKey, SceneTree};
+use gdnative::console::{self, ArgError, Command, ConsoleError, ConsoleUi};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_console_execute();
+    status &= test_console_history_and_completion();
+    status &= test_console_call();
+    status &= test_console_ui();
+
+    status
+}
+
+crate::godot_itest! { test_console_execute {
+    let spawned = Rc::new(Cell::new(0));
+    Command::new("test_spawn", {
+        let spawned = spawned.clone();
+        move |(kind, count): (String, Option<u32>)| {
+            let count = count.unwrap_or(1);
+            spawned.set(spawned.get() + count);
+            format!("spawned {count} {kind}")
+        }
+    })
+    .with_help("Spawns things")
+    .register();
+    console::command("test_fail", |(): ()| Err::<(), _>("nope"));
+
+    assert_eq!(Ok("spawned 1 goblin".into()), console::execute("test_spawn goblin"));
+    assert_eq!(
+        Ok("spawned 3 orc chief".into()),
+        console::execute("test_spawn 'orc chief' 3"),
+    );
+    assert_eq!(4, spawned.get());
+
+    assert_eq!(
+        Err(ConsoleError::Args {
+            command: "test_spawn".into(),
+            usage: "<String> [u32]".into(),
+            error: ArgError::Invalid { index: 1, value: "many".into(), expected: "u32" },
+        }),
+        console::execute("test_spawn goblin many"),
+    );
+    assert!(matches!(
+        console::execute("test_spawn goblin 1 2"),
+        Err(ConsoleError::Args { error: ArgError::Excess { count: 1 }, .. }),
+    ));
+    assert!(matches!(
+        console::execute("test_spawn"),
+        Err(ConsoleError::Args { error: ArgError::Missing { index: 0, .. }, .. }),
+    ));
+    assert_eq!(4, spawned.get());
+
+    assert_eq!(
+        Err(ConsoleError::Failed { command: "test_fail".into(), message: "nope".into() }),
+        console::execute("test_fail"),
+    );
+    assert_eq!(
+        Err(ConsoleError::UnknownCommand("test_missing".into())),
+        console::execute("test_missing"),
+    );
+    assert!(matches!(console::execute("test_spawn 'goblin"), Err(ConsoleError::Syntax(_))));
+
+    assert_eq!(
+        Ok("test_spawn <String> [u32] -- Spawns things".into()),
+        console::execute("help test_spawn"),
+    );
+
+    assert!(console::remove_command("test_spawn"));
+    assert!(console::remove_command("test_fail"));
+    assert!(!console::remove_command("test_fail"));
+}}
+
+crate::godot_itest! { test_console_history_and_completion {
+    console::clear_history();
+    console::command("test_alpha", |(): ()| ());
+    console::command("test_beta", |(): ()| ());
+
+    console::execute("test_alpha").unwrap();
+    console::execute("test_alpha").unwrap();
+    console::execute("  ").unwrap();
+    console::execute("test_beta").unwrap();
+    assert_eq!(vec!["test_alpha".to_string(), "test_beta".into()], console::history());
+
+    assert_eq!(vec!["test_alpha".to_string(), "test_beta".into()], console::complete("test_"));
+    assert_eq!(vec!["test_beta".to_string()], console::complete("test_b"));
+    assert_eq!(vec!["help test_alpha".to_string()], console::complete("help test_a"));
+    assert!(console::complete("test_alpha ").is_empty());
+
+    // Methods of registered classes are completed for `call`
+    let methods = console::complete("call /root/DoesNotExist get_v");
+    assert!(methods.contains(&"call /root/DoesNotExist get_value".to_string()));
+
+    console::remove_command("test_alpha");
+    console::remove_command("test_beta");
+    console::clear_history();
+}}
+
+crate::godot_itest! { test_console_call {
+    let main_loop = Engine::godot_singleton().get_main_loop().unwrap();
+    let tree = unsafe { main_loop.assume_safe() }.cast::<SceneTree>().unwrap();
+    let root = unsafe { tree.root().unwrap().assume_safe() };
+
+    let node = Node::new().into_shared();
+    let node = unsafe { node.assume_safe() };
+    node.set_name("ConsoleTarget");
+    root.add_child(node, false);
+
+    assert_eq!(Ok("ConsoleTarget".into()), console::execute("call ConsoleTarget get_name"));
+    assert_eq!(Ok(String::new()), console::execute("call ConsoleTarget set_name Renamed"));
+    assert_eq!("Renamed", node.name().to_string());
+
+    assert!(matches!(
+        console::execute("call Renamed no_such_method"),
+        Err(ConsoleError::Failed { .. }),
+    ));
+    assert!(matches!(
+        console::execute("call DoesNotExist get_name"),
+        Err(ConsoleError::Failed { .. }),
+    ));
+
+    node.free();
+}}
+
+crate::godot_itest! { test_console_ui {
+    let parent = Node::new();
+    let mut ui = ConsoleUi::attach(&parent);
+    assert!(!ui.is_open());
+
+    let key = InputEventKey::new();
+    key.set_scancode(GlobalConstants::KEY_QUOTELEFT);
+    key.set_pressed(true);
+    assert!(ui.handle_input(&key));
+    assert!(ui.is_open());
+
+    key.set_scancode(GlobalConstants::KEY_A);
+    assert!(!ui.handle_input(&key));
+
+    key.set_scancode(GlobalConstants::KEY_ESCAPE);
+    assert!(ui.handle_input(&key));
+    assert!(!ui.is_open());
+
+    ui.submit("help");
+    ui.print("text");
+
+    parent.free();
+    assert!(!ui.is_open());
+    ui.toggle();
+    ui.submit("help");
+}}
@@ -0,0 +1,64 @@
+use std::thread;
+
+use gdnative::export::DeferredSignalEmitter;
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_deferred_signal_emitter();
+
+    status
+}
+
+#[cfg(not(feature = "no-manual-register"))]
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<DeferredSignals>();
+}
+
+#[cfg(feature = "no-manual-register")]
+pub(crate) fn register(_handle: InitHandle) {}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[register_with(Self::register)]
+struct DeferredSignals;
+
+#[methods]
+impl DeferredSignals {
+    fn new(_base: &Reference) -> Self {
+        DeferredSignals
+    }
+
+    fn register(builder: &ClassBuilder<Self>) {
+        builder
+            .signal("progress")
+            .with_param_untyped("value")
+            .done();
+    }
+
+    /// Emits `progress` with the values `0..count` from another thread. Used by the GDScript
+    /// tests, which check that the signals arrive on a later frame.
+    #[method]
+    fn emit_from_thread(&self, #[base] base: &Reference, count: i64) -> bool {
+        let emitter = DeferredSignalEmitter::new(base);
+        thread::spawn(move || (0..count).all(|i| emitter.emit("progress", &[i.to_variant()])))
+            .join()
+            .unwrap()
+    }
+}
+
+crate::godot_itest! { test_deferred_signal_emitter {
+    let node = Node::new();
+    let emitter = DeferredSignalEmitter::new(&*node);
+    assert_eq!(node.get_instance_id(), emitter.target().to_i64());
+
+    let queued = thread::spawn({
+        let emitter = emitter.clone();
+        move || emitter.emit("ready", &[])
+    });
+    assert!(queued.join().unwrap());
+
+    // The signal is discarded, since the node no longer exists when deferred calls are processed.
+    node.free();
+}}
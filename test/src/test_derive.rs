@@ -2,7 +2,8 @@ use std::cell::{self, Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use gdnative::export::Property;
+use gdnative::core_types::GodotStr;
+use gdnative::export::{Property, PropertyBag, Watched};
 use gdnative::prelude::*;
 
 pub(crate) fn run_tests() -> bool {
@@ -11,17 +12,30 @@ pub(crate) fn run_tests() -> bool {
     status &= test_derive_to_variant();
     status &= test_derive_to_variant_repr();
     status &= test_derive_to_variant_str();
+    status &= test_derive_to_variant_transparent();
     status &= test_derive_owned_to_variant();
     status &= test_derive_nativeclass();
     status &= test_derive_nativeclass_without_constructor();
+    status &= test_derive_nativeclass_with_base();
     status &= test_derive_nativeclass_without_inherit();
     status &= test_derive_nativeclass_godot_attr_without_base();
     status &= test_derive_nativeclass_godot_attr_with_base();
     status &= test_derive_nativeclass_godot_attr_deref_return();
     status &= test_derive_nativeclass_godot_attr_rename_method();
     status &= test_derive_nativeclass_godot_attr_all_arguments();
+    status &= test_derive_nativeclass_borrowed_object_arguments();
     status &= test_derive_nativeclass_with_property_get_set();
     status &= test_derive_nativeclass_property_with_only_getter();
+    status &= test_derive_nativeclass_property_bag();
+    status &= test_derive_nativeclass_watched_property();
+    status &= test_derive_nativeclass_conditional_properties();
+    status &= test_derive_nativeclass_property_usage();
+    status &= test_derive_nativeclass_property_hints();
+    status &= test_derive_nativeclass_flags_property();
+    status &= test_derive_nativeclass_fallible_setter();
+    status &= test_derive_nativeclass_method_err();
+    status &= test_derive_nativeclass_borrowed_string_arguments();
+    status &= test_derive_nativeclass_processing_methods();
 
     status
 }
@@ -36,8 +50,19 @@ pub(crate) fn register(handle: InitHandle) {
     handle.add_class::<GodotAttrDerefReturn>();
     handle.add_class::<GodotAttrRenameMethod>();
     handle.add_class::<GodotAttrAllArguments>();
+    handle.add_class::<BorrowedObjectArgs>();
     handle.add_class::<CustomGetSet>();
     handle.add_class::<MyVec>();
+    handle.add_class::<DynamicProps>();
+    handle.add_class::<WatchedProps>();
+    handle.add_class::<ConditionalProps>();
+    handle.add_class::<UsageProps>();
+    handle.add_class::<HintedProps>();
+    handle.add_class::<FlagsProps>();
+    handle.add_class::<ValidatedProps>();
+    handle.add_class::<FallibleMethods>();
+    handle.add_class::<BorrowedStringArgs>();
+    handle.add_class::<ProcessingMethods>();
 }
 
 #[cfg(feature = "no-manual-register")]
@@ -269,6 +294,33 @@ crate::godot_itest! { test_derive_to_variant_str {
     assert_eq!(None, Variant::new("D").to::<ToVarStr>());
 }}
 
+crate::godot_itest! { test_derive_to_variant_transparent {
+    #[derive(Clone, Eq, PartialEq, Debug, ToVariant, FromVariant)]
+    #[variant(transparent)]
+    struct Health {
+        value: u32,
+        #[variant(skip)]
+        regenerating: bool,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Debug, OwnedToVariant, FromVariant)]
+    #[variant(transparent)]
+    struct Name(String);
+
+    let health = Health { value: 42, regenerating: true };
+    let variant = health.to_variant();
+    assert_eq!(Some(42), variant.to::<u32>());
+    assert_eq!(
+        Some(Health { value: 42, regenerating: false }),
+        variant.to::<Health>()
+    );
+    assert_eq!(None, Variant::new("42").to::<Health>());
+
+    let variant = Name("Goblin".into()).owned_to_variant();
+    assert_eq!(Some("Goblin"), variant.to::<String>().as_deref());
+    assert_eq!(Some(Name("Goblin".into())), variant.to::<Name>());
+}}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 
 crate::godot_itest! { test_derive_owned_to_variant {
@@ -349,6 +401,23 @@ crate::godot_itest! { test_derive_nativeclass_without_constructor {
     assert_eq!(Ok(54), foo.map(|foo, base| { foo.answer(&base) }));
 }}
 
+crate::godot_itest! { test_derive_nativeclass_with_base {
+    let base = Reference::new();
+    base.set_meta("configured", true);
+    let thing = Instance::<MinimalDerive, _>::new_with_base(base);
+    assert_eq!(Ok(54), thing.map(|thing, base| thing.answer(&base)));
+    let base = thing.into_base();
+    assert_eq!(Some(true), base.get_meta("configured", Variant::nil()).to::<bool>());
+    assert_eq!(unsafe { base.call("answer", &[]).to::<i64>() }, Some(54));
+
+    // Replaces the script attached before
+    let foo = EmplacementOnly(42).emplace_with_base(base);
+    assert_eq!(Ok(42), foo.map(|foo, base| foo.answer(&base)));
+    let base = foo.into_base();
+    assert_eq!(Some(true), base.get_meta("configured", Variant::nil()).to::<bool>());
+    assert!(Instance::<MinimalDerive, _>::try_from_base(base).is_err());
+}}
+
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 
 #[derive(NativeClass)]
@@ -502,6 +571,75 @@ crate::godot_itest! { test_derive_nativeclass_godot_attr_all_arguments {
 
 // ----------------------------------------------------------------------------------------------------------------------------------------------
 
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct BorrowedObjectArgs {
+    last_body: Option<String>,
+}
+
+#[methods]
+impl BorrowedObjectArgs {
+    fn new(_owner: &Reference) -> Self {
+        Self { last_body: None }
+    }
+
+    #[method]
+    fn name_of(&self, node: &Node) -> String {
+        node.name().to_string()
+    }
+
+    #[method]
+    fn on_body_entered(&mut self, body: TRef<Node>) {
+        self.last_body = Some(body.name().to_string());
+    }
+
+    #[method]
+    fn last_body(&self) -> Option<String> {
+        self.last_body.clone()
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_borrowed_object_arguments {
+    let thing = Instance::<BorrowedObjectArgs, _>::new().into_shared();
+    let base = unsafe { thing.base().assume_safe() };
+
+    let node = Node::new().into_shared();
+    let node = unsafe { node.assume_safe() };
+    node.set_name("Player");
+
+    let name = unsafe { base.call("name_of", &[node.to_variant()]) };
+    assert_eq!(Some("Player".to_owned()), name.to::<String>());
+
+    // An object of the wrong class is reported as an error, without calling the method.
+    let reference = Reference::new().into_shared();
+    let name = unsafe { base.call("name_of", &[reference.to_variant()]) };
+    assert!(name.is_nil());
+
+    let emitter = Node::new().into_shared();
+    let emitter = unsafe { emitter.assume_safe() };
+    emitter.add_user_signal("body_entered", VariantArray::new_shared());
+    emitter
+        .connect(
+            "body_entered",
+            base,
+            "on_body_entered",
+            VariantArray::new_shared(),
+            0,
+        )
+        .unwrap();
+    emitter.emit_signal("body_entered", &[node.to_variant()]);
+
+    let last_body = unsafe { base.call("last_body", &[]) };
+    assert_eq!(Some("Player".to_owned()), last_body.to::<String>());
+
+    unsafe {
+        emitter.assume_unique().free();
+        node.assume_unique().free();
+    }
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
 #[derive(NativeClass)]
 #[inherit(Node)]
 struct CustomGetSet {
@@ -602,3 +740,597 @@ crate::godot_itest! { test_derive_nativeclass_property_with_only_getter {
     let _ = std::panic::catch_unwind(|| owner.set("size", 3));
     assert_eq!(u32::from_variant(&owner.get("size")).unwrap(), 1);
 }}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct DynamicProps {
+    #[property]
+    fixed: i64,
+    extra: PropertyBag,
+}
+
+#[methods]
+impl DynamicProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            fixed: 0,
+            extra: PropertyBag::new(),
+        }
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_property_bag {
+    use gdnative::export::user_data::Map;
+    let (owner, script) = DynamicProps::new_instance().decouple();
+
+    owner.set("speed", 2.5);
+    owner.set("fixed", 42);
+    owner.set("name", "Dynamic");
+
+    assert_eq!(Some(2.5), owner.get("speed").to::<f64>());
+    assert_eq!(Some(42), owner.get("fixed").to::<i64>());
+    assert_eq!("Dynamic", owner.name().to_string());
+
+    script
+        .map(|script| {
+            assert_eq!(42, script.fixed);
+            assert_eq!(1, script.extra.len());
+            assert_eq!(Some(2.5), script.extra.get("speed").and_then(|v| v.to::<f64>()));
+        })
+        .unwrap();
+
+    let listed = owner
+        .get_property_list()
+        .iter()
+        .filter_map(|entry| entry.to::<Dictionary>())
+        .any(|entry| entry.get("name").and_then(|name| name.to::<String>()).as_deref() == Some("speed"));
+    assert!(listed);
+
+    owner.set("speed", Variant::nil());
+    assert!(owner.get("speed").is_nil());
+    script.map(|script| assert!(script.extra.is_empty())).unwrap();
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct WatchedProps {
+    #[property(set = "Self::set_items")]
+    items: Watched<VariantArray>,
+    changes: u32,
+}
+
+#[methods]
+impl WatchedProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            items: Watched::new(VariantArray::new_shared()),
+            changes: 0,
+        }
+    }
+
+    fn set_items(&mut self, _owner: TRef<Node>, items: Watched<VariantArray>) {
+        if self.items.set(items.into_inner()) {
+            self.changes += 1;
+        }
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_watched_property {
+    use gdnative::export::user_data::{Map, MapMut};
+    let (owner, script) = WatchedProps::new_instance().decouple();
+
+    let stats = Dictionary::new();
+    stats.insert("hp", 10);
+    let stats = stats.into_shared();
+    let items = VariantArray::new();
+    items.push(1);
+    items.push(&stats);
+    let items = items.into_shared();
+
+    owner.set("items", &items);
+    script.map(|script| assert_eq!(1, script.changes)).unwrap();
+
+    // Setting the same contents again is not a change.
+    owner.set("items", &items);
+    script.map(|script| assert_eq!(1, script.changes)).unwrap();
+
+    // The array is shared with the instance, so this modifies the property in place, like the
+    // inspector does before calling the setter.
+    items.set(0, 2);
+    owner.set("items", &items);
+    script.map(|script| assert_eq!(2, script.changes)).unwrap();
+
+    // Nested collections are compared by their contents too.
+    stats.update("hp", 5);
+    script
+        .map_mut(|script| {
+            assert!(script.items.is_changed());
+            assert!(script.items.take_changed());
+            assert!(!script.items.take_changed());
+        })
+        .unwrap();
+
+    owner.set("items", items.duplicate_deep().into_shared());
+    script.map(|script| assert_eq!(2, script.changes)).unwrap();
+
+    // NaNs are equal to each other.
+    let with_nan = VariantArray::new();
+    with_nan.push(f64::NAN);
+    let with_nan = with_nan.into_shared();
+    owner.set("items", &with_nan);
+    owner.set("items", &with_nan);
+    script.map(|script| assert_eq!(3, script.changes)).unwrap();
+
+    // Collections may contain themselves.
+    let cyclic = VariantArray::new();
+    cyclic.push(0);
+    cyclic.push(Variant::nil());
+    let cyclic = cyclic.into_shared();
+    cyclic.set(1, &cyclic);
+    owner.set("items", &cyclic);
+    owner.set("items", &cyclic);
+    script.map(|script| assert_eq!(4, script.changes)).unwrap();
+
+    cyclic.set(0, 1);
+    owner.set("items", &cyclic);
+    script.map(|script| assert_eq!(5, script.changes)).unwrap();
+
+    // Breaks the cycle, so that the array is freed.
+    cyclic.set(1, Variant::nil());
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct ConditionalProps {
+    #[property(refresh_inspector)]
+    mode: i64,
+    #[property(visible_if = "Self::is_custom")]
+    custom_value: f64,
+    #[property(path = "shadows/enabled", group_toggle)]
+    shadows: bool,
+    #[property(path = "shadows/size")]
+    shadow_size: i64,
+}
+
+#[methods]
+impl ConditionalProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            mode: 0,
+            custom_value: 0.0,
+            shadows: false,
+            shadow_size: 2,
+        }
+    }
+
+    fn is_custom(&self) -> bool {
+        self.mode == 1
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_conditional_properties {
+    use gdnative::export::PropertyUsage;
+
+    let (owner, _script) = ConditionalProps::new_instance().decouple();
+
+    let shown_in_editor = |name: &str| {
+        owner
+            .get_property_list()
+            .iter()
+            .filter_map(|entry| entry.to::<Dictionary>())
+            .filter(|entry| entry.get("name").and_then(|name| name.to::<String>()).as_deref() == Some(name))
+            .filter_map(|entry| entry.get("usage").and_then(|usage| usage.to::<u32>()))
+            .any(|usage| PropertyUsage::from_bits_truncate(usage).contains(PropertyUsage::EDITOR))
+    };
+
+    assert!(shown_in_editor("mode"));
+    assert!(shown_in_editor("shadows/enabled"));
+    assert!(!shown_in_editor("custom_value"));
+    assert!(!shown_in_editor("shadows/size"));
+
+    owner.set("mode", 1);
+    owner.set("shadows/enabled", true);
+    assert!(shown_in_editor("custom_value"));
+    assert!(shown_in_editor("shadows/size"));
+
+    // Hidden properties are still accessible
+    owner.set("shadows/enabled", false);
+    owner.set("shadows/size", 4);
+    assert_eq!(Some(4), owner.get("shadows/size").to::<i64>());
+    assert!(!shown_in_editor("shadows/size"));
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct UsageProps {
+    #[property(usage = "STORAGE")]
+    saved: i64,
+    #[property(usage = "EDITOR | NETWORK")]
+    synced: i64,
+}
+
+#[methods]
+impl UsageProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            saved: 1,
+            synced: 2,
+        }
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_property_usage {
+    use gdnative::export::PropertyUsage;
+
+    let (owner, _script) = UsageProps::new_instance().decouple();
+
+    let usage = |name: &str| {
+        owner
+            .get_property_list()
+            .iter()
+            .filter_map(|entry| entry.to::<Dictionary>())
+            .filter(|entry| entry.get("name").and_then(|name| name.to::<String>()).as_deref() == Some(name))
+            .find_map(|entry| entry.get("usage").and_then(|usage| usage.to::<u32>()))
+            .map(PropertyUsage::from_bits_truncate)
+    };
+
+    assert_eq!(Some(PropertyUsage::storage_only()), usage("saved"));
+    assert_eq!(Some(PropertyUsage::EDITOR | PropertyUsage::NETWORK), usage("synced"));
+    assert_eq!(Some(1), owner.get("saved").to::<i64>());
+    assert_eq!(Some(2), owner.get("synced").to::<i64>());
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct HintedProps {
+    #[property(hint = "Self::quality_hint")]
+    quality: i64,
+    #[property(hint = "Self::fade_hint")]
+    fade: f32,
+    #[property(hint = "Self::texture_hint")]
+    texture: String,
+    #[property(hint = "Self::name_hint")]
+    display_name: String,
+}
+
+#[methods]
+impl HintedProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            quality: 1,
+            fade: 1.0,
+            texture: String::new(),
+            display_name: String::new(),
+        }
+    }
+
+    fn quality_hint() -> gdnative::export::hint::EnumHint {
+        use gdnative::export::hint::{EnumHint, EnumHintEntry};
+        EnumHint::with_entries(vec![
+            EnumHintEntry::with_value("Low".into(), 1),
+            EnumHintEntry::with_value("High".into(), 10),
+        ])
+    }
+
+    fn fade_hint() -> gdnative::export::hint::ExpEasingHint {
+        gdnative::export::hint::ExpEasingHint::new()
+            .attenuation()
+            .in_out()
+    }
+
+    fn texture_hint() -> gdnative::export::hint::StringHint {
+        gdnative::export::hint::StringHint::file(["*.png", "*.jpg"])
+    }
+
+    fn name_hint() -> gdnative::export::hint::StringHint {
+        gdnative::export::hint::StringHint::placeholder("Unnamed")
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_property_hints {
+    let (owner, _script) = HintedProps::new_instance().decouple();
+
+    let hint_string = |name: &str| {
+        owner
+            .get_property_list()
+            .iter()
+            .filter_map(|entry| entry.to::<Dictionary>())
+            .find(|entry| entry.get("name").and_then(|name| name.to::<String>()).as_deref() == Some(name))
+            .and_then(|entry| entry.get("hint_string"))
+            .and_then(|hint_string| hint_string.to::<String>())
+    };
+
+    assert_eq!(Some("Low:1,High:10"), hint_string("quality").as_deref());
+    assert_eq!(Some("attenuation,inout"), hint_string("fade").as_deref());
+    assert_eq!(Some("*.png,*.jpg"), hint_string("texture").as_deref());
+    assert_eq!(Some("Unnamed"), hint_string("display_name").as_deref());
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+bitflags::bitflags! {
+    struct Layers: u32 {
+        const GROUND = 1 << 0;
+        const WATER = 1 << 1;
+        const AIR = 1 << 3;
+        const SURFACE = Self::GROUND.bits | Self::WATER.bits;
+    }
+}
+
+gdnative::export_flags!(Layers, GROUND, WATER, AIR, SURFACE);
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct FlagsProps {
+    #[property]
+    layers: Layers,
+}
+
+#[methods]
+impl FlagsProps {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            layers: Layers::GROUND,
+        }
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_flags_property {
+    use gdnative::export::user_data::Map;
+
+    let (owner, script) = FlagsProps::new_instance().decouple();
+
+    let entry = owner
+        .get_property_list()
+        .iter()
+        .filter_map(|entry| entry.to::<Dictionary>())
+        .find(|entry| entry.get("name").and_then(|name| name.to::<String>()).as_deref() == Some("layers"))
+        .unwrap();
+    assert_eq!(Some("GROUND,WATER,,AIR".into()), entry.get("hint_string").and_then(|s| s.to::<String>()));
+    assert_eq!(Some(VariantType::I64 as i64), entry.get("type").and_then(|t| t.to::<i64>()));
+
+    assert_eq!(Some(1), owner.get("layers").to::<i64>());
+
+    owner.set("layers", 0b1011);
+    assert_eq!(Some(0b1011), owner.get("layers").to::<i64>());
+    script
+        .map(|script| assert_eq!(Layers::SURFACE | Layers::AIR, script.layers))
+        .unwrap();
+
+    // Unknown bits are rejected
+    owner.set("layers", 0b0100);
+    assert_eq!(Some(0b1011), owner.get("layers").to::<i64>());
+    assert!(Layers::from_variant(&(-1).to_variant()).is_err());
+    assert_eq!(Ok(Layers::WATER), Layers::from_variant(&Layers::WATER.to_variant()));
+
+    owner.free();
+}}
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct ValidatedProps {
+    #[property(get = "Self::health", set = "Self::set_health")]
+    health: i64,
+}
+
+#[methods]
+impl ValidatedProps {
+    fn new(_owner: &Node) -> Self {
+        Self { health: 100 }
+    }
+
+    fn health(&self, _owner: TRef<Node>) -> i64 {
+        self.health
+    }
+
+    fn set_health(&mut self, _owner: TRef<Node>, health: i64) -> Result<(), String> {
+        if health < 0 {
+            return Err(format!("health can't be negative, got {health}"));
+        }
+        self.health = health;
+        Ok(())
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_fallible_setter {
+    use std::sync::{Arc, Mutex};
+
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+    let handler_rejected = Arc::clone(&rejected);
+    gdnative::export::set_validation_handler(move |err| {
+        handler_rejected.lock().unwrap().push(err.clone());
+    });
+
+    let (owner, _script) = ValidatedProps::new_instance().decouple();
+
+    owner.set("health", 50);
+    assert_eq!(Some(50), owner.get("health").to::<i64>());
+
+    owner.set("health", -1);
+    assert_eq!(Some(50), owner.get("health").to::<i64>());
+
+    let rejected = rejected.lock().unwrap();
+    assert_eq!(1, rejected.len());
+    assert_eq!("health", rejected[0].property_name);
+    assert_eq!(owner.get_instance_id(), rejected[0].instance_id.to_i64());
+    assert_eq!("health can't be negative, got -1", rejected[0].message);
+
+    owner.free();
+}}
+
+// ----------------------------------------------------------------------------------------------------------------------------------------------
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+#[register_with(Self::register_signals)]
+struct FallibleMethods {
+    errors: RefCell<Vec<(String, String)>>,
+}
+
+#[methods]
+impl FallibleMethods {
+    fn new(_owner: &Node) -> Self {
+        Self {
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn register_signals(builder: &ClassBuilder<Self>) {
+        builder
+            .signal("script_error")
+            .with_param("method", VariantType::GodotString)
+            .with_param("message", VariantType::GodotString)
+            .done();
+    }
+
+    #[method(err)]
+    fn parse(&self, text: String) -> Result<i64, std::num::ParseIntError> {
+        let value = text.trim().parse::<i64>()?;
+        Ok(value * 2)
+    }
+
+    #[method(err, name = "parse_renamed")]
+    fn parse_other(&self, text: String) -> Result<i64, std::num::ParseIntError> {
+        self.parse(text)
+    }
+
+    #[method]
+    fn record_error(&self, method: String, message: String) {
+        self.errors.borrow_mut().push((method, message));
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_method_err {
+    let instance = FallibleMethods::new_instance().into_shared();
+    let instance = unsafe { instance.assume_safe() };
+    let owner = instance.base();
+
+    owner
+        .connect("script_error", owner, "record_error", VariantArray::new_shared(), 0)
+        .unwrap();
+
+    assert_eq!(Some(42), unsafe { owner.call("parse", &[" 21 ".to_variant()]) }.to::<i64>());
+    assert!(unsafe { owner.call("parse", &["x".to_variant()]) }.is_nil());
+    assert!(unsafe { owner.call("parse_renamed", &["".to_variant()]) }.is_nil());
+
+    let errors = instance.map(|script, _| script.errors.borrow().clone()).unwrap();
+    assert_eq!(2, errors.len());
+    assert_eq!("parse", errors[0].0);
+    assert_eq!("invalid digit found in string", errors[0].1);
+    assert_eq!("parse_renamed", errors[1].0);
+
+    unsafe { owner.claim().assume_unique().free() };
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct BorrowedStringArgs;
+
+#[methods]
+impl BorrowedStringArgs {
+    fn new(_owner: &Reference) -> Self {
+        BorrowedStringArgs
+    }
+
+    #[method]
+    fn is_quit(&self, command: GodotStr<'_>) -> bool {
+        command == "quit"
+    }
+
+    #[method]
+    fn greet(&self, greeting: GodotStr<'_>, #[opt] name: GodotStr<'_>) -> String {
+        if name.is_empty() {
+            format!("{greeting}!")
+        } else {
+            format!("{greeting}, {name}!")
+        }
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_borrowed_string_arguments {
+    let instance = BorrowedStringArgs::new_instance().into_shared();
+    let base = unsafe { instance.base().assume_safe() };
+
+    assert_eq!(Some(true), unsafe { base.call("is_quit", &["quit".to_variant()]) }.to::<bool>());
+    assert_eq!(Some(false), unsafe { base.call("is_quit", &["qui".to_variant()]) }.to::<bool>());
+    assert!(unsafe { base.call("is_quit", &[1.to_variant()]) }.is_nil());
+
+    assert_eq!(
+        Some("Hello, Ferris!".to_owned()),
+        unsafe { base.call("greet", &["Hello".to_variant(), "Ferris".to_variant()]) }.to::<String>(),
+    );
+    assert_eq!(
+        Some("Hi!".to_owned()),
+        unsafe { base.call("greet", &["Hi".to_variant()]) }.to::<String>(),
+    );
+}}
+
+#[derive(NativeClass)]
+#[inherit(Node)]
+struct ProcessingMethods {
+    idle_time: f64,
+    physics_time: f64,
+}
+
+#[methods]
+impl ProcessingMethods {
+    fn new(_owner: &Node) -> Self {
+        ProcessingMethods {
+            idle_time: 0.0,
+            physics_time: 0.0,
+        }
+    }
+
+    #[method(process)]
+    fn tick(&mut self, delta: f64) {
+        self.idle_time += delta;
+    }
+
+    #[method(physics_process)]
+    fn step(&mut self, #[base] _base: &Node, delta: f64) {
+        self.physics_time += delta;
+    }
+}
+
+crate::godot_itest! { test_derive_nativeclass_processing_methods {
+    let instance = ProcessingMethods::new_instance();
+    let base = instance.base();
+
+    assert!(base.is_processing());
+    assert!(base.is_physics_processing());
+    assert!(base.has_method("_process"));
+    assert!(base.has_method("_physics_process"));
+    assert!(!base.has_method("tick"));
+
+    unsafe {
+        base.call("_process", &[0.5.to_variant()]);
+        base.call("_physics_process", &[0.25.to_variant()]);
+    }
+    assert_eq!(Ok((0.5, 0.25)), instance.map(|this, _| (this.idle_time, this.physics_time)));
+    instance.into_base().free();
+
+    // Nodes without the attributes are not affected
+    let node = Node::new();
+    assert!(!node.is_processing());
+    node.free();
+}}
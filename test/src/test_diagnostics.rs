@@ -0,0 +1,95 @@
+use gdnative::diagnostics::{self, DebugFields, DebugState, DumpFormat};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_dump_tree();
+    status &= test_dump_instances();
+
+    status
+}
+
+#[cfg(not(feature = "no-manual-register"))]
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<DebugProbe>();
+}
+
+#[cfg(feature = "no-manual-register")]
+pub(crate) fn register(_handle: InitHandle) {}
+
+#[derive(NativeClass)]
+#[no_constructor]
+#[inherit(Node)]
+struct DebugProbe {
+    health: u32,
+}
+
+#[methods]
+impl DebugProbe {}
+
+impl DebugState for DebugProbe {
+    fn debug_state(&self, fields: &mut DebugFields) {
+        fields.field("health", self.health).field("note", "a \"b\"");
+    }
+}
+
+crate::godot_itest! { test_dump_tree {
+    diagnostics::register_debug_state::<DebugProbe>();
+
+    let root = Node::new();
+    root.set_name("Root");
+    let probe = Instance::emplace(DebugProbe { health: 3 }).into_base();
+    probe.set_name("Probe");
+    let probe_id = probe.get_instance_id();
+    root.add_child(probe, false);
+
+    let json = diagnostics::dump_tree(&root, DumpFormat::Json);
+    assert_eq!(
+        format!(
+            concat!(
+                r#"{{"name":"Root","class":"Node","instance_id":{},"script":null,"rust":false,"#,
+                r#""state":{{}},"children":[{{"name":"Probe","class":"Node","instance_id":{},"#,
+                r#""script":"DebugProbe","rust":true,"#,
+                r#""state":{{"health":"3","note":"\"a \\\"b\\\"\""}},"children":[]}}]}}"#,
+            ),
+            root.get_instance_id(),
+            probe_id,
+        ),
+        json,
+    );
+
+    let dot = diagnostics::dump_tree(&root, DumpFormat::Dot);
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.contains(&format!("  n{} -> n{probe_id};\n", root.get_instance_id())));
+    assert!(dot.contains(r#"Probe (Node)\nhealth: 3\nnote: \"a \\\"b\\\"\"\nscript: DebugProbe""#));
+    assert!(dot.contains("style=filled"));
+
+    root.free();
+}}
+
+crate::godot_itest! { test_dump_instances {
+    diagnostics::register_debug_state::<DebugProbe>();
+    diagnostics::track_instances(true);
+
+    let probe = Instance::emplace(DebugProbe { health: 7 }).into_base();
+    let probe_id = probe.get_instance_id();
+    assert_eq!(
+        vec![InstanceId::from_i64(probe_id)],
+        diagnostics::live_instances::<DebugProbe>(),
+    );
+
+    let json = diagnostics::dump_instances::<DebugProbe>(DumpFormat::Json);
+    assert_eq!(
+        format!(
+            r#"[{{"instance_id":{probe_id},"class":"Node","state":{{"health":"7","note":"\"a \\\"b\\\"\""}}}}]"#,
+        ),
+        json,
+    );
+
+    probe.free();
+    assert!(diagnostics::live_instances::<DebugProbe>().is_empty());
+    assert_eq!("[]", diagnostics::dump_instances::<DebugProbe>(DumpFormat::Json));
+
+    diagnostics::track_instances(false);
+}}
@@ -0,0 +1,71 @@
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_dispatch_primitive_args();
+
+    status
+}
+
+#[cfg(not(feature = "no-manual-register"))]
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<Dispatch>();
+}
+
+#[cfg(feature = "no-manual-register")]
+pub(crate) fn register(_handle: InitHandle) {}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct Dispatch {
+    elapsed: f64,
+}
+
+#[methods]
+impl Dispatch {
+    fn new(_owner: &Reference) -> Self {
+        Dispatch { elapsed: 0.0 }
+    }
+
+    #[method]
+    fn add(&self, a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    #[method]
+    fn scale(&self, value: f64, by: f32) -> f64 {
+        value * by as f64
+    }
+
+    #[method]
+    fn negate(&self, value: bool) -> bool {
+        !value
+    }
+
+    #[method]
+    fn tick(&mut self, #[base] _owner: &Reference, delta: f64) -> f64 {
+        self.elapsed += delta;
+        self.elapsed
+    }
+}
+
+crate::godot_itest! { test_dispatch_primitive_args {
+    let obj = Dispatch::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    let call = |name: &str, args: &[Variant]| unsafe { base.call(name, args) };
+
+    assert_eq!(Some(5), call("add", &[2.to_variant(), 3.to_variant()]).to::<i64>());
+    assert_eq!(Some(3.0), call("scale", &[1.5.to_variant(), 2.0.to_variant()]).to::<f64>());
+    assert_eq!(Some(false), call("negate", &[true.to_variant()]).to::<bool>());
+    assert_eq!(Some(0.5), call("tick", &[0.5.to_variant()]).to::<f64>());
+    assert_eq!(Some(1.25), call("tick", &[0.75.to_variant()]).to::<f64>());
+
+    // Wrong arguments are reported, and the method isn't called.
+    assert!(call("add", &[2.to_variant(), "3".to_variant()]).is_nil());
+    assert!(call("add", &[2.to_variant()]).is_nil());
+    assert!(call("add", &[1.to_variant(), 2.to_variant(), 3.to_variant()]).is_nil());
+    assert!(call("tick", &["0.5".to_variant()]).is_nil());
+    assert_eq!(Some(1.5), call("tick", &[0.25.to_variant()]).to::<f64>());
+}}
@@ -0,0 +1,65 @@
+use gdnative::api::visual_server::{CanvasItemRid, CanvasRid};
+use gdnative::api::{ImageTexture, VisualServer};
+use gdnative::draw::Draw2D;
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_draw_batching();
+    status &= test_typed_rids();
+
+    status
+}
+
+crate::godot_itest! { test_draw_batching {
+    let white = Color::from_rgb(1.0, 1.0, 1.0);
+    let texture = ImageTexture::new().into_shared().upcast::<Texture>();
+
+    let mut geometry = Draw2D::new();
+    assert!(geometry.is_empty());
+
+    let id = geometry.add_texture(texture.clone());
+    assert_eq!(id, geometry.add_texture(texture));
+
+    geometry
+        .rect(Rect2::new(Vector2::ZERO, Vector2::new(10.0, 10.0)), white)
+        .circle(Vector2::new(5.0, 5.0), 4.0, white)
+        .texture_rect(Rect2::new(Vector2::ZERO, Vector2::ONE), id, white)
+        .texture_rect(Rect2::new(Vector2::ONE, Vector2::ONE), id, white)
+        .polyline(&[Vector2::ZERO, Vector2::ONE, Vector2::new(2.0, 0.0)], white, 1.0)
+        .line(Vector2::ZERO, Vector2::ONE, white, 2.0);
+
+    assert_eq!(4, geometry.batch_count());
+
+    let node = Node2D::new();
+    geometry.draw(&*node);
+    node.free();
+
+    geometry.clear();
+    assert!(geometry.is_empty());
+}}
+
+crate::godot_itest! { test_typed_rids {
+    let server = VisualServer::godot_singleton();
+    let canvas: CanvasRid = server.canvas_create();
+    let item: CanvasItemRid = server.canvas_item_create();
+    assert!(canvas.is_occupied());
+    assert!(item.is_occupied());
+
+    unsafe { server.canvas_item_set_parent(item, canvas.rid()) };
+
+    assert_eq!(Some(item), item.to_variant().to::<CanvasItemRid>());
+    assert_eq!(item.rid(), Rid::from(item));
+
+    let node = Node2D::new();
+    let node_item: CanvasItemRid = node.get_canvas_item();
+    assert!(node_item.is_occupied());
+    assert_ne!(item, node_item);
+    node.free();
+
+    unsafe {
+        server.free_rid(item.rid());
+        server.free_rid(canvas.rid());
+    }
+}}
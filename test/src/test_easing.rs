@@ -0,0 +1,79 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use gdnative::api::tween::{EaseType, TransitionType};
+use gdnative::easing::{interpolate, interpolate_value};
+use gdnative::globalscope::is_equal_approx;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_easing_endpoints();
+    status &= test_easing_values();
+
+    status
+}
+
+const TRANSITIONS: [TransitionType; 11] = [
+    TransitionType::LINEAR,
+    TransitionType::SINE,
+    TransitionType::QUINT,
+    TransitionType::QUART,
+    TransitionType::QUAD,
+    TransitionType::EXPO,
+    TransitionType::ELASTIC,
+    TransitionType::CUBIC,
+    TransitionType::CIRC,
+    TransitionType::BOUNCE,
+    TransitionType::BACK,
+];
+
+const EASES: [EaseType; 4] = [
+    EaseType::IN,
+    EaseType::OUT,
+    EaseType::IN_OUT,
+    EaseType::OUT_IN,
+];
+
+crate::godot_itest! { test_easing_endpoints {
+    for transition in TRANSITIONS {
+        for ease in EASES {
+            let start = interpolate(transition, ease, 0.0);
+            let end = interpolate(transition, ease, 1.0);
+
+            assert!(start.abs() < 0.002, "{transition:?} {ease:?} starts at {start}");
+            assert!((end - 1.0).abs() < 0.002, "{transition:?} {ease:?} ends at {end}");
+        }
+    }
+
+    // Out of range progress is clamped
+    assert_eq!(1.0, interpolate(TransitionType::QUAD, EaseType::IN, 2.0));
+    assert_eq!(0.0, interpolate(TransitionType::QUAD, EaseType::IN, -1.0));
+}}
+
+crate::godot_itest! { test_easing_values {
+    let cases = [
+        (TransitionType::SINE, EaseType::IN, 0.5, 1.0 - FRAC_1_SQRT_2),
+        (TransitionType::SINE, EaseType::OUT, 0.5, FRAC_1_SQRT_2),
+        (TransitionType::QUINT, EaseType::IN_OUT, 0.25, 0.015_625),
+        (TransitionType::QUART, EaseType::OUT, 0.5, 0.937_5),
+        (TransitionType::QUAD, EaseType::OUT_IN, 0.25, 0.375),
+        (TransitionType::EXPO, EaseType::IN, 0.5, 0.030_25),
+        (TransitionType::ELASTIC, EaseType::OUT, 0.5, 1.015_625),
+        (TransitionType::CUBIC, EaseType::IN_OUT, 0.75, 0.937_5),
+        (TransitionType::CIRC, EaseType::IN, 0.6, 0.2),
+        (TransitionType::BOUNCE, EaseType::OUT, 0.5, 0.765_625),
+        (TransitionType::BOUNCE, EaseType::IN_OUT, 0.25, 0.117_187_5),
+        (TransitionType::BACK, EaseType::IN, 0.5, -0.087_697_5),
+    ];
+
+    for (transition, ease, t, expected) in cases {
+        let actual = interpolate(transition, ease, t);
+        assert!(
+            is_equal_approx(actual, expected),
+            "{transition:?} {ease:?} at {t}: expected {expected}, got {actual}",
+        );
+    }
+
+    assert_eq!(15.0, interpolate_value(10.0, 20.0, 3.0, 6.0, TransitionType::LINEAR, EaseType::IN));
+    assert_eq!(20.0, interpolate_value(10.0, 20.0, 0.0, 0.0, TransitionType::LINEAR, EaseType::IN));
+}}
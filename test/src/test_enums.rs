@@ -0,0 +1,19 @@
+use gdnative::api::networked_multiplayer_peer::TransferMode;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_enums_unknown_values();
+
+    status
+}
+
+crate::godot_itest! { test_enums_unknown_values {
+    assert_eq!(Some("RELIABLE"), TransferMode::RELIABLE.name());
+    assert!(TransferMode::from(1).is_known());
+
+    let unknown = TransferMode(1000);
+    assert!(!unknown.is_known());
+    assert_eq!(None, unknown.name());
+    assert_eq!(1000, i64::from(unknown));
+}}
@@ -0,0 +1,69 @@
+use gdnative::api::file::ModeFlags;
+use gdnative::api::File;
+use gdnative::core_types::GodotError;
+use gdnative::file::{self, Progress};
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_file_save_load();
+    status &= test_file_chunks();
+
+    status
+}
+
+crate::godot_itest! { test_file_save_load {
+    let path = "user://gdnative_test_file.bin";
+    let data = (0..3_000_000_u32).map(|i| i as u8).collect::<Vec<_>>();
+
+    let mut saved = Vec::new();
+    file::save(path, &data, |progress| saved.push(progress)).unwrap();
+    assert_eq!(3, saved.len());
+    assert_eq!(
+        Some(&Progress { done: 3_000_000, total: 3_000_000 }),
+        saved.last(),
+    );
+
+    let mut loaded = Vec::new();
+    assert_eq!(data, file::load(path, |progress| loaded.push(progress)).unwrap());
+    assert_eq!(saved, loaded);
+
+    assert_eq!(
+        Err(GodotError::FileNotFound),
+        file::load("user://gdnative_test_does_not_exist.bin", |_| {}),
+    );
+}}
+
+crate::godot_itest! { test_file_chunks {
+    let path = "user://gdnative_test_chunks.bin";
+
+    let file = File::new();
+    file.open(path, ModeFlags::WRITE.0).unwrap();
+    file::write_chunks(&file, b"hello chunked world", 4, |_| {}).unwrap();
+    file.close();
+
+    file.open(path, ModeFlags::READ.0).unwrap();
+    file.seek(6);
+    let mut chunks = Vec::new();
+    file::read_chunks(&file, 4, |chunk, progress| {
+        chunks.push((chunk.to_vec(), progress.done));
+    })
+    .unwrap();
+    assert_eq!(
+        vec![
+            (b"chun".to_vec(), 4),
+            (b"ked ".to_vec(), 8),
+            (b"worl".to_vec(), 12),
+            (b"d".to_vec(), 13),
+        ],
+        chunks,
+    );
+
+    file.seek(0);
+    assert_eq!(b"hello chunked world".to_vec(), file::read_to_vec(&file, 7, |_| {}).unwrap());
+    file.close();
+
+    let empty = Progress { done: 0, total: 0 };
+    assert!(empty.is_complete());
+    assert_eq!(1.0, empty.fraction());
+}}
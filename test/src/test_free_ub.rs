@@ -6,6 +6,7 @@ pub(crate) fn run_tests() -> bool {
     let mut status = true;
 
     status &= test_owner_free_ub();
+    status &= test_free_guard();
 
     status
 }
@@ -73,3 +74,25 @@ crate::godot_itest! { test_owner_free_ub {
     // the values are eventually dropped
     assert_eq!(2, drop_counter.load(AtomicOrdering::Acquire));
 }}
+
+crate::godot_itest! { test_free_guard {
+    let drop_counter = Arc::new(AtomicUsize::new(0));
+
+    {
+        let guard = Bar(42, Arc::clone(&drop_counter)).emplace().into_base().into_free_guard();
+        assert_eq!(0, guard.get_child_count());
+    }
+    assert_eq!(1, drop_counter.load(AtomicOrdering::Acquire));
+
+    let node = Bar(42, Arc::clone(&drop_counter)).emplace().into_base().into_free_guard().release();
+    assert_eq!(1, drop_counter.load(AtomicOrdering::Acquire));
+    node.free();
+    assert_eq!(2, drop_counter.load(AtomicOrdering::Acquire));
+
+    let name = Bar(42, Arc::clone(&drop_counter)).emplace().into_base().scoped(|node| {
+        node.set_name("Scoped");
+        node.name().to_string()
+    });
+    assert_eq!("Scoped", name);
+    assert_eq!(3, drop_counter.load(AtomicOrdering::Acquire));
+}}
@@ -0,0 +1,43 @@
+use gdnative::api::{Translation, TranslationServer};
+use gdnative::i18n::{self, tr};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_i18n_tr();
+
+    status
+}
+
+crate::godot_itest! { test_i18n_tr {
+    let server = TranslationServer::godot_singleton();
+    let previous_locale = server.get_locale();
+
+    let translation = Translation::new();
+    translation.set_locale("de");
+    translation.add_message("GDNATIVE_TEST_GREETING", "Hallo");
+    let translation = translation.into_shared();
+    server.add_translation(&translation);
+
+    server.set_locale("en");
+    assert_eq!("GDNATIVE_TEST_GREETING", tr!("GDNATIVE_TEST_GREETING").to_string());
+
+    // Changing the locale invalidates the cache
+    server.set_locale("de");
+    assert_eq!("Hallo", tr!("GDNATIVE_TEST_GREETING").to_string());
+
+    let node = Node::new();
+    assert_eq!("Hallo", tr!(node, "GDNATIVE_TEST_GREETING").to_string());
+    node.set_message_translation(false);
+    assert_eq!("GDNATIVE_TEST_GREETING", tr!(node, "GDNATIVE_TEST_GREETING").to_string());
+    node.free();
+
+    // Removed translations are only seen after clearing the cache
+    server.remove_translation(&translation);
+    assert_eq!("Hallo", tr!("GDNATIVE_TEST_GREETING").to_string());
+    i18n::clear_cache();
+    assert_eq!("GDNATIVE_TEST_GREETING", tr!("GDNATIVE_TEST_GREETING").to_string());
+
+    server.set_locale(previous_locale);
+}}
@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gdnative::api::Input;
+use gdnative::input::{Gamepad, GamepadEvent, Gamepads};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_gamepad_event_from_signal_args();
+    status &= test_gamepads_hotplug();
+
+    status
+}
+
+crate::godot_itest! { test_gamepad_event_from_signal_args {
+    let pad = Gamepad::from_id(3);
+    assert_eq!(
+        Some(GamepadEvent::Connected(pad)),
+        GamepadEvent::from_signal_args(&[3.to_variant(), true.to_variant()]),
+    );
+    assert_eq!(
+        Some(GamepadEvent::Disconnected(pad)),
+        GamepadEvent::from_signal_args(&[3.to_variant(), false.to_variant()]),
+    );
+    assert_eq!(None, GamepadEvent::from_signal_args(&[3.to_variant()]));
+    assert_eq!(
+        None,
+        GamepadEvent::from_signal_args(&["3".to_variant(), true.to_variant()]),
+    );
+}}
+
+crate::godot_itest! { test_gamepads_hotplug {
+    const DEVICE: i64 = 13;
+    let input = Input::godot_singleton();
+    let pad = Gamepad::from_id(DEVICE);
+
+    let mut gamepads = Gamepads::new();
+    assert!(!gamepads.contains(pad));
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    gamepads.on_change({
+        let seen = seen.clone();
+        move |event| seen.borrow_mut().push(event)
+    });
+
+    // Simulates a device being plugged in.
+    input.joy_connection_changed(DEVICE, true, "gdnative test pad", "__gdnative_test_guid");
+    assert!(pad.is_connected());
+    assert_eq!("gdnative test pad", pad.name());
+    assert!(!pad.is_known());
+
+    assert_eq!(vec![GamepadEvent::Connected(pad)], gamepads.refresh());
+    assert!(gamepads.contains(pad));
+    assert!(gamepads.refresh().is_empty());
+
+    // The signal may arrive after the directory has been refreshed.
+    assert_eq!(None, gamepads.handle_connection_changed(DEVICE, true));
+
+    input.joy_connection_changed(DEVICE, false, "", "");
+    assert!(!pad.is_connected());
+    assert_eq!(
+        Some(GamepadEvent::Disconnected(pad)),
+        gamepads.handle_connection_changed(DEVICE, false),
+    );
+    assert!(!gamepads.contains(pad));
+    assert!(gamepads.refresh().is_empty());
+
+    assert_eq!(
+        vec![GamepadEvent::Connected(pad), GamepadEvent::Disconnected(pad)],
+        *seen.borrow(),
+    );
+}}
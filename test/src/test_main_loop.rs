@@ -0,0 +1,100 @@
+use gdnative::api::{InputEventKey, MainLoop};
+use gdnative::main_loop::{register_main_loop, CustomMainLoop};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_main_loop_callbacks();
+
+    status
+}
+
+#[cfg(not(feature = "no-manual-register"))]
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<CountingLoop>();
+}
+
+#[cfg(feature = "no-manual-register")]
+pub(crate) fn register(_handle: InitHandle) {}
+
+#[derive(NativeClass)]
+#[inherit(MainLoop)]
+#[register_with(register_main_loop)]
+struct CountingLoop {
+    log: Vec<String>,
+    ticks: i64,
+}
+
+#[methods]
+impl CountingLoop {
+    fn new(_base: &MainLoop) -> Self {
+        CountingLoop {
+            log: Vec::new(),
+            ticks: 0,
+        }
+    }
+}
+
+impl CustomMainLoop for CountingLoop {
+    fn initialize(&mut self, _base: TRef<MainLoop>) {
+        self.log.push("initialize".into());
+    }
+
+    fn iteration(&mut self, _base: TRef<MainLoop>, delta: f64) -> bool {
+        self.log.push(format!("iteration {delta}"));
+        self.ticks += 1;
+        self.ticks >= 2
+    }
+
+    fn idle(&mut self, _base: TRef<MainLoop>, delta: f64) -> bool {
+        self.log.push(format!("idle {delta}"));
+        false
+    }
+
+    fn input_event(&mut self, _base: TRef<MainLoop>, event: TRef<InputEvent>) {
+        self.log.push(format!("input {}", event.is_pressed()));
+    }
+
+    fn input_text(&mut self, _base: TRef<MainLoop>, text: String) {
+        self.log.push(format!("text {text}"));
+    }
+
+    fn finalize(&mut self, _base: TRef<MainLoop>) {
+        self.log.push("finalize".into());
+    }
+}
+
+crate::godot_itest! { test_main_loop_callbacks {
+    let main_loop = Instance::<CountingLoop, _>::new();
+    let base = main_loop.base();
+
+    let event = InputEventKey::new();
+    event.set_pressed(true);
+
+    unsafe {
+        base.call("_initialize", &[]);
+        assert_eq!(Some(false), base.call("_iteration", &[0.5.to_variant()]).to());
+        assert_eq!(Some(false), base.call("_idle", &[0.25.to_variant()]).to());
+        assert_eq!(Some(true), base.call("_iteration", &[0.5.to_variant()]).to());
+        base.call("_input_event", &[event.owned_to_variant()]);
+        base.call("_input_text", &["abc".to_variant()]);
+        base.call("_finalize", &[]);
+    }
+
+    let log = main_loop.map(|script, _| script.log.clone()).unwrap();
+    assert_eq!(
+        vec![
+            "initialize",
+            "iteration 0.5",
+            "idle 0.25",
+            "iteration 0.5",
+            "input true",
+            "text abc",
+            "finalize",
+        ],
+        log,
+    );
+
+    main_loop.into_base().free();
+}}
@@ -0,0 +1,90 @@
+use gdnative::nav::{NavMap, NavMeshBuilder, NavPath, NavPolygonBuilder, NavRegion};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_nav_path();
+    status &= test_nav_mesh_builder();
+    status &= test_nav_polygon_builder();
+    status &= test_nav_server_handles();
+
+    status
+}
+
+crate::godot_itest! { test_nav_path {
+    let path = NavPath::from_points(vec![
+        Vector2::new(0.0, 0.0),
+        Vector2::new(3.0, 0.0),
+        Vector2::new(3.0, 4.0),
+    ]);
+
+    assert_eq!(3, path.len());
+    assert_eq!(Some(Vector2::new(0.0, 0.0)), path.start());
+    assert_eq!(Some(Vector2::new(3.0, 4.0)), path.end());
+    assert_eq!(2, path.segments().count());
+    assert!((path.length() - 7.0).abs() < 1e-6);
+
+    assert_eq!(Some(Vector2::new(0.0, 0.0)), path.point_at_distance(-1.0));
+    assert_eq!(Some(Vector2::new(1.5, 0.0)), path.point_at_distance(1.5));
+    assert_eq!(Some(Vector2::new(3.0, 2.0)), path.point_at_distance(5.0));
+    assert_eq!(Some(Vector2::new(3.0, 4.0)), path.point_at_distance(100.0));
+
+    let pool = PoolArray::from_vec(path.points().to_vec());
+    assert_eq!(path, NavPath::from(pool));
+
+    let empty = NavPath::<Vector3>::default();
+    assert!(empty.is_empty());
+    assert_eq!(0.0, empty.length());
+    assert_eq!(None, empty.point_at_distance(1.0));
+}}
+
+crate::godot_itest! { test_nav_mesh_builder {
+    let a = Vector3::new(0.0, 0.0, 0.0);
+    let b = Vector3::new(1.0, 0.0, 0.0);
+    let c = Vector3::new(1.0, 0.0, 1.0);
+    let d = Vector3::new(0.0, 0.0, 1.0);
+
+    let mut builder = NavMeshBuilder::new();
+    builder.push_polygon(&[a, b, c]);
+    builder.push_polygon(&[a, c, d]);
+    assert_eq!(&[a, b, c, d], builder.vertices());
+    assert_eq!(2, builder.polygon_count());
+
+    let mesh = builder.done();
+    assert_eq!(4, mesh.vertices().len());
+    assert_eq!(2, mesh.get_polygon_count());
+    assert_eq!(vec![0, 2, 3], mesh.get_polygon(1).to_vec());
+}}
+
+crate::godot_itest! { test_nav_polygon_builder {
+    let mut builder = NavPolygonBuilder::new();
+    builder.push_outline(&[
+        Vector2::new(0.0, 0.0),
+        Vector2::new(10.0, 0.0),
+        Vector2::new(10.0, 10.0),
+        Vector2::new(0.0, 10.0),
+    ]);
+    assert_eq!(1, builder.outline_count());
+
+    let polygon = builder.done();
+    assert_eq!(1, polygon.get_outline_count());
+    assert!(polygon.get_polygon_count() > 0);
+}}
+
+crate::godot_itest! { test_nav_server_handles {
+    let map = NavMap::new();
+    assert!(map.rid().is_occupied());
+    map.set_cell_size(0.25);
+
+    let region = NavRegion::new();
+    assert!(region.rid().is_occupied());
+    assert_ne!(map.rid().rid(), region.rid().rid());
+    region.set_map(&map);
+    region.set_navigation_layers(1);
+    region.set_travel_cost(2.0);
+
+    // Both are freed on the server when dropped.
+    drop(region);
+    drop(map);
+}}
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use gdnative::api::networked_multiplayer_peer::{ConnectionStatus, TransferMode};
+use gdnative::api::MultiplayerPeerGDNative;
+use gdnative::net::{MultiplayerPeer, NetBinding, PacketPeer, PeerEvent};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_net_multiplayer_peer();
+
+    status
+}
+
+/// Peer that receives its own packets.
+struct LoopbackPeer {
+    packets: VecDeque<Vec<u8>>,
+    transfer_mode: TransferMode,
+    target_peer: i64,
+}
+
+impl PacketPeer for LoopbackPeer {
+    fn get_packet(&mut self) -> Result<Vec<u8>, GodotError> {
+        self.packets.pop_front().ok_or(GodotError::Unavailable)
+    }
+
+    fn put_packet(&mut self, packet: &[u8]) -> Result<(), GodotError> {
+        self.packets.push_back(packet.to_vec());
+        Ok(())
+    }
+
+    fn get_available_packet_count(&self) -> i64 {
+        self.packets.len() as i64
+    }
+
+    fn get_max_packet_size(&self) -> i64 {
+        1 << 16
+    }
+}
+
+impl MultiplayerPeer for LoopbackPeer {
+    fn set_transfer_mode(&mut self, mode: TransferMode) {
+        self.transfer_mode = mode;
+    }
+
+    fn get_transfer_mode(&self) -> TransferMode {
+        self.transfer_mode
+    }
+
+    fn set_target_peer(&mut self, id: i64) {
+        self.target_peer = id;
+    }
+
+    fn get_packet_peer(&self) -> i64 {
+        1
+    }
+
+    fn is_server(&self) -> bool {
+        true
+    }
+
+    fn poll(&mut self, _events: &mut Vec<PeerEvent>) {}
+
+    fn get_unique_id(&self) -> i32 {
+        1
+    }
+
+    fn set_refuse_new_connections(&mut self, _enable: bool) {}
+
+    fn is_refusing_new_connections(&self) -> bool {
+        false
+    }
+
+    fn get_connection_status(&self) -> ConnectionStatus {
+        ConnectionStatus::CONNECTED
+    }
+}
+
+crate::godot_itest! { test_net_multiplayer_peer {
+    let peer = MultiplayerPeerGDNative::new().into_shared();
+    let peer = unsafe { peer.assume_safe() };
+
+    let binding = NetBinding::multiplayer_peer(
+        &peer,
+        LoopbackPeer {
+            packets: VecDeque::new(),
+            transfer_mode: TransferMode::UNRELIABLE,
+            target_peer: 0,
+        },
+    );
+
+    assert_eq!(1, peer.get_unique_id());
+    assert_eq!(1, peer.get_packet_peer());
+    assert_eq!(ConnectionStatus::CONNECTED, peer.get_connection_status());
+
+    peer.set_transfer_mode(TransferMode::RELIABLE.0);
+    peer.set_target_peer(42);
+    assert_eq!(TransferMode::RELIABLE, peer.transfer_mode());
+    assert_eq!(42, binding.peer().target_peer);
+
+    assert!(peer.put_packet(PoolArray::from_slice(&[1, 2, 3])).is_ok());
+    assert!(peer.put_packet(PoolArray::from_slice(&[4, 5])).is_ok());
+    assert_eq!(2, peer.get_available_packet_count());
+    assert_eq!(&[1, 2, 3], &*peer.get_packet().read());
+    assert_eq!(&[4, 5], &*binding.peer_mut().get_packet().unwrap());
+    assert_eq!(0, peer.get_available_packet_count());
+
+    drop(binding);
+}}
@@ -0,0 +1,39 @@
+use gdnative::object::{HandlePolicy, ObjectHandle};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_object_handle();
+
+    status
+}
+
+crate::godot_itest! { test_object_handle {
+    let node = Node::new();
+    node.set_name("Target");
+
+    let handle = ObjectHandle::new(&*node).with_policy(HandlePolicy::Silent);
+    assert_eq!(node.instance_id(), handle.instance_id());
+    assert!(handle.is_valid());
+
+    let target = unsafe { handle.get() }.expect("node should be alive");
+    assert_eq!("Target", target.name().to_string());
+
+    let as_object = ObjectHandle::<Object>::from_instance_id(handle.instance_id());
+    assert!(as_object.is_valid());
+    let as_reference = ObjectHandle::<Reference>::from_instance_id(handle.instance_id())
+        .with_policy(HandlePolicy::Silent);
+    assert!(!as_reference.is_valid());
+    assert!(unsafe { as_reference.get() }.is_none());
+
+    let upgraded = unsafe { handle.upgrade() }.expect("node should be alive");
+    assert_eq!(node.instance_id(), unsafe { upgraded.assume_safe() }.instance_id());
+
+    node.free();
+
+    assert!(!handle.is_valid());
+    assert!(unsafe { handle.upgrade() }.is_none());
+    assert!(unsafe { handle.get() }.is_none());
+    assert_eq!(handle, handle.clone());
+}}
@@ -0,0 +1,59 @@
+use gdnative::export::user_data::OnceData;
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_once_data();
+
+    status
+}
+
+#[cfg(not(feature = "no-manual-register"))]
+pub(crate) fn register(handle: InitHandle) {
+    handle.add_class::<Config>();
+}
+
+#[cfg(feature = "no-manual-register")]
+pub(crate) fn register(_handle: InitHandle) {}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[user_data(OnceData<Self>)]
+struct Config {
+    #[property(get)]
+    name: String,
+    limits: Vec<i64>,
+}
+
+#[methods]
+impl Config {
+    fn new(_base: &Reference) -> Self {
+        Config {
+            name: "default".into(),
+            limits: vec![10, 20, 30],
+        }
+    }
+
+    #[method]
+    fn limit(&self, index: i64) -> i64 {
+        self.limits.get(index as usize).copied().unwrap_or(-1)
+    }
+}
+
+crate::godot_itest! { test_once_data {
+    let config = Config::new_instance().into_shared();
+    let config = unsafe { config.assume_safe() };
+    let base = config.base();
+
+    assert_eq!(Some("default".to_string()), base.get("name").to::<String>());
+    assert_eq!(Some(20), unsafe { base.call("limit", &[1.to_variant()]) }.to::<i64>());
+    assert_eq!(Some(-1), unsafe { base.call("limit", &[5.to_variant()]) }.to::<i64>());
+
+    let sum = config.map(|config, _| config.limits.iter().sum::<i64>()).unwrap();
+    assert_eq!(60, sum);
+
+    // Setters need mutable access, so the property is read-only
+    base.set("name", "changed");
+    assert_eq!(Some("default".to_string()), base.get("name").to::<String>());
+}}
@@ -0,0 +1,48 @@
+use gdnative::api::{PhysicsShapeQueryParameters, StaticBody};
+use gdnative::physics::{CollisionMask, ExclusionList, QueryFilter};
+use gdnative::prelude::*;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_physics_collision_mask();
+    status &= test_physics_query_filter();
+
+    status
+}
+
+crate::godot_itest! { test_physics_collision_mask {
+    let mask = CollisionMask::layer(1) | CollisionMask::layer(3);
+    assert_eq!(0b101, mask.bits());
+    assert!(mask.contains(3));
+    assert!(!mask.contains(2));
+    assert_eq!(CollisionMask::layer(1), mask.without_layer(3));
+    assert_eq!(1 << 31, CollisionMask::NONE.with_layer(32).bits());
+    assert_eq!(CollisionMask::ALL, CollisionMask::default());
+}}
+
+crate::godot_itest! { test_physics_query_filter {
+    let body = StaticBody::new();
+
+    let filter = QueryFilter::new()
+        .with_mask(CollisionMask::layer(2))
+        .with_areas(true)
+        .with_excluded(&body);
+    assert_eq!(1, filter.exclusions().len());
+    assert_eq!(body.get_rid(), filter.exclusions().rids()[0]);
+
+    let params = PhysicsShapeQueryParameters::new();
+    filter.apply(&params);
+    assert_eq!(0b10, params.collision_mask());
+    assert!(params.is_collide_with_bodies_enabled());
+    assert!(params.is_collide_with_areas_enabled());
+
+    let exclude = params.exclude();
+    assert_eq!(1, exclude.len());
+    assert_eq!(Some(body.get_rid()), exclude.get(0).to::<Rid>());
+
+    let list = std::iter::once(body.get_rid()).collect::<ExclusionList>();
+    assert_eq!(list.rids(), filter.exclusions().rids());
+
+    body.free();
+}}
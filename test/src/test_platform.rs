@@ -0,0 +1,18 @@
+use gdnative::platform::android;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_android_unavailable();
+
+    status
+}
+
+crate::godot_itest! { test_android_unavailable {
+    // The tests never run on Android.
+    assert!(!android::is_available());
+    assert!(android::jni_env().is_none());
+    assert!(android::activity().is_none());
+    assert!(android::surface().is_none());
+    assert!(!android::is_activity_resumed());
+}}
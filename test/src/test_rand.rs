@@ -0,0 +1,49 @@
+use gdnative::rand::{GodotRng, RngState};
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_rand_seeded_sequences();
+    status &= test_rand_snapshot_restore();
+
+    status
+}
+
+crate::godot_itest! { test_rand_seeded_sequences {
+    let mut a = GodotRng::with_seed(1234);
+    let mut b = GodotRng::with_seed(1234);
+    assert_eq!(1234, a.seed());
+
+    let a_values = (0..16).map(|_| a.next_u32()).collect::<Vec<_>>();
+    let b_values = (0..16).map(|_| b.next_u32()).collect::<Vec<_>>();
+    assert_eq!(a_values, b_values);
+
+    for _ in 0..16 {
+        let value = a.randi_range(-3, 3);
+        assert!((-3..=3).contains(&value));
+    }
+
+    let mut c = GodotRng::with_seed(4321);
+    let c_values = (0..16).map(|_| c.next_u32()).collect::<Vec<_>>();
+    assert_ne!(a_values, c_values);
+}}
+
+crate::godot_itest! { test_rand_snapshot_restore {
+    let mut rng = GodotRng::with_seed(99);
+    rng.next_u32();
+
+    let snapshot = rng.snapshot();
+    assert_eq!(99, snapshot.seed);
+    let expected = (0..8).map(|_| rng.randf()).collect::<Vec<_>>();
+
+    rng.restore(snapshot);
+    assert_eq!(expected, (0..8).map(|_| rng.randf()).collect::<Vec<_>>());
+
+    let mut restored = GodotRng::from_state(snapshot);
+    let mut cloned = restored.clone();
+    assert_eq!(expected[0], restored.randf());
+    assert_eq!(expected[0], cloned.randf());
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(snapshot, serde_json::from_str::<RngState>(&json).unwrap());
+}}
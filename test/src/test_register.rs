@@ -1,16 +1,39 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::ops::Add;
 
-use gdnative::export::{StaticArgs, StaticArgsMethod, StaticallyNamed};
+use gdnative::diagnostics;
+use gdnative::export::c_abi::{self, Status};
+use gdnative::export::hint::{IntHint, RangeHint};
+use gdnative::export::{class_db, InstantiateError, MixinState};
+use gdnative::export::{PropertyDefinition, StaticArgs, StaticArgsMethod, StaticallyNamed};
+use gdnative::init::{extend_class, LateRegistrationError};
+use gdnative::object::WithInstanceError;
 use gdnative::prelude::*;
 
 pub(crate) fn run_tests() -> bool {
     let mut status = true;
 
     status &= test_register_property();
+    status &= test_register_schema_properties();
     status &= test_advanced_methods();
     status &= test_varargs_gets();
     status &= test_varargs_to_tuple();
+    status &= test_c_export();
+    status &= test_cfg_godot();
+    status &= test_call_stack();
+    status &= test_watchdog();
+    status &= test_registration_report();
+    status &= test_class_db();
+    status &= test_mixin_state();
+    status &= test_c_abi();
+    status &= test_validate_api();
+    status &= test_storage();
+    status &= test_with_instance();
+    status &= test_rename_all();
+    status &= test_late_registration();
+    status &= test_trait_methods();
+    status &= test_registered_signals();
 
     status
 }
@@ -19,15 +42,31 @@ pub(crate) fn run_tests() -> bool {
 pub(crate) fn register(handle: InitHandle) {
     handle.add_class::<RegisterSignal>();
     handle.add_class::<RegisterProperty>();
+    handle.add_class::<SchemaProperties>();
     handle.add_class::<AdvancedMethods>();
     handle.add_class::<VarargsGets>();
     handle.add_class::<VarargsToTuple>();
+    handle.add_class::<CExport>();
+    handle.add_class::<CfgGodotMethods>();
+    handle.add_class::<CallStackProbe>();
+    handle.add_class::<MixinStateHolder>();
+    handle.add_class::<StorageV2>();
+    handle.add_class::<WithTarget>();
+    handle.add_class::<RenameAll>();
+    handle.add_class::<LateMembers>();
+    // SAFETY: `LateMembers` is only used on the main thread.
+    unsafe { handle.register_late::<LateMembers>(register_late_members) };
+    handle.add_class::<TraitSquare>();
+    handle.add_class::<TraitCircle>();
+    handle.add_class::<DerivedSignals>();
 }
 
 #[cfg(feature = "no-manual-register")]
 pub(crate) fn register(handle: InitHandle) {
     handle.add_class::<RegisterSignal>();
     handle.add_class::<RegisterProperty>();
+    // SAFETY: `LateMembers` is only used on the main thread.
+    unsafe { handle.register_late::<LateMembers>(register_late_members) };
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -44,6 +83,17 @@ impl NativeClass for RegisterSignal {
             .signal("progress")
             .with_param("amount", VariantType::I64)
             .done();
+        builder
+            .signal("finished")
+            .with_param_export::<i64>("code", Some(IntHint::Range(RangeHint::new(0, 255))))
+            .with_param_custom(
+                SignalParam::new("message", ExportInfo::new(VariantType::GodotString))
+                    .with_default(Variant::new("ok"))
+                    .with_usage(PropertyUsage::EDITOR),
+            )
+            .with_documentation("Emitted once the work is done.")
+            .with_default_flags(ConnectFlags::DEFERRED | ConnectFlags::ONESHOT)
+            .done();
     }
 }
 
@@ -104,6 +154,56 @@ crate::godot_itest! { test_register_property {
     assert_eq!(Some(4242), unsafe { base.call("get_value", &[]).to() });
 }}
 
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[register_with(Self::register)]
+struct SchemaProperties {
+    values: HashMap<String, Variant>,
+}
+
+#[methods]
+impl SchemaProperties {
+    fn new(_owner: TRef<Reference>) -> Self {
+        SchemaProperties {
+            values: HashMap::new(),
+        }
+    }
+
+    fn register(builder: &ClassBuilder<Self>) {
+        let schema = vec![
+            PropertyDefinition::of::<f64>("gravity", None).with_default(Variant::new(9.8)),
+            PropertyDefinition::new("title", ExportInfo::new(VariantType::GodotString)),
+        ];
+
+        builder.properties_from_schema(
+            schema,
+            |this, _base, name| this.values.get(name).cloned().unwrap_or_default(),
+            |this, _base, name, value| {
+                this.values.insert(name.to_owned(), value);
+            },
+        );
+    }
+}
+
+crate::godot_itest! { test_register_schema_properties {
+    let obj = SchemaProperties::new_instance();
+    let base = obj.into_base();
+
+    assert!(base.get("gravity").is_nil());
+    base.set("gravity", 4.5.to_variant());
+    base.set("title", "foo".to_variant());
+    assert_eq!(Some(4.5), base.get("gravity").to::<f64>());
+    assert_eq!(Some("foo".to_owned()), base.get("title").to::<String>());
+
+    let names = base
+        .get_property_list()
+        .iter()
+        .filter_map(|info| info.to::<Dictionary>()?.get("name")?.to::<String>())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"gravity".to_owned()));
+    assert!(names.contains(&"title".to_owned()));
+}}
+
 #[derive(NativeClass)]
 #[inherit(Reference)]
 #[register_with(register_methods)]
@@ -306,3 +406,788 @@ crate::godot_itest! { test_varargs_to_tuple {
     let args = [3_i64.to_variant(), 4_i64.to_variant(), 5_i64.to_variant()];
     assert_eq!(unsafe { base.call("calc", &args).to() }, Some(7));
 }}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[no_constructor]
+struct CExport {
+    offset: i64,
+}
+
+#[methods]
+impl CExport {
+    #[method(c_export)]
+    fn add(&self, a: i64, b: i64) -> i64 {
+        a + b + self.offset
+    }
+
+    #[method(name = "describe", c_export = "gdnative_test_c_export_describe")]
+    fn describe_offset(&self, #[base] base: TRef<Reference>) -> String {
+        format!("{} {}", base.get_class(), self.offset)
+    }
+}
+
+extern "C" {
+    fn gdnative_CExport_add(
+        this: *mut gdnative::sys::godot_object,
+        num_args: gdnative::libc::c_int,
+        args: *mut *mut gdnative::sys::godot_variant,
+    ) -> gdnative::sys::godot_variant;
+
+    fn gdnative_test_c_export_describe(
+        this: *mut gdnative::sys::godot_object,
+        num_args: gdnative::libc::c_int,
+        args: *mut *mut gdnative::sys::godot_variant,
+    ) -> gdnative::sys::godot_variant;
+}
+
+crate::godot_itest! { test_c_export {
+    let obj = CExport { offset: 40 }.emplace().into_shared();
+    let this = obj.base().as_ptr();
+
+    let mut args = [1.to_variant(), 2.to_variant()];
+    let mut arg_ptrs = args.iter_mut().map(|v| v.sys_mut()).collect::<Vec<_>>();
+    let ret = unsafe {
+        Variant::from_sys(gdnative_CExport_add(this, 2, arg_ptrs.as_mut_ptr()))
+    };
+    assert_eq!(Some(43), ret.to::<i64>());
+
+    let ret = unsafe {
+        Variant::from_sys(gdnative_test_c_export_describe(this, 0, std::ptr::null_mut()))
+    };
+    assert_eq!(Some("Reference 40".to_string()), ret.to::<String>());
+
+    // Objects that aren't instances of the class are rejected
+    let other = Reference::new().into_shared();
+    let ret = unsafe {
+        Variant::from_sys(gdnative_CExport_add(other.as_ptr(), 2, arg_ptrs.as_mut_ptr()))
+    };
+    assert!(ret.is_nil());
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct CfgGodotMethods;
+
+#[methods]
+impl CfgGodotMethods {
+    fn new(_base: &Reference) -> Self {
+        CfgGodotMethods
+    }
+
+    #[method]
+    fn always(&self) {}
+
+    #[method]
+    #[cfg_godot(feature = "__gdnative_test_missing_tag")]
+    fn missing_tag(&self) {}
+
+    #[method]
+    #[cfg_godot(not(feature = "__gdnative_test_missing_tag"))]
+    fn not_missing_tag(&self) {}
+
+    #[method]
+    #[cfg_godot(any(platform = "android", platform = "ios"))]
+    fn mobile_only(&self) {}
+}
+
+crate::godot_itest! { test_cfg_godot {
+    let obj = CfgGodotMethods::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    assert!(base.has_method("always"));
+    assert!(!base.has_method("missing_tag"));
+    assert!(base.has_method("not_missing_tag"));
+    assert!(!base.has_method("mobile_only"));
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct CallStackProbe;
+
+#[methods]
+impl CallStackProbe {
+    fn new(_base: &Reference) -> Self {
+        CallStackProbe
+    }
+
+    #[method]
+    fn outer(&self, #[base] base: TRef<Reference>) -> Variant {
+        unsafe { base.call("inner", &[]) }
+    }
+
+    #[method]
+    fn inner(&self) -> Vec<String> {
+        diagnostics::call_stack()
+            .iter()
+            .map(|frame| format!("{}::{}", frame.class(), frame.method()))
+            .collect()
+    }
+
+    #[method]
+    fn sleep(&self, millis: u64) {
+        std::thread::sleep(std::time::Duration::from_millis(millis));
+    }
+}
+
+crate::godot_itest! { test_call_stack {
+    let obj = CallStackProbe::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    let stack = unsafe { base.call("outer", &[]) };
+    assert_eq!(Some(Vec::<String>::new()), stack.to::<Vec<String>>());
+
+    diagnostics::record_calls(true);
+    let stack = unsafe { base.call("outer", &[]) };
+    diagnostics::record_calls(false);
+
+    assert_eq!(
+        Some(vec![
+            "CallStackProbe::outer".to_string(),
+            "CallStackProbe::inner".to_string(),
+        ]),
+        stack.to::<Vec<String>>(),
+    );
+    assert_eq!(0, diagnostics::call_depth());
+}}
+
+crate::godot_itest! { test_watchdog {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let obj = CallStackProbe::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    let slow_calls = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&slow_calls);
+    diagnostics::Watchdog::new(Duration::from_millis(10))
+        .on_slow_call(move |call| {
+            sink.lock().unwrap().push((call.frame().method().to_owned(), call.duration()));
+        })
+        .install();
+
+    unsafe {
+        base.call("sleep", &[0.to_variant()]);
+        base.call("sleep", &[20.to_variant()]);
+    }
+    assert!(diagnostics::Watchdog::uninstall());
+    unsafe { base.call("sleep", &[20.to_variant()]) };
+
+    let slow_calls = slow_calls.lock().unwrap();
+    assert_eq!(1, slow_calls.len());
+    assert_eq!("sleep", slow_calls[0].0);
+    assert!(slow_calls[0].1 >= Duration::from_millis(20));
+}}
+
+crate::godot_itest! { test_registration_report {
+    let report = gdnative::init::registration_report();
+
+    let class = report.class("RegisterSignal").expect("class should be registered");
+    assert_eq!("Reference", class.base);
+    assert!(!class.is_tool);
+    assert_eq!(vec!["progress".to_owned(), "finished".to_owned()], class.signals);
+
+    let class = report.class("RegisterProperty").expect("class should be registered");
+    assert!(class.properties.iter().any(|name| name == "value"));
+}}
+
+crate::godot_itest! { test_class_db {
+    let db = class_db();
+
+    assert!(db.contains("RegisterProperty"));
+    assert!(!db.contains("NotRegistered"));
+    assert!(db.class_names().iter().any(|name| name == "RegisterSignal"));
+
+    let class = db.class("RegisterProperty").expect("class should be registered");
+    assert_eq!("Reference", class.base);
+    assert!(class.properties.iter().any(|name| name == "value"));
+
+    let obj = db
+        .instantiate_as::<Reference>("RegisterProperty")
+        .expect("class should be instantiated");
+    let obj = unsafe { obj.assume_safe() };
+    assert_eq!(Some(42), obj.get("value").to::<i64>());
+
+    assert!(matches!(
+        db.instantiate_as::<Object>("RegisterProperty"),
+        Err(InstantiateError::IncompatibleType { .. })
+    ));
+    assert!(matches!(
+        db.instantiate_as::<Node>("RegisterProperty"),
+        Err(InstantiateError::IncompatibleType { .. })
+    ));
+    assert!(matches!(
+        db.instantiate("NotRegistered"),
+        Err(InstantiateError::UnknownClass(_))
+    ));
+}}
+
+static COUNTERS_DROPPED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[derive(Default)]
+struct Counter {
+    count: i64,
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        COUNTERS_DROPPED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[register_with(register_mixin_state)]
+#[no_constructor]
+struct MixinStateHolder;
+
+fn register_mixin_state(builder: &ClassBuilder<MixinStateHolder>) {
+    builder.mixin::<CounterMixin>();
+}
+
+#[methods(mixin = "CounterMixin", state = "Counter")]
+impl MixinStateHolder {
+    #[method]
+    fn increment(&self, #[base] base: &Reference, amount: i64) -> i64 {
+        let counter = MixinState::<Self, CounterMixin>::of(base).unwrap();
+        counter.map_mut(|counter| {
+            counter.count += amount;
+            counter.count
+        })
+    }
+}
+
+crate::godot_itest! { test_mixin_state {
+    use std::sync::atomic::Ordering;
+
+    let a = MixinStateHolder.emplace().into_shared().into_base();
+    let b = MixinStateHolder.emplace().into_shared().into_base();
+
+    assert_eq!(Some(1), unsafe { a.call("increment", &[1.to_variant()]) }.to::<i64>());
+    assert_eq!(Some(3), unsafe { a.call("increment", &[2.to_variant()]) }.to::<i64>());
+    assert_eq!(Some(5), unsafe { b.call("increment", &[5.to_variant()]) }.to::<i64>());
+
+    let state = MixinState::<MixinStateHolder, CounterMixin>::of(&unsafe { a.assume_safe() });
+    assert_eq!(Some(3), state.as_ref().map(|state| state.map(|counter| counter.count)));
+
+    let plain = Reference::new();
+    assert!(MixinState::<MixinStateHolder, CounterMixin>::of(&plain).is_none());
+
+    // States are dropped with the instance, unless a handle is still alive.
+    let dropped = COUNTERS_DROPPED.load(Ordering::SeqCst);
+    drop(b);
+    assert_eq!(dropped + 1, COUNTERS_DROPPED.load(Ordering::SeqCst));
+    drop(a);
+    assert_eq!(dropped + 1, COUNTERS_DROPPED.load(Ordering::SeqCst));
+    drop(state);
+    assert_eq!(dropped + 2, COUNTERS_DROPPED.load(Ordering::SeqCst));
+}}
+
+crate::godot_itest! { test_c_abi {
+    use std::ffi::{CStr, CString};
+
+    assert_eq!(c_abi::ABI_VERSION, c_abi::gdnative_plugin_abi_version());
+    assert_eq!(class_db().class_names().len(), c_abi::gdnative_plugin_class_count());
+
+    let class = CString::new("RegisterProperty").unwrap();
+    let unknown = CString::new("NotRegistered").unwrap();
+    let set_value = CString::new("set_value").unwrap();
+    let get_value = CString::new("get_value").unwrap();
+
+    let mut buf = [0; 64];
+    let count = unsafe { c_abi::gdnative_plugin_method_count(class.as_ptr()) };
+    let names = (0..count as usize)
+        .map(|index| {
+            let len = unsafe {
+                c_abi::gdnative_plugin_method_name(
+                    class.as_ptr(),
+                    index,
+                    buf.as_mut_ptr(),
+                    buf.len(),
+                )
+            };
+            assert!(len >= 0 && (len as usize) < buf.len());
+            let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+            name.to_str().unwrap().to_owned()
+        })
+        .collect::<Vec<_>>();
+    assert!(names.iter().any(|name| name == "get_value"));
+    assert_eq!(-1, unsafe { c_abi::gdnative_plugin_method_count(unknown.as_ptr()) });
+
+    let mut obj = Variant::nil().leak();
+    let status = unsafe { c_abi::gdnative_plugin_instantiate(class.as_ptr(), &mut obj) };
+    assert_eq!(Status::Ok, status);
+
+    let args = [4242.to_variant().leak()];
+    let mut ret = Variant::nil().leak();
+    let status = unsafe {
+        c_abi::gdnative_plugin_call(&mut obj, set_value.as_ptr(), args.as_ptr(), 1, &mut ret)
+    };
+    assert_eq!(Status::Ok, status);
+    unsafe { c_abi::gdnative_plugin_variant_destroy(&mut ret) };
+
+    let status = unsafe {
+        c_abi::gdnative_plugin_call(&mut obj, get_value.as_ptr(), std::ptr::null(), 0, &mut ret)
+    };
+    assert_eq!(Status::Ok, status);
+    assert_eq!(Some(4242), Variant::from_sys(ret).to::<i64>());
+
+    let status = unsafe { c_abi::gdnative_plugin_instantiate(unknown.as_ptr(), &mut ret) };
+    assert_eq!(Status::UnknownClass, status);
+
+    drop(args.map(Variant::from_sys));
+    unsafe { c_abi::gdnative_plugin_variant_destroy(&mut obj) };
+}}
+
+crate::godot_itest! { test_validate_api {
+    assert!(gdnative::init::diagnostics::validate_api());
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[storage(version = 2, migrate = "Self::migrate")]
+struct StorageV2 {
+    #[property(storage)]
+    health: i64,
+    #[property(storage)]
+    name: String,
+}
+
+impl StorageV2 {
+    fn new(_base: &Reference) -> Self {
+        StorageV2 {
+            health: 100,
+            name: "default".into(),
+        }
+    }
+
+    // Version 1 saved the health as `hp`
+    fn migrate(from_version: u32, fields: Dictionary<Unique>) -> Dictionary<Unique> {
+        assert_eq!(1, from_version);
+        fields.insert("health", fields.get_or_nil("hp"));
+        fields.erase("hp");
+        fields
+    }
+}
+
+#[methods]
+impl StorageV2 {}
+
+crate::godot_itest! { test_storage {
+    use gdnative::export::user_data::Map;
+    use gdnative::export::STORAGE_PROPERTY;
+
+    let (base, script) = StorageV2::new_instance().decouple();
+
+    let fields = Dictionary::new();
+    fields.insert("hp", 42);
+    fields.insert("name", "old");
+    let state = Dictionary::new();
+    state.insert("version", 1);
+    state.insert("fields", fields);
+    base.set(STORAGE_PROPERTY, state);
+
+    script
+        .map(|script| {
+            assert_eq!(42, script.health);
+            assert_eq!("old", script.name);
+        })
+        .unwrap();
+
+    let saved = base.get(STORAGE_PROPERTY).to::<Dictionary>().unwrap();
+    assert_eq!(Some(2), saved.get("version").and_then(|v| v.to::<u32>()));
+
+    let (other_base, other) = StorageV2::new_instance().decouple();
+    other_base.set(STORAGE_PROPERTY, saved);
+    other
+        .map(|script| {
+            assert_eq!(42, script.health);
+            assert_eq!("old", script.name);
+        })
+        .unwrap();
+
+    // State saved with a newer version is ignored
+    let newer = Dictionary::new();
+    newer.insert("version", 3);
+    newer.insert("fields", Dictionary::new());
+    other_base.set(STORAGE_PROPERTY, newer);
+    assert_eq!(42, other.map(|script| script.health).unwrap());
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct WithTarget {
+    health: i64,
+}
+
+#[methods]
+impl WithTarget {
+    fn new(_base: &Reference) -> Self {
+        WithTarget { health: 100 }
+    }
+
+    #[method]
+    fn take_damage(&mut self, amount: i64) -> i64 {
+        self.health -= amount;
+        self.health
+    }
+
+    #[method]
+    fn hit_self(&mut self, #[base] base: TRef<Reference>) -> bool {
+        match base.with::<WithTarget, _>(|target, _| target.take_damage(1)) {
+            Err(WithInstanceError::Borrow { call_stack, .. }) => call_stack
+                .iter()
+                .any(|frame| frame.class() == "WithTarget" && frame.method() == "hit_self"),
+            _ => false,
+        }
+    }
+}
+
+crate::godot_itest! { test_with_instance {
+    let target = WithTarget::new_instance().into_shared();
+    let base = unsafe { target.base().assume_safe() };
+    let object = base.upcast::<Object>();
+
+    assert_eq!(Ok(90), object.with::<WithTarget, _>(|target, _| target.take_damage(10)).map_err(|e| e.to_string()));
+    assert_eq!(Ok(85), object.with::<WithTarget, _>(|target, _| target.take_damage(5)).map_err(|e| e.to_string()));
+
+    let other = Reference::new().into_shared();
+    let other = unsafe { other.assume_safe() };
+    assert!(matches!(
+        other.with::<WithTarget, _>(|target, _| target.health),
+        Err(WithInstanceError::NotAnInstance { .. })
+    ));
+
+    // Re-entrant borrows are reported with the call borrowing the instance
+    diagnostics::record_calls(true);
+    assert_eq!(Some(true), unsafe { base.call("hit_self", &[]) }.to::<bool>());
+    diagnostics::record_calls(false);
+    assert_eq!(85, target.map(|target, _| target.health).unwrap());
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[native_class(rename = "RenamedStats", rename_all = "camelCase")]
+struct RenameAll {
+    #[property]
+    max_health: i64,
+    #[property(name = "armor_class")]
+    armor: i64,
+}
+
+#[methods(rename_all = "camelCase")]
+impl RenameAll {
+    fn new(_base: &Reference) -> Self {
+        RenameAll {
+            max_health: 100,
+            armor: 3,
+        }
+    }
+
+    #[method]
+    fn take_damage(&mut self, amount: i64) -> i64 {
+        self.max_health -= amount;
+        self.max_health
+    }
+
+    #[method(name = "raw_armor")]
+    fn armor_value(&self) -> i64 {
+        self.armor
+    }
+
+    #[method]
+    fn _on_timer_timeout(&self) {}
+}
+
+crate::godot_itest! { test_rename_all {
+    assert_eq!("RenamedStats", <RenameAll as StaticallyNamed>::CLASS_NAME);
+
+    let obj = RenameAll::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    assert!(base.has_method("takeDamage"));
+    assert!(!base.has_method("take_damage"));
+    assert!(base.has_method("raw_armor"));
+    assert!(base.has_method("_on_timer_timeout"));
+
+    assert_eq!(Some(90), unsafe { base.call("takeDamage", &[10.to_variant()]) }.to::<i64>());
+    assert_eq!(Some(90), base.get("maxHealth").to::<i64>());
+    assert_eq!(Some(3), base.get("armor_class").to::<i64>());
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct LateMembers;
+
+#[methods]
+impl LateMembers {
+    fn new(_base: &Reference) -> Self {
+        LateMembers
+    }
+}
+
+#[methods(mixin = "LateMixin")]
+impl LateMembers {
+    #[method]
+    fn late_method(&self) -> i64 {
+        42
+    }
+}
+
+fn register_late_members(builder: &ClassBuilder<LateMembers>) {
+    builder.mixin::<LateMixin>();
+    builder.signal("late_signal").done();
+}
+
+/// Not registered with the library.
+struct Unregistered;
+
+impl NativeClass for Unregistered {
+    type Base = Reference;
+    type UserData = user_data::Aether<Unregistered>;
+    fn nativeclass_init(_owner: TRef<Reference>) -> Unregistered {
+        Unregistered
+    }
+}
+
+crate::godot_itest! { test_late_registration {
+    // Members registered with `register_late` are added at the start of the first frame, which
+    // is tested in `tests.gd`.
+    let obj = LateMembers::new_instance().into_shared();
+    let base = unsafe { obj.base().assume_safe() };
+
+    // Existing instances see members added at run time
+    // SAFETY: Tests run on the main thread.
+    unsafe { extend_class::<LateMembers>(|builder| builder.signal("runtime_signal").done()) }
+        .unwrap();
+    assert!(base.has_signal("runtime_signal"));
+
+    let report = gdnative::init::registration_report();
+    let class = report.class("LateMembers").unwrap();
+    assert!(class.signals.iter().any(|name| name == "runtime_signal"));
+
+    assert!(matches!(
+        unsafe { extend_class::<Unregistered>(|_| {}) },
+        Err(LateRegistrationError::NotRegistered { .. })
+    ));
+}}
+
+#[methods(mixin = "ShapeMethods")]
+trait Shape {
+    #[method]
+    fn area(&self) -> f64;
+
+    #[method]
+    fn scale(&mut self, factor: f64);
+
+    #[method]
+    fn describe(&self, #[opt] prefix: Option<String>) -> String {
+        format!("{}{:.1}", prefix.unwrap_or_default(), self.area())
+    }
+
+    /// Not exported.
+    fn secret(&self) -> i64 {
+        42
+    }
+}
+
+fn register_shape<C>(builder: &ClassBuilder<C>)
+where
+    C: NativeClass + Shape,
+    C::UserData: user_data::Map + user_data::MapMut,
+{
+    builder.mixin::<ShapeMethods>();
+}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[register_with(register_shape)]
+struct TraitSquare {
+    side: f64,
+}
+
+#[methods]
+impl TraitSquare {
+    fn new(_base: &Reference) -> Self {
+        TraitSquare { side: 2.0 }
+    }
+}
+
+impl Shape for TraitSquare {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.side *= factor;
+    }
+}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+#[register_with(register_shape)]
+struct TraitCircle {
+    radius: f64,
+}
+
+#[methods]
+impl TraitCircle {
+    fn new(_base: &Reference) -> Self {
+        TraitCircle { radius: 1.0 }
+    }
+}
+
+impl Shape for TraitCircle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.radius *= factor;
+    }
+
+    fn describe(&self, prefix: Option<String>) -> String {
+        format!("circle {}{:.1}", prefix.unwrap_or_default(), self.area())
+    }
+}
+
+crate::godot_itest! { test_trait_methods {
+    let square = TraitSquare::new_instance().into_shared();
+    let circle = TraitCircle::new_instance().into_shared();
+    let square = unsafe { square.base().assume_safe() };
+    let circle = unsafe { circle.base().assume_safe() };
+
+    assert!(!square.has_method("secret"));
+    assert!(!circle.has_method("secret"));
+
+    assert_eq!(Some(4.0), unsafe { square.call("area", &[]) }.to::<f64>());
+    unsafe { square.call("scale", &[1.5.to_variant()]) };
+    assert_eq!(Some(9.0), unsafe { square.call("area", &[]) }.to::<f64>());
+
+    // Provided methods are exported, and use the implementation of the class if overridden
+    assert_eq!(
+        Some("area 9.0".to_owned()),
+        unsafe { square.call("describe", &["area ".to_variant()]) }.to::<String>(),
+    );
+    assert_eq!(
+        Some("circle 3.1".to_owned()),
+        unsafe { circle.call("describe", &[]) }.to::<String>(),
+    );
+}}
+
+#[derive(NativeClass)]
+#[inherit(Reference)]
+struct DerivedSignals {
+    cleared: Vec<(i64, String)>,
+}
+
+#[methods]
+impl DerivedSignals {
+    fn new(_base: &Reference) -> Self {
+        DerivedSignals {
+            cleared: Vec::new(),
+        }
+    }
+
+    /// Emitted when a level is cleared.
+    #[signal(flags = "DEFERRED")]
+    fn level_cleared(
+        #[hint(IntHint::Range(RangeHint::new(1, 99)))] level: i64,
+        #[default(GodotString::from("none"))] bonus: GodotString,
+    ) {
+    }
+
+    #[signal(name = "game_over")]
+    fn lost() {}
+
+    #[method]
+    fn on_level_cleared(&mut self, level: i64, bonus: String) {
+        self.cleared.push((level, bonus));
+    }
+}
+
+const HINT_NONE: i64 = gdnative::sys::godot_property_hint_GODOT_PROPERTY_HINT_NONE as i64;
+const HINT_RANGE: i64 = gdnative::sys::godot_property_hint_GODOT_PROPERTY_HINT_RANGE as i64;
+
+/// Returns the entry of `get_signal_list` for `signal`.
+fn signal_info(object: TRef<Reference>, signal: &str) -> Option<Dictionary> {
+    object
+        .get_signal_list()
+        .iter()
+        .filter_map(|info| info.to::<Dictionary>())
+        .find(|info| {
+            info.get("name")
+                .and_then(|name| name.to::<String>())
+                .as_deref()
+                == Some(signal)
+        })
+}
+
+/// Returns the names, types and hints of the arguments of `signal`.
+fn signal_args(object: TRef<Reference>, signal: &str) -> Vec<(String, i64, i64)> {
+    let info = signal_info(object, signal).expect("signal should be listed");
+    let args = info
+        .get("args")
+        .and_then(|args| args.to::<VariantArray>())
+        .unwrap();
+    args.iter()
+        .map(|arg| {
+            let arg = arg.to::<Dictionary>().unwrap();
+            let field = |key: &str| arg.get(key).unwrap();
+            (
+                field("name").to::<String>().unwrap(),
+                field("type").to::<i64>().unwrap(),
+                field("hint").to::<i64>().unwrap(),
+            )
+        })
+        .collect()
+}
+
+crate::godot_itest! { test_registered_signals {
+    let manual = RegisterSignal.emplace().into_shared();
+    let manual = unsafe { manual.base().assume_safe() };
+
+    assert!(manual.has_signal("progress"));
+    assert!(manual.has_signal("finished"));
+    assert_eq!(
+        vec![
+            ("code".to_owned(), VariantType::I64 as i64, HINT_RANGE),
+            ("message".to_owned(), VariantType::GodotString as i64, HINT_NONE),
+        ],
+        signal_args(manual, "finished"),
+    );
+
+    let emitter = DerivedSignals::new_instance().into_shared();
+    let emitter = unsafe { emitter.base().assume_safe() };
+
+    assert!(emitter.has_signal("level_cleared"));
+    assert!(emitter.has_signal("game_over"));
+    assert!(!emitter.has_signal("lost"));
+    assert!(!emitter.has_method("level_cleared"));
+    assert_eq!(
+        vec![
+            ("level".to_owned(), VariantType::I64 as i64, HINT_RANGE),
+            ("bonus".to_owned(), VariantType::GodotString as i64, HINT_NONE),
+        ],
+        signal_args(emitter, "level_cleared"),
+    );
+    assert!(signal_args(emitter, "game_over").is_empty());
+
+    let listener = DerivedSignals::new_instance().into_shared();
+    let listener_base = unsafe { listener.base().assume_safe() };
+    emitter
+        .connect(
+            "level_cleared",
+            listener_base,
+            "on_level_cleared",
+            VariantArray::new_shared(),
+            0,
+        )
+        .unwrap();
+    emitter.emit_signal("level_cleared", &[3.to_variant(), "perfect".to_variant()]);
+
+    assert_eq!(
+        vec![(3, "perfect".to_owned())],
+        listener.map(|listener, _| listener.cleared.clone()).unwrap(),
+    );
+}}
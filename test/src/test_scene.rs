@@ -0,0 +1,223 @@
+use gdnative::prelude::*;
+use gdnative::scene::{NodeSnapshot, SnapshotChange, SnapshotOptions, TreeBatch};
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_tree_batch_order();
+    status &= test_tree_batch_coalesce();
+    status &= test_scene_unique_nodes();
+    status &= test_scene_snapshot();
+
+    status
+}
+
+fn parent_id(node: &Node) -> Option<i64> {
+    node.get_parent()
+        .map(|parent| unsafe { parent.assume_safe() }.get_instance_id())
+}
+
+crate::godot_itest! { test_tree_batch_order {
+    let parent = Node::new();
+    let other = Node::new();
+    let a = Node::new();
+    let b = Node::new();
+
+    let mut batch = TreeBatch::new();
+    batch.add_child(&parent, &a);
+    batch.add_child(&parent, &b);
+    batch.apply();
+
+    assert_eq!(2, parent.get_child_count());
+    assert_eq!(0, a.get_index());
+    assert_eq!(1, b.get_index());
+
+    let mut batch = TreeBatch::new();
+    batch.reparent(&a, &other);
+    batch.apply();
+
+    assert_eq!(1, parent.get_child_count());
+    assert_eq!(Some(other.get_instance_id()), parent_id(&a));
+
+    parent.free();
+    other.free();
+}}
+
+crate::godot_itest! { test_tree_batch_coalesce {
+    let parent = Node::new();
+    let other = Node::new();
+    let a = Node::new();
+    let b = Node::new();
+
+    let mut batch = TreeBatch::new();
+    batch.add_child(&parent, &a);
+    batch.add_child(&parent, &b);
+    batch.remove_child(&parent, &a);
+    batch.add_child(&other, &b);
+    assert_eq!(2, batch.len());
+    batch.apply();
+
+    assert_eq!(0, parent.get_child_count());
+    assert_eq!(None, parent_id(&a));
+    assert_eq!(Some(other.get_instance_id()), parent_id(&b));
+
+    a.free();
+    parent.free();
+    other.free();
+}}
+
+crate::godot_itest! { test_scene_unique_nodes {
+    let root = unsafe { Node::new().into_shared().assume_safe() };
+    let middle = unsafe { Node::new().into_shared().assume_safe() };
+    let player = unsafe { Node2D::new().into_shared().assume_safe() };
+    middle.set_name("Middle");
+    player.set_name("Player");
+
+    root.add_child(middle, false);
+    middle.add_child(player, false);
+    middle.set_owner(root);
+    player.set_owner(root);
+    player.set_unique_name_in_owner(true);
+
+    let found = unsafe { middle.get_unique_as::<Node2D>("%Player") }.expect("unique node");
+    assert_eq!(player.instance_id(), found.instance_id());
+    assert!(unsafe { middle.get_unique_as::<Node2D>("Player") }.is_some());
+    assert!(unsafe { middle.get_unique_as::<Spatial>("%Player") }.is_none());
+    assert!(unsafe { middle.get_unique_as::<Node>("%Middle/Player") }.is_none());
+
+    let owned = unsafe { player.get_owned_as::<Node>("Middle") }.expect("owned node");
+    assert_eq!(middle.instance_id(), owned.instance_id());
+
+    let mut cache = NodeCache::new();
+    for _ in 0..2 {
+        let cached = unsafe { cache.get_unique_as::<Node2D>(&middle, "%Player") };
+        assert_eq!(Some(player.instance_id()), cached.map(|node| node.instance_id()));
+    }
+
+    // Renamed and reparented nodes are looked up again
+    player.set_name("Hero");
+    assert!(unsafe { cache.get_unique_as::<Node2D>(&middle, "%Player") }.is_none());
+    player.set_name("Player");
+
+    middle.remove_child(player);
+    root.add_child(player, false);
+    player.set_owner(root);
+    player.set_unique_name_in_owner(true);
+    let cached = unsafe { cache.get_unique_as::<Node2D>(&middle, "%Player") };
+    assert_eq!(Some(player.instance_id()), cached.map(|node| node.instance_id()));
+
+    let mut fields = NodeFields::default();
+    unsafe { fields.resolve_nodes(&middle) }.unwrap();
+    assert_eq!(
+        Some(player.instance_id()),
+        fields.player.map(|node| unsafe { node.assume_safe() }.instance_id()),
+    );
+    assert_eq!(
+        Some(root.instance_id()),
+        fields.root.map(|node| unsafe { node.assume_safe() }.instance_id()),
+    );
+
+    let mut missing = MissingNodeField::default();
+    assert!(matches!(
+        unsafe { missing.resolve_nodes(&middle) },
+        Err(NodeFieldError::WrongType { field: "player", .. })
+    ));
+
+    player.set_unique_name_in_owner(false);
+    assert!(unsafe { cache.get_unique_as::<Node2D>(&middle, "%Player") }.is_none());
+    assert!(matches!(
+        unsafe { fields.resolve_nodes(&middle) },
+        Err(NodeFieldError::NotFound { field: "player", path: "%Player" })
+    ));
+
+    unsafe { root.assume_unique().free() };
+}}
+
+#[derive(NativeClass, Default)]
+#[inherit(Node)]
+#[no_constructor]
+struct NodeFields {
+    #[node("%Player")]
+    player: Option<Ref<Node2D>>,
+    #[node("..")]
+    root: Option<Ref<Node>>,
+}
+
+#[methods]
+impl NodeFields {}
+
+#[derive(NativeClass, Default)]
+#[inherit(Node)]
+#[no_constructor]
+struct MissingNodeField {
+    #[node("%Player")]
+    player: Option<Ref<Spatial>>,
+}
+
+#[methods]
+impl MissingNodeField {}
+
+crate::godot_itest! { test_scene_snapshot {
+    let root = unsafe { Node::new().into_shared().assume_safe() };
+    let player = unsafe { Node2D::new().into_shared().assume_safe() };
+    let sprite = unsafe { Sprite::new().into_shared().assume_safe() };
+    let enemies = unsafe { Node2D::new().into_shared().assume_safe() };
+    root.set_name("Level");
+    player.set_name("Player");
+    sprite.set_name("Sprite");
+    enemies.set_name("Enemies");
+
+    root.add_child(player, false);
+    root.add_child(enemies, false);
+    player.add_child(sprite, false);
+
+    let options = SnapshotOptions::new().property("position");
+    let before = options.capture(&root);
+    before.assert_matches(
+        "
+Level (Node)
+  Player (Node2D)
+    .position = Vector2((0, 0))
+    Sprite (Sprite)
+      .position = Vector2((0, 0))
+  Enemies (Node2D)
+    .position = Vector2((0, 0))
+",
+    );
+    assert_eq!(before, before.to_string().parse::<NodeSnapshot>().unwrap());
+
+    let shallow = SnapshotOptions::new().max_depth(1).capture(&root);
+    assert!(shallow.find("Player").unwrap().children.is_empty());
+
+    // Reparent the sprite and move the player
+    player.remove_child(sprite);
+    enemies.add_child(sprite, false);
+    player.set_position(Vector2::new(1.0, 2.0));
+
+    let changes = options.capture(&root).diff(&before);
+    assert_eq!(
+        vec![
+            SnapshotChange::Property {
+                path: "Level/Player".into(),
+                property: "position".into(),
+                expected: Some("Vector2((0, 0))".into()),
+                actual: Some("Vector2((1, 2))".into()),
+            },
+            SnapshotChange::Removed { path: "Level/Player/Sprite".into() },
+            SnapshotChange::Added { path: "Level/Enemies/Sprite".into() },
+        ],
+        changes
+    );
+
+    // Free the enemies, including the sprite
+    let reparented = NodeSnapshot::capture(&root);
+    root.remove_child(enemies);
+    unsafe { enemies.assume_unique().free() };
+
+    assert_eq!(
+        vec![SnapshotChange::Removed { path: "Level/Enemies".into() }],
+        NodeSnapshot::capture(&root).diff(&reparented)
+    );
+
+    unsafe { root.assume_unique().free() };
+}}
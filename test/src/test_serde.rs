@@ -1,4 +1,4 @@
-use gdnative::core_types::variant::VariantDispatch;
+use gdnative::core_types::variant::{JsonOptions, NonJsonValue, ToJsonError, VariantDispatch};
 use gdnative::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +18,8 @@ pub(crate) fn run_tests() -> bool {
     status &= test_json();
     status &= test_msgpack();
     status &= test_bincode();
+    status &= test_json_value();
+    status &= test_json_value_non_json();
 
     status
 }
@@ -195,3 +197,49 @@ crate::godot_itest! { test_bincode {
         Foo::from_variant(&Variant::from(&disp)).expect("Foo from Dispatch from bincode");
     assert_eq!(foo, result);
 }}
+
+crate::godot_itest! { test_json_value {
+    let value = serde_json::json!({
+        "nil": null,
+        "bool": true,
+        "int": 42,
+        "float": 1.5,
+        "string": "foo",
+        "array": [1, "two", [3.0]],
+        "dict": { "nested": { "key": "value" } },
+    });
+
+    let variant = Variant::from_json_value(&value);
+    let dict = variant.to::<Dictionary>().expect("Dictionary from JSON");
+    assert_eq!(Some(42), dict.get("int").and_then(|v| v.to::<i64>()));
+    assert_eq!(Some(1.5), dict.get("float").and_then(|v| v.to::<f64>()));
+
+    let result = variant.to_json_value().expect("Variant to JSON");
+    assert_eq!(value, result);
+
+    let dict = Dictionary::new();
+    dict.insert(1, Vector2::new(1.0, 2.0));
+    dict.insert(NodePath::from_str("a/b"), PoolArray::from_vec(vec![1_u8, 2]));
+    let result = dict.to_json_value().expect("Dictionary to JSON");
+    assert_eq!(
+        serde_json::json!({ "1": { "x": 1.0, "y": 2.0 }, "a/b": [1, 2] }),
+        result
+    );
+}}
+
+crate::godot_itest! { test_json_value_non_json {
+    let arr = VariantArray::new();
+    arr.push(Rid::new());
+    arr.push(f64::NAN);
+
+    assert_eq!(
+        Err(ToJsonError::UnsupportedType(VariantType::Rid)),
+        arr.to_json_value()
+    );
+
+    let options = JsonOptions::new()
+        .with_rids(NonJsonValue::Null)
+        .with_non_finite_floats(NonJsonValue::String);
+    let result = arr.to_json_value_with(&options).expect("VariantArray to JSON");
+    assert_eq!(serde_json::json!([null, "nan"]), result);
+}}
@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gdnative::export::hint::{IntHint, RangeHint};
+use gdnative::prelude::*;
+use gdnative::settings::{self, CustomSetting, SettingError, SettingsWatcher};
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_settings_typed_access();
+    status &= test_settings_custom_setting();
+    status &= test_settings_watcher();
+
+    status
+}
+
+crate::godot_itest! { test_settings_typed_access {
+    let name = settings::setting::<String>("application/config/name");
+    assert!(name.is_ok());
+
+    assert_eq!(
+        Err(SettingError::Missing("gdnative_test/does_not_exist".into())),
+        settings::setting::<i64>("gdnative_test/does_not_exist"),
+    );
+
+    assert!(matches!(
+        settings::setting::<Vector3>("application/config/name"),
+        Err(SettingError::Type { .. }),
+    ));
+}}
+
+crate::godot_itest! { test_settings_custom_setting {
+    let name = "gdnative_test/custom/speed";
+
+    CustomSetting::new(name, 5_i64)
+        .with_hint(IntHint::Range(RangeHint::new(0, 10)))
+        .register();
+    assert_eq!(Ok(5), settings::setting::<i64>(name));
+
+    // Registering again keeps the current value
+    settings::set_setting(name, 7);
+    CustomSetting::new(name, 5_i64).register();
+    assert_eq!(Ok(7), settings::setting::<i64>(name));
+
+    settings::set_setting(name, Variant::nil());
+    assert!(!settings::has_setting(name));
+}}
+
+crate::godot_itest! { test_settings_watcher {
+    let name = "gdnative_test/watched";
+    settings::set_setting(name, 1);
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut watcher = SettingsWatcher::new();
+    watcher.watch(name, {
+        let seen = seen.clone();
+        move |value| seen.borrow_mut().push(value.to::<i64>())
+    });
+
+    assert_eq!(0, watcher.poll());
+
+    settings::set_setting(name, 2);
+    assert_eq!(1, watcher.poll());
+    assert_eq!(0, watcher.poll());
+
+    settings::set_setting(name, Variant::nil());
+    assert_eq!(1, watcher.poll());
+    assert_eq!(vec![Some(2), None], *seen.borrow());
+}}
@@ -0,0 +1,26 @@
+use gdnative::api::OS;
+use gdnative::worker;
+
+pub(crate) fn run_tests() -> bool {
+    let mut status = true;
+
+    status &= test_worker_par_call();
+
+    status
+}
+
+crate::godot_itest! { test_worker_par_call {
+    let name = OS::godot_singleton().get_name().to_string();
+
+    let results = worker::par_call((0..100).collect(), |os: &OS, i: i64| {
+        (i * 2, os.get_name().to_string())
+    });
+
+    assert_eq!(100, results.len());
+    for (i, (doubled, os_name)) in results.into_iter().enumerate() {
+        assert_eq!(i as i64 * 2, doubled);
+        assert_eq!(name, os_name);
+    }
+
+    assert!(worker::par_call(Vec::<i64>::new(), |_: &OS, i| i).is_empty());
+}}
@@ -41,6 +41,8 @@ publishedCrates=(
     "bindings-generator"
     "gdnative-bindings"
     "gdnative-async"
+    "upgrade-assist"
+    "script-interface"
     "gdnative"
 )
 
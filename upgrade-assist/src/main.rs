@@ -0,0 +1,164 @@
+//! Rewrites code written for older versions of godot-rust to the current API.
+//!
+//! Run `cargo gdnative-upgrade` in the root of a crate to migrate all `.rs` files in it, or pass
+//! the files and directories to migrate as arguments. Files are rewritten in place, so make sure
+//! that any changes are committed first. With `--check`, files are only checked, and the exit
+//! status is non-zero if anything would be rewritten.
+//!
+//! The following are migrated:
+//!
+//! - `#[export]` methods, which are changed to `#[method]`, with the owner marked as `#[base]`.
+//! - The legacy init macros, such as `godot_init!`, which are replaced with an implementation of
+//!   `GDNativeCallbacks` using `#[gdnative::init::callbacks]`.
+//! - Renamed modules and types, such as `gdnative::nativescript` and `RefInstance`, and
+//!   the removed aliases of `PoolArray`, such as `PoolByteArray`.
+//!
+//! Code is only changed syntactically, without any type information, so the result should
+//! be reviewed. Everything that can't be migrated automatically is reported with its location.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod migrate;
+mod source;
+
+use migrate::NoteKind;
+
+const USAGE: &str = "\
+Rewrites code using deprecated godot-rust syntax to the current API.
+
+Usage: cargo gdnative-upgrade [--check] [PATH]...
+
+Arguments:
+  [PATH]...  Files or directories to migrate [default: .]
+
+Options:
+  --check    Report the changes without rewriting any files
+  -h, --help Print help";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+
+    // Skip the subcommand name when invoked through `cargo gdnative-upgrade`.
+    if args.peek().map(String::as_str) == Some("gdnative-upgrade") {
+        args.next();
+    }
+
+    let mut check = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("error: unknown option `{arg}`\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        if let Err(err) = collect_files(path, &mut files) {
+            eprintln!("error: cannot read `{}`: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut failed = false;
+    let mut rewritten = 0;
+    let mut manual = 0;
+
+    for file in &files {
+        match upgrade_file(file, check) {
+            Ok(Upgrade { changed, manual: m }) => {
+                rewritten += usize::from(changed);
+                manual += m;
+            }
+            Err(err) => {
+                eprintln!("error: cannot migrate `{}`: {err}", file.display());
+                failed = true;
+            }
+        }
+    }
+
+    let verb = if check {
+        "would be rewritten"
+    } else {
+        "rewritten"
+    };
+    println!("{rewritten} files {verb}, {manual} places need manual migration");
+
+    if failed || (check && rewritten > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+struct Upgrade {
+    changed: bool,
+    manual: usize,
+}
+
+fn upgrade_file(file: &Path, check: bool) -> Result<Upgrade, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(file)?;
+    let migration = migrate::migrate(&text)?;
+
+    let mut manual = 0;
+    for note in &migration.notes {
+        let location = format!("{}:{}:{}", file.display(), note.line, note.column);
+        match note.kind {
+            NoteKind::Migrated => println!("{location}: {}", note.message),
+            NoteKind::Manual => {
+                manual += 1;
+                eprintln!("{location}: manual migration needed: {}", note.message);
+            }
+        }
+    }
+
+    let changed = migration.output.is_some();
+    if let (Some(output), false) = (migration.output, check) {
+        fs::write(file, output)?;
+    }
+
+    Ok(Upgrade { changed, manual })
+}
+
+/// Adds the Rust files at `path` to `files`, skipping build output and hidden directories.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        fs::metadata(path)?;
+        files.push(path.to_owned());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_files(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
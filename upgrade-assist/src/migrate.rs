@@ -0,0 +1,526 @@
+use proc_macro2::Span;
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, FnArg, Ident, ImplItem, ImplItemMethod, ItemImpl, ItemMacro, ItemUse, Macro, Path,
+    PathArguments, Type, UseTree,
+};
+
+use crate::source::Source;
+
+/// Result of migrating a source file.
+pub(crate) struct Migration {
+    /// The migrated text, or `None` if nothing was changed.
+    pub output: Option<String>,
+    pub notes: Vec<Note>,
+}
+
+/// Change made to a source file, or place that has to be migrated manually.
+pub(crate) struct Note {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, in characters.
+    pub column: usize,
+    pub kind: NoteKind,
+    pub message: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum NoteKind {
+    Migrated,
+    Manual,
+}
+
+/// Module paths that were renamed, as the path leading to a segment, the old name of the
+/// segment, and the new name, or `None` if the segment was removed.
+const MODULE_RENAMES: &[(&[&str], &str, Option<&str>)] = &[
+    (&["gdnative"], "nativescript", Some("export")),
+    (&["gdnative", "nativescript"], "init", None),
+    (&["gdnative", "nativescript", "init"], "property", None),
+    (&["gdnative"], "thread_access", Some("object::ownership")),
+    (&["gdnative"], "ref_kind", Some("object::memory")),
+];
+
+const TYPE_RENAMES: &[(&str, &str)] = &[
+    ("RefInstance", "TInstance"),
+    ("TypedArray", "PoolArray"),
+    ("ThreadAccess", "Ownership"),
+    ("RefKind", "Memory"),
+    ("SignalArgument", "SignalParam"),
+];
+
+/// Removed aliases of `PoolArray`, with their element types.
+const POOL_ARRAY_ALIASES: &[(&str, &str)] = &[
+    ("PoolByteArray", "u8"),
+    ("PoolIntArray", "i32"),
+    ("PoolRealArray", "f32"),
+    ("PoolStringArray", "GodotString"),
+    ("PoolVector2Array", "Vector2"),
+    ("PoolVector3Array", "Vector3"),
+    ("PoolColorArray", "Color"),
+];
+
+const INIT_MACROS: &[&str] = &[
+    "godot_init",
+    "godot_nativescript_init",
+    "godot_gdnative_init",
+    "godot_gdnative_terminate",
+];
+
+/// Migrates the Rust source `text`.
+pub(crate) fn migrate(text: &str) -> syn::Result<Migration> {
+    let (bom, text) = match text.strip_prefix('\u{feff}') {
+        Some(text) => ("\u{feff}", text),
+        None => ("", text),
+    };
+
+    let file = syn::parse_file(text)?;
+
+    let mut migrator = Migrator {
+        source: Source::new(text),
+        notes: Vec::new(),
+        imports_pool_array: false,
+    };
+    migrator.visit_file(&file);
+
+    let Migrator {
+        source, mut notes, ..
+    } = migrator;
+    notes.sort_by_key(|note| (note.line, note.column));
+
+    Ok(Migration {
+        output: source.apply().map(|output| format!("{bom}{output}")),
+        notes,
+    })
+}
+
+struct Migrator<'a> {
+    source: Source<'a>,
+    notes: Vec<Note>,
+    /// Whether `PoolArray` is imported by a `use` item seen so far.
+    imports_pool_array: bool,
+}
+
+impl Migrator<'_> {
+    fn note(&mut self, span: Span, kind: NoteKind, message: impl Into<String>) {
+        let start = span.start();
+        self.notes.push(Note {
+            line: start.line,
+            column: start.column + 1,
+            kind,
+            message: message.into(),
+        });
+    }
+
+    fn migrated(&mut self, span: Span, message: impl Into<String>) {
+        self.note(span, NoteKind::Migrated, message);
+    }
+
+    fn manual(&mut self, span: Span, message: impl Into<String>) {
+        self.note(span, NoteKind::Manual, message);
+    }
+
+    /// Replaces `#[export]` with `#[method]`, and marks the owner parameter with `#[base]`.
+    fn migrate_export(&mut self, method: &ImplItemMethod, attr: &Attribute) {
+        let name = &method.sig.ident;
+        let mut inputs = method.sig.inputs.iter();
+
+        let owner = match (inputs.next(), inputs.next()) {
+            (Some(FnArg::Receiver(_)), Some(FnArg::Typed(owner))) => owner,
+            _ => {
+                self.manual(
+                    attr.span(),
+                    format!("`{name}` is exported with `#[export]`, but doesn't take `self` and the owner"),
+                );
+                return;
+            }
+        };
+
+        if let Type::Path(ty) = &*owner.ty {
+            if let Some(segment) = ty.path.segments.last() {
+                let ty_name = segment.ident.to_string();
+                if matches!(ty_name.as_str(), "Ref" | "Instance" | "TInstance") {
+                    self.manual(
+                        owner.ty.span(),
+                        format!("the owner of `{name}` can't be a `#[base]` parameter of type `{ty_name}<T>`; take `TRef<T>` and call `claim()` instead"),
+                    );
+                    return;
+                }
+            }
+        }
+
+        let export = &attr.path.segments.last().expect("matched by name").ident;
+        self.source.replace(export.span(), "method");
+
+        if !owner.attrs.iter().any(|attr| attr.path.is_ident("base")) {
+            self.source.insert_before(owner.pat.span(), "#[base] ");
+        }
+
+        self.migrated(
+            attr.span(),
+            format!("replaced `#[export]` with `#[method]` on `{name}`"),
+        );
+    }
+
+    /// Replaces the legacy init macros with an implementation of `GDNativeCallbacks`.
+    fn migrate_init_macro(&mut self, item: &ItemMacro, name: &str) {
+        let span = item.span();
+
+        let callback = match parse_callback.parse2(item.mac.tokens.clone()) {
+            Ok(callback) if item.attrs.is_empty() => callback,
+            _ => {
+                self.manual(
+                    span,
+                    format!("`{name}!` can't be migrated automatically; use `#[gdnative::init::callbacks]`"),
+                );
+                return;
+            }
+        };
+
+        match (name, callback) {
+            ("godot_gdnative_init" | "godot_gdnative_terminate", None) => {
+                self.source.remove_lines(span);
+                self.migrated(span, format!("removed `{name}!`, which does nothing"));
+            }
+            ("godot_init" | "godot_nativescript_init", Some(callback)) => {
+                self.replace_with_callbacks(span, name, Some(&callback));
+            }
+            ("godot_nativescript_init", None) => self.replace_with_callbacks(span, name, None),
+            _ => {
+                self.manual(
+                    span,
+                    format!("`{name}!` can't be migrated automatically; use `#[gdnative::init::callbacks]`"),
+                );
+            }
+        }
+    }
+
+    fn replace_with_callbacks(&mut self, span: Span, name: &str, callback: Option<&Ident>) {
+        let indent = self.source.indentation(span);
+        let body = match callback {
+            Some(callback) => format!(
+                "\n\
+                {indent}    fn nativescript_init(handle: gdnative::init::InitHandle) {{\n\
+                {indent}        {callback}(handle);\n\
+                {indent}    }}\n\
+                {indent}"
+            ),
+            None => String::new(),
+        };
+
+        self.source.replace(
+            span,
+            format!(
+                "struct GDNativeCallbacksImpl;\n\
+                \n\
+                {indent}#[gdnative::init::callbacks]\n\
+                {indent}impl gdnative::init::GDNativeCallbacks for GDNativeCallbacksImpl {{{body}}}"
+            ),
+        );
+        self.migrated(
+            span,
+            format!("replaced `{name}!` with `#[gdnative::init::callbacks]`"),
+        );
+    }
+
+    /// Migrates the module segment `ident`, which follows `context` in a path, and is followed
+    /// by the segment starting at `next`, if any.
+    fn migrate_module(&mut self, context: &[String], ident: &Ident, next: Option<Span>) {
+        let Some(&(_, _, new_name)) = MODULE_RENAMES
+            .iter()
+            .find(|(path, name, _)| ident == name && *path == context)
+        else {
+            return;
+        };
+
+        let old_path = format!("{}::{ident}", context.join("::"));
+        match (new_name, next) {
+            (Some(new_name), _) => {
+                self.source.replace(ident.span(), new_name);
+                self.migrated(
+                    ident.span(),
+                    format!(
+                        "renamed `{old_path}` to `{}::{new_name}`",
+                        context.join("::")
+                    ),
+                );
+            }
+            (None, Some(next)) => {
+                let start = self.source.offset(ident.span().start());
+                let end = self.source.offset(next.start());
+                self.source.replace_range(start..end, "");
+                self.migrated(
+                    ident.span(),
+                    format!(
+                        "moved the contents of `{old_path}` to `{}`",
+                        context.join("::")
+                    ),
+                );
+            }
+            (None, None) => {
+                self.manual(
+                    ident.span(),
+                    format!(
+                        "`{old_path}` has been removed, and its contents moved to `{}`",
+                        context.join("::")
+                    ),
+                );
+            }
+        }
+    }
+
+    fn migrate_type_name(&mut self, ident: &Ident) {
+        if let Some((old, new)) = TYPE_RENAMES.iter().find(|(old, _)| ident == old) {
+            self.source.replace(ident.span(), *new);
+            self.migrated(ident.span(), format!("renamed `{old}` to `{new}`"));
+        }
+    }
+
+    fn migrate_use_tree(&mut self, tree: &UseTree, context: &mut Vec<String>) {
+        match tree {
+            UseTree::Path(path) => {
+                if context.first().is_some_and(|root| root == "gdnative") {
+                    self.migrate_module(context, &path.ident, Some(path.tree.span()));
+                }
+                context.push(path.ident.to_string());
+                self.migrate_use_tree(&path.tree, context);
+                context.pop();
+            }
+            UseTree::Name(name) => self.migrate_imported_name(context, &name.ident),
+            UseTree::Rename(rename) => self.migrate_imported_name(context, &rename.ident),
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.migrate_use_tree(tree, context);
+                }
+            }
+            UseTree::Glob(_) => {}
+        }
+    }
+
+    fn migrate_imported_name(&mut self, context: &[String], ident: &Ident) {
+        if context.first().is_some_and(|root| root == "gdnative") {
+            self.migrate_module(context, ident, None);
+        }
+        self.migrate_type_name(ident);
+
+        if ident == "PoolArray" {
+            self.imports_pool_array = true;
+        } else if let Some((alias, _)) = POOL_ARRAY_ALIASES.iter().find(|(alias, _)| ident == alias)
+        {
+            if self.imports_pool_array {
+                self.manual(
+                    ident.span(),
+                    format!("`{alias}` has been removed; remove this import, as `PoolArray` is already imported"),
+                );
+            } else {
+                self.imports_pool_array = true;
+                self.source.replace(ident.span(), "PoolArray");
+                self.migrated(
+                    ident.span(),
+                    format!("replaced the import of `{alias}` with `PoolArray`"),
+                );
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Migrator<'_> {
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        if item
+            .attrs
+            .iter()
+            .any(|attr| is_named(&attr.path, "methods"))
+        {
+            for impl_item in &item.items {
+                if let ImplItem::Method(method) = impl_item {
+                    if let Some(attr) = method.attrs.iter().find(|a| is_named(&a.path, "export")) {
+                        self.migrate_export(method, attr);
+                    }
+                }
+            }
+        }
+
+        visit::visit_item_impl(self, item);
+    }
+
+    fn visit_item_macro(&mut self, item: &'ast ItemMacro) {
+        let name = item.mac.path.segments.last().map(|s| s.ident.to_string());
+        match name.as_deref() {
+            Some(name) if INIT_MACROS.contains(&name) => self.migrate_init_macro(item, name),
+            _ => visit::visit_item_macro(self, item),
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if is_named(&mac.path, "godot_wrap_method") {
+            self.manual(
+                mac.span(),
+                "`godot_wrap_method!` is deprecated; export the method with `#[methods]`, or implement `Method` manually",
+            );
+        }
+
+        visit::visit_macro(self, mac);
+    }
+
+    fn visit_item_use(&mut self, item: &'ast ItemUse) {
+        self.migrate_use_tree(&item.tree, &mut Vec::new());
+    }
+
+    fn visit_path(&mut self, path: &'ast Path) {
+        let segments = path.segments.iter().collect::<Vec<_>>();
+
+        if segments.first().is_some_and(|s| s.ident == "gdnative") {
+            let mut context = vec!["gdnative".to_owned()];
+            for (i, segment) in segments.iter().enumerate().skip(1) {
+                let next = segments.get(i + 1).map(|s| s.span());
+                self.migrate_module(&context, &segment.ident, next);
+                context.push(segment.ident.to_string());
+            }
+        }
+
+        for (i, segment) in segments.iter().enumerate() {
+            self.migrate_type_name(&segment.ident);
+
+            let alias = POOL_ARRAY_ALIASES
+                .iter()
+                .find(|(alias, _)| segment.ident == alias);
+            if let (Some((alias, element)), PathArguments::None) = (alias, &segment.arguments) {
+                let separator = if i + 1 < segments.len() { "::" } else { "" };
+                self.source.replace(
+                    segment.ident.span(),
+                    format!("PoolArray{separator}<{element}>"),
+                );
+                self.migrated(
+                    segment.ident.span(),
+                    format!("replaced `{alias}` with `PoolArray<{element}>`"),
+                );
+            }
+        }
+
+        visit::visit_path(self, path);
+    }
+}
+
+/// Returns `true` if the last segment of `path` is `name`.
+fn is_named(path: &Path, name: &str) -> bool {
+    path.segments.last().is_some_and(|s| s.ident == name)
+}
+
+/// Parses the arguments of a legacy init macro, which may be empty or a single callback.
+fn parse_callback(input: ParseStream) -> syn::Result<Option<Ident>> {
+    if input.is_empty() {
+        Ok(None)
+    } else {
+        input.parse().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated(text: &str) -> String {
+        migrate(text)
+            .unwrap()
+            .output
+            .expect("text should be changed")
+    }
+
+    #[test]
+    fn export_is_replaced_with_method() {
+        let text = "
+#[methods]
+impl Foo {
+    #[export]
+    fn _ready(&self, owner: &Node) {}
+
+    #[export(name = \"bar\")]
+    fn bar(&mut self, _owner: TRef<Node>, x: i64) {}
+}
+";
+        let expected = "
+#[methods]
+impl Foo {
+    #[method]
+    fn _ready(&self, #[base] owner: &Node) {}
+
+    #[method(name = \"bar\")]
+    fn bar(&mut self, #[base] _owner: TRef<Node>, x: i64) {}
+}
+";
+        assert_eq!(expected, migrated(text));
+    }
+
+    #[test]
+    fn export_without_owner_is_reported() {
+        let text = "
+#[methods]
+impl Foo {
+    #[export]
+    fn foo(&self) {}
+}
+";
+        let migration = migrate(text).unwrap();
+        assert!(migration.output.is_none());
+        assert_eq!(1, migration.notes.len());
+        assert_eq!(NoteKind::Manual, migration.notes[0].kind);
+        assert_eq!((4, 5), (migration.notes[0].line, migration.notes[0].column));
+    }
+
+    #[test]
+    fn init_macros_are_replaced_with_callbacks() {
+        let text = "
+fn init(handle: InitHandle) {}
+
+godot_gdnative_init!();
+godot_nativescript_init!(init);
+godot_gdnative_terminate!();
+";
+        let expected = "
+fn init(handle: InitHandle) {}
+
+struct GDNativeCallbacksImpl;
+
+#[gdnative::init::callbacks]
+impl gdnative::init::GDNativeCallbacks for GDNativeCallbacksImpl {
+    fn nativescript_init(handle: gdnative::init::InitHandle) {
+        init(handle);
+    }
+}
+";
+        assert_eq!(expected, migrated(text));
+    }
+
+    #[test]
+    fn paths_are_renamed() {
+        let text = "
+use gdnative::nativescript::{init::property::hint::EnumHint, NativeClass};
+use gdnative::thread_access::ThreadAccess;
+use gdnative::core_types::{PoolByteArray, PoolIntArray};
+
+fn foo(_: gdnative::nativescript::init::ClassBuilder<Foo>, _: RefInstance<Foo, Shared>) {
+    let bytes: PoolByteArray = PoolByteArray::new();
+}
+";
+        let expected = "
+use gdnative::export::{hint::EnumHint, NativeClass};
+use gdnative::object::ownership::Ownership;
+use gdnative::core_types::{PoolArray, PoolIntArray};
+
+fn foo(_: gdnative::export::ClassBuilder<Foo>, _: TInstance<Foo, Shared>) {
+    let bytes: PoolArray<u8> = PoolArray::<u8>::new();
+}
+";
+        let migration = migrate(text).unwrap();
+        assert_eq!(Some(expected), migration.output.as_deref());
+        assert_eq!(
+            1,
+            migration
+                .notes
+                .iter()
+                .filter(|note| note.kind == NoteKind::Manual)
+                .count()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use proc_macro2::{LineColumn, Span};
+
+/// Text of a source file, along with the edits to be made to it.
+///
+/// Edits are made to the original text, so that everything that isn't migrated keeps its
+/// formatting and comments.
+pub(crate) struct Source<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+    edits: Vec<(Range<usize>, String)>,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Source {
+            text,
+            line_starts,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Returns the byte offset of `location`, whose column is counted in characters.
+    pub fn offset(&self, location: LineColumn) -> usize {
+        let start = self.line_starts[location.line - 1];
+        self.text[start..]
+            .char_indices()
+            .nth(location.column)
+            .map_or(self.text.len(), |(i, _)| start + i)
+    }
+
+    pub fn range(&self, span: Span) -> Range<usize> {
+        self.offset(span.start())..self.offset(span.end())
+    }
+
+    /// Returns the whitespace at the start of the line containing the start of `span`.
+    pub fn indentation(&self, span: Span) -> &'a str {
+        let start = self.line_starts[span.start().line - 1];
+        let line = &self.text[start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    pub fn replace(&mut self, span: Span, replacement: impl Into<String>) {
+        let range = self.range(span);
+        self.replace_range(range, replacement);
+    }
+
+    pub fn replace_range(&mut self, range: Range<usize>, replacement: impl Into<String>) {
+        self.edits.push((range, replacement.into()));
+    }
+
+    pub fn insert_before(&mut self, span: Span, text: impl Into<String>) {
+        let offset = self.offset(span.start());
+        self.replace_range(offset..offset, text);
+    }
+
+    /// Removes the text of `span`, along with the lines it is on if nothing else is on them.
+    pub fn remove_lines(&mut self, span: Span) {
+        let Range { mut start, mut end } = self.range(span);
+
+        let before = &self.text[..start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let after = &self.text[end..];
+        let line_end = after.find('\n').map_or(self.text.len(), |i| end + i + 1);
+
+        if before[line_start..].trim().is_empty() && self.text[end..line_end].trim().is_empty() {
+            start = line_start;
+            end = line_end;
+        }
+
+        self.replace_range(start..end, "");
+    }
+
+    /// Applies the edits, returning the new text, or `None` if there are no edits.
+    ///
+    /// # Panics
+    ///
+    /// If any edits overlap.
+    pub fn apply(mut self) -> Option<String> {
+        if self.edits.is_empty() {
+            return None;
+        }
+
+        self.edits
+            .sort_by_key(|(range, _)| (range.start, range.end));
+
+        let mut output = String::with_capacity(self.text.len());
+        let mut copied = 0;
+        for (range, replacement) in &self.edits {
+            assert!(range.start >= copied, "overlapping edits");
+            output.push_str(&self.text[copied..range.start]);
+            output.push_str(replacement);
+            copied = range.end;
+        }
+        output.push_str(&self.text[copied..]);
+
+        Some(output)
+    }
+}